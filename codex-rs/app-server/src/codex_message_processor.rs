@@ -3,6 +3,8 @@ use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::fuzzy_file_search::run_fuzzy_file_search;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotification;
+use chrono::DateTime;
+use chrono::Utc;
 use codex_app_server_protocol::AddConversationListenerParams;
 use codex_app_server_protocol::AddConversationSubscriptionResponse;
 use codex_app_server_protocol::ApplyPatchApprovalParams;
@@ -51,12 +53,13 @@ use codex_app_server_protocol::UserInfoResponse;
 use codex_app_server_protocol::UserSavedConfig;
 use codex_core::AuthManager;
 use codex_core::CodexConversation;
+use codex_core::ConversationFilter;
+use codex_core::ConversationItem;
 use codex_core::ConversationManager;
 use codex_core::Cursor as RolloutCursor;
 use codex_core::INTERACTIVE_SESSION_SOURCES;
 use codex_core::NewConversation;
 use codex_core::RolloutRecorder;
-use codex_core::SessionMeta;
 use codex_core::auth::CLIENT_ID;
 use codex_core::auth::get_auth_file;
 use codex_core::auth::login_with_api_key;
@@ -84,10 +87,7 @@ use codex_login::ServerOptions as LoginServerOptions;
 use codex_login::ShutdownHandle;
 use codex_login::run_login_server;
 use codex_protocol::ConversationId;
-use codex_protocol::models::ContentItem;
-use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::InputMessageKind;
-use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 use codex_utils_json_to_toml::json_to_toml;
 use std::collections::HashMap;
 use std::ffi::OsStr;
@@ -617,6 +617,8 @@ impl CodexMessageProcessor {
         };
         tracing::debug!("Sandbox type: {sandbox_type:?}");
         let codex_linux_sandbox_exe = self.config.codex_linux_sandbox_exe.clone();
+        let resource_limits = self.config.resource_limits.clone();
+        let linux_sandbox = self.config.linux_sandbox;
         let outgoing = self.outgoing.clone();
         let req_id = request_id;
         let sandbox_cwd = self.config.cwd.clone();
@@ -628,6 +630,8 @@ impl CodexMessageProcessor {
                 &effective_policy,
                 sandbox_cwd.as_path(),
                 &codex_linux_sandbox_exe,
+                &resource_limits,
+                linux_sandbox,
                 None,
             )
             .await
@@ -706,11 +710,34 @@ impl CodexMessageProcessor {
         };
         let cursor_ref = cursor_obj.as_ref();
 
+        let (created_after, created_before) = match (
+            parse_rfc3339(params.created_after.as_deref()),
+            parse_rfc3339(params.created_before.as_deref()),
+        ) {
+            (Ok(after), Ok(before)) => (after, before),
+            (Err(err), _) | (_, Err(err)) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("invalid timestamp: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+        let filter = ConversationFilter {
+            cwd: params.cwd,
+            git_branch: params.git_branch,
+            created_after,
+            created_before,
+        };
+
         let page = match RolloutRecorder::list_conversations(
             &self.config.codex_home,
             page_size,
             cursor_ref,
             INTERACTIVE_SESSION_SOURCES,
+            &filter,
         )
         .await
         {
@@ -726,11 +753,7 @@ impl CodexMessageProcessor {
             }
         };
 
-        let items = page
-            .items
-            .into_iter()
-            .filter_map(|it| extract_conversation_summary(it.path, &it.head))
-            .collect();
+        let items = page.items.into_iter().map(conversation_summary).collect();
 
         // Encode next_cursor as a plain string
         let next_cursor = match page.next_cursor {
@@ -857,6 +880,7 @@ impl CodexMessageProcessor {
         };
 
         let required_suffix = format!("{conversation_id}.jsonl");
+        let compressed_suffix = format!("{required_suffix}.zst");
         let Some(file_name) = canonical_rollout_path.file_name().map(OsStr::to_owned) else {
             let error = JSONRPCErrorError {
                 code: INVALID_REQUEST_ERROR_CODE,
@@ -870,9 +894,9 @@ impl CodexMessageProcessor {
             return;
         };
 
-        if !file_name
-            .to_string_lossy()
-            .ends_with(required_suffix.as_str())
+        let file_name_str = file_name.to_string_lossy();
+        if !file_name_str.ends_with(required_suffix.as_str())
+            && !file_name_str.ends_with(compressed_suffix.as_str())
         {
             let error = JSONRPCErrorError {
                 code: INVALID_REQUEST_ERROR_CODE,
@@ -936,14 +960,25 @@ impl CodexMessageProcessor {
             }
         }
 
-        // Move the .jsonl file to the archived sessions subdir.
+        // Move the .jsonl file to the archived sessions subdir. Shutting the
+        // conversation down may have compressed it in the meantime.
         let result: std::io::Result<()> = async {
             let archive_folder = self
                 .config
                 .codex_home
                 .join(codex_core::ARCHIVED_SESSIONS_SUBDIR);
             tokio::fs::create_dir_all(&archive_folder).await?;
-            tokio::fs::rename(&canonical_rollout_path, &archive_folder.join(&file_name)).await?;
+            let compressed_path = codex_core::compressed_rollout_path(&canonical_rollout_path);
+            let (source, file_name) = if !tokio::fs::try_exists(&canonical_rollout_path).await?
+                && tokio::fs::try_exists(&compressed_path).await?
+            {
+                let mut compressed_name = file_name;
+                compressed_name.push(".zst");
+                (compressed_path, compressed_name)
+            } else {
+                (canonical_rollout_path, file_name)
+            };
+            tokio::fs::rename(&source, &archive_folder.join(&file_name)).await?;
             Ok(())
         }
         .await;
@@ -1284,6 +1319,7 @@ async fn apply_bespoke_event_handling(
             command,
             cwd,
             reason,
+            sandbox_denials,
         }) => {
             let params = ExecCommandApprovalParams {
                 conversation_id,
@@ -1291,6 +1327,7 @@ async fn apply_bespoke_event_handling(
                 command,
                 cwd,
                 reason,
+                sandbox_denials,
             };
             let rx = outgoing
                 .send_request(ServerRequestPayload::ExecCommandApproval(params))
@@ -1301,6 +1338,32 @@ async fn apply_bespoke_event_handling(
                 on_exec_approval_response(event_id, rx, conversation).await;
             });
         }
+        // Sampling prompts are not forwarded to app-server clients yet, so only
+        // servers configured with `auto_approve_sampling` can sample.
+        EventMsg::McpSamplingApprovalRequest(_) => {
+            if let Err(err) = conversation
+                .submit(Op::McpSamplingApproval {
+                    id: event_id,
+                    decision: ReviewDecision::Denied,
+                })
+                .await
+            {
+                error!("failed to decline MCP sampling request: {err}");
+            }
+        }
+        // Budget prompts are not forwarded to app-server clients yet, so a
+        // session that reaches `session_budget_usd` stops.
+        EventMsg::CostBudgetApprovalRequest(_) => {
+            if let Err(err) = conversation
+                .submit(Op::CostBudgetApproval {
+                    id: event_id,
+                    decision: ReviewDecision::Denied,
+                })
+                .await
+            {
+                error!("failed to answer cost budget request: {err}");
+            }
+        }
         // If this is a TurnAborted, reply to any pending interrupt requests.
         EventMsg::TurnAborted(turn_aborted_event) => {
             let pending = {
@@ -1351,6 +1414,7 @@ async fn derive_config_from_params(
         include_view_image_tool: None,
         show_raw_agent_reasoning: None,
         tools_web_search_request: None,
+        cassette: None,
     };
 
     let cli_overrides = cli_overrides
@@ -1440,101 +1504,76 @@ async fn on_exec_approval_response(
     }
 }
 
-fn extract_conversation_summary(
-    path: PathBuf,
-    head: &[serde_json::Value],
-) -> Option<ConversationSummary> {
-    let session_meta = match head.first() {
-        Some(first_line) => serde_json::from_value::<SessionMeta>(first_line.clone()).ok()?,
-        None => return None,
-    };
-
-    let preview = head
-        .iter()
-        .filter_map(|value| serde_json::from_value::<ResponseItem>(value.clone()).ok())
-        .find_map(|item| match item {
-            ResponseItem::Message { content, .. } => {
-                content.into_iter().find_map(|content| match content {
-                    ContentItem::InputText { text } => {
-                        match InputMessageKind::from(("user", &text)) {
-                            InputMessageKind::Plain => Some(text),
-                            _ => None,
-                        }
-                    }
-                    _ => None,
-                })
-            }
-            _ => None,
-        })?;
-
-    let preview = match preview.find(USER_MESSAGE_BEGIN) {
-        Some(idx) => preview[idx + USER_MESSAGE_BEGIN.len()..].trim(),
-        None => preview.as_str(),
-    };
-
-    let timestamp = if session_meta.timestamp.is_empty() {
+fn conversation_summary(item: ConversationItem) -> ConversationSummary {
+    let timestamp = if item.created_at.is_empty() {
         None
     } else {
-        Some(session_meta.timestamp.clone())
+        Some(item.created_at)
     };
 
-    Some(ConversationSummary {
-        conversation_id: session_meta.id,
+    ConversationSummary {
+        conversation_id: item.id,
+        path: item.path,
+        preview: item.preview.unwrap_or_default(),
         timestamp,
-        path,
-        preview: preview.to_string(),
-    })
+        cwd: item.cwd,
+        git_branch: item.git_branch,
+    }
+}
+
+fn parse_rfc3339(timestamp: Option<&str>) -> Result<Option<DateTime<Utc>>, chrono::ParseError> {
+    timestamp
+        .map(|ts| DateTime::parse_from_rfc3339(ts).map(|dt| dt.with_timezone(&Utc)))
+        .transpose()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use anyhow::Result;
+    use codex_protocol::protocol::SessionSource;
     use pretty_assertions::assert_eq;
-    use serde_json::json;
 
     #[test]
-    fn extract_conversation_summary_prefers_plain_user_messages() -> Result<()> {
+    fn conversation_summary_carries_index_fields() -> Result<()> {
         let conversation_id = ConversationId::from_string("3f941c35-29b3-493b-b0a4-e25800d9aeb0")?;
-        let timestamp = Some("2025-09-05T16:53:11.850Z".to_string());
         let path = PathBuf::from("rollout.jsonl");
+        let item = ConversationItem {
+            id: conversation_id,
+            path: path.clone(),
+            source: SessionSource::VSCode,
+            cwd: PathBuf::from("/repo"),
+            git_branch: Some("main".to_string()),
+            preview: Some("Count to 5".to_string()),
+            created_at: "2025-09-05T16:53:11.850Z".to_string(),
+            updated_at: "2025-09-05T16:54:00.000Z".to_string(),
+            total_tokens: 42,
+        };
 
-        let head = vec![
-            json!({
-                "id": conversation_id.to_string(),
-                "timestamp": timestamp,
-                "cwd": "/",
-                "originator": "codex",
-                "cli_version": "0.0.0",
-                "instructions": null
-            }),
-            json!({
-                "type": "message",
-                "role": "user",
-                "content": [{
-                    "type": "input_text",
-                    "text": "<user_instructions>\n<AGENTS.md contents>\n</user_instructions>".to_string(),
-                }],
-            }),
-            json!({
-                "type": "message",
-                "role": "user",
-                "content": [{
-                    "type": "input_text",
-                    "text": format!("<prior context> {USER_MESSAGE_BEGIN}Count to 5"),
-                }],
-            }),
-        ];
-
-        let summary = extract_conversation_summary(path.clone(), &head).expect("summary");
+        let summary = conversation_summary(item);
 
-        assert_eq!(summary.conversation_id, conversation_id);
         assert_eq!(
-            summary.timestamp,
-            Some("2025-09-05T16:53:11.850Z".to_string())
+            summary,
+            ConversationSummary {
+                conversation_id,
+                path,
+                preview: "Count to 5".to_string(),
+                timestamp: Some("2025-09-05T16:53:11.850Z".to_string()),
+                cwd: PathBuf::from("/repo"),
+                git_branch: Some("main".to_string()),
+            }
         );
-        assert_eq!(summary.path, path);
-        assert_eq!(summary.preview, "Count to 5");
         Ok(())
     }
+
+    #[test]
+    fn parse_rfc3339_rejects_malformed_timestamps() {
+        assert_eq!(parse_rfc3339(None), Ok(None));
+        assert!(
+            parse_rfc3339(Some("2025-09-05T16:53:11Z"))
+                .unwrap()
+                .is_some()
+        );
+        assert!(parse_rfc3339(Some("yesterday")).is_err());
+    }
 }
@@ -1,5 +1,6 @@
 use std::fs;
 use std::path::Path;
+use std::path::PathBuf;
 
 use app_test_support::McpProcess;
 use app_test_support::to_response;
@@ -57,6 +58,7 @@ async fn test_list_and_resume_conversations() {
         .send_list_conversations_request(ListConversationsParams {
             page_size: Some(2),
             cursor: None,
+            ..Default::default()
         })
         .await
         .expect("send listConversations");
@@ -82,6 +84,7 @@ async fn test_list_and_resume_conversations() {
         .send_list_conversations_request(ListConversationsParams {
             page_size: Some(2),
             cursor: next_cursor,
+            ..Default::default()
         })
         .await
         .expect("send listConversations page 2");
@@ -99,7 +102,30 @@ async fn test_list_and_resume_conversations() {
     } = to_response::<ListConversationsResponse>(resp2).expect("deserialize response");
     assert_eq!(items2.len(), 1);
     assert_eq!(items2[0].preview, "Hello C");
-    assert!(next2.is_some());
+    assert_eq!(next2, None);
+
+    // Filter by creation time using the session index.
+    let filtered_req_id = mcp
+        .send_list_conversations_request(ListConversationsParams {
+            created_after: Some("2025-01-01T12:30:00Z".to_string()),
+            created_before: Some("2025-01-02T00:00:00Z".to_string()),
+            cwd: Some(PathBuf::from("/")),
+            ..Default::default()
+        })
+        .await
+        .expect("send filtered listConversations");
+    let filtered_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(filtered_req_id)),
+    )
+    .await
+    .expect("filtered listConversations timeout")
+    .expect("filtered listConversations resp");
+    let ListConversationsResponse {
+        items: filtered, ..
+    } = to_response::<ListConversationsResponse>(filtered_resp).expect("deserialize response");
+    let previews: Vec<&str> = filtered.iter().map(|item| item.preview.as_str()).collect();
+    assert_eq!(previews, vec!["Hello B"]);
 
     // Now resume one of the sessions and expect a SessionConfigured notification and response.
     let resume_req_id = mcp
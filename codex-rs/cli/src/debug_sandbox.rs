@@ -4,6 +4,8 @@ use codex_common::CliConfigOverrides;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::exec_env::create_env;
+use codex_core::landlock::LinuxSandboxIsolation;
+use codex_core::landlock::LinuxSandboxOptions;
 use codex_core::landlock::spawn_command_under_linux_sandbox;
 use codex_core::seatbelt::spawn_command_under_seatbelt;
 use codex_core::spawn::StdioPolicy;
@@ -38,6 +40,7 @@ pub async fn run_command_under_landlock(
 ) -> anyhow::Result<()> {
     let LandlockCommand {
         full_auto,
+        isolation,
         config_overrides,
         command,
     } = command;
@@ -46,14 +49,14 @@ pub async fn run_command_under_landlock(
         command,
         config_overrides,
         codex_linux_sandbox_exe,
-        SandboxType::Landlock,
+        SandboxType::Landlock { isolation },
     )
     .await
 }
 
 enum SandboxType {
     Seatbelt,
-    Landlock,
+    Landlock { isolation: LinuxSandboxIsolation },
 }
 
 async fn run_command_under_sandbox(
@@ -94,12 +97,13 @@ async fn run_command_under_sandbox(
                 cwd,
                 &config.sandbox_policy,
                 sandbox_policy_cwd.as_path(),
+                &config.resource_limits,
                 stdio_policy,
                 env,
             )
             .await?
         }
-        SandboxType::Landlock => {
+        SandboxType::Landlock { isolation } => {
             #[expect(clippy::expect_used)]
             let codex_linux_sandbox_exe = config
                 .codex_linux_sandbox_exe
@@ -110,6 +114,13 @@ async fn run_command_under_sandbox(
                 cwd,
                 &config.sandbox_policy,
                 sandbox_policy_cwd.as_path(),
+                None,
+                &config.resource_limits,
+                LinuxSandboxOptions {
+                    isolation,
+                    ..LinuxSandboxOptions::default()
+                },
+                None,
                 stdio_policy,
                 env,
             )
@@ -4,6 +4,7 @@ pub mod login;
 
 use clap::Parser;
 use codex_common::CliConfigOverrides;
+use codex_core::landlock::LinuxSandboxIsolation;
 
 #[derive(Debug, Parser)]
 pub struct SeatbeltCommand {
@@ -25,6 +26,12 @@ pub struct LandlockCommand {
     #[arg(long = "full-auto", default_value_t = false)]
     pub full_auto: bool,
 
+    /// How to isolate the command: `landlock`, or `namespaces` to also run it
+    /// in its own user, mount and PID namespaces with a read-only view of the
+    /// system outside the writable roots.
+    #[arg(long, default_value_t = LinuxSandboxIsolation::Landlock)]
+    pub isolation: LinuxSandboxIsolation,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -1,3 +1,7 @@
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::Utc;
 use clap::CommandFactory;
 use clap::Parser;
 use clap_complete::Shell;
@@ -15,6 +19,7 @@ use codex_cli::login::run_login_with_device_code;
 use codex_cli::login::run_logout;
 use codex_cloud_tasks::Cli as CloudTasksCli;
 use codex_common::CliConfigOverrides;
+use codex_core::ConversationFilter;
 use codex_exec::Cli as ExecCli;
 use codex_responses_api_proxy::Args as ResponsesApiProxyArgs;
 use codex_tui::AppExitInfo;
@@ -24,8 +29,10 @@ use std::path::PathBuf;
 use supports_color::Stream;
 
 mod mcp_cmd;
+mod sessions_cmd;
 
 use crate::mcp_cmd::McpCli;
+use crate::sessions_cmd::SessionsCli;
 
 /// Codex CLI
 ///
@@ -87,6 +94,9 @@ enum Subcommand {
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Search and export recorded sessions.
+    Sessions(SessionsCli),
+
     /// Internal: generate TypeScript protocol bindings.
     #[clap(hide = true)]
     GenerateTs(GenerateTsCommand),
@@ -117,10 +127,78 @@ struct ResumeCommand {
     #[arg(long = "last", default_value_t = false, conflicts_with = "session_id")]
     last: bool,
 
+    /// Instead of resuming, start a new session from the history before this
+    /// 0-based user turn (as reported by `codex sessions search`).
+    #[arg(long = "fork-at-turn", value_name = "TURN", requires = "session_id")]
+    fork_at_turn: Option<usize>,
+
+    #[clap(flatten)]
+    filter: ResumeFilterArgs,
+
     #[clap(flatten)]
     config_overrides: TuiCli,
 }
 
+/// Narrows down the sessions offered by the picker and `--last`.
+#[derive(Debug, Default, clap::Args)]
+struct ResumeFilterArgs {
+    /// Only sessions started in this directory.
+    #[arg(long = "cwd", value_name = "DIR", conflicts_with = "session_id")]
+    cwd: Option<PathBuf>,
+
+    /// Only sessions started on this git branch.
+    #[arg(long = "branch", value_name = "BRANCH", conflicts_with = "session_id")]
+    branch: Option<String>,
+
+    /// Only sessions started on or after this day (YYYY-MM-DD, UTC) or time (RFC 3339).
+    #[arg(long = "since", value_name = "DATE", value_parser = parse_since, conflicts_with = "session_id")]
+    since: Option<DateTime<Utc>>,
+
+    /// Only sessions started on or before this day (YYYY-MM-DD, UTC) or before this time (RFC 3339).
+    #[arg(long = "until", value_name = "DATE", value_parser = parse_until, conflicts_with = "session_id")]
+    until: Option<DateTime<Utc>>,
+}
+
+impl ResumeFilterArgs {
+    fn into_filter(self) -> anyhow::Result<ConversationFilter> {
+        let cwd = match self.cwd {
+            Some(cwd) => Some(std::path::absolute(cwd)?),
+            None => None,
+        };
+        Ok(ConversationFilter {
+            cwd,
+            git_branch: self.branch,
+            created_after: self.since,
+            created_before: self.until,
+        })
+    }
+}
+
+fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_date(value, false)
+}
+
+fn parse_until(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_date(value, true)
+}
+
+/// Parses an RFC 3339 timestamp, or a day that stands for its start (or, with
+/// `end_of_day`, the start of the following day).
+fn parse_date(value: &str, end_of_day: bool) -> Result<DateTime<Utc>, String> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    let day = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("expected YYYY-MM-DD or an RFC 3339 timestamp, got `{value}`"))?;
+    let day = if end_of_day {
+        day.succ_opt()
+            .ok_or_else(|| format!("date out of range: `{value}`"))?
+    } else {
+        day
+    };
+    Ok(day.and_time(NaiveTime::MIN).and_utc())
+}
+
 #[derive(Debug, Parser)]
 struct SandboxArgs {
     #[command(subcommand)]
@@ -277,12 +355,21 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             prepend_config_flags(&mut mcp_cli.config_overrides, root_config_overrides.clone());
             mcp_cli.run().await?;
         }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            prepend_config_flags(
+                &mut sessions_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            sessions_cli.run().await?;
+        }
         Some(Subcommand::AppServer) => {
             codex_app_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
         }
         Some(Subcommand::Resume(ResumeCommand {
             session_id,
             last,
+            fork_at_turn,
+            filter,
             config_overrides,
         })) => {
             interactive = finalize_resume_interactive(
@@ -290,6 +377,8 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 root_config_overrides.clone(),
                 session_id,
                 last,
+                fork_at_turn,
+                filter.into_filter()?,
                 config_overrides,
             );
             let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
@@ -403,6 +492,8 @@ fn finalize_resume_interactive(
     root_config_overrides: CliConfigOverrides,
     session_id: Option<String>,
     last: bool,
+    fork_at_turn: Option<usize>,
+    resume_filter: ConversationFilter,
     resume_cli: TuiCli,
 ) -> TuiCli {
     // Start with the parsed interactive CLI so resume shares the same
@@ -411,6 +502,8 @@ fn finalize_resume_interactive(
     interactive.resume_picker = resume_session_id.is_none() && !last;
     interactive.resume_last = last;
     interactive.resume_session_id = resume_session_id;
+    interactive.resume_fork_at_turn = fork_at_turn;
+    interactive.resume_filter = resume_filter;
 
     // Merge resume-scoped flags and overrides with highest precedence.
     merge_resume_cli_flags(&mut interactive, resume_cli);
@@ -489,13 +582,23 @@ mod tests {
         let Subcommand::Resume(ResumeCommand {
             session_id,
             last,
+            fork_at_turn,
+            filter,
             config_overrides: resume_cli,
         }) = subcommand.expect("resume present")
         else {
             unreachable!()
         };
 
-        finalize_resume_interactive(interactive, root_overrides, session_id, last, resume_cli)
+        finalize_resume_interactive(
+            interactive,
+            root_overrides,
+            session_id,
+            last,
+            fork_at_turn,
+            filter.into_filter().expect("filter"),
+            resume_cli,
+        )
     }
 
     fn sample_exit_info(conversation: Option<&str>) -> AppExitInfo {
@@ -562,6 +665,54 @@ mod tests {
         assert_eq!(interactive.resume_session_id, None);
     }
 
+    #[test]
+    fn resume_filter_flags_narrow_the_picker() {
+        let interactive = finalize_from_args(
+            [
+                "codex",
+                "resume",
+                "--cwd",
+                "/repo",
+                "--branch",
+                "main",
+                "--since",
+                "2025-05-01",
+                "--until",
+                "2025-05-02",
+            ]
+            .as_ref(),
+        );
+        assert!(interactive.resume_picker);
+        assert_eq!(
+            interactive.resume_filter,
+            ConversationFilter {
+                cwd: Some(PathBuf::from("/repo")),
+                git_branch: Some("main".to_string()),
+                created_after: Some("2025-05-01T00:00:00Z".parse().expect("date")),
+                created_before: Some("2025-05-03T00:00:00Z".parse().expect("date")),
+            }
+        );
+    }
+
+    #[test]
+    fn resume_since_accepts_timestamps() {
+        let interactive = finalize_from_args(
+            [
+                "codex",
+                "resume",
+                "--last",
+                "--since",
+                "2025-05-01T12:00:00+02:00",
+            ]
+            .as_ref(),
+        );
+        assert!(interactive.resume_last);
+        assert_eq!(
+            interactive.resume_filter.created_after,
+            Some("2025-05-01T10:00:00Z".parse().expect("date"))
+        );
+    }
+
     #[test]
     fn resume_picker_logic_last() {
         let interactive = finalize_from_args(["codex", "resume", "--last"].as_ref());
@@ -578,6 +729,16 @@ mod tests {
         assert_eq!(interactive.resume_session_id.as_deref(), Some("1234"));
     }
 
+    #[test]
+    fn resume_fork_at_turn_requires_session_id() {
+        let interactive =
+            finalize_from_args(["codex", "resume", "1234", "--fork-at-turn", "2"].as_ref());
+        assert_eq!(interactive.resume_session_id.as_deref(), Some("1234"));
+        assert_eq!(interactive.resume_fork_at_turn, Some(2));
+
+        assert!(MultitoolCli::try_parse_from(["codex", "resume", "--fork-at-turn", "2"]).is_err());
+    }
+
     #[test]
     fn resume_merges_option_flags_and_full_auto() {
         let interactive = finalize_from_args(
@@ -239,6 +239,7 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         enabled: true,
         startup_timeout_sec: None,
         tool_timeout_sec: None,
+        auto_approve_sampling: false,
     };
 
     servers.insert(name.clone(), new_entry);
@@ -0,0 +1,260 @@
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::RolloutRecorder;
+use codex_core::SearchHitKind;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::find_codex_home;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::prune_sessions;
+use codex_tui::session_export::ExportFormat;
+use codex_tui::session_export::export_session;
+use std::path::PathBuf;
+
+/// Inspect recorded sessions.
+///
+/// Subcommands:
+/// - `search` — full-text search over the messages and commands of every session
+/// - `export` — render a session as a Markdown, HTML or JSON transcript
+/// - `prune` — delete sessions according to the retention policy
+#[derive(Debug, clap::Parser)]
+pub struct SessionsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionsSubcommand {
+    /// Search user messages, assistant messages and commands of recorded sessions.
+    Search(SearchArgs),
+
+    /// Render a recorded session as a transcript.
+    Export(ExportArgs),
+
+    /// Delete old sessions according to `[session_retention]` in config.toml.
+    Prune(PruneArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SearchArgs {
+    /// Words that must all appear in a matching message or command.
+    #[arg(value_name = "QUERY", required = true, num_args = 1..)]
+    pub query: Vec<String>,
+
+    /// Maximum number of hits to show.
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Output the hits as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ExportArgs {
+    /// Session id (UUID) or path to a rollout file.
+    #[arg(value_name = "SESSION")]
+    pub session: String,
+
+    /// Transcript format.
+    #[arg(long, value_enum, default_value = "md")]
+    pub format: ExportFormat,
+
+    /// Write the transcript to this file instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct PruneArgs {
+    /// Delete sessions without activity for more than this many days.
+    #[arg(long, value_name = "DAYS")]
+    pub max_age_days: Option<u64>,
+
+    /// Delete the least recently active sessions until the rest fit in this many bytes.
+    #[arg(long, value_name = "BYTES")]
+    pub max_total_bytes: Option<u64>,
+
+    /// Also prune sessions under `archived_sessions`.
+    #[arg(long)]
+    pub include_archived: bool,
+
+    /// Only list the sessions that would be deleted.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Output the report as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl SessionsCli {
+    pub async fn run(self) -> Result<()> {
+        let SessionsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        match subcommand {
+            SessionsSubcommand::Search(args) => {
+                run_search(&config_overrides, args).await?;
+            }
+            SessionsSubcommand::Export(args) => {
+                run_export(&config_overrides, args).await?;
+            }
+            SessionsSubcommand::Prune(args) => {
+                run_prune(&config_overrides, args).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn run_search(config_overrides: &CliConfigOverrides, search_args: SearchArgs) -> Result<()> {
+    // Validate any provided overrides even though they are not currently applied.
+    config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+
+    let SearchArgs { query, limit, json } = search_args;
+    let query = query.join(" ");
+
+    let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+    // Sessions from every source are searched; any of them can be resumed.
+    let hits = RolloutRecorder::search_conversations(&codex_home, &query, &[], limit)
+        .await
+        .with_context(|| format!("failed to search sessions in {}", codex_home.display()))?;
+
+    if json {
+        let output = serde_json::to_string_pretty(&hits)?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!("No sessions match '{query}'.");
+        return Ok(());
+    }
+
+    for hit in &hits {
+        let conversation = &hit.conversation;
+        let date = conversation
+            .created_at
+            .get(..10)
+            .unwrap_or(&conversation.created_at);
+        let mut location = conversation.cwd.display().to_string();
+        if let Some(branch) = &conversation.git_branch {
+            location.push_str(&format!(" ({branch})"));
+        }
+        let kind = match hit.kind {
+            SearchHitKind::UserMessage => "user",
+            SearchHitKind::AssistantMessage => "assistant",
+            SearchHitKind::Command => "command",
+        };
+        println!("{}  {date}  {location}", conversation.id);
+        println!("    turn {}, {kind}: {}", hit.turn, hit.snippet);
+    }
+    println!();
+    println!(
+        "Resume with `codex resume <SESSION_ID>`, or start over from before a turn with `codex resume <SESSION_ID> --fork-at-turn <TURN>`."
+    );
+
+    Ok(())
+}
+
+async fn run_export(config_overrides: &CliConfigOverrides, export_args: ExportArgs) -> Result<()> {
+    config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+
+    let ExportArgs {
+        session,
+        format,
+        output,
+    } = export_args;
+
+    let rollout_path = PathBuf::from(&session);
+    let rollout_path = if rollout_path.is_file() {
+        rollout_path
+    } else {
+        let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+        match find_conversation_path_by_id_str(&codex_home, &session).await? {
+            Some(path) => path,
+            None => bail!("No session found for '{session}'."),
+        }
+    };
+
+    let transcript = export_session(&rollout_path, format)
+        .await
+        .with_context(|| format!("failed to export {}", rollout_path.display()))?;
+
+    match output {
+        Some(output) => {
+            std::fs::write(&output, transcript)
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            println!("Wrote {}.", output.display());
+        }
+        None => print!("{transcript}"),
+    }
+
+    Ok(())
+}
+
+async fn run_prune(config_overrides: &CliConfigOverrides, prune_args: PruneArgs) -> Result<()> {
+    let overrides = config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+    let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+        .await
+        .context("failed to load configuration")?;
+
+    let PruneArgs {
+        max_age_days,
+        max_total_bytes,
+        include_archived,
+        dry_run,
+        json,
+    } = prune_args;
+
+    let mut retention = config.session_retention;
+    retention.max_age_days = max_age_days.or(retention.max_age_days);
+    retention.max_total_bytes = max_total_bytes.or(retention.max_total_bytes);
+    if include_archived {
+        retention.keep_archived = false;
+    }
+    if !retention.has_limits() {
+        bail!(
+            "No retention limit configured. Set `max_age_days` or `max_total_bytes` under [session_retention] in config.toml, or pass --max-age-days / --max-total-bytes."
+        );
+    }
+
+    let report = prune_sessions(&config.codex_home, &retention, None, dry_run)
+        .await
+        .with_context(|| {
+            format!(
+                "failed to prune sessions in {}",
+                config.codex_home.display()
+            )
+        })?;
+
+    if json {
+        let output = serde_json::to_string_pretty(&report)?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    for file in &report.removed {
+        println!("{}  ({} bytes)", file.path.display(), file.bytes);
+    }
+    let verb = if dry_run { "Would delete" } else { "Deleted" };
+    println!(
+        "{verb} {} sessions ({} bytes); {} sessions ({} bytes) kept.",
+        report.removed.len(),
+        report.removed_bytes(),
+        report.kept,
+        report.kept_bytes
+    );
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use predicates::str::contains;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use tempfile::TempDir;
+
+const SESSION_ID: &str = "0199a213-81c0-7800-8aa1-bbab2a035a53";
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+fn write_session(codex_home: &Path) -> Result<PathBuf> {
+    let dir = codex_home.join("sessions/2025/07/01");
+    std::fs::create_dir_all(&dir)?;
+    let lines = [
+        json!({
+            "timestamp": "2025-07-01T09:00:00.000Z",
+            "type": "session_meta",
+            "payload": {
+                "id": SESSION_ID,
+                "timestamp": "2025-07-01T09:00:00.000Z",
+                "cwd": "/repo",
+                "originator": "codex_cli_rs",
+                "cli_version": "0.0.0",
+                "instructions": null,
+                "source": "cli",
+            },
+        }),
+        json!({
+            "timestamp": "2025-07-01T09:00:01.000Z",
+            "type": "event_msg",
+            "payload": { "type": "user_message", "message": "fix the migration bug", "kind": "plain" },
+        }),
+        json!({
+            "timestamp": "2025-07-01T09:00:02.000Z",
+            "type": "event_msg",
+            "payload": { "type": "agent_message", "message": "The migration runs again." },
+        }),
+    ];
+    let contents: Vec<String> = lines.iter().map(JsonValue::to_string).collect();
+    let path = dir.join(format!("rollout-2025-07-01T09-00-00-{SESSION_ID}.jsonl"));
+    std::fs::write(&path, contents.join("\n") + "\n")?;
+    Ok(path)
+}
+
+#[test]
+fn export_by_id_renders_markdown() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    write_session(codex_home.path())?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    cmd.args(["sessions", "export", SESSION_ID])
+        .assert()
+        .success()
+        .stdout(contains(format!("# Codex session {SESSION_ID}")))
+        .stdout(contains("## User\n\nfix the migration bug"))
+        .stdout(contains("## Codex\n\nThe migration runs again."));
+
+    Ok(())
+}
+
+#[test]
+fn export_by_path_writes_html_file() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let rollout_path = write_session(codex_home.path())?;
+    let output = codex_home.path().join("transcript.html");
+
+    let mut cmd = codex_command(codex_home.path())?;
+    cmd.arg("sessions")
+        .arg("export")
+        .arg(&rollout_path)
+        .args(["--format", "html", "-o"])
+        .arg(&output)
+        .assert()
+        .success();
+
+    let html = std::fs::read_to_string(&output)?;
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<p>fix the migration bug</p>"));
+
+    Ok(())
+}
+
+#[test]
+fn export_unknown_session_fails() -> Result<()> {
+    let codex_home = TempDir::new()?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    cmd.args(["sessions", "export", SESSION_ID])
+        .assert()
+        .failure()
+        .stderr(contains("No session found"));
+
+    Ok(())
+}
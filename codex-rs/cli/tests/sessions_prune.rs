@@ -0,0 +1,81 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use predicates::str::contains;
+use serde_json::json;
+use tempfile::TempDir;
+
+const SESSION_ID: &str = "0199a213-81c0-7800-8aa1-bbab2a035a53";
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+fn write_session(codex_home: &Path) -> Result<PathBuf> {
+    let dir = codex_home.join("sessions/2025/07/01");
+    std::fs::create_dir_all(&dir)?;
+    let meta = json!({
+        "timestamp": "2025-07-01T09:00:00.000Z",
+        "type": "session_meta",
+        "payload": {
+            "id": SESSION_ID,
+            "timestamp": "2025-07-01T09:00:00.000Z",
+            "cwd": "/repo",
+            "originator": "codex_cli_rs",
+            "cli_version": "0.0.0",
+            "instructions": null,
+            "source": "cli",
+        },
+    });
+    let path = dir.join(format!("rollout-2025-07-01T09-00-00-{SESSION_ID}.jsonl"));
+    std::fs::write(&path, meta.to_string() + "\n")?;
+    Ok(path)
+}
+
+#[test]
+fn prune_dry_run_keeps_files() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let rollout_path = write_session(codex_home.path())?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    cmd.args(["sessions", "prune", "--max-total-bytes", "0", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(contains(SESSION_ID))
+        .stdout(contains("Would delete 1 sessions"));
+    assert!(rollout_path.exists());
+
+    Ok(())
+}
+
+#[test]
+fn prune_deletes_sessions_over_the_limit() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let rollout_path = write_session(codex_home.path())?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    cmd.args(["sessions", "prune", "--max-total-bytes", "0"])
+        .assert()
+        .success()
+        .stdout(contains("Deleted 1 sessions"));
+    assert!(!rollout_path.exists());
+
+    Ok(())
+}
+
+#[test]
+fn prune_without_limits_fails() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    write_session(codex_home.path())?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    cmd.args(["sessions", "prune"])
+        .assert()
+        .failure()
+        .stderr(contains("No retention limit configured"));
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use anyhow::Result;
+use predicates::str::contains;
+use pretty_assertions::assert_eq;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use tempfile::TempDir;
+
+const SESSION_ID: &str = "0199a213-81c0-7800-8aa1-bbab2a035a53";
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+fn message(timestamp: &str, role: &str, content_type: &str, text: &str) -> JsonValue {
+    json!({
+        "timestamp": timestamp,
+        "type": "response_item",
+        "payload": {
+            "type": "message",
+            "role": role,
+            "content": [{ "type": content_type, "text": text }],
+        },
+    })
+}
+
+fn write_session(codex_home: &Path) -> Result<()> {
+    let dir = codex_home.join("sessions/2025/07/01");
+    std::fs::create_dir_all(&dir)?;
+    let lines = [
+        json!({
+            "timestamp": "2025-07-01T09:00:00.000Z",
+            "type": "session_meta",
+            "payload": {
+                "id": SESSION_ID,
+                "timestamp": "2025-07-01T09:00:00.000Z",
+                "cwd": "/repo",
+                "originator": "codex_cli_rs",
+                "cli_version": "0.0.0",
+                "instructions": null,
+                "source": "cli",
+            },
+        }),
+        message(
+            "2025-07-01T09:00:01.000Z",
+            "user",
+            "input_text",
+            "list the tables",
+        ),
+        message(
+            "2025-07-01T09:00:02.000Z",
+            "user",
+            "input_text",
+            "fix the migration bug",
+        ),
+        message(
+            "2025-07-01T09:00:03.000Z",
+            "assistant",
+            "output_text",
+            "Done, the migration runs again",
+        ),
+    ];
+    let contents: Vec<String> = lines.iter().map(JsonValue::to_string).collect();
+    std::fs::write(
+        dir.join(format!("rollout-2025-07-01T09-00-00-{SESSION_ID}.jsonl")),
+        contents.join("\n") + "\n",
+    )?;
+    Ok(())
+}
+
+#[test]
+fn search_prints_matching_turns() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    write_session(codex_home.path())?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    cmd.args(["sessions", "search", "Migration", "bug"])
+        .assert()
+        .success()
+        .stdout(contains(format!("{SESSION_ID}  2025-07-01  /repo")))
+        .stdout(contains("turn 1, user: fix the migration bug"));
+
+    let mut cmd = codex_command(codex_home.path())?;
+    cmd.args(["sessions", "search", "rollback"])
+        .assert()
+        .success()
+        .stdout(contains("No sessions match 'rollback'."));
+
+    Ok(())
+}
+
+#[test]
+fn search_outputs_json() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    write_session(codex_home.path())?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    let output = cmd
+        .args(["sessions", "search", "migration", "--json"])
+        .output()?;
+    assert!(output.status.success());
+    let hits: JsonValue = serde_json::from_slice(&output.stdout)?;
+    let summary: Vec<(JsonValue, JsonValue)> = hits
+        .as_array()
+        .expect("array")
+        .iter()
+        .map(|hit| (hit["kind"].clone(), hit["turn"].clone()))
+        .collect();
+    // The user message outranks the assistant reply in the same turn.
+    assert_eq!(summary, vec![(json!("user_message"), json!(1))]);
+    assert_eq!(hits[0]["conversation"]["id"], json!(SESSION_ID));
+
+    Ok(())
+}
@@ -3,12 +3,14 @@ use codex_core::protocol::SandboxPolicy;
 pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
     match sandbox_policy {
         SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
-        SandboxPolicy::ReadOnly => "read-only".to_string(),
+        SandboxPolicy::ReadOnly { .. } => "read-only".to_string(),
         SandboxPolicy::WorkspaceWrite {
             writable_roots,
             network_access,
+            allowed_domains,
             exclude_tmpdir_env_var,
             exclude_slash_tmp,
+            ..
         } => {
             let mut summary = "workspace-write".to_string();
 
@@ -29,6 +31,11 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             summary.push_str(&format!(" [{}]", writable_entries.join(", ")));
             if *network_access {
                 summary.push_str(" (network access enabled)");
+            } else if !allowed_domains.is_empty() {
+                summary.push_str(&format!(
+                    " (network access limited to {})",
+                    allowed_domains.join(", ")
+                ));
             }
             summary
         }
@@ -0,0 +1,352 @@
+//! Record-and-replay of model traffic.
+//!
+//! In record mode each request body is written to `<dir>/<key>.request.json`
+//! and the raw SSE response to `<dir>/<key>.sse`, where `<key>` is a hash of
+//! the request with per-session and per-machine details removed. Only streams
+//! that complete without an error are saved. In replay mode the recorded
+//! response is streamed back without touching the network, and a request with
+//! no recording fails the turn.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::pin::Pin;
+
+use bytes::Bytes;
+use codex_protocol::protocol::ENVIRONMENT_CONTEXT_OPEN_TAG;
+use futures::Stream;
+use futures::StreamExt;
+use serde_json::Map;
+use serde_json::Value;
+use sha1::Digest;
+use sha1::Sha1;
+use tracing::warn;
+
+use crate::config_types::CassetteConfig;
+use crate::config_types::CassetteMode;
+use crate::error::CodexErr;
+use crate::error::Result;
+
+/// Request fields that differ between otherwise identical sessions.
+const VOLATILE_FIELDS: &[&str] = &["prompt_cache_key"];
+
+/// Environment context lines that describe the machine rather than the task.
+const VOLATILE_ENVIRONMENT_TAGS: &[&str] = &["<root>", "<shell>"];
+
+const CWD_PLACEHOLDER: &str = "<cwd>";
+const HOME_PLACEHOLDER: &str = "<home>";
+
+pub(crate) type CassetteStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
+
+/// Returns the recorded response for `payload` when replaying a cassette.
+pub(crate) fn replay(
+    cassette: Option<&CassetteConfig>,
+    payload: &Value,
+) -> Result<Option<CassetteStream>> {
+    let Some(cassette) = cassette.filter(|c| c.mode == CassetteMode::Replay) else {
+        return Ok(None);
+    };
+    let key = cassette_key(&normalize(payload));
+    let path = response_path(&cassette.dir, &key);
+    let body = std::fs::read(&path).map_err(|err| {
+        CodexErr::Fatal(format!(
+            "no recorded response for request {key} in cassette {}: {err}; \
+             the prompt, tools or instructions changed since it was recorded",
+            cassette.dir.display()
+        ))
+    })?;
+    let chunk: Result<Bytes> = Ok(Bytes::from(body));
+    Ok(Some(Box::pin(futures::stream::iter([chunk]))))
+}
+
+/// Passes `stream` through, saving a copy to the cassette when recording.
+/// Nothing is saved if the stream fails or is dropped before it ends.
+pub(crate) fn record<S>(
+    cassette: Option<&CassetteConfig>,
+    payload: &Value,
+    stream: S,
+) -> Result<CassetteStream>
+where
+    S: Stream<Item = Result<Bytes>> + Send + 'static,
+{
+    let Some(cassette) = cassette.filter(|c| c.mode == CassetteMode::Record) else {
+        return Ok(Box::pin(stream));
+    };
+    let request = normalize(payload);
+    let recording = Recording {
+        stream: Box::pin(stream),
+        dir: cassette.dir.clone(),
+        key: cassette_key(&request),
+        request: serde_json::to_string_pretty(&request)?,
+        body: Vec::new(),
+    };
+    Ok(Box::pin(futures::stream::unfold(
+        Some(recording),
+        |recording| async move {
+            let mut recording = recording?;
+            match recording.stream.next().await {
+                Some(Ok(chunk)) => {
+                    recording.body.extend_from_slice(&chunk);
+                    Some((Ok(chunk), Some(recording)))
+                }
+                Some(Err(err)) => Some((Err(err), None)),
+                None => {
+                    recording.save().await;
+                    None
+                }
+            }
+        },
+    )))
+}
+
+/// A response being copied into the cassette as it streams.
+struct Recording {
+    stream: CassetteStream,
+    dir: PathBuf,
+    key: String,
+    request: String,
+    body: Vec<u8>,
+}
+
+impl Recording {
+    async fn save(self) {
+        if let Err(err) = self.write().await {
+            warn!("failed to record response for {}: {err}", self.key);
+        }
+    }
+
+    async fn write(&self) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(
+            self.dir.join(format!("{}.request.json", self.key)),
+            &self.request,
+        )
+        .await?;
+        // Write then rename so a crash never leaves a truncated response.
+        let path = response_path(&self.dir, &self.key);
+        let tmp = path.with_extension("sse.tmp");
+        tokio::fs::write(&tmp, &self.body).await?;
+        tokio::fs::rename(&tmp, &path).await
+    }
+}
+
+/// Stable identifier for a normalized request: a hash of its canonical JSON
+/// form.
+fn cassette_key(payload: &Value) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(canonicalize(payload).to_string().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Removes what differs between runs of the same task: [`VOLATILE_FIELDS`],
+/// the working directory and home directory in any string, and
+/// [`VOLATILE_ENVIRONMENT_TAGS`] in the environment context. This lets a
+/// cassette recorded in one checkout replay in another.
+fn normalize(payload: &Value) -> Value {
+    let mut payload = payload.clone();
+    if let Some(obj) = payload.as_object_mut() {
+        for field in VOLATILE_FIELDS {
+            obj.remove(*field);
+        }
+    }
+    let cwd = find_cwd(&payload);
+    let home = dirs::home_dir();
+    map_strings(&mut payload, &|text| {
+        let mut text = text.to_string();
+        if text.contains(ENVIRONMENT_CONTEXT_OPEN_TAG) {
+            text = text
+                .lines()
+                .filter(|line| {
+                    let line = line.trim_start();
+                    !VOLATILE_ENVIRONMENT_TAGS
+                        .iter()
+                        .any(|tag| line.starts_with(tag))
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+        if let Some(cwd) = cwd.as_deref() {
+            text = text.replace(cwd, CWD_PLACEHOLDER);
+        }
+        if let Some(home) = home.as_deref().and_then(Path::to_str)
+            && !home.is_empty()
+        {
+            text = text.replace(home, HOME_PLACEHOLDER);
+        }
+        text
+    });
+    payload
+}
+
+/// The `<cwd>` reported in the request's environment context, if any.
+fn find_cwd(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => {
+            let (_, rest) = text.split_once("<cwd>")?;
+            let (cwd, _) = rest.split_once("</cwd>")?;
+            Some(cwd.to_string()).filter(|cwd| !cwd.is_empty())
+        }
+        Value::Array(items) => items.iter().find_map(find_cwd),
+        Value::Object(obj) => obj.values().find_map(find_cwd),
+        _ => None,
+    }
+}
+
+fn map_strings(value: &mut Value, f: &dyn Fn(&str) -> String) {
+    match value {
+        Value::String(text) => *text = f(text),
+        Value::Array(items) => items.iter_mut().for_each(|item| map_strings(item, f)),
+        Value::Object(obj) => obj.values_mut().for_each(|item| map_strings(item, f)),
+        _ => {}
+    }
+}
+
+/// Sorts object keys so the key does not depend on insertion order.
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(obj) => {
+            let sorted: BTreeMap<&String, Value> =
+                obj.iter().map(|(k, v)| (k, canonicalize(v))).collect();
+            Value::Object(
+                sorted
+                    .into_iter()
+                    .map(|(k, v)| (k.clone(), v))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        other => other.clone(),
+    }
+}
+
+fn response_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{key}.sse"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn cassette(dir: &TempDir, mode: CassetteMode) -> CassetteConfig {
+        CassetteConfig {
+            mode,
+            dir: dir.path().to_path_buf(),
+        }
+    }
+
+    async fn collect(stream: CassetteStream) -> Vec<u8> {
+        stream
+            .map(|chunk| chunk.expect("chunk").to_vec())
+            .concat()
+            .await
+    }
+
+    #[test]
+    fn key_ignores_volatile_fields_and_key_order() {
+        let a = json!({"model": "m", "input": [{"a": 1, "b": 2}], "prompt_cache_key": "x"});
+        let b = json!({"input": [{"b": 2, "a": 1}], "prompt_cache_key": "y", "model": "m"});
+        let c = json!({"model": "m", "input": [{"a": 1, "b": 3}]});
+
+        assert_eq!(cassette_key(&normalize(&a)), cassette_key(&normalize(&b)));
+        assert_ne!(cassette_key(&normalize(&a)), cassette_key(&normalize(&c)));
+    }
+
+    #[test]
+    fn key_ignores_the_checkout_location() {
+        let request = |cwd: &str, shell: &str| {
+            json!({"model": "m", "input": [
+                {"role": "user", "content": [{"type": "input_text", "text": format!(
+                    "<environment_context>\n  <cwd>{cwd}</cwd>\n  <writable_roots>\n    <root>{cwd}</root>\n    <root>/tmp/x{shell}</root>\n  </writable_roots>\n  <shell>{shell}</shell>\n</environment_context>"
+                )}]},
+                {"type": "function_call_output", "call_id": "c", "output": format!("{cwd}/src/lib.rs")},
+            ]})
+        };
+
+        assert_eq!(
+            cassette_key(&normalize(&request("/home/me/repo", "zsh"))),
+            cassette_key(&normalize(&request("/ci/build/repo", "bash")))
+        );
+    }
+
+    #[tokio::test]
+    async fn recorded_response_replays() {
+        let dir = TempDir::new().expect("tempdir");
+        let payload = json!({"model": "m", "input": "hi"});
+        let chunks: Vec<Result<Bytes>> = vec![
+            Ok(Bytes::from_static(b"data: one\n\n")),
+            Ok(Bytes::from_static(b"data: two\n\n")),
+        ];
+
+        let recorded = record(
+            Some(&cassette(&dir, CassetteMode::Record)),
+            &payload,
+            futures::stream::iter(chunks),
+        )
+        .expect("record");
+        assert_eq!(collect(recorded).await, b"data: one\n\ndata: two\n\n");
+
+        let replay_config = cassette(&dir, CassetteMode::Replay);
+        let replayed = replay(Some(&replay_config), &payload)
+            .expect("replay")
+            .expect("replay mode");
+        assert_eq!(collect(replayed).await, b"data: one\n\ndata: two\n\n");
+    }
+
+    #[tokio::test]
+    async fn failed_or_unfinished_streams_are_not_recorded() {
+        let dir = TempDir::new().expect("tempdir");
+        let record_config = cassette(&dir, CassetteMode::Record);
+        let chunks: Vec<Result<Bytes>> = vec![
+            Ok(Bytes::from_static(b"data: one\n\n")),
+            Err(CodexErr::Stream("boom".to_string(), None)),
+        ];
+        let failed = record(
+            Some(&record_config),
+            &json!({"model": "m", "input": "a"}),
+            futures::stream::iter(chunks),
+        )
+        .expect("record");
+        let _ = failed.collect::<Vec<_>>().await;
+
+        let chunks: Vec<Result<Bytes>> = vec![
+            Ok(Bytes::from_static(b"data: one\n\n")),
+            Ok(Bytes::from_static(b"data: two\n\n")),
+        ];
+        let mut unfinished = record(
+            Some(&record_config),
+            &json!({"model": "m", "input": "b"}),
+            futures::stream::iter(chunks),
+        )
+        .expect("record");
+        let _ = unfinished.next().await;
+        drop(unfinished);
+
+        let saved = std::fs::read_dir(dir.path()).expect("read dir").count();
+        assert_eq!(saved, 0);
+    }
+
+    #[test]
+    fn replay_fails_for_unrecorded_request() {
+        let dir = TempDir::new().expect("tempdir");
+        let replay_config = cassette(&dir, CassetteMode::Replay);
+
+        let Err(CodexErr::Fatal(message)) = replay(Some(&replay_config), &json!({"model": "m"}))
+        else {
+            panic!("expected a fatal error");
+        };
+        assert!(message.contains("no recorded response"), "{message}");
+    }
+
+    #[test]
+    fn passes_through_outside_of_its_mode() {
+        let dir = TempDir::new().expect("tempdir");
+        let record_config = cassette(&dir, CassetteMode::Record);
+
+        assert!(matches!(replay(None, &json!({})), Ok(None)));
+        assert!(matches!(replay(Some(&record_config), &json!({})), Ok(None)));
+    }
+}
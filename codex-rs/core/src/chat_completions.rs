@@ -1,9 +1,11 @@
 use std::time::Duration;
 
 use crate::ModelProviderInfo;
+use crate::cassette;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
+use crate::config_types::CassetteConfig;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::RetryLimitReachedError;
@@ -37,6 +39,7 @@ pub(crate) async fn stream_chat_completions(
     client: &reqwest::Client,
     provider: &ModelProviderInfo,
     otel_event_manager: &OtelEventManager,
+    cassette: Option<&CassetteConfig>,
 ) -> Result<ResponseStream> {
     if prompt.output_schema.is_some() {
         return Err(CodexErr::UnsupportedOperation(
@@ -277,12 +280,17 @@ pub(crate) async fn stream_chat_completions(
     }
 
     let tools_json = create_tools_json_for_chat_completions_api(&prompt.tools)?;
-    let payload = json!({
+    let mut payload = json!({
         "model": model_family.slug,
         "messages": messages,
         "stream": true,
         "tools": tools_json,
     });
+    if let Some(max_tokens) = prompt.max_output_tokens
+        && let Some(obj) = payload.as_object_mut()
+    {
+        obj.insert("max_tokens".to_string(), json!(max_tokens));
+    }
 
     debug!(
         "POST to {}: {}",
@@ -290,6 +298,17 @@ pub(crate) async fn stream_chat_completions(
         serde_json::to_string_pretty(&payload).unwrap_or_default()
     );
 
+    if let Some(stream) = cassette::replay(cassette, &payload)? {
+        let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+        tokio::spawn(process_chat_sse(
+            stream,
+            tx_event,
+            provider.stream_idle_timeout(),
+            otel_event_manager.clone(),
+        ));
+        return Ok(ResponseStream { rx_event });
+    }
+
     let mut attempt = 0;
     let max_retries = provider.request_max_retries();
     loop {
@@ -309,7 +328,11 @@ pub(crate) async fn stream_chat_completions(
         match res {
             Ok(resp) if resp.status().is_success() => {
                 let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
-                let stream = resp.bytes_stream().map_err(CodexErr::Reqwest);
+                let stream = cassette::record(
+                    cassette,
+                    &payload,
+                    resp.bytes_stream().map_err(CodexErr::Reqwest),
+                )?;
                 tokio::spawn(process_chat_sse(
                     stream,
                     tx_event,
@@ -1,10 +1,13 @@
 use std::io::BufRead;
 use std::path::Path;
 use std::sync::OnceLock;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 use crate::AuthManager;
 use crate::auth::CodexAuth;
+use crate::cassette;
 use crate::error::RetryLimitReachedError;
 use crate::error::UnexpectedResponseError;
 use bytes::Bytes;
@@ -34,22 +37,30 @@ use crate::client_common::ResponsesApiRequest;
 use crate::client_common::create_reasoning_param_for_request;
 use crate::client_common::create_text_param_for_request;
 use crate::config::Config;
+use crate::config_types::ModelFallback;
 use crate::default_client::create_client;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::UsageLimitReachedError;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
+use crate::messages_api::is_messages_api_reasoning;
+use crate::messages_api::stream_messages;
 use crate::model_family::ModelFamily;
+use crate::model_family::derive_default_model_family;
+use crate::model_family::find_family_for_model;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
 use crate::openai_model_info::get_model_info;
 use crate::openai_tools::create_tools_json_for_responses_api;
+use crate::pricing::ModelPricing;
+use crate::pricing::find_model_pricing;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::RateLimitWindow;
 use crate::protocol::TokenUsage;
 use crate::token_data::PlanType;
 use crate::util::backoff;
 use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use codex_protocol::models::ResponseItem;
@@ -81,6 +92,12 @@ pub struct ModelClient {
     conversation_id: ConversationId,
     effort: Option<ReasoningEffortConfig>,
     summary: ReasoningSummaryConfig,
+    /// Clients for the configured `model_fallbacks`, in order.
+    fallbacks: Arc<Vec<ModelClient>>,
+    /// Entry currently serving requests: `0` is this client and `n` is
+    /// `fallbacks[n - 1]`. Shared between clones so a failover sticks for the
+    /// rest of the task.
+    active: Arc<AtomicUsize>,
 }
 
 impl ModelClient {
@@ -95,6 +112,27 @@ impl ModelClient {
     ) -> Self {
         let client = create_client();
 
+        let fallbacks = config
+            .model_fallbacks
+            .iter()
+            .map(|fallback| {
+                let fallback_config = fallback_config(&config, fallback);
+                let otel_event_manager = otel_event_manager.with_model(
+                    fallback_config.model.as_str(),
+                    fallback_config.model_family.slug.as_str(),
+                );
+                ModelClient::new(
+                    Arc::new(fallback_config),
+                    auth_manager.clone(),
+                    otel_event_manager,
+                    fallback.model_provider.clone(),
+                    effort,
+                    summary,
+                    conversation_id,
+                )
+            })
+            .collect();
+
         Self {
             config,
             auth_manager,
@@ -104,25 +142,41 @@ impl ModelClient {
             conversation_id,
             effort,
             summary,
+            fallbacks: Arc::new(fallbacks),
+            active: Arc::new(AtomicUsize::new(0)),
         }
     }
 
+    /// Context window of the model serving requests.
     pub fn get_model_context_window(&self) -> Option<u64> {
-        self.config
+        let config = &self.active_client().config;
+        config
             .model_context_window
-            .or_else(|| get_model_info(&self.config.model_family).map(|info| info.context_window))
+            .or_else(|| get_model_info(&config.model_family).map(|info| info.context_window))
     }
 
+    /// Auto-compact limit of the model serving requests.
     pub fn get_auto_compact_token_limit(&self) -> Option<i64> {
-        self.config.model_auto_compact_token_limit.or_else(|| {
-            get_model_info(&self.config.model_family).and_then(|info| info.auto_compact_token_limit)
+        let config = &self.active_client().config;
+        config.model_auto_compact_token_limit.or_else(|| {
+            get_model_info(&config.model_family).and_then(|info| info.auto_compact_token_limit)
         })
     }
 
-    /// Dispatches to either the Responses or Chat implementation depending on
-    /// the provider config.  Public callers always invoke `stream()` – the
-    /// specialised helpers are private to avoid accidental misuse.
+    pub fn get_compaction_strategy(&self) -> CompactionStrategy {
+        self.config.compaction_strategy
+    }
+
+    /// Streams a response from the active entry of the fallback chain.
+    /// Public callers always invoke `stream()` – the specialised helpers are
+    /// private to avoid accidental misuse.
     pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        self.active_client().stream_with_wire_api(prompt).await
+    }
+
+    /// Dispatches to the Responses, Chat or Messages implementation depending
+    /// on the provider config.
+    async fn stream_with_wire_api(&self, prompt: &Prompt) -> Result<ResponseStream> {
         match self.provider.wire_api {
             WireApi::Responses => self.stream_responses(prompt).await,
             WireApi::Chat => {
@@ -133,6 +187,7 @@ impl ModelClient {
                     &self.client,
                     &self.provider,
                     &self.otel_event_manager,
+                    self.config.cassette.as_ref(),
                 )
                 .await?;
 
@@ -161,6 +216,17 @@ impl ModelClient {
 
                 Ok(ResponseStream { rx_event: rx })
             }
+            WireApi::Messages => {
+                stream_messages(
+                    prompt,
+                    &self.config,
+                    self.effort,
+                    &self.client,
+                    &self.provider,
+                    &self.otel_event_manager,
+                )
+                .await
+            }
         }
     }
 
@@ -193,7 +259,10 @@ impl ModelClient {
             vec![]
         };
 
-        let input_with_instructions = prompt.get_formatted_input();
+        // Thinking signatures from the Messages API are rejected here, e.g.
+        // when a session fails over from an Anthropic model.
+        let mut input_with_instructions = prompt.get_formatted_input();
+        input_with_instructions.retain(|item| !is_messages_api_reasoning(item));
 
         let verbosity = match &self.config.model_family.family {
             family if family == "gpt-5" => self.config.model_verbosity,
@@ -234,6 +303,7 @@ impl ModelClient {
             include,
             prompt_cache_key: Some(self.conversation_id.to_string()),
             text,
+            max_output_tokens: prompt.max_output_tokens,
         };
 
         let mut payload_json = serde_json::to_value(&payload)?;
@@ -241,6 +311,17 @@ impl ModelClient {
             attach_item_ids(&mut payload_json, &input_with_instructions);
         }
 
+        if let Some(stream) = cassette::replay(self.config.cassette.as_ref(), &payload_json)? {
+            let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+            tokio::spawn(process_sse(
+                stream,
+                tx_event,
+                self.provider.stream_idle_timeout(),
+                self.otel_event_manager.clone(),
+            ));
+            return Ok(ResponseStream { rx_event });
+        }
+
         let max_attempts = self.provider.request_max_retries();
         for attempt in 0..=max_attempts {
             match self
@@ -336,7 +417,12 @@ impl ModelClient {
                 }
 
                 // spawn task to process SSE
-                let stream = resp.bytes_stream().map_err(CodexErr::Reqwest);
+                let stream = cassette::record(
+                    self.config.cassette.as_ref(),
+                    payload_json,
+                    resp.bytes_stream().map_err(CodexErr::Reqwest),
+                )
+                .map_err(StreamAttemptError::Fatal)?;
                 tokio::spawn(process_sse(
                     stream,
                     tx_event,
@@ -451,6 +537,84 @@ impl ModelClient {
     pub fn get_auth_manager(&self) -> Option<Arc<AuthManager>> {
         self.auth_manager.clone()
     }
+
+    /// Returns the model serving requests, which differs from
+    /// [`ModelClient::get_model`] after a failover.
+    pub fn active_model(&self) -> String {
+        self.active_client().config.model.clone()
+    }
+
+    /// Returns the family of the model serving requests, which differs from
+    /// [`ModelClient::get_model_family`] after a failover.
+    pub fn active_model_family(&self) -> ModelFamily {
+        self.active_client().config.model_family.clone()
+    }
+
+    /// Returns the provider serving requests, which differs from
+    /// [`ModelClient::get_provider`] after a failover.
+    pub fn active_provider(&self) -> ModelProviderInfo {
+        self.active_client().provider.clone()
+    }
+
+    /// Price of the model serving requests, if known.
+    pub fn active_pricing(&self) -> Option<ModelPricing> {
+        let config = &self.active_client().config;
+        find_model_pricing(
+            &config.model_pricing,
+            &config.model_provider_id,
+            &config.model,
+        )
+    }
+
+    /// Short description of the active entry for user-facing messages.
+    pub fn active_label(&self) -> String {
+        let client = self.active_client();
+        format!("`{}` on {}", client.config.model, client.provider.name)
+    }
+
+    /// Switches subsequent requests to the next entry in `model_fallbacks`.
+    /// Returns `false` when the chain is exhausted.
+    pub fn fail_over(&self) -> bool {
+        let next = self.active.load(Ordering::SeqCst) + 1;
+        if next > self.fallbacks.len() {
+            return false;
+        }
+        self.active.store(next, Ordering::SeqCst);
+        true
+    }
+
+    /// Sends requests to the primary entry again.
+    pub fn reset_fallback(&self) {
+        self.active.store(0, Ordering::SeqCst);
+    }
+
+    fn active_client(&self) -> &ModelClient {
+        match self.active.load(Ordering::SeqCst) {
+            0 => self,
+            n => self.fallbacks.get(n - 1).unwrap_or(self),
+        }
+    }
+}
+
+/// Config for a fallback entry: the primary config with the model and
+/// provider swapped out. Model limits come from the fallback model rather
+/// than from overrides meant for the primary one.
+fn fallback_config(config: &Config, fallback: &ModelFallback) -> Config {
+    let model_family = find_family_for_model(&fallback.model)
+        .unwrap_or_else(|| derive_default_model_family(&fallback.model));
+    let model_info = get_model_info(&model_family);
+
+    let mut config = config.clone();
+    config.model = fallback.model.clone();
+    config.model_context_window = model_info.as_ref().map(|info| info.context_window);
+    config.model_max_output_tokens = model_info.as_ref().map(|info| info.max_output_tokens);
+    config.model_auto_compact_token_limit =
+        model_info.and_then(|info| info.auto_compact_token_limit);
+    config.model_family = model_family;
+    config.model_provider_id = fallback.model_provider_id.clone();
+    config.model_provider = fallback.model_provider.clone();
+    config.model_fallbacks = Vec::new();
+    config
 }
 
 enum StreamAttemptError {
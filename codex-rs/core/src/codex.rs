@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::fmt::Debug;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
@@ -30,6 +31,8 @@ use mcp_types::CallToolResult;
 use serde_json;
 use serde_json::Value;
 use tokio::sync::Mutex;
+use tokio::sync::RwLock;
+use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tracing::debug;
 use tracing::error;
@@ -42,6 +45,7 @@ use crate::apply_patch::convert_apply_patch_to_protocol;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
+use crate::command_safety::exec_policy::load_user_exec_policy;
 use crate::config::Config;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::conversation_history::ConversationHistory;
@@ -56,9 +60,12 @@ use crate::exec_command::ExecSessionManager;
 use crate::exec_command::WriteStdinParams;
 use crate::executor::Executor;
 use crate::executor::ExecutorConfig;
+use crate::executor::ProjectApprovalStore;
 use crate::executor::normalize_exec_result;
+use crate::hooks::HookRunner;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_connection_manager::McpSamplingRequest;
 use crate::model_family::find_family_for_model;
 use crate::openai_model_info::get_model_info;
 use crate::openai_tools::ToolsConfig;
@@ -72,6 +79,7 @@ use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
+use crate::protocol::DenyRead;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
@@ -80,12 +88,17 @@ use crate::protocol::ExecCommandBeginEvent;
 use crate::protocol::ExecCommandEndEvent;
 use crate::protocol::InputItem;
 use crate::protocol::ListCustomPromptsResponseEvent;
+use crate::protocol::McpListResourcesResponseEvent;
+use crate::protocol::McpServersReloadedEvent;
 use crate::protocol::Op;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
+use crate::protocol::PinnedItem;
+use crate::protocol::PinnedItemsEvent;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReviewDecision;
 use crate::protocol::ReviewOutputEvent;
+use crate::protocol::SandboxDenial;
 use crate::protocol::SandboxPolicy;
 use crate::protocol::SessionConfiguredEvent;
 use crate::protocol::StreamErrorEvent;
@@ -112,6 +125,7 @@ use crate::user_instructions::UserInstructions;
 use crate::user_notification::UserNotification;
 use crate::util::backoff;
 use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use codex_protocol::custom_prompts::CustomPrompt;
@@ -122,8 +136,9 @@ use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::InitialHistory;
 
 pub mod compact;
-use self::compact::build_compacted_history;
-use self::compact::collect_user_messages;
+use self::compact::compact_history;
+mod cost_budget;
+mod mcp_sampling;
 
 /// The high-level interface to the Codex system.
 /// It operates as a queue pair where you send submissions and receive events.
@@ -154,6 +169,7 @@ impl Codex {
     ) -> CodexResult<CodexSpawnOk> {
         let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
         let (tx_event, rx_event) = async_channel::unbounded();
+        let (tx_sampling, rx_sampling) = mpsc::channel(SUBMISSION_CHANNEL_CAPACITY);
 
         let user_instructions = get_user_instructions(&config).await;
 
@@ -178,6 +194,7 @@ impl Codex {
             config.clone(),
             auth_manager.clone(),
             tx_event.clone(),
+            tx_sampling,
             conversation_history,
             session_source,
         )
@@ -189,7 +206,13 @@ impl Codex {
         let conversation_id = session.conversation_id;
 
         // This task will run until Op::Shutdown is received.
-        tokio::spawn(submission_loop(session, turn_context, config, rx_sub));
+        tokio::spawn(submission_loop(
+            session,
+            turn_context,
+            config,
+            rx_sub,
+            rx_sampling,
+        ));
         let codex = Codex {
             next_id: AtomicU64::new(0),
             tx_sub,
@@ -313,6 +336,7 @@ impl Session {
         config: Arc<Config>,
         auth_manager: Arc<AuthManager>,
         tx_event: Sender<Event>,
+        tx_sampling: mpsc::Sender<McpSamplingRequest>,
         initial_history: InitialHistory,
         session_source: SessionSource,
     ) -> anyhow::Result<(Arc<Self>, TurnContext)> {
@@ -366,6 +390,7 @@ impl Session {
             config.mcp_servers.clone(),
             config.use_experimental_use_rmcp_client,
             config.mcp_oauth_credentials_store_mode,
+            tx_sampling,
         );
         let default_shell_fut = shell::default_user_shell();
         let history_meta_fut = crate::message_history::history_metadata(&config);
@@ -390,7 +415,8 @@ impl Session {
 
         let rollout_path = rollout_recorder.rollout_path.clone();
         // Create the mutable state for the Session.
-        let state = SessionState::new();
+        let mut state = SessionState::new();
+        state.set_session_budget(config.session_budget_usd);
 
         // Handle MCP manager result and record any startup failures.
         let (mcp_connection_manager, failed_clients) = match mcp_res {
@@ -472,18 +498,43 @@ impl Session {
             is_review_mode: false,
             final_output_json_schema: None,
         };
+        // Merge the user's execpolicy files into the default policy and report
+        // files that fail to parse or fail their own examples.
+        let user_exec_policy =
+            load_user_exec_policy(&config.codex_home, &turn_context.cwd, config.cwd_trusted);
+        for issue in &user_exec_policy.issues {
+            warn!("ignoring execpolicy file {issue}");
+            post_session_configured_error_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::BackgroundEvent(BackgroundEventEvent {
+                    message: format!("Ignoring execpolicy file {issue}"),
+                }),
+            });
+        }
+
         let services = SessionServices {
-            mcp_connection_manager,
+            mcp_connection_manager: RwLock::new(Arc::new(mcp_connection_manager)),
+            mcp_reload: Mutex::new(()),
             session_manager: ExecSessionManager::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: notify,
+            hooks: HookRunner::new(&config.hooks),
             rollout: Mutex::new(Some(rollout_recorder)),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
-            executor: Executor::new(ExecutorConfig::new(
-                turn_context.sandbox_policy.clone(),
-                turn_context.cwd.clone(),
-                config.codex_linux_sandbox_exe.clone(),
+            executor: Executor::new(
+                ExecutorConfig::new(
+                    turn_context.sandbox_policy.clone(),
+                    turn_context.cwd.clone(),
+                    config.codex_linux_sandbox_exe.clone(),
+                )
+                .with_exec_policy(user_exec_policy.policy)
+                .with_resource_limits(config.resource_limits.clone())
+                .with_linux_sandbox(config.linux_sandbox),
+            )
+            .with_approval_store(ProjectApprovalStore::for_cwd(
+                &config.codex_home,
+                &turn_context.cwd,
             )),
         };
 
@@ -555,6 +606,22 @@ impl Session {
                     self.record_into_history(&reconstructed_history).await;
                 }
 
+                // Resumed and forked sessions keep counting from the last
+                // estimated cost of the history they start from.
+                if let Some(cost_usd) = rollout_items.iter().rev().find_map(|item| match item {
+                    RolloutItem::EventMsg(EventMsg::TokenCount(ev)) => ev.cost_usd,
+                    _ => None,
+                }) {
+                    self.state.lock().await.add_cost(cost_usd);
+                }
+
+                if let Some(items) = rollout_items.iter().rev().find_map(|item| match item {
+                    RolloutItem::EventMsg(EventMsg::PinnedItems(ev)) => Some(ev.items.clone()),
+                    _ => None,
+                }) {
+                    self.state.lock().await.pinned_items = items;
+                }
+
                 // If persisting, persist all rollout items as-is (recorder filters)
                 if persist && !rollout_items.is_empty() {
                     self.persist_rollout_items(&rollout_items).await;
@@ -585,6 +652,7 @@ impl Session {
         command: Vec<String>,
         cwd: PathBuf,
         reason: Option<String>,
+        sandbox_denials: Vec<SandboxDenial>,
     ) -> ReviewDecision {
         // Add the tx_approve callback to the map before sending the request.
         let (tx_approve, rx_approve) = oneshot::channel();
@@ -610,6 +678,7 @@ impl Session {
                 command,
                 cwd,
                 reason,
+                sandbox_denials,
             }),
         };
         self.send_event(event).await;
@@ -695,11 +764,12 @@ impl Session {
                 }
                 RolloutItem::Compacted(compacted) => {
                     let snapshot = history.contents();
-                    let user_messages = collect_user_messages(&snapshot);
-                    let rebuilt = build_compacted_history(
+                    let rebuilt = compact_history(
+                        compacted.strategy,
                         self.build_initial_context(turn_context),
-                        &user_messages,
+                        &snapshot,
                         &compacted.message,
+                        &compacted.pinned,
                     );
                     history.replace(rebuilt);
                 }
@@ -773,6 +843,9 @@ impl Session {
                     token_usage,
                     turn_context.client.get_model_context_window(),
                 );
+                if let Some(pricing) = turn_context.client.active_pricing() {
+                    state.add_cost(pricing.cost_usd(token_usage));
+                }
             }
         }
         self.send_token_count_event(sub_id).await;
@@ -787,13 +860,18 @@ impl Session {
     }
 
     async fn send_token_count_event(&self, sub_id: &str) {
-        let (info, rate_limits) = {
+        let (info, rate_limits, cost_usd) = {
             let state = self.state.lock().await;
-            state.token_info_and_rate_limits()
+            let (info, rate_limits) = state.token_info_and_rate_limits();
+            (info, rate_limits, state.cost_usd)
         };
         let event = Event {
             id: sub_id.to_string(),
-            msg: EventMsg::TokenCount(TokenCountEvent { info, rate_limits }),
+            msg: EventMsg::TokenCount(TokenCountEvent {
+                info,
+                rate_limits,
+                cost_usd,
+            }),
         };
         self.send_event(event).await;
     }
@@ -893,6 +971,8 @@ impl Session {
             duration,
             exit_code,
             timed_out: _,
+            resource_limit_exceeded: _,
+            sandbox_denials,
         } = output;
         // Send full stdout/stderr to clients; do not truncate.
         let stdout = stdout.text.clone();
@@ -916,6 +996,7 @@ impl Session {
                 exit_code: *exit_code,
                 duration: *duration,
                 formatted_output,
+                sandbox_denials: sandbox_denials.clone(),
             })
         };
 
@@ -996,6 +1077,54 @@ impl Session {
         self.send_event(event).await;
     }
 
+    /// Keeps `item` across compaction and reports the pinned items. Files
+    /// are checked now but read when the history is compacted.
+    async fn pin_item(&self, sub_id: &str, cwd: &Path, item: PinnedItem) {
+        let item = match item {
+            PinnedItem::File { path } => {
+                let path = cwd.join(path);
+                if !path.is_file() {
+                    let event = Event {
+                        id: sub_id.to_string(),
+                        msg: EventMsg::Error(ErrorEvent {
+                            message: format!("Cannot pin {}: not a file", path.display()),
+                        }),
+                    };
+                    self.send_event(event).await;
+                    return;
+                }
+                PinnedItem::File { path }
+            }
+            message @ PinnedItem::Message { .. } => message,
+        };
+        let items = {
+            let mut state = self.state.lock().await;
+            if !state.pinned_items.contains(&item) {
+                state.pinned_items.push(item);
+            }
+            state.pinned_items.clone()
+        };
+        let event = Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::PinnedItems(PinnedItemsEvent { items }),
+        };
+        self.send_event(event).await;
+    }
+
+    /// Stops keeping `item` across compaction and reports the pinned items.
+    async fn unpin_item(&self, sub_id: &str, item: &PinnedItem) {
+        let items = {
+            let mut state = self.state.lock().await;
+            state.pinned_items.retain(|pinned| pinned != item);
+            state.pinned_items.clone()
+        };
+        let event = Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::PinnedItems(PinnedItemsEvent { items }),
+        };
+        self.send_event(event).await;
+    }
+
     async fn notify_stream_error(&self, sub_id: &str, message: impl Into<String>) {
         let event = Event {
             id: sub_id.to_string(),
@@ -1016,6 +1145,41 @@ impl Session {
         [history, extra].concat()
     }
 
+    /// Expands MCP prompt invocations and `@server:resource` mentions in user
+    /// input. Prompt messages that precede the final user messages are
+    /// recorded in the conversation with their own roles. Returns `None` when
+    /// there is nothing left to send, after reporting an error if an MCP
+    /// prompt could not be rendered. Mentioned resources that `deny_read`
+    /// covers are not inlined.
+    async fn expand_mcp_input(
+        &self,
+        sub_id: &str,
+        items: Vec<InputItem>,
+        deny_read: &DenyRead,
+    ) -> Option<Vec<InputItem>> {
+        let manager = self.mcp_connection_manager().await;
+        let items = match crate::mcp::prompts::expand_mcp_prompt_invocation(&manager, items).await {
+            Ok(expanded) => {
+                if !expanded.history.is_empty() {
+                    self.record_conversation_items(&expanded.history).await;
+                }
+                if expanded.input.is_empty() {
+                    return None;
+                }
+                expanded.input
+            }
+            Err(message) => {
+                self.send_event(Event {
+                    id: sub_id.to_string(),
+                    msg: EventMsg::Error(ErrorEvent { message }),
+                })
+                .await;
+                return None;
+            }
+        };
+        Some(crate::mcp::resources::inline_resource_mentions(&manager, items, deny_read).await)
+    }
+
     /// Returns the input if there was no task running to inject into
     pub async fn inject_input(&self, input: Vec<InputItem>) -> Result<(), Vec<InputItem>> {
         let mut active = self.active_turn.lock().await;
@@ -1046,15 +1210,83 @@ impl Session {
         tool: &str,
         arguments: Option<serde_json::Value>,
     ) -> anyhow::Result<CallToolResult> {
-        self.services
-            .mcp_connection_manager
+        self.mcp_connection_manager()
+            .await
             .call_tool(server, tool, arguments)
             .await
     }
 
-    pub(crate) fn parse_mcp_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
-        self.services
-            .mcp_connection_manager
+    /// The current MCP connection manager. The lock is held only to clone the
+    /// `Arc`, so a long tool call never holds up a reload, and a reload never
+    /// holds up anything else: it swaps in a new manager once it is ready.
+    pub(crate) async fn mcp_connection_manager(&self) -> Arc<McpConnectionManager> {
+        Arc::clone(&*self.services.mcp_connection_manager.read().await)
+    }
+
+    /// Re-reads `mcp_servers` with the session's profile and `-c` overrides
+    /// and starts, stops or restarts MCP clients to match it. The new clients
+    /// are started next to the current ones, which stay in use until the new
+    /// manager is swapped in. Servers that fail to start are reported the same
+    /// way as at session start. Reloads requested while one is in progress
+    /// wait for it, then apply the configuration as it is by then.
+    async fn reload_mcp_servers(&self, sub_id: &str, config: &Config) {
+        let _reload = self.services.mcp_reload.lock().await;
+        let mcp_servers = match config.reload().await {
+            Ok(config) => config.mcp_servers,
+            Err(e) => {
+                self.send_event(Event {
+                    id: sub_id.to_string(),
+                    msg: EventMsg::Error(ErrorEvent {
+                        message: format!("Failed to load MCP server configuration: {e}"),
+                    }),
+                })
+                .await;
+                return;
+            }
+        };
+
+        let current = self.mcp_connection_manager().await;
+        let (manager, outcome) = match current.reload(mcp_servers).await {
+            Ok(reloaded) => reloaded,
+            Err(e) => {
+                self.send_event(Event {
+                    id: sub_id.to_string(),
+                    msg: EventMsg::Error(ErrorEvent {
+                        message: format!("Failed to reload MCP servers: {e:#}"),
+                    }),
+                })
+                .await;
+                return;
+            }
+        };
+        let tools = manager.list_all_tools();
+        *self.services.mcp_connection_manager.write().await = Arc::new(manager);
+
+        for (server_name, err) in outcome.errors {
+            let message = format!("MCP client for `{server_name}` failed to start: {err:#}");
+            error!("{message}");
+            self.send_event(Event {
+                id: sub_id.to_string(),
+                msg: EventMsg::Error(ErrorEvent { message }),
+            })
+            .await;
+        }
+
+        self.send_event(Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::McpServersReloaded(McpServersReloadedEvent {
+                started: outcome.started,
+                restarted: outcome.restarted,
+                stopped: outcome.stopped,
+                tools,
+            }),
+        })
+        .await;
+    }
+
+    pub(crate) async fn parse_mcp_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
+        self.mcp_connection_manager()
+            .await
             .parse_tool_name(tool_name)
     }
 
@@ -1113,6 +1345,10 @@ impl Session {
         }
     }
 
+    pub(crate) fn conversation_id(&self) -> ConversationId {
+        self.conversation_id
+    }
+
     pub(crate) fn notifier(&self) -> &UserNotifier {
         &self.services.notifier
     }
@@ -1137,11 +1373,28 @@ async fn submission_loop(
     turn_context: TurnContext,
     config: Arc<Config>,
     rx_sub: Receiver<Submission>,
+    mut rx_sampling: mpsc::Receiver<McpSamplingRequest>,
 ) {
     // Wrap once to avoid cloning TurnContext for each task.
     let mut turn_context = Arc::new(turn_context);
     // To break out of this loop, send Op::Shutdown.
-    while let Ok(sub) = rx_sub.recv().await {
+    loop {
+        let sub = tokio::select! {
+            sub = rx_sub.recv() => match sub {
+                Ok(sub) => sub,
+                Err(_) => break,
+            },
+            // Sampling requests from MCP servers may wait on the user's
+            // approval, so answer them off the submission loop.
+            Some(request) = rx_sampling.recv() => {
+                tokio::spawn(mcp_sampling::handle_sampling_request(
+                    Arc::clone(&sess),
+                    Arc::clone(&turn_context),
+                    request,
+                ));
+                continue;
+            }
+        };
         debug!(?sub, "Submission");
         match sub.op {
             Op::Interrupt => {
@@ -1246,6 +1499,12 @@ async fn submission_loop(
                     .client
                     .get_otel_event_manager()
                     .user_prompt(&items);
+                let deny_read = turn_context
+                    .sandbox_policy
+                    .get_deny_read_with_cwd(&turn_context.cwd);
+                let Some(items) = sess.expand_mcp_input(&sub.id, items, &deny_read).await else {
+                    continue;
+                };
                 // attempt to inject input into current task
                 if let Err(items) = sess.inject_input(items).await {
                     // no current task, spawn a new one
@@ -1267,6 +1526,10 @@ async fn submission_loop(
                     .client
                     .get_otel_event_manager()
                     .user_prompt(&items);
+                let deny_read = sandbox_policy.get_deny_read_with_cwd(&cwd);
+                let Some(items) = sess.expand_mcp_input(&sub.id, items, &deny_read).await else {
+                    continue;
+                };
                 // attempt to inject input into current task
                 if let Err(items) = sess.inject_input(items).await {
                     // Derive a fresh TurnContext for this turn using the provided overrides.
@@ -1365,6 +1628,12 @@ async fn submission_loop(
                 }
                 other => sess.notify_approval(&id, other).await,
             },
+            Op::McpSamplingApproval { id, decision } => {
+                sess.notify_approval(&id, decision).await;
+            }
+            Op::CostBudgetApproval { id, decision } => {
+                sess.notify_approval(&id, decision).await;
+            }
             Op::AddToHistory { text } => {
                 let id = sess.conversation_id;
                 let config = config.clone();
@@ -1413,7 +1682,7 @@ async fn submission_loop(
                 let sub_id = sub.id.clone();
 
                 // This is a cheap lookup from the connection manager's cache.
-                let tools = sess.services.mcp_connection_manager.list_all_tools();
+                let tools = sess.mcp_connection_manager().await.list_all_tools();
                 let auth_statuses = compute_auth_statuses(
                     config.mcp_servers.iter(),
                     config.mcp_oauth_credentials_store_mode,
@@ -1430,15 +1699,43 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
+            Op::ListMcpResources => {
+                let sub_id = sub.id.clone();
+
+                // Resources are listed once at startup, so this is also a cache lookup.
+                let mcp_connection_manager = sess.mcp_connection_manager().await;
+                let event = Event {
+                    id: sub_id,
+                    msg: EventMsg::McpListResourcesResponse(McpListResourcesResponseEvent {
+                        resources: mcp_connection_manager.list_all_resources(),
+                        resource_templates: mcp_connection_manager.list_all_resource_templates(),
+                    }),
+                };
+                sess.send_event(event).await;
+            }
+            Op::ReloadMcpServers => {
+                let sess_clone = sess.clone();
+                let sub_id = sub.id.clone();
+                let config = Arc::clone(&config);
+
+                // Starting servers can take a while, so keep the submission
+                // loop free to handle other ops in the meantime.
+                tokio::spawn(async move {
+                    sess_clone.reload_mcp_servers(&sub_id, &config).await;
+                });
+            }
             Op::ListCustomPrompts => {
                 let sub_id = sub.id.clone();
 
-                let custom_prompts: Vec<CustomPrompt> =
+                let mut custom_prompts: Vec<CustomPrompt> =
                     if let Some(dir) = crate::custom_prompts::default_prompts_dir() {
                         crate::custom_prompts::discover_prompts_in(&dir).await
                     } else {
                         Vec::new()
                     };
+                custom_prompts.extend(crate::mcp::prompts::list_mcp_prompts(
+                    &*sess.mcp_connection_manager().await,
+                ));
 
                 let event = Event {
                     id: sub_id,
@@ -1448,18 +1745,46 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
-            Op::Compact => {
-                // Attempt to inject input into current task
-                if let Err(items) = sess
-                    .inject_input(vec![InputItem::Text {
-                        text: compact::SUMMARIZATION_PROMPT.to_string(),
-                    }])
-                    .await
-                {
-                    sess.spawn_task(Arc::clone(&turn_context), sub.id, items, CompactTask)
-                        .await;
+            Op::Compact { strategy } => {
+                let strategy =
+                    strategy.unwrap_or_else(|| turn_context.client.get_compaction_strategy());
+                let input = vec![InputItem::Text {
+                    text: compact::SUMMARIZATION_PROMPT.to_string(),
+                }];
+                // A summary can be requested from the current task; the other
+                // strategies rewrite the history in a task of their own, which
+                // must not replace a running turn.
+                let pending = if strategy == CompactionStrategy::Summarize {
+                    sess.inject_input(input).await.err()
+                } else if sess.active_turn.lock().await.is_some() {
+                    let event = Event {
+                        id: sub.id.clone(),
+                        msg: EventMsg::Error(ErrorEvent {
+                            message: "Cannot compact while a turn is running; try again once it finishes."
+                                .to_string(),
+                        }),
+                    };
+                    sess.send_event(event).await;
+                    None
+                } else {
+                    Some(input)
+                };
+                if let Some(items) = pending {
+                    sess.spawn_task(
+                        Arc::clone(&turn_context),
+                        sub.id,
+                        items,
+                        CompactTask { strategy },
+                    )
+                    .await;
                 }
             }
+            Op::PinItem { item } => {
+                sess.pin_item(&sub.id, &turn_context.cwd, item).await;
+            }
+            Op::UnpinItem { item } => {
+                sess.unpin_item(&sub.id, &item).await;
+            }
             Op::Shutdown => {
                 sess.abort_all_tasks(TurnAbortReason::Interrupted).await;
                 info!("Shutting down Codex instance");
@@ -1648,6 +1973,9 @@ pub(crate) async fn run_task(
     if input.is_empty() {
         return None;
     }
+    // Every task starts on the primary model; a failover only lasts for the
+    // task that triggered it.
+    turn_context.client.reset_fallback();
     let event = Event {
         id: sub_id.clone(),
         msg: EventMsg::TaskStarted(TaskStartedEvent {
@@ -1953,27 +2281,35 @@ async fn run_turn(
     sub_id: String,
     input: Vec<ResponseItem>,
 ) -> CodexResult<TurnRunResult> {
-    let mcp_tools = sess.services.mcp_connection_manager.list_all_tools();
-    let router = Arc::new(ToolRouter::from_config(
-        &turn_context.tools_config,
-        Some(mcp_tools),
-    ));
-
-    let model_supports_parallel = turn_context
-        .client
-        .get_model_family()
-        .supports_parallel_tool_calls;
-    let parallel_tool_calls = model_supports_parallel;
-    let prompt = Prompt {
-        input,
-        tools: router.specs(),
-        parallel_tool_calls,
-        base_instructions_override: turn_context.base_instructions.clone(),
-        output_schema: turn_context.final_output_json_schema.clone(),
+    let (mcp_tools, has_resource_servers) = {
+        let mcp_connection_manager = sess.mcp_connection_manager().await;
+        (
+            mcp_connection_manager.list_all_tools(),
+            mcp_connection_manager.has_resource_servers(),
+        )
     };
 
     let mut retries = 0;
     loop {
+        // Built for the model serving requests, which a failover below changes.
+        let model_family = turn_context.client.active_model_family();
+        let mut tools_config = turn_context.tools_config.for_model_family(&model_family);
+        tools_config.mcp_resource_tools = has_resource_servers;
+        let router = Arc::new(ToolRouter::from_config(&tools_config, Some(mcp_tools.clone())));
+        // Without parallel tool calls the model sends one call per response, so
+        // read-only calls could never overlap. Mutating calls still run one at a
+        // time (see `ToolCallRuntime`).
+        let parallel_tool_calls =
+            model_family.supports_parallel_tool_calls || router.has_parallel_tools();
+        let prompt = Prompt {
+            input: input.clone(),
+            tools: router.specs(),
+            parallel_tool_calls,
+            base_instructions_override: turn_context.base_instructions.clone(),
+            output_schema: turn_context.final_output_json_schema.clone(),
+            max_output_tokens: None,
+        };
+
         match try_run_turn(
             Arc::clone(&router),
             Arc::clone(&sess),
@@ -1988,6 +2324,7 @@ async fn run_turn(
             Err(CodexErr::Interrupted) => return Err(CodexErr::Interrupted),
             Err(CodexErr::EnvVar(var)) => return Err(CodexErr::EnvVar(var)),
             Err(e @ CodexErr::Fatal(_)) => return Err(e),
+            Err(e @ CodexErr::CostBudgetReached) => return Err(e),
             Err(e @ CodexErr::ContextWindowExceeded) => {
                 sess.set_total_tokens_full(&sub_id, &turn_context).await;
                 return Err(e);
@@ -1997,12 +2334,16 @@ async fn run_turn(
                 if let Some(rate_limits) = rate_limits {
                     sess.update_rate_limits(&sub_id, rate_limits).await;
                 }
+                if try_fail_over(&sess, &turn_context, &sub_id, "usage limit reached").await {
+                    retries = 0;
+                    continue;
+                }
                 return Err(CodexErr::UsageLimitReached(e));
             }
             Err(CodexErr::UsageNotIncluded) => return Err(CodexErr::UsageNotIncluded),
             Err(e) => {
                 // Use the configured provider-specific stream retry budget.
-                let max_retries = turn_context.client.get_provider().stream_max_retries();
+                let max_retries = turn_context.client.active_provider().stream_max_retries();
                 if retries < max_retries {
                     retries += 1;
                     let delay = match e {
@@ -2025,6 +2366,8 @@ async fn run_turn(
                     .await;
 
                     tokio::time::sleep(delay).await;
+                } else if try_fail_over(&sess, &turn_context, &sub_id, &e.to_string()).await {
+                    retries = 0;
                 } else {
                     return Err(e);
                 }
@@ -2033,6 +2376,35 @@ async fn run_turn(
     }
 }
 
+/// Moves the turn onto the next `model_fallbacks` entry, if any, and tells the
+/// user why. Returns `false` when there is nothing left to fall back to.
+async fn try_fail_over(
+    sess: &Session,
+    turn_context: &TurnContext,
+    sub_id: &str,
+    reason: &str,
+) -> bool {
+    let from = turn_context.client.active_label();
+    if !turn_context.client.fail_over() {
+        return false;
+    }
+    let to = turn_context.client.active_label();
+    warn!("switching from {from} to {to}: {reason}");
+    sess.notify_background_event(
+        sub_id,
+        format!("Switching from {from} to {to} for the rest of this turn: {reason}"),
+    )
+    .await;
+    true
+}
+
+fn rate_limit_exhausted(snapshot: &RateLimitSnapshot) -> bool {
+    [&snapshot.primary, &snapshot.secondary]
+        .into_iter()
+        .flatten()
+        .any(|window| window.used_percent >= 100.0)
+}
+
 /// When the model is prompted, it returns a stream of events. Some of these
 /// events map to a `ResponseItem`. A `ResponseItem` may need to be
 /// "handled" such that it produces a `ResponseInputItem` that needs to be
@@ -2116,11 +2488,12 @@ async fn try_run_turn(
         cwd: turn_context.cwd.clone(),
         approval_policy: turn_context.approval_policy,
         sandbox_policy: turn_context.sandbox_policy.clone(),
-        model: turn_context.client.get_model(),
+        model: turn_context.client.active_model(),
         effort: turn_context.client.get_reasoning_effort(),
         summary: turn_context.client.get_reasoning_summary(),
     });
     sess.persist_rollout_items(&[rollout_item]).await;
+    cost_budget::ensure_within_budget(&sess, sub_id).await?;
     let mut stream = turn_context.client.clone().stream(&prompt).await?;
 
     let tool_runtime = ToolCallRuntime::new(
@@ -2155,7 +2528,7 @@ async fn try_run_turn(
         match event {
             ResponseEvent::Created => {}
             ResponseEvent::OutputItemDone(item) => {
-                match ToolRouter::build_tool_call(sess.as_ref(), item.clone()) {
+                match ToolRouter::build_tool_call(sess.as_ref(), item.clone()).await {
                     Ok(Some(call)) => {
                         let payload_preview = call.payload.log_payload().into_owned();
                         tracing::info!("ToolCall: {} {}", call.tool_name, payload_preview);
@@ -2230,6 +2603,10 @@ async fn try_run_turn(
                     .await;
             }
             ResponseEvent::RateLimits(snapshot) => {
+                if rate_limit_exhausted(&snapshot) {
+                    try_fail_over(&sess, &turn_context, sub_id, "rate limit window exhausted")
+                        .await;
+                }
                 // Update internal state with latest rate limits, but defer sending until
                 // token usage is available to avoid duplicate TokenCount events.
                 sess.update_rate_limits(sub_id, snapshot).await;
@@ -2463,6 +2840,8 @@ mod tests {
     use crate::config::ConfigOverrides;
     use crate::config::ConfigToml;
 
+    use super::compact::build_compacted_history;
+    use super::compact::collect_user_messages;
     use crate::protocol::CompactedItem;
     use crate::protocol::InitialHistory;
     use crate::protocol::ResumedHistory;
@@ -2519,6 +2898,64 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn reconstruct_history_replays_local_compaction_strategies() {
+        let (session, turn_context) = make_session_and_context();
+        let message = |role: &str, text: &str| ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        };
+        let rollout_items = vec![
+            RolloutItem::ResponseItem(message("user", "first")),
+            RolloutItem::ResponseItem(message("assistant", "one")),
+            RolloutItem::ResponseItem(message("user", "second")),
+            RolloutItem::Compacted(CompactedItem {
+                message: String::new(),
+                strategy: CompactionStrategy::KeepLastTurns(1),
+                pinned: Vec::new(),
+            }),
+            RolloutItem::ResponseItem(message("assistant", "two")),
+        ];
+
+        let reconstructed = session.reconstruct_history_from_rollout(&turn_context, &rollout_items);
+
+        let mut expected = session.build_initial_context(&turn_context);
+        expected.push(message("user", "second"));
+        expected.push(message("assistant", "two"));
+        assert_eq!(expected, reconstructed);
+    }
+
+    #[test]
+    fn resumed_session_keeps_its_estimated_cost() {
+        let (session, turn_context) = make_session_and_context();
+        let token_count = |cost_usd| {
+            RolloutItem::EventMsg(EventMsg::TokenCount(TokenCountEvent {
+                info: None,
+                rate_limits: None,
+                cost_usd,
+            }))
+        };
+
+        tokio_test::block_on(session.record_initial_history(
+            &turn_context,
+            InitialHistory::Resumed(ResumedHistory {
+                conversation_id: ConversationId::default(),
+                history: vec![
+                    token_count(Some(0.5)),
+                    token_count(Some(1.25)),
+                    token_count(None),
+                ],
+                rollout_path: PathBuf::from("/tmp/resume.jsonl"),
+            }),
+        ));
+
+        let cost_usd = tokio_test::block_on(async { session.state.lock().await.cost_usd });
+        assert_eq!(cost_usd, Some(1.25));
+    }
+
     #[test]
     fn record_initial_history_reconstructs_forked_transcript() {
         let (session, turn_context) = make_session_and_context();
@@ -2570,6 +3007,8 @@ mod tests {
             aggregated_output: StreamOutput::new(full),
             duration: StdDuration::from_secs(1),
             timed_out: false,
+            resource_limit_exceeded: None,
+            sandbox_denials: Vec::new(),
         };
 
         let out = format_exec_output_str(&exec);
@@ -2619,6 +3058,8 @@ mod tests {
             aggregated_output: StreamOutput::new(full.clone()),
             duration: StdDuration::from_secs(1),
             timed_out: false,
+            resource_limit_exceeded: None,
+            sandbox_denials: Vec::new(),
         };
 
         let out = format_exec_output_str(&exec);
@@ -2655,6 +3096,8 @@ mod tests {
             aggregated_output: StreamOutput::new("Command output".to_string()),
             duration: StdDuration::from_secs(1),
             timed_out: true,
+            resource_limit_exceeded: None,
+            sandbox_denials: Vec::new(),
         };
 
         let out = format_exec_output_str(&exec);
@@ -2780,10 +3223,12 @@ mod tests {
             final_output_json_schema: None,
         };
         let services = SessionServices {
-            mcp_connection_manager: McpConnectionManager::default(),
+            mcp_connection_manager: RwLock::new(Arc::new(McpConnectionManager::default())),
+            mcp_reload: Mutex::new(()),
             session_manager: ExecSessionManager::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::default(),
+            hooks: HookRunner::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -2853,10 +3298,12 @@ mod tests {
             final_output_json_schema: None,
         });
         let services = SessionServices {
-            mcp_connection_manager: McpConnectionManager::default(),
+            mcp_connection_manager: RwLock::new(Arc::new(McpConnectionManager::default())),
+            mcp_reload: Mutex::new(()),
             session_manager: ExecSessionManager::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::default(),
+            hooks: HookRunner::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -2983,7 +3430,7 @@ mod tests {
         let (session, turn_context, _rx) = make_session_and_context_with_rx();
         let router = ToolRouter::from_config(
             &turn_context.tools_config,
-            Some(session.services.mcp_connection_manager.list_all_tools()),
+            Some(session.mcp_connection_manager().await.list_all_tools()),
         );
         let item = ResponseItem::CustomToolCall {
             id: None,
@@ -2994,6 +3441,7 @@ mod tests {
         };
 
         let call = ToolRouter::build_tool_call(session.as_ref(), item.clone())
+            .await
             .expect("build tool call")
             .expect("tool call present");
         let tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
@@ -3060,6 +3508,8 @@ mod tests {
         live_history.replace(rebuilt1);
         rollout_items.push(RolloutItem::Compacted(CompactedItem {
             message: summary1.to_string(),
+            strategy: CompactionStrategy::Summarize,
+            pinned: Vec::new(),
         }));
 
         let user2 = ResponseItem::Message {
@@ -3093,6 +3543,8 @@ mod tests {
         live_history.replace(rebuilt2);
         rollout_items.push(RolloutItem::Compacted(CompactedItem {
             message: summary2.to_string(),
+            strategy: CompactionStrategy::Summarize,
+            pinned: Vec::new(),
         }));
 
         let user3 = ResponseItem::Message {
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use super::Session;
 use super::TurnContext;
+use super::cost_budget;
 use super::get_last_assistant_message_from_turn;
 use crate::Prompt;
 use crate::client_common::ResponseEvent;
@@ -14,12 +15,17 @@ use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::InputItem;
 use crate::protocol::InputMessageKind;
+use crate::protocol::PINNED_CONTEXT_CLOSE_TAG;
+use crate::protocol::PINNED_CONTEXT_OPEN_TAG;
+use crate::protocol::PinnedItem;
 use crate::protocol::TaskStartedEvent;
 use crate::protocol::TurnContextItem;
 use crate::truncate::truncate_middle;
 use crate::util::backoff;
 use askama::Template;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::RolloutItem;
@@ -27,6 +33,13 @@ use futures::prelude::*;
 
 pub const SUMMARIZATION_PROMPT: &str = include_str!("../../templates/compact/prompt.md");
 const COMPACT_USER_MESSAGE_MAX_TOKENS: usize = 20_000;
+/// Tool outputs up to this size are kept by [`CompactionStrategy::DropToolOutputs`];
+/// a stub would not save anything.
+const TOOL_OUTPUT_STUB_MIN_BYTES: usize = 512;
+/// The latest tool outputs, which the model is most likely still working
+/// from, are never stubbed.
+const RECENT_TOOL_OUTPUTS_KEPT: usize = 4;
+const PINNED_ITEM_MAX_TOKENS: usize = 10_000;
 
 #[derive(Template)]
 #[template(path = "compact/history_bridge.md", escape = "none")]
@@ -43,7 +56,8 @@ pub(crate) async fn run_inline_auto_compact_task(
     let input = vec![InputItem::Text {
         text: SUMMARIZATION_PROMPT.to_string(),
     }];
-    run_compact_task_inner(sess, turn_context, sub_id, input).await;
+    let strategy = turn_context.client.get_compaction_strategy();
+    run_compact_task_inner(sess, turn_context, sub_id, input, strategy).await;
 }
 
 pub(crate) async fn run_compact_task(
@@ -51,6 +65,7 @@ pub(crate) async fn run_compact_task(
     turn_context: Arc<TurnContext>,
     sub_id: String,
     input: Vec<InputItem>,
+    strategy: CompactionStrategy,
 ) -> Option<String> {
     let start_event = Event {
         id: sub_id.clone(),
@@ -59,7 +74,7 @@ pub(crate) async fn run_compact_task(
         }),
     };
     sess.send_event(start_event).await;
-    run_compact_task_inner(sess.clone(), turn_context, sub_id.clone(), input).await;
+    run_compact_task_inner(sess.clone(), turn_context, sub_id.clone(), input, strategy).await;
     None
 }
 
@@ -68,7 +83,64 @@ async fn run_compact_task_inner(
     turn_context: Arc<TurnContext>,
     sub_id: String,
     input: Vec<InputItem>,
+    strategy: CompactionStrategy,
 ) {
+    if strategy == CompactionStrategy::Summarize
+        && !request_summary(&sess, &turn_context, &sub_id, input).await
+    {
+        return;
+    }
+
+    let history_snapshot = sess.history_snapshot().await;
+    let summary_text = match strategy {
+        CompactionStrategy::Summarize => {
+            get_last_assistant_message_from_turn(&history_snapshot).unwrap_or_default()
+        }
+        CompactionStrategy::DropToolOutputs | CompactionStrategy::KeepLastTurns(_) => String::new(),
+    };
+    let pinned: Vec<String> = sess
+        .state
+        .lock()
+        .await
+        .pinned_items
+        .iter()
+        .map(render_pinned_item)
+        .collect();
+    let initial_context = sess.build_initial_context(turn_context.as_ref());
+    let new_history = compact_history(
+        strategy,
+        initial_context,
+        &history_snapshot,
+        &summary_text,
+        &pinned,
+    );
+    sess.replace_history(new_history).await;
+
+    let rollout_item = RolloutItem::Compacted(CompactedItem {
+        message: summary_text,
+        strategy,
+        pinned,
+    });
+    sess.persist_rollout_items(&[rollout_item]).await;
+
+    let event = Event {
+        id: sub_id.clone(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Compact task completed".to_string(),
+        }),
+    };
+    sess.send_event(event).await;
+}
+
+/// Asks the model to summarize the conversation, leaving the summary as the
+/// last assistant message in the history. Returns `false` when no summary was
+/// produced; the error has been reported to the client.
+async fn request_summary(
+    sess: &Session,
+    turn_context: &TurnContext,
+    sub_id: &str,
+    input: Vec<InputItem>,
+) -> bool {
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
     let mut turn_input = sess
         .turn_input_with_history(vec![initial_input_for_turn.clone().into()])
@@ -93,14 +165,13 @@ async fn run_compact_task_inner(
             input: turn_input.clone(),
             ..Default::default()
         };
-        let attempt_result =
-            drain_to_completed(&sess, turn_context.as_ref(), &sub_id, &prompt).await;
+        let attempt_result = drain_to_completed(sess, turn_context, sub_id, &prompt).await;
 
         match attempt_result {
             Ok(()) => {
                 if truncated_count > 0 {
                     sess.notify_background_event(
-                        &sub_id,
+                        sub_id,
                         format!(
                             "Trimmed {truncated_count} older conversation item(s) before compacting so the prompt fits the model context window."
                         ),
@@ -110,7 +181,17 @@ async fn run_compact_task_inner(
                 break;
             }
             Err(CodexErr::Interrupted) => {
-                return;
+                return false;
+            }
+            Err(e @ CodexErr::CostBudgetReached) => {
+                let event = Event {
+                    id: sub_id.to_string(),
+                    msg: EventMsg::Error(ErrorEvent {
+                        message: e.to_string(),
+                    }),
+                };
+                sess.send_event(event).await;
+                return false;
             }
             Err(e @ CodexErr::ContextWindowExceeded) => {
                 if turn_input.len() > 1 {
@@ -119,23 +200,22 @@ async fn run_compact_task_inner(
                     retries = 0;
                     continue;
                 }
-                sess.set_total_tokens_full(&sub_id, turn_context.as_ref())
-                    .await;
+                sess.set_total_tokens_full(sub_id, turn_context).await;
                 let event = Event {
-                    id: sub_id.clone(),
+                    id: sub_id.to_string(),
                     msg: EventMsg::Error(ErrorEvent {
                         message: e.to_string(),
                     }),
                 };
                 sess.send_event(event).await;
-                return;
+                return false;
             }
             Err(e) => {
                 if retries < max_retries {
                     retries += 1;
                     let delay = backoff(retries);
                     sess.notify_stream_error(
-                        &sub_id,
+                        sub_id,
                         format!(
                             "stream error: {e}; retrying {retries}/{max_retries} in {delay:?}…"
                         ),
@@ -145,37 +225,19 @@ async fn run_compact_task_inner(
                     continue;
                 } else {
                     let event = Event {
-                        id: sub_id.clone(),
+                        id: sub_id.to_string(),
                         msg: EventMsg::Error(ErrorEvent {
                             message: e.to_string(),
                         }),
                     };
                     sess.send_event(event).await;
-                    return;
+                    return false;
                 }
             }
         }
     }
 
-    let history_snapshot = sess.history_snapshot().await;
-    let summary_text = get_last_assistant_message_from_turn(&history_snapshot).unwrap_or_default();
-    let user_messages = collect_user_messages(&history_snapshot);
-    let initial_context = sess.build_initial_context(turn_context.as_ref());
-    let new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
-    sess.replace_history(new_history).await;
-
-    let rollout_item = RolloutItem::Compacted(CompactedItem {
-        message: summary_text.clone(),
-    });
-    sess.persist_rollout_items(&[rollout_item]).await;
-
-    let event = Event {
-        id: sub_id.clone(),
-        msg: EventMsg::AgentMessage(AgentMessageEvent {
-            message: "Compact task completed".to_string(),
-        }),
-    };
-    sess.send_event(event).await;
+    true
 }
 
 pub fn content_items_to_text(content: &[ContentItem]) -> Option<String> {
@@ -213,10 +275,187 @@ pub(crate) fn collect_user_messages(items: &[ResponseItem]) -> Vec<String> {
 pub fn is_session_prefix_message(text: &str) -> bool {
     matches!(
         InputMessageKind::from(("user", text)),
-        InputMessageKind::UserInstructions | InputMessageKind::EnvironmentContext
+        InputMessageKind::UserInstructions
+            | InputMessageKind::EnvironmentContext
+            | InputMessageKind::PinnedContext
     )
 }
 
+/// Renders a pinned item the way it is inserted into the compacted history.
+/// Files are read here so the model sees their current contents.
+pub(crate) fn render_pinned_item(item: &PinnedItem) -> String {
+    let mut body = match item {
+        PinnedItem::Message { text } => text.clone(),
+        PinnedItem::File { path } => {
+            let contents = std::fs::read_to_string(path)
+                .unwrap_or_else(|err| format!("(could not read the file: {err})"));
+            format!("<path>{}</path>\n{contents}", path.display())
+        }
+    };
+    let max_bytes = PINNED_ITEM_MAX_TOKENS * 4;
+    if body.len() > max_bytes {
+        body = truncate_middle(&body, max_bytes).0;
+    }
+    format!("{PINNED_CONTEXT_OPEN_TAG}\n{body}\n{PINNED_CONTEXT_CLOSE_TAG}")
+}
+
+/// Builds the history that replaces `history` when compacting with
+/// `strategy`. Only [`CompactionStrategy::Summarize`] uses `summary_text`.
+/// `pinned` holds rendered pinned items, which replace any left by an earlier
+/// compaction. Resuming a session replays this to rebuild the compacted
+/// history.
+pub(crate) fn compact_history(
+    strategy: CompactionStrategy,
+    initial_context: Vec<ResponseItem>,
+    history: &[ResponseItem],
+    summary_text: &str,
+    pinned: &[String],
+) -> Vec<ResponseItem> {
+    let compacted = match strategy {
+        CompactionStrategy::Summarize => {
+            let user_messages = collect_user_messages(history);
+            build_compacted_history(initial_context, &user_messages, summary_text)
+        }
+        CompactionStrategy::DropToolOutputs => drop_tool_outputs(history),
+        CompactionStrategy::KeepLastTurns(turns) => {
+            keep_last_turns(initial_context, history, turns)
+        }
+    };
+    insert_pinned(compacted, pinned)
+}
+
+/// Places `pinned` right after the session prefix (user instructions and
+/// environment context), dropping previously pinned copies.
+fn insert_pinned(history: Vec<ResponseItem>, pinned: &[String]) -> Vec<ResponseItem> {
+    let mut history: Vec<ResponseItem> = history
+        .into_iter()
+        .filter(|item| {
+            !matches!(
+                user_message_kind(item),
+                Some(InputMessageKind::PinnedContext)
+            )
+        })
+        .collect();
+    let prefix_len = history
+        .iter()
+        .take_while(|item| {
+            matches!(
+                user_message_kind(item),
+                Some(InputMessageKind::UserInstructions | InputMessageKind::EnvironmentContext)
+            )
+        })
+        .count();
+    history.splice(
+        prefix_len..prefix_len,
+        pinned.iter().map(|text| ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text: text.clone() }],
+        }),
+    );
+    history
+}
+
+fn user_message_kind(item: &ResponseItem) -> Option<InputMessageKind> {
+    match item {
+        ResponseItem::Message { role, content, .. } if role == "user" => {
+            content_items_to_text(content).map(|text| InputMessageKind::from(("user", text)))
+        }
+        _ => None,
+    }
+}
+
+/// Replaces large tool outputs, except the [`RECENT_TOOL_OUTPUTS_KEPT`] most
+/// recent ones, with a stub. The calls stay so the model still knows what it
+/// did. This also shrinks a single long turn.
+fn drop_tool_outputs(history: &[ResponseItem]) -> Vec<ResponseItem> {
+    let tool_outputs: Vec<usize> = history
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            matches!(
+                item,
+                ResponseItem::FunctionCallOutput { .. } | ResponseItem::CustomToolCallOutput { .. }
+            )
+        })
+        .map(|(idx, _)| idx)
+        .collect();
+    let keep_from = tool_outputs
+        .len()
+        .checked_sub(RECENT_TOOL_OUTPUTS_KEPT)
+        .and_then(|n| tool_outputs.get(n))
+        .copied()
+        .unwrap_or(0);
+    let stub = |output: &str| {
+        format!(
+            "[output of {} bytes removed during compaction]",
+            output.len()
+        )
+    };
+    history
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| match item {
+            ResponseItem::FunctionCallOutput { call_id, output }
+                if idx < keep_from && output.content.len() > TOOL_OUTPUT_STUB_MIN_BYTES =>
+            {
+                ResponseItem::FunctionCallOutput {
+                    call_id: call_id.clone(),
+                    output: FunctionCallOutputPayload {
+                        content: stub(&output.content),
+                        success: output.success,
+                    },
+                }
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output }
+                if idx < keep_from && output.len() > TOOL_OUTPUT_STUB_MIN_BYTES =>
+            {
+                ResponseItem::CustomToolCallOutput {
+                    call_id: call_id.clone(),
+                    output: stub(output),
+                }
+            }
+            other => other.clone(),
+        })
+        .collect()
+}
+
+/// Keeps the last `turns` user turns, at least the current one, after a fresh
+/// copy of the initial context. Large tool outputs in the kept turns are
+/// stubbed as in [`drop_tool_outputs`], so a single long turn still shrinks.
+fn keep_last_turns(
+    initial_context: Vec<ResponseItem>,
+    history: &[ResponseItem],
+    turns: usize,
+) -> Vec<ResponseItem> {
+    let starts = user_turn_starts(history);
+    let keep_from = starts
+        .get(starts.len().saturating_sub(turns.max(1)))
+        .copied()
+        .unwrap_or(history.len());
+    let mut compacted = initial_context;
+    compacted.extend(drop_tool_outputs(&history[keep_from..]));
+    compacted
+}
+
+/// Indices of the user messages that start a turn.
+fn user_turn_starts(history: &[ResponseItem]) -> Vec<usize> {
+    history
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, item)| match item {
+            ResponseItem::Message { role, content, .. }
+                if role == "user"
+                    && content_items_to_text(content)
+                        .is_none_or(|text| !is_session_prefix_message(&text)) =>
+            {
+                Some(idx)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 pub(crate) fn build_compacted_history(
     initial_context: Vec<ResponseItem>,
     user_messages: &[String],
@@ -260,6 +499,7 @@ async fn drain_to_completed(
     sub_id: &str,
     prompt: &Prompt,
 ) -> CodexResult<()> {
+    cost_budget::ensure_within_budget(sess, sub_id).await?;
     let mut stream = turn_context.client.clone().stream(prompt).await?;
     loop {
         let maybe_event = stream.next().await;
@@ -416,4 +656,164 @@ mod tests {
             "bridge should include the provided summary text"
         );
     }
+
+    fn user_message(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn tool_output(call_id: &str, content: &str) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                success: Some(true),
+            },
+        }
+    }
+
+    #[test]
+    fn drop_tool_outputs_stubs_large_outputs_except_the_latest() {
+        let large = "x".repeat(TOOL_OUTPUT_STUB_MIN_BYTES + 1);
+        let mut history = vec![
+            user_message("<environment_context>cwd=/tmp</environment_context>"),
+            user_message("first"),
+            tool_output("old-large", &large),
+            tool_output("old-small", "ok"),
+            user_message("second"),
+        ];
+        history.extend((0..RECENT_TOOL_OUTPUTS_KEPT).map(|_| tool_output("recent", &large)));
+
+        let compacted = compact_history(
+            CompactionStrategy::DropToolOutputs,
+            Vec::new(),
+            &history,
+            "",
+            &[],
+        );
+
+        let mut expected = history.clone();
+        expected[2] = tool_output(
+            "old-large",
+            "[output of 513 bytes removed during compaction]",
+        );
+        assert_eq!(expected, compacted);
+    }
+
+    #[test]
+    fn drop_tool_outputs_shrinks_a_single_long_turn() {
+        let large = "x".repeat(TOOL_OUTPUT_STUB_MIN_BYTES + 1);
+        let mut history = vec![user_message("only turn")];
+        history.extend((0..=RECENT_TOOL_OUTPUTS_KEPT).map(|_| tool_output("call", &large)));
+
+        for strategy in [
+            CompactionStrategy::DropToolOutputs,
+            CompactionStrategy::KeepLastTurns(1),
+        ] {
+            let compacted = compact_history(strategy, Vec::new(), &history, "", &[]);
+
+            let mut expected = history.clone();
+            expected[1] = tool_output("call", "[output of 513 bytes removed during compaction]");
+            assert_eq!(expected, compacted);
+        }
+    }
+
+    #[test]
+    fn keep_last_turns_keeps_whole_turns_after_the_initial_context() {
+        let context = user_message("<environment_context>cwd=/new</environment_context>");
+        let history = vec![
+            user_message("<environment_context>cwd=/old</environment_context>"),
+            user_message("first"),
+            tool_output("call-1", "one"),
+            user_message("second"),
+            tool_output("call-2", "two"),
+            user_message("third"),
+        ];
+
+        let keep = |turns| {
+            compact_history(
+                CompactionStrategy::KeepLastTurns(turns),
+                vec![context.clone()],
+                &history,
+                "",
+                &[],
+            )
+        };
+
+        assert_eq!(
+            vec![
+                context.clone(),
+                history[3].clone(),
+                history[4].clone(),
+                history[5].clone()
+            ],
+            keep(2)
+        );
+        // The current turn is always kept.
+        assert_eq!(vec![context.clone(), history[5].clone()], keep(0));
+        let mut everything = vec![context.clone()];
+        everything.extend_from_slice(&history[1..]);
+        assert_eq!(everything, keep(10));
+    }
+
+    #[test]
+    fn pinned_items_replace_earlier_pins_after_the_session_prefix() {
+        let instructions = user_message("<user_instructions>be brief</user_instructions>");
+        let context = user_message("<environment_context>cwd=/tmp</environment_context>");
+        let pinned = |text: &str| {
+            render_pinned_item(&PinnedItem::Message {
+                text: text.to_string(),
+            })
+        };
+        let history = vec![
+            instructions.clone(),
+            context.clone(),
+            user_message(&pinned("old spec")),
+            user_message("first"),
+            tool_output("call-1", "one"),
+        ];
+
+        let compacted = compact_history(
+            CompactionStrategy::DropToolOutputs,
+            Vec::new(),
+            &history,
+            "",
+            &[pinned("spec"), pinned("error log")],
+        );
+
+        assert_eq!(
+            vec![
+                instructions,
+                context,
+                user_message("<pinned_context>\nspec\n</pinned_context>"),
+                user_message("<pinned_context>\nerror log\n</pinned_context>"),
+                history[3].clone(),
+                history[4].clone(),
+            ],
+            compacted
+        );
+        assert_eq!(vec!["first".to_string()], collect_user_messages(&compacted));
+    }
+
+    #[test]
+    fn pinned_files_are_read_when_rendered() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let path = dir.path().join("spec.md");
+        std::fs::write(&path, "v1").expect("write spec");
+        let item = PinnedItem::File { path: path.clone() };
+        std::fs::write(&path, "v2").expect("write spec");
+
+        assert_eq!(
+            format!(
+                "<pinned_context>\n<path>{}</path>\nv2\n</pinned_context>",
+                path.display()
+            ),
+            render_pinned_item(&item)
+        );
+    }
 }
@@ -0,0 +1,65 @@
+//! Pausing a task once the session has spent its `session_budget_usd`.
+
+use tokio::sync::oneshot;
+use tracing::warn;
+
+use super::Session;
+use crate::error::CodexErr;
+use crate::error::Result as CodexResult;
+use crate::protocol::CostBudgetApprovalRequestEvent;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::ReviewDecision;
+
+/// Called before every sampling request. Fails with
+/// [`CodexErr::CostBudgetReached`] when the session is over its budget and the
+/// user chose not to continue.
+pub(super) async fn ensure_within_budget(sess: &Session, sub_id: &str) -> CodexResult<()> {
+    if confirm_within_budget(sess, sub_id).await {
+        Ok(())
+    } else {
+        Err(CodexErr::CostBudgetReached)
+    }
+}
+
+/// Asks the user whether to keep going when the session cost has reached its
+/// limit. Returns `false` when the task should stop.
+async fn confirm_within_budget(sess: &Session, sub_id: &str) -> bool {
+    let Some((cost_usd, budget_usd)) = sess.state.lock().await.cost_over_limit() else {
+        return true;
+    };
+
+    let (tx_approve, rx_approve) = oneshot::channel();
+    {
+        let mut active = sess.active_turn.lock().await;
+        let Some(at) = active.as_mut() else {
+            warn!("stopping at cost budget: no turn is running");
+            return false;
+        };
+        let mut ts = at.turn_state.lock().await;
+        ts.insert_pending_approval(sub_id.to_string(), tx_approve);
+    }
+
+    let event = Event {
+        id: sub_id.to_string(),
+        msg: EventMsg::CostBudgetApprovalRequest(CostBudgetApprovalRequestEvent {
+            cost_usd,
+            budget_usd,
+        }),
+    };
+    sess.send_event(event).await;
+
+    let decision = rx_approve.await.unwrap_or_default();
+    let mut state = sess.state.lock().await;
+    match decision {
+        ReviewDecision::Approved => {
+            state.extend_cost_limit();
+            true
+        }
+        ReviewDecision::ApprovedForSession | ReviewDecision::ApprovedForProject => {
+            state.cost_limit_usd = None;
+            true
+        }
+        ReviewDecision::Denied | ReviewDecision::Abort => false,
+    }
+}
@@ -0,0 +1,319 @@
+//! Answers `sampling/createMessage` requests from MCP servers by sampling the
+//! session's model. Unless the server is configured with
+//! `auto_approve_sampling`, the user is asked first.
+
+use std::sync::Arc;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use futures::prelude::*;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::CreateMessageResultContent;
+use mcp_types::Role;
+use mcp_types::SamplingMessage;
+use mcp_types::SamplingMessageContent;
+use mcp_types::TextContent;
+use tokio::sync::oneshot;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::Session;
+use super::TurnContext;
+use super::cost_budget;
+use crate::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::error::CodexErr;
+use crate::error::Result as CodexResult;
+use crate::mcp_connection_manager::McpSamplingRequest;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::McpSamplingApprovalRequestEvent;
+use crate::protocol::ReviewDecision;
+
+/// Stop reason reported to the server once the model finished its response.
+const STOP_REASON_END_TURN: &str = "endTurn";
+
+pub(super) async fn handle_sampling_request(
+    sess: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    request: McpSamplingRequest,
+) {
+    let McpSamplingRequest {
+        server,
+        auto_approve,
+        params,
+        respond_to,
+    } = request;
+    let sub_id = format!("mcp-sampling-{}", Uuid::new_v4());
+
+    let approved = auto_approve || request_approval(&sess, &sub_id, &server, &params).await;
+    let result = if approved {
+        sample(&sess, &turn_context, &sub_id, params)
+            .await
+            .map_err(|e| format!("sampling failed: {e}"))
+    } else {
+        Err("the user declined the sampling request".to_string())
+    };
+    if respond_to.send(result).is_err() {
+        warn!("MCP server `{server}` stopped waiting for its sampling response");
+    }
+}
+
+/// Emits an `McpSamplingApprovalRequest` and waits for the user's decision.
+/// Requests that arrive while no turn is running are declined, since there is
+/// nobody to ask.
+async fn request_approval(
+    sess: &Session,
+    sub_id: &str,
+    server: &str,
+    params: &CreateMessageRequestParams,
+) -> bool {
+    if sess
+        .state
+        .lock()
+        .await
+        .sampling_approved_servers
+        .contains(server)
+    {
+        return true;
+    }
+
+    let (tx_approve, rx_approve) = oneshot::channel();
+    {
+        let mut active = sess.active_turn.lock().await;
+        let Some(at) = active.as_mut() else {
+            warn!("declining sampling request from `{server}`: no turn is running");
+            return false;
+        };
+        let mut ts = at.turn_state.lock().await;
+        ts.insert_pending_approval(sub_id.to_string(), tx_approve);
+    }
+
+    let event = Event {
+        id: sub_id.to_string(),
+        msg: EventMsg::McpSamplingApprovalRequest(McpSamplingApprovalRequestEvent {
+            server: server.to_string(),
+            system_prompt: params.system_prompt.clone(),
+            messages: params.messages.clone(),
+            max_tokens: params.max_tokens,
+        }),
+    };
+    sess.send_event(event).await;
+
+    match rx_approve.await.unwrap_or_default() {
+        ReviewDecision::Approved => true,
+        ReviewDecision::ApprovedForSession | ReviewDecision::ApprovedForProject => {
+            let mut state = sess.state.lock().await;
+            state.sampling_approved_servers.insert(server.to_string());
+            true
+        }
+        ReviewDecision::Denied | ReviewDecision::Abort => false,
+    }
+}
+
+/// Sends the server's messages to the session's model and returns the text
+/// of its reply. The usage is added to the session's token totals.
+async fn sample(
+    sess: &Session,
+    turn_context: &TurnContext,
+    sub_id: &str,
+    params: CreateMessageRequestParams,
+) -> CodexResult<CreateMessageResult> {
+    let prompt = sampling_prompt(params);
+    cost_budget::ensure_within_budget(sess, sub_id).await?;
+    let mut stream = turn_context.client.clone().stream(&prompt).await?;
+    let mut text = String::new();
+    loop {
+        let Some(event) = stream.next().await else {
+            return Err(CodexErr::Stream(
+                "stream closed before response.completed".into(),
+                None,
+            ));
+        };
+        match event? {
+            ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. })
+                if role == "assistant" =>
+            {
+                for item in content {
+                    if let ContentItem::OutputText { text: chunk } = item {
+                        text.push_str(&chunk);
+                    }
+                }
+            }
+            ResponseEvent::RateLimits(snapshot) => {
+                sess.update_rate_limits(sub_id, snapshot).await;
+            }
+            ResponseEvent::Completed { token_usage, .. } => {
+                if let Some(token_usage) = token_usage {
+                    let mut state = sess.state.lock().await;
+                    state.add_token_usage_outside_context(
+                        &token_usage,
+                        turn_context.client.get_model_context_window(),
+                    );
+                    if let Some(pricing) = turn_context.client.active_pricing() {
+                        state.add_cost(pricing.cost_usd(&token_usage));
+                    }
+                }
+                sess.send_token_count_event(sub_id).await;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(CreateMessageResult {
+        content: CreateMessageResultContent::TextContent(TextContent {
+            annotations: None,
+            text,
+            r#type: "text".to_string(),
+        }),
+        model: turn_context.client.get_model(),
+        role: Role::Assistant,
+        stop_reason: Some(STOP_REASON_END_TURN.to_string()),
+    })
+}
+
+/// The prompt for a sampling request. The server's `maxTokens` caps the reply.
+fn sampling_prompt(params: CreateMessageRequestParams) -> Prompt {
+    Prompt {
+        input: params
+            .messages
+            .into_iter()
+            .map(sampling_message_to_response_item)
+            .collect(),
+        base_instructions_override: params.system_prompt,
+        max_output_tokens: u64::try_from(params.max_tokens).ok().filter(|n| *n > 0),
+        ..Default::default()
+    }
+}
+
+fn sampling_message_to_response_item(message: SamplingMessage) -> ResponseItem {
+    let (role, content) = match (message.role, message.content) {
+        (Role::User, SamplingMessageContent::ImageContent(image)) => (
+            "user",
+            ContentItem::InputImage {
+                image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+            },
+        ),
+        (Role::User, content) => (
+            "user",
+            ContentItem::InputText {
+                text: sampling_content_text(content),
+            },
+        ),
+        (Role::Assistant, content) => (
+            "assistant",
+            ContentItem::OutputText {
+                text: sampling_content_text(content),
+            },
+        ),
+    };
+    ResponseItem::Message {
+        id: None,
+        role: role.to_string(),
+        content: vec![content],
+    }
+}
+
+fn sampling_content_text(content: SamplingMessageContent) -> String {
+    match content {
+        SamplingMessageContent::TextContent(text) => text.text,
+        SamplingMessageContent::ImageContent(image) => {
+            format!("[{} image omitted]", image.mime_type)
+        }
+        SamplingMessageContent::AudioContent(audio) => {
+            format!("[{} audio omitted]", audio.mime_type)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::ImageContent;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn converts_sampling_messages_by_role() {
+        let user_text = SamplingMessage {
+            content: SamplingMessageContent::TextContent(TextContent {
+                annotations: None,
+                text: "summarize this".to_string(),
+                r#type: "text".to_string(),
+            }),
+            role: Role::User,
+        };
+        let user_image = SamplingMessage {
+            content: SamplingMessageContent::ImageContent(ImageContent {
+                annotations: None,
+                data: "AAAA".to_string(),
+                mime_type: "image/png".to_string(),
+                r#type: "image".to_string(),
+            }),
+            role: Role::User,
+        };
+        let assistant_image = SamplingMessage {
+            content: SamplingMessageContent::ImageContent(ImageContent {
+                annotations: None,
+                data: "AAAA".to_string(),
+                mime_type: "image/png".to_string(),
+                r#type: "image".to_string(),
+            }),
+            role: Role::Assistant,
+        };
+
+        let items: Vec<ResponseItem> = [user_text, user_image, assistant_image]
+            .into_iter()
+            .map(sampling_message_to_response_item)
+            .collect();
+
+        assert_eq!(
+            items,
+            vec![
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: "summarize this".to_string(),
+                    }],
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputImage {
+                        image_url: "data:image/png;base64,AAAA".to_string(),
+                    }],
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText {
+                        text: "[image/png image omitted]".to_string(),
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn sampling_prompt_honors_max_tokens() {
+        let params = CreateMessageRequestParams {
+            include_context: None,
+            max_tokens: 256,
+            messages: Vec::new(),
+            metadata: None,
+            model_preferences: None,
+            stop_sequences: None,
+            system_prompt: Some("be brief".to_string()),
+            temperature: None,
+        };
+
+        let prompt = sampling_prompt(params);
+        assert_eq!(prompt.max_output_tokens, Some(256));
+        assert_eq!(
+            prompt.base_instructions_override.as_deref(),
+            Some("be brief")
+        );
+    }
+}
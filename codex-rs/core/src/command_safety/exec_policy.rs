@@ -0,0 +1,205 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::Policy;
+use codex_execpolicy::PolicyFileIssue;
+use codex_execpolicy::load_user_policy_files;
+use tracing::warn;
+
+use crate::bash::parse_bash_lc_plain_commands;
+use crate::git_info::resolve_root_git_project_for_trust;
+
+/// Directory under `CODEX_HOME` holding the user's `*.policy` files.
+pub const USER_POLICY_DIR: &str = "policy.d";
+
+/// Directory, relative to the project root, holding project `*.policy` files.
+pub const PROJECT_POLICY_DIR: &str = ".codex/policy";
+
+/// Result of loading the user and project execpolicy files for a session.
+#[derive(Debug, Default)]
+pub(crate) struct UserExecPolicy {
+    /// The user files merged together, or `None` when no user file was
+    /// loaded. The default policy is left out: programs it knows, such as
+    /// `printenv`, are not safe to run without approval because of it.
+    pub(crate) policy: Option<Arc<Policy>>,
+    /// Problems found in user files, to be reported at startup.
+    pub(crate) issues: Vec<PolicyFileIssue>,
+}
+
+/// Loads `$CODEX_HOME/policy.d/*.policy` followed by
+/// `<project root>/.codex/policy/*.policy`, so project files override the
+/// user's own files when both define the same program.
+///
+/// The project files are only loaded when `project_trusted` is set: any
+/// repository could otherwise declare its own commands safe to run without
+/// approval.
+pub(crate) fn load_user_exec_policy(
+    codex_home: &Path,
+    cwd: &Path,
+    project_trusted: bool,
+) -> UserExecPolicy {
+    let mut dirs: Vec<PathBuf> = vec![codex_home.join(USER_POLICY_DIR)];
+    if project_trusted {
+        let project_root = resolve_root_git_project_for_trust(cwd).unwrap_or_else(|| cwd.into());
+        dirs.push(project_root.join(PROJECT_POLICY_DIR));
+    }
+    match load_user_policy_files(&dirs) {
+        Ok(loaded) => UserExecPolicy {
+            policy: (!loaded.user_files.is_empty()).then(|| Arc::new(loaded.policy)),
+            issues: loaded.issues,
+        },
+        Err(err) => {
+            warn!("failed to load execpolicy: {err:#}");
+            UserExecPolicy::default()
+        }
+    }
+}
+
+/// Returns true when `policy` allows `command` without it writing any files.
+/// A `bash -lc` script qualifies when every plain command in it does.
+pub(crate) fn is_safe_per_exec_policy(policy: &Policy, command: &[String]) -> bool {
+    if is_read_only_match(policy, command) {
+        return true;
+    }
+    match parse_bash_lc_plain_commands(command) {
+        Some(commands) if !commands.is_empty() => {
+            commands.iter().all(|argv| is_read_only_match(policy, argv))
+        }
+        _ => false,
+    }
+}
+
+fn is_read_only_match(policy: &Policy, argv: &[String]) -> bool {
+    let Some((program, args)) = argv.split_first() else {
+        return false;
+    };
+    let exec_call = ExecCall {
+        program: program.clone(),
+        args: args.to_vec(),
+    };
+    matches!(
+        policy.check(&exec_call),
+        Ok(MatchedExec::Match { exec }) if !exec.might_write_files()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn vec_str(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    const KUBECTL_POLICY: &str = r#"
+define_program(
+    program="kubectl",
+    args=["get", ARG_OPAQUE_VALUE],
+    should_match=[["get", "pods"]],
+)
+"#;
+
+    const CP_POLICY: &str = r#"
+define_program(
+    program="cp",
+    args=[ARG_RFILE, ARG_WFILE],
+    should_match=[["a.txt", "b.txt"]],
+)
+"#;
+
+    #[test]
+    fn no_user_files_means_no_policy() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let loaded = load_user_exec_policy(codex_home.path(), cwd.path(), true);
+        assert!(loaded.policy.is_none());
+        assert_eq!(loaded.issues, Vec::new());
+    }
+
+    #[test]
+    fn project_policy_makes_read_only_commands_safe() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let dir = cwd.path().join(PROJECT_POLICY_DIR);
+        std::fs::create_dir_all(&dir).expect("mkdir");
+        std::fs::write(dir.join("kubectl.policy"), KUBECTL_POLICY).expect("write");
+        std::fs::write(dir.join("cp.policy"), CP_POLICY).expect("write");
+
+        let loaded = load_user_exec_policy(codex_home.path(), cwd.path(), true);
+        let policy = loaded.policy.expect("policy loaded");
+
+        assert!(is_safe_per_exec_policy(
+            &policy,
+            &vec_str(&["kubectl", "get", "pods"])
+        ));
+        assert!(is_safe_per_exec_policy(
+            &policy,
+            &vec_str(&["bash", "-lc", "kubectl get pods && pwd"])
+        ));
+        assert!(!is_safe_per_exec_policy(
+            &policy,
+            &vec_str(&["kubectl", "delete", "pods"])
+        ));
+        // Matches that may write files (here `cp`'s destination) are not safe.
+        assert!(!is_safe_per_exec_policy(
+            &policy,
+            &vec_str(&["cp", "a.txt", "b.txt"])
+        ));
+    }
+
+    #[test]
+    fn default_policy_programs_are_not_auto_approved() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let dir = codex_home.path().join(USER_POLICY_DIR);
+        std::fs::create_dir_all(&dir).expect("mkdir");
+        std::fs::write(dir.join("kubectl.policy"), KUBECTL_POLICY).expect("write");
+
+        let loaded = load_user_exec_policy(codex_home.path(), cwd.path(), false);
+        let policy = loaded.policy.expect("policy loaded");
+
+        assert!(!is_safe_per_exec_policy(
+            &policy,
+            &vec_str(&["printenv", "AWS_SECRET_ACCESS_KEY"])
+        ));
+        assert!(!is_safe_per_exec_policy(
+            &policy,
+            &vec_str(&["bash", "-lc", "kubectl get pods && printenv"])
+        ));
+    }
+
+    #[test]
+    fn project_policy_is_ignored_in_untrusted_projects() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let dir = cwd.path().join(PROJECT_POLICY_DIR);
+        std::fs::create_dir_all(&dir).expect("mkdir");
+        std::fs::write(dir.join("kubectl.policy"), KUBECTL_POLICY).expect("write");
+
+        let loaded = load_user_exec_policy(codex_home.path(), cwd.path(), false);
+        assert!(loaded.policy.is_none());
+    }
+
+    #[test]
+    fn self_test_failures_are_reported() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let dir = codex_home.path().join(USER_POLICY_DIR);
+        std::fs::create_dir_all(&dir).expect("mkdir");
+        std::fs::write(
+            dir.join("kubectl.policy"),
+            KUBECTL_POLICY.replace(r#"[["get", "pods"]]"#, r#"[["apply", "-f", "x"]]"#),
+        )
+        .expect("write");
+
+        let loaded = load_user_exec_policy(codex_home.path(), cwd.path(), false);
+        assert!(loaded.policy.is_none());
+        assert_eq!(loaded.issues.len(), 1);
+        assert_eq!(loaded.issues[0].path, dir.join("kubectl.policy"));
+    }
+}
@@ -1,3 +1,4 @@
+pub(crate) mod exec_policy;
 pub mod is_dangerous_command;
 pub mod is_safe_command;
 #[cfg(target_os = "windows")]
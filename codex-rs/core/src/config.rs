@@ -3,21 +3,30 @@ pub use crate::config_loader::load_config_as_toml;
 use crate::config_loader::load_config_layers_with_overrides;
 use crate::config_loader::merge_toml_values;
 use crate::config_profile::ConfigProfile;
+use crate::config_types::CassetteConfig;
 use crate::config_types::DEFAULT_OTEL_ENVIRONMENT;
 use crate::config_types::History;
+use crate::config_types::Hooks;
 use crate::config_types::McpServerConfig;
 use crate::config_types::McpServerTransportConfig;
+use crate::config_types::ModelFallback;
+use crate::config_types::ModelFallbackToml;
+use crate::config_types::ModelPricingOverride;
 use crate::config_types::Notifications;
 use crate::config_types::OtelConfig;
 use crate::config_types::OtelConfigToml;
 use crate::config_types::OtelExporterKind;
 use crate::config_types::ReasoningSummaryFormat;
+use crate::config_types::ResourceLimits;
+use crate::config_types::SandboxRead;
 use crate::config_types::SandboxWorkspaceWrite;
+use crate::config_types::SessionRetention;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::config_types::ShellEnvironmentPolicyToml;
 use crate::config_types::Tui;
 use crate::config_types::UriBasedFileOpener;
 use crate::git_info::resolve_root_git_project_for_trust;
+use crate::landlock::LinuxSandboxOptions;
 use crate::model_family::ModelFamily;
 use crate::model_family::derive_default_model_family;
 use crate::model_family::find_family_for_model;
@@ -29,6 +38,7 @@ use crate::protocol::SandboxPolicy;
 use anyhow::Context;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::config_types::SandboxMode;
@@ -83,12 +93,26 @@ pub struct Config {
     /// Token usage threshold triggering auto-compaction of conversation history.
     pub model_auto_compact_token_limit: Option<i64>,
 
+    /// How `/compact` and auto-compaction shrink the conversation history.
+    pub compaction_strategy: CompactionStrategy,
+
     /// Key into the model_providers map that specifies which provider to use.
     pub model_provider_id: String,
 
     /// Info needed to make an API request to the model.
     pub model_provider: ModelProviderInfo,
 
+    /// Ordered entries the model client fails over to when the active
+    /// provider is unavailable.
+    pub model_fallbacks: Vec<ModelFallback>,
+
+    /// Entries that replace the built-in price of a model.
+    pub model_pricing: Vec<ModelPricingOverride>,
+
+    /// Session cost in USD after which the agent pauses and asks whether to
+    /// keep going. Unset means no budget.
+    pub session_budget_usd: Option<f64>,
+
     /// Approval policy for executing commands.
     pub approval_policy: AskForApproval,
 
@@ -96,6 +120,13 @@ pub struct Config {
 
     pub shell_environment_policy: ShellEnvironmentPolicy,
 
+    /// Limits on the memory, CPU time, processes and file sizes of shell
+    /// tool calls.
+    pub resource_limits: ResourceLimits,
+
+    /// How the Linux sandbox runs shell tool calls.
+    pub linux_sandbox: LinuxSandboxOptions,
+
     /// When `true`, `AgentReasoning` events emitted by the backend will be
     /// suppressed from the frontend output. This can reduce visual noise when
     /// users are only interested in the final agent responses.
@@ -133,6 +164,13 @@ pub struct Config {
     /// If unset the feature is disabled.
     pub notify: Option<Vec<String>>,
 
+    /// Commands run before and after tool calls. See [`Hooks`].
+    pub hooks: Hooks,
+
+    /// When set, model traffic is recorded to or replayed from a directory.
+    /// See [`crate::config_types::CassetteConfig`].
+    pub cassette: Option<CassetteConfig>,
+
     /// TUI notifications preference. When set, the TUI will send OSC 9 notifications on approvals
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
@@ -142,6 +180,11 @@ pub struct Config {
     /// resolved against this path.
     pub cwd: PathBuf,
 
+    /// Whether `cwd` belongs to a project the user marked as trusted. Files
+    /// the project itself provides, such as its execpolicy files, are only
+    /// honored when it is.
+    pub cwd_trusted: bool,
+
     /// Definition for MCP servers that Codex can reach out to for tool calls.
     pub mcp_servers: HashMap<String, McpServerConfig>,
 
@@ -170,6 +213,10 @@ pub struct Config {
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     pub history: History,
 
+    /// How long recorded sessions are kept and whether finished ones are
+    /// compressed.
+    pub session_retention: SessionRetention,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: UriBasedFileOpener,
@@ -221,6 +268,10 @@ pub struct Config {
     /// The active profile name used to derive this `Config` (if any).
     pub active_profile: Option<String>,
 
+    /// The `-c key=value` overrides this `Config` was loaded with, kept so
+    /// the configuration can be read again by [`Config::reload`].
+    pub cli_overrides: Vec<(String, TomlValue)>,
+
     /// Tracks whether the Windows onboarding screen has been acknowledged.
     pub windows_wsl_setup_acknowledged: bool,
 
@@ -242,17 +293,50 @@ impl Config {
 
         let root_value = load_resolved_config(
             &codex_home,
-            cli_overrides,
+            cli_overrides.clone(),
+            crate::config_loader::LoaderOverrides::default(),
+        )
+        .await?;
+
+        let cfg: ConfigToml = root_value.try_into().map_err(|e| {
+            tracing::error!("Failed to deserialize overridden config: {e}");
+            std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+        })?;
+
+        let mut config = Self::load_from_base_config_with_overrides(cfg, overrides, codex_home)?;
+        config.cli_overrides = cli_overrides;
+        Ok(config)
+    }
+
+    /// Reads the config files in `codex_home` again, applying the same `-c`
+    /// overrides, profile and working directory as this `Config`. Other
+    /// [`ConfigOverrides`] are not reapplied, so only settings that come from
+    /// the config files (such as `mcp_servers`) should be taken from the result.
+    pub async fn reload(&self) -> std::io::Result<Self> {
+        let root_value = load_resolved_config(
+            &self.codex_home,
+            self.cli_overrides.clone(),
             crate::config_loader::LoaderOverrides::default(),
         )
         .await?;
+        if let Some(servers_value) = root_value.get("mcp_servers") {
+            ensure_no_inline_bearer_tokens(servers_value)?;
+        }
 
         let cfg: ConfigToml = root_value.try_into().map_err(|e| {
             tracing::error!("Failed to deserialize overridden config: {e}");
             std::io::Error::new(std::io::ErrorKind::InvalidData, e)
         })?;
+        let overrides = ConfigOverrides {
+            cwd: Some(self.cwd.clone()),
+            config_profile: self.active_profile.clone(),
+            ..Default::default()
+        };
 
-        Self::load_from_base_config_with_overrides(cfg, overrides, codex_home)
+        let mut config =
+            Self::load_from_base_config_with_overrides(cfg, overrides, self.codex_home.clone())?;
+        config.cli_overrides = self.cli_overrides.clone();
+        Ok(config)
     }
 }
 
@@ -413,6 +497,10 @@ pub fn write_global_mcp_servers(
                 entry["tool_timeout_sec"] = toml_edit::value(timeout.as_secs_f64());
             }
 
+            if config.auto_approve_sampling {
+                entry["auto_approve_sampling"] = toml_edit::value(true);
+            }
+
             doc["mcp_servers"][name.as_str()] = TomlItem::Table(entry);
         }
     }
@@ -704,6 +792,16 @@ pub struct ConfigToml {
     /// Provider to use from the model_providers map.
     pub model_provider: Option<String>,
 
+    /// Ordered `(provider, model)` pairs to fail over to when the primary
+    /// provider keeps failing or its rate limits are exhausted.
+    pub model_fallbacks: Option<Vec<ModelFallbackToml>>,
+
+    /// Prices replacing the built-in table used to compute session cost.
+    pub model_pricing: Option<Vec<ModelPricingOverride>>,
+
+    /// Session cost in USD after which the agent asks before continuing.
+    pub session_budget_usd: Option<f64>,
+
     /// Size of the context window for the model, in tokens.
     pub model_context_window: Option<u64>,
 
@@ -713,6 +811,9 @@ pub struct ConfigToml {
     /// Token usage threshold triggering auto-compaction of conversation history.
     pub model_auto_compact_token_limit: Option<i64>,
 
+    /// How `/compact` and auto-compaction shrink the conversation history.
+    pub compaction_strategy: Option<CompactionStrategy>,
+
     /// Default approval policy for executing commands.
     pub approval_policy: Option<AskForApproval>,
 
@@ -725,10 +826,24 @@ pub struct ConfigToml {
     /// Sandbox configuration to apply if `sandbox` is `WorkspaceWrite`.
     pub sandbox_workspace_write: Option<SandboxWorkspaceWrite>,
 
+    /// Read restrictions to apply if `sandbox` is `ReadOnly` or
+    /// `WorkspaceWrite`.
+    pub sandbox_read: Option<SandboxRead>,
+
+    /// Limits applied to every shell tool call.
+    pub resource_limits: Option<ResourceLimits>,
+
     /// Optional external command to spawn for end-user notifications.
     #[serde(default)]
     pub notify: Option<Vec<String>>,
 
+    /// Commands to run before and after tool calls.
+    #[serde(default)]
+    pub hooks: Option<Hooks>,
+
+    /// Record model traffic to, or replay it from, a directory.
+    pub cassette: Option<CassetteConfig>,
+
     /// System instructions.
     pub instructions: Option<String>,
 
@@ -765,6 +880,11 @@ pub struct ConfigToml {
     #[serde(default)]
     pub history: Option<History>,
 
+    /// How long recorded sessions are kept and whether finished ones are
+    /// compressed.
+    #[serde(default)]
+    pub session_retention: Option<SessionRetention>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
@@ -872,50 +992,50 @@ impl ConfigToml {
         let resolved_sandbox_mode = sandbox_mode_override
             .or(self.sandbox_mode)
             .unwrap_or_default();
-        match resolved_sandbox_mode {
+        let mut policy = match resolved_sandbox_mode {
             SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
             SandboxMode::WorkspaceWrite => match self.sandbox_workspace_write.as_ref() {
                 Some(SandboxWorkspaceWrite {
                     writable_roots,
                     network_access,
+                    allowed_domains,
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
+                    isolation: _,
+                    report_denials: _,
                 }) => SandboxPolicy::WorkspaceWrite {
                     writable_roots: writable_roots.clone(),
                     network_access: *network_access,
+                    allowed_domains: allowed_domains.clone(),
                     exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                     exclude_slash_tmp: *exclude_slash_tmp,
+                    ..SandboxPolicy::new_workspace_write_policy()
                 },
                 None => SandboxPolicy::new_workspace_write_policy(),
             },
             SandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
+        };
+        if let Some(sandbox_read) = self.sandbox_read.as_ref()
+            && let SandboxPolicy::ReadOnly {
+                readable_roots,
+                deny_read,
+            }
+            | SandboxPolicy::WorkspaceWrite {
+                readable_roots,
+                deny_read,
+                ..
+            } = &mut policy
+        {
+            readable_roots.clone_from(&sandbox_read.readable_roots);
+            if let Some(configured) = &sandbox_read.deny_read {
+                deny_read.clone_from(configured);
+            }
         }
+        policy
     }
 
     pub fn is_cwd_trusted(&self, resolved_cwd: &Path) -> bool {
-        let projects = self.projects.clone().unwrap_or_default();
-
-        let is_path_trusted = |path: &Path| {
-            let path_str = path.to_string_lossy().to_string();
-            projects
-                .get(&path_str)
-                .map(|p| p.trust_level.as_deref() == Some("trusted"))
-                .unwrap_or(false)
-        };
-
-        // Fast path: exact cwd match
-        if is_path_trusted(resolved_cwd) {
-            return true;
-        }
-
-        // If cwd lives inside a git worktree, check whether the root git project
-        // (the primary repository working directory) is trusted. This lets
-        // worktrees inherit trust from the main project.
-        if let Some(root_project) = resolve_root_git_project_for_trust(resolved_cwd) {
-            return is_path_trusted(&root_project);
-        }
-
-        false
+        is_cwd_trusted(self.projects.as_ref(), resolved_cwd)
     }
 
     pub fn get_config_profile(
@@ -940,6 +1060,32 @@ impl ConfigToml {
     }
 }
 
+/// Whether `resolved_cwd`, or the root git project it belongs to, is marked
+/// as trusted in `projects`.
+fn is_cwd_trusted(projects: Option<&HashMap<String, ProjectConfig>>, resolved_cwd: &Path) -> bool {
+    let is_path_trusted = |path: &Path| {
+        let path_str = path.to_string_lossy().to_string();
+        projects
+            .and_then(|projects| projects.get(&path_str))
+            .map(|p| p.trust_level.as_deref() == Some("trusted"))
+            .unwrap_or(false)
+    };
+
+    // Fast path: exact cwd match
+    if is_path_trusted(resolved_cwd) {
+        return true;
+    }
+
+    // If cwd lives inside a git worktree, check whether the root git project
+    // (the primary repository working directory) is trusted. This lets
+    // worktrees inherit trust from the main project.
+    if let Some(root_project) = resolve_root_git_project_for_trust(resolved_cwd) {
+        return is_path_trusted(&root_project);
+    }
+
+    false
+}
+
 /// Optional overrides for user configuration (e.g., from CLI flags).
 #[derive(Default, Debug, Clone)]
 pub struct ConfigOverrides {
@@ -957,6 +1103,7 @@ pub struct ConfigOverrides {
     pub include_view_image_tool: Option<bool>,
     pub show_raw_agent_reasoning: Option<bool>,
     pub tools_web_search_request: Option<bool>,
+    pub cassette: Option<CassetteConfig>,
 }
 
 impl Config {
@@ -985,6 +1132,7 @@ impl Config {
             include_view_image_tool,
             show_raw_agent_reasoning,
             tools_web_search_request: override_tools_web_search_request,
+            cassette,
         } = overrides;
 
         let active_profile_name = config_profile_key
@@ -1027,6 +1175,34 @@ impl Config {
             })?
             .clone();
 
+        let model_fallbacks = config_profile
+            .model_fallbacks
+            .or(cfg.model_fallbacks)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|fallback| {
+                let model_provider_id = fallback
+                    .model_provider
+                    .unwrap_or_else(|| model_provider_id.clone());
+                let model_provider = model_providers
+                    .get(&model_provider_id)
+                    .ok_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::NotFound,
+                            format!(
+                                "Model provider `{model_provider_id}` in `model_fallbacks` not found"
+                            ),
+                        )
+                    })?
+                    .clone();
+                Ok(ModelFallback {
+                    model_provider_id,
+                    model_provider,
+                    model: fallback.model,
+                })
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
         let resolved_cwd = {
@@ -1048,6 +1224,12 @@ impl Config {
             }
         };
 
+        // A relative cassette directory is resolved against the working directory.
+        let cassette = cassette.or(cfg.cassette).map(|cassette| CassetteConfig {
+            dir: resolved_cwd.join(&cassette.dir),
+            ..cassette
+        });
+
         let history = cfg.history.unwrap_or_default();
 
         let tools_web_search_request = override_tools_web_search_request
@@ -1104,6 +1286,7 @@ impl Config {
             .or(cfg.review_model)
             .unwrap_or_else(default_review_model);
 
+        let cwd_trusted = is_cwd_trusted(cfg.projects.as_ref(), &resolved_cwd);
         let config = Self {
             model,
             review_model,
@@ -1111,16 +1294,34 @@ impl Config {
             model_context_window,
             model_max_output_tokens,
             model_auto_compact_token_limit,
+            compaction_strategy: cfg.compaction_strategy.unwrap_or_default(),
             model_provider_id,
             model_provider,
+            model_fallbacks,
+            model_pricing: cfg.model_pricing.unwrap_or_default(),
+            session_budget_usd: config_profile
+                .session_budget_usd
+                .or(cfg.session_budget_usd),
             cwd: resolved_cwd,
+            cwd_trusted,
             approval_policy: approval_policy
                 .or(config_profile.approval_policy)
                 .or(cfg.approval_policy)
                 .unwrap_or_else(AskForApproval::default),
             sandbox_policy,
             shell_environment_policy,
+            resource_limits: cfg.resource_limits.unwrap_or_default(),
+            linux_sandbox: cfg
+                .sandbox_workspace_write
+                .as_ref()
+                .map(|sandbox_workspace_write| LinuxSandboxOptions {
+                    isolation: sandbox_workspace_write.isolation,
+                    report_denials: sandbox_workspace_write.report_denials,
+                })
+                .unwrap_or_default(),
             notify: cfg.notify,
+            hooks: cfg.hooks.unwrap_or_default(),
+            cassette,
             user_instructions,
             base_instructions,
             mcp_servers: cfg.mcp_servers,
@@ -1144,6 +1345,7 @@ impl Config {
                 .collect(),
             codex_home,
             history,
+            session_retention: cfg.session_retention.unwrap_or_default(),
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
             codex_linux_sandbox_exe,
 
@@ -1178,6 +1380,7 @@ impl Config {
             use_experimental_use_rmcp_client: cfg.experimental_use_rmcp_client.unwrap_or(false),
             include_view_image_tool,
             active_profile: active_profile_name,
+            cli_overrides: Vec::new(),
             windows_wsl_setup_acknowledged: cfg.windows_wsl_setup_acknowledged.unwrap_or(false),
             disable_paste_burst: cfg.disable_paste_burst.unwrap_or(false),
             tui_notifications: cfg
@@ -1308,7 +1511,9 @@ pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
 #[cfg(test)]
 mod tests {
     use crate::config_types::HistoryPersistence;
+    use crate::config_types::HookConfig;
     use crate::config_types::Notifications;
+    use crate::landlock::LinuxSandboxIsolation;
 
     use super::*;
     use pretty_assertions::assert_eq;
@@ -1348,6 +1553,48 @@ persistence = "none"
         );
     }
 
+    #[test]
+    fn session_retention_parsing() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[session_retention]
+max_age_days = 30
+compress = true
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(SessionRetention {
+                max_age_days: Some(30),
+                max_total_bytes: None,
+                keep_archived: true,
+                compress: true,
+            }),
+            cfg.session_retention
+        );
+    }
+
+    #[test]
+    fn resource_limits_parsing() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[resource_limits]
+max_memory_bytes = 4294967296
+max_processes = 512
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(ResourceLimits {
+                max_memory_bytes: Some(4_294_967_296),
+                max_cpu_seconds: None,
+                max_processes: Some(512),
+                max_file_size_bytes: None,
+            }),
+            cfg.resource_limits
+        );
+    }
+
     #[test]
     fn tui_config_missing_notifications_field_defaults_to_disabled() {
         let cfg = r#"
@@ -1361,6 +1608,60 @@ persistence = "none"
         assert_eq!(tui.notifications, Notifications::Enabled(false));
     }
 
+    #[test]
+    fn hooks_config_parsing() {
+        let cfg = r#"
+[[hooks.pre_tool_use]]
+matcher = "shell"
+command = ["./check.sh"]
+timeout_sec = 5
+
+[[hooks.post_tool_use]]
+command = ["logger", "-t", "codex"]
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("hooks config should parse");
+
+        assert_eq!(
+            parsed.hooks,
+            Some(Hooks {
+                pre_tool_use: vec![HookConfig {
+                    matcher: Some("shell".to_string()),
+                    command: vec!["./check.sh".to_string()],
+                    timeout_sec: Some(5),
+                }],
+                post_tool_use: vec![HookConfig {
+                    matcher: None,
+                    command: vec![
+                        "logger".to_string(),
+                        "-t".to_string(),
+                        "codex".to_string(),
+                    ],
+                    timeout_sec: None,
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn compaction_strategy_parsing() {
+        let parse = |cfg: &str| {
+            toml::from_str::<ConfigToml>(cfg)
+                .expect("compaction strategy should parse")
+                .compaction_strategy
+        };
+
+        assert_eq!(
+            parse(r#"compaction_strategy = "drop_tool_outputs""#),
+            Some(CompactionStrategy::DropToolOutputs)
+        );
+        assert_eq!(
+            parse("compaction_strategy = { keep_last_turns = 3 }"),
+            Some(CompactionStrategy::KeepLastTurns(3))
+        );
+        assert_eq!(parse(""), None);
+    }
+
     #[test]
     fn test_sandbox_config_parsing() {
         let sandbox_full_access = r#"
@@ -1388,7 +1689,7 @@ network_access = true  # This should be ignored.
             .expect("TOML deserialization should succeed");
         let sandbox_mode_override = None;
         assert_eq!(
-            SandboxPolicy::ReadOnly,
+            SandboxPolicy::new_read_only_policy(),
             sandbox_read_only_cfg.derive_sandbox_policy(sandbox_mode_override)
         );
 
@@ -1399,8 +1700,11 @@ sandbox_mode = "workspace-write"
 writable_roots = [
     "/my/workspace",
 ]
+allowed_domains = ["registry.npmjs.org"]
 exclude_tmpdir_env_var = true
 exclude_slash_tmp = true
+isolation = "namespaces"
+report_denials = true
 "#;
 
         let sandbox_workspace_write_cfg = toml::from_str::<ConfigToml>(sandbox_workspace_write)
@@ -1410,11 +1714,41 @@ exclude_slash_tmp = true
             SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![PathBuf::from("/my/workspace")],
                 network_access: false,
+                allowed_domains: vec!["registry.npmjs.org".to_string()],
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                ..SandboxPolicy::new_workspace_write_policy()
             },
             sandbox_workspace_write_cfg.derive_sandbox_policy(sandbox_mode_override)
         );
+        assert_eq!(
+            sandbox_workspace_write_cfg
+                .sandbox_workspace_write
+                .map(|sandbox_workspace_write| (
+                    sandbox_workspace_write.isolation,
+                    sandbox_workspace_write.report_denials
+                )),
+            Some((LinuxSandboxIsolation::Namespaces, true))
+        );
+
+        let sandbox_read = r#"
+sandbox_mode = "read-only"
+
+[sandbox_read]
+readable_roots = ["/srv/shared"]
+deny_read = ["secrets"]
+"#;
+
+        let sandbox_read_cfg = toml::from_str::<ConfigToml>(sandbox_read)
+            .expect("TOML deserialization should succeed");
+        let sandbox_mode_override = None;
+        assert_eq!(
+            SandboxPolicy::ReadOnly {
+                readable_roots: vec![PathBuf::from("/srv/shared")],
+                deny_read: vec![PathBuf::from("secrets")],
+            },
+            sandbox_read_cfg.derive_sandbox_policy(sandbox_mode_override)
+        );
     }
 
     #[test]
@@ -1458,6 +1792,64 @@ exclude_slash_tmp = true
         Ok(())
     }
 
+    #[test]
+    fn model_fallbacks_resolve_providers_and_profile_overrides() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+model_fallbacks = [
+  { model = "gpt-5-codex" },
+  { model_provider = "oss", model = "gpt-oss:20b" },
+]
+
+[profiles.backup]
+model_fallbacks = [{ model_provider = "oss", model = "gpt-oss:120b" }]
+"#,
+        )
+        .expect("model_fallbacks should parse");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg.clone(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        let fallbacks = config
+            .model_fallbacks
+            .iter()
+            .map(|f| (f.model_provider_id.as_str(), f.model.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fallbacks,
+            vec![("openai", "gpt-5-codex"), ("oss", "gpt-oss:20b")]
+        );
+        assert_eq!(config.model_fallbacks[1].model_provider.name, "gpt-oss");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                config_profile: Some("backup".to_string()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.model_fallbacks.len(), 1);
+        assert_eq!(config.model_fallbacks[0].model, "gpt-oss:120b");
+
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"model_fallbacks = [{ model_provider = "missing", model = "m" }]"#,
+        )
+        .expect("model_fallbacks should parse");
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("unknown fallback provider should be rejected");
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn managed_config_overrides_oauth_store_mode() -> anyhow::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1522,6 +1914,7 @@ exclude_slash_tmp = true
                 enabled: true,
                 startup_timeout_sec: Some(Duration::from_secs(3)),
                 tool_timeout_sec: Some(Duration::from_secs(5)),
+                auto_approve_sampling: false,
             },
         );
 
@@ -1605,6 +1998,41 @@ startup_timeout_ms = 2500
         Ok(())
     }
 
+    #[tokio::test]
+    async fn reload_applies_the_original_cli_overrides() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let config_path = codex_home.path().join(CONFIG_TOML_FILE);
+
+        let mut config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                cwd: Some(codex_home.path().to_path_buf()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        config.cli_overrides = vec![(
+            "mcp_servers.extra.command".to_string(),
+            TomlValue::String("cat".to_string()),
+        )];
+
+        std::fs::write(
+            &config_path,
+            r#"
+[mcp_servers.docs]
+command = "echo"
+"#,
+        )?;
+
+        let reloaded = config.reload().await?;
+        let mut names: Vec<&String> = reloaded.mcp_servers.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["docs", "extra"]);
+        assert_eq!(reloaded.cli_overrides, config.cli_overrides);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn load_global_mcp_servers_rejects_inline_bearer_token() -> anyhow::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1648,6 +2076,7 @@ bearer_token = "secret"
                 enabled: true,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                auto_approve_sampling: false,
             },
         )]);
 
@@ -1699,6 +2128,7 @@ ZIG_VAR = "3"
                 enabled: true,
                 startup_timeout_sec: Some(Duration::from_secs(2)),
                 tool_timeout_sec: None,
+                auto_approve_sampling: false,
             },
         )]);
 
@@ -1739,6 +2169,7 @@ startup_timeout_sec = 2.0
                 enabled: true,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                auto_approve_sampling: false,
             },
         );
         write_global_mcp_servers(codex_home.path(), &servers)?;
@@ -1782,6 +2213,7 @@ url = "https://example.com/mcp"
                 enabled: false,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                auto_approve_sampling: false,
             },
         )]);
 
@@ -2089,14 +2521,23 @@ model_verbosity = "high"
                 model_context_window: Some(200_000),
                 model_max_output_tokens: Some(100_000),
                 model_auto_compact_token_limit: None,
+                compaction_strategy: CompactionStrategy::default(),
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
+                model_fallbacks: Vec::new(),
+                model_pricing: Vec::new(),
+                session_budget_usd: None,
                 approval_policy: AskForApproval::Never,
                 sandbox_policy: SandboxPolicy::new_read_only_policy(),
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
+                resource_limits: ResourceLimits::default(),
+                linux_sandbox: LinuxSandboxOptions::default(),
                 user_instructions: None,
                 notify: None,
+                hooks: Hooks::default(),
+                cassette: None,
                 cwd: fixture.cwd(),
+                cwd_trusted: false,
                 mcp_servers: HashMap::new(),
                 mcp_oauth_credentials_store_mode: Default::default(),
                 model_providers: fixture.model_provider_map.clone(),
@@ -2104,6 +2545,7 @@ model_verbosity = "high"
                 project_doc_fallback_filenames: Vec::new(),
                 codex_home: fixture.codex_home(),
                 history: History::default(),
+                session_retention: SessionRetention::default(),
                 file_opener: UriBasedFileOpener::VsCode,
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
@@ -2121,6 +2563,7 @@ model_verbosity = "high"
                 use_experimental_use_rmcp_client: false,
                 include_view_image_tool: true,
                 active_profile: Some("o3".to_string()),
+                cli_overrides: Vec::new(),
                 windows_wsl_setup_acknowledged: false,
                 disable_paste_burst: false,
                 tui_notifications: Default::default(),
@@ -2152,14 +2595,23 @@ model_verbosity = "high"
             model_context_window: Some(16_385),
             model_max_output_tokens: Some(4_096),
             model_auto_compact_token_limit: None,
+            compaction_strategy: CompactionStrategy::default(),
             model_provider_id: "openai-chat-completions".to_string(),
             model_provider: fixture.openai_chat_completions_provider.clone(),
+            model_fallbacks: Vec::new(),
+            model_pricing: Vec::new(),
+            session_budget_usd: None,
             approval_policy: AskForApproval::UnlessTrusted,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            resource_limits: ResourceLimits::default(),
+            linux_sandbox: LinuxSandboxOptions::default(),
             user_instructions: None,
             notify: None,
+            hooks: Hooks::default(),
+            cassette: None,
             cwd: fixture.cwd(),
+            cwd_trusted: false,
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
@@ -2167,6 +2619,7 @@ model_verbosity = "high"
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
+            session_retention: SessionRetention::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -2184,6 +2637,7 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
             active_profile: Some("gpt3".to_string()),
+            cli_overrides: Vec::new(),
             windows_wsl_setup_acknowledged: false,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
@@ -2230,14 +2684,23 @@ model_verbosity = "high"
             model_context_window: Some(200_000),
             model_max_output_tokens: Some(100_000),
             model_auto_compact_token_limit: None,
+            compaction_strategy: CompactionStrategy::default(),
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            model_fallbacks: Vec::new(),
+            model_pricing: Vec::new(),
+            session_budget_usd: None,
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            resource_limits: ResourceLimits::default(),
+            linux_sandbox: LinuxSandboxOptions::default(),
             user_instructions: None,
             notify: None,
+            hooks: Hooks::default(),
+            cassette: None,
             cwd: fixture.cwd(),
+            cwd_trusted: false,
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
@@ -2245,6 +2708,7 @@ model_verbosity = "high"
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
+            session_retention: SessionRetention::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -2262,6 +2726,7 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
             active_profile: Some("zdr".to_string()),
+            cli_overrides: Vec::new(),
             windows_wsl_setup_acknowledged: false,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
@@ -2294,14 +2759,23 @@ model_verbosity = "high"
             model_context_window: Some(272_000),
             model_max_output_tokens: Some(128_000),
             model_auto_compact_token_limit: None,
+            compaction_strategy: CompactionStrategy::default(),
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            model_fallbacks: Vec::new(),
+            model_pricing: Vec::new(),
+            session_budget_usd: None,
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            resource_limits: ResourceLimits::default(),
+            linux_sandbox: LinuxSandboxOptions::default(),
             user_instructions: None,
             notify: None,
+            hooks: Hooks::default(),
+            cassette: None,
             cwd: fixture.cwd(),
+            cwd_trusted: false,
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
@@ -2309,6 +2783,7 @@ model_verbosity = "high"
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
+            session_retention: SessionRetention::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -2326,6 +2801,7 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
             active_profile: Some("gpt5".to_string()),
+            cli_overrides: Vec::new(),
             windows_wsl_setup_acknowledged: false,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use std::path::PathBuf;
 
+use crate::config_types::ModelFallbackToml;
 use crate::protocol::AskForApproval;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
@@ -14,6 +15,11 @@ pub struct ConfigProfile {
     /// The key in the `model_providers` map identifying the
     /// [`ModelProviderInfo`] to use.
     pub model_provider: Option<String>,
+    /// Ordered `(provider, model)` pairs to fail over to. Replaces the
+    /// top-level `model_fallbacks` list when set.
+    pub model_fallbacks: Option<Vec<ModelFallbackToml>>,
+    /// Session cost in USD after which the agent asks before continuing.
+    pub session_budget_usd: Option<f64>,
     pub approval_policy: Option<AskForApproval>,
     pub model_reasoning_effort: Option<ReasoningEffort>,
     pub model_reasoning_summary: Option<ReasoningSummary>,
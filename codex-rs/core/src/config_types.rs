@@ -13,6 +13,10 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde::de::Error as SerdeError;
 
+use crate::landlock::LinuxSandboxIsolation;
+use crate::model_provider_info::ModelProviderInfo;
+use crate::pricing::ModelPricing;
+
 pub const DEFAULT_OTEL_ENVIRONMENT: &str = "dev";
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -35,6 +39,11 @@ pub struct McpServerConfig {
     /// Default timeout for MCP tool calls initiated via this server.
     #[serde(default, with = "option_duration_secs")]
     pub tool_timeout_sec: Option<Duration>,
+
+    /// When `true`, `sampling/createMessage` requests from this server are
+    /// sent to the model without asking the user first.
+    #[serde(default)]
+    pub auto_approve_sampling: bool,
 }
 
 impl<'de> Deserialize<'de> for McpServerConfig {
@@ -62,6 +71,8 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             tool_timeout_sec: Option<Duration>,
             #[serde(default)]
             enabled: Option<bool>,
+            #[serde(default)]
+            auto_approve_sampling: bool,
         }
 
         let raw = RawMcpServerConfig::deserialize(deserializer)?;
@@ -134,6 +145,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             startup_timeout_sec,
             tool_timeout_sec: raw.tool_timeout_sec,
             enabled: raw.enabled.unwrap_or_else(default_enabled),
+            auto_approve_sampling: raw.auto_approve_sampling,
         })
     }
 }
@@ -227,11 +239,49 @@ pub struct History {
     /// If true, history entries will not be written to disk.
     pub persistence: HistoryPersistence,
 
-    /// If set, the maximum size of the history file in bytes.
-    /// TODO(mbolin): Not currently honored.
+    /// If set, the maximum size of the history file in bytes. The oldest
+    /// entries are dropped once an append pushes the file past this size.
     pub max_bytes: Option<usize>,
 }
 
+/// Settings that govern how long recorded sessions are kept under
+/// `~/.codex/sessions` and how they are stored.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct SessionRetention {
+    /// Sessions without activity for this many days are deleted.
+    pub max_age_days: Option<u64>,
+
+    /// Once all sessions together take more than this many bytes on disk, the
+    /// least recently active ones are deleted.
+    pub max_total_bytes: Option<u64>,
+
+    /// If false, sessions under `~/.codex/archived_sessions` are pruned too.
+    #[serde(default = "default_enabled")]
+    pub keep_archived: bool,
+
+    /// Compress rollout files with zstd once their session ends.
+    #[serde(default)]
+    pub compress: bool,
+}
+
+impl Default for SessionRetention {
+    fn default() -> Self {
+        Self {
+            max_age_days: None,
+            max_total_bytes: None,
+            keep_archived: true,
+            compress: false,
+        }
+    }
+}
+
+impl SessionRetention {
+    /// Whether any limit is configured, i.e. pruning can delete anything.
+    pub fn has_limits(&self) -> bool {
+        self.max_age_days.is_some() || self.max_total_bytes.is_some()
+    }
+}
+
 #[derive(Deserialize, Debug, Copy, Clone, PartialEq, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum HistoryPersistence {
@@ -300,6 +350,87 @@ impl Default for OtelConfig {
     }
 }
 
+/// Entry of the `model_fallbacks` chain as written in config.toml.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ModelFallbackToml {
+    /// Key in the `model_providers` map. Defaults to the primary provider.
+    pub model_provider: Option<String>,
+
+    /// Model to request from the fallback provider.
+    pub model: String,
+}
+
+/// A `(provider, model)` pair that the model client switches to when the
+/// active entry keeps failing or reports an exhausted rate limit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelFallback {
+    pub model_provider_id: String,
+    pub model_provider: ModelProviderInfo,
+    pub model: String,
+}
+
+/// A `[[model_pricing]]` entry that replaces the built-in price of a model.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ModelPricingOverride {
+    pub model: String,
+
+    /// Key in the `model_providers` map. When unset the price applies to
+    /// every provider serving `model`.
+    pub model_provider: Option<String>,
+
+    #[serde(flatten)]
+    pub pricing: ModelPricing,
+}
+
+/// Whether model traffic is written to or served from a cassette.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CassetteMode {
+    /// Send requests to the provider and store each request with its response.
+    Record,
+    /// Serve stored responses without touching the network.
+    Replay,
+}
+
+/// Record-and-replay of model traffic, configured under `[cassette]`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CassetteConfig {
+    pub mode: CassetteMode,
+
+    /// Directory holding the recorded interactions.
+    pub dir: PathBuf,
+}
+
+/// Commands to run around tool calls, configured under `[hooks]`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Hooks {
+    /// Run before a tool call. A hook can block the call or add a message
+    /// for the model.
+    #[serde(default)]
+    pub pre_tool_use: Vec<HookConfig>,
+
+    /// Run after a tool call, with its output. A hook can add a message for
+    /// the model or mark the call as failed.
+    #[serde(default)]
+    pub post_tool_use: Vec<HookConfig>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct HookConfig {
+    /// Glob matched against the tool name, e.g. `shell` or `github__*`. The
+    /// hook runs for every tool when unset.
+    #[serde(default)]
+    pub matcher: Option<String>,
+
+    /// Program and arguments to run. The tool call is written to its stdin as
+    /// JSON.
+    pub command: Vec<String>,
+
+    /// Seconds to wait for the command before giving up on it (default: 60).
+    #[serde(default)]
+    pub timeout_sec: Option<u64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(untagged)]
 pub enum Notifications {
@@ -328,10 +459,67 @@ pub struct SandboxWorkspaceWrite {
     pub writable_roots: Vec<PathBuf>,
     #[serde(default)]
     pub network_access: bool,
+    /// Domains reachable through the Codex network proxy while
+    /// `network_access` is `false`.
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
     #[serde(default)]
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
+    /// How the Linux sandbox isolates commands. Ignored on other platforms.
+    #[serde(default)]
+    pub isolation: LinuxSandboxIsolation,
+    /// Whether the Linux sandbox reports the writes and network connections
+    /// it blocks. Ignored on other platforms.
+    #[serde(default)]
+    pub report_denials: bool,
+}
+
+/// Read restrictions applied to the `read-only` and `workspace-write`
+/// sandboxes, configured under `[sandbox_read]`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SandboxRead {
+    /// When non-empty, sandboxed commands may only read these folders, the
+    /// writable roots, the cwd and the system folders needed to run programs.
+    #[serde(default)]
+    pub readable_roots: Vec<PathBuf>,
+
+    /// Paths that sandboxed commands must never read. An entry without a `/`
+    /// names files and folders in every folder (like `.env`); other entries
+    /// may start with `~/` or be relative to the cwd. When unset, `~/.ssh`,
+    /// `~/.aws`, `~/.config/gcloud` and `.env` are denied; set to `[]` to
+    /// allow them. Codex's own file tools honor the list too.
+    pub deny_read: Option<Vec<PathBuf>>,
+}
+
+/// Per-command resource limits for shell tool calls. Unset limits are not
+/// enforced.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ResourceLimits {
+    /// Maximum memory, in bytes. Enforced through a cgroup when the Linux
+    /// sandbox can create one, otherwise as a limit on address space.
+    pub max_memory_bytes: Option<u64>,
+
+    /// Maximum CPU time, in seconds.
+    pub max_cpu_seconds: Option<u64>,
+
+    /// Maximum number of processes (including threads) the command may have
+    /// running at once. Only enforced through a cgroup of the Linux sandbox;
+    /// commands that cannot get one are refused.
+    pub max_processes: Option<u64>,
+
+    /// Maximum size, in bytes, of any file the command writes.
+    pub max_file_size_bytes: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_memory_bytes.is_none()
+            && self.max_cpu_seconds.is_none()
+            && self.max_processes.is_none()
+            && self.max_file_size_bytes.is_none()
+    }
 }
 
 impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings {
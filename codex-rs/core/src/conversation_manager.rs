@@ -34,20 +34,19 @@ pub struct NewConversation {
 }
 
 /// Extract the conversation ID from a rollout file path.
-/// Expected filename format: `rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl`
+/// Expected filename format: `rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl[.zst]`
 fn extract_conversation_id_from_path(path: &Path) -> CodexResult<ConversationId> {
-    let filename = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| {
-            CodexErr::Io(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                format!("Invalid rollout path: {path:?}"),
-            ))
-        })?;
-
-    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl
+    let filename = path.file_name().and_then(|n| n.to_str()).ok_or_else(|| {
+        CodexErr::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid rollout path: {path:?}"),
+        ))
+    })?;
+
+    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl, optionally followed by `.zst`.
     let core = filename
+        .strip_suffix(".zst")
+        .unwrap_or(filename)
         .strip_prefix("rollout-")
         .and_then(|s| s.strip_suffix(".jsonl"))
         .ok_or_else(|| {
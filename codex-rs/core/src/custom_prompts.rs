@@ -70,6 +70,7 @@ pub async fn discover_prompts_in_excluding(
             content: body,
             description,
             argument_hint,
+            mcp: None,
         });
     }
     out.sort_by(|a, b| a.name.cmp(&b.name));
@@ -4,9 +4,11 @@ use crate::token_data::PlanType;
 use crate::truncate::truncate_middle;
 use codex_protocol::ConversationId;
 use codex_protocol::protocol::RateLimitSnapshot;
+use codex_protocol::protocol::SandboxDenial;
 use reqwest::StatusCode;
 use serde_json;
 use std::io;
+use std::path::PathBuf;
 use std::time::Duration;
 use thiserror::Error;
 use tokio::task::JoinError;
@@ -25,6 +27,13 @@ pub enum SandboxErr {
     )]
     Denied { output: Box<ExecToolCallOutput> },
 
+    /// The command tried to read paths listed in the policy's `deny_read`
+    #[error("sandbox denied read access to {}", display_paths(.paths))]
+    ReadDenied {
+        paths: Vec<PathBuf>,
+        output: Box<ExecToolCallOutput>,
+    },
+
     /// Error from linux seccomp filter setup
     #[cfg(target_os = "linux")]
     #[error("seccomp setup error")]
@@ -48,6 +57,26 @@ pub enum SandboxErr {
     LandlockRestrict,
 }
 
+impl SandboxErr {
+    /// What the sandbox blocked, when the command ran far enough to tell.
+    pub fn denials(&self) -> &[SandboxDenial] {
+        match self {
+            SandboxErr::Denied { output }
+            | SandboxErr::ReadDenied { output, .. }
+            | SandboxErr::Timeout { output } => &output.sandbox_denials,
+            _ => &[],
+        }
+    }
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 #[derive(Error, Debug)]
 pub enum CodexErr {
     /// Returned by ResponsesClient when the SSE stream disconnects or errors out **after** the HTTP
@@ -96,6 +125,10 @@ pub enum CodexErr {
     )]
     UsageNotIncluded,
 
+    /// The session reached its `session_budget_usd` and the user chose to stop.
+    #[error("Stopped: the session reached its cost budget.")]
+    CostBudgetReached,
+
     #[error("We're currently experiencing high demand, which may cause temporary errors.")]
     InternalServerError,
 
@@ -327,6 +360,10 @@ pub fn get_error_message_ui(e: &CodexErr) -> String {
                 }
             }
         }
+        CodexErr::Sandbox(SandboxErr::ReadDenied { paths, .. }) => format!(
+            "sandbox denied read access to {}; these paths are listed in `deny_read` and cannot be read by commands",
+            display_paths(paths)
+        ),
         // Timeouts are not sandbox errors from a UX perspective; present them plainly
         CodexErr::Sandbox(SandboxErr::Timeout { output }) => {
             format!(
@@ -384,6 +421,8 @@ mod tests {
             aggregated_output: StreamOutput::new("aggregate detail".to_string()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            resource_limit_exceeded: None,
+            sandbox_denials: Vec::new(),
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -400,6 +439,8 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            resource_limit_exceeded: None,
+            sandbox_denials: Vec::new(),
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -416,6 +457,8 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(8),
             timed_out: false,
+            resource_limit_exceeded: None,
+            sandbox_denials: Vec::new(),
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -432,6 +475,8 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(5),
             timed_out: false,
+            resource_limit_exceeded: None,
+            sandbox_denials: Vec::new(),
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
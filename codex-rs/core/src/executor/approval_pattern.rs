@@ -0,0 +1,297 @@
+use std::fmt;
+
+use wildmatch::WildMatchPattern;
+
+use crate::bash::parse_bash_lc_plain_commands;
+
+/// Token that, when it appears as the final element of a pattern, matches any
+/// number (including zero) of remaining arguments.
+const REST_WILDCARD: &str = "*";
+
+type TokenPattern = WildMatchPattern<'*', '?'>;
+
+/// An argv pattern the user approved for a project, such as `cargo test *`.
+///
+/// Each whitespace-separated token is matched against the argument in the same
+/// position using glob syntax (`*` and `?`). A trailing lone `*` matches any
+/// remaining arguments, which makes the pattern behave like an argv prefix.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ApprovalPattern {
+    tokens: Vec<String>,
+}
+
+impl ApprovalPattern {
+    /// Parses a pattern written with shell quoting rules. Returns `None` for
+    /// empty or unparsable input.
+    pub fn parse(pattern: &str) -> Option<Self> {
+        let tokens = shlex::split(pattern)?;
+        if tokens.is_empty() {
+            return None;
+        }
+        Some(Self { tokens })
+    }
+
+    /// Suggests a pattern covering `command` and its close variants: the
+    /// program, its subcommand when it has one, and any trailing arguments.
+    ///
+    /// `cargo test -p foo` becomes `cargo test *` and `ls` becomes `ls *`.
+    /// When a flag comes before any subcommand, as in `git -C dir push`, the
+    /// flag may change what the rest means, so only the exact command is
+    /// offered.
+    ///
+    /// Returns `None` unless `command` comes down to a single plain command
+    /// whose program does not run code or commands given as arguments: a
+    /// pattern such as `bash *`, `python3 *` or `sudo *` would cover anything.
+    pub fn for_command(command: &[String]) -> Option<Self> {
+        let command = match parse_bash_lc_plain_commands(command) {
+            Some(mut commands) if commands.len() == 1 => commands.remove(0),
+            Some(_) => return None,
+            None => command.to_vec(),
+        };
+
+        let program = command.first()?;
+        if runs_arguments(program) || has_glob(program) {
+            return None;
+        }
+        if command.get(1).is_some_and(|arg| arg.starts_with('-')) {
+            // Tokens are globs, so an exact pattern needs literal arguments.
+            if command.iter().any(|arg| has_glob(arg)) {
+                return None;
+            }
+            return Some(Self { tokens: command });
+        }
+
+        let mut tokens = vec![program.clone()];
+        if let Some(subcommand) = command.get(1).filter(|arg| is_subcommand(arg)) {
+            tokens.push(subcommand.clone());
+        }
+        tokens.push(REST_WILDCARD.to_string());
+        Some(Self { tokens })
+    }
+
+    /// Returns true when `command` is covered by this pattern. Commands
+    /// wrapped in `bash -lc` match when every plain command in the script
+    /// matches.
+    pub fn matches(&self, command: &[String]) -> bool {
+        if self.matches_argv(command) {
+            return true;
+        }
+        match parse_bash_lc_plain_commands(command) {
+            Some(commands) if !commands.is_empty() => {
+                commands.iter().all(|argv| self.matches_argv(argv))
+            }
+            _ => false,
+        }
+    }
+
+    fn matches_argv(&self, argv: &[String]) -> bool {
+        let (fixed, open_ended) = match self.tokens.split_last() {
+            Some((last, rest)) if last == REST_WILDCARD && !rest.is_empty() => (rest, true),
+            _ => (self.tokens.as_slice(), false),
+        };
+
+        if argv.len() < fixed.len() || (!open_ended && argv.len() != fixed.len()) {
+            return false;
+        }
+
+        fixed
+            .iter()
+            .zip(argv)
+            .all(|(pattern, arg)| TokenPattern::new(pattern).matches(arg))
+    }
+}
+
+impl fmt::Display for ApprovalPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rendered = self
+            .tokens
+            .iter()
+            .map(|token| {
+                // Only quote when needed so glob characters stay readable.
+                if token
+                    .chars()
+                    .any(|c| c.is_whitespace() || c == '\'' || c == '"')
+                {
+                    shlex::try_quote(token)
+                        .map(std::borrow::Cow::into_owned)
+                        .unwrap_or_else(|_| token.clone())
+                } else {
+                    token.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        f.write_str(&rendered)
+    }
+}
+
+/// Shells, interpreters and wrappers run whatever their arguments say, so no
+/// pattern is offered for them. Version suffixes such as `python3.12` are
+/// ignored.
+const RUNS_ARGUMENTS: &[&str] = &[
+    // Shells.
+    "bash",
+    "sh",
+    "zsh",
+    "dash",
+    "ksh",
+    "fish",
+    "csh",
+    "tcsh",
+    "pwsh",
+    "powershell",
+    // Interpreters.
+    "python",
+    "pypy",
+    "node",
+    "deno",
+    "bun",
+    "ruby",
+    "perl",
+    "php",
+    "lua",
+    "tclsh",
+    "osascript",
+    // Package runners.
+    "npx",
+    "bunx",
+    "pnpx",
+    "uvx",
+    "pipx",
+    // Wrappers that run another command.
+    "env",
+    "xargs",
+    "sudo",
+    "doas",
+    "su",
+    "nohup",
+    "nice",
+    "ionice",
+    "timeout",
+    "time",
+    "exec",
+    "eval",
+    "command",
+    "builtin",
+    "watch",
+    "chroot",
+    "setsid",
+    "stdbuf",
+    "strace",
+    "flock",
+];
+
+fn runs_arguments(program: &str) -> bool {
+    let name = program.rsplit('/').next().unwrap_or(program);
+    let name = name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    RUNS_ARGUMENTS.contains(&name)
+}
+
+fn has_glob(token: &str) -> bool {
+    token.contains(['*', '?', '['])
+}
+
+fn is_subcommand(arg: &str) -> bool {
+    !arg.is_empty()
+        && !arg.starts_with('-')
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn vec_str(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn trailing_wildcard_matches_any_remaining_args() {
+        let pattern = ApprovalPattern::parse("cargo test *").expect("pattern");
+        assert!(pattern.matches(&vec_str(&["cargo", "test"])));
+        assert!(pattern.matches(&vec_str(&["cargo", "test", "-p", "bar"])));
+        assert!(!pattern.matches(&vec_str(&["cargo", "build", "-p", "bar"])));
+        assert!(!pattern.matches(&vec_str(&["cargo"])));
+    }
+
+    #[test]
+    fn pattern_without_wildcard_requires_exact_length() {
+        let pattern = ApprovalPattern::parse("make lint").expect("pattern");
+        assert!(pattern.matches(&vec_str(&["make", "lint"])));
+        assert!(!pattern.matches(&vec_str(&["make", "lint", "fix"])));
+    }
+
+    #[test]
+    fn tokens_support_globs() {
+        let pattern = ApprovalPattern::parse("npm run test:*").expect("pattern");
+        assert!(pattern.matches(&vec_str(&["npm", "run", "test:unit"])));
+        assert!(!pattern.matches(&vec_str(&["npm", "run", "build"])));
+    }
+
+    #[test]
+    fn bash_lc_matches_when_all_commands_match() {
+        let pattern = ApprovalPattern::parse("cargo *").expect("pattern");
+        assert!(pattern.matches(&vec_str(&["bash", "-lc", "cargo fmt && cargo test -p foo"])));
+        assert!(!pattern.matches(&vec_str(&["bash", "-lc", "cargo test && rm -rf /"])));
+    }
+
+    fn suggested(args: &[&str]) -> Option<String> {
+        ApprovalPattern::for_command(&vec_str(args)).map(|pattern| pattern.to_string())
+    }
+
+    #[test]
+    fn for_command_keeps_program_and_subcommand() {
+        assert_eq!(
+            suggested(&["cargo", "test", "-p", "foo"]),
+            Some("cargo test *".to_string())
+        );
+        assert_eq!(suggested(&["ls"]), Some("ls *".to_string()));
+        assert_eq!(
+            suggested(&["bash", "-lc", "pnpm run lint"]),
+            Some("pnpm run *".to_string())
+        );
+    }
+
+    #[test]
+    fn for_command_offers_no_pattern_for_scripts() {
+        assert_eq!(suggested(&["bash", "-lc", "cargo fmt && cargo test"]), None);
+        assert_eq!(suggested(&["bash", "-lc", "echo $(whoami)"]), None);
+        assert_eq!(suggested(&["bash", "-c", "rm -rf /"]), None);
+        assert_eq!(suggested(&["/bin/sh", "script.sh"]), None);
+    }
+
+    #[test]
+    fn for_command_offers_no_pattern_for_interpreters_and_wrappers() {
+        assert_eq!(suggested(&["python3", "script.py"]), None);
+        assert_eq!(suggested(&["/usr/bin/python3.12", "-c", "print(1)"]), None);
+        assert_eq!(suggested(&["node", "-e", "process.exit(0)"]), None);
+        assert_eq!(suggested(&["env", "FOO=1", "rm", "-rf", "/"]), None);
+        assert_eq!(suggested(&["xargs", "rm"]), None);
+        assert_eq!(suggested(&["sudo", "apt", "install", "foo"]), None);
+        assert_eq!(suggested(&["bash", "-lc", "npx some-package"]), None);
+    }
+
+    #[test]
+    fn for_command_offers_exact_command_after_leading_flag() {
+        assert_eq!(
+            suggested(&["git", "-C", "dir", "push"]),
+            Some("git -C dir push".to_string())
+        );
+        assert_eq!(suggested(&["ls", "-la"]), Some("ls -la".to_string()));
+        assert_eq!(suggested(&["ls", "-la", "*.rs"]), None);
+
+        let pattern =
+            ApprovalPattern::for_command(&vec_str(&["git", "-C", "dir", "push"])).expect("pattern");
+        assert!(pattern.matches(&vec_str(&["git", "-C", "dir", "push"])));
+        assert!(!pattern.matches(&vec_str(&["git", "-C", "dir", "push", "--force"])));
+        assert!(!pattern.matches(&vec_str(&["git", "-C", "other", "push"])));
+    }
+
+    #[test]
+    fn empty_pattern_is_rejected() {
+        assert_eq!(ApprovalPattern::parse("   "), None);
+    }
+}
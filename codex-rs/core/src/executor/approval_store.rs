@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use tempfile::NamedTempFile;
+use toml_edit::DocumentMut;
+use tracing::warn;
+
+use super::approval_pattern::ApprovalPattern;
+use crate::git_info::resolve_root_git_project_for_trust;
+
+/// Sidecar file under `CODEX_HOME` holding per-project command approvals:
+///
+/// ```toml
+/// [projects."/path/to/repo"]
+/// allow = ["cargo test *", "npm run lint"]
+/// ```
+pub const COMMAND_APPROVALS_FILE: &str = "command_approvals.toml";
+
+#[derive(Deserialize, Debug, Default)]
+struct CommandApprovalsToml {
+    #[serde(default)]
+    projects: HashMap<String, ProjectCommandApprovalsToml>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct ProjectCommandApprovalsToml {
+    #[serde(default)]
+    allow: Vec<String>,
+}
+
+/// Reads and writes the approval patterns recorded for a single project.
+#[derive(Clone, Debug)]
+pub(crate) struct ProjectApprovalStore {
+    path: PathBuf,
+    project_key: String,
+}
+
+impl ProjectApprovalStore {
+    /// Resolves the project for `cwd` the same way project trust does: linked
+    /// worktrees and nested directories share the approvals of the main
+    /// repository root.
+    pub(crate) fn for_cwd(codex_home: &Path, cwd: &Path) -> Self {
+        let project_root = resolve_root_git_project_for_trust(cwd).unwrap_or_else(|| cwd.into());
+        Self::new(codex_home, &project_root)
+    }
+
+    pub(crate) fn new(codex_home: &Path, project_root: &Path) -> Self {
+        Self {
+            path: codex_home.join(COMMAND_APPROVALS_FILE),
+            project_key: project_root.to_string_lossy().to_string(),
+        }
+    }
+
+    /// Loads the patterns approved for this project. Missing or malformed
+    /// files yield no patterns.
+    pub(crate) fn load(&self) -> Vec<ApprovalPattern> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+            Err(err) => {
+                warn!("failed to read {}: {err}", self.path.display());
+                return Vec::new();
+            }
+        };
+        let parsed: CommandApprovalsToml = match toml::from_str(&contents) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                warn!("failed to parse {}: {err}", self.path.display());
+                return Vec::new();
+            }
+        };
+        parsed
+            .projects
+            .get(&self.project_key)
+            .map(|project| {
+                project
+                    .allow
+                    .iter()
+                    .filter_map(|pattern| ApprovalPattern::parse(pattern))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Appends `pattern` to this project's allow list, preserving any other
+    /// content in the file.
+    pub(crate) fn persist(&self, pattern: &ApprovalPattern) -> anyhow::Result<()> {
+        let mut doc = match std::fs::read_to_string(&self.path) {
+            Ok(s) => s.parse::<DocumentMut>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => DocumentMut::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let projects = doc
+            .entry("projects")
+            .or_insert_with(|| {
+                let mut tbl = toml_edit::Table::new();
+                tbl.set_implicit(true);
+                toml_edit::Item::Table(tbl)
+            })
+            .as_table_mut()
+            .ok_or_else(|| anyhow::anyhow!("`projects` is not a table"))?;
+        let project = projects
+            .entry(&self.project_key)
+            .or_insert_with(toml_edit::table)
+            .as_table_mut()
+            .ok_or_else(|| {
+                anyhow::anyhow!("project entry for {} is not a table", self.project_key)
+            })?;
+        let allow = project
+            .entry("allow")
+            .or_insert_with(|| toml_edit::value(toml_edit::Array::new()))
+            .as_array_mut()
+            .ok_or_else(|| anyhow::anyhow!("`allow` for {} is not an array", self.project_key))?;
+
+        let rendered = pattern.to_string();
+        if !allow.iter().any(|v| v.as_str() == Some(rendered.as_str())) {
+            allow.push(rendered);
+        }
+
+        let Some(dir) = self.path.parent() else {
+            return Err(anyhow::anyhow!("invalid approvals path"));
+        };
+        std::fs::create_dir_all(dir)?;
+        let tmp_file = NamedTempFile::new_in(dir)?;
+        std::fs::write(tmp_file.path(), doc.to_string())?;
+        tmp_file.persist(&self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn persist_round_trips_and_dedupes() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let store = ProjectApprovalStore::new(codex_home.path(), Path::new("/work/repo"));
+        assert_eq!(store.load(), Vec::new());
+
+        let pattern = ApprovalPattern::parse("cargo test *").expect("pattern");
+        store.persist(&pattern).expect("persist");
+        store.persist(&pattern).expect("persist again");
+
+        assert_eq!(store.load(), vec![pattern]);
+        let contents =
+            std::fs::read_to_string(codex_home.path().join(COMMAND_APPROVALS_FILE)).expect("read");
+        assert_eq!(
+            contents,
+            r#"[projects."/work/repo"]
+allow = ["cargo test *"]
+"#
+        );
+    }
+
+    #[test]
+    fn approvals_are_scoped_per_project() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let repo_a = ProjectApprovalStore::new(codex_home.path(), Path::new("/work/a"));
+        let repo_b = ProjectApprovalStore::new(codex_home.path(), Path::new("/work/b"));
+
+        let pattern = ApprovalPattern::parse("make *").expect("pattern");
+        repo_a.persist(&pattern).expect("persist");
+
+        assert_eq!(repo_a.load(), vec![pattern]);
+        assert_eq!(repo_b.load(), Vec::new());
+    }
+}
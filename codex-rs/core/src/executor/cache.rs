@@ -2,14 +2,46 @@ use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::Mutex;
 
+use tracing::warn;
+
+use super::approval_pattern::ApprovalPattern;
+use super::approval_store::ProjectApprovalStore;
+
 #[derive(Clone, Debug, Default)]
 /// Thread-safe store of user approvals so repeated commands can reuse
-/// previously granted trust.
+/// previously granted trust. Exact commands live for the session; patterns
+/// are additionally persisted for the project when a store is attached.
 pub(crate) struct ApprovalCache {
     inner: Arc<Mutex<HashSet<Vec<String>>>>,
+    patterns: Arc<Mutex<Vec<ApprovalPattern>>>,
+    store: Option<ProjectApprovalStore>,
+}
+
+/// Point-in-time view of the approvals consulted when selecting a sandbox.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ApprovalSnapshot {
+    pub(crate) commands: HashSet<Vec<String>>,
+    pub(crate) patterns: Vec<ApprovalPattern>,
+}
+
+impl ApprovalSnapshot {
+    pub(crate) fn matches_pattern(&self, command: &[String]) -> bool {
+        self.patterns.iter().any(|pattern| pattern.matches(command))
+    }
 }
 
 impl ApprovalCache {
+    /// Creates a cache seeded with the patterns previously approved for the
+    /// project backing `store`.
+    pub(crate) fn with_store(store: ProjectApprovalStore) -> Self {
+        let patterns = store.load();
+        Self {
+            inner: Arc::default(),
+            patterns: Arc::new(Mutex::new(patterns)),
+            store: Some(store),
+        }
+    }
+
     pub(crate) fn insert(&self, command: Vec<String>) {
         if command.is_empty() {
             return;
@@ -19,8 +51,26 @@ impl ApprovalCache {
         }
     }
 
-    pub(crate) fn snapshot(&self) -> HashSet<Vec<String>> {
-        self.inner.lock().map(|g| g.clone()).unwrap_or_default()
+    /// Records `pattern` for the rest of the session and persists it for the
+    /// project so future sessions start with it approved.
+    pub(crate) fn insert_pattern(&self, pattern: ApprovalPattern) {
+        if let Some(store) = &self.store
+            && let Err(err) = store.persist(&pattern)
+        {
+            warn!("failed to persist approval pattern `{pattern}`: {err:#}");
+        }
+        if let Ok(mut guard) = self.patterns.lock()
+            && !guard.contains(&pattern)
+        {
+            guard.push(pattern);
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> ApprovalSnapshot {
+        ApprovalSnapshot {
+            commands: self.inner.lock().map(|g| g.clone()).unwrap_or_default(),
+            patterns: self.patterns.lock().map(|g| g.clone()).unwrap_or_default(),
+        }
     }
 }
 
@@ -28,6 +78,7 @@ impl ApprovalCache {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use std::path::Path;
 
     #[test]
     fn insert_ignores_empty_and_dedupes() {
@@ -35,17 +86,38 @@ mod tests {
 
         // Empty should be ignored
         cache.insert(vec![]);
-        assert!(cache.snapshot().is_empty());
+        assert!(cache.snapshot().commands.is_empty());
 
         // Insert a command and verify snapshot contains it
         let cmd = vec!["foo".to_string(), "bar".to_string()];
         cache.insert(cmd.clone());
-        let snap1 = cache.snapshot();
+        let snap1 = cache.snapshot().commands;
         assert!(snap1.contains(&cmd));
 
         // Reinserting should not create duplicates
         cache.insert(cmd);
-        let snap2 = cache.snapshot();
+        let snap2 = cache.snapshot().commands;
         assert_eq!(snap1, snap2);
     }
+
+    #[test]
+    fn patterns_persist_and_reload_for_project() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let store = ProjectApprovalStore::new(codex_home.path(), Path::new("/work/repo"));
+        let cache = ApprovalCache::with_store(store.clone());
+        let command = vec!["cargo".to_string(), "test".to_string(), "-p".to_string()];
+        assert!(!cache.snapshot().matches_pattern(&command));
+
+        cache.insert_pattern(ApprovalPattern::for_command(&command).expect("pattern"));
+        assert!(cache.snapshot().matches_pattern(&command));
+
+        // A fresh cache for the same project picks the pattern back up.
+        let reloaded = ApprovalCache::with_store(store);
+        assert!(reloaded.snapshot().matches_pattern(&[
+            "cargo".to_string(),
+            "test".to_string(),
+            "-p".to_string(),
+            "bar".to_string(),
+        ]));
+    }
 }
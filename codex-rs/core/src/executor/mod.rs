@@ -1,8 +1,13 @@
+mod approval_pattern;
+mod approval_store;
 mod backends;
 mod cache;
 mod runner;
 mod sandbox;
 
+pub use approval_pattern::ApprovalPattern;
+pub use approval_store::COMMAND_APPROVALS_FILE;
+pub(crate) use approval_store::ProjectApprovalStore;
 pub(crate) use backends::ExecutionMode;
 pub(crate) use runner::ExecutionRequest;
 pub(crate) use runner::Executor;
@@ -46,6 +51,7 @@ pub(crate) mod linkers {
 pub mod errors {
     use crate::error::CodexErr;
     use crate::function_tool::FunctionCallError;
+    use codex_protocol::protocol::SandboxDenial;
     use thiserror::Error;
 
     #[derive(Debug, Error)]
@@ -54,6 +60,13 @@ pub mod errors {
         Function(#[from] FunctionCallError),
         #[error(transparent)]
         Codex(#[from] CodexErr),
+        /// The sandbox blocked the command and it was not run again without
+        /// it. The message is reported to the model like a rejection.
+        #[error("{message}")]
+        SandboxRejected {
+            message: String,
+            denials: Vec<SandboxDenial>,
+        },
     }
 
     impl ExecError {
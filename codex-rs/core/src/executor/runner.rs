@@ -3,10 +3,13 @@ use std::sync::Arc;
 use std::sync::RwLock;
 use std::time::Duration;
 
+use super::approval_pattern::ApprovalPattern;
+use super::approval_store::ProjectApprovalStore;
 use super::backends::ExecutionMode;
 use super::backends::backend_for_mode;
 use super::cache::ApprovalCache;
 use crate::codex::Session;
+use crate::config_types::ResourceLimits;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::error::get_error_message_ui;
@@ -19,11 +22,13 @@ use crate::exec::process_exec_tool_call;
 use crate::executor::errors::ExecError;
 use crate::executor::sandbox::select_sandbox;
 use crate::function_tool::FunctionCallError;
+use crate::landlock::LinuxSandboxOptions;
 use crate::protocol::AskForApproval;
 use crate::protocol::ReviewDecision;
 use crate::protocol::SandboxPolicy;
 use crate::shell;
 use crate::tools::context::ExecCommandContext;
+use codex_execpolicy::Policy;
 use codex_otel::otel_event_manager::ToolDecisionSource;
 
 #[derive(Clone, Debug)]
@@ -31,6 +36,9 @@ pub(crate) struct ExecutorConfig {
     pub(crate) sandbox_policy: SandboxPolicy,
     pub(crate) sandbox_cwd: PathBuf,
     codex_linux_sandbox_exe: Option<PathBuf>,
+    pub(crate) exec_policy: Option<Arc<Policy>>,
+    resource_limits: ResourceLimits,
+    linux_sandbox: LinuxSandboxOptions,
 }
 
 impl ExecutorConfig {
@@ -43,8 +51,30 @@ impl ExecutorConfig {
             sandbox_policy,
             sandbox_cwd,
             codex_linux_sandbox_exe,
+            exec_policy: None,
+            resource_limits: ResourceLimits::default(),
+            linux_sandbox: LinuxSandboxOptions::default(),
         }
     }
+
+    /// Consults `exec_policy` (the default policy merged with the user's
+    /// policy files) when deciding whether a command is known to be safe.
+    pub(crate) fn with_exec_policy(mut self, exec_policy: Option<Arc<Policy>>) -> Self {
+        self.exec_policy = exec_policy;
+        self
+    }
+
+    /// Applies `resource_limits` to every command, sandboxed or not.
+    pub(crate) fn with_resource_limits(mut self, resource_limits: ResourceLimits) -> Self {
+        self.resource_limits = resource_limits;
+        self
+    }
+
+    /// Runs commands in the Linux sandbox with `linux_sandbox`.
+    pub(crate) fn with_linux_sandbox(mut self, linux_sandbox: LinuxSandboxOptions) -> Self {
+        self.linux_sandbox = linux_sandbox;
+        self
+    }
 }
 
 /// Coordinates sandbox selection, backend-specific preparation, and command
@@ -62,6 +92,13 @@ impl Executor {
         }
     }
 
+    /// Seeds the executor with the command patterns persisted for a project
+    /// and records new "always allow" approvals back to the same store.
+    pub(crate) fn with_approval_store(mut self, store: ProjectApprovalStore) -> Self {
+        self.approval_cache = ApprovalCache::with_store(store);
+        self
+    }
+
     /// Updates the sandbox policy and working directory used for future
     /// executions without recreating the executor.
     pub(crate) fn update_environment(&self, sandbox_policy: SandboxPolicy, sandbox_cwd: PathBuf) {
@@ -119,6 +156,11 @@ impl Executor {
         if sandbox_decision.record_session_approval {
             self.approval_cache.insert(request.approval_command.clone());
         }
+        if sandbox_decision.record_project_approval
+            && let Some(pattern) = ApprovalPattern::for_command(&request.approval_command)
+        {
+            self.approval_cache.insert_pattern(pattern);
+        }
 
         // Step 4: Launch the command within the chosen sandbox.
         let first_attempt = self
@@ -136,6 +178,11 @@ impl Executor {
             Err(CodexErr::Sandbox(SandboxErr::Timeout { output })) => {
                 Err(CodexErr::Sandbox(SandboxErr::Timeout { output }).into())
             }
+            // Retrying without the sandbox would hand the denied paths to the
+            // command, so never offer it.
+            Err(CodexErr::Sandbox(error @ SandboxErr::ReadDenied { .. })) => {
+                Err(sandbox_rejection(error))
+            }
             Err(CodexErr::Sandbox(error)) => {
                 if sandbox_decision.escalate_on_failure {
                     self.retry_without_sandbox(
@@ -148,8 +195,7 @@ impl Executor {
                     )
                     .await
                 } else {
-                    let message = sandbox_failure_message(error);
-                    Err(ExecError::rejection(message))
+                    Err(sandbox_rejection(error))
                 }
             }
             Err(err) => Err(err.into()),
@@ -173,6 +219,7 @@ impl Executor {
                 format!("Execution failed: {sandbox_error}"),
             )
             .await;
+        let sandbox_denials = sandbox_error.denials().to_vec();
         let decision = session
             .request_command_approval(
                 context.sub_id.to_string(),
@@ -180,6 +227,7 @@ impl Executor {
                 request.approval_command.clone(),
                 request.params.cwd.clone(),
                 Some("command failed; retry without sandbox?".to_string()),
+                sandbox_denials.clone(),
             )
             .await;
 
@@ -190,15 +238,25 @@ impl Executor {
             ToolDecisionSource::User,
         );
         match decision {
-            ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
-                if matches!(decision, ReviewDecision::ApprovedForSession) {
+            ReviewDecision::Approved
+            | ReviewDecision::ApprovedForSession
+            | ReviewDecision::ApprovedForProject => {
+                if matches!(
+                    decision,
+                    ReviewDecision::ApprovedForSession | ReviewDecision::ApprovedForProject
+                ) {
                     self.approval_cache.insert(request.approval_command.clone());
                 }
+                if matches!(decision, ReviewDecision::ApprovedForProject)
+                    && let Some(pattern) = ApprovalPattern::for_command(&request.approval_command)
+                {
+                    self.approval_cache.insert_pattern(pattern);
+                }
                 session
                     .notify_background_event(&context.sub_id, "retrying command without sandbox")
                     .await;
 
-                let retry_output = self
+                let mut retry_output = self
                     .spawn(
                         request.params.clone(),
                         SandboxType::None,
@@ -206,6 +264,9 @@ impl Executor {
                         stdout_stream,
                     )
                     .await?;
+                // Keep what made the sandboxed attempt fail, so that the user
+                // can widen the sandbox instead next time.
+                retry_output.sandbox_denials = sandbox_denials;
 
                 Ok(retry_output)
             }
@@ -228,6 +289,8 @@ impl Executor {
             &config.sandbox_policy,
             &config.sandbox_cwd,
             &config.codex_linux_sandbox_exe,
+            &config.resource_limits,
+            config.linux_sandbox,
             stdout_stream,
         )
         .await
@@ -259,6 +322,14 @@ fn sandbox_failure_message(error: SandboxErr) -> String {
     format!("failed in sandbox: {friendly}")
 }
 
+fn sandbox_rejection(error: SandboxErr) -> ExecError {
+    let denials = error.denials().to_vec();
+    ExecError::SandboxRejected {
+        message: sandbox_failure_message(error),
+        denials,
+    }
+}
+
 pub(crate) struct ExecutionRequest {
     pub params: ExecParams,
     pub approval_command: Vec<String>,
@@ -305,6 +376,10 @@ pub(crate) fn normalize_exec_result(
                 ExecError::Codex(e) => get_error_message_ui(e),
                 err => err.to_string(),
             };
+            let sandbox_denials = match err {
+                ExecError::SandboxRejected { denials, .. } => denials.clone(),
+                _ => Vec::new(),
+            };
             let synthetic = ExecToolCallOutput {
                 exit_code: -1,
                 stdout: StreamOutput::new(String::new()),
@@ -312,6 +387,8 @@ pub(crate) fn normalize_exec_result(
                 aggregated_output: StreamOutput::new(message),
                 duration: Duration::default(),
                 timed_out: false,
+                resource_limit_exceeded: None,
+                sandbox_denials,
             };
             NormalizedExecOutput {
                 borrowed: None,
@@ -328,6 +405,8 @@ mod tests {
     use crate::error::EnvVarError;
     use crate::error::SandboxErr;
     use crate::exec::StreamOutput;
+    use crate::protocol::SandboxDenial;
+    use crate::protocol::SandboxDeniedOperation;
     use pretty_assertions::assert_eq;
 
     fn make_output(text: &str) -> ExecToolCallOutput {
@@ -338,6 +417,8 @@ mod tests {
             aggregated_output: StreamOutput::new(text.to_string()),
             duration: Duration::from_millis(123),
             timed_out: false,
+            resource_limit_exceeded: None,
+            sandbox_denials: Vec::new(),
         }
     }
 
@@ -372,6 +453,8 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            resource_limit_exceeded: None,
+            sandbox_denials: Vec::new(),
         };
         let err = SandboxErr::Denied {
             output: Box::new(output),
@@ -389,6 +472,8 @@ mod tests {
             aggregated_output: StreamOutput::new("aggregate text".to_string()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            resource_limit_exceeded: None,
+            sandbox_denials: Vec::new(),
         };
         let err = SandboxErr::Denied {
             output: Box::new(output),
@@ -405,6 +490,29 @@ mod tests {
         assert_eq!(normalized.event_output().aggregated_output.text, "boom");
     }
 
+    #[test]
+    fn normalize_sandbox_rejection_keeps_denials() {
+        let denial = SandboxDenial {
+            operation: SandboxDeniedOperation::Write,
+            target: "/home/user/.cache/tool".to_string(),
+        };
+        let mut output = make_output("touch: cannot touch: Read-only file system");
+        output.sandbox_denials = vec![denial.clone()];
+        let err = sandbox_rejection(SandboxErr::Denied {
+            output: Box::new(output),
+        });
+        let result: Result<ExecToolCallOutput, ExecError> = Err(err);
+        let normalized = normalize_exec_result(&result);
+        let event_output = normalized.event_output();
+        assert_eq!(event_output.sandbox_denials, vec![denial]);
+        assert!(
+            event_output
+                .aggregated_output
+                .text
+                .starts_with("failed in sandbox: ")
+        );
+    }
+
     #[test]
     fn normalize_codex_error_synthesizes_user_message() {
         // Use a simple EnvVar error which formats to a clear message
@@ -4,6 +4,7 @@ use crate::exec::SandboxType;
 use crate::executor::ExecutionMode;
 use crate::executor::ExecutionRequest;
 use crate::executor::ExecutorConfig;
+use crate::executor::cache::ApprovalSnapshot;
 use crate::executor::errors::ExecError;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_command_safety;
@@ -12,7 +13,6 @@ use codex_otel::otel_event_manager::OtelEventManager;
 use codex_otel::otel_event_manager::ToolDecisionSource;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
-use std::collections::HashSet;
 
 /// Sandbox placement options selected for an execution run, including whether
 /// to escalate after failures and whether approvals should persist.
@@ -20,6 +20,7 @@ pub(crate) struct SandboxDecision {
     pub(crate) initial_sandbox: SandboxType,
     pub(crate) escalate_on_failure: bool,
     pub(crate) record_session_approval: bool,
+    pub(crate) record_project_approval: bool,
 }
 
 impl SandboxDecision {
@@ -28,6 +29,7 @@ impl SandboxDecision {
             initial_sandbox: sandbox,
             escalate_on_failure,
             record_session_approval: false,
+            record_project_approval: false,
         }
     }
 
@@ -36,6 +38,14 @@ impl SandboxDecision {
             initial_sandbox: SandboxType::None,
             escalate_on_failure: false,
             record_session_approval,
+            record_project_approval: false,
+        }
+    }
+
+    fn user_override_for_project() -> Self {
+        Self {
+            record_project_approval: true,
+            ..Self::user_override(true)
         }
     }
 }
@@ -56,7 +66,7 @@ fn should_escalate_on_failure(approval: AskForApproval, sandbox: SandboxType) ->
 pub async fn select_sandbox(
     request: &ExecutionRequest,
     approval_policy: AskForApproval,
-    approval_cache: HashSet<Vec<String>>,
+    approval_cache: ApprovalSnapshot,
     config: &ExecutorConfig,
     session: &Session,
     sub_id: &str,
@@ -87,7 +97,7 @@ pub async fn select_sandbox(
 async fn select_shell_sandbox(
     request: &ExecutionRequest,
     approval_policy: AskForApproval,
-    approved_snapshot: HashSet<Vec<String>>,
+    approved_snapshot: ApprovalSnapshot,
     config: &ExecutorConfig,
     session: &Session,
     sub_id: &str,
@@ -100,11 +110,20 @@ async fn select_shell_sandbox(
         request.approval_command.clone()
     };
 
+    // Commands covered by a persisted project pattern are treated exactly like
+    // commands the user already approved for this session.
+    let matches_pattern = approved_snapshot.matches_pattern(&command_for_safety);
+    let mut approved = approved_snapshot.commands;
+    if matches_pattern {
+        approved.insert(command_for_safety.clone());
+    }
+
     let safety = assess_command_safety(
         &command_for_safety,
         approval_policy,
         &config.sandbox_policy,
-        &approved_snapshot,
+        &approved,
+        config.exec_policy.as_deref(),
         request.params.with_escalated_permissions.unwrap_or(false),
     );
 
@@ -136,6 +155,7 @@ async fn select_shell_sandbox(
                     request.approval_command.clone(),
                     request.params.cwd.clone(),
                     request.params.justification.clone(),
+                    Vec::new(),
                 )
                 .await;
 
@@ -148,6 +168,9 @@ async fn select_shell_sandbox(
             match decision {
                 ReviewDecision::Approved => Ok(SandboxDecision::user_override(false)),
                 ReviewDecision::ApprovedForSession => Ok(SandboxDecision::user_override(true)),
+                ReviewDecision::ApprovedForProject => {
+                    Ok(SandboxDecision::user_override_for_project())
+                }
                 ReviewDecision::Denied | ReviewDecision::Abort => {
                     Err(ExecError::rejection("exec command rejected by user"))
                 }
@@ -192,6 +215,7 @@ mod tests {
     use super::*;
     use crate::codex::make_session_and_context;
     use crate::exec::ExecParams;
+    use crate::executor::ApprovalPattern;
     use crate::function_tool::FunctionCallError;
     use crate::protocol::SandboxPolicy;
     use codex_apply_patch::ApplyPatchAction;
@@ -207,7 +231,11 @@ mod tests {
             action,
             user_explicitly_approved_this_action: true,
         };
-        let cfg = ExecutorConfig::new(SandboxPolicy::ReadOnly, std::env::temp_dir(), None);
+        let cfg = ExecutorConfig::new(
+            SandboxPolicy::new_read_only_policy(),
+            std::env::temp_dir(),
+            None,
+        );
         let request = ExecutionRequest {
             params: ExecParams {
                 command: vec!["apply_patch".into()],
@@ -294,7 +322,11 @@ mod tests {
             action,
             user_explicitly_approved_this_action: false,
         };
-        let cfg = ExecutorConfig::new(SandboxPolicy::ReadOnly, std::env::temp_dir(), None);
+        let cfg = ExecutorConfig::new(
+            SandboxPolicy::new_read_only_policy(),
+            std::env::temp_dir(),
+            None,
+        );
         let request = ExecutionRequest {
             params: ExecParams {
                 command: vec!["apply_patch".into()],
@@ -365,11 +397,58 @@ mod tests {
         assert_eq!(decision.escalate_on_failure, false);
     }
 
+    #[tokio::test]
+    async fn select_shell_autoapproves_command_matching_project_pattern() {
+        let (session, ctx) = make_session_and_context();
+        let cfg = ExecutorConfig::new(
+            SandboxPolicy::new_read_only_policy(),
+            std::env::temp_dir(),
+            None,
+        );
+        let request = ExecutionRequest {
+            params: ExecParams {
+                command: vec!["some-unknown".into(), "--flag".into()],
+                cwd: std::env::temp_dir(),
+                timeout_ms: None,
+                env: std::collections::HashMap::new(),
+                with_escalated_permissions: None,
+                justification: None,
+            },
+            approval_command: vec!["some-unknown".into(), "--flag".into()],
+            mode: ExecutionMode::Shell,
+            stdout_stream: None,
+            use_shell_profile: false,
+        };
+        let approvals = ApprovalSnapshot {
+            commands: Default::default(),
+            patterns: vec![ApprovalPattern::parse("some-unknown *").expect("pattern")],
+        };
+        let otel_event_manager = ctx.client.get_otel_event_manager();
+        let decision = select_sandbox(
+            &request,
+            AskForApproval::UnlessTrusted,
+            approvals,
+            &cfg,
+            &session,
+            "sub",
+            "call",
+            &otel_event_manager,
+        )
+        .await
+        .expect("ok");
+        assert_eq!(decision.initial_sandbox, SandboxType::None);
+        assert_eq!(decision.record_session_approval, true);
+    }
+
     #[cfg(any(target_os = "macos", target_os = "linux"))]
     #[tokio::test]
     async fn select_shell_escalates_on_failure_with_platform_sandbox() {
         let (session, ctx) = make_session_and_context();
-        let cfg = ExecutorConfig::new(SandboxPolicy::ReadOnly, std::env::temp_dir(), None);
+        let cfg = ExecutorConfig::new(
+            SandboxPolicy::new_read_only_policy(),
+            std::env::temp_dir(),
+            None,
+        );
         let request = ExecutionRequest {
             params: ExecParams {
                 // Unknown command => untrusted but not flagged dangerous
@@ -0,0 +1,587 @@
+//! User-configured commands that run before and after each tool call.
+//!
+//! Hooks are declared in `config.toml` under `[[hooks.pre_tool_use]]` and
+//! `[[hooks.post_tool_use]]`. Each hook receives a JSON description of the
+//! tool call on stdin and communicates back through its exit status:
+//!
+//! * `0` – carry on. Anything written to stdout is passed to the model
+//!   alongside the tool output.
+//! * `2` – a `pre_tool_use` hook blocks the call; a `post_tool_use` hook marks
+//!   the call as failed. Stdout (or stderr, when stdout is empty) is passed to
+//!   the model as the reason.
+//! * anything else – the hook itself failed, as it does when it cannot be
+//!   started or times out. A failing `pre_tool_use` hook blocks the call:
+//!   hooks that enforce policy fail closed. A failing `post_tool_use` hook is
+//!   logged and the call proceeds as if the hook had not run.
+//!
+//! Shell commands that invoke `apply_patch` also run the hooks whose matcher
+//! matches `apply_patch`, so a patch cannot bypass them by going through the
+//! shell.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Output;
+use std::process::Stdio;
+use std::time::Duration;
+
+use codex_apply_patch::MaybeApplyPatch;
+use codex_apply_patch::maybe_parse_apply_patch;
+use mcp_types::ContentBlock;
+use mcp_types::TextContent;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::debug;
+use tracing::warn;
+use wildmatch::WildMatchPattern;
+
+use crate::config_types::HookConfig;
+use crate::config_types::Hooks;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Matcher name that also selects shell commands running `apply_patch`.
+const APPLY_PATCH_TOOL_NAME: &str = "apply_patch";
+
+/// Exit status a hook uses to block (pre) or fail (post) a tool call.
+const BLOCKING_EXIT_CODE: i32 = 2;
+
+#[derive(Debug, Default)]
+pub(crate) struct HookRunner {
+    pre_tool_use: Vec<Hook>,
+    post_tool_use: Vec<Hook>,
+}
+
+#[derive(Debug)]
+struct Hook {
+    matcher: Option<WildMatchPattern<'*', '?'>>,
+    command: Vec<String>,
+    timeout: Duration,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum HookEvent {
+    PreToolUse,
+    PostToolUse,
+}
+
+/// The tool call as presented to hooks on stdin.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct HookCall {
+    session_id: String,
+    call_id: String,
+    tool_name: String,
+    cwd: PathBuf,
+    tool_input: Value,
+    /// Set when the call is a shell command that runs `apply_patch`.
+    #[serde(skip)]
+    invokes_apply_patch: bool,
+}
+
+#[derive(Serialize)]
+struct HookInput<'a> {
+    hook_event_name: HookEvent,
+    #[serde(flatten)]
+    call: &'a HookCall,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_output: Option<Value>,
+}
+
+/// Combined result of running every hook that matched a tool call.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct HookOutcome {
+    /// Messages for the model, in the order the hooks produced them.
+    pub(crate) messages: Vec<String>,
+    /// Set when a hook exited with the blocking status. Remaining hooks are
+    /// skipped.
+    pub(crate) blocked: Option<String>,
+}
+
+impl HookRunner {
+    pub(crate) fn new(hooks: &Hooks) -> Self {
+        Self {
+            pre_tool_use: hooks.pre_tool_use.iter().filter_map(Hook::new).collect(),
+            post_tool_use: hooks.post_tool_use.iter().filter_map(Hook::new).collect(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pre_tool_use.is_empty() && self.post_tool_use.is_empty()
+    }
+
+    pub(crate) async fn run_pre_tool_use(&self, call: &HookCall) -> HookOutcome {
+        let input = HookInput {
+            hook_event_name: HookEvent::PreToolUse,
+            call,
+            tool_output: None,
+        };
+        run_hooks(&self.pre_tool_use, &input).await
+    }
+
+    pub(crate) async fn run_post_tool_use(
+        &self,
+        call: &HookCall,
+        tool_output: Value,
+    ) -> HookOutcome {
+        let input = HookInput {
+            hook_event_name: HookEvent::PostToolUse,
+            call,
+            tool_output: Some(tool_output),
+        };
+        run_hooks(&self.post_tool_use, &input).await
+    }
+}
+
+impl HookCall {
+    pub(crate) fn new(invocation: &ToolInvocation) -> Self {
+        let tool_input = tool_input(&invocation.payload);
+        Self {
+            session_id: invocation.session.conversation_id().to_string(),
+            call_id: invocation.call_id.clone(),
+            tool_name: invocation.tool_name.clone(),
+            cwd: invocation.turn.cwd.clone(),
+            invokes_apply_patch: invokes_apply_patch(&tool_input),
+            tool_input,
+        }
+    }
+}
+
+impl Hook {
+    fn new(config: &HookConfig) -> Option<Self> {
+        if config.command.is_empty() {
+            warn!("ignoring hook with an empty command");
+            return None;
+        }
+        Some(Self {
+            matcher: config.matcher.as_deref().map(WildMatchPattern::new),
+            command: config.command.clone(),
+            timeout: config
+                .timeout_sec
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_HOOK_TIMEOUT),
+        })
+    }
+
+    fn matches(&self, call: &HookCall) -> bool {
+        self.matcher.as_ref().is_none_or(|matcher| {
+            matcher.matches(&call.tool_name)
+                || (call.invokes_apply_patch && matcher.matches(APPLY_PATCH_TOOL_NAME))
+        })
+    }
+
+    async fn run(&self, input: &[u8], cwd: &Path) -> io::Result<Output> {
+        let mut child = Command::new(&self.command[0])
+            .args(&self.command[1..])
+            .current_dir(cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        // Write the input while draining the output, so a hook that does not
+        // read stdin cannot stall either side past the timeout.
+        let stdin = child.stdin.take();
+        let write_input = async move {
+            if let Some(mut stdin) = stdin {
+                // Hooks are free to exit without reading their input.
+                if let Err(e) = stdin.write_all(input).await {
+                    debug!("failed to write hook input: {e}");
+                }
+            }
+        };
+        let run = async {
+            let ((), output) = tokio::join!(write_input, child.wait_with_output());
+            output
+        };
+
+        tokio::time::timeout(self.timeout, run).await.map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("timed out after {}s", self.timeout.as_secs()),
+            )
+        })?
+    }
+}
+
+async fn run_hooks(hooks: &[Hook], input: &HookInput<'_>) -> HookOutcome {
+    let mut outcome = HookOutcome::default();
+    let matching: Vec<&Hook> = hooks
+        .iter()
+        .filter(|hook| hook.matches(input.call))
+        .collect();
+    if matching.is_empty() {
+        return outcome;
+    }
+
+    let bytes = match serde_json::to_vec(input) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("failed to serialize hook input: {e}");
+            return outcome;
+        }
+    };
+
+    for hook in matching {
+        let output = match hook.run(&bytes, &input.call.cwd).await {
+            Ok(output) => output,
+            Err(e) => {
+                let failure = format!("hook `{}` failed: {e}", hook.command[0]);
+                warn!("{failure}");
+                if fails_closed(input.hook_event_name) {
+                    outcome.blocked = Some(failure);
+                    break;
+                }
+                continue;
+            }
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        match output.status.code() {
+            Some(0) => {
+                if !stdout.is_empty() {
+                    outcome.messages.push(stdout);
+                }
+            }
+            Some(BLOCKING_EXIT_CODE) => {
+                let reason = if stdout.is_empty() {
+                    String::from_utf8_lossy(&output.stderr).trim().to_string()
+                } else {
+                    stdout
+                };
+                outcome.blocked = Some(reason);
+                break;
+            }
+            _ => {
+                let failure = format!(
+                    "hook `{}` exited with {}: {}",
+                    hook.command[0],
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                warn!("{failure}");
+                if fails_closed(input.hook_event_name) {
+                    outcome.blocked = Some(failure);
+                    break;
+                }
+            }
+        }
+    }
+
+    outcome
+}
+
+/// Whether a hook that fails to run blocks the call. `pre_tool_use` hooks
+/// guard the call, so they fail closed.
+fn fails_closed(event: HookEvent) -> bool {
+    matches!(event, HookEvent::PreToolUse)
+}
+
+/// Whether `tool_input` is a shell command (`{"command": [...]}`) that runs
+/// `apply_patch`.
+fn invokes_apply_patch(tool_input: &Value) -> bool {
+    let Some(command) = tool_input.get("command").and_then(Value::as_array) else {
+        return false;
+    };
+    let Some(argv) = command
+        .iter()
+        .map(|arg| arg.as_str().map(str::to_string))
+        .collect::<Option<Vec<String>>>()
+    else {
+        return false;
+    };
+    !matches!(
+        maybe_parse_apply_patch(&argv),
+        MaybeApplyPatch::NotApplyPatch
+    )
+}
+
+/// Best-effort JSON view of the tool arguments. Arguments that are not valid
+/// JSON are passed through as a string.
+fn tool_input(payload: &ToolPayload) -> Value {
+    let parse =
+        |raw: &str| serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+    match payload {
+        ToolPayload::Function { arguments } | ToolPayload::UnifiedExec { arguments } => {
+            parse(arguments)
+        }
+        ToolPayload::Custom { input } => Value::String(input.clone()),
+        ToolPayload::LocalShell { params } => json!({
+            "command": params.command,
+            "workdir": params.workdir,
+            "timeout_ms": params.timeout_ms,
+        }),
+        ToolPayload::Mcp { raw_arguments, .. } => parse(raw_arguments),
+    }
+}
+
+/// JSON view of a tool result, passed to `post_tool_use` hooks.
+pub(crate) fn tool_output_json(output: &ToolOutput) -> Value {
+    match output {
+        ToolOutput::Function { content, success } => json!({
+            "content": content,
+            "success": success.unwrap_or(true),
+        }),
+        ToolOutput::Mcp { result: Ok(result) } => {
+            serde_json::to_value(result).unwrap_or(Value::Null)
+        }
+        ToolOutput::Mcp { result: Err(err) } => json!({ "error": err }),
+    }
+}
+
+/// Appends hook messages to a tool result. When `failed` is set the result is
+/// also marked as unsuccessful.
+pub(crate) fn apply_to_output(output: &mut ToolOutput, messages: &[String], failed: bool) {
+    match output {
+        ToolOutput::Function { content, success } => {
+            append_messages(content, messages);
+            if failed {
+                *success = Some(false);
+            }
+        }
+        ToolOutput::Mcp { result: Ok(result) } => {
+            result.content.extend(messages.iter().map(|message| {
+                ContentBlock::TextContent(TextContent {
+                    annotations: None,
+                    text: format_message(message),
+                    r#type: "text".to_string(),
+                })
+            }));
+            if failed {
+                result.is_error = Some(true);
+            }
+        }
+        ToolOutput::Mcp { result: Err(err) } => append_messages(err, messages),
+    }
+}
+
+pub(crate) fn append_messages(text: &mut String, messages: &[String]) {
+    for message in messages {
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str(&format_message(message));
+    }
+}
+
+fn format_message(message: &str) -> String {
+    format!("[hook] {message}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn hook(matcher: Option<&str>, script: &str) -> HookConfig {
+        HookConfig {
+            matcher: matcher.map(str::to_string),
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            timeout_sec: None,
+        }
+    }
+
+    fn call(tool_name: &str) -> HookCall {
+        HookCall {
+            session_id: "session".to_string(),
+            call_id: "call-1".to_string(),
+            tool_name: tool_name.to_string(),
+            cwd: std::env::temp_dir(),
+            tool_input: json!({ "command": ["echo", "hi"] }),
+            invokes_apply_patch: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn exit_zero_stdout_becomes_message() {
+        let runner = HookRunner::new(&Hooks {
+            pre_tool_use: vec![hook(None, "echo looks fine")],
+            post_tool_use: Vec::new(),
+        });
+
+        let outcome = runner.run_pre_tool_use(&call("shell")).await;
+
+        assert_eq!(
+            outcome,
+            HookOutcome {
+                messages: vec!["looks fine".to_string()],
+                blocked: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn exit_two_blocks_and_skips_remaining_hooks() {
+        let runner = HookRunner::new(&Hooks {
+            pre_tool_use: vec![
+                hook(None, "echo 'no shell today' >&2; exit 2"),
+                hook(None, "echo unreachable"),
+            ],
+            post_tool_use: Vec::new(),
+        });
+
+        let outcome = runner.run_pre_tool_use(&call("shell")).await;
+
+        assert_eq!(
+            outcome,
+            HookOutcome {
+                messages: Vec::new(),
+                blocked: Some("no shell today".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn matcher_limits_hooks_to_matching_tools() {
+        let runner = HookRunner::new(&Hooks {
+            pre_tool_use: vec![hook(Some("github__*"), "exit 2")],
+            post_tool_use: Vec::new(),
+        });
+
+        assert_eq!(
+            runner.run_pre_tool_use(&call("shell")).await,
+            HookOutcome::default()
+        );
+        assert!(
+            runner
+                .run_pre_tool_use(&call("github__create_issue"))
+                .await
+                .blocked
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn hook_receives_call_as_json_on_stdin() {
+        let runner = HookRunner::new(&Hooks {
+            pre_tool_use: Vec::new(),
+            post_tool_use: vec![hook(None, "cat")],
+        });
+
+        let outcome = runner
+            .run_post_tool_use(&call("shell"), json!({ "content": "hi" }))
+            .await;
+
+        let [message] = outcome.messages.as_slice() else {
+            panic!("expected one message, got {outcome:?}");
+        };
+        let input: Value = serde_json::from_str(message).expect("hook input is json");
+        assert_eq!(
+            input,
+            json!({
+                "hook_event_name": "post_tool_use",
+                "session_id": "session",
+                "call_id": "call-1",
+                "tool_name": "shell",
+                "cwd": std::env::temp_dir(),
+                "tool_input": { "command": ["echo", "hi"] },
+                "tool_output": { "content": "hi" },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_or_slow_pre_hooks_block_the_call() {
+        let broken = HookRunner::new(&Hooks {
+            pre_tool_use: vec![hook(None, "echo broken >&2; exit 1")],
+            post_tool_use: Vec::new(),
+        });
+        let slow = HookRunner::new(&Hooks {
+            pre_tool_use: vec![HookConfig {
+                timeout_sec: Some(0),
+                ..hook(None, "sleep 5")
+            }],
+            post_tool_use: Vec::new(),
+        });
+
+        let blocked = broken.run_pre_tool_use(&call("shell")).await.blocked;
+        assert!(blocked.is_some_and(|reason| reason.contains("broken")));
+        let blocked = slow.run_pre_tool_use(&call("shell")).await.blocked;
+        assert!(blocked.is_some_and(|reason| reason.contains("timed out")));
+    }
+
+    #[tokio::test]
+    async fn failing_or_slow_post_hooks_are_ignored() {
+        let runner = HookRunner::new(&Hooks {
+            pre_tool_use: Vec::new(),
+            post_tool_use: vec![
+                hook(None, "echo broken >&2; exit 1"),
+                HookConfig {
+                    timeout_sec: Some(0),
+                    ..hook(None, "sleep 5")
+                },
+            ],
+        });
+
+        assert_eq!(
+            runner
+                .run_post_tool_use(&call("shell"), json!({ "content": "hi" }))
+                .await,
+            HookOutcome::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn hook_that_ignores_large_input_still_times_out() {
+        let runner = HookRunner::new(&Hooks {
+            pre_tool_use: vec![HookConfig {
+                timeout_sec: Some(1),
+                ..hook(None, "sleep 30")
+            }],
+            post_tool_use: Vec::new(),
+        });
+        let mut call = call("shell");
+        call.tool_input = json!({ "command": ["echo", "x".repeat(1 << 20)] });
+
+        let started = std::time::Instant::now();
+        let outcome = runner.run_pre_tool_use(&call).await;
+
+        assert!(outcome.blocked.is_some());
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn apply_patch_matcher_covers_shell_invocations() {
+        let runner = HookRunner::new(&Hooks {
+            pre_tool_use: vec![hook(Some("apply_patch"), "exit 2")],
+            post_tool_use: Vec::new(),
+        });
+        let tool_input = json!({
+            "command": [
+                "bash",
+                "-lc",
+                "apply_patch <<'EOF'\n*** Begin Patch\n*** Add File: a.txt\n+hi\n*** End Patch\nEOF\n",
+            ],
+        });
+        let call = HookCall {
+            invokes_apply_patch: invokes_apply_patch(&tool_input),
+            tool_input,
+            ..call("shell")
+        };
+
+        assert!(call.invokes_apply_patch);
+        assert!(runner.run_pre_tool_use(&call).await.blocked.is_some());
+    }
+
+    #[test]
+    fn messages_are_appended_to_function_output() {
+        let mut output = ToolOutput::Function {
+            content: "done".to_string(),
+            success: Some(true),
+        };
+
+        apply_to_output(&mut output, &["checked".to_string()], true);
+
+        let ToolOutput::Function { content, success } = output else {
+            panic!("expected function output");
+        };
+        assert_eq!(content, "done\n\n[hook] checked");
+        assert_eq!(success, Some(false));
+    }
+}
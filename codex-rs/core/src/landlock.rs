@@ -1,31 +1,158 @@
+use crate::config_types::ResourceLimits;
+use crate::protocol::SandboxDenial;
 use crate::protocol::SandboxPolicy;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::fmt;
+use std::io::PipeWriter;
 use std::path::Path;
 use std::path::PathBuf;
+use std::str::FromStr;
 use tokio::process::Child;
 
+/// How `codex-linux-sandbox` isolates the command from the rest of the
+/// system.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LinuxSandboxIsolation {
+    /// Landlock and seccomp only: the command sees the real file-system and
+    /// processes, but can only write to the writable roots. `deny_read` paths
+    /// inside a writable root are hidden in a mount namespace of their own.
+    #[default]
+    Landlock,
+
+    /// Additionally run the command in its own user, mount and PID
+    /// namespaces, where everything but the writable roots is mounted
+    /// read-only, `/tmp` is a private tmpfs and `deny_read` paths are hidden.
+    /// Commands fail instead of running when unprivileged user namespaces are
+    /// unavailable.
+    Namespaces,
+}
+
+impl LinuxSandboxIsolation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LinuxSandboxIsolation::Landlock => "landlock",
+            LinuxSandboxIsolation::Namespaces => "namespaces",
+        }
+    }
+}
+
+impl fmt::Display for LinuxSandboxIsolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LinuxSandboxIsolation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "landlock" => Ok(LinuxSandboxIsolation::Landlock),
+            "namespaces" => Ok(LinuxSandboxIsolation::Namespaces),
+            other => Err(format!(
+                "unknown isolation mode `{other}`; expected `landlock` or `namespaces`"
+            )),
+        }
+    }
+}
+
+/// How `codex-linux-sandbox` runs commands, on top of what the sandbox
+/// policy allows them to do.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinuxSandboxOptions {
+    pub isolation: LinuxSandboxIsolation,
+
+    /// Whether to watch the writes and network sockets of commands with a
+    /// seccomp supervisor, so that the ones the sandbox blocks are reported.
+    pub report_denials: bool,
+}
+
+/// File descriptor on which `codex-linux-sandbox` reports what happened to
+/// the command: exceeded resource limits and denied operations, one per line.
+/// Unlike `stderr`, the command cannot write to it, so its reports cannot be
+/// forged.
+pub const SANDBOX_REPORT_FD: i32 = 3;
+
+/// Reported by `codex-linux-sandbox`, followed by a [`SandboxDenial`] in its
+/// `"<operation> <target>"` form, for each operation of the command that the
+/// sandbox blocked.
+pub const SANDBOX_DENIAL_MARKER: &str = "codex-linux-sandbox: denied: ";
+
+/// The denials in the report of `codex-linux-sandbox`.
+pub fn parse_sandbox_denials(sandbox_report: &str) -> Vec<SandboxDenial> {
+    sandbox_report
+        .lines()
+        .filter_map(|line| {
+            let (_, denial) = line.split_once(SANDBOX_DENIAL_MARKER)?;
+            SandboxDenial::parse(denial)
+        })
+        .collect()
+}
+
+/// Reported by `codex-linux-sandbox`, followed by the number of folders it
+/// searched, when it stopped searching the cwd and the writable roots for
+/// `deny_read` names before it was done. Files of those names in the folders
+/// it did not get to stay readable.
+pub const DENY_READ_SEARCH_INCOMPLETE_MARKER: &str =
+    "codex-linux-sandbox: deny_read names searched in the first folders only: ";
+
+/// The number of folders `codex-linux-sandbox` searched for `deny_read` names,
+/// when its report says that it had to stop early.
+pub fn parse_incomplete_deny_read_search(sandbox_report: &str) -> Option<usize> {
+    sandbox_report.lines().find_map(|line| {
+        line.split_once(DENY_READ_SEARCH_INCOMPLETE_MARKER)?
+            .1
+            .trim()
+            .parse()
+            .ok()
+    })
+}
+
 /// Spawn a shell tool command under the Linux Landlock+seccomp sandbox helper
 /// (codex-linux-sandbox).
 ///
+/// When `network_proxy_socket` is set, the helper gives the command network
+/// access through the Codex network proxy listening on that socket only.
+/// `resource_limits` are enforced by the helper rather than inherited from
+/// it, so that they only apply to the command. `options` select how strongly
+/// the command is separated from the rest of the system and whether denials
+/// are reported. Reports are written to `report_channel` when it is set, and
+/// dropped otherwise.
+///
 /// Unlike macOS Seatbelt where we directly embed the policy text, the Linux
 /// helper accepts a list of `--sandbox-permission`/`-s` flags mirroring the
 /// public CLI. We convert the internal [`SandboxPolicy`] representation into
 /// the equivalent CLI options.
+#[allow(clippy::too_many_arguments)]
 pub async fn spawn_command_under_linux_sandbox<P>(
     codex_linux_sandbox_exe: P,
     command: Vec<String>,
     command_cwd: PathBuf,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    network_proxy_socket: Option<&Path>,
+    resource_limits: &ResourceLimits,
+    options: LinuxSandboxOptions,
+    report_channel: Option<&PipeWriter>,
     stdio_policy: StdioPolicy,
     env: HashMap<String, String>,
 ) -> std::io::Result<Child>
 where
     P: AsRef<Path>,
 {
-    let args = create_linux_sandbox_command_args(command, sandbox_policy, sandbox_policy_cwd);
+    let args = create_linux_sandbox_command_args(
+        command,
+        sandbox_policy,
+        sandbox_policy_cwd,
+        network_proxy_socket,
+        resource_limits,
+        options,
+        report_channel.is_some(),
+    );
     let arg0 = Some("codex-linux-sandbox");
     spawn_child_async(
         codex_linux_sandbox_exe.as_ref().to_path_buf(),
@@ -35,6 +162,8 @@ where
         sandbox_policy,
         stdio_policy,
         env,
+        &ResourceLimits::default(),
+        report_channel,
     )
     .await
 }
@@ -44,6 +173,10 @@ fn create_linux_sandbox_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    network_proxy_socket: Option<&Path>,
+    resource_limits: &ResourceLimits,
+    options: LinuxSandboxOptions,
+    report: bool,
 ) -> Vec<String> {
     #[expect(clippy::expect_used)]
     let sandbox_policy_cwd = sandbox_policy_cwd
@@ -55,13 +188,50 @@ fn create_linux_sandbox_command_args(
     let sandbox_policy_json =
         serde_json::to_string(sandbox_policy).expect("Failed to serialize SandboxPolicy to JSON");
 
-    let mut linux_cmd: Vec<String> = vec![
+    let LinuxSandboxOptions {
+        isolation,
+        report_denials,
+    } = options;
+    let mut linux_cmd: Vec<String> = Vec::new();
+    if isolation != LinuxSandboxIsolation::Landlock {
+        linux_cmd.push("--isolation".to_string());
+        linux_cmd.push(isolation.to_string());
+    }
+    if report {
+        linux_cmd.push("--report-fd".to_string());
+        linux_cmd.push(SANDBOX_REPORT_FD.to_string());
+        if report_denials {
+            linux_cmd.push("--report-denials".to_string());
+        }
+    }
+    if let Some(network_proxy_socket) = network_proxy_socket {
+        linux_cmd.push("--network-proxy-socket".to_string());
+        linux_cmd.push(network_proxy_socket.to_string_lossy().to_string());
+    }
+    let ResourceLimits {
+        max_memory_bytes,
+        max_cpu_seconds,
+        max_processes,
+        max_file_size_bytes,
+    } = resource_limits;
+    for (flag, limit) in [
+        ("--max-memory-bytes", max_memory_bytes),
+        ("--max-cpu-seconds", max_cpu_seconds),
+        ("--max-processes", max_processes),
+        ("--max-file-size-bytes", max_file_size_bytes),
+    ] {
+        if let Some(limit) = limit {
+            linux_cmd.push(flag.to_string());
+            linux_cmd.push(limit.to_string());
+        }
+    }
+    linux_cmd.extend([
         sandbox_policy_cwd,
         sandbox_policy_json,
         // Separator so that command arguments starting with `-` are not parsed as
         // options of the helper itself.
         "--".to_string(),
-    ];
+    ]);
 
     // Append the original tool command.
     linux_cmd.extend(command);
@@ -8,6 +8,7 @@
 mod apply_patch;
 pub mod auth;
 pub mod bash;
+mod cassette;
 mod chat_completions;
 mod client;
 mod client_common;
@@ -31,12 +32,16 @@ pub mod exec_env;
 pub mod executor;
 mod flags;
 pub mod git_info;
+mod hooks;
 pub mod landlock;
 pub mod mcp;
 mod mcp_connection_manager;
 mod mcp_tool_call;
 mod message_history;
+mod messages_api;
 mod model_provider_info;
+#[cfg(unix)]
+mod network_proxy;
 pub mod parse_command;
 mod truncate;
 mod unified_exec;
@@ -59,7 +64,9 @@ pub mod default_client;
 pub mod model_family;
 mod openai_model_info;
 mod openai_tools;
+pub mod pricing;
 pub mod project_doc;
+pub mod resource_limits;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
@@ -73,11 +80,19 @@ pub use rollout::INTERACTIVE_SESSION_SOURCES;
 pub use rollout::RolloutRecorder;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
+pub use rollout::compression::compressed_rollout_path;
+pub use rollout::compression::read_rollout_text;
 pub use rollout::find_conversation_path_by_id_str;
 pub use rollout::find_most_recent_conversation_path;
+pub use rollout::list::ConversationFilter;
 pub use rollout::list::ConversationItem;
 pub use rollout::list::ConversationsPage;
 pub use rollout::list::Cursor;
+pub use rollout::retention::PruneReport;
+pub use rollout::retention::RolloutFile;
+pub use rollout::retention::prune_sessions;
+pub use rollout::search::SearchHitKind;
+pub use rollout::search::SessionSearchHit;
 mod function_tool;
 mod state;
 mod tasks;
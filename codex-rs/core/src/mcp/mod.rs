@@ -1 +1,3 @@
 pub mod auth;
+pub(crate) mod prompts;
+pub(crate) mod resources;
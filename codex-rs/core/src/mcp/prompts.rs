@@ -0,0 +1,428 @@
+//! Helpers for MCP prompts: listing them as `/mcp:server:name` slash commands
+//! and rendering an invocation through `prompts/get`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use codex_protocol::custom_prompts::CustomPrompt;
+use codex_protocol::custom_prompts::MCP_PROMPTS_CMD_PREFIX;
+use codex_protocol::custom_prompts::McpPromptSource;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use mcp_types::ContentBlock;
+use mcp_types::EmbeddedResourceResource;
+use mcp_types::GetPromptResult;
+use mcp_types::Prompt;
+use mcp_types::PromptMessage;
+use mcp_types::Role;
+
+use crate::mcp::resources::render_blob_summary;
+use crate::mcp_connection_manager::McpConnectionManager;
+use crate::protocol::InputItem;
+
+/// Prompts published by the connected MCP servers, sorted by command.
+pub(crate) fn list_mcp_prompts(manager: &McpConnectionManager) -> Vec<CustomPrompt> {
+    let mut prompts: Vec<CustomPrompt> = manager
+        .list_all_prompts()
+        .into_iter()
+        .flat_map(|(server, prompts)| {
+            prompts
+                .into_iter()
+                .map(move |prompt| to_custom_prompt(&server, prompt))
+        })
+        .collect();
+    prompts.sort_by_key(CustomPrompt::command);
+    prompts
+}
+
+fn to_custom_prompt(server: &str, prompt: Prompt) -> CustomPrompt {
+    let arguments = prompt.arguments.unwrap_or_default();
+    let argument_hint = (!arguments.is_empty()).then(|| {
+        arguments
+            .iter()
+            .map(|arg| {
+                if arg.required.unwrap_or(false) {
+                    arg.name.clone()
+                } else {
+                    format!("[{}]", arg.name)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+    CustomPrompt {
+        name: prompt.name,
+        path: PathBuf::new(),
+        content: String::new(),
+        description: prompt.description.or(prompt.title),
+        argument_hint,
+        mcp: Some(McpPromptSource {
+            server: server.to_string(),
+            arguments,
+        }),
+    }
+}
+
+/// User input after MCP prompt expansion.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ExpandedInput {
+    /// Prompt messages that precede the final user messages, kept with their
+    /// original roles so they can be recorded in the conversation first.
+    pub(crate) history: Vec<ResponseItem>,
+    /// Input for the turn. Empty when the prompt ends with an assistant message.
+    pub(crate) input: Vec<InputItem>,
+}
+
+/// When the first text item starts with `/mcp:server:name` naming a prompt
+/// that `server` published, replaces that item with the prompt as rendered by
+/// the server. Arguments follow the command as `key=value` pairs. Other input
+/// is returned unchanged. The error is a message suitable for the user.
+pub(crate) async fn expand_mcp_prompt_invocation(
+    manager: &McpConnectionManager,
+    mut items: Vec<InputItem>,
+) -> Result<ExpandedInput, String> {
+    let Some(InputItem::Text { text }) = items.first() else {
+        return Ok(ExpandedInput {
+            history: Vec::new(),
+            input: items,
+        });
+    };
+    let Some((server, name, rest)) = parse_prompt_invocation(text) else {
+        return Ok(ExpandedInput {
+            history: Vec::new(),
+            input: items,
+        });
+    };
+    let Some(prompt) = manager.find_prompt(server, name) else {
+        return Ok(ExpandedInput {
+            history: Vec::new(),
+            input: items,
+        });
+    };
+
+    let command = format!("/{MCP_PROMPTS_CMD_PREFIX}:{server}:{name}");
+    let arguments = parse_prompt_arguments(&command, prompt, rest)?;
+    let result = manager
+        .get_prompt(server, name, arguments)
+        .await
+        .map_err(|e| format!("{e:#}"))?;
+
+    let mut expanded = render_prompt_messages(&result);
+    items.remove(0);
+    expanded.input.extend(items);
+    Ok(expanded)
+}
+
+/// Splits `/mcp:server:name rest` into `("server", "name", "rest")`.
+fn parse_prompt_invocation(text: &str) -> Option<(&str, &str, &str)> {
+    let stripped = text.trim_start().strip_prefix('/')?;
+    let (command, rest) = match stripped.find(char::is_whitespace) {
+        Some(idx) => (&stripped[..idx], stripped[idx..].trim_start()),
+        None => (stripped, ""),
+    };
+    let command = command
+        .strip_prefix(MCP_PROMPTS_CMD_PREFIX)?
+        .strip_prefix(':')?;
+    let (server, name) = command.split_once(':')?;
+    if server.is_empty() || name.is_empty() {
+        return None;
+    }
+    Some((server, name, rest))
+}
+
+fn parse_prompt_arguments(
+    command: &str,
+    prompt: &Prompt,
+    rest: &str,
+) -> Result<HashMap<String, String>, String> {
+    let tokens = shlex::split(rest)
+        .ok_or_else(|| format!("Could not parse {command}: unbalanced quotes."))?;
+    let mut arguments = HashMap::new();
+    for token in tokens {
+        let Some((key, value)) = token.split_once('=') else {
+            return Err(format!(
+                "Could not parse {command}: expected key=value but found '{token}'."
+            ));
+        };
+        arguments.insert(key.to_string(), value.to_string());
+    }
+
+    let missing: Vec<&str> = prompt
+        .arguments
+        .iter()
+        .flatten()
+        .filter(|arg| arg.required.unwrap_or(false) && !arguments.contains_key(&arg.name))
+        .map(|arg| arg.name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Missing required args for {command}: {}.",
+            missing.join(", ")
+        ));
+    }
+    Ok(arguments)
+}
+
+/// Splits the messages of a rendered prompt at the last run of user messages.
+/// That run becomes the turn input: one text item holding every textual part,
+/// followed by any images. Earlier messages are kept as conversation items
+/// with their own roles.
+fn render_prompt_messages(result: &GetPromptResult) -> ExpandedInput {
+    let messages = &result.messages;
+    let split = messages
+        .iter()
+        .rposition(|message| !matches!(message.role, Role::User))
+        .map_or(0, |idx| idx + 1);
+
+    let mut history: Vec<ResponseItem> = Vec::new();
+    for message in &messages[..split] {
+        let role = match message.role {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        };
+        let content = render_content_item(message);
+        match history.last_mut() {
+            Some(ResponseItem::Message {
+                role: last_role,
+                content: last_content,
+                ..
+            }) if last_role == role => last_content.push(content),
+            _ => history.push(ResponseItem::Message {
+                id: None,
+                role: role.to_string(),
+                content: vec![content],
+            }),
+        }
+    }
+
+    let mut texts = Vec::new();
+    let mut images = Vec::new();
+    for message in &messages[split..] {
+        match &message.content {
+            ContentBlock::ImageContent(image) => images.push(InputItem::Image {
+                image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+            }),
+            content => texts.push(render_text(content)),
+        }
+    }
+    let mut input = Vec::with_capacity(images.len() + 1);
+    if !texts.is_empty() {
+        input.push(InputItem::Text {
+            text: texts.join("\n\n"),
+        });
+    }
+    input.extend(images);
+
+    ExpandedInput { history, input }
+}
+
+/// A prompt message as a content item for a recorded conversation message.
+/// Assistant messages cannot carry images, so those are summarized as text.
+fn render_content_item(message: &PromptMessage) -> ContentItem {
+    match (&message.role, &message.content) {
+        (Role::User, ContentBlock::ImageContent(image)) => ContentItem::InputImage {
+            image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+        },
+        (Role::User, content) => ContentItem::InputText {
+            text: render_text(content),
+        },
+        (Role::Assistant, content) => ContentItem::OutputText {
+            text: render_text(content),
+        },
+    }
+}
+
+fn render_text(content: &ContentBlock) -> String {
+    match content {
+        ContentBlock::TextContent(text) => text.text.clone(),
+        ContentBlock::ImageContent(image) => format!("[{} image omitted]", image.mime_type),
+        ContentBlock::AudioContent(audio) => format!("[{} audio omitted]", audio.mime_type),
+        ContentBlock::ResourceLink(link) => format!("[resource {}]", link.uri),
+        ContentBlock::EmbeddedResource(embedded) => match &embedded.resource {
+            EmbeddedResourceResource::TextResourceContents(text) => text.text.clone(),
+            EmbeddedResourceResource::BlobResourceContents(blob) => render_blob_summary(blob),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::ImageContent;
+    use mcp_types::PromptArgument;
+    use mcp_types::TextContent;
+    use pretty_assertions::assert_eq;
+
+    fn review_prompt() -> Prompt {
+        Prompt {
+            arguments: Some(vec![
+                PromptArgument {
+                    description: None,
+                    name: "file".to_string(),
+                    required: Some(true),
+                    title: None,
+                },
+                PromptArgument {
+                    description: None,
+                    name: "focus".to_string(),
+                    required: None,
+                    title: None,
+                },
+            ]),
+            description: Some("Review a file".to_string()),
+            name: "review".to_string(),
+            title: None,
+        }
+    }
+
+    #[test]
+    fn parses_invocation() {
+        assert_eq!(
+            parse_prompt_invocation("/mcp:github:review file=src/lib.rs"),
+            Some(("github", "review", "file=src/lib.rs"))
+        );
+        assert_eq!(
+            parse_prompt_invocation("/mcp:github:review"),
+            Some(("github", "review", ""))
+        );
+        assert_eq!(
+            parse_prompt_invocation("/mcp:prompts:review"),
+            Some(("prompts", "review", ""))
+        );
+        assert_eq!(parse_prompt_invocation("/prompts:review"), None);
+        assert_eq!(parse_prompt_invocation("/github:review"), None);
+        assert_eq!(parse_prompt_invocation("/review"), None);
+        assert_eq!(parse_prompt_invocation("review github:x"), None);
+    }
+
+    #[test]
+    fn converts_prompt_with_argument_hint() {
+        let prompt = to_custom_prompt("github", review_prompt());
+
+        assert_eq!(prompt.command(), "mcp:github:review");
+        assert_eq!(prompt.argument_hint.as_deref(), Some("file [focus]"));
+        assert_eq!(prompt.description.as_deref(), Some("Review a file"));
+    }
+
+    #[test]
+    fn parses_arguments_and_reports_missing_required_ones() {
+        let prompt = review_prompt();
+
+        assert_eq!(
+            parse_prompt_arguments("/mcp:github:review", &prompt, r#"file="src/my lib.rs""#),
+            Ok(HashMap::from([(
+                "file".to_string(),
+                "src/my lib.rs".to_string()
+            )]))
+        );
+        assert_eq!(
+            parse_prompt_arguments("/mcp:github:review", &prompt, "focus=tests"),
+            Err("Missing required args for /mcp:github:review: file.".to_string())
+        );
+        assert_eq!(
+            parse_prompt_arguments("/mcp:github:review", &prompt, "src/lib.rs"),
+            Err(
+                "Could not parse /mcp:github:review: expected key=value but found 'src/lib.rs'."
+                    .to_string()
+            )
+        );
+    }
+
+    fn text_message(role: Role, text: &str) -> PromptMessage {
+        PromptMessage {
+            content: ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            }),
+            role,
+        }
+    }
+
+    #[test]
+    fn renders_text_and_images() {
+        let result = GetPromptResult {
+            description: None,
+            messages: vec![
+                text_message(Role::User, "Review src/lib.rs"),
+                PromptMessage {
+                    content: ContentBlock::ImageContent(ImageContent {
+                        annotations: None,
+                        data: "AAAA".to_string(),
+                        mime_type: "image/png".to_string(),
+                        r#type: "image".to_string(),
+                    }),
+                    role: Role::User,
+                },
+                text_message(Role::User, "Focus on error handling"),
+            ],
+        };
+
+        assert_eq!(
+            render_prompt_messages(&result),
+            ExpandedInput {
+                history: Vec::new(),
+                input: vec![
+                    InputItem::Text {
+                        text: "Review src/lib.rs\n\nFocus on error handling".to_string(),
+                    },
+                    InputItem::Image {
+                        image_url: "data:image/png;base64,AAAA".to_string(),
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_roles_of_earlier_messages() {
+        let result = GetPromptResult {
+            description: None,
+            messages: vec![
+                text_message(Role::User, "What does this crate do?"),
+                text_message(Role::Assistant, "It parses config files."),
+                text_message(Role::User, "Review its error handling"),
+            ],
+        };
+
+        assert_eq!(
+            render_prompt_messages(&result),
+            ExpandedInput {
+                history: vec![
+                    ResponseItem::Message {
+                        id: None,
+                        role: "user".to_string(),
+                        content: vec![ContentItem::InputText {
+                            text: "What does this crate do?".to_string(),
+                        }],
+                    },
+                    ResponseItem::Message {
+                        id: None,
+                        role: "assistant".to_string(),
+                        content: vec![ContentItem::OutputText {
+                            text: "It parses config files.".to_string(),
+                        }],
+                    },
+                ],
+                input: vec![InputItem::Text {
+                    text: "Review its error handling".to_string(),
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn prompt_ending_with_assistant_message_has_no_input() {
+        let result = GetPromptResult {
+            description: None,
+            messages: vec![
+                text_message(Role::User, "Summarize the design"),
+                text_message(Role::Assistant, "Here is the design:"),
+            ],
+        };
+
+        let expanded = render_prompt_messages(&result);
+        assert_eq!(expanded.history.len(), 2);
+        assert_eq!(expanded.input, Vec::new());
+    }
+}
@@ -0,0 +1,223 @@
+//! Helpers for MCP resources: rendering `resources/read` results as text and
+//! inlining resources referenced with `@server:resource` mentions.
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use anyhow::bail;
+use mcp_types::BlobResourceContents;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use tracing::warn;
+use url::Url;
+
+use crate::mcp_connection_manager::McpConnectionManager;
+use crate::protocol::DenyRead;
+use crate::protocol::InputItem;
+
+/// Characters stripped from the end of a mention when the raw token does not
+/// resolve, so `see @docs:runbook.md.` still finds `runbook.md`.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '"', '\''];
+
+/// Renders the contents returned by `resources/read` as plain text. Binary
+/// contents are summarized rather than inlined.
+pub(crate) fn render_resource_contents(result: &ReadResourceResult) -> String {
+    result
+        .contents
+        .iter()
+        .map(|contents| match contents {
+            ReadResourceResultContents::TextResourceContents(text) => text.text.clone(),
+            ReadResourceResultContents::BlobResourceContents(blob) => render_blob_summary(blob),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Placeholder text for binary resource contents, which are not inlined.
+pub(crate) fn render_blob_summary(blob: &BlobResourceContents) -> String {
+    format!(
+        "[binary resource {} ({}, {} base64 bytes) omitted]",
+        blob.uri,
+        blob.mime_type.as_deref().unwrap_or("unknown type"),
+        blob.blob.len()
+    )
+}
+
+/// Reads `uri` from `server`, refusing `file://` URIs of paths that
+/// `deny_read` covers, whether requested or returned by the server.
+pub(crate) async fn read_resource(
+    manager: &McpConnectionManager,
+    server: &str,
+    uri: &str,
+    deny_read: &DenyRead,
+) -> Result<ReadResourceResult> {
+    if is_denied_file_uri(uri, deny_read) {
+        bail!("`{uri}` is listed in `deny_read` and cannot be read");
+    }
+    let result = manager.read_resource(server, uri).await?;
+    for contents in &result.contents {
+        let contents_uri = match contents {
+            ReadResourceResultContents::TextResourceContents(text) => &text.uri,
+            ReadResourceResultContents::BlobResourceContents(blob) => &blob.uri,
+        };
+        if is_denied_file_uri(contents_uri, deny_read) {
+            bail!("`{contents_uri}` is listed in `deny_read` and cannot be read");
+        }
+    }
+    Ok(result)
+}
+
+fn is_denied_file_uri(uri: &str, deny_read: &DenyRead) -> bool {
+    Url::parse(uri)
+        .ok()
+        .filter(|url| url.scheme() == "file")
+        .and_then(|url| url.to_file_path().ok())
+        .is_some_and(|path| deny_read.is_path_denied(&path))
+}
+
+/// Splits `@server:resource` into `("server", "resource")`.
+pub(crate) fn parse_resource_mention(token: &str) -> Option<(&str, &str)> {
+    let (server, resource) = token.strip_prefix('@')?.split_once(':')?;
+    if server.is_empty() || resource.is_empty() {
+        return None;
+    }
+    Some((server, resource))
+}
+
+/// Appends the contents of every MCP resource mentioned in the text items of
+/// `items`. A mention resolves when `resource` is the name or URI of a
+/// resource the server listed, or any URI (e.g. one built from a resource
+/// template) for a server that exposes resources. Resources that `deny_read`
+/// covers are left out.
+pub(crate) async fn inline_resource_mentions(
+    manager: &McpConnectionManager,
+    mut items: Vec<InputItem>,
+    deny_read: &DenyRead,
+) -> Vec<InputItem> {
+    let mut mentions: Vec<(String, String)> = Vec::new();
+    let mut seen = HashSet::new();
+    for item in &items {
+        let InputItem::Text { text } = item else {
+            continue;
+        };
+        for token in text.split_whitespace() {
+            if let Some(mention) = resolve_mention(manager, token)
+                && seen.insert(mention.clone())
+            {
+                mentions.push(mention);
+            }
+        }
+    }
+
+    for (server, uri) in mentions {
+        match read_resource(manager, &server, &uri, deny_read).await {
+            Ok(result) => items.push(InputItem::Text {
+                text: format!(
+                    "<mcp_resource server=\"{server}\" uri=\"{uri}\">\n{}\n</mcp_resource>",
+                    render_resource_contents(&result)
+                ),
+            }),
+            Err(e) => warn!("failed to inline MCP resource `{server}` `{uri}`: {e:#}"),
+        }
+    }
+
+    items
+}
+
+fn resolve_mention(manager: &McpConnectionManager, token: &str) -> Option<(String, String)> {
+    let candidates = [token, token.trim_end_matches(TRAILING_PUNCTUATION)];
+    for candidate in candidates {
+        let Some((server, resource)) = parse_resource_mention(candidate) else {
+            continue;
+        };
+        if let Some(found) = manager.find_resource(server, resource) {
+            return Some((server.to_string(), found.uri.clone()));
+        }
+        if resource.contains("://") && manager.lists_resources(server) {
+            return Some((server.to_string(), resource.to_string()));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::SandboxPolicy;
+    use mcp_types::TextResourceContents;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parses_server_and_resource() {
+        assert_eq!(
+            parse_resource_mention("@docs-server:runbook.md"),
+            Some(("docs-server", "runbook.md"))
+        );
+        assert_eq!(
+            parse_resource_mention("@docs:file:///srv/runbook.md"),
+            Some(("docs", "file:///srv/runbook.md"))
+        );
+        assert_eq!(parse_resource_mention("@src/main.rs"), None);
+        assert_eq!(parse_resource_mention("docs:runbook.md"), None);
+        assert_eq!(parse_resource_mention("@docs:"), None);
+    }
+
+    #[test]
+    fn renders_text_and_summarizes_blobs() {
+        let result = ReadResourceResult {
+            contents: vec![
+                ReadResourceResultContents::TextResourceContents(TextResourceContents {
+                    mime_type: Some("text/markdown".to_string()),
+                    text: "# Runbook".to_string(),
+                    uri: "file:///runbook.md".to_string(),
+                }),
+                ReadResourceResultContents::BlobResourceContents(BlobResourceContents {
+                    blob: "AAAA".to_string(),
+                    mime_type: Some("image/png".to_string()),
+                    uri: "file:///diagram.png".to_string(),
+                }),
+            ],
+        };
+
+        assert_eq!(
+            render_resource_contents(&result),
+            "# Runbook\n\n[binary resource file:///diagram.png (image/png, 4 base64 bytes) omitted]"
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_mentions_are_left_alone() {
+        let manager = McpConnectionManager::default();
+        let items = vec![InputItem::Text {
+            text: "check @docs:runbook.md and @src/lib.rs".to_string(),
+        }];
+
+        let deny_read = SandboxPolicy::DangerFullAccess.get_deny_read_with_cwd(Path::new("/"));
+        let inlined = inline_resource_mentions(&manager, items.clone(), &deny_read).await;
+
+        assert_eq!(inlined, items);
+    }
+
+    #[tokio::test]
+    async fn denied_file_uris_are_not_read() {
+        let manager = McpConnectionManager::default();
+        let policy = SandboxPolicy::ReadOnly {
+            readable_roots: vec![],
+            deny_read: vec![PathBuf::from(".env")],
+        };
+        let deny_read = policy.get_deny_read_with_cwd(Path::new("/work/project"));
+
+        let err = read_resource(
+            &manager,
+            "files",
+            "file:///work/project/app/.env",
+            &deny_read,
+        )
+        .await
+        .expect_err("denied file URIs are refused");
+
+        assert!(err.to_string().contains("deny_read"), "{err}");
+    }
+}
@@ -4,7 +4,11 @@
 //! configured server (keyed by the *server name*). It offers convenience
 //! helpers to query the available tools across *all* servers and returns them
 //! in a single aggregated map using the fully-qualified tool name
-//! `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the key.
+//! `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the key. Resources,
+//! resource templates and prompts are enumerated at startup as well, keyed by
+//! server name, and all of these are listed again when the configuration is
+//! reloaded with [`McpConnectionManager::reload`]. Every client advertises the `sampling` capability and forwards
+//! `sampling/createMessage` requests to the session as [`McpSamplingRequest`]s.
 
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -17,15 +21,31 @@ use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
 use codex_mcp_client::McpClient;
+use codex_mcp_client::SamplingHandler;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
 use codex_rmcp_client::RmcpClient;
 use mcp_types::ClientCapabilities;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::Implementation;
+use mcp_types::ListPromptsRequestParams;
+use mcp_types::ListResourceTemplatesRequestParams;
+use mcp_types::ListResourcesRequestParams;
+use mcp_types::Prompt;
+use mcp_types::ReadResourceRequestParams;
+use mcp_types::ReadResourceResult;
+use mcp_types::Resource;
+use mcp_types::ResourceTemplate;
+use mcp_types::ServerCapabilities;
 use mcp_types::Tool;
 
 use serde_json::json;
 use sha1::Digest;
 use sha1::Sha1;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
 use tokio::task::JoinSet;
 use tracing::info;
 use tracing::warn;
@@ -47,10 +67,52 @@ const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
 /// Default timeout for individual tool calls.
 const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Most pages fetched from one paginated `*/list` method, so that a server
+/// that never stops handing out cursors cannot stall startup.
+const MAX_LIST_PAGES: usize = 100;
+
 /// Map that holds a startup error for every MCP server that could **not** be
 /// spawned successfully.
 pub type ClientStartErrors = HashMap<String, anyhow::Error>;
 
+/// A `sampling/createMessage` request received from `server`. The session
+/// answers it through `respond_to`; an `Err` is reported back to the server.
+pub(crate) struct McpSamplingRequest {
+    pub server: String,
+    pub auto_approve: bool,
+    pub params: CreateMessageRequestParams,
+    pub respond_to: oneshot::Sender<Result<CreateMessageResult, String>>,
+}
+
+/// Builds the handler installed on `server`'s client: each request is handed
+/// to the session over `sampling_tx` and the handler waits for its answer.
+fn sampling_handler(
+    server: String,
+    auto_approve: bool,
+    sampling_tx: mpsc::Sender<McpSamplingRequest>,
+) -> SamplingHandler {
+    Arc::new(move |params| {
+        let server = server.clone();
+        let sampling_tx = sampling_tx.clone();
+        Box::pin(async move {
+            let (respond_to, response) = oneshot::channel();
+            let request = McpSamplingRequest {
+                server,
+                auto_approve,
+                params,
+                respond_to,
+            };
+            sampling_tx
+                .send(request)
+                .await
+                .map_err(|_| "Codex session is no longer running".to_string())?;
+            response
+                .await
+                .map_err(|_| "Codex dropped the sampling request".to_string())?
+        })
+    })
+}
+
 fn qualify_tools(tools: Vec<ToolInfo>) -> HashMap<String, ToolInfo> {
     let mut used_names = HashSet::new();
     let mut qualified_tools = HashMap::new();
@@ -89,10 +151,15 @@ struct ToolInfo {
     tool: Tool,
 }
 
+#[derive(Clone)]
 struct ManagedClient {
     client: McpClientAdapter,
     startup_timeout: Duration,
     tool_timeout: Option<Duration>,
+    capabilities: ServerCapabilities,
+    /// The configuration the client was started with, compared against on
+    /// reload.
+    config: McpServerConfig,
 }
 
 #[derive(Clone)]
@@ -109,15 +176,18 @@ impl McpClientAdapter {
         env: Option<HashMap<String, String>>,
         params: mcp_types::InitializeRequestParams,
         startup_timeout: Duration,
-    ) -> Result<Self> {
+        sampling_handler: SamplingHandler,
+    ) -> Result<(Self, mcp_types::InitializeResult)> {
         if use_rmcp_client {
             let client = Arc::new(RmcpClient::new_stdio_client(program, args, env).await?);
-            client.initialize(params, Some(startup_timeout)).await?;
-            Ok(McpClientAdapter::Rmcp(client))
+            client.set_sampling_handler(sampling_handler).await;
+            let initialize_result = client.initialize(params, Some(startup_timeout)).await?;
+            Ok((McpClientAdapter::Rmcp(client), initialize_result))
         } else {
             let client = Arc::new(McpClient::new_stdio_client(program, args, env).await?);
-            client.initialize(params, Some(startup_timeout)).await?;
-            Ok(McpClientAdapter::Legacy(client))
+            client.set_sampling_handler(sampling_handler).await;
+            let initialize_result = client.initialize(params, Some(startup_timeout)).await?;
+            Ok((McpClientAdapter::Legacy(client), initialize_result))
         }
     }
 
@@ -128,13 +198,15 @@ impl McpClientAdapter {
         params: mcp_types::InitializeRequestParams,
         startup_timeout: Duration,
         store_mode: OAuthCredentialsStoreMode,
-    ) -> Result<Self> {
+        sampling_handler: SamplingHandler,
+    ) -> Result<(Self, mcp_types::InitializeResult)> {
         let client = Arc::new(
             RmcpClient::new_streamable_http_client(&server_name, &url, bearer_token, store_mode)
                 .await?,
         );
-        client.initialize(params, Some(startup_timeout)).await?;
-        Ok(McpClientAdapter::Rmcp(client))
+        client.set_sampling_handler(sampling_handler).await;
+        let initialize_result = client.initialize(params, Some(startup_timeout)).await?;
+        Ok((McpClientAdapter::Rmcp(client), initialize_result))
     }
 
     async fn list_tools(
@@ -159,39 +231,83 @@ impl McpClientAdapter {
             McpClientAdapter::Rmcp(client) => client.call_tool(name, arguments, timeout).await,
         }
     }
-}
 
-/// A thin wrapper around a set of running [`McpClient`] instances.
-#[derive(Default)]
-pub(crate) struct McpConnectionManager {
-    /// Server-name -> client instance.
-    ///
-    /// The server name originates from the keys of the `mcp_servers` map in
-    /// the user configuration.
-    clients: HashMap<String, ManagedClient>,
+    async fn list_resources(
+        &self,
+        params: Option<ListResourcesRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<mcp_types::ListResourcesResult> {
+        match self {
+            McpClientAdapter::Legacy(client) => client.list_resources(params, timeout).await,
+            McpClientAdapter::Rmcp(client) => client.list_resources(params, timeout).await,
+        }
+    }
 
-    /// Fully qualified tool name -> tool instance.
-    tools: HashMap<String, ToolInfo>,
+    async fn list_resource_templates(
+        &self,
+        params: Option<ListResourceTemplatesRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<mcp_types::ListResourceTemplatesResult> {
+        match self {
+            McpClientAdapter::Legacy(client) => {
+                client.list_resource_templates(params, timeout).await
+            }
+            McpClientAdapter::Rmcp(client) => client.list_resource_templates(params, timeout).await,
+        }
+    }
+
+    async fn read_resource(
+        &self,
+        params: ReadResourceRequestParams,
+        timeout: Option<Duration>,
+    ) -> Result<ReadResourceResult> {
+        match self {
+            McpClientAdapter::Legacy(client) => client.read_resource(params, timeout).await,
+            McpClientAdapter::Rmcp(client) => client.read_resource(params, timeout).await,
+        }
+    }
+
+    async fn list_prompts(
+        &self,
+        params: Option<ListPromptsRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<mcp_types::ListPromptsResult> {
+        match self {
+            McpClientAdapter::Legacy(client) => client.list_prompts(params, timeout).await,
+            McpClientAdapter::Rmcp(client) => client.list_prompts(params, timeout).await,
+        }
+    }
+
+    async fn get_prompt(
+        &self,
+        params: GetPromptRequestParams,
+        timeout: Option<Duration>,
+    ) -> Result<GetPromptResult> {
+        match self {
+            McpClientAdapter::Legacy(client) => client.get_prompt(params, timeout).await,
+            McpClientAdapter::Rmcp(client) => client.get_prompt(params, timeout).await,
+        }
+    }
 }
 
-impl McpConnectionManager {
-    /// Spawn a [`McpClient`] for each configured server.
-    ///
-    /// * `mcp_servers` – Map loaded from the user configuration where *keys*
-    ///   are human-readable server identifiers and *values* are the spawn
-    ///   instructions.
-    ///
-    /// Servers that fail to start are reported in `ClientStartErrors`: the
-    /// user should be informed about these errors.
-    pub async fn new(
+/// Settings shared by every client the manager starts. They are kept so that
+/// servers can be started again when the configuration is reloaded.
+#[derive(Clone)]
+struct ClientLauncher {
+    use_rmcp_client: bool,
+    store_mode: OAuthCredentialsStoreMode,
+    sampling_tx: mpsc::Sender<McpSamplingRequest>,
+}
+
+impl ClientLauncher {
+    /// Start a client for each of `mcp_servers` concurrently. Disabled
+    /// servers are skipped.
+    async fn start_clients(
+        &self,
         mcp_servers: HashMap<String, McpServerConfig>,
-        use_rmcp_client: bool,
-        store_mode: OAuthCredentialsStoreMode,
-    ) -> Result<(Self, ClientStartErrors)> {
-        // Early exit if no servers are configured.
-        if mcp_servers.is_empty() {
-            return Ok((Self::default(), ClientStartErrors::default()));
-        }
+    ) -> (HashMap<String, ManagedClient>, ClientStartErrors) {
+        let use_rmcp_client = self.use_rmcp_client;
+        let store_mode = self.store_mode;
 
         // Launch all configured servers concurrently.
         let mut join_set = JoinSet::new();
@@ -212,7 +328,6 @@ impl McpConnectionManager {
             }
 
             let startup_timeout = cfg.startup_timeout_sec.unwrap_or(DEFAULT_STARTUP_TIMEOUT);
-            let tool_timeout = cfg.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT);
 
             let resolved_bearer_token = match &cfg.transport {
                 McpServerTransportConfig::StreamableHttp {
@@ -222,13 +337,18 @@ impl McpConnectionManager {
                 _ => Ok(None),
             };
 
+            let sampling_handler = sampling_handler(
+                server_name.clone(),
+                cfg.auto_approve_sampling,
+                self.sampling_tx.clone(),
+            );
+
             join_set.spawn(async move {
-                let McpServerConfig { transport, .. } = cfg;
                 let params = mcp_types::InitializeRequestParams {
                     capabilities: ClientCapabilities {
                         experimental: None,
                         roots: None,
-                        sampling: None,
+                        sampling: Some(json!({})),
                         // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
                         // indicates this should be an empty object.
                         elicitation: Some(json!({})),
@@ -245,7 +365,7 @@ impl McpConnectionManager {
                     protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
                 };
 
-                let client = match transport {
+                let client = match cfg.transport.clone() {
                     McpServerTransportConfig::Stdio { command, args, env } => {
                         let command_os: OsString = command.into();
                         let args_os: Vec<OsString> = args.into_iter().map(Into::into).collect();
@@ -256,6 +376,7 @@ impl McpConnectionManager {
                             env,
                             params,
                             startup_timeout,
+                            sampling_handler,
                         )
                         .await
                     }
@@ -267,20 +388,21 @@ impl McpConnectionManager {
                             params,
                             startup_timeout,
                             store_mode,
+                            sampling_handler,
                         )
                         .await
                     }
                 }
-                .map(|c| (c, startup_timeout));
+                .map(|(c, initialize_result)| (c, startup_timeout, initialize_result.capabilities));
 
-                ((server_name, tool_timeout), client)
+                ((server_name, cfg), client)
             });
         }
 
         let mut clients: HashMap<String, ManagedClient> = HashMap::with_capacity(join_set.len());
 
         while let Some(res) = join_set.join_next().await {
-            let ((server_name, tool_timeout), client_res) = match res {
+            let ((server_name, config), client_res) = match res {
                 Ok(result) => result,
                 Err(e) => {
                     warn!("Task panic when starting MCP server: {e:#}");
@@ -289,13 +411,16 @@ impl McpConnectionManager {
             };
 
             match client_res {
-                Ok((client, startup_timeout)) => {
+                Ok((client, startup_timeout, capabilities)) => {
+                    let tool_timeout = config.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT);
                     clients.insert(
                         server_name,
                         ManagedClient {
                             client,
                             startup_timeout,
                             tool_timeout: Some(tool_timeout),
+                            capabilities,
+                            config,
                         },
                     );
                 }
@@ -305,7 +430,166 @@ impl McpConnectionManager {
             }
         }
 
-        let all_tools = match list_all_tools(&clients).await {
+        (clients, errors)
+    }
+}
+
+/// What [`McpConnectionManager::reload`] changed. Each list of server names is
+/// sorted.
+#[derive(Default)]
+pub(crate) struct McpReloadOutcome {
+    /// Servers that were not running before and started successfully.
+    pub started: Vec<String>,
+    /// Servers whose configuration changed or that stopped responding, and
+    /// came back up.
+    pub restarted: Vec<String>,
+    /// Servers that were shut down because they were removed or disabled.
+    pub stopped: Vec<String>,
+    /// Servers that failed to start.
+    pub errors: ClientStartErrors,
+}
+
+/// A thin wrapper around a set of running [`McpClient`] instances.
+#[derive(Default)]
+pub(crate) struct McpConnectionManager {
+    /// Server-name -> client instance.
+    ///
+    /// The server name originates from the keys of the `mcp_servers` map in
+    /// the user configuration.
+    clients: HashMap<String, ManagedClient>,
+
+    /// Fully qualified tool name -> tool instance.
+    tools: HashMap<String, ToolInfo>,
+
+    /// Server name -> resources and resource templates it exposes.
+    resources: HashMap<String, ServerResources>,
+
+    /// Server name -> prompts it publishes.
+    prompts: HashMap<String, Vec<Prompt>>,
+
+    /// Used to start clients again on reload. `None` for a manager that was
+    /// never started.
+    launcher: Option<ClientLauncher>,
+}
+
+impl McpConnectionManager {
+    /// Spawn a [`McpClient`] for each configured server.
+    ///
+    /// * `mcp_servers` – Map loaded from the user configuration where *keys*
+    ///   are human-readable server identifiers and *values* are the spawn
+    ///   instructions.
+    ///
+    /// * `sampling_tx` – Receives the `sampling/createMessage` requests the
+    ///   servers issue.
+    ///
+    /// Servers that fail to start are reported in `ClientStartErrors`: the
+    /// user should be informed about these errors.
+    pub async fn new(
+        mcp_servers: HashMap<String, McpServerConfig>,
+        use_rmcp_client: bool,
+        store_mode: OAuthCredentialsStoreMode,
+        sampling_tx: mpsc::Sender<McpSamplingRequest>,
+    ) -> Result<(Self, ClientStartErrors)> {
+        let launcher = ClientLauncher {
+            use_rmcp_client,
+            store_mode,
+            sampling_tx,
+        };
+
+        // Early exit if no servers are configured.
+        if mcp_servers.is_empty() {
+            let manager = Self {
+                launcher: Some(launcher),
+                ..Self::default()
+            };
+            return Ok((manager, ClientStartErrors::default()));
+        }
+
+        let (clients, errors) = launcher.start_clients(mcp_servers).await;
+        let mut manager = Self {
+            clients,
+            launcher: Some(launcher),
+            ..Self::default()
+        };
+        manager.refresh_listings().await;
+
+        Ok((manager, errors))
+    }
+
+    /// Build a manager whose clients match `mcp_servers`, reusing the clients
+    /// of `self` that are still configured and responsive. Servers that are no
+    /// longer configured (or were disabled) are left out and shut down once
+    /// `self` is dropped, new ones are started, and servers whose
+    /// configuration changed or that stopped responding are restarted. Tools,
+    /// resources and prompts are listed again for the new manager.
+    ///
+    /// `self` is only read, so callers can keep using it while servers start
+    /// and swap in the result afterwards.
+    pub async fn reload(
+        &self,
+        mcp_servers: HashMap<String, McpServerConfig>,
+    ) -> Result<(Self, McpReloadOutcome)> {
+        let launcher = self
+            .launcher
+            .clone()
+            .ok_or_else(|| anyhow!("MCP connection manager was never started"))?;
+
+        let mut outcome = McpReloadOutcome::default();
+        let unresponsive = unresponsive_servers(&self.clients).await;
+        let mut clients = HashMap::new();
+        let mut to_start = HashMap::new();
+
+        for (server_name, managed) in &self.clients {
+            let cfg = match mcp_servers.get(server_name) {
+                Some(cfg) if cfg.enabled => cfg,
+                _ => {
+                    outcome.stopped.push(server_name.clone());
+                    continue;
+                }
+            };
+            if managed.config != *cfg || unresponsive.contains(server_name) {
+                to_start.insert(server_name.clone(), cfg.clone());
+                outcome.restarted.push(server_name.clone());
+            } else {
+                clients.insert(server_name.clone(), managed.clone());
+            }
+        }
+
+        for (server_name, cfg) in mcp_servers {
+            if !self.clients.contains_key(&server_name) {
+                if cfg.enabled {
+                    outcome.started.push(server_name.clone());
+                }
+                to_start.insert(server_name, cfg);
+            }
+        }
+
+        let (started, errors) = launcher.start_clients(to_start).await;
+        clients.extend(started);
+        outcome
+            .started
+            .retain(|server_name| clients.contains_key(server_name));
+        outcome
+            .restarted
+            .retain(|server_name| clients.contains_key(server_name));
+        outcome.started.sort();
+        outcome.restarted.sort();
+        outcome.stopped.sort();
+        outcome.errors = errors;
+
+        let mut manager = Self {
+            clients,
+            launcher: Some(launcher),
+            ..Self::default()
+        };
+        manager.refresh_listings().await;
+
+        Ok((manager, outcome))
+    }
+
+    /// List the tools, resources and prompts of every running client again.
+    async fn refresh_listings(&mut self) {
+        let all_tools = match list_all_tools(&self.clients).await {
             Ok(tools) => tools,
             Err(e) => {
                 warn!("Failed to list tools from some MCP servers: {e:#}");
@@ -313,9 +597,9 @@ impl McpConnectionManager {
             }
         };
 
-        let tools = qualify_tools(all_tools);
-
-        Ok((Self { clients, tools }, errors))
+        self.tools = qualify_tools(all_tools);
+        self.resources = list_all_resources(&self.clients).await;
+        self.prompts = list_all_prompts(&self.clients).await;
     }
 
     /// Returns a single map that contains **all** tools. Each key is the
@@ -352,6 +636,118 @@ impl McpConnectionManager {
             .get(tool_name)
             .map(|tool| (tool.server_name.clone(), tool.tool_name.clone()))
     }
+
+    /// Returns true when at least one server advertises the `resources`
+    /// capability.
+    pub fn has_resource_servers(&self) -> bool {
+        self.clients
+            .values()
+            .any(|managed| managed.capabilities.resources.is_some())
+    }
+
+    /// Server name -> resources listed by that server at startup.
+    pub fn list_all_resources(&self) -> HashMap<String, Vec<Resource>> {
+        self.resources
+            .iter()
+            .map(|(server, listed)| (server.clone(), listed.resources.clone()))
+            .collect()
+    }
+
+    /// Server name -> resource templates listed by that server at startup.
+    pub fn list_all_resource_templates(&self) -> HashMap<String, Vec<ResourceTemplate>> {
+        self.resources
+            .iter()
+            .map(|(server, listed)| (server.clone(), listed.templates.clone()))
+            .collect()
+    }
+
+    /// Returns true when `server` listed its resources at startup.
+    pub fn lists_resources(&self, server: &str) -> bool {
+        self.resources.contains_key(server)
+    }
+
+    /// Finds a resource listed by `server` whose name or URI is `name_or_uri`.
+    pub fn find_resource(&self, server: &str, name_or_uri: &str) -> Option<&Resource> {
+        self.resources.get(server).and_then(|listed| {
+            listed
+                .resources
+                .iter()
+                .find(|resource| resource.uri == name_or_uri)
+                .or_else(|| {
+                    listed
+                        .resources
+                        .iter()
+                        .find(|resource| resource.name == name_or_uri)
+                })
+        })
+    }
+
+    /// Read the resource identified by `uri` from `server`.
+    pub async fn read_resource(&self, server: &str, uri: &str) -> Result<ReadResourceResult> {
+        let managed = self
+            .clients
+            .get(server)
+            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
+        let client = managed.client.clone();
+        let timeout = managed.tool_timeout;
+
+        client
+            .read_resource(
+                ReadResourceRequestParams {
+                    uri: uri.to_string(),
+                },
+                timeout,
+            )
+            .await
+            .with_context(|| format!("resource read failed for `{server}` `{uri}`"))
+    }
+
+    /// Server name -> prompts listed by that server at startup.
+    pub fn list_all_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        self.prompts.clone()
+    }
+
+    /// Finds the prompt called `name` listed by `server`.
+    pub fn find_prompt(&self, server: &str, name: &str) -> Option<&Prompt> {
+        self.prompts
+            .get(server)
+            .and_then(|prompts| prompts.iter().find(|prompt| prompt.name == name))
+    }
+
+    /// Render the prompt `name` from `server` with the given arguments.
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<GetPromptResult> {
+        let managed = self
+            .clients
+            .get(server)
+            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
+        let client = managed.client.clone();
+        let timeout = managed.tool_timeout;
+
+        let arguments = (!arguments.is_empty())
+            .then(|| serde_json::to_value(arguments))
+            .transpose()?;
+        client
+            .get_prompt(
+                GetPromptRequestParams {
+                    arguments,
+                    name: name.to_string(),
+                },
+                timeout,
+            )
+            .await
+            .with_context(|| format!("prompt request failed for `{server}:{name}`"))
+    }
+}
+
+#[derive(Default)]
+struct ServerResources {
+    resources: Vec<Resource>,
+    templates: Vec<ResourceTemplate>,
 }
 
 fn resolve_bearer_token(
@@ -435,6 +831,249 @@ async fn list_all_tools(clients: &HashMap<String, ManagedClient>) -> Result<Vec<
     Ok(aggregated)
 }
 
+/// Names of the servers in `clients` that fail to answer `tools/list`, e.g.
+/// because their process exited.
+async fn unresponsive_servers(clients: &HashMap<String, ManagedClient>) -> HashSet<String> {
+    let mut join_set = JoinSet::new();
+    for (server_name, managed_client) in clients {
+        let server_name_cloned = server_name.clone();
+        let client_clone = managed_client.client.clone();
+        let startup_timeout = managed_client.startup_timeout;
+        join_set.spawn(async move {
+            let res = client_clone.list_tools(None, Some(startup_timeout)).await;
+            (server_name_cloned, res)
+        });
+    }
+
+    let mut unresponsive = HashSet::new();
+    while let Some(join_res) = join_set.join_next().await {
+        match join_res {
+            Ok((_, Ok(_))) => {}
+            Ok((server_name, Err(e))) => {
+                warn!("MCP server '{server_name}' is not responding: {e:#}");
+                unresponsive.insert(server_name);
+            }
+            Err(e) => warn!("Task panic when checking MCP server: {e:#}"),
+        }
+    }
+    unresponsive
+}
+
+/// Query every server that advertises the `resources` capability for its
+/// resources and resource templates. Servers that fail to answer are logged
+/// and left out of the result.
+async fn list_all_resources(
+    clients: &HashMap<String, ManagedClient>,
+) -> HashMap<String, ServerResources> {
+    let mut join_set = JoinSet::new();
+
+    for (server_name, managed_client) in clients {
+        if managed_client.capabilities.resources.is_none() {
+            continue;
+        }
+        let server_name_cloned = server_name.clone();
+        let client_clone = managed_client.client.clone();
+        let startup_timeout = managed_client.startup_timeout;
+        join_set.spawn(async move {
+            let res =
+                list_server_resources(&server_name_cloned, &client_clone, startup_timeout).await;
+            (server_name_cloned, res)
+        });
+    }
+
+    let mut aggregated = HashMap::with_capacity(join_set.len());
+    while let Some(join_res) = join_set.join_next().await {
+        let (server_name, list_result) = match join_res {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("Task panic when listing resources for MCP server: {e:#}");
+                continue;
+            }
+        };
+
+        match list_result {
+            Ok(resources) => {
+                aggregated.insert(server_name, resources);
+            }
+            Err(e) => {
+                warn!("Failed to list resources for MCP server '{server_name}': {e:#}");
+            }
+        }
+    }
+
+    aggregated
+}
+
+/// Fetch every page of `resources/list` and `resources/templates/list`.
+/// Templates are optional, so a server that rejects the templates request
+/// still contributes its resources.
+async fn list_server_resources(
+    server_name: &str,
+    client: &McpClientAdapter,
+    timeout: Duration,
+) -> Result<ServerResources> {
+    let mut listed = ServerResources::default();
+
+    let mut pager = ListPager::new(server_name, "resources/list");
+    let mut cursor = None;
+    loop {
+        let page = client
+            .list_resources(
+                Some(ListResourcesRequestParams {
+                    cursor: cursor.take(),
+                }),
+                Some(timeout),
+            )
+            .await?;
+        listed.resources.extend(page.resources);
+        match pager.next_cursor(page.next_cursor) {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    let mut pager = ListPager::new(server_name, "resources/templates/list");
+    let mut cursor = None;
+    loop {
+        let page = match client
+            .list_resource_templates(
+                Some(ListResourceTemplatesRequestParams {
+                    cursor: cursor.take(),
+                }),
+                Some(timeout),
+            )
+            .await
+        {
+            Ok(page) => page,
+            Err(e) => {
+                info!("MCP server does not list resource templates: {e:#}");
+                break;
+            }
+        };
+        listed.templates.extend(page.resource_templates);
+        match pager.next_cursor(page.next_cursor) {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    Ok(listed)
+}
+
+/// Query every server that advertises the `prompts` capability for its
+/// prompts. Servers that fail to answer are logged and left out of the result.
+async fn list_all_prompts(
+    clients: &HashMap<String, ManagedClient>,
+) -> HashMap<String, Vec<Prompt>> {
+    let mut join_set = JoinSet::new();
+
+    for (server_name, managed_client) in clients {
+        if managed_client.capabilities.prompts.is_none() {
+            continue;
+        }
+        let server_name_cloned = server_name.clone();
+        let client_clone = managed_client.client.clone();
+        let startup_timeout = managed_client.startup_timeout;
+        join_set.spawn(async move {
+            let res =
+                list_server_prompts(&server_name_cloned, &client_clone, startup_timeout).await;
+            (server_name_cloned, res)
+        });
+    }
+
+    let mut aggregated = HashMap::with_capacity(join_set.len());
+    while let Some(join_res) = join_set.join_next().await {
+        let (server_name, list_result) = match join_res {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("Task panic when listing prompts for MCP server: {e:#}");
+                continue;
+            }
+        };
+
+        match list_result {
+            Ok(prompts) => {
+                aggregated.insert(server_name, prompts);
+            }
+            Err(e) => {
+                warn!("Failed to list prompts for MCP server '{server_name}': {e:#}");
+            }
+        }
+    }
+
+    aggregated
+}
+
+/// Fetch every page of `prompts/list`.
+async fn list_server_prompts(
+    server_name: &str,
+    client: &McpClientAdapter,
+    timeout: Duration,
+) -> Result<Vec<Prompt>> {
+    let mut prompts = Vec::new();
+    let mut pager = ListPager::new(server_name, "prompts/list");
+    let mut cursor = None;
+    loop {
+        let page = client
+            .list_prompts(
+                Some(ListPromptsRequestParams {
+                    cursor: cursor.take(),
+                }),
+                Some(timeout),
+            )
+            .await?;
+        prompts.extend(page.prompts);
+        match pager.next_cursor(page.next_cursor) {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    Ok(prompts)
+}
+
+/// Follows `next_cursor` across the pages of one paginated `*/list` method,
+/// keeping what was listed so far when a server repeats a cursor or hands out
+/// more than [`MAX_LIST_PAGES`] pages.
+struct ListPager<'a> {
+    server_name: &'a str,
+    method: &'static str,
+    pages: usize,
+    seen_cursors: HashSet<String>,
+}
+
+impl<'a> ListPager<'a> {
+    fn new(server_name: &'a str, method: &'static str) -> Self {
+        Self {
+            server_name,
+            method,
+            pages: 0,
+            seen_cursors: HashSet::new(),
+        }
+    }
+
+    /// Records a fetched page and returns the cursor of the next one, or
+    /// `None` when listing should stop.
+    fn next_cursor(&mut self, next_cursor: Option<String>) -> Option<String> {
+        self.pages += 1;
+        let next = next_cursor?;
+        let server_name = self.server_name;
+        let method = self.method;
+        if self.pages >= MAX_LIST_PAGES {
+            warn!(
+                "MCP server '{server_name}' returned more than {MAX_LIST_PAGES} pages for {method}; ignoring the rest"
+            );
+            return None;
+        }
+        if !self.seen_cursors.insert(next.clone()) {
+            warn!(
+                "MCP server '{server_name}' repeated cursor '{next}' for {method}; ignoring the rest"
+            );
+            return None;
+        }
+        Some(next)
+    }
+}
+
 fn is_valid_mcp_server_name(server_name: &str) -> bool {
     !server_name.is_empty()
         && server_name
@@ -528,4 +1167,25 @@ mod tests {
             "my_server__yet_another_e1c3987bd9c50b826cbe1687966f79f0c602d19ca"
         );
     }
+
+    #[test]
+    fn list_pager_stops_at_repeated_cursor() {
+        let mut pager = ListPager::new("server", "resources/list");
+        assert_eq!(pager.next_cursor(Some("a".into())), Some("a".into()));
+        assert_eq!(pager.next_cursor(Some("b".into())), Some("b".into()));
+        assert_eq!(pager.next_cursor(Some("a".into())), None);
+    }
+
+    #[test]
+    fn list_pager_stops_at_page_limit() {
+        let mut pager = ListPager::new("server", "prompts/list");
+        let mut pages = 1;
+        while pager.next_cursor(Some(format!("cursor-{pages}"))).is_some() {
+            pages += 1;
+        }
+        assert_eq!(pages, MAX_LIST_PAGES);
+
+        let mut pager = ListPager::new("server", "prompts/list");
+        assert_eq!(pager.next_cursor(None), None);
+    }
 }
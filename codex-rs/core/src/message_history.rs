@@ -13,11 +13,22 @@
 //! trailing `\n`) and write it with a **single `write(2)` system call** while
 //! the file descriptor is opened with the `O_APPEND` flag. POSIX guarantees
 //! that writes up to `PIPE_BUF` bytes are atomic in that case.
+//!
+//! When `history.max_bytes` is configured, the append that pushes the file
+//! past the limit also drops the oldest entries, still under the exclusive
+//! lock, until the file is back under 80% of the limit. The trimmed history is
+//! written to a new file that replaces the old one, so readers never see a
+//! partially written file and the `log_id` handed out for the old file stops
+//! matching.
 
 use std::fs::File;
 use std::fs::OpenOptions;
+use std::io::Read;
 use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
 use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 
 use serde::Deserialize;
@@ -39,6 +50,10 @@ use std::os::unix::fs::PermissionsExt;
 /// Filename that stores the message history inside `~/.codex`.
 const HISTORY_FILENAME: &str = "history.jsonl";
 
+/// A history file over `max_bytes` is trimmed to this percentage of the
+/// limit, so that trimming does not happen again on the very next append.
+const HISTORY_SOFT_CAP_PERCENT: usize = 80;
+
 const MAX_RETRIES: usize = 10;
 const RETRY_SLEEP: Duration = Duration::from_millis(100);
 
@@ -97,28 +112,31 @@ pub(crate) async fn append_entry(
         .map_err(|e| std::io::Error::other(format!("failed to serialise history entry: {e}")))?;
     line.push('\n');
 
-    // Open in append-only mode.
-    let mut options = OpenOptions::new();
-    options.append(true).read(true).create(true);
-    #[cfg(unix)]
-    {
-        options.mode(0o600);
-    }
-
-    let mut history_file = options.open(&path)?;
+    let mut history_file = open_history_file(&path)?;
 
     // Ensure permissions.
     ensure_owner_only_permissions(&history_file).await?;
 
+    let max_bytes = config.history.max_bytes;
+
     // Perform a blocking write under an advisory write lock using std::fs.
     tokio::task::spawn_blocking(move || -> Result<()> {
         // Retry a few times to avoid indefinite blocking when contended.
         for _ in 0..MAX_RETRIES {
             match history_file.try_lock() {
                 Ok(()) => {
+                    // Another process may have replaced the file while trimming
+                    // it; appending to the old one would lose the entry.
+                    if is_replaced(&history_file, &path)? {
+                        history_file = open_history_file(&path)?;
+                        continue;
+                    }
                     // While holding the exclusive lock, write the full line.
                     history_file.write_all(line.as_bytes())?;
                     history_file.flush()?;
+                    if let Some(max_bytes) = max_bytes {
+                        enforce_history_limit(&mut history_file, &path, max_bytes)?;
+                    }
                     return Ok(());
                 }
                 Err(std::fs::TryLockError::WouldBlock) => {
@@ -138,6 +156,76 @@ pub(crate) async fn append_entry(
     Ok(())
 }
 
+/// Opens the history file at `path` in append-only mode, creating it if needed.
+fn open_history_file(path: &Path) -> Result<File> {
+    let mut options = OpenOptions::new();
+    options.append(true).read(true).create(true);
+    #[cfg(unix)]
+    {
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Whether `path` no longer refers to the open `history_file`.
+#[cfg(unix)]
+fn is_replaced(history_file: &File, path: &Path) -> Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let open = history_file.metadata()?;
+    match std::fs::metadata(path) {
+        Ok(current) => Ok(current.ino() != open.ino() || current.dev() != open.dev()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(unix))]
+fn is_replaced(_history_file: &File, _path: &Path) -> Result<bool> {
+    Ok(false)
+}
+
+/// Drops the oldest entries of `history_file` once it is larger than
+/// `max_bytes` by writing the newest ones to a new file that replaces it at
+/// `path`. The newest entry is always kept, even if it alone exceeds the
+/// limit. Must be called while holding the exclusive lock on the file.
+fn enforce_history_limit(history_file: &mut File, path: &Path, max_bytes: usize) -> Result<()> {
+    let len = history_file.metadata()?.len();
+    if len <= max_bytes as u64 {
+        return Ok(());
+    }
+
+    let mut contents = Vec::new();
+    history_file.seek(SeekFrom::Start(0))?;
+    history_file.read_to_end(&mut contents)?;
+
+    let target = max_bytes.saturating_mul(HISTORY_SOFT_CAP_PERCENT) / 100;
+    let mut start = 0;
+    while contents.len() - start > target {
+        match contents[start..].iter().position(|&b| b == b'\n') {
+            Some(idx) if start + idx + 1 < contents.len() => start += idx + 1,
+            _ => break,
+        }
+    }
+
+    let tmp_path = path.with_extension(format!("jsonl.{}.tmp", std::process::id()));
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        options.mode(0o600);
+    }
+    let mut tmp_file = options.open(&tmp_path)?;
+    let written = tmp_file
+        .write_all(&contents[start..])
+        .and_then(|()| tmp_file.sync_all())
+        .and_then(|()| std::fs::rename(&tmp_path, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    written
+}
+
 /// Asynchronously fetch the history file's *identifier* (inode on Unix) and
 /// the current number of entries by counting newline characters.
 pub(crate) async fn history_metadata(config: &Config) -> (u64, usize) {
@@ -284,3 +372,64 @@ async fn ensure_owner_only_permissions(_file: &File) -> Result<()> {
     // For now, on non-Unix, simply succeed.
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn history_file_with(contents: &str) -> (tempfile::TempDir, PathBuf, File) {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join(HISTORY_FILENAME);
+        std::fs::write(&path, contents).expect("write history");
+        let file = open_history_file(&path).expect("open history");
+        (dir, path, file)
+    }
+
+    fn read_back(dir: &tempfile::TempDir) -> String {
+        std::fs::read_to_string(dir.path().join(HISTORY_FILENAME)).expect("read history")
+    }
+
+    #[test]
+    fn history_under_limit_is_untouched() {
+        let (dir, path, mut file) = history_file_with("one\ntwo\n");
+        enforce_history_limit(&mut file, &path, 100).expect("enforce limit");
+        assert_eq!(read_back(&dir), "one\ntwo\n");
+        assert!(!is_replaced(&file, &path).expect("stat history"));
+    }
+
+    #[test]
+    fn history_over_limit_drops_oldest_entries() {
+        let (dir, path, mut file) = history_file_with("aaaaaaaaa\nbbbbbbbbb\nccccccccc\n");
+        // 30 bytes against a limit of 25 trims to at most 20 bytes.
+        enforce_history_limit(&mut file, &path, 25).expect("enforce limit");
+        assert_eq!(read_back(&dir), "bbbbbbbbb\nccccccccc\n");
+        assert_eq!(
+            std::fs::read_dir(dir.path()).expect("list dir").count(),
+            1,
+            "no temporary file is left behind"
+        );
+    }
+
+    #[test]
+    fn history_keeps_newest_entry_even_if_too_large() {
+        let (dir, path, mut file) = history_file_with("old\nthis entry is longer than the limit\n");
+        enforce_history_limit(&mut file, &path, 10).expect("enforce limit");
+        assert_eq!(read_back(&dir), "this entry is longer than the limit\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn trimming_replaces_the_file_and_its_log_id() {
+        use std::os::unix::fs::MetadataExt;
+
+        let (_dir, path, mut file) = history_file_with("aaaaaaaaa\nbbbbbbbbb\nccccccccc\n");
+        let before = std::fs::metadata(&path).expect("stat history").ino();
+        enforce_history_limit(&mut file, &path, 25).expect("enforce limit");
+        assert!(is_replaced(&file, &path).expect("stat history"));
+        assert_ne!(
+            std::fs::metadata(&path).expect("stat history").ino(),
+            before
+        );
+    }
+}
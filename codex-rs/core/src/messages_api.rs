@@ -0,0 +1,969 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::ModelProviderInfo;
+use crate::cassette;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::config::Config;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::error::RetryLimitReachedError;
+use crate::error::UnexpectedResponseError;
+use crate::openai_tools::create_tools_json_for_messages_api;
+use crate::protocol::TokenUsage;
+use crate::util::backoff;
+use bytes::Bytes;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use codex_protocol::models::ResponseItem;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+
+/// Value sent in the `anthropic-version` header unless the provider config
+/// already sets one.
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// The Messages API requires an explicit cap on output tokens, including any
+/// extended thinking budget. Used when neither the prompt nor
+/// `model_max_output_tokens` sets one.
+const DEFAULT_MAX_OUTPUT_TOKENS: u64 = 32_000;
+
+/// Smallest `budget_tokens` the Messages API accepts for extended thinking.
+const MIN_THINKING_BUDGET_TOKENS: u64 = 1_024;
+
+/// Prefix of the ids given to reasoning items produced here. Their
+/// `encrypted_content` holds an Anthropic thinking signature, which only the
+/// Messages API accepts.
+const THINKING_ID_PREFIX: &str = "thinking_";
+
+/// Whether `item` is a reasoning item produced by the Messages API. Such items
+/// must not be sent to other wire APIs, e.g. after a model failover.
+pub(crate) fn is_messages_api_reasoning(item: &ResponseItem) -> bool {
+    matches!(item, ResponseItem::Reasoning { id, .. } if id.starts_with(THINKING_ID_PREFIX))
+}
+
+/// Implementation for the Anthropic Messages API.
+pub(crate) async fn stream_messages(
+    prompt: &Prompt,
+    config: &Config,
+    effort: Option<ReasoningEffortConfig>,
+    client: &reqwest::Client,
+    provider: &ModelProviderInfo,
+    otel_event_manager: &OtelEventManager,
+) -> Result<ResponseStream> {
+    if prompt.output_schema.is_some() {
+        return Err(CodexErr::UnsupportedOperation(
+            "output_schema is not supported for Messages API".to_string(),
+        ));
+    }
+
+    let model_family = &config.model_family;
+    let cassette = config.cassette.as_ref();
+    let full_instructions = prompt.get_full_instructions(model_family);
+    let input = prompt.get_formatted_input();
+    let messages = build_messages(&input);
+    let tools_json = create_tools_json_for_messages_api(&prompt.tools)?;
+    let max_tokens = prompt
+        .max_output_tokens
+        .or(config.model_max_output_tokens)
+        .unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS);
+
+    let mut payload = json!({
+        "model": model_family.slug,
+        "system": full_instructions,
+        "messages": messages,
+        "max_tokens": max_tokens,
+        "stream": true,
+    });
+    if let Some(obj) = payload.as_object_mut() {
+        if !tools_json.is_empty() {
+            obj.insert("tools".to_string(), json!(tools_json));
+        }
+        if let Some(budget_tokens) = effort.and_then(|effort| thinking_budget(effort, max_tokens)) {
+            obj.insert(
+                "thinking".to_string(),
+                json!({"type": "enabled", "budget_tokens": budget_tokens}),
+            );
+        }
+    }
+
+    debug!(
+        "POST to {}: {}",
+        provider.get_full_url(&None),
+        serde_json::to_string_pretty(&payload).unwrap_or_default()
+    );
+
+    if let Some(stream) = cassette::replay(cassette, &payload)? {
+        let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+        tokio::spawn(process_messages_sse(
+            stream,
+            tx_event,
+            provider.stream_idle_timeout(),
+            otel_event_manager.clone(),
+        ));
+        return Ok(ResponseStream { rx_event });
+    }
+
+    let sets_version_header = provider.http_headers.as_ref().is_some_and(|headers| {
+        headers
+            .keys()
+            .any(|name| name.eq_ignore_ascii_case("anthropic-version"))
+    });
+
+    let mut attempt = 0;
+    let max_retries = provider.request_max_retries();
+    loop {
+        attempt += 1;
+
+        let mut req_builder = provider.create_request_builder(client, &None).await?;
+        if !sets_version_header {
+            req_builder = req_builder.header("anthropic-version", ANTHROPIC_VERSION);
+        }
+
+        let res = otel_event_manager
+            .log_request(attempt, || {
+                req_builder
+                    .header(reqwest::header::ACCEPT, "text/event-stream")
+                    .json(&payload)
+                    .send()
+            })
+            .await;
+
+        match res {
+            Ok(resp) if resp.status().is_success() => {
+                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+                let stream = cassette::record(
+                    cassette,
+                    &payload,
+                    resp.bytes_stream().map_err(CodexErr::Reqwest),
+                )?;
+                tokio::spawn(process_messages_sse(
+                    stream,
+                    tx_event,
+                    provider.stream_idle_timeout(),
+                    otel_event_manager.clone(),
+                ));
+                return Ok(ResponseStream { rx_event });
+            }
+            Ok(res) => {
+                let status = res.status();
+                if !(status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()) {
+                    let body = (res.text().await).unwrap_or_default();
+                    return Err(CodexErr::UnexpectedStatus(UnexpectedResponseError {
+                        status,
+                        body,
+                        request_id: None,
+                    }));
+                }
+
+                if attempt > max_retries {
+                    return Err(CodexErr::RetryLimit(RetryLimitReachedError {
+                        status,
+                        request_id: None,
+                    }));
+                }
+
+                let retry_after_secs = res
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|s| s.parse::<u64>().ok());
+
+                let delay = retry_after_secs
+                    .map(|s| Duration::from_millis(s * 1_000))
+                    .unwrap_or_else(|| backoff(attempt));
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                if attempt > max_retries {
+                    return Err(e.into());
+                }
+                let delay = backoff(attempt);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// Maps the configured reasoning effort onto an extended thinking budget that
+/// leaves at least [`MIN_THINKING_BUDGET_TOKENS`] of `max_tokens` for the
+/// answer. `minimal`, or a cap too small to think within, disables thinking.
+fn thinking_budget(effort: ReasoningEffortConfig, max_tokens: u64) -> Option<u64> {
+    let budget = match effort {
+        ReasoningEffortConfig::Minimal => return None,
+        ReasoningEffortConfig::Low => 4_096,
+        ReasoningEffortConfig::Medium => 10_000,
+        ReasoningEffortConfig::High => 24_000,
+    };
+    let budget = budget.min(max_tokens.saturating_sub(MIN_THINKING_BUDGET_TOKENS));
+    (budget >= MIN_THINKING_BUDGET_TOKENS).then_some(budget)
+}
+
+/// Converts the conversation history into the `messages` array. Items are
+/// mapped onto content blocks and consecutive blocks from the same role are
+/// merged, since the API expects user and assistant turns to alternate and
+/// tool results to follow the assistant message carrying the `tool_use`.
+fn build_messages(input: &[ResponseItem]) -> Vec<Value> {
+    let mut messages: Vec<Value> = Vec::new();
+
+    for item in input {
+        let (role, blocks) = match item {
+            ResponseItem::Message { role, content, .. } => {
+                let role = if role == "assistant" {
+                    "assistant"
+                } else {
+                    "user"
+                };
+                (role, content.iter().filter_map(content_block).collect())
+            }
+            ResponseItem::Reasoning {
+                summary,
+                content,
+                encrypted_content,
+                ..
+            } => {
+                // Thinking can only be replayed together with the signature
+                // that accompanied it, which we keep in `encrypted_content`.
+                // Reasoning from other wire APIs carries no such signature.
+                if !is_messages_api_reasoning(item) {
+                    continue;
+                }
+                let Some(signature) = encrypted_content else {
+                    continue;
+                };
+                let mut text = summary
+                    .iter()
+                    .map(|ReasoningItemReasoningSummary::SummaryText { text }| text.as_str())
+                    .collect::<String>();
+                if text.is_empty()
+                    && let Some(content) = content
+                {
+                    for c in content {
+                        match c {
+                            ReasoningItemContent::ReasoningText { text: t }
+                            | ReasoningItemContent::Text { text: t } => text.push_str(t),
+                        }
+                    }
+                }
+                let block = if text.is_empty() {
+                    json!({"type": "redacted_thinking", "data": signature})
+                } else {
+                    json!({"type": "thinking", "thinking": text, "signature": signature})
+                };
+                ("assistant", vec![block])
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => (
+                "assistant",
+                vec![json!({
+                    "type": "tool_use",
+                    "id": call_id,
+                    "name": name,
+                    "input": tool_input(arguments),
+                })],
+            ),
+            // Freeform tools are offered as function tools taking a single
+            // `input` string; see `create_tools_json_for_messages_api`.
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => (
+                "assistant",
+                vec![json!({
+                    "type": "tool_use",
+                    "id": call_id,
+                    "name": name,
+                    "input": {"input": input},
+                })],
+            ),
+            ResponseItem::FunctionCallOutput { call_id, output } => (
+                "user",
+                vec![json!({
+                    "type": "tool_result",
+                    "tool_use_id": call_id,
+                    "content": output.content,
+                    "is_error": output.success == Some(false),
+                })],
+            ),
+            ResponseItem::CustomToolCallOutput { call_id, output } => (
+                "user",
+                vec![json!({
+                    "type": "tool_result",
+                    "tool_use_id": call_id,
+                    "content": output,
+                })],
+            ),
+            // `local_shell` is offered as the `shell` function tool.
+            ResponseItem::LocalShellCall {
+                id,
+                call_id,
+                action: LocalShellAction::Exec(exec),
+                ..
+            } => {
+                let Some(call_id) = call_id.as_ref().or(id.as_ref()) else {
+                    continue;
+                };
+                (
+                    "assistant",
+                    vec![json!({
+                        "type": "tool_use",
+                        "id": call_id,
+                        "name": "shell",
+                        "input": {
+                            "command": exec.command,
+                            "workdir": exec.working_directory,
+                            "timeout_ms": exec.timeout_ms,
+                        },
+                    })],
+                )
+            }
+            ResponseItem::WebSearchCall { .. } | ResponseItem::Other => {
+                // Omit these items from the conversation history.
+                continue;
+            }
+        };
+
+        if blocks.is_empty() {
+            continue;
+        }
+
+        if let Some(last) = messages.last_mut()
+            && last["role"] == role
+            && let Some(Value::Array(existing)) = last.get_mut("content")
+        {
+            existing.extend(blocks);
+        } else {
+            messages.push(json!({"role": role, "content": blocks}));
+        }
+    }
+
+    messages
+}
+
+fn content_block(item: &ContentItem) -> Option<Value> {
+    match item {
+        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+            // Empty text blocks are rejected by the API.
+            (!text.is_empty()).then(|| json!({"type": "text", "text": text}))
+        }
+        ContentItem::InputImage { image_url } => {
+            let source = match image_url
+                .strip_prefix("data:")
+                .and_then(|rest| rest.split_once(";base64,"))
+            {
+                Some((media_type, data)) => {
+                    json!({"type": "base64", "media_type": media_type, "data": data})
+                }
+                None => json!({"type": "url", "url": image_url}),
+            };
+            Some(json!({"type": "image", "source": source}))
+        }
+    }
+}
+
+/// `tool_use.input` must be an object, whereas function call arguments are
+/// recorded as the raw JSON string the model produced.
+fn tool_input(arguments: &str) -> Value {
+    serde_json::from_str::<Value>(arguments)
+        .ok()
+        .filter(Value::is_object)
+        .unwrap_or_else(|| json!({}))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    MessageStart {
+        message: MessageStart,
+    },
+    ContentBlockStart {
+        index: u64,
+        content_block: ContentBlockStart,
+    },
+    ContentBlockDelta {
+        index: u64,
+        delta: ContentBlockDelta,
+    },
+    ContentBlockStop {
+        index: u64,
+    },
+    MessageDelta {
+        #[serde(default)]
+        usage: Option<Usage>,
+    },
+    MessageStop,
+    Error {
+        error: StreamError,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageStart {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlockStart {
+    Text {
+        #[serde(default)]
+        text: String,
+    },
+    Thinking {
+        #[serde(default)]
+        thinking: String,
+        #[serde(default)]
+        signature: String,
+    },
+    RedactedThinking {
+        data: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        #[serde(default)]
+        input: Option<Value>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlockDelta {
+    TextDelta {
+        text: String,
+    },
+    ThinkingDelta {
+        thinking: String,
+    },
+    SignatureDelta {
+        signature: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Usage {
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+    cache_creation_input_tokens: Option<u64>,
+    cache_read_input_tokens: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamError {
+    #[serde(default)]
+    message: String,
+}
+
+/// A content block that is still receiving deltas.
+enum PendingBlock {
+    Text(String),
+    Thinking {
+        text: String,
+        signature: String,
+    },
+    RedactedThinking(String),
+    ToolUse {
+        id: String,
+        name: String,
+        initial_input: Option<Value>,
+        input_json: String,
+    },
+}
+
+impl PendingBlock {
+    /// Converts the finished block into a response item. `id` names reasoning
+    /// items and must start with [`THINKING_ID_PREFIX`].
+    fn into_response_item(self, id: String) -> Option<ResponseItem> {
+        match self {
+            PendingBlock::Text(text) if text.is_empty() => None,
+            PendingBlock::Text(text) => Some(ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText { text }],
+            }),
+            PendingBlock::Thinking { text, signature } => Some(ResponseItem::Reasoning {
+                id,
+                summary: vec![ReasoningItemReasoningSummary::SummaryText { text }],
+                content: None,
+                encrypted_content: (!signature.is_empty()).then_some(signature),
+            }),
+            PendingBlock::RedactedThinking(data) => Some(ResponseItem::Reasoning {
+                id,
+                summary: Vec::new(),
+                content: None,
+                encrypted_content: Some(data),
+            }),
+            PendingBlock::ToolUse {
+                id,
+                name,
+                initial_input,
+                input_json,
+            } => {
+                let arguments = if input_json.is_empty() {
+                    initial_input
+                        .map(|input| input.to_string())
+                        .unwrap_or_else(|| "{}".to_string())
+                } else {
+                    input_json
+                };
+                Some(ResponseItem::FunctionCall {
+                    id: None,
+                    name,
+                    arguments,
+                    call_id: id,
+                })
+            }
+        }
+    }
+}
+
+/// Token counts reported across `message_start` and `message_delta`. The
+/// Messages API reports cache reads and writes separately from
+/// `input_tokens`, whereas [`TokenUsage::input_tokens`] includes them.
+#[derive(Default)]
+struct UsageState {
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_input_tokens: u64,
+    cache_read_input_tokens: u64,
+}
+
+impl UsageState {
+    fn update(&mut self, usage: Usage) {
+        if let Some(v) = usage.input_tokens {
+            self.input_tokens = v;
+        }
+        if let Some(v) = usage.output_tokens {
+            self.output_tokens = v;
+        }
+        if let Some(v) = usage.cache_creation_input_tokens {
+            self.cache_creation_input_tokens = v;
+        }
+        if let Some(v) = usage.cache_read_input_tokens {
+            self.cache_read_input_tokens = v;
+        }
+    }
+
+    fn token_usage(&self) -> TokenUsage {
+        let input_tokens =
+            self.input_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens;
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens: self.cache_read_input_tokens,
+            output_tokens: self.output_tokens,
+            reasoning_output_tokens: 0,
+            total_tokens: input_tokens + self.output_tokens,
+        }
+    }
+}
+
+/// SSE processor for the Messages streaming format. Each content block is
+/// forwarded as deltas while it streams and as a single
+/// [`ResponseEvent::OutputItemDone`] once `content_block_stop` arrives, so
+/// the rest of the pipeline sees the same shape as the Responses API.
+async fn process_messages_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent>>,
+    idle_timeout: Duration,
+    otel_event_manager: OtelEventManager,
+) where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+
+    let mut response_id = String::new();
+    let mut usage = UsageState::default();
+    let mut pending: HashMap<u64, PendingBlock> = HashMap::new();
+    let mut thinking_blocks = 0usize;
+
+    loop {
+        let start = std::time::Instant::now();
+        let response = timeout(idle_timeout, stream.next()).await;
+        let duration = start.elapsed();
+        otel_event_manager.log_sse_event(&response, duration);
+
+        let sse = match response {
+            Ok(Some(Ok(ev))) => ev,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(e.to_string(), None)))
+                    .await;
+                return;
+            }
+            Ok(None) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "stream closed before message_stop".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "idle timeout waiting for SSE".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+        };
+
+        let event: StreamEvent = match serde_json::from_str(&sse.data) {
+            Ok(event) => event,
+            Err(e) => {
+                debug!(
+                    "failed to parse Messages SSE event: {e}, data: {}",
+                    &sse.data
+                );
+                continue;
+            }
+        };
+        trace!("messages received SSE event: {event:?}");
+
+        match event {
+            StreamEvent::MessageStart { message } => {
+                response_id = message.id;
+                if let Some(u) = message.usage {
+                    usage.update(u);
+                }
+            }
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block,
+            } => {
+                let block = match content_block {
+                    ContentBlockStart::Text { text } => {
+                        if !text.is_empty() {
+                            let _ = tx_event
+                                .send(Ok(ResponseEvent::OutputTextDelta(text.clone())))
+                                .await;
+                        }
+                        PendingBlock::Text(text)
+                    }
+                    ContentBlockStart::Thinking {
+                        thinking,
+                        signature,
+                    } => {
+                        if thinking_blocks > 0 {
+                            let _ = tx_event
+                                .send(Ok(ResponseEvent::ReasoningSummaryPartAdded))
+                                .await;
+                        }
+                        thinking_blocks += 1;
+                        if !thinking.is_empty() {
+                            let _ = tx_event
+                                .send(Ok(ResponseEvent::ReasoningSummaryDelta(thinking.clone())))
+                                .await;
+                        }
+                        PendingBlock::Thinking {
+                            text: thinking,
+                            signature,
+                        }
+                    }
+                    ContentBlockStart::RedactedThinking { data } => {
+                        PendingBlock::RedactedThinking(data)
+                    }
+                    ContentBlockStart::ToolUse { id, name, input } => PendingBlock::ToolUse {
+                        id,
+                        name,
+                        initial_input: input
+                            .filter(|v| v.as_object().is_some_and(|o| !o.is_empty())),
+                        input_json: String::new(),
+                    },
+                    ContentBlockStart::Other => continue,
+                };
+                pending.insert(index, block);
+            }
+            StreamEvent::ContentBlockDelta { index, delta } => {
+                let Some(block) = pending.get_mut(&index) else {
+                    continue;
+                };
+                match (block, delta) {
+                    (PendingBlock::Text(text), ContentBlockDelta::TextDelta { text: delta }) => {
+                        text.push_str(&delta);
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputTextDelta(delta)))
+                            .await;
+                    }
+                    (
+                        PendingBlock::Thinking { text, .. },
+                        ContentBlockDelta::ThinkingDelta { thinking },
+                    ) => {
+                        text.push_str(&thinking);
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::ReasoningSummaryDelta(thinking)))
+                            .await;
+                    }
+                    (
+                        PendingBlock::Thinking { signature, .. },
+                        ContentBlockDelta::SignatureDelta { signature: delta },
+                    ) => {
+                        signature.push_str(&delta);
+                    }
+                    (
+                        PendingBlock::ToolUse { input_json, .. },
+                        ContentBlockDelta::InputJsonDelta { partial_json },
+                    ) => {
+                        input_json.push_str(&partial_json);
+                    }
+                    _ => {}
+                }
+            }
+            StreamEvent::ContentBlockStop { index } => {
+                let id = format!("{THINKING_ID_PREFIX}{response_id}_{index}");
+                if let Some(item) = pending
+                    .remove(&index)
+                    .and_then(|block| block.into_response_item(id))
+                {
+                    let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+                }
+            }
+            StreamEvent::MessageDelta { usage: Some(u) } => usage.update(u),
+            StreamEvent::MessageDelta { usage: None } => {}
+            StreamEvent::MessageStop => {
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage: Some(usage.token_usage()),
+                    }))
+                    .await;
+                return;
+            }
+            StreamEvent::Error { error } => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(error.message, None)))
+                    .await;
+                return;
+            }
+            StreamEvent::Other => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::models::LocalShellExecAction;
+    use codex_protocol::models::LocalShellStatus;
+    use pretty_assertions::assert_eq;
+
+    fn user_text(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn merges_parallel_tool_calls_and_results() {
+        let input = vec![
+            user_text("hi"),
+            ResponseItem::Reasoning {
+                id: "thinking_msg_1_0".to_string(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "plan".to_string(),
+                }],
+                content: None,
+                encrypted_content: Some("sig".to_string()),
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: "{\"command\":[\"ls\"]}".to_string(),
+                call_id: "toolu_1".to_string(),
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "read_file".to_string(),
+                arguments: "not json".to_string(),
+                call_id: "toolu_2".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "toolu_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "a.txt".to_string(),
+                    success: Some(true),
+                },
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "toolu_2".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "boom".to_string(),
+                    success: Some(false),
+                },
+            },
+        ];
+
+        assert_eq!(
+            build_messages(&input),
+            vec![
+                json!({"role": "user", "content": [{"type": "text", "text": "hi"}]}),
+                json!({"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "plan", "signature": "sig"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": ["ls"]}},
+                    {"type": "tool_use", "id": "toolu_2", "name": "read_file", "input": {}},
+                ]}),
+                json!({"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "a.txt", "is_error": false},
+                    {"type": "tool_result", "tool_use_id": "toolu_2", "content": "boom", "is_error": true},
+                ]}),
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_images_and_skips_unsigned_reasoning() {
+        let input = vec![
+            ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![
+                    ContentItem::InputImage {
+                        image_url: "data:image/png;base64,AAAA".to_string(),
+                    },
+                    ContentItem::InputImage {
+                        image_url: "https://example.com/cat.png".to_string(),
+                    },
+                    ContentItem::InputText {
+                        text: String::new(),
+                    },
+                ],
+            },
+            ResponseItem::Reasoning {
+                id: "thinking_msg_1_0".to_string(),
+                summary: Vec::new(),
+                content: Some(vec![ReasoningItemContent::ReasoningText {
+                    text: "unsigned".to_string(),
+                }]),
+                encrypted_content: None,
+            },
+            ResponseItem::Reasoning {
+                id: "thinking_msg_1_1".to_string(),
+                summary: Vec::new(),
+                content: None,
+                encrypted_content: Some("opaque".to_string()),
+            },
+        ];
+
+        assert_eq!(
+            build_messages(&input),
+            vec![
+                json!({"role": "user", "content": [
+                    {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "AAAA"}},
+                    {"type": "image", "source": {"type": "url", "url": "https://example.com/cat.png"}},
+                ]}),
+                json!({"role": "assistant", "content": [
+                    {"type": "redacted_thinking", "data": "opaque"},
+                ]}),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_reasoning_from_other_wire_apis() {
+        let reasoning = ResponseItem::Reasoning {
+            id: "rs_1".to_string(),
+            summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                text: "plan".to_string(),
+            }],
+            content: None,
+            encrypted_content: Some("gAAAA".to_string()),
+        };
+
+        assert!(!is_messages_api_reasoning(&reasoning));
+        assert_eq!(
+            build_messages(&[user_text("hi"), reasoning]),
+            vec![json!({"role": "user", "content": [{"type": "text", "text": "hi"}]})]
+        );
+    }
+
+    #[test]
+    fn maps_custom_and_local_shell_calls_to_tool_use() {
+        let input = vec![
+            ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "toolu_1".to_string(),
+                name: "apply_patch".to_string(),
+                input: "*** Begin Patch".to_string(),
+            },
+            ResponseItem::LocalShellCall {
+                id: None,
+                call_id: Some("toolu_2".to_string()),
+                status: LocalShellStatus::Completed,
+                action: LocalShellAction::Exec(LocalShellExecAction {
+                    command: vec!["ls".to_string()],
+                    timeout_ms: None,
+                    working_directory: None,
+                    env: None,
+                    user: None,
+                }),
+            },
+        ];
+
+        assert_eq!(
+            build_messages(&input),
+            vec![json!({"role": "assistant", "content": [
+                {"type": "tool_use", "id": "toolu_1", "name": "apply_patch", "input": {"input": "*** Begin Patch"}},
+                {"type": "tool_use", "id": "toolu_2", "name": "shell", "input": {"command": ["ls"], "workdir": null, "timeout_ms": null}},
+            ]})]
+        );
+    }
+
+    #[test]
+    fn thinking_budget_fits_within_max_tokens() {
+        assert_eq!(
+            thinking_budget(ReasoningEffortConfig::High, 32_000),
+            Some(24_000)
+        );
+        assert_eq!(
+            thinking_budget(ReasoningEffortConfig::High, 8_192),
+            Some(7_168)
+        );
+        assert_eq!(thinking_budget(ReasoningEffortConfig::Low, 2_000), None);
+        assert_eq!(
+            thinking_budget(ReasoningEffortConfig::Minimal, 32_000),
+            None
+        );
+    }
+}
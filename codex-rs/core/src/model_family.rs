@@ -36,7 +36,8 @@ pub struct ModelFamily {
     pub uses_local_shell_tool: bool,
 
     /// Whether this model supports parallel tool calls when using the
-    /// Responses API.
+    /// Responses API. Parallel tool calls are requested from other models too
+    /// when a read-only tool is offered.
     pub supports_parallel_tool_calls: bool,
 
     /// Present if the model performs better when `apply_patch` is provided as
@@ -119,6 +120,7 @@ pub fn find_family_for_model(mut slug: &str) -> Option<ModelFamily> {
             reasoning_summary_format: ReasoningSummaryFormat::Experimental,
             base_instructions: GPT_5_CODEX_INSTRUCTIONS.to_string(),
             experimental_supported_tools: vec![
+                "code_outline".to_string(),
                 "grep_files".to_string(),
                 "list_dir".to_string(),
                 "read_file".to_string(),
@@ -136,6 +138,7 @@ pub fn find_family_for_model(mut slug: &str) -> Option<ModelFamily> {
             base_instructions: GPT_5_CODEX_INSTRUCTIONS.to_string(),
             apply_patch_tool_type: Some(ApplyPatchToolType::Freeform),
             experimental_supported_tools: vec![
+                "code_outline".to_string(),
                 "grep_files".to_string(),
                 "list_dir".to_string(),
                 "read_file".to_string(),
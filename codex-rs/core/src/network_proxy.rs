@@ -0,0 +1,321 @@
+//! Local HTTP proxy that gives sandboxed commands network access to the
+//! domains listed in the sandbox policy's `allowed_domains`, and nothing else.
+//!
+//! The proxy listens on a Unix socket. `codex-linux-sandbox` runs the command
+//! in a network namespace of its own, where the only route out is a loopback
+//! port forwarded to that socket, and points `HTTP_PROXY`/`HTTPS_PROXY` at it.
+//!
+//! Both `CONNECT host:port` (used for HTTPS) and absolute-form plain HTTP
+//! requests are supported. Requests for any other host get a `403` and are
+//! recorded so they can be reported along with the command's output.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use tempfile::TempDir;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::net::UnixListener;
+use tokio::net::UnixStream;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// Request heads larger than this are rejected.
+const MAX_REQUEST_HEAD_BYTES: usize = 16 * 1024;
+
+const FORBIDDEN_RESPONSE: &[u8] = b"HTTP/1.1 403 Forbidden\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\nThis host is not in the sandbox's allowed_domains.\n";
+const BAD_REQUEST_RESPONSE: &[u8] =
+    b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const BAD_GATEWAY_RESPONSE: &[u8] =
+    b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const CONNECTION_ESTABLISHED_RESPONSE: &[u8] = b"HTTP/1.1 200 Connection Established\r\n\r\n";
+
+/// A running proxy. It stops accepting connections when dropped.
+pub(crate) struct NetworkProxy {
+    socket_path: PathBuf,
+    denied_hosts: Arc<Mutex<Vec<String>>>,
+    accept_task: JoinHandle<()>,
+    _socket_dir: TempDir,
+}
+
+impl NetworkProxy {
+    /// Starts a proxy that only lets connections to `allowed_domains` through.
+    pub(crate) fn start(allowed_domains: &[String]) -> io::Result<Self> {
+        let socket_dir = tempfile::Builder::new()
+            .prefix("codex-network-proxy")
+            .tempdir()?;
+        let socket_path = socket_dir.path().join("proxy.sock");
+        let listener = UnixListener::bind(&socket_path)?;
+
+        let allowed_domains: Arc<Vec<String>> = Arc::new(
+            allowed_domains
+                .iter()
+                .map(|domain| normalize_host(domain))
+                .collect(),
+        );
+        let denied_hosts = Arc::new(Mutex::new(Vec::new()));
+        let accept_task = tokio::spawn({
+            let denied_hosts = Arc::clone(&denied_hosts);
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let allowed_domains = Arc::clone(&allowed_domains);
+                    let denied_hosts = Arc::clone(&denied_hosts);
+                    tokio::spawn(async move {
+                        if let Err(err) =
+                            handle_connection(stream, &allowed_domains, &denied_hosts).await
+                        {
+                            debug!("network proxy connection failed: {err}");
+                        }
+                    });
+                }
+            }
+        });
+
+        Ok(Self {
+            socket_path,
+            denied_hosts,
+            accept_task,
+            _socket_dir: socket_dir,
+        })
+    }
+
+    pub(crate) fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// The `host:port` pairs refused so far, in the order they were first
+    /// requested.
+    pub(crate) fn denied_hosts(&self) -> Vec<String> {
+        self.denied_hosts
+            .lock()
+            .map(|hosts| hosts.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for NetworkProxy {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ProxyRequest {
+    host: String,
+    port: u16,
+    /// For plain HTTP, the request head rewritten to origin-form, to be sent
+    /// upstream. `None` for `CONNECT`.
+    forward_head: Option<Vec<u8>>,
+}
+
+async fn handle_connection(
+    mut client: UnixStream,
+    allowed_domains: &[String],
+    denied_hosts: &Mutex<Vec<String>>,
+) -> io::Result<()> {
+    let (head, rest) = read_request_head(&mut client).await?;
+    let Some(request) = parse_request(&head) else {
+        return client.write_all(BAD_REQUEST_RESPONSE).await;
+    };
+
+    if !is_domain_allowed(&request.host, allowed_domains) {
+        let denied = format!("{}:{}", request.host, request.port);
+        if let Ok(mut hosts) = denied_hosts.lock()
+            && !hosts.contains(&denied)
+        {
+            hosts.push(denied);
+        }
+        return client.write_all(FORBIDDEN_RESPONSE).await;
+    }
+
+    let mut upstream = match TcpStream::connect((request.host.as_str(), request.port)).await {
+        Ok(upstream) => upstream,
+        Err(err) => {
+            debug!(
+                "network proxy failed to connect to {}:{}: {err}",
+                request.host, request.port
+            );
+            return client.write_all(BAD_GATEWAY_RESPONSE).await;
+        }
+    };
+    match &request.forward_head {
+        Some(forward_head) => upstream.write_all(forward_head).await?,
+        None => client.write_all(CONNECTION_ESTABLISHED_RESPONSE).await?,
+    }
+    upstream.write_all(&rest).await?;
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+/// Reads up to and including the blank line that ends the request head.
+/// Returns the head and any bytes the client already sent after it.
+async fn read_request_head(client: &mut UnixStream) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = client.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed before the request head ended",
+            ));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(end) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            return Ok((buf, rest));
+        }
+        if buf.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head too large",
+            ));
+        }
+    }
+}
+
+fn parse_request(head: &[u8]) -> Option<ProxyRequest> {
+    let head = std::str::from_utf8(head).ok()?;
+    let (request_line, headers) = head.split_once("\r\n")?;
+    let mut parts = request_line.split(' ');
+    let (method, target, version) = (parts.next()?, parts.next()?, parts.next()?);
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_host_port(target)?;
+        return Some(ProxyRequest {
+            host,
+            port: port?,
+            forward_head: None,
+        });
+    }
+
+    let target = target.strip_prefix("http://")?;
+    let (authority, path) = match target.find('/') {
+        Some(index) => target.split_at(index),
+        None => (target, "/"),
+    };
+    let (host, port) = split_host_port(authority)?;
+    // Proxy-specific headers are meant for this proxy, not for the server.
+    let forwarded_headers: String = headers
+        .split_inclusive("\r\n")
+        .filter(|line| !line.to_ascii_lowercase().starts_with("proxy-"))
+        .collect();
+    Some(ProxyRequest {
+        host,
+        port: port.unwrap_or(80),
+        forward_head: Some(
+            format!("{method} {path} {version}\r\n{forwarded_headers}").into_bytes(),
+        ),
+    })
+}
+
+/// Splits `host[:port]`, including bracketed IPv6 literals. The port is `None`
+/// when absent and the whole result is `None` when it is malformed.
+fn split_host_port(authority: &str) -> Option<(String, Option<u16>)> {
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        return None;
+    }
+    let port = match port {
+        Some(port) => Some(port.parse().ok()?),
+        None => None,
+    };
+    Some((normalize_host(host), port))
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Whether `host` is listed in `allowed_domains`, where an entry starting with
+/// `*.` matches every subdomain of the rest (but not the rest itself).
+fn is_domain_allowed(host: &str, allowed_domains: &[String]) -> bool {
+    allowed_domains
+        .iter()
+        .any(|domain| match domain.strip_prefix('*') {
+            Some(suffix) => suffix.starts_with('.') && host.ends_with(suffix),
+            None => host == domain,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn domain_matching_supports_wildcards() {
+        let allowed = vec![
+            "registry.npmjs.org".to_string(),
+            "*.corp.example".to_string(),
+        ];
+        assert!(is_domain_allowed("registry.npmjs.org", &allowed));
+        assert!(is_domain_allowed("crates.corp.example", &allowed));
+        assert!(!is_domain_allowed("corp.example", &allowed));
+        assert!(!is_domain_allowed("evil-registry.npmjs.org", &allowed));
+        assert!(!is_domain_allowed("github.com", &allowed));
+    }
+
+    #[test]
+    fn parses_connect_requests() {
+        let request = parse_request(b"CONNECT Registry.NPMjs.org.:443 HTTP/1.1\r\nHost: x\r\n\r\n");
+        assert_eq!(
+            request,
+            Some(ProxyRequest {
+                host: "registry.npmjs.org".to_string(),
+                port: 443,
+                forward_head: None,
+            })
+        );
+        assert_eq!(parse_request(b"CONNECT example.com HTTP/1.1\r\n\r\n"), None);
+    }
+
+    #[test]
+    fn rewrites_plain_http_requests_to_origin_form() {
+        let request = parse_request(
+            b"GET http://[::1]:8080/simple/ HTTP/1.1\r\nHost: [::1]:8080\r\nProxy-Connection: keep-alive\r\n\r\n",
+        );
+        assert_eq!(
+            request,
+            Some(ProxyRequest {
+                host: "::1".to_string(),
+                port: 8080,
+                forward_head: Some(b"GET /simple/ HTTP/1.1\r\nHost: [::1]:8080\r\n\r\n".to_vec()),
+            })
+        );
+        assert_eq!(
+            parse_request(b"GET https://example.com/ HTTP/1.1\r\n\r\n"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn refuses_and_records_hosts_outside_the_allowlist() -> io::Result<()> {
+        let proxy = NetworkProxy::start(&["allowed.example".to_string()])?;
+        let mut client = UnixStream::connect(proxy.socket_path()).await?;
+        client
+            .write_all(b"CONNECT blocked.example:443 HTTP/1.1\r\n\r\n")
+            .await?;
+        let mut response = String::new();
+        client.read_to_string(&mut response).await?;
+
+        assert!(response.starts_with("HTTP/1.1 403"));
+        assert_eq!(
+            proxy.denied_hosts(),
+            vec!["blocked.example:443".to_string()]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+//! Token prices used to estimate what a session costs.
+//!
+//! The built-in table holds OpenAI list prices and goes stale, so every entry
+//! can be replaced from config.toml via `[[model_pricing]]`.
+
+use serde::Deserialize;
+
+use crate::config_types::ModelPricingOverride;
+use crate::protocol::TokenUsage;
+
+/// Prices in USD per million tokens.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input: f64,
+
+    /// Price of input tokens served from the prompt cache. Defaults to
+    /// `input` when unset.
+    #[serde(default)]
+    pub cached_input: Option<f64>,
+
+    /// Price of output tokens, including reasoning tokens.
+    pub output: f64,
+}
+
+impl ModelPricing {
+    const fn new(input: f64, cached_input: f64, output: f64) -> Self {
+        Self {
+            input,
+            cached_input: Some(cached_input),
+            output,
+        }
+    }
+
+    /// Cost of a single request with the given usage.
+    pub fn cost_usd(&self, usage: &TokenUsage) -> f64 {
+        let cached = usage.cached_input_tokens.min(usage.input_tokens);
+        let uncached = usage.input_tokens - cached;
+        let cached_price = self.cached_input.unwrap_or(self.input);
+        (uncached as f64 * self.input
+            + cached as f64 * cached_price
+            + usage.output_tokens as f64 * self.output)
+            / 1_000_000.0
+    }
+}
+
+/// Looks up the price of `model` on `model_provider_id`. Overrides for the
+/// provider win over provider-agnostic overrides, which win over the
+/// built-in table.
+pub(crate) fn find_model_pricing(
+    overrides: &[ModelPricingOverride],
+    model_provider_id: &str,
+    model: &str,
+) -> Option<ModelPricing> {
+    let for_provider = overrides.iter().find(|entry| {
+        entry.model == model && entry.model_provider.as_deref() == Some(model_provider_id)
+    });
+    let for_any_provider = || {
+        overrides
+            .iter()
+            .find(|entry| entry.model == model && entry.model_provider.is_none())
+    };
+    for_provider
+        .or_else(for_any_provider)
+        .map(|entry| entry.pricing)
+        .or_else(|| builtin_model_pricing(model))
+}
+
+// https://platform.openai.com/docs/pricing
+fn builtin_model_pricing(model: &str) -> Option<ModelPricing> {
+    match model {
+        "o3" => Some(ModelPricing::new(2.0, 0.5, 8.0)),
+        "o4-mini" => Some(ModelPricing::new(1.1, 0.275, 4.4)),
+        "codex-mini-latest" => Some(ModelPricing::new(1.5, 0.375, 6.0)),
+        _ if model.starts_with("gpt-4.1-mini") => Some(ModelPricing::new(0.4, 0.1, 1.6)),
+        _ if model.starts_with("gpt-4.1-nano") => Some(ModelPricing::new(0.1, 0.025, 0.4)),
+        _ if model.starts_with("gpt-4.1") => Some(ModelPricing::new(2.0, 0.5, 8.0)),
+        _ if model.starts_with("gpt-4o-mini") => Some(ModelPricing::new(0.15, 0.075, 0.6)),
+        _ if model.starts_with("gpt-4o") => Some(ModelPricing::new(2.5, 1.25, 10.0)),
+        _ if model.starts_with("gpt-5-mini") => Some(ModelPricing::new(0.25, 0.025, 2.0)),
+        _ if model.starts_with("gpt-5-nano") => Some(ModelPricing::new(0.05, 0.005, 0.4)),
+        _ if model.starts_with("gpt-5") => Some(ModelPricing::new(1.25, 0.125, 10.0)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn pricing_override(model_provider: Option<&str>, input: f64) -> ModelPricingOverride {
+        ModelPricingOverride {
+            model: "gpt-5".to_string(),
+            model_provider: model_provider.map(str::to_string),
+            pricing: ModelPricing {
+                input,
+                cached_input: None,
+                output: 1.0,
+            },
+        }
+    }
+
+    #[test]
+    fn cost_charges_cached_input_at_the_cached_rate() {
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            cached_input_tokens: 400_000,
+            output_tokens: 100_000,
+            reasoning_output_tokens: 50_000,
+            total_tokens: 1_100_000,
+        };
+
+        let cost = ModelPricing::new(1.25, 0.125, 10.0).cost_usd(&usage);
+
+        // 600k uncached + 400k cached input, 100k output.
+        assert!((cost - (0.75 + 0.05 + 1.0)).abs() < 1e-9, "{cost}");
+    }
+
+    #[test]
+    fn overrides_prefer_the_matching_provider() {
+        let overrides = vec![
+            pricing_override(None, 2.0),
+            pricing_override(Some("azure"), 3.0),
+        ];
+
+        let price = |provider: &str| find_model_pricing(&overrides, provider, "gpt-5");
+
+        assert_eq!(price("azure").map(|p| p.input), Some(3.0));
+        assert_eq!(price("openai").map(|p| p.input), Some(2.0));
+        assert_eq!(
+            find_model_pricing(&[], "openai", "gpt-5-codex").map(|p| p.input),
+            Some(1.25)
+        );
+        assert_eq!(find_model_pricing(&[], "oss", "gpt-oss:20b"), None);
+    }
+}
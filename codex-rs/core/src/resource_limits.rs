@@ -0,0 +1,272 @@
+//! Enforcement of `[resource_limits]` for shell tool calls, and detection of
+//! the limit a command ran into so it can be reported to the model.
+//!
+//! Unsandboxed and Seatbelt commands get the limits as `setrlimit(2)` limits
+//! applied right before exec. `codex-linux-sandbox` enforces the memory and
+//! process limits through a cgroup v2 of its own when it can create one, and
+//! reports [`RESOURCE_LIMIT_EXCEEDED_MARKER`] when the cgroup stopped the
+//! command. The process limit has no `setrlimit(2)` fallback, since
+//! `RLIMIT_NPROC` counts every process of the user rather than those of the
+//! command.
+
+use std::fmt;
+#[cfg(unix)]
+use std::io;
+
+use crate::config_types::ResourceLimits;
+
+/// Reported by `codex-linux-sandbox` on
+/// [`SANDBOX_REPORT_FD`](crate::landlock::SANDBOX_REPORT_FD), followed by
+/// [`ResourceLimitKind::as_str`], when the command hit a cgroup limit.
+pub const RESOURCE_LIMIT_EXCEEDED_MARKER: &str = "codex-linux-sandbox: resource limit exceeded: ";
+
+/// Seconds of CPU time a command gets after `SIGXCPU` before the kernel
+/// kills it.
+#[cfg(unix)]
+const CPU_HARD_LIMIT_GRACE_SECONDS: u64 = 5;
+
+/// A limit from [`ResourceLimits`] that a command exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceLimitKind {
+    Memory,
+    CpuTime,
+    Processes,
+    FileSize,
+}
+
+impl ResourceLimitKind {
+    /// Stable identifier, as printed after [`RESOURCE_LIMIT_EXCEEDED_MARKER`].
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ResourceLimitKind::Memory => "memory",
+            ResourceLimitKind::CpuTime => "cpu_time",
+            ResourceLimitKind::Processes => "processes",
+            ResourceLimitKind::FileSize => "file_size",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "memory" => Some(ResourceLimitKind::Memory),
+            "cpu_time" => Some(ResourceLimitKind::CpuTime),
+            "processes" => Some(ResourceLimitKind::Processes),
+            "file_size" => Some(ResourceLimitKind::FileSize),
+            _ => None,
+        }
+    }
+
+    /// Name of the setting under `[resource_limits]` that sets this limit.
+    pub fn config_key(self) -> &'static str {
+        match self {
+            ResourceLimitKind::Memory => "max_memory_bytes",
+            ResourceLimitKind::CpuTime => "max_cpu_seconds",
+            ResourceLimitKind::Processes => "max_processes",
+            ResourceLimitKind::FileSize => "max_file_size_bytes",
+        }
+    }
+
+    /// The limit the kernel signals a process with when it exceeds it.
+    #[cfg(unix)]
+    fn from_signal(signal: i32) -> Option<Self> {
+        match signal {
+            libc::SIGXCPU => Some(ResourceLimitKind::CpuTime),
+            libc::SIGXFSZ => Some(ResourceLimitKind::FileSize),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn from_signal(_signal: i32) -> Option<Self> {
+        None
+    }
+
+    fn is_set(self, limits: &ResourceLimits) -> bool {
+        match self {
+            ResourceLimitKind::Memory => limits.max_memory_bytes.is_some(),
+            ResourceLimitKind::CpuTime => limits.max_cpu_seconds.is_some(),
+            ResourceLimitKind::Processes => limits.max_processes.is_some(),
+            ResourceLimitKind::FileSize => limits.max_file_size_bytes.is_some(),
+        }
+    }
+}
+
+impl fmt::Display for ResourceLimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ResourceLimitKind::Memory => "memory",
+            ResourceLimitKind::CpuTime => "CPU time",
+            ResourceLimitKind::Processes => "process count",
+            ResourceLimitKind::FileSize => "file size",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Determines which configured limit, if any, made a command fail, from the
+/// [`RESOURCE_LIMIT_EXCEEDED_MARKER`] line in the report of
+/// `codex-linux-sandbox` or the signal the kernel sends for CPU time and file
+/// size (exit code `128 + signal`).
+///
+/// The output of the command is not consulted: programs print "out of
+/// memory" or "Resource temporarily unavailable" for plenty of other reasons.
+/// A memory limit enforced with `setrlimit(2)` therefore goes unreported.
+pub fn detect_exceeded_limit(
+    limits: &ResourceLimits,
+    exit_code: i32,
+    sandbox_report: &str,
+) -> Option<ResourceLimitKind> {
+    if limits.is_unlimited() || exit_code == 0 {
+        return None;
+    }
+
+    let reported = sandbox_report
+        .lines()
+        .filter_map(|line| line.strip_prefix(RESOURCE_LIMIT_EXCEEDED_MARKER))
+        .find_map(|kind| ResourceLimitKind::parse(kind.trim()))
+        .filter(|kind| kind.is_set(limits));
+    if reported.is_some() {
+        return reported;
+    }
+
+    exit_code
+        .checked_sub(128)
+        .and_then(ResourceLimitKind::from_signal)
+        .filter(|kind| kind.is_set(limits))
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type RlimitResource = libc::__rlimit_resource_t;
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type RlimitResource = libc::c_int;
+
+/// Applies `limits` to the current process with `setrlimit(2)`. The limits
+/// are inherited across `fork` and `exec`.
+///
+/// `max_processes` is not applied: only a cgroup can count the processes of
+/// the command alone, see [`ensure_process_limit_enforceable`].
+///
+/// Only performs system calls, so it may be called between `fork` and `exec`.
+#[cfg(unix)]
+pub fn apply_rlimits(limits: &ResourceLimits) -> io::Result<()> {
+    if let Some(bytes) = limits.max_memory_bytes {
+        set_rlimit(libc::RLIMIT_AS, bytes, bytes)?;
+    }
+    if let Some(seconds) = limits.max_cpu_seconds {
+        // The soft limit sends SIGXCPU, which lets us tell the limit apart
+        // from other kills; the hard limit is the backstop for commands that
+        // handle it.
+        set_rlimit(
+            libc::RLIMIT_CPU,
+            seconds,
+            seconds.saturating_add(CPU_HARD_LIMIT_GRACE_SECONDS),
+        )?;
+    }
+    if let Some(bytes) = limits.max_file_size_bytes {
+        set_rlimit(libc::RLIMIT_FSIZE, bytes, bytes)?;
+    }
+    Ok(())
+}
+
+/// Fails when `limits` sets `max_processes` for a command that does not run
+/// in the Linux sandbox, the only place it can be enforced.
+pub fn ensure_process_limit_enforceable(
+    limits: &ResourceLimits,
+    linux_sandbox: bool,
+) -> Result<(), String> {
+    if limits.max_processes.is_some() && !linux_sandbox {
+        return Err(
+            "`resource_limits.max_processes` is only enforced for commands in the Linux sandbox; \
+             unset it to run commands outside of it"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Lowers the limit for `resource`, never raising it above the current hard
+/// limit (which an unprivileged process cannot do).
+#[cfg(unix)]
+fn set_rlimit(resource: RlimitResource, soft: u64, hard: u64) -> io::Result<()> {
+    let mut current = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { libc::getrlimit(resource, &mut current) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let hard = (hard as libc::rlim_t).min(current.rlim_max);
+    let limit = libc::rlimit {
+        rlim_cur: (soft as libc::rlim_t).min(hard),
+        rlim_max: hard,
+    };
+    if unsafe { libc::setrlimit(resource, &limit) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn limits() -> ResourceLimits {
+        ResourceLimits {
+            max_memory_bytes: Some(1 << 30),
+            max_cpu_seconds: Some(60),
+            max_processes: None,
+            max_file_size_bytes: Some(1 << 20),
+        }
+    }
+
+    #[test]
+    fn detects_limit_reported_by_linux_sandbox() {
+        let output = format!("building...\n{RESOURCE_LIMIT_EXCEEDED_MARKER}memory\n");
+        assert_eq!(
+            detect_exceeded_limit(&limits(), 137, &output),
+            Some(ResourceLimitKind::Memory)
+        );
+
+        let output = format!("{RESOURCE_LIMIT_EXCEEDED_MARKER}processes\n");
+        assert_eq!(detect_exceeded_limit(&limits(), 137, &output), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn detects_limits_signalled_by_the_kernel() {
+        assert_eq!(
+            detect_exceeded_limit(&limits(), 128 + libc::SIGXCPU, ""),
+            Some(ResourceLimitKind::CpuTime)
+        );
+        assert_eq!(
+            detect_exceeded_limit(&limits(), 128 + libc::SIGXFSZ, ""),
+            Some(ResourceLimitKind::FileSize)
+        );
+    }
+
+    #[test]
+    fn ignores_error_messages_of_the_command() {
+        for output in [
+            "memory allocation of 4294967296 bytes failed\n",
+            "fatal error: out of memory\n",
+            "bash: fork: retry: Resource temporarily unavailable\n",
+            "cp: error writing 'big': File too large\n",
+        ] {
+            assert_eq!(detect_exceeded_limit(&limits(), 1, output), None);
+        }
+    }
+
+    #[test]
+    fn process_limit_requires_the_linux_sandbox() {
+        let limits = ResourceLimits {
+            max_processes: Some(64),
+            ..ResourceLimits::default()
+        };
+        assert!(ensure_process_limit_enforceable(&limits, false).is_err());
+        assert_eq!(ensure_process_limit_enforceable(&limits, true), Ok(()));
+        assert_eq!(
+            ensure_process_limit_enforceable(&ResourceLimits::default(), false),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! zstd compression of finished rollout files.
+//!
+//! A compressed rollout keeps its original file name with a `.zst` suffix
+//! appended (`rollout-…-<uuid>.jsonl.zst`). Everything that reads rollouts
+//! goes through [`read_rollout_text`], so callers never need to care which
+//! form a session is stored in.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Extension appended to the name of a compressed rollout file.
+pub const COMPRESSED_ROLLOUT_EXTENSION: &str = "zst";
+
+/// zstd level used for rollouts; JSONL compresses well even at low levels.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Whether `path` names a compressed rollout file.
+pub fn is_compressed_rollout(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == COMPRESSED_ROLLOUT_EXTENSION)
+}
+
+/// Path the rollout at `path` has once it is compressed.
+pub fn compressed_rollout_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(COMPRESSED_ROLLOUT_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Reads the rollout at `path` as text, decompressing it if needed.
+pub async fn read_rollout_text(path: &Path) -> io::Result<String> {
+    if !is_compressed_rollout(path) {
+        return tokio::fs::read_to_string(path).await;
+    }
+    let compressed = tokio::fs::read(path).await?;
+    let bytes = tokio::task::spawn_blocking(move || zstd::stream::decode_all(&compressed[..]))
+        .await
+        .map_err(io::Error::other)??;
+    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Replaces the plain rollout at `path` with a compressed copy and returns
+/// the path of the compressed file.
+pub(crate) async fn compress_rollout(path: &Path) -> io::Result<PathBuf> {
+    let target = compressed_rollout_path(path);
+    let plain = tokio::fs::read(path).await?;
+    let compressed = tokio::task::spawn_blocking(move || {
+        zstd::stream::encode_all(&plain[..], COMPRESSION_LEVEL)
+    })
+    .await
+    .map_err(io::Error::other)??;
+    write_then_replace(&target, &compressed, path).await?;
+    Ok(target)
+}
+
+/// Replaces the compressed rollout at `path` with its plain form so the
+/// session can be appended to again, and returns the plain file's path.
+pub(crate) async fn decompress_rollout(path: &Path) -> io::Result<PathBuf> {
+    let target = path.with_extension("");
+    let text = read_rollout_text(path).await?;
+    write_then_replace(&target, text.as_bytes(), path).await?;
+    Ok(target)
+}
+
+/// Writes `contents` to `target` through a temporary file and removes
+/// `source` once `target` is in place, so a crash never loses the session.
+async fn write_then_replace(target: &Path, contents: &[u8], source: &Path) -> io::Result<()> {
+    let mut tmp_name = target.as_os_str().to_owned();
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp_path = PathBuf::from(tmp_name);
+    tokio::fs::write(&tmp_path, contents).await?;
+    tokio::fs::rename(&tmp_path, target).await?;
+    tokio::fs::remove_file(source).await
+}
@@ -0,0 +1,440 @@
+//! Append-only index of recorded sessions, kept at `sessions/index.jsonl`.
+//!
+//! Every line is a complete [`ConversationItem`] and the last line for an id
+//! wins. The rollout writer appends a line whenever the summary of its session
+//! changes, so listing sessions rarely has to open rollout files. A missing
+//! index is rebuilt from the rollout files on disk, rollout files missing from
+//! the index are added to it, and readers rewrite the index once superseded
+//! lines dominate it. Appends and rewrites hold an advisory lock on
+//! `sessions/index.jsonl.lock` so no process loses another's lines.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ConversationId;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::InputMessageKind;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionMetaLine;
+use codex_protocol::protocol::SessionSource;
+use codex_protocol::protocol::USER_MESSAGE_BEGIN;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use super::SESSIONS_SUBDIR;
+use super::compression::read_rollout_text;
+use super::list::parse_timestamp_uuid_from_filename;
+
+pub const SESSION_INDEX_FILE: &str = "index.jsonl";
+
+/// The index is replaced by renaming a new file over it, so the lock that
+/// serializes changes lives in a file of its own.
+const SESSION_INDEX_LOCK_FILE: &str = "index.jsonl.lock";
+
+/// Previews longer than this many characters are truncated.
+const PREVIEW_MAX_CHARS: usize = 500;
+
+/// The index is rewritten when it holds more than this many lines per session.
+const MAX_LINES_PER_SESSION: usize = 4;
+
+/// Summary of a recorded session, as stored in the session index.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConversationItem {
+    pub id: ConversationId,
+    /// Absolute path to the rollout file.
+    pub path: PathBuf,
+    #[serde(default)]
+    pub source: SessionSource,
+    /// Working directory the session was started in.
+    pub cwd: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    /// First message the user typed, or `None` until they send one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+    /// RFC3339 timestamp string for when the session was created.
+    pub created_at: String,
+    /// RFC3339 timestamp string for the most recent recorded activity.
+    pub updated_at: String,
+    /// Tokens used by the session so far.
+    #[serde(default)]
+    pub total_tokens: u64,
+}
+
+impl ConversationItem {
+    pub(crate) fn new(path: PathBuf, session_meta_line: &SessionMetaLine) -> Self {
+        let meta = &session_meta_line.meta;
+        Self {
+            id: meta.id,
+            path,
+            source: meta.source,
+            cwd: meta.cwd.clone(),
+            git_branch: session_meta_line
+                .git
+                .as_ref()
+                .and_then(|git| git.branch.clone()),
+            preview: None,
+            created_at: meta.timestamp.clone(),
+            updated_at: meta.timestamp.clone(),
+            total_tokens: 0,
+        }
+    }
+
+    /// Folds an item recorded at `timestamp` into the summary. Returns whether
+    /// the index needs a new line; `updated_at` alone does not warrant one.
+    pub(crate) fn observe(&mut self, timestamp: &str, item: &RolloutItem) -> bool {
+        self.updated_at = timestamp.to_string();
+        match item {
+            RolloutItem::GitInfo(git) => {
+                let changed = self.git_branch != git.branch;
+                self.git_branch = git.branch.clone();
+                changed
+            }
+            RolloutItem::EventMsg(EventMsg::UserMessage(ev))
+                if self.preview.is_none()
+                    && matches!(ev.kind, None | Some(InputMessageKind::Plain)) =>
+            {
+                // Strip ide context.
+                let message = match ev.message.find(USER_MESSAGE_BEGIN) {
+                    Some(idx) => &ev.message[idx + USER_MESSAGE_BEGIN.len()..],
+                    None => ev.message.as_str(),
+                };
+                let preview: String = message.trim().chars().take(PREVIEW_MAX_CHARS).collect();
+                if preview.is_empty() {
+                    return false;
+                }
+                self.preview = Some(preview);
+                true
+            }
+            RolloutItem::EventMsg(EventMsg::TokenCount(ev)) => {
+                let Some(info) = ev.info.as_ref() else {
+                    return false;
+                };
+                let total_tokens = info.total_token_usage.total_tokens;
+                let changed = self.total_tokens != total_tokens;
+                self.total_tokens = total_tokens;
+                changed
+            }
+            _ => false,
+        }
+    }
+}
+
+fn index_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(SESSIONS_SUBDIR).join(SESSION_INDEX_FILE)
+}
+
+/// Exclusive advisory lock on the index, released when dropped.
+struct IndexLock {
+    _file: std::fs::File,
+}
+
+/// Waits for the exclusive lock on the index of `codex_home`.
+async fn lock_index(codex_home: &Path) -> io::Result<IndexLock> {
+    let path = codex_home
+        .join(SESSIONS_SUBDIR)
+        .join(SESSION_INDEX_LOCK_FILE);
+    tokio::task::spawn_blocking(move || {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+        file.lock()?;
+        Ok(IndexLock { _file: file })
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Appends `item` to the index. Does nothing while the index does not exist
+/// yet: the next reader rebuilds it from the rollout files, this one included.
+/// Waits for a rebuild or rewrite in progress, so the line is never lost to
+/// one.
+pub(crate) async fn append_to_index(codex_home: &Path, item: &ConversationItem) -> io::Result<()> {
+    let mut line = serde_json::to_string(item)?;
+    line.push('\n');
+    let _lock = match lock_index(codex_home).await {
+        Ok(lock) => lock,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    // Reopened for every line so that appends never land in an index that a
+    // reader has since replaced.
+    let mut file = match tokio::fs::OpenOptions::new()
+        .append(true)
+        .open(index_path(codex_home))
+        .await
+    {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    file.write_all(line.as_bytes()).await
+}
+
+/// Returns the latest summary of every indexed session, rebuilding the index
+/// first if it does not exist and indexing rollout files it is missing.
+/// Sessions whose rollout file has since been moved or deleted may still be
+/// included.
+pub(crate) async fn load_index(codex_home: &Path) -> io::Result<Vec<ConversationItem>> {
+    let Some(text) = read_index(codex_home).await? else {
+        return rebuild_index(codex_home).await;
+    };
+    let (items, num_lines) = parse_index(&text);
+    let items = match reconcile_index(codex_home, items).await {
+        Ok(items) => items,
+        Err(e) => {
+            warn!("failed to index missing sessions: {e}");
+            parse_index(&text).0
+        }
+    };
+
+    if num_lines > items.len().max(1) * MAX_LINES_PER_SESSION
+        && let Err(e) = compact_index(codex_home).await
+    {
+        warn!("failed to compact session index: {e}");
+    }
+    Ok(items)
+}
+
+/// Reads the index, or returns `None` if it does not exist.
+async fn read_index(codex_home: &Path) -> io::Result<Option<String>> {
+    match tokio::fs::read_to_string(index_path(codex_home)).await {
+        Ok(text) => Ok(Some(text)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Returns the latest summary of every session in `text` and the number of
+/// lines it took.
+fn parse_index(text: &str) -> (Vec<ConversationItem>, usize) {
+    let mut num_lines = 0usize;
+    let mut latest: HashMap<ConversationId, ConversationItem> = HashMap::new();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        num_lines += 1;
+        match serde_json::from_str::<ConversationItem>(line) {
+            Ok(item) => {
+                latest.insert(item.id, item);
+            }
+            Err(e) => warn!("skipping malformed session index line: {e}"),
+        }
+    }
+    (latest.into_values().collect(), num_lines)
+}
+
+/// Adds the rollout files on disk that `items` does not mention, e.g. sessions
+/// recorded while the index could not be written, or compressed since they
+/// were indexed. Returns `items` with their summaries folded in.
+async fn reconcile_index(
+    codex_home: &Path,
+    items: Vec<ConversationItem>,
+) -> io::Result<Vec<ConversationItem>> {
+    let indexed: HashSet<&Path> = items.iter().map(|item| item.path.as_path()).collect();
+    let missing: Vec<PathBuf> = list_rollout_files(codex_home)
+        .await?
+        .into_iter()
+        .filter(|path| !indexed.contains(path.as_path()))
+        .collect();
+    if missing.is_empty() {
+        return Ok(items);
+    }
+
+    let mut found = Vec::new();
+    for path in missing {
+        match summarize_rollout(&path).await {
+            Ok(Some(item)) => found.push(item),
+            Ok(None) => {}
+            Err(e) => warn!("failed to index {path:?}: {e}"),
+        }
+    }
+
+    let _lock = lock_index(codex_home).await?;
+    // Another process may have indexed these sessions, with newer summaries,
+    // since the index was read.
+    let Some(text) = read_index(codex_home).await? else {
+        return Ok(items);
+    };
+    let mut latest: HashMap<ConversationId, ConversationItem> = parse_index(&text)
+        .0
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect();
+    let mut lines = String::new();
+    for item in found {
+        if let Some(indexed) = latest.get(&item.id)
+            && tokio::fs::try_exists(&indexed.path).await.unwrap_or(false)
+        {
+            continue;
+        }
+        lines.push_str(&serde_json::to_string(&item)?);
+        lines.push('\n');
+        latest.insert(item.id, item);
+    }
+    if !lines.is_empty() {
+        let mut file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(index_path(codex_home))
+            .await?;
+        file.write_all(lines.as_bytes()).await?;
+    }
+    Ok(latest.into_values().collect())
+}
+
+/// Rewrites the index with one line per session whose rollout file still
+/// exists. Re-reads the index under the lock so no appended line is lost.
+async fn compact_index(codex_home: &Path) -> io::Result<()> {
+    let _lock = lock_index(codex_home).await?;
+    let Some(text) = read_index(codex_home).await? else {
+        return Ok(());
+    };
+    let mut live = Vec::new();
+    for item in parse_index(&text).0 {
+        if tokio::fs::try_exists(&item.path).await.unwrap_or(false) {
+            live.push(item);
+        }
+    }
+    write_index(codex_home, &live).await
+}
+
+/// Summarizes a rollout file by replaying all of its records. Returns `None`
+/// if the file does not start a session.
+pub(crate) async fn summarize_rollout(path: &Path) -> io::Result<Option<ConversationItem>> {
+    let text = read_rollout_text(path).await?;
+    let mut summary: Option<ConversationItem> = None;
+    for line in text.lines() {
+        let Ok(RolloutLine { timestamp, item }) = serde_json::from_str::<RolloutLine>(line) else {
+            continue;
+        };
+        match summary.as_mut() {
+            Some(summary) => {
+                summary.observe(&timestamp, &item);
+            }
+            None => {
+                if let RolloutItem::SessionMeta(session_meta_line) = &item {
+                    summary = Some(ConversationItem::new(path.to_path_buf(), session_meta_line));
+                }
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Recreates the index from the rollout files under
+/// `~/.codex/sessions/YYYY/MM/DD/`.
+async fn rebuild_index(codex_home: &Path) -> io::Result<Vec<ConversationItem>> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    if !tokio::fs::try_exists(&root).await? {
+        return Ok(Vec::new());
+    }
+
+    // Appends wait for the lock, so none is lost while the files are read.
+    let _lock = lock_index(codex_home).await?;
+    // Another process may have rebuilt the index while this one waited.
+    if let Some(text) = read_index(codex_home).await? {
+        return Ok(parse_index(&text).0);
+    }
+
+    let mut items = Vec::new();
+    for path in list_rollout_files(codex_home).await? {
+        match summarize_rollout(&path).await {
+            Ok(Some(item)) => items.push(item),
+            Ok(None) => {}
+            Err(e) => warn!("failed to index {path:?}: {e}"),
+        }
+    }
+
+    write_index(codex_home, &items).await?;
+    Ok(items)
+}
+
+/// Lists the rollout files under `~/.codex/sessions/YYYY/MM/DD/`, newest day
+/// first.
+async fn list_rollout_files(codex_home: &Path) -> io::Result<Vec<PathBuf>> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    if !tokio::fs::try_exists(&root).await? {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = Vec::new();
+    for (_year, year_path) in collect_dirs_desc(&root, |s| s.parse::<u16>().ok()).await? {
+        for (_month, month_path) in collect_dirs_desc(&year_path, |s| s.parse::<u8>().ok()).await? {
+            for (_day, day_path) in collect_dirs_desc(&month_path, |s| s.parse::<u8>().ok()).await?
+            {
+                paths.extend(collect_rollout_files(&day_path).await?);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// Atomically replaces the index with one line per item.
+async fn write_index(codex_home: &Path, items: &[ConversationItem]) -> io::Result<()> {
+    let mut contents = String::new();
+    for item in items {
+        contents.push_str(&serde_json::to_string(item)?);
+        contents.push('\n');
+    }
+    let path = index_path(codex_home);
+    let tmp_path = path.with_extension(format!("jsonl.{}.tmp", std::process::id()));
+    tokio::fs::write(&tmp_path, contents).await?;
+    tokio::fs::rename(&tmp_path, &path).await
+}
+
+/// Collects immediate subdirectories of `parent`, parses their (string) names with `parse`,
+/// and returns them sorted descending by the parsed key.
+pub(super) async fn collect_dirs_desc<T, F>(
+    parent: &Path,
+    parse: F,
+) -> io::Result<Vec<(T, PathBuf)>>
+where
+    T: Ord + Copy,
+    F: Fn(&str) -> Option<T>,
+{
+    let mut dir = tokio::fs::read_dir(parent).await?;
+    let mut vec: Vec<(T, PathBuf)> = Vec::new();
+    while let Some(entry) = dir.next_entry().await? {
+        if entry
+            .file_type()
+            .await
+            .map(|ft| ft.is_dir())
+            .unwrap_or(false)
+            && let Some(s) = entry.file_name().to_str()
+            && let Some(v) = parse(s)
+        {
+            vec.push((v, entry.path()));
+        }
+    }
+    vec.sort_by_key(|(v, _)| Reverse(*v));
+    Ok(vec)
+}
+
+/// Collects the rollout files, compressed or not, directly inside `parent`.
+pub(super) async fn collect_rollout_files(parent: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut dir = tokio::fs::read_dir(parent).await?;
+    let mut collected: Vec<PathBuf> = Vec::new();
+    while let Some(entry) = dir.next_entry().await? {
+        if entry
+            .file_type()
+            .await
+            .map(|ft| ft.is_file())
+            .unwrap_or(false)
+            && let Some(s) = entry.file_name().to_str()
+            && parse_timestamp_uuid_from_filename(s).is_some()
+        {
+            collected.push(entry.path());
+        }
+    }
+    Ok(collected)
+}
@@ -7,9 +7,13 @@ pub const ARCHIVED_SESSIONS_SUBDIR: &str = "archived_sessions";
 pub const INTERACTIVE_SESSION_SOURCES: &[SessionSource] =
     &[SessionSource::Cli, SessionSource::VSCode];
 
+pub mod compression;
+pub(crate) mod index;
 pub mod list;
 pub(crate) mod policy;
 pub mod recorder;
+pub mod retention;
+pub mod search;
 
 pub use codex_protocol::protocol::SessionMeta;
 pub use list::find_conversation_path_by_id_str;
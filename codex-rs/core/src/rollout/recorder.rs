@@ -19,10 +19,21 @@ use tracing::info;
 use tracing::warn;
 
 use super::SESSIONS_SUBDIR;
+use super::compression::compress_rollout;
+use super::compression::decompress_rollout;
+use super::compression::is_compressed_rollout;
+use super::compression::read_rollout_text;
+use super::index::ConversationItem;
+use super::index::append_to_index;
+use super::index::summarize_rollout;
+use super::list::ConversationFilter;
 use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
 use super::policy::is_persisted_response_item;
+use super::retention::prune_sessions;
+use super::search::SessionSearchHit;
+use super::search::search_conversations;
 use crate::config::Config;
 use crate::default_client::originator;
 use crate::git_info::collect_git_info;
@@ -97,15 +108,27 @@ impl RolloutRecorder {
         page_size: usize,
         cursor: Option<&Cursor>,
         allowed_sources: &[SessionSource],
+        filter: &ConversationFilter,
     ) -> std::io::Result<ConversationsPage> {
-        get_conversations(codex_home, page_size, cursor, allowed_sources).await
+        get_conversations(codex_home, page_size, cursor, allowed_sources, filter).await
+    }
+
+    /// Search the user messages, assistant messages and shell commands of all
+    /// recorded conversations. Returns at most `limit` hits, best first.
+    pub async fn search_conversations(
+        codex_home: &Path,
+        query: &str,
+        allowed_sources: &[SessionSource],
+        limit: usize,
+    ) -> std::io::Result<Vec<SessionSearchHit>> {
+        search_conversations(codex_home, query, allowed_sources, limit).await
     }
 
     /// Attempt to create a new [`RolloutRecorder`]. If the sessions directory
     /// cannot be created or the rollout file cannot be opened we return the
     /// error so the caller can decide whether to disable persistence.
     pub async fn new(config: &Config, params: RolloutRecorderParams) -> std::io::Result<Self> {
-        let (file, rollout_path, meta) = match params {
+        let (file, rollout_path, meta, index_entry) = match params {
             RolloutRecorderParams::Create {
                 conversation_id,
                 instructions,
@@ -126,6 +149,7 @@ impl RolloutRecorder {
                     .format(timestamp_format)
                     .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))?;
 
+                mark_in_use(&file, &path);
                 (
                     tokio::fs::File::from_std(file),
                     path,
@@ -138,16 +162,26 @@ impl RolloutRecorder {
                         instructions,
                         source,
                     }),
+                    None,
                 )
             }
-            RolloutRecorderParams::Resume { path } => (
-                tokio::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&path)
-                    .await?,
-                path,
-                None,
-            ),
+            RolloutRecorderParams::Resume { path } => {
+                // A compressed rollout is restored to plain JSONL so that the
+                // resumed session can keep appending to it.
+                let was_compressed = is_compressed_rollout(&path);
+                let path = if was_compressed {
+                    decompress_rollout(&path).await?
+                } else {
+                    path
+                };
+                let index_entry = summarize_rollout(&path).await?;
+                if was_compressed && let Some(entry) = index_entry.as_ref() {
+                    update_index(&config.codex_home, entry).await;
+                }
+                let file = std::fs::OpenOptions::new().append(true).open(&path)?;
+                mark_in_use(&file, &path);
+                (tokio::fs::File::from_std(file), path, None, index_entry)
+            }
         };
 
         // Clone the cwd for the spawned task to collect git info asynchronously
@@ -162,7 +196,34 @@ impl RolloutRecorder {
         // writes. Using `tokio::fs::File` keeps everything on the async I/O
         // driver instead of blocking the runtime.
         let should_collect_git = meta.is_some();
-        tokio::task::spawn(rollout_writer(file, rx, meta, cwd.clone()));
+        tokio::task::spawn(rollout_writer(
+            file,
+            rx,
+            meta,
+            rollout_path.clone(),
+            config.codex_home.clone(),
+            index_entry,
+            config.session_retention.compress,
+        ));
+
+        // Starting a session is the natural point to enforce the retention
+        // policy; it runs in the background so startup is not delayed.
+        if should_collect_git && config.session_retention.has_limits() {
+            let codex_home = config.codex_home.clone();
+            let retention = config.session_retention.clone();
+            let keep = rollout_path.clone();
+            tokio::spawn(async move {
+                match prune_sessions(&codex_home, &retention, Some(&keep), false).await {
+                    Ok(report) if !report.removed.is_empty() => info!(
+                        "pruned {} sessions ({} bytes)",
+                        report.removed.len(),
+                        report.removed_bytes()
+                    ),
+                    Ok(_) => {}
+                    Err(e) => warn!("failed to prune sessions: {e}"),
+                }
+            });
+        }
 
         // If we're creating a new session (not resuming), spawn a background task to collect
         // git info asynchronously and write it to the rollout file after SessionMeta.
@@ -173,9 +234,10 @@ impl RolloutRecorder {
             tokio::spawn(async move {
                 if let Some(git_info) = collect_git_info(&cwd).await {
                     // Send the git info to be written as a separate RolloutItem
-                    if let Err(_) = tx_clone
+                    if tx_clone
                         .send(RolloutCmd::AddItems(vec![RolloutItem::GitInfo(git_info)]))
                         .await
+                        .is_err()
                     {
                         tracing::trace!("git info send failed - rollout writer already shutdown");
                     }
@@ -218,7 +280,7 @@ impl RolloutRecorder {
 
     pub(crate) async fn get_rollout_history(path: &Path) -> std::io::Result<InitialHistory> {
         info!("Resuming rollout from {path:?}");
-        let text = tokio::fs::read_to_string(path).await?;
+        let text = read_rollout_text(path).await?;
         if text.trim().is_empty() {
             return Err(IoError::other("empty session file"));
         }
@@ -255,13 +317,13 @@ impl RolloutRecorder {
                         // Merge GitInfo into the SessionMeta if we have one.
                         // GitInfo is written separately after SessionMeta to avoid blocking
                         // session startup on slow git commands.
-                        if let Some(idx) = session_meta_index {
-                            if let Some(RolloutItem::SessionMeta(session_meta)) = items.get_mut(idx) {
-                                // Unconditionally assign git info - last write wins.
-                                // This ensures we always have the most recent git metadata
-                                // if multiple GitInfo items are present (future-proofing).
-                                session_meta.git = Some(git_info);
-                            }
+                        if let Some(idx) = session_meta_index
+                            && let Some(RolloutItem::SessionMeta(session_meta)) = items.get_mut(idx)
+                        {
+                            // Unconditionally assign git info - last write wins.
+                            // This ensures we always have the most recent git metadata
+                            // if multiple GitInfo items are present (future-proofing).
+                            session_meta.git = Some(git_info);
                         }
                         // Don't push GitInfo as a separate item - it's been merged into SessionMeta
                     }
@@ -376,11 +438,22 @@ fn create_log_file(
     })
 }
 
+/// Takes a shared lock on the rollout for as long as `file` stays open, so
+/// that [`prune_sessions`] in other processes leaves it alone.
+fn mark_in_use(file: &File, path: &Path) {
+    if let Err(e) = file.try_lock_shared() {
+        warn!("failed to lock {path:?}: {e}");
+    }
+}
+
 async fn rollout_writer(
     file: tokio::fs::File,
     mut rx: mpsc::Receiver<RolloutCmd>,
     mut meta: Option<SessionMeta>,
-    _cwd: std::path::PathBuf,
+    rollout_path: PathBuf,
+    codex_home: PathBuf,
+    mut index_entry: Option<ConversationItem>,
+    compress_on_shutdown: bool,
 ) -> std::io::Result<()> {
     let mut writer = JsonlWriter { file };
 
@@ -396,21 +469,33 @@ async fn rollout_writer(
             git: None,
         };
 
+        let entry = ConversationItem::new(rollout_path.clone(), &session_meta_line);
+
         // Write the SessionMeta as the first item in the file, wrapped in a rollout line
         writer
             .write_rollout_item(RolloutItem::SessionMeta(session_meta_line))
             .await?;
+        update_index(&codex_home, &entry).await;
+        index_entry = Some(entry);
     }
 
     // Process rollout commands
     while let Some(cmd) = rx.recv().await {
         match cmd {
             RolloutCmd::AddItems(items) => {
+                let mut index_changed = false;
                 for item in items {
                     if is_persisted_response_item(&item) {
-                        writer.write_rollout_item(item).await?;
+                        let timestamp = rollout_timestamp()?;
+                        if let Some(entry) = index_entry.as_mut() {
+                            index_changed |= entry.observe(&timestamp, &item);
+                        }
+                        writer.write_line(&RolloutLine { timestamp, item }).await?;
                     }
                 }
+                if index_changed && let Some(entry) = index_entry.as_ref() {
+                    update_index(&codex_home, entry).await;
+                }
             }
             RolloutCmd::Flush { ack } => {
                 // Ensure underlying file is flushed and then ack.
@@ -421,6 +506,13 @@ async fn rollout_writer(
                 let _ = ack.send(());
             }
             RolloutCmd::Shutdown { ack } => {
+                if compress_on_shutdown {
+                    // Close the file before replacing it with its compressed form.
+                    drop(writer);
+                    compress_finished_rollout(&rollout_path, &codex_home, index_entry).await;
+                    let _ = ack.send(());
+                    return Ok(());
+                }
                 let _ = ack.send(());
             }
         }
@@ -429,21 +521,48 @@ async fn rollout_writer(
     Ok(())
 }
 
+/// Compresses the rollout of a session that has ended and points its index
+/// entry at the compressed file. Failing leaves the plain rollout in place.
+async fn compress_finished_rollout(
+    rollout_path: &Path,
+    codex_home: &Path,
+    index_entry: Option<ConversationItem>,
+) {
+    match compress_rollout(rollout_path).await {
+        Ok(compressed_path) => {
+            if let Some(mut entry) = index_entry {
+                entry.path = compressed_path;
+                update_index(codex_home, &entry).await;
+            }
+        }
+        Err(e) => warn!("failed to compress rollout {rollout_path:?}: {e}"),
+    }
+}
+
+/// The session index only speeds up listing, so failing to update it must not
+/// stop the rollout from being recorded.
+async fn update_index(codex_home: &Path, entry: &ConversationItem) {
+    if let Err(e) = append_to_index(codex_home, entry).await {
+        warn!("failed to update session index: {e}");
+    }
+}
+
+fn rollout_timestamp() -> std::io::Result<String> {
+    let timestamp_format: &[FormatItem] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z");
+    OffsetDateTime::now_utc()
+        .format(timestamp_format)
+        .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))
+}
+
 struct JsonlWriter {
     file: tokio::fs::File,
 }
 
 impl JsonlWriter {
     async fn write_rollout_item(&mut self, rollout_item: RolloutItem) -> std::io::Result<()> {
-        let timestamp_format: &[FormatItem] = format_description!(
-            "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
-        );
-        let timestamp = OffsetDateTime::now_utc()
-            .format(timestamp_format)
-            .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))?;
-
         let line = RolloutLine {
-            timestamp,
+            timestamp: rollout_timestamp()?,
             item: rollout_item,
         };
         self.write_line(&line).await
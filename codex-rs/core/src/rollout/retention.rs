@@ -0,0 +1,228 @@
+//! Pruning of recorded sessions according to [`SessionRetention`].
+//!
+//! A session's last activity is the modification time of its rollout file,
+//! which the recorder touches on every write. Sessions older than
+//! `max_age_days` are removed first; if the remaining ones still take more
+//! than `max_total_bytes`, the least recently active are removed until they
+//! fit. Rollouts that a running session holds locked are never removed.
+//! Stale lines in the session index are dropped the next time it is
+//! compacted.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use tracing::warn;
+
+use super::ARCHIVED_SESSIONS_SUBDIR;
+use super::SESSIONS_SUBDIR;
+use super::index::collect_dirs_desc;
+use super::index::collect_rollout_files;
+use crate::config_types::SessionRetention;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A rollout file considered for pruning.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RolloutFile {
+    pub path: PathBuf,
+    /// Size on disk in bytes.
+    pub bytes: u64,
+    /// Last time the session wrote to the file.
+    #[serde(skip)]
+    pub modified: SystemTime,
+}
+
+/// Outcome of [`prune_sessions`].
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct PruneReport {
+    /// Rollout files that were removed, or would be on a dry run.
+    pub removed: Vec<RolloutFile>,
+    /// Number of rollout files left in place.
+    pub kept: usize,
+    /// Total size of the rollout files left in place.
+    pub kept_bytes: u64,
+}
+
+impl PruneReport {
+    pub fn removed_bytes(&self) -> u64 {
+        self.removed.iter().map(|file| file.bytes).sum()
+    }
+}
+
+/// Applies `retention` to the recorded sessions under `codex_home`. The
+/// rollout at `keep`, typically the session being recorded, is never removed.
+/// With `dry_run` set nothing is deleted and the report lists what would be.
+pub async fn prune_sessions(
+    codex_home: &Path,
+    retention: &SessionRetention,
+    keep: Option<&Path>,
+    dry_run: bool,
+) -> io::Result<PruneReport> {
+    let mut files = collect_session_files(codex_home).await?;
+    if !retention.keep_archived {
+        files.extend(collect_archived_files(codex_home).await?);
+    }
+    let (removed, kept) = select_for_pruning(files, retention, keep, SystemTime::now());
+
+    let mut report = PruneReport {
+        removed: Vec::with_capacity(removed.len()),
+        kept: kept.len(),
+        kept_bytes: kept.iter().map(|file| file.bytes).sum(),
+    };
+    for file in removed {
+        match remove_unless_in_use(&file.path, dry_run).await {
+            Ok(true) => {
+                if !dry_run {
+                    remove_empty_parents(codex_home, &file.path).await;
+                }
+            }
+            Ok(false) => {
+                report.kept += 1;
+                report.kept_bytes += file.bytes;
+                continue;
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => {
+                warn!("failed to remove {:?}: {err}", file.path);
+                report.kept += 1;
+                report.kept_bytes += file.bytes;
+                continue;
+            }
+        }
+        report.removed.push(file);
+    }
+    Ok(report)
+}
+
+/// Removes the rollout at `path` unless a running session, in this process
+/// or another, holds it locked. Returns whether it was, or with `dry_run`
+/// would be, removed.
+async fn remove_unless_in_use(path: &Path, dry_run: bool) -> io::Result<bool> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let file = std::fs::File::open(&path)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(std::fs::TryLockError::WouldBlock) => return Ok(false),
+            Err(std::fs::TryLockError::Error(err)) => return Err(err),
+        }
+        // The lock is held until `file` is dropped, so no session can pick
+        // the rollout up in between.
+        if !dry_run {
+            std::fs::remove_file(&path)?;
+        }
+        Ok(true)
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Splits `files` into those `retention` removes and those it keeps, both
+/// ordered least recently active first.
+pub(crate) fn select_for_pruning(
+    mut files: Vec<RolloutFile>,
+    retention: &SessionRetention,
+    keep: Option<&Path>,
+    now: SystemTime,
+) -> (Vec<RolloutFile>, Vec<RolloutFile>) {
+    files.sort_by_key(|file| file.modified);
+
+    let max_age = retention
+        .max_age_days
+        .map(|days| Duration::from_secs(days.saturating_mul(SECONDS_PER_DAY)));
+    let (mut removed, mut kept): (Vec<_>, Vec<_>) = files.into_iter().partition(|file| {
+        keep != Some(file.path.as_path())
+            && max_age.is_some_and(|max_age| {
+                now.duration_since(file.modified)
+                    .is_ok_and(|age| age > max_age)
+            })
+    });
+
+    if let Some(max_total_bytes) = retention.max_total_bytes {
+        let mut total: u64 = kept.iter().map(|file| file.bytes).sum();
+        let mut idx = 0;
+        while total > max_total_bytes && idx < kept.len() {
+            if keep == Some(kept[idx].path.as_path()) {
+                idx += 1;
+                continue;
+            }
+            let file = kept.remove(idx);
+            total -= file.bytes;
+            removed.push(file);
+        }
+    }
+
+    removed.sort_by_key(|file| file.modified);
+    (removed, kept)
+}
+
+/// Collects the rollout files under `~/.codex/sessions/YYYY/MM/DD/`.
+async fn collect_session_files(codex_home: &Path) -> io::Result<Vec<RolloutFile>> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    if !tokio::fs::try_exists(&root).await? {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for (_year, year_path) in collect_dirs_desc(&root, |s| s.parse::<u16>().ok()).await? {
+        for (_month, month_path) in collect_dirs_desc(&year_path, |s| s.parse::<u8>().ok()).await? {
+            for (_day, day_path) in collect_dirs_desc(&month_path, |s| s.parse::<u8>().ok()).await?
+            {
+                for path in collect_rollout_files(&day_path).await? {
+                    files.extend(stat_rollout(path).await);
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Collects the rollout files under `~/.codex/archived_sessions/`.
+async fn collect_archived_files(codex_home: &Path) -> io::Result<Vec<RolloutFile>> {
+    let root = codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    if !tokio::fs::try_exists(&root).await? {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for path in collect_rollout_files(&root).await? {
+        files.extend(stat_rollout(path).await);
+    }
+    Ok(files)
+}
+
+async fn stat_rollout(path: PathBuf) -> Option<RolloutFile> {
+    let metadata = match tokio::fs::metadata(&path).await {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            warn!("failed to stat {path:?}: {e}");
+            return None;
+        }
+    };
+    Some(RolloutFile {
+        path,
+        bytes: metadata.len(),
+        modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+    })
+}
+
+/// Removes the day, month and year directories left empty by deleting
+/// `path`. Stops at the first directory that still has entries.
+async fn remove_empty_parents(codex_home: &Path, path: &Path) {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == root || !current.starts_with(&root) {
+            break;
+        }
+        // `remove_dir` refuses to delete a directory that is not empty.
+        if tokio::fs::remove_dir(current).await.is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
@@ -0,0 +1,314 @@
+//! Full-text search over recorded sessions.
+//!
+//! Sessions are enumerated through the session index and every rollout file is
+//! scanned for user messages, assistant messages and shell commands that
+//! contain all of the query terms. Matching is case-insensitive.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use codex_protocol::ConversationId;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionSource;
+use futures::StreamExt;
+use serde::Serialize;
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+use super::compression::read_rollout_text;
+use super::index::ConversationItem;
+use super::index::load_index;
+use crate::codex::compact::content_items_to_text;
+use crate::codex::compact::is_session_prefix_message;
+
+/// Characters of context kept before the first match in a snippet.
+const SNIPPET_CHARS_BEFORE: usize = 40;
+
+/// Characters of context kept after the first match in a snippet.
+const SNIPPET_CHARS_AFTER: usize = 80;
+
+/// Rollout files read at once, across all searches in the process.
+const MAX_CONCURRENT_READS: usize = 8;
+
+static READ_PERMITS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_READS);
+
+/// What kind of record a search hit was found in.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchHitKind {
+    UserMessage,
+    AssistantMessage,
+    Command,
+}
+
+impl SearchHitKind {
+    /// Relative weight of a match; what the user typed ranks highest.
+    fn weight(self) -> usize {
+        match self {
+            SearchHitKind::UserMessage => 3,
+            SearchHitKind::Command => 2,
+            SearchHitKind::AssistantMessage => 1,
+        }
+    }
+}
+
+/// A turn of a recorded session that matched a search query.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SessionSearchHit {
+    /// Summary of the session the hit belongs to.
+    pub conversation: ConversationItem,
+    pub kind: SearchHitKind,
+    /// 0-based index of the user message that started the matching turn.
+    /// Passing it to `ConversationManager::fork_conversation` forks the
+    /// session right before that turn.
+    pub turn: usize,
+    /// Excerpt around the first match with whitespace collapsed.
+    pub snippet: String,
+    /// RFC3339 timestamp of the matching record.
+    pub timestamp: String,
+    /// Higher is better.
+    pub score: usize,
+}
+
+/// Searches every indexed session for records containing all terms of
+/// `query`, returning at most `limit` hits ordered best first. Only the best
+/// hit of each turn is kept. An empty `allowed_sources` matches all sources.
+pub(crate) async fn search_conversations(
+    codex_home: &Path,
+    query: &str,
+    allowed_sources: &[SessionSource],
+    limit: usize,
+) -> io::Result<Vec<SessionSearchHit>> {
+    let terms: Vec<Vec<char>> = query.split_whitespace().map(fold).collect();
+    if terms.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let items = load_index(codex_home)
+        .await?
+        .into_iter()
+        .filter(|item| allowed_sources.is_empty() || allowed_sources.contains(&item.source));
+    let terms = &terms;
+    let mut hits: Vec<SessionSearchHit> = futures::stream::iter(items)
+        .map(|item| async move {
+            let text = {
+                let Ok(_permit) = READ_PERMITS.acquire().await else {
+                    return Vec::new();
+                };
+                read_rollout_text(&item.path).await
+            };
+            match text {
+                Ok(text) => search_rollout(&item, &text, terms),
+                // The index can lag behind files that were archived or deleted.
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+                Err(err) => {
+                    warn!("failed to search {:?}: {err}", item.path);
+                    Vec::new()
+                }
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_READS)
+        .flat_map(futures::stream::iter)
+        .collect()
+        .await;
+
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.timestamp.cmp(&a.timestamp))
+            // Files are read concurrently, so ties need a stable order.
+            .then_with(|| a.conversation.path.cmp(&b.conversation.path))
+            .then_with(|| a.turn.cmp(&b.turn))
+    });
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+/// Returns the best hit of every matching turn in one rollout file.
+fn search_rollout(
+    conversation: &ConversationItem,
+    text: &str,
+    terms: &[Vec<char>],
+) -> Vec<SessionSearchHit> {
+    let mut best: HashMap<(ConversationId, usize), SessionSearchHit> = HashMap::new();
+    let mut user_messages = 0usize;
+    for line in text.lines() {
+        let Ok(RolloutLine {
+            timestamp,
+            item: RolloutItem::ResponseItem(item),
+        }) = serde_json::from_str::<RolloutLine>(line)
+        else {
+            continue;
+        };
+        let Some((kind, searchable)) = searchable_text(&item) else {
+            continue;
+        };
+        // Turns are counted the same way `fork_conversation` counts them.
+        if kind == SearchHitKind::UserMessage {
+            user_messages += 1;
+        }
+        let turn = user_messages.saturating_sub(1);
+        let Some((score, snippet)) = match_text(&searchable, terms) else {
+            continue;
+        };
+        let hit = SessionSearchHit {
+            conversation: conversation.clone(),
+            kind,
+            turn,
+            snippet,
+            timestamp,
+            score: score * kind.weight(),
+        };
+        match best.get(&(conversation.id, turn)) {
+            Some(existing) if existing.score >= hit.score => {}
+            _ => {
+                best.insert((conversation.id, turn), hit);
+            }
+        }
+    }
+    best.into_values().collect()
+}
+
+/// Extracts the text of records that are worth searching.
+fn searchable_text(item: &ResponseItem) -> Option<(SearchHitKind, String)> {
+    match item {
+        ResponseItem::Message { role, content, .. } => {
+            let text = content_items_to_text(content)?;
+            match role.as_str() {
+                "user" if !is_session_prefix_message(&text) => {
+                    Some((SearchHitKind::UserMessage, text))
+                }
+                "assistant" => Some((SearchHitKind::AssistantMessage, text)),
+                _ => None,
+            }
+        }
+        ResponseItem::LocalShellCall {
+            action: LocalShellAction::Exec(exec),
+            ..
+        } => Some((SearchHitKind::Command, join_command(&exec.command))),
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } if name == "shell" || name == "container.exec" => {
+            let params = serde_json::from_str::<ShellToolCallParams>(arguments).ok()?;
+            Some((SearchHitKind::Command, join_command(&params.command)))
+        }
+        _ => None,
+    }
+}
+
+fn join_command(command: &[String]) -> String {
+    shlex::try_join(command.iter().map(String::as_str)).unwrap_or_else(|_| command.join(" "))
+}
+
+/// Returns the number of term occurrences and a snippet around the first
+/// match, or `None` unless every term occurs in `text`.
+fn match_text(text: &str, terms: &[Vec<char>]) -> Option<(usize, String)> {
+    let collapsed: Vec<char> = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect();
+    let folded: Vec<char> = collapsed.iter().copied().map(fold_char).collect();
+
+    let mut occurrences = 0;
+    let mut first_match: Option<(usize, usize)> = None;
+    for term in terms {
+        let positions = find_all(&folded, term);
+        let first = *positions.first()?;
+        occurrences += positions.len();
+        if first_match.is_none_or(|(start, _)| first < start) {
+            first_match = Some((first, term.len()));
+        }
+    }
+    let (start, len) = first_match?;
+
+    let from = start.saturating_sub(SNIPPET_CHARS_BEFORE);
+    let to = (start + len + SNIPPET_CHARS_AFTER).min(collapsed.len());
+    let mut snippet = String::new();
+    if from > 0 {
+        snippet.push('…');
+    }
+    snippet.extend(&collapsed[from..to]);
+    if to < collapsed.len() {
+        snippet.push('…');
+    }
+    Some((occurrences, snippet))
+}
+
+/// Start positions of the non-overlapping occurrences of `needle`.
+fn find_all(haystack: &[char], needle: &[char]) -> Vec<usize> {
+    let mut positions = Vec::new();
+    let mut idx = 0;
+    while idx + needle.len() <= haystack.len() {
+        if haystack[idx..idx + needle.len()] == *needle {
+            positions.push(idx);
+            idx += needle.len();
+        } else {
+            idx += 1;
+        }
+    }
+    positions
+}
+
+fn fold(text: &str) -> Vec<char> {
+    text.chars().map(fold_char).collect()
+}
+
+/// Lowercases `c` when that maps it to a single character, so positions in
+/// the folded text line up with the original.
+fn fold_char(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn terms(query: &str) -> Vec<Vec<char>> {
+        query.split_whitespace().map(fold).collect()
+    }
+
+    #[test]
+    fn requires_every_term_case_insensitively() {
+        assert_eq!(
+            match_text("Fix the Migration bug", &terms("migration BUG")),
+            Some((2, "Fix the Migration bug".to_string()))
+        );
+        assert_eq!(
+            match_text("Fix the Migration", &terms("migration bug")),
+            None
+        );
+    }
+
+    #[test]
+    fn snippet_is_cut_around_first_match() {
+        let text = format!("{}needle{}", "a ".repeat(50), " b".repeat(60));
+        let (score, snippet) = match_text(&text, &terms("needle")).expect("match");
+        assert_eq!(score, 1);
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+        assert_eq!(
+            snippet.chars().count(),
+            SNIPPET_CHARS_BEFORE + "needle".len() + SNIPPET_CHARS_AFTER + 2
+        );
+    }
+
+    #[test]
+    fn snippet_respects_multibyte_characters() {
+        let text = format!("{}ÄÖÜ schema {}", "ü".repeat(60), "ß".repeat(100));
+        let (_, snippet) = match_text(&text, &terms("äöü")).expect("match");
+        assert!(snippet.contains("ÄÖÜ schema"));
+    }
+}
@@ -4,6 +4,9 @@ use std::fs::File;
 use std::fs::{self};
 use std::io::Write;
 use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use tempfile::TempDir;
 use time::OffsetDateTime;
@@ -12,26 +15,48 @@ use time::format_description::FormatItem;
 use time::macros::format_description;
 use uuid::Uuid;
 
+use crate::config::Config;
+use crate::config::ConfigOverrides;
+use crate::config::ConfigToml;
+use crate::config_types::SessionRetention;
+use crate::rollout::ARCHIVED_SESSIONS_SUBDIR;
 use crate::rollout::INTERACTIVE_SESSION_SOURCES;
+use crate::rollout::RolloutRecorder;
+use crate::rollout::RolloutRecorderParams;
+use crate::rollout::compression::compressed_rollout_path;
+use crate::rollout::index::SESSION_INDEX_FILE;
+use crate::rollout::index::append_to_index;
+use crate::rollout::list::ConversationFilter;
 use crate::rollout::list::ConversationItem;
 use crate::rollout::list::ConversationsPage;
 use crate::rollout::list::Cursor;
 use crate::rollout::list::get_conversation;
 use crate::rollout::list::get_conversations;
-use crate::rollout::RolloutRecorder;
+use crate::rollout::retention::RolloutFile;
+use crate::rollout::retention::prune_sessions;
+use crate::rollout::retention::select_for_pruning;
+use crate::rollout::search::SearchHitKind;
+use crate::rollout::search::search_conversations;
 use anyhow::Result;
 use codex_protocol::ConversationId;
 use codex_protocol::models::ContentItem;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::LocalShellExecAction;
+use codex_protocol::models::LocalShellStatus;
 use codex_protocol::models::ResponseItem;
-use codex_protocol::protocol::CompactedItem;
 use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::GitInfo;
+use codex_protocol::protocol::InitialHistory;
 use codex_protocol::protocol::InputMessageKind;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::RolloutLine;
 use codex_protocol::protocol::SessionMeta;
 use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::SessionSource;
+use codex_protocol::protocol::TokenCountEvent;
+use codex_protocol::protocol::TokenUsage;
+use codex_protocol::protocol::TokenUsageInfo;
+use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 use codex_protocol::protocol::UserMessageEvent;
 
 const NO_SOURCE_FILTER: &[SessionSource] = &[];
@@ -101,6 +126,88 @@ fn write_session_file(
     Ok((dt, uuid))
 }
 
+fn session_path(home: &Path, ts_str: &str, uuid: Uuid) -> PathBuf {
+    home.join("sessions")
+        .join(&ts_str[0..4])
+        .join(&ts_str[5..7])
+        .join(&ts_str[8..10])
+        .join(format!("rollout-{ts_str}-{uuid}.jsonl"))
+}
+
+/// The summary `write_session_file` produces for a session.
+fn expected_item(home: &Path, ts_str: &str, uuid: Uuid, source: SessionSource) -> ConversationItem {
+    ConversationItem {
+        id: ConversationId::from_string(&uuid.to_string()).unwrap(),
+        path: session_path(home, ts_str, uuid),
+        source,
+        cwd: PathBuf::from("."),
+        git_branch: None,
+        preview: Some("Hello from user".to_string()),
+        created_at: ts_str.to_string(),
+        updated_at: ts_str.to_string(),
+        total_tokens: 0,
+    }
+}
+
+fn cursor(ts_str: &str, uuid: Uuid) -> Cursor {
+    serde_json::from_str(&format!("\"{ts_str}|{uuid}\"")).unwrap()
+}
+
+fn write_rollout_lines(path: &Path, lines: &[RolloutLine]) -> std::io::Result<()> {
+    fs::create_dir_all(path.parent().unwrap())?;
+    let mut file = File::create(path)?;
+    for line in lines {
+        writeln!(file, "{}", serde_json::to_string(line)?)?;
+    }
+    Ok(())
+}
+
+fn session_meta_line(ts_str: &str, uuid: Uuid, cwd: &str) -> RolloutLine {
+    RolloutLine {
+        timestamp: ts_str.to_string(),
+        item: RolloutItem::SessionMeta(SessionMetaLine {
+            meta: SessionMeta {
+                id: ConversationId::from_string(&uuid.to_string()).unwrap(),
+                timestamp: ts_str.to_string(),
+                instructions: None,
+                cwd: cwd.into(),
+                originator: "test_originator".into(),
+                cli_version: "test_version".into(),
+                source: SessionSource::Cli,
+            },
+            git: None,
+        }),
+    }
+}
+
+fn user_message_line(ts_str: &str, message: &str) -> RolloutLine {
+    RolloutLine {
+        timestamp: ts_str.to_string(),
+        item: RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+            message: message.to_string(),
+            kind: Some(InputMessageKind::Plain),
+            images: None,
+        })),
+    }
+}
+
+fn git_branch_line(ts_str: &str, branch: &str) -> RolloutLine {
+    RolloutLine {
+        timestamp: ts_str.to_string(),
+        item: RolloutItem::GitInfo(GitInfo {
+            commit_hash: None,
+            branch: Some(branch.to_string()),
+            repository_url: None,
+        }),
+    }
+}
+
+async fn list(home: &Path, filter: &ConversationFilter) -> ConversationsPage {
+    get_conversations(home, 10, None, NO_SOURCE_FILTER, filter)
+        .await
+        .unwrap()
+}
+
 #[tokio::test]
 async fn test_list_conversations_latest_first() {
     let temp = TempDir::new().unwrap();
@@ -112,113 +219,31 @@ async fn test_list_conversations_latest_first() {
     let u3 = Uuid::from_u128(3);
 
     // Create three sessions across three days
-    write_session_file(
-        home,
-        "2025-01-01T12-00-00",
-        u1,
-        3,
-        Some(SessionSource::VSCode),
-    )
-    .unwrap();
-    write_session_file(
-        home,
-        "2025-01-02T12-00-00",
-        u2,
-        3,
-        Some(SessionSource::VSCode),
-    )
-    .unwrap();
-    write_session_file(
+    for (ts, uuid) in [
+        ("2025-01-01T12-00-00", u1),
+        ("2025-01-02T12-00-00", u2),
+        ("2025-01-03T12-00-00", u3),
+    ] {
+        write_session_file(home, ts, uuid, 3, Some(SessionSource::VSCode)).unwrap();
+    }
+
+    let page = get_conversations(
         home,
-        "2025-01-03T12-00-00",
-        u3,
-        3,
-        Some(SessionSource::VSCode),
+        10,
+        None,
+        INTERACTIVE_SESSION_SOURCES,
+        &ConversationFilter::default(),
     )
+    .await
     .unwrap();
 
-    let page = get_conversations(home, 10, None, INTERACTIVE_SESSION_SOURCES)
-        .await
-        .unwrap();
-
-    // Build expected objects
-    let p1 = home
-        .join("sessions")
-        .join("2025")
-        .join("01")
-        .join("03")
-        .join(format!("rollout-2025-01-03T12-00-00-{u3}.jsonl"));
-    let p2 = home
-        .join("sessions")
-        .join("2025")
-        .join("01")
-        .join("02")
-        .join(format!("rollout-2025-01-02T12-00-00-{u2}.jsonl"));
-    let p3 = home
-        .join("sessions")
-        .join("2025")
-        .join("01")
-        .join("01")
-        .join(format!("rollout-2025-01-01T12-00-00-{u1}.jsonl"));
-
-    let head_3 = vec![serde_json::json!({
-        "id": u3,
-        "timestamp": "2025-01-03T12-00-00",
-        "instructions": null,
-        "cwd": ".",
-        "originator": "test_originator",
-        "cli_version": "test_version",
-        "source": "vscode",
-    })];
-    let head_2 = vec![serde_json::json!({
-        "id": u2,
-        "timestamp": "2025-01-02T12-00-00",
-        "instructions": null,
-        "cwd": ".",
-        "originator": "test_originator",
-        "cli_version": "test_version",
-        "source": "vscode",
-    })];
-    let head_1 = vec![serde_json::json!({
-        "id": u1,
-        "timestamp": "2025-01-01T12-00-00",
-        "instructions": null,
-        "cwd": ".",
-        "originator": "test_originator",
-        "cli_version": "test_version",
-        "source": "vscode",
-    })];
-
-    let expected_cursor: Cursor =
-        serde_json::from_str(&format!("\"2025-01-01T12-00-00|{u1}\"")).unwrap();
-
     let expected = ConversationsPage {
         items: vec![
-            ConversationItem {
-                path: p1,
-                head: head_3,
-                tail: Vec::new(),
-                created_at: Some("2025-01-03T12-00-00".into()),
-                updated_at: Some("2025-01-03T12-00-00".into()),
-            },
-            ConversationItem {
-                path: p2,
-                head: head_2,
-                tail: Vec::new(),
-                created_at: Some("2025-01-02T12-00-00".into()),
-                updated_at: Some("2025-01-02T12-00-00".into()),
-            },
-            ConversationItem {
-                path: p3,
-                head: head_1,
-                tail: Vec::new(),
-                created_at: Some("2025-01-01T12-00-00".into()),
-                updated_at: Some("2025-01-01T12-00-00".into()),
-            },
+            expected_item(home, "2025-01-03T12-00-00", u3, SessionSource::VSCode),
+            expected_item(home, "2025-01-02T12-00-00", u2, SessionSource::VSCode),
+            expected_item(home, "2025-01-01T12-00-00", u1, SessionSource::VSCode),
         ],
-        next_cursor: Some(expected_cursor),
-        num_scanned_files: 3,
-        reached_scan_cap: false,
+        next_cursor: None,
     };
 
     assert_eq!(page, expected);
@@ -237,102 +262,26 @@ async fn test_pagination_cursor() {
     let u5 = Uuid::from_u128(55);
 
     // Oldest to newest
-    write_session_file(
-        home,
-        "2025-03-01T09-00-00",
-        u1,
-        1,
-        Some(SessionSource::VSCode),
-    )
-    .unwrap();
-    write_session_file(
-        home,
-        "2025-03-02T09-00-00",
-        u2,
-        1,
-        Some(SessionSource::VSCode),
-    )
-    .unwrap();
-    write_session_file(
-        home,
-        "2025-03-03T09-00-00",
-        u3,
-        1,
-        Some(SessionSource::VSCode),
-    )
-    .unwrap();
-    write_session_file(
-        home,
-        "2025-03-04T09-00-00",
-        u4,
-        1,
-        Some(SessionSource::VSCode),
-    )
-    .unwrap();
-    write_session_file(
-        home,
-        "2025-03-05T09-00-00",
-        u5,
-        1,
-        Some(SessionSource::VSCode),
-    )
-    .unwrap();
+    for (ts, uuid) in [
+        ("2025-03-01T09-00-00", u1),
+        ("2025-03-02T09-00-00", u2),
+        ("2025-03-03T09-00-00", u3),
+        ("2025-03-04T09-00-00", u4),
+        ("2025-03-05T09-00-00", u5),
+    ] {
+        write_session_file(home, ts, uuid, 1, Some(SessionSource::VSCode)).unwrap();
+    }
 
-    let page1 = get_conversations(home, 2, None, INTERACTIVE_SESSION_SOURCES)
+    let no_filter = ConversationFilter::default();
+    let page1 = get_conversations(home, 2, None, INTERACTIVE_SESSION_SOURCES, &no_filter)
         .await
         .unwrap();
-    let p5 = home
-        .join("sessions")
-        .join("2025")
-        .join("03")
-        .join("05")
-        .join(format!("rollout-2025-03-05T09-00-00-{u5}.jsonl"));
-    let p4 = home
-        .join("sessions")
-        .join("2025")
-        .join("03")
-        .join("04")
-        .join(format!("rollout-2025-03-04T09-00-00-{u4}.jsonl"));
-    let head_5 = vec![serde_json::json!({
-        "id": u5,
-        "timestamp": "2025-03-05T09-00-00",
-        "instructions": null,
-        "cwd": ".",
-        "originator": "test_originator",
-        "cli_version": "test_version",
-        "source": "vscode",
-    })];
-    let head_4 = vec![serde_json::json!({
-        "id": u4,
-        "timestamp": "2025-03-04T09-00-00",
-        "instructions": null,
-        "cwd": ".",
-        "originator": "test_originator",
-        "cli_version": "test_version",
-        "source": "vscode",
-    })];
-    let expected_cursor1: Cursor =
-        serde_json::from_str(&format!("\"2025-03-04T09-00-00|{u4}\"")).unwrap();
     let expected_page1 = ConversationsPage {
         items: vec![
-            ConversationItem {
-                path: p5,
-                head: head_5,
-                tail: Vec::new(),
-                created_at: Some("2025-03-05T09-00-00".into()),
-                updated_at: Some("2025-03-05T09-00-00".into()),
-            },
-            ConversationItem {
-                path: p4,
-                head: head_4,
-                tail: Vec::new(),
-                created_at: Some("2025-03-04T09-00-00".into()),
-                updated_at: Some("2025-03-04T09-00-00".into()),
-            },
+            expected_item(home, "2025-03-05T09-00-00", u5, SessionSource::VSCode),
+            expected_item(home, "2025-03-04T09-00-00", u4, SessionSource::VSCode),
         ],
-        next_cursor: Some(expected_cursor1.clone()),
-        num_scanned_files: 3, // scanned 05, 04, and peeked at 03 before breaking
-        reached_scan_cap: false,
+        next_cursor: Some(cursor("2025-03-04T09-00-00", u4)),
     };
     assert_eq!(page1, expected_page1);
 
@@ -341,61 +290,16 @@ async fn test_pagination_cursor() {
         2,
         page1.next_cursor.as_ref(),
         INTERACTIVE_SESSION_SOURCES,
+        &no_filter,
     )
     .await
     .unwrap();
-    let p3 = home
-        .join("sessions")
-        .join("2025")
-        .join("03")
-        .join("03")
-        .join(format!("rollout-2025-03-03T09-00-00-{u3}.jsonl"));
-    let p2 = home
-        .join("sessions")
-        .join("2025")
-        .join("03")
-        .join("02")
-        .join(format!("rollout-2025-03-02T09-00-00-{u2}.jsonl"));
-    let head_3 = vec![serde_json::json!({
-        "id": u3,
-        "timestamp": "2025-03-03T09-00-00",
-        "instructions": null,
-        "cwd": ".",
-        "originator": "test_originator",
-        "cli_version": "test_version",
-        "source": "vscode",
-    })];
-    let head_2 = vec![serde_json::json!({
-        "id": u2,
-        "timestamp": "2025-03-02T09-00-00",
-        "instructions": null,
-        "cwd": ".",
-        "originator": "test_originator",
-        "cli_version": "test_version",
-        "source": "vscode",
-    })];
-    let expected_cursor2: Cursor =
-        serde_json::from_str(&format!("\"2025-03-02T09-00-00|{u2}\"")).unwrap();
     let expected_page2 = ConversationsPage {
         items: vec![
-            ConversationItem {
-                path: p3,
-                head: head_3,
-                tail: Vec::new(),
-                created_at: Some("2025-03-03T09-00-00".into()),
-                updated_at: Some("2025-03-03T09-00-00".into()),
-            },
-            ConversationItem {
-                path: p2,
-                head: head_2,
-                tail: Vec::new(),
-                created_at: Some("2025-03-02T09-00-00".into()),
-                updated_at: Some("2025-03-02T09-00-00".into()),
-            },
+            expected_item(home, "2025-03-03T09-00-00", u3, SessionSource::VSCode),
+            expected_item(home, "2025-03-02T09-00-00", u2, SessionSource::VSCode),
         ],
-        next_cursor: Some(expected_cursor2.clone()),
-        num_scanned_files: 5, // scanned 05, 04 (anchor), 03, 02, and peeked at 01
-        reached_scan_cap: false,
+        next_cursor: Some(cursor("2025-03-02T09-00-00", u2)),
     };
     assert_eq!(page2, expected_page2);
 
@@ -404,37 +308,18 @@ async fn test_pagination_cursor() {
         2,
         page2.next_cursor.as_ref(),
         INTERACTIVE_SESSION_SOURCES,
+        &no_filter,
     )
     .await
     .unwrap();
-    let p1 = home
-        .join("sessions")
-        .join("2025")
-        .join("03")
-        .join("01")
-        .join(format!("rollout-2025-03-01T09-00-00-{u1}.jsonl"));
-    let head_1 = vec![serde_json::json!({
-        "id": u1,
-        "timestamp": "2025-03-01T09-00-00",
-        "instructions": null,
-        "cwd": ".",
-        "originator": "test_originator",
-        "cli_version": "test_version",
-        "source": "vscode",
-    })];
-    let expected_cursor3: Cursor =
-        serde_json::from_str(&format!("\"2025-03-01T09-00-00|{u1}\"")).unwrap();
     let expected_page3 = ConversationsPage {
-        items: vec![ConversationItem {
-            path: p1,
-            head: head_1,
-            tail: Vec::new(),
-            created_at: Some("2025-03-01T09-00-00".into()),
-            updated_at: Some("2025-03-01T09-00-00".into()),
-        }],
-        next_cursor: Some(expected_cursor3),
-        num_scanned_files: 5, // scanned 05, 04 (anchor), 03, 02 (anchor), 01
-        reached_scan_cap: false,
+        items: vec![expected_item(
+            home,
+            "2025-03-01T09-00-00",
+            u1,
+            SessionSource::VSCode,
+        )],
+        next_cursor: None,
     };
     assert_eq!(page3, expected_page3);
 }
@@ -448,41 +333,23 @@ async fn test_get_conversation_contents() {
     let ts = "2025-04-01T10-30-00";
     write_session_file(home, ts, uuid, 2, Some(SessionSource::VSCode)).unwrap();
 
-    let page = get_conversations(home, 1, None, INTERACTIVE_SESSION_SOURCES)
-        .await
-        .unwrap();
+    let page = get_conversations(
+        home,
+        1,
+        None,
+        INTERACTIVE_SESSION_SOURCES,
+        &ConversationFilter::default(),
+    )
+    .await
+    .unwrap();
     let path = &page.items[0].path;
 
     let content = get_conversation(path).await.unwrap();
 
     // Page equality (single item)
-    let expected_path = home
-        .join("sessions")
-        .join("2025")
-        .join("04")
-        .join("01")
-        .join(format!("rollout-2025-04-01T10-30-00-{uuid}.jsonl"));
-    let expected_head = vec![serde_json::json!({
-        "id": uuid,
-        "timestamp": ts,
-        "instructions": null,
-        "cwd": ".",
-        "originator": "test_originator",
-        "cli_version": "test_version",
-        "source": "vscode",
-    })];
-    let expected_cursor: Cursor = serde_json::from_str(&format!("\"{ts}|{uuid}\"")).unwrap();
     let expected_page = ConversationsPage {
-        items: vec![ConversationItem {
-            path: expected_path,
-            head: expected_head,
-            tail: Vec::new(),
-            created_at: Some(ts.into()),
-            updated_at: Some(ts.into()),
-        }],
-        next_cursor: Some(expected_cursor),
-        num_scanned_files: 1,
-        reached_scan_cap: false,
+        items: vec![expected_item(home, ts, uuid, SessionSource::VSCode)],
+        next_cursor: None,
     };
     assert_eq!(page, expected_page);
 
@@ -511,272 +378,6 @@ async fn test_get_conversation_contents() {
     assert_eq!(content, expected_content);
 }
 
-#[tokio::test]
-async fn test_tail_includes_last_response_items() -> Result<()> {
-    let temp = TempDir::new().unwrap();
-    let home = temp.path();
-
-    let ts = "2025-06-01T08-00-00";
-    let uuid = Uuid::from_u128(42);
-    let day_dir = home.join("sessions").join("2025").join("06").join("01");
-    fs::create_dir_all(&day_dir)?;
-    let file_path = day_dir.join(format!("rollout-{ts}-{uuid}.jsonl"));
-    let mut file = File::create(&file_path)?;
-
-    let conversation_id = ConversationId::from_string(&uuid.to_string())?;
-    let meta_line = RolloutLine {
-        timestamp: ts.to_string(),
-        item: RolloutItem::SessionMeta(SessionMetaLine {
-            meta: SessionMeta {
-                id: conversation_id,
-                timestamp: ts.to_string(),
-                instructions: None,
-                cwd: ".".into(),
-                originator: "test_originator".into(),
-                cli_version: "test_version".into(),
-                source: SessionSource::VSCode,
-            },
-            git: None,
-        }),
-    };
-    writeln!(file, "{}", serde_json::to_string(&meta_line)?)?;
-
-    let user_event_line = RolloutLine {
-        timestamp: ts.to_string(),
-        item: RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
-            message: "hello".into(),
-            kind: Some(InputMessageKind::Plain),
-            images: None,
-        })),
-    };
-    writeln!(file, "{}", serde_json::to_string(&user_event_line)?)?;
-
-    let total_messages = 12usize;
-    for idx in 0..total_messages {
-        let response_line = RolloutLine {
-            timestamp: format!("{ts}-{idx:02}"),
-            item: RolloutItem::ResponseItem(ResponseItem::Message {
-                id: None,
-                role: "assistant".into(),
-                content: vec![ContentItem::OutputText {
-                    text: format!("reply-{idx}"),
-                }],
-            }),
-        };
-        writeln!(file, "{}", serde_json::to_string(&response_line)?)?;
-    }
-    drop(file);
-
-    let page = get_conversations(home, 1, None, INTERACTIVE_SESSION_SOURCES).await?;
-    let item = page.items.first().expect("conversation item");
-    let tail_len = item.tail.len();
-    assert_eq!(tail_len, 10usize.min(total_messages));
-
-    let expected: Vec<serde_json::Value> = (total_messages - tail_len..total_messages)
-        .map(|idx| {
-            serde_json::json!({
-                "type": "message",
-                "role": "assistant",
-                "content": [
-                    {
-                        "type": "output_text",
-                        "text": format!("reply-{idx}"),
-                    }
-                ],
-            })
-        })
-        .collect();
-
-    assert_eq!(item.tail, expected);
-    assert_eq!(item.created_at.as_deref(), Some(ts));
-    let expected_updated = format!("{ts}-{last:02}", last = total_messages - 1);
-    assert_eq!(item.updated_at.as_deref(), Some(expected_updated.as_str()));
-
-    Ok(())
-}
-
-#[tokio::test]
-async fn test_tail_handles_short_sessions() -> Result<()> {
-    let temp = TempDir::new().unwrap();
-    let home = temp.path();
-
-    let ts = "2025-06-02T08-30-00";
-    let uuid = Uuid::from_u128(7);
-    let day_dir = home.join("sessions").join("2025").join("06").join("02");
-    fs::create_dir_all(&day_dir)?;
-    let file_path = day_dir.join(format!("rollout-{ts}-{uuid}.jsonl"));
-    let mut file = File::create(&file_path)?;
-
-    let conversation_id = ConversationId::from_string(&uuid.to_string())?;
-    let meta_line = RolloutLine {
-        timestamp: ts.to_string(),
-        item: RolloutItem::SessionMeta(SessionMetaLine {
-            meta: SessionMeta {
-                id: conversation_id,
-                timestamp: ts.to_string(),
-                instructions: None,
-                cwd: ".".into(),
-                originator: "test_originator".into(),
-                cli_version: "test_version".into(),
-                source: SessionSource::VSCode,
-            },
-            git: None,
-        }),
-    };
-    writeln!(file, "{}", serde_json::to_string(&meta_line)?)?;
-
-    let user_event_line = RolloutLine {
-        timestamp: ts.to_string(),
-        item: RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
-            message: "hi".into(),
-            kind: Some(InputMessageKind::Plain),
-            images: None,
-        })),
-    };
-    writeln!(file, "{}", serde_json::to_string(&user_event_line)?)?;
-
-    for idx in 0..3 {
-        let response_line = RolloutLine {
-            timestamp: format!("{ts}-{idx:02}"),
-            item: RolloutItem::ResponseItem(ResponseItem::Message {
-                id: None,
-                role: "assistant".into(),
-                content: vec![ContentItem::OutputText {
-                    text: format!("short-{idx}"),
-                }],
-            }),
-        };
-        writeln!(file, "{}", serde_json::to_string(&response_line)?)?;
-    }
-    drop(file);
-
-    let page = get_conversations(home, 1, None, INTERACTIVE_SESSION_SOURCES).await?;
-    let tail = &page.items.first().expect("conversation item").tail;
-
-    assert_eq!(tail.len(), 3);
-
-    let expected: Vec<serde_json::Value> = (0..3)
-        .map(|idx| {
-            serde_json::json!({
-                "type": "message",
-                "role": "assistant",
-                "content": [
-                    {
-                        "type": "output_text",
-                        "text": format!("short-{idx}"),
-                    }
-                ],
-            })
-        })
-        .collect();
-
-    assert_eq!(tail, &expected);
-    let expected_updated = format!("{ts}-{last:02}", last = 2);
-    assert_eq!(
-        page.items[0].updated_at.as_deref(),
-        Some(expected_updated.as_str())
-    );
-
-    Ok(())
-}
-
-#[tokio::test]
-async fn test_tail_skips_trailing_non_responses() -> Result<()> {
-    let temp = TempDir::new().unwrap();
-    let home = temp.path();
-
-    let ts = "2025-06-03T10-00-00";
-    let uuid = Uuid::from_u128(11);
-    let day_dir = home.join("sessions").join("2025").join("06").join("03");
-    fs::create_dir_all(&day_dir)?;
-    let file_path = day_dir.join(format!("rollout-{ts}-{uuid}.jsonl"));
-    let mut file = File::create(&file_path)?;
-
-    let conversation_id = ConversationId::from_string(&uuid.to_string())?;
-    let meta_line = RolloutLine {
-        timestamp: ts.to_string(),
-        item: RolloutItem::SessionMeta(SessionMetaLine {
-            meta: SessionMeta {
-                id: conversation_id,
-                timestamp: ts.to_string(),
-                instructions: None,
-                cwd: ".".into(),
-                originator: "test_originator".into(),
-                cli_version: "test_version".into(),
-                source: SessionSource::VSCode,
-            },
-            git: None,
-        }),
-    };
-    writeln!(file, "{}", serde_json::to_string(&meta_line)?)?;
-
-    let user_event_line = RolloutLine {
-        timestamp: ts.to_string(),
-        item: RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
-            message: "hello".into(),
-            kind: Some(InputMessageKind::Plain),
-            images: None,
-        })),
-    };
-    writeln!(file, "{}", serde_json::to_string(&user_event_line)?)?;
-
-    for idx in 0..4 {
-        let response_line = RolloutLine {
-            timestamp: format!("{ts}-{idx:02}"),
-            item: RolloutItem::ResponseItem(ResponseItem::Message {
-                id: None,
-                role: "assistant".into(),
-                content: vec![ContentItem::OutputText {
-                    text: format!("response-{idx}"),
-                }],
-            }),
-        };
-        writeln!(file, "{}", serde_json::to_string(&response_line)?)?;
-    }
-
-    let compacted_line = RolloutLine {
-        timestamp: format!("{ts}-compacted"),
-        item: RolloutItem::Compacted(CompactedItem {
-            message: "compacted".into(),
-        }),
-    };
-    writeln!(file, "{}", serde_json::to_string(&compacted_line)?)?;
-
-    let shutdown_event = RolloutLine {
-        timestamp: format!("{ts}-shutdown"),
-        item: RolloutItem::EventMsg(EventMsg::ShutdownComplete),
-    };
-    writeln!(file, "{}", serde_json::to_string(&shutdown_event)?)?;
-    drop(file);
-
-    let page = get_conversations(home, 1, None, INTERACTIVE_SESSION_SOURCES).await?;
-    let tail = &page.items.first().expect("conversation item").tail;
-
-    let expected: Vec<serde_json::Value> = (0..4)
-        .map(|idx| {
-            serde_json::json!({
-                "type": "message",
-                "role": "assistant",
-                "content": [
-                    {
-                        "type": "output_text",
-                        "text": format!("response-{idx}"),
-                    }
-                ],
-            })
-        })
-        .collect();
-
-    assert_eq!(tail, &expected);
-    let expected_updated = format!("{ts}-{last:02}", last = 3);
-    assert_eq!(
-        page.items[0].updated_at.as_deref(),
-        Some(expected_updated.as_str())
-    );
-
-    Ok(())
-}
-
 #[tokio::test]
 async fn test_stable_ordering_same_second_pagination() {
     let temp = TempDir::new().unwrap();
@@ -791,54 +392,16 @@ async fn test_stable_ordering_same_second_pagination() {
     write_session_file(home, ts, u2, 0, Some(SessionSource::VSCode)).unwrap();
     write_session_file(home, ts, u3, 0, Some(SessionSource::VSCode)).unwrap();
 
-    let page1 = get_conversations(home, 2, None, INTERACTIVE_SESSION_SOURCES)
+    let no_filter = ConversationFilter::default();
+    let page1 = get_conversations(home, 2, None, INTERACTIVE_SESSION_SOURCES, &no_filter)
         .await
         .unwrap();
-
-    let p3 = home
-        .join("sessions")
-        .join("2025")
-        .join("07")
-        .join("01")
-        .join(format!("rollout-2025-07-01T00-00-00-{u3}.jsonl"));
-    let p2 = home
-        .join("sessions")
-        .join("2025")
-        .join("07")
-        .join("01")
-        .join(format!("rollout-2025-07-01T00-00-00-{u2}.jsonl"));
-    let head = |u: Uuid| -> Vec<serde_json::Value> {
-        vec![serde_json::json!({
-            "id": u,
-            "timestamp": ts,
-            "instructions": null,
-            "cwd": ".",
-            "originator": "test_originator",
-            "cli_version": "test_version",
-            "source": "vscode",
-        })]
-    };
-    let expected_cursor1: Cursor = serde_json::from_str(&format!("\"{ts}|{u2}\"")).unwrap();
     let expected_page1 = ConversationsPage {
         items: vec![
-            ConversationItem {
-                path: p3,
-                head: head(u3),
-                tail: Vec::new(),
-                created_at: Some(ts.to_string()),
-                updated_at: Some(ts.to_string()),
-            },
-            ConversationItem {
-                path: p2,
-                head: head(u2),
-                tail: Vec::new(),
-                created_at: Some(ts.to_string()),
-                updated_at: Some(ts.to_string()),
-            },
+            expected_item(home, ts, u3, SessionSource::VSCode),
+            expected_item(home, ts, u2, SessionSource::VSCode),
         ],
-        next_cursor: Some(expected_cursor1.clone()),
-        num_scanned_files: 3, // scanned u3, u2, peeked u1
-        reached_scan_cap: false,
+        next_cursor: Some(cursor(ts, u2)),
     };
     assert_eq!(page1, expected_page1);
 
@@ -847,27 +410,13 @@ async fn test_stable_ordering_same_second_pagination() {
         2,
         page1.next_cursor.as_ref(),
         INTERACTIVE_SESSION_SOURCES,
+        &no_filter,
     )
     .await
     .unwrap();
-    let p1 = home
-        .join("sessions")
-        .join("2025")
-        .join("07")
-        .join("01")
-        .join(format!("rollout-2025-07-01T00-00-00-{u1}.jsonl"));
-    let expected_cursor2: Cursor = serde_json::from_str(&format!("\"{ts}|{u1}\"")).unwrap();
     let expected_page2 = ConversationsPage {
-        items: vec![ConversationItem {
-            path: p1,
-            head: head(u1),
-            tail: Vec::new(),
-            created_at: Some(ts.to_string()),
-            updated_at: Some(ts.to_string()),
-        }],
-        next_cursor: Some(expected_cursor2),
-        num_scanned_files: 3, // scanned u3, u2 (anchor), u1
-        reached_scan_cap: false,
+        items: vec![expected_item(home, ts, u1, SessionSource::VSCode)],
+        next_cursor: None,
     };
     assert_eq!(page2, expected_page2);
 }
@@ -897,9 +446,15 @@ async fn test_source_filter_excludes_non_matching_sessions() {
     )
     .unwrap();
 
-    let interactive_only = get_conversations(home, 10, None, INTERACTIVE_SESSION_SOURCES)
-        .await
-        .unwrap();
+    let interactive_only = get_conversations(
+        home,
+        10,
+        None,
+        INTERACTIVE_SESSION_SOURCES,
+        &ConversationFilter::default(),
+    )
+    .await
+    .unwrap();
     let paths: Vec<_> = interactive_only
         .items
         .iter()
@@ -911,9 +466,7 @@ async fn test_source_filter_excludes_non_matching_sessions() {
         path.ends_with("rollout-2025-08-02T10-00-00-00000000-0000-0000-0000-00000000002a.jsonl")
     }));
 
-    let all_sessions = get_conversations(home, 10, None, NO_SOURCE_FILTER)
-        .await
-        .unwrap();
+    let all_sessions = list(home, &ConversationFilter::default()).await;
     let all_paths: Vec<_> = all_sessions
         .items
         .into_iter()
@@ -928,6 +481,293 @@ async fn test_source_filter_excludes_non_matching_sessions() {
     }));
 }
 
+#[tokio::test]
+async fn test_index_summarizes_branch_preview_and_tokens() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let uuid = Uuid::from_u128(7);
+    let path = session_path(home, "2025-06-01T08-00-00", uuid);
+    let usage = TokenUsage {
+        total_tokens: 1234,
+        ..Default::default()
+    };
+    write_rollout_lines(
+        &path,
+        &[
+            session_meta_line("2025-06-01T08:00:00.000Z", uuid, "/repo"),
+            git_branch_line("2025-06-01T08:00:01.000Z", "feature"),
+            RolloutLine {
+                timestamp: "2025-06-01T08:00:02.000Z".to_string(),
+                item: RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                    message: "<user_instructions>rules</user_instructions>".to_string(),
+                    kind: Some(InputMessageKind::UserInstructions),
+                    images: None,
+                })),
+            },
+            user_message_line(
+                "2025-06-01T08:00:03.000Z",
+                &format!("<ide context> {USER_MESSAGE_BEGIN} Count to 5"),
+            ),
+            user_message_line("2025-06-01T08:00:04.000Z", "and then to 10"),
+            RolloutLine {
+                timestamp: "2025-06-01T08:00:05.000Z".to_string(),
+                item: RolloutItem::EventMsg(EventMsg::TokenCount(TokenCountEvent {
+                    info: Some(TokenUsageInfo {
+                        total_token_usage: usage.clone(),
+                        last_token_usage: usage,
+                        model_context_window: None,
+                    }),
+                    rate_limits: None,
+                    cost_usd: None,
+                })),
+            },
+        ],
+    )?;
+
+    let page = list(home, &ConversationFilter::default()).await;
+
+    let expected = ConversationItem {
+        id: ConversationId::from_string(&uuid.to_string())?,
+        path,
+        source: SessionSource::Cli,
+        cwd: PathBuf::from("/repo"),
+        git_branch: Some("feature".to_string()),
+        preview: Some("Count to 5".to_string()),
+        created_at: "2025-06-01T08:00:00.000Z".to_string(),
+        updated_at: "2025-06-01T08:00:05.000Z".to_string(),
+        total_tokens: 1234,
+    };
+    assert_eq!(page.items, vec![expected]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_filter_by_cwd_branch_and_creation_time() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let sessions = [
+        ("2025-05-01T10:00:00Z", "/repo", "main"),
+        ("2025-05-02T10:00:00Z", "/repo", "feature"),
+        ("2025-05-03T10:00:00Z", "/other", "main"),
+    ];
+    for (i, (ts, cwd, branch)) in sessions.into_iter().enumerate() {
+        let uuid = Uuid::from_u128(i as u128 + 1);
+        let file_ts = ts[..19].replace(':', "-");
+        write_rollout_lines(
+            &session_path(home, &file_ts, uuid),
+            &[
+                session_meta_line(ts, uuid, cwd),
+                git_branch_line(ts, branch),
+                user_message_line(ts, &format!("{cwd} on {branch}")),
+            ],
+        )?;
+    }
+
+    let previews = |page: ConversationsPage| -> Vec<String> {
+        page.items
+            .into_iter()
+            .filter_map(|item| item.preview)
+            .collect()
+    };
+    let utc = |ts: &str| {
+        chrono::DateTime::parse_from_rfc3339(ts)
+            .unwrap()
+            .with_timezone(&chrono::Utc)
+    };
+
+    let in_repo = ConversationFilter {
+        cwd: Some(PathBuf::from("/repo")),
+        ..Default::default()
+    };
+    assert_eq!(
+        previews(list(home, &in_repo).await),
+        vec!["/repo on feature", "/repo on main"]
+    );
+
+    let on_main = ConversationFilter {
+        git_branch: Some("main".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        previews(list(home, &on_main).await),
+        vec!["/other on main", "/repo on main"]
+    );
+
+    let on_may_second = ConversationFilter {
+        created_after: Some(utc("2025-05-02T00:00:00Z")),
+        created_before: Some(utc("2025-05-03T00:00:00Z")),
+        ..Default::default()
+    };
+    assert_eq!(
+        previews(list(home, &on_may_second).await),
+        vec!["/repo on feature"]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_index_keeps_latest_line_and_skips_removed_sessions() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let kept = Uuid::from_u128(1);
+    let removed = Uuid::from_u128(2);
+    write_session_file(home, "2025-02-01T10-00-00", kept, 0, None)?;
+    write_session_file(home, "2025-02-02T10-00-00", removed, 0, None)?;
+
+    // The first listing builds the index from the rollout files.
+    assert_eq!(
+        list(home, &ConversationFilter::default()).await.items.len(),
+        2
+    );
+    let index_path = home.join("sessions").join(SESSION_INDEX_FILE);
+    assert_eq!(fs::read_to_string(&index_path)?.lines().count(), 2);
+
+    let mut updated = expected_item(home, "2025-02-01T10-00-00", kept, SessionSource::VSCode);
+    for total_tokens in 1..=10 {
+        updated.total_tokens = total_tokens;
+        append_to_index(home, &updated).await?;
+    }
+    fs::remove_file(session_path(home, "2025-02-02T10-00-00", removed))?;
+
+    let page = list(home, &ConversationFilter::default()).await;
+    assert_eq!(page.items, vec![updated.clone()]);
+
+    // Superseded lines and removed sessions are dropped once they dominate the index.
+    let index: Vec<ConversationItem> = fs::read_to_string(&index_path)?
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(index, vec![updated]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_index_picks_up_sessions_missing_from_it() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let first = Uuid::from_u128(1);
+    write_session_file(home, "2025-02-01T10-00-00", first, 0, None)?;
+    assert_eq!(
+        list(home, &ConversationFilter::default()).await.items.len(),
+        1
+    );
+
+    // Recorded while the index could not be written to.
+    let second = Uuid::from_u128(2);
+    write_session_file(home, "2025-02-02T10-00-00", second, 0, None)?;
+
+    let page = list(home, &ConversationFilter::default()).await;
+    assert_eq!(page.items.len(), 2);
+    let index_path = home.join("sessions").join(SESSION_INDEX_FILE);
+    assert_eq!(fs::read_to_string(&index_path)?.lines().count(), 2);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_index_keeps_lines_appended_during_a_rewrite() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+
+    let timestamps: Vec<String> = (1..=8)
+        .map(|day| format!("2025-02-{day:02}T10-00-00"))
+        .collect();
+    for (n, ts) in timestamps.iter().enumerate() {
+        write_session_file(&home, ts, Uuid::from_u128(n as u128), 0, None)?;
+    }
+    // Superseded lines make every listing below rewrite the index.
+    for _ in 0..4 {
+        list(&home, &ConversationFilter::default()).await;
+        for (n, ts) in timestamps.iter().enumerate() {
+            let item = expected_item(&home, ts, Uuid::from_u128(n as u128), SessionSource::VSCode);
+            append_to_index(&home, &item).await?;
+        }
+    }
+
+    let mut tasks = Vec::new();
+    for (n, ts) in timestamps.iter().enumerate() {
+        let mut item = expected_item(&home, ts, Uuid::from_u128(n as u128), SessionSource::VSCode);
+        item.total_tokens = 7;
+        let home = home.clone();
+        tasks.push(tokio::spawn(
+            async move { append_to_index(&home, &item).await },
+        ));
+        let home = home.clone();
+        tasks.push(tokio::spawn(async move {
+            get_conversations(
+                &home,
+                10,
+                None,
+                NO_SOURCE_FILTER,
+                &ConversationFilter::default(),
+            )
+            .await
+            .map(|_| ())
+        }));
+    }
+    for task in tasks {
+        task.await??;
+    }
+
+    let page = list(&home, &ConversationFilter::default()).await;
+    assert_eq!(page.items.len(), 8);
+    assert!(page.items.iter().all(|item| item.total_tokens == 7));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recorder_appends_to_existing_index() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let cwd = TempDir::new().unwrap();
+
+    let mut config = Config::load_from_base_config_with_overrides(
+        ConfigToml::default(),
+        ConfigOverrides::default(),
+        home.to_path_buf(),
+    )?;
+    config.cwd = cwd.path().to_path_buf();
+
+    let conversation_id = ConversationId::new();
+    let recorder = RolloutRecorder::new(
+        &config,
+        RolloutRecorderParams::new(conversation_id, None, SessionSource::Cli),
+    )
+    .await?;
+    recorder.flush().await?;
+
+    // No index exists yet, so listing rebuilds it. The session has no user
+    // message and is therefore not listed.
+    assert!(
+        list(home, &ConversationFilter::default())
+            .await
+            .items
+            .is_empty()
+    );
+    let index_path = home.join("sessions").join(SESSION_INDEX_FILE);
+    assert_eq!(fs::read_to_string(&index_path)?.lines().count(), 1);
+
+    recorder
+        .record_items(&[user_message_line("", "first prompt").item])
+        .await?;
+    recorder.flush().await?;
+
+    let page = list(home, &ConversationFilter::default()).await;
+    assert_eq!(page.items.len(), 1);
+    let item = &page.items[0];
+    assert_eq!(item.id, conversation_id);
+    assert_eq!(item.path, recorder.get_rollout_path());
+    assert_eq!(item.cwd, cwd.path());
+    assert_eq!(item.preview.as_deref(), Some("first prompt"));
+    assert_eq!(fs::read_to_string(&index_path)?.lines().count(), 2);
+
+    recorder.shutdown().await?;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_git_info_merges_into_session_meta() -> Result<()> {
     let temp = TempDir::new().unwrap();
@@ -1037,3 +877,291 @@ async fn test_git_info_merges_into_session_meta() -> Result<()> {
 
     Ok(())
 }
+
+fn response_message_line(ts_str: &str, role: &str, text: &str) -> RolloutLine {
+    let content = if role == "assistant" {
+        ContentItem::OutputText {
+            text: text.to_string(),
+        }
+    } else {
+        ContentItem::InputText {
+            text: text.to_string(),
+        }
+    };
+    RolloutLine {
+        timestamp: ts_str.to_string(),
+        item: RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![content],
+        }),
+    }
+}
+
+#[tokio::test]
+async fn test_search_ranks_hits_and_reports_turns() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let first = Uuid::from_u128(1);
+    write_rollout_lines(
+        &session_path(home, "2025-07-01T09-00-00", first),
+        &[
+            session_meta_line("2025-07-01T09:00:00Z", first, "/repo"),
+            response_message_line(
+                "2025-07-01T09:00:00Z",
+                "user",
+                "<environment_context>migration</environment_context>",
+            ),
+            response_message_line("2025-07-01T09:00:01Z", "user", "list the tables"),
+            response_message_line(
+                "2025-07-01T09:00:02Z",
+                "assistant",
+                "The Migration table is missing",
+            ),
+            response_message_line("2025-07-01T09:00:03Z", "user", "fix the migration bug"),
+            RolloutLine {
+                timestamp: "2025-07-01T09:00:04Z".to_string(),
+                item: RolloutItem::ResponseItem(ResponseItem::LocalShellCall {
+                    id: None,
+                    call_id: Some("call".to_string()),
+                    status: LocalShellStatus::Completed,
+                    action: LocalShellAction::Exec(LocalShellExecAction {
+                        command: vec!["cargo".to_string(), "run-migration".to_string()],
+                        timeout_ms: None,
+                        working_directory: None,
+                        env: None,
+                        user: None,
+                    }),
+                }),
+            },
+        ],
+    )?;
+
+    let second = Uuid::from_u128(2);
+    write_rollout_lines(
+        &session_path(home, "2025-07-02T09-00-00", second),
+        &[
+            session_meta_line("2025-07-02T09:00:00Z", second, "/repo"),
+            response_message_line("2025-07-02T09:00:01Z", "user", "write the docs"),
+        ],
+    )?;
+
+    let hits = search_conversations(home, "MIGRATION", NO_SOURCE_FILTER, 10).await?;
+    let summary: Vec<(SearchHitKind, usize, &str)> = hits
+        .iter()
+        .map(|hit| (hit.kind, hit.turn, hit.snippet.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (SearchHitKind::UserMessage, 1, "fix the migration bug"),
+            (
+                SearchHitKind::AssistantMessage,
+                0,
+                "The Migration table is missing"
+            ),
+        ]
+    );
+    assert_eq!(hits[0].conversation.id.to_string(), first.to_string());
+
+    let hits = search_conversations(home, "migration docs", NO_SOURCE_FILTER, 10).await?;
+    assert_eq!(hits, Vec::new());
+
+    let hits = search_conversations(home, "run-migration", NO_SOURCE_FILTER, 10).await?;
+    let summary: Vec<(SearchHitKind, usize)> =
+        hits.iter().map(|hit| (hit.kind, hit.turn)).collect();
+    assert_eq!(summary, vec![(SearchHitKind::Command, 1)]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_compressed_rollout_is_listed_read_and_resumed() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let cwd = TempDir::new().unwrap();
+
+    let mut config = Config::load_from_base_config_with_overrides(
+        ConfigToml::default(),
+        ConfigOverrides::default(),
+        home.to_path_buf(),
+    )?;
+    config.cwd = cwd.path().to_path_buf();
+    config.session_retention.compress = true;
+
+    let conversation_id = ConversationId::new();
+    let recorder = RolloutRecorder::new(
+        &config,
+        RolloutRecorderParams::new(conversation_id, None, SessionSource::Cli),
+    )
+    .await?;
+    recorder
+        .record_items(&[user_message_line("", "compress me").item])
+        .await?;
+    let plain_path = recorder.get_rollout_path();
+    recorder.shutdown().await?;
+
+    let compressed_path = compressed_rollout_path(&plain_path);
+    assert!(!plain_path.exists());
+    assert!(compressed_path.exists());
+
+    let page = list(home, &ConversationFilter::default()).await;
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].path, compressed_path);
+    assert_eq!(page.items[0].preview.as_deref(), Some("compress me"));
+    assert!(
+        get_conversation(&compressed_path)
+            .await?
+            .contains("compress me")
+    );
+
+    let InitialHistory::Resumed(resumed) =
+        RolloutRecorder::get_rollout_history(&compressed_path).await?
+    else {
+        panic!("expected resumed history");
+    };
+    assert_eq!(resumed.conversation_id, conversation_id);
+
+    // Resuming restores the plain rollout so new items can be appended.
+    config.session_retention.compress = false;
+    let recorder = RolloutRecorder::new(
+        &config,
+        RolloutRecorderParams::resume(compressed_path.clone()),
+    )
+    .await?;
+    assert_eq!(recorder.get_rollout_path(), plain_path);
+    recorder.shutdown().await?;
+    assert!(plain_path.exists());
+    assert!(!compressed_path.exists());
+    assert_eq!(
+        list(home, &ConversationFilter::default()).await.items[0].path,
+        plain_path
+    );
+    Ok(())
+}
+
+fn rollout_file(name: &str, bytes: u64, days_old: u64, now: SystemTime) -> RolloutFile {
+    RolloutFile {
+        path: PathBuf::from(name),
+        bytes,
+        modified: now - Duration::from_secs(days_old * 24 * 60 * 60),
+    }
+}
+
+#[test]
+fn test_select_for_pruning_applies_age_then_total_size() {
+    let now = SystemTime::now();
+    let files = vec![
+        rollout_file("recent", 40, 1, now),
+        rollout_file("ancient", 10, 90, now),
+        rollout_file("older", 30, 10, now),
+        rollout_file("old", 20, 5, now),
+    ];
+    let retention = SessionRetention {
+        max_age_days: Some(30),
+        max_total_bytes: Some(60),
+        ..SessionRetention::default()
+    };
+
+    let (removed, kept) = select_for_pruning(files, &retention, None, now);
+    let names = |files: &[RolloutFile]| -> Vec<String> {
+        files
+            .iter()
+            .map(|file| file.path.display().to_string())
+            .collect()
+    };
+    assert_eq!(names(&removed), vec!["ancient", "older"]);
+    assert_eq!(names(&kept), vec!["old", "recent"]);
+}
+
+#[test]
+fn test_select_for_pruning_never_removes_kept_rollout() {
+    let now = SystemTime::now();
+    let files = vec![
+        rollout_file("current", 100, 60, now),
+        rollout_file("other", 10, 1, now),
+    ];
+    let retention = SessionRetention {
+        max_age_days: Some(30),
+        max_total_bytes: Some(50),
+        ..SessionRetention::default()
+    };
+
+    let (removed, kept) = select_for_pruning(files, &retention, Some(Path::new("current")), now);
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].path, PathBuf::from("other"));
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].path, PathBuf::from("current"));
+}
+
+#[tokio::test]
+async fn test_prune_sessions_dry_run_and_archived() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let session = Uuid::from_u128(1);
+    write_session_file(home, "2025-03-01T10-00-00", session, 0, None)?;
+    let session_file = session_path(home, "2025-03-01T10-00-00", session);
+    let archived_dir = home.join(ARCHIVED_SESSIONS_SUBDIR);
+    fs::create_dir_all(&archived_dir)?;
+    let archived_file = archived_dir.join(format!(
+        "rollout-2025-02-01T10-00-00-{}.jsonl",
+        Uuid::from_u128(2)
+    ));
+    fs::write(&archived_file, "{}\n")?;
+
+    let retention = SessionRetention {
+        max_total_bytes: Some(0),
+        ..SessionRetention::default()
+    };
+
+    let report = prune_sessions(home, &retention, None, true).await?;
+    assert_eq!(report.removed.len(), 1);
+    assert_eq!(report.removed[0].path, session_file);
+    assert!(session_file.exists());
+
+    let report = prune_sessions(home, &retention, None, false).await?;
+    assert_eq!(report.removed.len(), 1);
+    assert!(!session_file.exists());
+    // The emptied day, month and year directories are removed as well.
+    assert!(!home.join("sessions").join("2025").exists());
+    assert!(archived_file.exists());
+
+    let retention = SessionRetention {
+        keep_archived: false,
+        ..retention
+    };
+    let report = prune_sessions(home, &retention, None, false).await?;
+    assert_eq!(report.removed.len(), 1);
+    assert!(!archived_file.exists());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prune_sessions_skips_rollouts_in_use() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let live = Uuid::from_u128(1);
+    let idle = Uuid::from_u128(2);
+    write_session_file(home, "2025-03-01T10-00-00", live, 0, None)?;
+    write_session_file(home, "2025-03-02T10-00-00", idle, 0, None)?;
+    let live_file = session_path(home, "2025-03-01T10-00-00", live);
+    let idle_file = session_path(home, "2025-03-02T10-00-00", idle);
+
+    // Another process recording to the rollout holds a shared lock on it.
+    let writer = File::open(&live_file)?;
+    writer.lock_shared()?;
+
+    let retention = SessionRetention {
+        max_total_bytes: Some(0),
+        ..SessionRetention::default()
+    };
+    let report = prune_sessions(home, &retention, None, false).await?;
+    assert_eq!(report.removed.len(), 1);
+    assert_eq!(report.removed[0].path, idle_file);
+    assert_eq!(report.kept, 1);
+    assert!(live_file.exists());
+    assert!(!idle_file.exists());
+    Ok(())
+}
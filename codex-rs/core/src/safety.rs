@@ -5,9 +5,11 @@ use std::path::PathBuf;
 
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_execpolicy::Policy;
 
 use crate::exec::SandboxType;
 
+use crate::command_safety::exec_policy::is_safe_per_exec_policy;
 use crate::command_safety::is_dangerous_command::command_might_be_dangerous;
 use crate::command_safety::is_safe_command::is_known_safe_command;
 use crate::protocol::AskForApproval;
@@ -87,13 +89,15 @@ pub fn assess_patch_safety(
 /// true:
 ///
 /// - the user has explicitly approved the command
-/// - the command is on the "known safe" list
+/// - the command is on the "known safe" list, or is a read-only match for the
+///   user's execpolicy files
 /// - `DangerFullAccess` was specified and `UnlessTrusted` was not
 pub fn assess_command_safety(
     command: &[String],
     approval_policy: AskForApproval,
     sandbox_policy: &SandboxPolicy,
     approved: &HashSet<Vec<String>>,
+    exec_policy: Option<&Policy>,
     with_escalated_permissions: bool,
 ) -> SafetyCheck {
     // Some commands look dangerous. Even if they are run inside a sandbox,
@@ -124,7 +128,9 @@ pub fn assess_command_safety(
     // `approved.contains(command)` is `true`, the user may have approved it for
     // the session _because_ they know it needs to run outside a sandbox.
 
-    if is_known_safe_command(command) || approved.contains(command) {
+    let known_safe = is_known_safe_command(command)
+        || exec_policy.is_some_and(|policy| is_safe_per_exec_policy(policy, command));
+    if known_safe || approved.contains(command) {
         let user_explicitly_approved = approved.contains(command);
         return SafetyCheck::AutoApprove {
             sandbox_type: SandboxType::None,
@@ -218,7 +224,7 @@ fn is_write_patch_constrained_to_writable_paths(
 ) -> bool {
     // Early‑exit if there are no declared writable roots.
     let writable_roots = match sandbox_policy {
-        SandboxPolicy::ReadOnly => {
+        SandboxPolicy::ReadOnly { .. } => {
             return false;
         }
         SandboxPolicy::DangerFullAccess => {
@@ -309,8 +315,11 @@ mod tests {
         let policy_workspace_only = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            allowed_domains: vec![],
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            readable_roots: vec![],
+            deny_read: vec![],
         };
 
         assert!(is_write_patch_constrained_to_writable_paths(
@@ -330,8 +339,11 @@ mod tests {
         let policy_with_parent = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![parent],
             network_access: false,
+            allowed_domains: vec![],
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            readable_roots: vec![],
+            deny_read: vec![],
         };
         assert!(is_write_patch_constrained_to_writable_paths(
             &add_outside,
@@ -345,7 +357,7 @@ mod tests {
         // Should not be a trusted command
         let command = vec!["git commit".to_string()];
         let approval_policy = AskForApproval::OnRequest;
-        let sandbox_policy = SandboxPolicy::ReadOnly;
+        let sandbox_policy = SandboxPolicy::new_read_only_policy();
         let approved: HashSet<Vec<String>> = HashSet::new();
         let request_escalated_privileges = true;
 
@@ -354,6 +366,7 @@ mod tests {
             approval_policy,
             &sandbox_policy,
             &approved,
+            None,
             request_escalated_privileges,
         );
 
@@ -364,7 +377,7 @@ mod tests {
     fn dangerous_command_allowed_if_explicitly_approved() {
         let command = vec!["git".to_string(), "reset".to_string(), "--hard".to_string()];
         let approval_policy = AskForApproval::OnRequest;
-        let sandbox_policy = SandboxPolicy::ReadOnly;
+        let sandbox_policy = SandboxPolicy::new_read_only_policy();
         let mut approved: HashSet<Vec<String>> = HashSet::new();
         approved.insert(command.clone());
         let request_escalated_privileges = false;
@@ -374,6 +387,7 @@ mod tests {
             approval_policy,
             &sandbox_policy,
             &approved,
+            None,
             request_escalated_privileges,
         );
 
@@ -390,7 +404,7 @@ mod tests {
     fn dangerous_command_not_allowed_if_not_explicitly_approved() {
         let command = vec!["git".to_string(), "reset".to_string(), "--hard".to_string()];
         let approval_policy = AskForApproval::Never;
-        let sandbox_policy = SandboxPolicy::ReadOnly;
+        let sandbox_policy = SandboxPolicy::new_read_only_policy();
         let approved: HashSet<Vec<String>> = HashSet::new();
         let request_escalated_privileges = false;
 
@@ -399,6 +413,7 @@ mod tests {
             approval_policy,
             &sandbox_policy,
             &approved,
+            None,
             request_escalated_privileges,
         );
 
@@ -415,7 +430,7 @@ mod tests {
     fn test_request_escalated_privileges_no_sandbox_fallback() {
         let command = vec!["git".to_string(), "commit".to_string()];
         let approval_policy = AskForApproval::OnRequest;
-        let sandbox_policy = SandboxPolicy::ReadOnly;
+        let sandbox_policy = SandboxPolicy::new_read_only_policy();
         let approved: HashSet<Vec<String>> = HashSet::new();
         let request_escalated_privileges = false;
 
@@ -424,6 +439,7 @@ mod tests {
             approval_policy,
             &sandbox_policy,
             &approved,
+            None,
             request_escalated_privileges,
         );
 
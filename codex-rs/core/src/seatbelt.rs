@@ -3,6 +3,7 @@ use std::path::Path;
 use std::path::PathBuf;
 use tokio::process::Child;
 
+use crate::config_types::ResourceLimits;
 use crate::protocol::SandboxPolicy;
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
 use crate::spawn::StdioPolicy;
@@ -10,6 +11,21 @@ use crate::spawn::spawn_child_async;
 
 const MACOS_SEATBELT_BASE_POLICY: &str = include_str!("seatbelt_base_policy.sbpl");
 
+/// Folders every program needs in order to run at all. They stay readable
+/// when `readable_roots` limits reads to a few folders.
+const SYSTEM_READABLE_ROOTS: &[&str] = &[
+    "/bin",
+    "/sbin",
+    "/usr",
+    "/System",
+    "/Library",
+    "/Applications",
+    "/private/etc",
+    "/dev",
+    "/opt",
+    "/nix",
+];
+
 /// When working with `sandbox-exec`, only consider `sandbox-exec` in `/usr/bin`
 /// to defend against an attacker trying to inject a malicious version on the
 /// PATH. If /usr/bin/sandbox-exec has been tampered with, then the attacker
@@ -21,6 +37,7 @@ pub async fn spawn_command_under_seatbelt(
     command_cwd: PathBuf,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    resource_limits: &ResourceLimits,
     stdio_policy: StdioPolicy,
     mut env: HashMap<String, String>,
 ) -> std::io::Result<Child> {
@@ -35,6 +52,8 @@ pub async fn spawn_command_under_seatbelt(
         sandbox_policy,
         stdio_policy,
         env,
+        resource_limits,
+        None,
     )
     .await
 }
@@ -44,7 +63,7 @@ fn create_seatbelt_command_args(
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
 ) -> Vec<String> {
-    let (file_write_policy, extra_cli_args) = {
+    let (file_write_policy, mut extra_cli_args) = {
         if sandbox_policy.has_full_disk_write_access() {
             // Allegedly, this is more permissive than `(allow file-write*)`.
             (
@@ -97,11 +116,60 @@ fn create_seatbelt_command_args(
         }
     };
 
-    let file_read_policy = if sandbox_policy.has_full_disk_read_access() {
-        "; allow read-only file operations\n(allow file-read*)"
-    } else {
-        ""
+    let mut file_read_policy = match sandbox_policy.get_readable_roots_with_cwd(sandbox_policy_cwd)
+    {
+        None => "; allow read-only file operations\n(allow file-read*)".to_string(),
+        Some(mut readable_roots) => {
+            readable_roots.extend(
+                sandbox_policy
+                    .get_writable_roots_with_cwd(sandbox_policy_cwd)
+                    .into_iter()
+                    .map(|writable_root| writable_root.root),
+            );
+            // Metadata stays readable everywhere, so that paths can be
+            // resolved through the folders that are not readable.
+            let mut readable_folder_policies = vec![r#"(literal "/")"#.to_string()];
+            readable_folder_policies.extend(
+                SYSTEM_READABLE_ROOTS
+                    .iter()
+                    .map(|root| format!("(subpath \"{root}\")")),
+            );
+            for (index, root) in readable_roots.iter().enumerate() {
+                let canonical_root = root.canonicalize().unwrap_or_else(|_| root.clone());
+                let root_param = format!("READABLE_ROOT_{index}");
+                extra_cli_args.push(format!(
+                    "-D{root_param}={}",
+                    canonical_root.to_string_lossy()
+                ));
+                readable_folder_policies.push(format!("(subpath (param \"{root_param}\"))"));
+            }
+            format!(
+                "; allow read-only file operations in the readable roots\n(allow file-read-metadata)\n(allow file-read*\n{}\n)",
+                readable_folder_policies.join(" ")
+            )
+        }
     };
+    for (index, path) in sandbox_policy
+        .get_deny_read_paths_with_cwd(sandbox_policy_cwd)
+        .iter()
+        .enumerate()
+    {
+        let canonical_path = path.canonicalize().unwrap_or_else(|_| path.clone());
+        let deny_param = format!("DENY_READ_{index}");
+        extra_cli_args.push(format!(
+            "-D{deny_param}={}",
+            canonical_path.to_string_lossy()
+        ));
+        file_read_policy.push_str(&format!(
+            "\n(deny file-read* (subpath (param \"{deny_param}\")))"
+        ));
+    }
+    for name in sandbox_policy.get_deny_read_names() {
+        file_read_policy.push_str(&format!(
+            "\n(deny file-read* (regex #\"(^|/){}(/|$)\"))",
+            escape_seatbelt_regex(&name)
+        ));
+    }
 
     // TODO(mbolin): apply_patch calls must also honor the SandboxPolicy.
     let network_policy = if sandbox_policy.has_full_network_access() {
@@ -121,6 +189,18 @@ fn create_seatbelt_command_args(
     seatbelt_args
 }
 
+/// Escapes `literal` for a `#"..."` regex of the seatbelt policy language.
+fn escape_seatbelt_regex(literal: &str) -> String {
+    let mut escaped = String::with_capacity(literal.len());
+    for c in literal.chars() {
+        if r#".^$|()[]{}*+?\""#.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::MACOS_SEATBELT_BASE_POLICY;
@@ -156,8 +236,11 @@ mod tests {
         let policy = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![root_with_git, root_without_git],
             network_access: false,
+            allowed_domains: vec![],
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            readable_roots: vec![],
+            deny_read: vec![],
         };
 
         let args = create_seatbelt_command_args(
@@ -231,8 +314,11 @@ mod tests {
         let policy = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            allowed_domains: vec![],
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            readable_roots: vec![],
+            deny_read: vec![],
         };
 
         let args = create_seatbelt_command_args(
@@ -301,6 +387,92 @@ mod tests {
         assert_eq!(expected_args, args);
     }
 
+    #[test]
+    fn create_seatbelt_args_with_deny_read() {
+        if cfg!(target_os = "windows") {
+            // /tmp does not exist on Windows, so skip this test.
+            return;
+        }
+
+        let tmp = TempDir::new().expect("tempdir");
+        let cwd = tmp.path().canonicalize().expect("canonicalize tmp");
+        let policy = SandboxPolicy::ReadOnly {
+            readable_roots: vec![],
+            deny_read: vec![PathBuf::from("./local.key"), PathBuf::from(".env")],
+        };
+
+        let args = create_seatbelt_command_args(
+            vec!["/bin/echo".to_string(), "hello".to_string()],
+            &policy,
+            &cwd,
+        );
+
+        let expected_policy = format!(
+            r#"{MACOS_SEATBELT_BASE_POLICY}
+; allow read-only file operations
+(allow file-read*)
+(deny file-read* (subpath (param "DENY_READ_0")))
+(deny file-read* (regex #"(^|/)\.env(/|$)"))
+
+"#,
+        );
+        let expected_args = vec![
+            "-p".to_string(),
+            expected_policy,
+            format!("-DDENY_READ_0={}", cwd.join("local.key").to_string_lossy()),
+            "--".to_string(),
+            "/bin/echo".to_string(),
+            "hello".to_string(),
+        ];
+
+        assert_eq!(expected_args, args);
+    }
+
+    #[test]
+    fn create_seatbelt_args_with_readable_roots() {
+        if cfg!(target_os = "windows") {
+            // /tmp does not exist on Windows, so skip this test.
+            return;
+        }
+
+        let tmp = TempDir::new().expect("tempdir");
+        let cwd = tmp.path().canonicalize().expect("canonicalize tmp");
+        let shared = cwd.join("shared");
+        let policy = SandboxPolicy::ReadOnly {
+            readable_roots: vec![PathBuf::from("./shared")],
+            deny_read: vec![PathBuf::from(".env")],
+        };
+
+        let args = create_seatbelt_command_args(
+            vec!["/bin/echo".to_string(), "hello".to_string()],
+            &policy,
+            &cwd,
+        );
+
+        let expected_policy = format!(
+            r#"{MACOS_SEATBELT_BASE_POLICY}
+; allow read-only file operations in the readable roots
+(allow file-read-metadata)
+(allow file-read*
+(literal "/") (subpath "/bin") (subpath "/sbin") (subpath "/usr") (subpath "/System") (subpath "/Library") (subpath "/Applications") (subpath "/private/etc") (subpath "/dev") (subpath "/opt") (subpath "/nix") (subpath (param "READABLE_ROOT_0")) (subpath (param "READABLE_ROOT_1"))
+)
+(deny file-read* (regex #"(^|/)\.env(/|$)"))
+
+"#,
+        );
+        let expected_args = vec![
+            "-p".to_string(),
+            expected_policy,
+            format!("-DREADABLE_ROOT_0={}", shared.to_string_lossy()),
+            format!("-DREADABLE_ROOT_1={}", cwd.to_string_lossy()),
+            "--".to_string(),
+            "/bin/echo".to_string(),
+            "hello".to_string(),
+        ];
+
+        assert_eq!(expected_args, args);
+    }
+
     struct PopulatedTmp {
         root_with_git: PathBuf,
         root_without_git: PathBuf,
@@ -305,9 +305,11 @@ mod tests {
         for (input, expected_cmd, expected_output) in cases {
             use std::collections::HashMap;
 
+            use crate::config_types::ResourceLimits;
             use crate::exec::ExecParams;
             use crate::exec::SandboxType;
             use crate::exec::process_exec_tool_call;
+            use crate::landlock::LinuxSandboxOptions;
             use crate::protocol::SandboxPolicy;
 
             let temp_home = tempfile::tempdir().unwrap();
@@ -352,6 +354,8 @@ mod tests {
                 &SandboxPolicy::DangerFullAccess,
                 temp_home.path(),
                 &None,
+                &ResourceLimits::default(),
+                LinuxSandboxOptions::default(),
                 None,
             )
             .await
@@ -412,9 +416,11 @@ mod macos_tests {
             use std::collections::HashMap;
             use std::path::PathBuf;
 
+            use crate::config_types::ResourceLimits;
             use crate::exec::ExecParams;
             use crate::exec::SandboxType;
             use crate::exec::process_exec_tool_call;
+            use crate::landlock::LinuxSandboxOptions;
             use crate::protocol::SandboxPolicy;
 
             // create a temp directory with a zshrc file in it
@@ -460,6 +466,8 @@ mod macos_tests {
                 &SandboxPolicy::DangerFullAccess,
                 temp_home.path(),
                 &None,
+                &ResourceLimits::default(),
+                LinuxSandboxOptions::default(),
                 None,
             )
             .await
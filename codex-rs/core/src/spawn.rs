@@ -1,10 +1,16 @@
 use std::collections::HashMap;
+use std::io::PipeWriter;
+#[cfg(unix)]
+use std::os::fd::AsRawFd;
 use std::path::PathBuf;
 use std::process::Stdio;
 use tokio::process::Child;
 use tokio::process::Command;
 use tracing::trace;
 
+use crate::config_types::ResourceLimits;
+#[cfg(unix)]
+use crate::landlock::SANDBOX_REPORT_FD;
 use crate::protocol::SandboxPolicy;
 
 /// Experimental environment variable that will be set to some non-empty value
@@ -35,6 +41,11 @@ pub enum StdioPolicy {
 /// For now, we take `SandboxPolicy` as a parameter to spawn_child() because
 /// we need to determine whether to set the
 /// `CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR` environment variable.
+///
+/// `resource_limits` are applied to the child with `setrlimit(2)` on Unix.
+/// `report_channel`, when set, is handed to the child as
+/// [`crate::landlock::SANDBOX_REPORT_FD`].
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn spawn_child_async(
     program: PathBuf,
     args: Vec<String>,
@@ -43,6 +54,8 @@ pub(crate) async fn spawn_child_async(
     sandbox_policy: &SandboxPolicy,
     stdio_policy: StdioPolicy,
     env: HashMap<String, String>,
+    resource_limits: &ResourceLimits,
+    #[cfg_attr(not(unix), allow(unused_variables))] report_channel: Option<&PipeWriter>,
 ) -> std::io::Result<Child> {
     trace!(
         "spawn_child_async: {program:?} {args:?} {arg0:?} {cwd:?} {sandbox_policy:?} {stdio_policy:?} {env:?}"
@@ -85,6 +98,36 @@ pub(crate) async fn spawn_child_async(
         });
     }
 
+    #[cfg(unix)]
+    if !resource_limits.is_unlimited() {
+        let resource_limits = resource_limits.clone();
+        unsafe {
+            cmd.pre_exec(move || crate::resource_limits::apply_rlimits(&resource_limits));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = resource_limits;
+
+    #[cfg(unix)]
+    if let Some(report_channel) = report_channel {
+        let fd = report_channel.as_raw_fd();
+        unsafe {
+            cmd.pre_exec(move || {
+                // `dup2` leaves the descriptor close-on-exec when it already
+                // has the right number.
+                let result = if fd == SANDBOX_REPORT_FD {
+                    libc::fcntl(fd, libc::F_SETFD, 0)
+                } else {
+                    libc::dup2(fd, SANDBOX_REPORT_FD)
+                };
+                if result == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+
     match stdio_policy {
         StdioPolicy::RedirectForShellTool => {
             // Do not create a file descriptor for stdin because otherwise some
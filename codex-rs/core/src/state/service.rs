@@ -1,16 +1,24 @@
 use crate::RolloutRecorder;
 use crate::exec_command::ExecSessionManager;
 use crate::executor::Executor;
+use crate::hooks::HookRunner;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
+use std::sync::Arc;
 use tokio::sync::Mutex;
+use tokio::sync::RwLock;
 
 pub(crate) struct SessionServices {
-    pub(crate) mcp_connection_manager: McpConnectionManager,
+    /// Replaced as a whole on reload; see `Session::mcp_connection_manager`.
+    pub(crate) mcp_connection_manager: RwLock<Arc<McpConnectionManager>>,
+    /// Held for the whole of a reload, so that concurrent reloads run one
+    /// after another instead of each starting the same new servers.
+    pub(crate) mcp_reload: Mutex<()>,
     pub(crate) session_manager: ExecSessionManager,
     pub(crate) unified_exec_manager: UnifiedExecSessionManager,
     pub(crate) notifier: UserNotifier,
+    pub(crate) hooks: HookRunner,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: crate::shell::Shell,
     pub(crate) show_raw_agent_reasoning: bool,
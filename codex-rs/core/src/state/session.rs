@@ -1,8 +1,11 @@
 //! Session-wide mutable state.
 
+use std::collections::HashSet;
+
 use codex_protocol::models::ResponseItem;
 
 use crate::conversation_history::ConversationHistory;
+use crate::protocol::PinnedItem;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
@@ -13,6 +16,18 @@ pub(crate) struct SessionState {
     pub(crate) history: ConversationHistory,
     pub(crate) token_info: Option<TokenUsageInfo>,
     pub(crate) latest_rate_limits: Option<RateLimitSnapshot>,
+    /// Estimated cost of the session's model requests in USD.
+    pub(crate) cost_usd: Option<f64>,
+    /// Configured `session_budget_usd`.
+    pub(crate) session_budget_usd: Option<f64>,
+    /// Cost at which the user is next asked whether to keep going. Raised by
+    /// `session_budget_usd` each time they agree, cleared when they lift the
+    /// budget for the session.
+    pub(crate) cost_limit_usd: Option<f64>,
+    /// Items re-inserted verbatim whenever the history is compacted.
+    pub(crate) pinned_items: Vec<PinnedItem>,
+    /// MCP servers whose sampling requests the user approved for the session.
+    pub(crate) sampling_approved_servers: HashSet<String>,
 }
 
 impl SessionState {
@@ -54,6 +69,46 @@ impl SessionState {
         );
     }
 
+    /// Adds usage from model requests made outside the conversation, such as
+    /// MCP sampling, to the running total. `last_token_usage` is left alone
+    /// because it tracks how full the conversation's context window is.
+    pub(crate) fn add_token_usage_outside_context(
+        &mut self,
+        usage: &TokenUsage,
+        model_context_window: Option<u64>,
+    ) {
+        let info = self.token_info.get_or_insert_with(|| TokenUsageInfo {
+            total_token_usage: TokenUsage::default(),
+            last_token_usage: TokenUsage::default(),
+            model_context_window,
+        });
+        info.total_token_usage.add_assign(usage);
+    }
+
+    pub(crate) fn add_cost(&mut self, cost_usd: f64) {
+        self.cost_usd = Some(self.cost_usd.unwrap_or_default() + cost_usd);
+    }
+
+    pub(crate) fn set_session_budget(&mut self, budget_usd: Option<f64>) {
+        self.session_budget_usd = budget_usd;
+        self.cost_limit_usd = budget_usd;
+    }
+
+    /// Returns the session cost and the limit it reached, if it reached one.
+    pub(crate) fn cost_over_limit(&self) -> Option<(f64, f64)> {
+        let cost = self.cost_usd?;
+        let limit = self.cost_limit_usd?;
+        (cost >= limit).then_some((cost, limit))
+    }
+
+    /// Allows another `session_budget_usd` worth of spending past the current
+    /// cost.
+    pub(crate) fn extend_cost_limit(&mut self) {
+        if let (Some(cost), Some(budget)) = (self.cost_usd, self.session_budget_usd) {
+            self.cost_limit_usd = Some(cost + budget);
+        }
+    }
+
     pub(crate) fn set_rate_limits(&mut self, snapshot: RateLimitSnapshot) {
         self.latest_rate_limits = Some(snapshot);
     }
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use codex_protocol::config_types::CompactionStrategy;
 
 use crate::codex::TurnContext;
 use crate::codex::compact;
@@ -11,7 +12,9 @@ use super::SessionTask;
 use super::SessionTaskContext;
 
 #[derive(Clone, Copy, Default)]
-pub(crate) struct CompactTask;
+pub(crate) struct CompactTask {
+    pub(crate) strategy: CompactionStrategy,
+}
 
 #[async_trait]
 impl SessionTask for CompactTask {
@@ -26,6 +29,6 @@ impl SessionTask for CompactTask {
         sub_id: String,
         input: Vec<InputItem>,
     ) -> Option<String> {
-        compact::run_compact_task(session.clone_session(), ctx, sub_id, input).await
+        compact::run_compact_task(session.clone_session(), ctx, sub_id, input, self.strategy).await
     }
 }
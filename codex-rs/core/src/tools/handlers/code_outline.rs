@@ -0,0 +1,565 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use ignore::WalkBuilder;
+use serde::Deserialize;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+
+use crate::function_tool::FunctionCallError;
+use crate::protocol::DenyRead;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ensure_readable;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct CodeOutlineHandler;
+
+/// Directory outlines stop after this many source files.
+const MAX_FILES: usize = 200;
+/// Outlines stop after this many lines of output.
+const MAX_OUTPUT_LINES: usize = 2000;
+/// Larger files (usually generated or minified) are skipped.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const INDENT: &str = "  ";
+
+/// JSON arguments accepted by the `code_outline` tool handler.
+#[derive(Deserialize)]
+struct CodeOutlineArgs {
+    /// Absolute path to a source file or a directory.
+    path: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Language {
+    Rust,
+    Python,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+/// A named definition and the 1-indexed lines it spans.
+#[derive(Debug, PartialEq)]
+struct Symbol {
+    label: &'static str,
+    name: String,
+    start_line: usize,
+    end_line: usize,
+    children: Vec<Symbol>,
+}
+
+/// How a syntax node is presented in the outline.
+struct SymbolKind {
+    label: &'static str,
+    name: String,
+    /// Whether nested definitions (methods, associated items) are listed.
+    container: bool,
+}
+
+#[async_trait]
+impl ToolHandler for CodeOutlineHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "code_outline handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: CodeOutlineArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        let path = PathBuf::from(&args.path);
+        if !path.is_absolute() {
+            return Err(FunctionCallError::RespondToModel(
+                "path must be an absolute path".to_string(),
+            ));
+        }
+        let deny_read = turn.sandbox_policy.get_deny_read_with_cwd(&turn.cwd);
+        ensure_readable(&deny_read, &path)?;
+
+        let content = tokio::task::spawn_blocking(move || outline_path(&path, &deny_read))
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("code_outline failed: {err}"))
+            })??;
+
+        Ok(ToolOutput::Function {
+            content,
+            success: Some(true),
+        })
+    }
+}
+
+fn outline_path(path: &Path, deny_read: &DenyRead) -> Result<String, FunctionCallError> {
+    let metadata = std::fs::metadata(path).map_err(|err| {
+        FunctionCallError::RespondToModel(format!("unable to access `{}`: {err}", path.display()))
+    })?;
+
+    if metadata.is_dir() {
+        return Ok(outline_dir(path, deny_read));
+    }
+
+    let Some(language) = Language::for_path(path) else {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "code_outline supports Rust, Python, TypeScript/JavaScript and Go files; `{}` is not one of them",
+            path.display()
+        )));
+    };
+    let source = std::fs::read_to_string(path).map_err(|err| {
+        FunctionCallError::RespondToModel(format!("failed to read `{}`: {err}", path.display()))
+    })?;
+
+    let symbols = outline_source(language, &source);
+    if symbols.is_empty() {
+        return Ok(format!("No symbols found in {}.", path.display()));
+    }
+    let mut lines = vec![path.display().to_string()];
+    render(&symbols, 1, &mut lines);
+    Ok(finish(lines))
+}
+
+/// Outlines every supported, non-ignored source file under `dir`, skipping
+/// the ones `deny_read` covers.
+fn outline_dir(dir: &Path, deny_read: &DenyRead) -> String {
+    let mut files: Vec<(PathBuf, Language)> = WalkBuilder::new(dir)
+        .require_git(false)
+        .filter_entry({
+            let deny_read = deny_read.clone();
+            move |entry| !deny_read.is_path_denied(entry.path())
+        })
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+                && entry
+                    .metadata()
+                    .is_ok_and(|metadata| metadata.len() <= MAX_FILE_BYTES)
+        })
+        .filter_map(|entry| {
+            let language = Language::for_path(entry.path())?;
+            Some((entry.into_path(), language))
+        })
+        .collect();
+    files.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut lines = vec![format!("Absolute path: {}", dir.display())];
+    let skipped_files = files.len().saturating_sub(MAX_FILES);
+    for (path, language) in files.into_iter().take(MAX_FILES) {
+        let Ok(source) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let symbols = outline_source(language, &source);
+        if symbols.is_empty() {
+            continue;
+        }
+        let relative = path.strip_prefix(dir).unwrap_or(&path);
+        lines.push(relative.display().to_string());
+        render(&symbols, 1, &mut lines);
+        if lines.len() > MAX_OUTPUT_LINES {
+            break;
+        }
+    }
+
+    if lines.len() == 1 {
+        return format!("No symbols found under {}.", dir.display());
+    }
+    if skipped_files > 0 {
+        lines.push(format!(
+            "[{skipped_files} more files not outlined; pass a narrower path]"
+        ));
+    }
+    finish(lines)
+}
+
+fn finish(mut lines: Vec<String>) -> String {
+    if lines.len() > MAX_OUTPUT_LINES {
+        lines.truncate(MAX_OUTPUT_LINES);
+        lines.push("[outline truncated; pass a narrower path]".to_string());
+    }
+    lines.join("\n")
+}
+
+fn render(symbols: &[Symbol], depth: usize, lines: &mut Vec<String>) {
+    for symbol in symbols {
+        lines.push(format!(
+            "{}{} {} [{}-{}]",
+            INDENT.repeat(depth),
+            symbol.label,
+            symbol.name,
+            symbol.start_line,
+            symbol.end_line
+        ));
+        render(&symbol.children, depth + 1, lines);
+    }
+}
+
+fn outline_source(language: Language, source: &str) -> Vec<Symbol> {
+    let mut parser = Parser::new();
+    if parser.set_language(&language.grammar()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+    let mut symbols = Vec::new();
+    collect_symbols(language, tree.root_node(), source.as_bytes(), &mut symbols);
+    symbols
+}
+
+/// Walks `node`'s descendants, turning definitions into symbols. The bodies
+/// of functions are not searched, so locals and closures stay out of the
+/// outline.
+fn collect_symbols(language: Language, node: Node, source: &[u8], out: &mut Vec<Symbol>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let Some(kind) = language.symbol_kind(child, source) else {
+            collect_symbols(language, child, source, out);
+            continue;
+        };
+        let mut children = Vec::new();
+        if kind.container {
+            collect_symbols(language, child, source, &mut children);
+        }
+        out.push(Symbol {
+            label: kind.label,
+            name: kind.name,
+            start_line: child.start_position().row + 1,
+            end_line: child.end_position().row + 1,
+            children,
+        });
+    }
+}
+
+impl Language {
+    fn for_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            // The TSX grammar is a superset of JavaScript.
+            "tsx" | "js" | "jsx" | "mjs" | "cjs" => Some(Self::Tsx),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    fn grammar(self) -> tree_sitter::Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    fn symbol_kind(self, node: Node, source: &[u8]) -> Option<SymbolKind> {
+        let field = |name: &str| field_text(node, name, source);
+        let named = |label, container| {
+            Some(SymbolKind {
+                label,
+                name: field("name")?,
+                container,
+            })
+        };
+        match (self, node.kind()) {
+            (Self::Rust, "function_item" | "function_signature_item") => named("fn", false),
+            (Self::Rust, "struct_item") => named("struct", false),
+            (Self::Rust, "enum_item") => named("enum", false),
+            (Self::Rust, "union_item") => named("union", false),
+            (Self::Rust, "type_item") => named("type", false),
+            (Self::Rust, "const_item") => named("const", false),
+            (Self::Rust, "static_item") => named("static", false),
+            (Self::Rust, "macro_definition") => named("macro_rules!", false),
+            (Self::Rust, "trait_item") => named("trait", true),
+            (Self::Rust, "mod_item") => named("mod", true),
+            (Self::Rust, "impl_item") => {
+                let ty = field("type")?;
+                let name = match field("trait") {
+                    Some(trait_name) => format!("{trait_name} for {ty}"),
+                    None => ty,
+                };
+                Some(SymbolKind {
+                    label: "impl",
+                    name,
+                    container: true,
+                })
+            }
+
+            (Self::Python, "function_definition") => named("def", false),
+            (Self::Python, "class_definition") => named("class", true),
+
+            (
+                Self::TypeScript | Self::Tsx,
+                "function_declaration" | "generator_function_declaration" | "function_signature",
+            ) => named("function", false),
+            (Self::TypeScript | Self::Tsx, "class_declaration" | "abstract_class_declaration") => {
+                named("class", true)
+            }
+            (
+                Self::TypeScript | Self::Tsx,
+                "method_definition" | "method_signature" | "abstract_method_signature",
+            ) => named("method", false),
+            (Self::TypeScript | Self::Tsx, "interface_declaration") => named("interface", true),
+            (Self::TypeScript | Self::Tsx, "type_alias_declaration") => named("type", false),
+            (Self::TypeScript | Self::Tsx, "enum_declaration") => named("enum", false),
+            (Self::TypeScript | Self::Tsx, "internal_module" | "module") => {
+                named("namespace", true)
+            }
+            (Self::TypeScript | Self::Tsx, "variable_declarator") => {
+                let value = node.child_by_field_name("value")?;
+                matches!(
+                    value.kind(),
+                    "arrow_function" | "function_expression" | "generator_function"
+                )
+                .then_some(())?;
+                named("function", false)
+            }
+
+            (Self::Go, "function_declaration" | "method_elem") => named("func", false),
+            (Self::Go, "method_declaration") => {
+                let receiver = field("receiver")?;
+                let name = field("name")?;
+                Some(SymbolKind {
+                    label: "func",
+                    name: format!("{receiver} {name}"),
+                    container: false,
+                })
+            }
+            (Self::Go, "type_spec" | "type_alias") => {
+                let label = match node.child_by_field_name("type").map(|ty| ty.kind()) {
+                    Some("struct_type") => "struct",
+                    Some("interface_type") => "interface",
+                    _ => "type",
+                };
+                named(label, label == "interface")
+            }
+
+            _ => None,
+        }
+    }
+}
+
+/// Source text of the named field, collapsed onto a single line.
+fn field_text(node: Node, field: &str, source: &[u8]) -> Option<String> {
+    let text = node.child_by_field_name(field)?.utf8_text(source).ok()?;
+    Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::SandboxPolicy;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn no_deny_read() -> DenyRead {
+        SandboxPolicy::DangerFullAccess.get_deny_read_with_cwd(Path::new("/"))
+    }
+
+    fn outline(language: Language, source: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        render(&outline_source(language, source), 0, &mut lines);
+        lines
+    }
+
+    #[test]
+    fn outlines_rust_items_and_impls() {
+        let source = r#"
+struct Config {
+    path: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let helper = || 1;
+        Self { path: String::new() }
+    }
+}
+
+trait Load {
+    fn load(&self);
+}
+
+mod tests {
+    fn roundtrip() {}
+}
+"#;
+
+        assert_eq!(
+            outline(Language::Rust, source),
+            vec![
+                "struct Config [2-4]",
+                "impl Default for Config [6-11]",
+                "  fn default [7-10]",
+                "trait Load [13-15]",
+                "  fn load [14-14]",
+                "mod tests [17-19]",
+                "  fn roundtrip [18-18]",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_python_classes_and_decorated_functions() {
+        let source = r#"
+import os
+
+class Store:
+    def get(self, key):
+        def inner():
+            pass
+        return key
+
+@cache
+async def fetch(url):
+    return url
+"#;
+
+        assert_eq!(
+            outline(Language::Python, source),
+            vec!["class Store [4-8]", "  def get [5-8]", "def fetch [11-12]",]
+        );
+    }
+
+    #[test]
+    fn outlines_typescript_declarations() {
+        let source = r#"
+export interface Props {
+  render(): void;
+}
+
+export class Widget {
+  draw(): void {}
+}
+
+export const helper = (value: number) => value * 2;
+
+type Id = string;
+"#;
+
+        assert_eq!(
+            outline(Language::TypeScript, source),
+            vec![
+                "interface Props [2-4]",
+                "  method render [3-3]",
+                "class Widget [6-8]",
+                "  method draw [7-7]",
+                "function helper [10-10]",
+                "type Id [12-12]",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_go_types_and_methods() {
+        let source = r#"
+package server
+
+type Server struct {
+	addr string
+}
+
+type Handler interface {
+	Serve() error
+}
+
+func (s *Server) Start() error {
+	return nil
+}
+
+func main() {}
+"#;
+
+        assert_eq!(
+            outline(Language::Go, source),
+            vec![
+                "struct Server [4-6]",
+                "interface Handler [8-10]",
+                "  func Serve [9-9]",
+                "func (s *Server) Start [12-14]",
+                "func main [16-16]",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_directories_and_skips_ignored_files() {
+        let temp = tempdir().expect("create temp dir");
+        let dir = temp.path();
+        std::fs::write(dir.join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("target")).unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "pub fn run() {}\n").unwrap();
+        std::fs::write(dir.join("target/gen.rs"), "pub fn generated() {}\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), "fn not_code() {}\n").unwrap();
+        std::fs::write(dir.join("app.py"), "def main():\n    pass\n").unwrap();
+
+        let content = outline_path(dir, &no_deny_read()).expect("outline succeeds");
+
+        assert_eq!(
+            content,
+            format!(
+                "Absolute path: {}\napp.py\n  def main [1-2]\nsrc/lib.rs\n  fn run [1-1]",
+                dir.display()
+            )
+        );
+    }
+
+    #[test]
+    fn skips_files_covered_by_deny_read() {
+        let temp = tempdir().expect("create temp dir");
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("private")).unwrap();
+        std::fs::write(dir.join("private/keys.py"), "def key():\n    pass\n").unwrap();
+        std::fs::write(dir.join("app.py"), "def main():\n    pass\n").unwrap();
+        let policy = SandboxPolicy::ReadOnly {
+            readable_roots: vec![],
+            deny_read: vec![PathBuf::from("private")],
+        };
+
+        let content =
+            outline_path(dir, &policy.get_deny_read_with_cwd(dir)).expect("outline succeeds");
+
+        assert_eq!(
+            content,
+            format!("Absolute path: {}\napp.py\n  def main [1-2]", dir.display())
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_files() {
+        let temp = tempdir().expect("create temp dir");
+        let path = temp.path().join("notes.txt");
+        std::fs::write(&path, "hello").unwrap();
+
+        let err = outline_path(&path, &no_deny_read()).expect_err("txt files are not supported");
+
+        assert!(
+            err.to_string().starts_with("code_outline supports"),
+            "{err}"
+        );
+    }
+}
@@ -1,15 +1,31 @@
+use std::io;
 use std::path::Path;
+use std::path::PathBuf;
 use std::time::Duration;
+use std::time::Instant;
 
 use async_trait::async_trait;
+use codex_utils_string::take_bytes_at_char_boundary;
+use grep_regex::RegexMatcher;
+use grep_regex::RegexMatcherBuilder;
+use grep_searcher::BinaryDetection;
+use grep_searcher::Searcher;
+use grep_searcher::SearcherBuilder;
+use grep_searcher::Sink;
+use grep_searcher::SinkContext;
+use grep_searcher::SinkMatch;
+use ignore::Walk;
+use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
 use serde::Deserialize;
-use tokio::process::Command;
-use tokio::time::timeout;
+use tracing::debug;
 
 use crate::function_tool::FunctionCallError;
+use crate::protocol::DenyRead;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ensure_readable;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 
@@ -17,7 +33,10 @@ pub struct GrepFilesHandler;
 
 const DEFAULT_LIMIT: usize = 100;
 const MAX_LIMIT: usize = 2000;
-const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_CONTEXT_LINES: usize = 10;
+const MAX_LINE_LENGTH: usize = 500;
+/// Wall-clock budget for one search, walk included.
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(30);
 
 fn default_limit() -> usize {
     DEFAULT_LIMIT
@@ -30,8 +49,35 @@ struct GrepFilesArgs {
     include: Option<String>,
     #[serde(default)]
     path: Option<String>,
+    /// Maximum number of matches to return.
     #[serde(default = "default_limit")]
     limit: usize,
+    /// Lines of context to include before and after each match.
+    #[serde(default)]
+    context: usize,
+    /// Whether the pattern may span multiple lines.
+    #[serde(default)]
+    multiline: bool,
+}
+
+struct GrepQuery {
+    pattern: String,
+    include: Option<String>,
+    search_path: PathBuf,
+    deny_read: DenyRead,
+    limit: usize,
+    context: usize,
+    multiline: bool,
+    timeout: Duration,
+}
+
+#[derive(Debug, Default)]
+struct GrepResults {
+    lines: Vec<String>,
+    matches: usize,
+    truncated: bool,
+    /// Set when the search ran out of time before visiting every file.
+    timed_out: bool,
 }
 
 #[async_trait]
@@ -40,6 +86,10 @@ impl ToolHandler for GrepFilesHandler {
         ToolKind::Function
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation { payload, turn, .. } = invocation;
 
@@ -71,8 +121,9 @@ impl ToolHandler for GrepFilesHandler {
             ));
         }
 
-        let limit = args.limit.min(MAX_LIMIT);
         let search_path = turn.resolve_path(args.path.clone());
+        let deny_read = turn.sandbox_policy.get_deny_read_with_cwd(&turn.cwd);
+        ensure_readable(&deny_read, &search_path)?;
 
         verify_path_exists(&search_path).await?;
 
@@ -84,20 +135,55 @@ impl ToolHandler for GrepFilesHandler {
             }
         });
 
-        let search_results =
-            run_rg_search(pattern, include.as_deref(), &search_path, limit, &turn.cwd).await?;
-
-        if search_results.is_empty() {
-            Ok(ToolOutput::Function {
-                content: "No matches found.".to_string(),
+        let query = GrepQuery {
+            pattern: pattern.to_string(),
+            include,
+            search_path,
+            deny_read,
+            limit: args.limit.min(MAX_LIMIT),
+            context: args.context.min(MAX_CONTEXT_LINES),
+            multiline: args.multiline,
+            timeout: SEARCH_TIMEOUT,
+        };
+        let results = tokio::task::spawn_blocking(move || search(&query))
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("grep_files search failed: {err}"))
+            })??;
+
+        if results.matches == 0 {
+            let content = if results.timed_out {
+                format!(
+                    "No matches found before the search timed out after {}s; narrow `path` or \
+                     `include`.",
+                    SEARCH_TIMEOUT.as_secs()
+                )
+            } else {
+                "No matches found.".to_string()
+            };
+            return Ok(ToolOutput::Function {
+                content,
                 success: Some(false),
-            })
-        } else {
-            Ok(ToolOutput::Function {
-                content: search_results.join("\n"),
-                success: Some(true),
-            })
+            });
         }
+
+        let mut content = results.lines.join("\n");
+        if results.timed_out {
+            content.push_str(&format!(
+                "\n\n[search timed out after {}s; results are incomplete, narrow `path` or \
+                 `include`]",
+                SEARCH_TIMEOUT.as_secs()
+            ));
+        } else if results.truncated {
+            content.push_str(&format!(
+                "\n\n[results truncated after {} matches; narrow the pattern or raise `limit`]",
+                results.matches
+            ));
+        }
+        Ok(ToolOutput::Function {
+            content,
+            success: Some(true),
+        })
     }
 }
 
@@ -108,165 +194,365 @@ async fn verify_path_exists(path: &Path) -> Result<(), FunctionCallError> {
     Ok(())
 }
 
-async fn run_rg_search(
-    pattern: &str,
-    include: Option<&str>,
-    search_path: &Path,
-    limit: usize,
-    cwd: &Path,
-) -> Result<Vec<String>, FunctionCallError> {
-    let mut command = Command::new("rg");
-    command
-        .current_dir(cwd)
-        .arg("--files-with-matches")
-        .arg("--sortr=modified")
-        .arg("--regexp")
-        .arg(pattern)
-        .arg("--no-messages");
+/// Searches every non-ignored file under `query.search_path` as the walk
+/// reaches it, skipping the ones `query.deny_read` covers, and renders hits
+/// the way `rg` does: `path:line:text` for matching lines and
+/// `path-line-text` for context lines. The walk and the search stop once
+/// `query.limit` matches are found or `query.timeout` passes.
+fn search(query: &GrepQuery) -> Result<GrepResults, FunctionCallError> {
+    let deadline = Instant::now() + query.timeout;
+    let matcher = build_matcher(&query.pattern, query.multiline)?;
+    let mut searcher = SearcherBuilder::new()
+        .line_number(true)
+        .multi_line(query.multiline)
+        .before_context(query.context)
+        .after_context(query.context)
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .build();
+
+    let mut results = GrepResults::default();
+    for entry in walk_files(
+        &query.search_path,
+        query.include.as_deref(),
+        &query.deny_read,
+    )? {
+        if Instant::now() >= deadline {
+            results.timed_out = true;
+            break;
+        }
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        let path = entry.path();
+        let path_display = path.display().to_string();
+        let mut sink = MatchSink {
+            path: &path_display,
+            results: &mut results,
+            limit: query.limit,
+            context: query.context,
+            started: false,
+        };
+        if let Err(err) = searcher.search_path(&matcher, path, &mut sink) {
+            debug!("grep_files skipped {path_display}: {err}");
+        }
+        if results.truncated {
+            break;
+        }
+    }
+    Ok(results)
+}
 
-    if let Some(glob) = include {
-        command.arg("--glob").arg(glob);
+fn build_matcher(pattern: &str, multiline: bool) -> Result<RegexMatcher, FunctionCallError> {
+    let mut builder = RegexMatcherBuilder::new();
+    builder.multi_line(true);
+    if multiline {
+        builder.dot_matches_new_line(true);
+    } else {
+        builder.line_terminator(Some(b'\n'));
     }
+    builder.build(pattern).map_err(|err| {
+        let hint = if multiline {
+            ""
+        } else {
+            " (set `multiline` to match across lines)"
+        };
+        FunctionCallError::RespondToModel(format!("invalid pattern `{pattern}`: {err}{hint}"))
+    })
+}
 
-    command.arg("--").arg(search_path);
+/// Walks the files to search, honouring `.gitignore`, `.ignore` and hidden
+/// files the same way `rg` does, in file name order. Files and folders
+/// covered by `deny_read` are skipped.
+fn walk_files(
+    search_path: &Path,
+    include: Option<&str>,
+    deny_read: &DenyRead,
+) -> Result<Walk, FunctionCallError> {
+    let mut walker = WalkBuilder::new(search_path);
+    walker.require_git(false);
+    walker.sort_by_file_name(|a, b| a.cmp(b));
+    let deny_read = deny_read.clone();
+    walker.filter_entry(move |entry| !deny_read.is_path_denied(entry.path()));
+    if let Some(glob) = include {
+        let invalid_glob = |err: ignore::Error| {
+            FunctionCallError::RespondToModel(format!("invalid include glob `{glob}`: {err}"))
+        };
+        let mut overrides = OverrideBuilder::new(search_path);
+        overrides.add(glob).map_err(invalid_glob)?;
+        walker.overrides(overrides.build().map_err(invalid_glob)?);
+    }
+    Ok(walker.build())
+}
 
-    let output = timeout(COMMAND_TIMEOUT, command.output())
-        .await
-        .map_err(|_| {
-            FunctionCallError::RespondToModel("rg timed out after 30 seconds".to_string())
-        })?
-        .map_err(|err| {
-            FunctionCallError::RespondToModel(format!(
-                "failed to launch rg: {err}. Ensure ripgrep is installed and on PATH."
-            ))
-        })?;
+struct MatchSink<'a> {
+    path: &'a str,
+    results: &'a mut GrepResults,
+    limit: usize,
+    context: usize,
+    started: bool,
+}
 
-    match output.status.code() {
-        Some(0) => Ok(parse_results(&output.stdout, limit)),
-        Some(1) => Ok(Vec::new()),
-        _ => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(FunctionCallError::RespondToModel(format!(
-                "rg failed: {stderr}"
-            )))
+impl MatchSink<'_> {
+    fn push_line(&mut self, line_number: u64, separator: char, bytes: &[u8]) {
+        if !self.started {
+            self.started = true;
+            if self.context > 0 && !self.results.lines.is_empty() {
+                self.results.lines.push("--".to_string());
+            }
         }
+        let text = String::from_utf8_lossy(bytes);
+        let text = text.trim_end_matches(['\r', '\n']);
+        let text = take_bytes_at_char_boundary(text, MAX_LINE_LENGTH);
+        self.results.lines.push(format!(
+            "{}{separator}{line_number}{separator}{text}",
+            self.path
+        ));
     }
 }
 
-fn parse_results(stdout: &[u8], limit: usize) -> Vec<String> {
-    let mut results = Vec::new();
-    for line in stdout.split(|byte| *byte == b'\n') {
-        if line.is_empty() {
-            continue;
+impl Sink for MatchSink<'_> {
+    type Error = io::Error;
+
+    fn matched(&mut self, _searcher: &Searcher, mat: &SinkMatch<'_>) -> Result<bool, io::Error> {
+        if self.results.matches >= self.limit {
+            self.results.truncated = true;
+            return Ok(false);
         }
-        if let Ok(text) = std::str::from_utf8(line) {
-            if text.is_empty() {
-                continue;
-            }
-            results.push(text.to_string());
-            if results.len() == limit {
-                break;
-            }
+        self.results.matches += 1;
+        let first_line = mat.line_number().unwrap_or(0);
+        for (offset, line) in (0u64..).zip(mat.lines()) {
+            self.push_line(first_line + offset, ':', line);
         }
+        Ok(true)
+    }
+
+    fn context(
+        &mut self,
+        _searcher: &Searcher,
+        context: &SinkContext<'_>,
+    ) -> Result<bool, io::Error> {
+        self.push_line(context.line_number().unwrap_or(0), '-', context.bytes());
+        Ok(true)
+    }
+
+    fn context_break(&mut self, _searcher: &Searcher) -> Result<bool, io::Error> {
+        self.results.lines.push("--".to_string());
+        Ok(true)
     }
-    results
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::process::Command as StdCommand;
+    use crate::protocol::SandboxPolicy;
+    use pretty_assertions::assert_eq;
     use tempfile::tempdir;
 
+    fn query(dir: &Path, pattern: &str) -> GrepQuery {
+        GrepQuery {
+            pattern: pattern.to_string(),
+            include: None,
+            search_path: dir.to_path_buf(),
+            deny_read: SandboxPolicy::DangerFullAccess.get_deny_read_with_cwd(dir),
+            limit: 10,
+            context: 0,
+            multiline: false,
+            timeout: SEARCH_TIMEOUT,
+        }
+    }
+
+    /// Strips the temp dir prefix so expectations stay readable.
+    fn relative_lines(results: &GrepResults, dir: &Path) -> Vec<String> {
+        let prefix = format!("{}/", dir.display());
+        results
+            .lines
+            .iter()
+            .map(|line| line.replace(&prefix, ""))
+            .collect()
+    }
+
     #[test]
-    fn parses_basic_results() {
-        let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n";
-        let parsed = parse_results(stdout, 10);
-        assert_eq!(
-            parsed,
-            vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
-        );
+    fn search_returns_matching_lines_with_numbers() {
+        let temp = tempdir().expect("create temp dir");
+        let dir = temp.path();
+        std::fs::write(dir.join("one.txt"), "omega\nalpha beta\ngamma\n").unwrap();
+        std::fs::write(dir.join("other.txt"), "omega").unwrap();
+
+        let results = search(&query(dir, "alpha")).expect("search succeeds");
+
+        assert_eq!(results.matches, 1);
+        assert_eq!(relative_lines(&results, dir), vec!["one.txt:2:alpha beta"]);
     }
 
     #[test]
-    fn parse_truncates_after_limit() {
-        let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n/tmp/file_c.rs\n";
-        let parsed = parse_results(stdout, 2);
+    fn search_with_glob_filter() {
+        let temp = tempdir().expect("create temp dir");
+        let dir = temp.path();
+        std::fs::write(dir.join("match_one.rs"), "alpha beta gamma").unwrap();
+        std::fs::write(dir.join("match_two.txt"), "alpha delta").unwrap();
+
+        let results = search(&GrepQuery {
+            include: Some("*.rs".to_string()),
+            ..query(dir, "alpha")
+        })
+        .expect("search succeeds");
+
         assert_eq!(
-            parsed,
-            vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
+            relative_lines(&results, dir),
+            vec!["match_one.rs:1:alpha beta gamma"]
         );
     }
 
-    #[tokio::test]
-    async fn run_search_returns_results() -> anyhow::Result<()> {
-        if !rg_available() {
-            return Ok(());
-        }
+    #[test]
+    fn search_respects_gitignore() {
         let temp = tempdir().expect("create temp dir");
         let dir = temp.path();
-        std::fs::write(dir.join("match_one.txt"), "alpha beta gamma").unwrap();
-        std::fs::write(dir.join("match_two.txt"), "alpha delta").unwrap();
-        std::fs::write(dir.join("other.txt"), "omega").unwrap();
+        std::fs::write(dir.join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir(dir.join("target")).unwrap();
+        std::fs::write(dir.join("target/generated.rs"), "alpha").unwrap();
+        std::fs::write(dir.join("lib.rs"), "alpha").unwrap();
+
+        let results = search(&query(dir, "alpha")).expect("search succeeds");
 
-        let results = run_rg_search("alpha", None, dir, 10, dir).await?;
-        assert_eq!(results.len(), 2);
-        assert!(results.iter().any(|path| path.ends_with("match_one.txt")));
-        assert!(results.iter().any(|path| path.ends_with("match_two.txt")));
-        Ok(())
+        assert_eq!(relative_lines(&results, dir), vec!["lib.rs:1:alpha"]);
     }
 
-    #[tokio::test]
-    async fn run_search_with_glob_filter() -> anyhow::Result<()> {
-        if !rg_available() {
-            return Ok(());
-        }
+    #[test]
+    fn search_skips_files_covered_by_deny_read() {
         let temp = tempdir().expect("create temp dir");
         let dir = temp.path();
-        std::fs::write(dir.join("match_one.rs"), "alpha beta gamma").unwrap();
-        std::fs::write(dir.join("match_two.txt"), "alpha delta").unwrap();
+        std::fs::create_dir_all(dir.join("app")).unwrap();
+        std::fs::create_dir_all(dir.join("keys")).unwrap();
+        std::fs::write(dir.join("app/credentials.json"), "alpha").unwrap();
+        std::fs::write(dir.join("keys/id"), "alpha").unwrap();
+        std::fs::write(dir.join("lib.rs"), "alpha").unwrap();
+        let policy = SandboxPolicy::ReadOnly {
+            readable_roots: vec![],
+            deny_read: vec![PathBuf::from("credentials.json"), PathBuf::from("./keys")],
+        };
+
+        let results = search(&GrepQuery {
+            deny_read: policy.get_deny_read_with_cwd(dir),
+            ..query(dir, "alpha")
+        })
+        .expect("search succeeds");
 
-        let results = run_rg_search("alpha", Some("*.rs"), dir, 10, dir).await?;
-        assert_eq!(results.len(), 1);
-        assert!(results.iter().all(|path| path.ends_with("match_one.rs")));
-        Ok(())
+        assert_eq!(relative_lines(&results, dir), vec!["lib.rs:1:alpha"]);
     }
 
-    #[tokio::test]
-    async fn run_search_respects_limit() -> anyhow::Result<()> {
-        if !rg_available() {
-            return Ok(());
-        }
+    #[test]
+    fn search_includes_context_lines() {
         let temp = tempdir().expect("create temp dir");
         let dir = temp.path();
-        std::fs::write(dir.join("one.txt"), "alpha one").unwrap();
-        std::fs::write(dir.join("two.txt"), "alpha two").unwrap();
-        std::fs::write(dir.join("three.txt"), "alpha three").unwrap();
+        std::fs::write(
+            dir.join("a.txt"),
+            "one\ntwo\nalpha\nthree\nfour\nfive\nalpha\n",
+        )
+        .unwrap();
+
+        let results = search(&GrepQuery {
+            context: 1,
+            ..query(dir, "alpha")
+        })
+        .expect("search succeeds");
 
-        let results = run_rg_search("alpha", None, dir, 2, dir).await?;
-        assert_eq!(results.len(), 2);
-        Ok(())
+        assert_eq!(
+            relative_lines(&results, dir),
+            vec![
+                "a.txt-2-two",
+                "a.txt:3:alpha",
+                "a.txt-4-three",
+                "--",
+                "a.txt-6-five",
+                "a.txt:7:alpha",
+            ]
+        );
     }
 
-    #[tokio::test]
-    async fn run_search_handles_no_matches() -> anyhow::Result<()> {
-        if !rg_available() {
-            return Ok(());
-        }
+    #[test]
+    fn search_supports_multiline_patterns() {
+        let temp = tempdir().expect("create temp dir");
+        let dir = temp.path();
+        std::fs::write(dir.join("a.rs"), "fn main() {\n    run();\n}\n").unwrap();
+
+        let err = search(&query(dir, r"main\(\) \{\n\s+run"))
+            .expect_err("newlines require multiline mode");
+        assert!(err.to_string().contains("set `multiline`"), "{err}");
+
+        let results = search(&GrepQuery {
+            multiline: true,
+            ..query(dir, r"main\(\) \{\n\s+run")
+        })
+        .expect("search succeeds");
+        assert_eq!(
+            relative_lines(&results, dir),
+            vec!["a.rs:1:fn main() {", "a.rs:2:    run();"]
+        );
+    }
+
+    #[test]
+    fn search_respects_limit() {
+        let temp = tempdir().expect("create temp dir");
+        let dir = temp.path();
+        std::fs::write(dir.join("one.txt"), "alpha one\nalpha two\nalpha three\n").unwrap();
+
+        let results = search(&GrepQuery {
+            limit: 2,
+            ..query(dir, "alpha")
+        })
+        .expect("search succeeds");
+
+        assert_eq!(results.matches, 2);
+        assert!(results.truncated);
+        assert_eq!(
+            relative_lines(&results, dir),
+            vec!["one.txt:1:alpha one", "one.txt:2:alpha two"]
+        );
+    }
+
+    #[test]
+    fn search_handles_no_matches() {
         let temp = tempdir().expect("create temp dir");
         let dir = temp.path();
         std::fs::write(dir.join("one.txt"), "omega").unwrap();
 
-        let results = run_rg_search("alpha", None, dir, 5, dir).await?;
-        assert!(results.is_empty());
-        Ok(())
+        let results = search(&query(dir, "alpha")).expect("search succeeds");
+
+        assert_eq!(results.matches, 0);
+        assert!(results.lines.is_empty());
     }
 
-    fn rg_available() -> bool {
-        StdCommand::new("rg")
-            .arg("--version")
-            .output()
-            .map(|output| output.status.success())
-            .unwrap_or(false)
+    #[test]
+    fn invalid_pattern_is_reported_to_model() {
+        let temp = tempdir().expect("create temp dir");
+
+        let err = search(&query(temp.path(), "(unclosed")).expect_err("pattern is invalid");
+
+        let FunctionCallError::RespondToModel(message) = err else {
+            panic!("expected RespondToModel, got {err:?}");
+        };
+        assert!(message.starts_with("invalid pattern `(unclosed`"));
+    }
+
+    #[test]
+    fn search_stops_when_out_of_time() {
+        let temp = tempdir().expect("create temp dir");
+        let dir = temp.path();
+        std::fs::write(dir.join("one.txt"), "alpha").unwrap();
+
+        let results = search(&GrepQuery {
+            timeout: Duration::ZERO,
+            ..query(dir, "alpha")
+        })
+        .expect("search succeeds");
+
+        assert!(results.timed_out);
+        assert_eq!(results.matches, 0);
     }
 }
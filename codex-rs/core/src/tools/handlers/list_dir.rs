@@ -10,9 +10,11 @@ use serde::Deserialize;
 use tokio::fs;
 
 use crate::function_tool::FunctionCallError;
+use crate::protocol::DenyRead;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ensure_readable;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 
@@ -50,8 +52,12 @@ impl ToolHandler for ListDirHandler {
         ToolKind::Function
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation { payload, turn, .. } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -100,7 +106,10 @@ impl ToolHandler for ListDirHandler {
             ));
         }
 
-        let entries = list_dir_slice(&path, offset, limit, depth).await?;
+        let deny_read = turn.sandbox_policy.get_deny_read_with_cwd(&turn.cwd);
+        ensure_readable(&deny_read, &path)?;
+
+        let entries = list_dir_slice(&path, offset, limit, depth, &deny_read).await?;
         let mut output = Vec::with_capacity(entries.len() + 1);
         output.push(format!("Absolute path: {}", path.display()));
         output.extend(entries);
@@ -116,9 +125,10 @@ async fn list_dir_slice(
     offset: usize,
     limit: usize,
     depth: usize,
+    deny_read: &DenyRead,
 ) -> Result<Vec<String>, FunctionCallError> {
     let mut entries = Vec::new();
-    collect_entries(path, Path::new(""), depth, &mut entries).await?;
+    collect_entries(path, Path::new(""), depth, deny_read, &mut entries).await?;
 
     if entries.is_empty() {
         return Ok(Vec::new());
@@ -149,10 +159,13 @@ async fn list_dir_slice(
     Ok(formatted)
 }
 
+/// Lists the entries under `dir_path`, breadth first. Folders covered by
+/// `deny_read` are listed but not descended into.
 async fn collect_entries(
     dir_path: &Path,
     relative_prefix: &Path,
     depth: usize,
+    deny_read: &DenyRead,
     entries: &mut Vec<DirEntry>,
 ) -> Result<(), FunctionCallError> {
     let mut queue = VecDeque::new();
@@ -199,7 +212,10 @@ async fn collect_entries(
         dir_entries.sort_unstable_by(|a, b| a.3.name.cmp(&b.3.name));
 
         for (entry_path, relative_path, kind, dir_entry) in dir_entries {
-            if kind == DirEntryKind::Directory && remaining_depth > 1 {
+            if kind == DirEntryKind::Directory
+                && remaining_depth > 1
+                && !deny_read.is_path_denied(&entry_path)
+            {
                 queue.push_back((entry_path, relative_path, remaining_depth - 1));
             }
             entries.push(dir_entry);
@@ -272,8 +288,13 @@ impl From<&FileType> for DirEntryKind {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protocol::SandboxPolicy;
     use tempfile::tempdir;
 
+    fn no_deny_read() -> DenyRead {
+        SandboxPolicy::DangerFullAccess.get_deny_read_with_cwd(Path::new("/"))
+    }
+
     #[tokio::test]
     async fn lists_directory_entries() {
         let temp = tempdir().expect("create tempdir");
@@ -306,7 +327,7 @@ mod tests {
             symlink(dir_path.join("entry.txt"), &link_path).expect("create symlink");
         }
 
-        let entries = list_dir_slice(dir_path, 1, 20, 3)
+        let entries = list_dir_slice(dir_path, 1, 20, 3, &no_deny_read())
             .await
             .expect("list directory");
 
@@ -340,7 +361,7 @@ mod tests {
             .await
             .expect("create sub dir");
 
-        let err = list_dir_slice(dir_path, 10, 1, 2)
+        let err = list_dir_slice(dir_path, 10, 1, 2, &no_deny_read())
             .await
             .expect_err("offset exceeds entries");
         assert_eq!(
@@ -367,7 +388,7 @@ mod tests {
             .await
             .expect("write deeper");
 
-        let entries_depth_one = list_dir_slice(dir_path, 1, 10, 1)
+        let entries_depth_one = list_dir_slice(dir_path, 1, 10, 1, &no_deny_read())
             .await
             .expect("list depth 1");
         assert_eq!(
@@ -375,7 +396,7 @@ mod tests {
             vec!["nested/".to_string(), "root.txt".to_string(),]
         );
 
-        let entries_depth_two = list_dir_slice(dir_path, 1, 20, 2)
+        let entries_depth_two = list_dir_slice(dir_path, 1, 20, 2, &no_deny_read())
             .await
             .expect("list depth 2");
         assert_eq!(
@@ -388,7 +409,7 @@ mod tests {
             ]
         );
 
-        let entries_depth_three = list_dir_slice(dir_path, 1, 30, 3)
+        let entries_depth_three = list_dir_slice(dir_path, 1, 30, 3, &no_deny_read())
             .await
             .expect("list depth 3");
         assert_eq!(
@@ -417,7 +438,7 @@ mod tests {
             .await
             .expect("write gamma");
 
-        let entries = list_dir_slice(dir_path, 2, usize::MAX, 1)
+        let entries = list_dir_slice(dir_path, 2, usize::MAX, 1, &no_deny_read())
             .await
             .expect("list without overflow");
         assert_eq!(
@@ -438,7 +459,7 @@ mod tests {
                 .expect("write file");
         }
 
-        let entries = list_dir_slice(dir_path, 1, 25, 1)
+        let entries = list_dir_slice(dir_path, 1, 25, 1, &no_deny_read())
             .await
             .expect("list directory");
         assert_eq!(entries.len(), 26);
@@ -460,7 +481,7 @@ mod tests {
         tokio::fs::write(nested.join("child.txt"), b"child").await?;
         tokio::fs::write(deeper.join("grandchild.txt"), b"deep").await?;
 
-        let entries_depth_three = list_dir_slice(dir_path, 1, 3, 3).await?;
+        let entries_depth_three = list_dir_slice(dir_path, 1, 3, 3, &no_deny_read()).await?;
         assert_eq!(
             entries_depth_three,
             vec![
@@ -473,4 +494,26 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn does_not_descend_into_denied_folders() -> anyhow::Result<()> {
+        let temp = tempdir()?;
+        let dir_path = temp.path();
+        tokio::fs::create_dir(dir_path.join(".ssh")).await?;
+        tokio::fs::write(dir_path.join(".ssh").join("id_ed25519"), b"key").await?;
+        tokio::fs::write(dir_path.join("notes.txt"), b"notes").await?;
+        let policy = SandboxPolicy::ReadOnly {
+            readable_roots: vec![],
+            deny_read: vec![PathBuf::from(".ssh")],
+        };
+        let deny_read = policy.get_deny_read_with_cwd(dir_path);
+
+        let entries = list_dir_slice(dir_path, 1, 10, 2, &deny_read).await?;
+        assert_eq!(entries, vec![".ssh/".to_string(), "notes.txt".to_string()]);
+
+        let err = ensure_readable(&deny_read, &dir_path.join(".ssh"))
+            .expect_err("denied folders cannot be listed");
+        assert!(err.to_string().contains("deny_read"), "{err}");
+        Ok(())
+    }
 }
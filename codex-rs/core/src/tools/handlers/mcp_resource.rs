@@ -0,0 +1,155 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::function_tool::FunctionCallError;
+use crate::mcp::resources::read_resource;
+use crate::mcp::resources::render_resource_contents;
+use crate::mcp_connection_manager::McpConnectionManager;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub const LIST_MCP_RESOURCES_TOOL_NAME: &str = "list_mcp_resources";
+pub const READ_MCP_RESOURCE_TOOL_NAME: &str = "read_mcp_resource";
+
+pub struct McpResourceHandler;
+
+#[derive(Deserialize, Default)]
+struct ListMcpResourcesArgs {
+    #[serde(default)]
+    server: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ReadMcpResourceArgs {
+    server: String,
+    uri: String,
+}
+
+#[async_trait]
+impl ToolHandler for McpResourceHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "mcp resource handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let content = match tool_name.as_str() {
+            LIST_MCP_RESOURCES_TOOL_NAME => {
+                let args: ListMcpResourcesArgs = if arguments.trim().is_empty() {
+                    ListMcpResourcesArgs::default()
+                } else {
+                    parse_arguments(&arguments)?
+                };
+                let manager = session.mcp_connection_manager().await;
+                list_resources(&manager, args.server)?
+            }
+            READ_MCP_RESOURCE_TOOL_NAME => {
+                let args: ReadMcpResourceArgs = parse_arguments(&arguments)?;
+                let manager = session.mcp_connection_manager().await;
+                let deny_read = turn.sandbox_policy.get_deny_read_with_cwd(&turn.cwd);
+                let result = read_resource(&manager, &args.server, &args.uri, &deny_read)
+                    .await
+                    .map_err(|e| FunctionCallError::RespondToModel(format!("{e:#}")))?;
+                render_resource_contents(&result)
+            }
+            _ => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "mcp resource handler does not support tool {tool_name}"
+                )));
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            success: Some(true),
+        })
+    }
+}
+
+fn parse_arguments<T: for<'de> Deserialize<'de>>(arguments: &str) -> Result<T, FunctionCallError> {
+    serde_json::from_str(arguments).map_err(|e| {
+        FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+    })
+}
+
+fn list_resources(
+    manager: &McpConnectionManager,
+    server: Option<String>,
+) -> Result<String, FunctionCallError> {
+    if let Some(server) = &server
+        && !manager.lists_resources(server)
+    {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "MCP server `{server}` does not expose resources"
+        )));
+    }
+    let wanted = |name: &String| server.as_ref().is_none_or(|server| server == name);
+
+    let mut resources: Vec<_> = manager
+        .list_all_resources()
+        .into_iter()
+        .filter(|(name, _)| wanted(name))
+        .flat_map(|(name, listed)| {
+            listed.into_iter().map(move |resource| {
+                json!({
+                    "server": name,
+                    "uri": resource.uri,
+                    "name": resource.name,
+                    "title": resource.title,
+                    "description": resource.description,
+                    "mimeType": resource.mime_type,
+                })
+            })
+        })
+        .collect();
+    resources.sort_by_key(|r| (r["server"].to_string(), r["uri"].to_string()));
+
+    let mut templates: Vec<_> = manager
+        .list_all_resource_templates()
+        .into_iter()
+        .filter(|(name, _)| wanted(name))
+        .flat_map(|(name, listed)| {
+            listed.into_iter().map(move |template| {
+                json!({
+                    "server": name,
+                    "uriTemplate": template.uri_template,
+                    "name": template.name,
+                    "title": template.title,
+                    "description": template.description,
+                    "mimeType": template.mime_type,
+                })
+            })
+        })
+        .collect();
+    templates.sort_by_key(|t| (t["server"].to_string(), t["uriTemplate"].to_string()));
+
+    Ok(json!({
+        "resources": resources,
+        "resourceTemplates": templates,
+    })
+    .to_string())
+}
@@ -1,8 +1,15 @@
+use std::path::Path;
+
+use crate::function_tool::FunctionCallError;
+use crate::protocol::DenyRead;
+
 pub mod apply_patch;
+mod code_outline;
 mod exec_stream;
 mod grep_files;
 mod list_dir;
 mod mcp;
+mod mcp_resource;
 mod plan;
 mod read_file;
 mod shell;
@@ -13,13 +20,29 @@ mod view_image;
 pub use plan::PLAN_TOOL;
 
 pub use apply_patch::ApplyPatchHandler;
+pub use code_outline::CodeOutlineHandler;
 pub use exec_stream::ExecStreamHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
+pub use mcp_resource::LIST_MCP_RESOURCES_TOOL_NAME;
+pub use mcp_resource::McpResourceHandler;
+pub use mcp_resource::READ_MCP_RESOURCE_TOOL_NAME;
 pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
 pub use shell::ShellHandler;
 pub use test_sync::TestSyncHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
+
+/// Refuses to read `path` when `deny_read` keeps it from sandboxed commands,
+/// since the handlers that read files in-process bypass the sandbox.
+fn ensure_readable(deny_read: &DenyRead, path: &Path) -> Result<(), FunctionCallError> {
+    if deny_read.is_path_denied(path) {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "`{}` is listed in `deny_read` and cannot be read",
+            path.display()
+        )));
+    }
+    Ok(())
+}
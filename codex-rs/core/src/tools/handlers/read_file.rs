@@ -9,6 +9,7 @@ use crate::function_tool::FunctionCallError;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ensure_readable;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 
@@ -95,8 +96,12 @@ impl ToolHandler for ReadFileHandler {
         ToolKind::Function
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation { payload, turn, .. } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -139,6 +144,10 @@ impl ToolHandler for ReadFileHandler {
                 "file_path must be an absolute path".to_string(),
             ));
         }
+        ensure_readable(
+            &turn.sandbox_policy.get_deny_read_with_cwd(&turn.cwd),
+            &path,
+        )?;
 
         let collected = match mode {
             ReadMode::Slice => slice::read(&path, offset, limit).await?,
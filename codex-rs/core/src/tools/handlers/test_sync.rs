@@ -59,6 +59,10 @@ impl ToolHandler for TestSyncHandler {
         ToolKind::Function
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation { payload, .. } = invocation;
 
@@ -10,6 +10,7 @@ use crate::protocol::ViewImageToolCallEvent;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ensure_readable;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 
@@ -26,6 +27,10 @@ impl ToolHandler for ViewImageHandler {
         ToolKind::Function
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
             session,
@@ -50,6 +55,10 @@ impl ToolHandler for ViewImageHandler {
         })?;
 
         let abs_path = turn.resolve_path(Some(args.path));
+        ensure_readable(
+            &turn.sandbox_policy.get_deny_read_with_cwd(&turn.cwd),
+            &abs_path,
+        )?;
 
         let metadata = fs::metadata(&abs_path).await.map_err(|error| {
             FunctionCallError::RespondToModel(format!(
@@ -20,6 +20,7 @@ use crate::executor::ExecutionMode;
 use crate::executor::errors::ExecError;
 use crate::executor::linkers::PreparedExec;
 use crate::function_tool::FunctionCallError;
+use crate::resource_limits::ResourceLimitKind;
 use crate::tools::context::ApplyPatchCommandContext;
 use crate::tools::context::ExecCommandContext;
 use crate::tools::context::SharedTurnDiffTracker;
@@ -167,6 +168,9 @@ pub(crate) async fn handle_container_exec_with_params(
             }
         }
         Err(ExecError::Function(err)) => Err(truncate_function_error(err)),
+        Err(ExecError::SandboxRejected { message, .. }) => Err(truncate_function_error(
+            FunctionCallError::RespondToModel(message),
+        )),
         Err(ExecError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output }))) => Err(
             FunctionCallError::RespondToModel(format_exec_output_apply_patch(&output)),
         ),
@@ -183,6 +187,7 @@ pub fn format_exec_output_apply_patch(exec_output: &ExecToolCallOutput) -> Strin
     let ExecToolCallOutput {
         exit_code,
         duration,
+        resource_limit_exceeded,
         ..
     } = exec_output;
 
@@ -190,6 +195,8 @@ pub fn format_exec_output_apply_patch(exec_output: &ExecToolCallOutput) -> Strin
     struct ExecMetadata {
         exit_code: i32,
         duration_seconds: f32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resource_limit_exceeded: Option<&'static str>,
     }
 
     #[derive(Serialize)]
@@ -208,6 +215,7 @@ pub fn format_exec_output_apply_patch(exec_output: &ExecToolCallOutput) -> Strin
         metadata: ExecMetadata {
             exit_code: *exit_code,
             duration_seconds,
+            resource_limit_exceeded: resource_limit_exceeded.map(ResourceLimitKind::as_str),
         },
     };
 
@@ -230,6 +238,14 @@ pub fn format_exec_output_str(exec_output: &ExecToolCallOutput) -> String {
         return format_exec_output(&prefixed);
     }
 
+    if let Some(kind) = exec_output.resource_limit_exceeded {
+        let prefixed = format!(
+            "command exceeded its {kind} limit (`resource_limits.{}` in config.toml)\n{content}",
+            kind.config_key()
+        );
+        return format_exec_output(&prefixed);
+    }
+
     format_exec_output(content)
 }
 
@@ -312,6 +328,8 @@ fn truncate_formatted_exec_output(content: &str, total_lines: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::exec::StreamOutput;
+    use pretty_assertions::assert_eq;
     use regex_lite::Regex;
 
     fn assert_truncated_message_matches(message: &str, line: &str, total_lines: usize) {
@@ -384,4 +402,27 @@ mod tests {
             other => panic!("unexpected error variant: {other:?}"),
         }
     }
+
+    #[test]
+    fn exec_output_reports_exceeded_resource_limit() {
+        let output = ExecToolCallOutput {
+            exit_code: 137,
+            stdout: StreamOutput::new(String::new()),
+            stderr: StreamOutput::new("Killed\n".to_string()),
+            aggregated_output: StreamOutput::new("Killed\n".to_string()),
+            duration: std::time::Duration::from_secs(2),
+            timed_out: false,
+            resource_limit_exceeded: Some(ResourceLimitKind::Memory),
+            sandbox_denials: Vec::new(),
+        };
+
+        assert_eq!(
+            format_exec_output_str(&output),
+            "command exceeded its memory limit (`resource_limits.max_memory_bytes` in config.toml)\nKilled\n"
+        );
+        assert_eq!(
+            format_exec_output_apply_patch(&output),
+            r#"{"output":"command exceeded its memory limit (`resource_limits.max_memory_bytes` in config.toml)\nKilled\n","metadata":{"exit_code":137,"duration_seconds":2.0,"resource_limit_exceeded":"memory"}}"#
+        );
+    }
 }
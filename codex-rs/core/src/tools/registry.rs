@@ -4,10 +4,14 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use codex_protocol::models::ResponseInputItem;
+use serde_json::json;
 use tracing::warn;
 
 use crate::client_common::tools::ToolSpec;
 use crate::function_tool::FunctionCallError;
+use crate::hooks;
+use crate::hooks::HookCall;
+use crate::hooks::HookRunner;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -32,6 +36,13 @@ pub trait ToolHandler: Send + Sync {
         )
     }
 
+    /// Whether the handler is free of side effects. Read-only calls from the
+    /// same model response run concurrently; everything else runs one call at
+    /// a time.
+    fn is_read_only(&self) -> bool {
+        false
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError>;
 }
 
@@ -96,6 +107,26 @@ impl ToolRegistry {
             return Err(FunctionCallError::Fatal(message));
         }
 
+        let session = Arc::clone(&invocation.session);
+        let hook_call = (!session.services.hooks.is_empty()).then(|| HookCall::new(&invocation));
+        let mut hook_messages = Vec::new();
+        if let Some(call) = &hook_call {
+            let outcome = session.services.hooks.run_pre_tool_use(call).await;
+            if let Some(reason) = outcome.blocked {
+                let message = format!("tool call blocked by hook: {reason}");
+                otel.tool_result(
+                    tool_name.as_ref(),
+                    &call_id_owned,
+                    log_payload.as_ref(),
+                    Duration::ZERO,
+                    false,
+                    &message,
+                );
+                return Err(FunctionCallError::RespondToModel(message));
+            }
+            hook_messages = outcome.messages;
+        }
+
         let output_cell = tokio::sync::Mutex::new(None);
 
         let result = otel
@@ -107,8 +138,15 @@ impl ToolRegistry {
                     let handler = handler.clone();
                     let output_cell = &output_cell;
                     let invocation = invocation;
+                    let hooks = &session.services.hooks;
+                    let hook_call = hook_call.as_ref();
                     async move {
-                        match handler.handle(invocation).await {
+                        let mut result = handler.handle(invocation).await;
+                        if let Some(call) = hook_call {
+                            result =
+                                run_post_tool_use_hooks(hooks, call, hook_messages, result).await;
+                        }
+                        match result {
                             Ok(output) => {
                                 let preview = output.log_preview();
                                 let success = output.success_for_logging();
@@ -153,7 +191,7 @@ impl ConfiguredToolSpec {
 
 pub struct ToolRegistryBuilder {
     handlers: HashMap<String, Arc<dyn ToolHandler>>,
-    specs: Vec<ConfiguredToolSpec>,
+    specs: Vec<ToolSpec>,
 }
 
 impl ToolRegistryBuilder {
@@ -165,16 +203,7 @@ impl ToolRegistryBuilder {
     }
 
     pub fn push_spec(&mut self, spec: ToolSpec) {
-        self.push_spec_with_parallel_support(spec, false);
-    }
-
-    pub fn push_spec_with_parallel_support(
-        &mut self,
-        spec: ToolSpec,
-        supports_parallel_tool_calls: bool,
-    ) {
-        self.specs
-            .push(ConfiguredToolSpec::new(spec, supports_parallel_tool_calls));
+        self.specs.push(spec);
     }
 
     pub fn register_handler(&mut self, name: impl Into<String>, handler: Arc<dyn ToolHandler>) {
@@ -207,8 +236,50 @@ impl ToolRegistryBuilder {
     // }
 
     pub fn build(self) -> (Vec<ConfiguredToolSpec>, ToolRegistry) {
+        let specs = self
+            .specs
+            .into_iter()
+            .map(|spec| {
+                let supports_parallel_tool_calls = self
+                    .handlers
+                    .get(spec.name())
+                    .is_some_and(|handler| handler.is_read_only());
+                ConfiguredToolSpec::new(spec, supports_parallel_tool_calls)
+            })
+            .collect();
         let registry = ToolRegistry::new(self.handlers);
-        (self.specs, registry)
+        (specs, registry)
+    }
+}
+
+/// Runs `post_tool_use` hooks and folds their messages, along with any left by
+/// `pre_tool_use` hooks, into the result that goes back to the model.
+async fn run_post_tool_use_hooks(
+    hooks: &HookRunner,
+    call: &HookCall,
+    mut messages: Vec<String>,
+    result: Result<ToolOutput, FunctionCallError>,
+) -> Result<ToolOutput, FunctionCallError> {
+    let tool_output = match &result {
+        Ok(output) => hooks::tool_output_json(output),
+        Err(FunctionCallError::RespondToModel(message)) => json!({ "error": message }),
+        Err(_) => return result,
+    };
+    let outcome = hooks.run_post_tool_use(call, tool_output).await;
+    messages.extend(outcome.messages);
+    let failed = outcome.blocked.is_some();
+    messages.extend(outcome.blocked);
+
+    match result {
+        Ok(mut output) => {
+            hooks::apply_to_output(&mut output, &messages, failed);
+            Ok(output)
+        }
+        Err(FunctionCallError::RespondToModel(mut message)) => {
+            hooks::append_messages(&mut message, &messages);
+            Err(FunctionCallError::RespondToModel(message))
+        }
+        Err(err) => Err(err),
     }
 }
 
@@ -47,6 +47,14 @@ impl ToolRouter {
             .collect()
     }
 
+    /// Whether any of the tools can run concurrently with other calls, which
+    /// is worth asking the model for parallel tool calls.
+    pub fn has_parallel_tools(&self) -> bool {
+        self.specs
+            .iter()
+            .any(|config| config.supports_parallel_tool_calls)
+    }
+
     pub fn tool_supports_parallel(&self, tool_name: &str) -> bool {
         self.specs
             .iter()
@@ -54,7 +62,7 @@ impl ToolRouter {
             .any(|config| config.spec.name() == tool_name)
     }
 
-    pub fn build_tool_call(
+    pub async fn build_tool_call(
         session: &Session,
         item: ResponseItem,
     ) -> Result<Option<ToolCall>, FunctionCallError> {
@@ -65,7 +73,7 @@ impl ToolRouter {
                 call_id,
                 ..
             } => {
-                if let Some((server, tool)) = session.parse_mcp_tool_name(&name) {
+                if let Some((server, tool)) = session.parse_mcp_tool_name(&name).await {
                     Ok(Some(ToolCall {
                         tool_name: name,
                         call_id,
@@ -1,7 +1,10 @@
+use crate::client_common::tools::FreeformTool;
 use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
 use crate::model_family::ModelFamily;
+use crate::tools::handlers::LIST_MCP_RESOURCES_TOOL_NAME;
 use crate::tools::handlers::PLAN_TOOL;
+use crate::tools::handlers::READ_MCP_RESOURCE_TOOL_NAME;
 use crate::tools::handlers::apply_patch::ApplyPatchToolType;
 use crate::tools::handlers::apply_patch::create_apply_patch_freeform_tool;
 use crate::tools::handlers::apply_patch::create_apply_patch_json_tool;
@@ -29,6 +32,11 @@ pub(crate) struct ToolsConfig {
     pub include_view_image_tool: bool,
     pub experimental_unified_exec_tool: bool,
     pub experimental_supported_tools: Vec<String>,
+    /// Expose `list_mcp_resources` / `read_mcp_resource`. Set per turn when a
+    /// connected MCP server advertises resources.
+    pub mcp_resource_tools: bool,
+    use_streamable_shell_tool: bool,
+    include_apply_patch_tool: bool,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -52,34 +60,58 @@ impl ToolsConfig {
             include_view_image_tool,
             experimental_unified_exec_tool,
         } = params;
-        let shell_type = if *use_streamable_shell_tool {
-            ConfigShellToolType::Streamable
-        } else if model_family.uses_local_shell_tool {
-            ConfigShellToolType::Local
-        } else {
-            ConfigShellToolType::Default
-        };
-
-        let apply_patch_tool_type = match model_family.apply_patch_tool_type {
-            Some(ApplyPatchToolType::Freeform) => Some(ApplyPatchToolType::Freeform),
-            Some(ApplyPatchToolType::Function) => Some(ApplyPatchToolType::Function),
-            None => {
-                if *include_apply_patch_tool {
-                    Some(ApplyPatchToolType::Freeform)
-                } else {
-                    None
-                }
-            }
-        };
-
         Self {
-            shell_type,
+            shell_type: shell_type(model_family, *use_streamable_shell_tool),
             plan_tool: *include_plan_tool,
-            apply_patch_tool_type,
+            apply_patch_tool_type: apply_patch_tool_type(model_family, *include_apply_patch_tool),
             web_search_request: *include_web_search_request,
             include_view_image_tool: *include_view_image_tool,
             experimental_unified_exec_tool: *experimental_unified_exec_tool,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
+            mcp_resource_tools: false,
+            use_streamable_shell_tool: *use_streamable_shell_tool,
+            include_apply_patch_tool: *include_apply_patch_tool,
+        }
+    }
+
+    /// The same tools in the flavours `model_family` understands, for a turn
+    /// that failed over to another model.
+    pub fn for_model_family(&self, model_family: &ModelFamily) -> Self {
+        Self {
+            shell_type: shell_type(model_family, self.use_streamable_shell_tool),
+            apply_patch_tool_type: apply_patch_tool_type(
+                model_family,
+                self.include_apply_patch_tool,
+            ),
+            experimental_supported_tools: model_family.experimental_supported_tools.clone(),
+            ..self.clone()
+        }
+    }
+}
+
+fn shell_type(model_family: &ModelFamily, use_streamable_shell_tool: bool) -> ConfigShellToolType {
+    if use_streamable_shell_tool {
+        ConfigShellToolType::Streamable
+    } else if model_family.uses_local_shell_tool {
+        ConfigShellToolType::Local
+    } else {
+        ConfigShellToolType::Default
+    }
+}
+
+fn apply_patch_tool_type(
+    model_family: &ModelFamily,
+    include_apply_patch_tool: bool,
+) -> Option<ApplyPatchToolType> {
+    match model_family.apply_patch_tool_type {
+        Some(ApplyPatchToolType::Freeform) => Some(ApplyPatchToolType::Freeform),
+        Some(ApplyPatchToolType::Function) => Some(ApplyPatchToolType::Function),
+        None => {
+            if include_apply_patch_tool {
+                Some(ApplyPatchToolType::Freeform)
+            } else {
+                None
+            }
         }
     }
 }
@@ -350,16 +382,32 @@ fn create_grep_files_tool() -> ToolSpec {
     properties.insert(
         "limit".to_string(),
         JsonSchema::Number {
+            description: Some("Maximum number of matches to return (defaults to 100).".to_string()),
+        },
+    );
+    properties.insert(
+        "context".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Number of lines to show before and after each match (defaults to 0, max 10)."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "multiline".to_string(),
+        JsonSchema::Boolean {
             description: Some(
-                "Maximum number of file paths to return (defaults to 100).".to_string(),
+                "Allow the pattern to match across lines; `.` also matches newlines.".to_string(),
             ),
         },
     );
 
     ToolSpec::Function(ResponsesApiTool {
         name: "grep_files".to_string(),
-        description: "Finds files whose contents match the pattern and lists them by modification \
-                      time."
+        description: "Searches file contents with a regular expression, skipping files ignored by \
+                      .gitignore. Returns matching lines as `path:line:text`, with files in \
+                      path order."
             .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
@@ -468,6 +516,32 @@ fn create_read_file_tool() -> ToolSpec {
     })
 }
 
+fn create_code_outline_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Absolute path to a source file or a directory to outline.".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "code_outline".to_string(),
+        description: "Lists the functions, types, impls and classes defined in Rust, Python, \
+                      TypeScript/JavaScript or Go sources, with the line range of each. Use it \
+                      to find the part of a large file worth reading."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["path".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_list_dir_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -512,6 +586,57 @@ fn create_list_dir_tool() -> ToolSpec {
         },
     })
 }
+fn create_list_mcp_resources_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "server".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Only list resources from this MCP server. Omit to list every server.".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: LIST_MCP_RESOURCES_TOOL_NAME.to_string(),
+        description: "Lists the resources and resource templates exposed by MCP servers. Read a resource with read_mcp_resource using its server and uri; fill in a template's uriTemplate to build a uri."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_read_mcp_resource_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "server".to_string(),
+        JsonSchema::String {
+            description: Some("Name of the MCP server that owns the resource.".to_string()),
+        },
+    );
+    properties.insert(
+        "uri".to_string(),
+        JsonSchema::String {
+            description: Some("URI of the resource to read.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: READ_MCP_RESOURCE_TOOL_NAME.to_string(),
+        description: "Reads an MCP resource and returns its contents.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["server".to_string(), "uri".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 /// TODO(dylan): deprecate once we get rid of json tool
 #[derive(Serialize, Deserialize)]
 pub(crate) struct ApplyPatchToolArgs {
@@ -564,6 +689,65 @@ pub(crate) fn create_tools_json_for_chat_completions_api(
     Ok(tools_json)
 }
 
+/// Returns JSON values that are compatible with tool use in the Anthropic
+/// Messages API, which only has function tools. Freeform tools become function
+/// tools taking their raw text as a single `input` string, and `local_shell`
+/// becomes the `shell` function tool. `web_search` has no equivalent and is
+/// dropped.
+pub(crate) fn create_tools_json_for_messages_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    let mut tools_json = Vec::new();
+    for tool in tools {
+        let function = match tool {
+            ToolSpec::Function(tool) => tool.clone(),
+            ToolSpec::Freeform(tool) => freeform_tool_to_function_tool(tool),
+            ToolSpec::LocalShell {} => match create_shell_tool() {
+                ToolSpec::Function(tool) => tool,
+                _ => continue,
+            },
+            ToolSpec::WebSearch {} => continue,
+        };
+        tools_json.push(json!({
+            "name": function.name,
+            "description": function.description,
+            "input_schema": serde_json::to_value(&function.parameters)?,
+        }));
+    }
+    Ok(tools_json)
+}
+
+/// Wraps a freeform tool as a function tool whose only argument is the raw
+/// `input` text. The grammar moves into the description so the model still
+/// sees it.
+fn freeform_tool_to_function_tool(tool: &FreeformTool) -> ResponsesApiTool {
+    let FreeformTool {
+        name,
+        description,
+        format,
+    } = tool;
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "input".to_string(),
+        JsonSchema::String {
+            description: Some(format!(
+                "The raw tool input. It must match this {} grammar:\n{}",
+                format.syntax, format.definition
+            )),
+        },
+    );
+    ResponsesApiTool {
+        name: name.clone(),
+        description: description.clone(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["input".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    }
+}
+
 pub(crate) fn mcp_tool_to_openai_tool(
     fully_qualified_name: String,
     tool: mcp_types::Tool,
@@ -720,10 +904,12 @@ pub(crate) fn build_specs(
     use crate::exec_command::create_exec_command_tool_for_responses_api;
     use crate::exec_command::create_write_stdin_tool_for_responses_api;
     use crate::tools::handlers::ApplyPatchHandler;
+    use crate::tools::handlers::CodeOutlineHandler;
     use crate::tools::handlers::ExecStreamHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
     use crate::tools::handlers::McpHandler;
+    use crate::tools::handlers::McpResourceHandler;
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
     use crate::tools::handlers::ShellHandler;
@@ -793,7 +979,7 @@ pub(crate) fn build_specs(
         .contains(&"grep_files".to_string())
     {
         let grep_files_handler = Arc::new(GrepFilesHandler);
-        builder.push_spec_with_parallel_support(create_grep_files_tool(), true);
+        builder.push_spec(create_grep_files_tool());
         builder.register_handler("grep_files", grep_files_handler);
     }
 
@@ -802,17 +988,26 @@ pub(crate) fn build_specs(
         .contains(&"read_file".to_string())
     {
         let read_file_handler = Arc::new(ReadFileHandler);
-        builder.push_spec_with_parallel_support(create_read_file_tool(), true);
+        builder.push_spec(create_read_file_tool());
         builder.register_handler("read_file", read_file_handler);
     }
 
+    if config
+        .experimental_supported_tools
+        .contains(&"code_outline".to_string())
+    {
+        let code_outline_handler = Arc::new(CodeOutlineHandler);
+        builder.push_spec(create_code_outline_tool());
+        builder.register_handler("code_outline", code_outline_handler);
+    }
+
     if config
         .experimental_supported_tools
         .iter()
         .any(|tool| tool == "list_dir")
     {
         let list_dir_handler = Arc::new(ListDirHandler);
-        builder.push_spec_with_parallel_support(create_list_dir_tool(), true);
+        builder.push_spec(create_list_dir_tool());
         builder.register_handler("list_dir", list_dir_handler);
     }
 
@@ -821,7 +1016,7 @@ pub(crate) fn build_specs(
         .contains(&"test_sync_tool".to_string())
     {
         let test_sync_handler = Arc::new(TestSyncHandler);
-        builder.push_spec_with_parallel_support(create_test_sync_tool(), true);
+        builder.push_spec(create_test_sync_tool());
         builder.register_handler("test_sync_tool", test_sync_handler);
     }
 
@@ -830,10 +1025,18 @@ pub(crate) fn build_specs(
     }
 
     if config.include_view_image_tool {
-        builder.push_spec_with_parallel_support(create_view_image_tool(), true);
+        builder.push_spec(create_view_image_tool());
         builder.register_handler("view_image", view_image_handler);
     }
 
+    if config.mcp_resource_tools {
+        let mcp_resource_handler = Arc::new(McpResourceHandler);
+        builder.push_spec(create_list_mcp_resources_tool());
+        builder.push_spec(create_read_mcp_resource_tool());
+        builder.register_handler(LIST_MCP_RESOURCES_TOOL_NAME, mcp_resource_handler.clone());
+        builder.register_handler(READ_MCP_RESOURCE_TOOL_NAME, mcp_resource_handler);
+    }
+
     if let Some(mcp_tools) = mcp_tools {
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
@@ -944,11 +1147,9 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
-    fn test_parallel_support_flags() {
-        let model_family = find_family_for_model("gpt-5-codex")
-            .expect("codex-mini-latest should be a valid model family");
-        let config = ToolsConfig::new(&ToolsConfigParams {
+    fn test_build_specs_mcp_resource_tools() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let mut config = ToolsConfig::new(&ToolsConfigParams {
             model_family: &model_family,
             include_plan_tool: false,
             include_apply_patch_tool: false,
@@ -957,12 +1158,39 @@ mod tests {
             include_view_image_tool: false,
             experimental_unified_exec_tool: true,
         });
+        config.mcp_resource_tools = true;
+        let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
+
+        assert_eq_tool_names(
+            &tools,
+            &["unified_exec", "list_mcp_resources", "read_mcp_resource"],
+        );
+        assert!(find_tool(&tools, "list_mcp_resources").supports_parallel_tool_calls);
+        assert!(find_tool(&tools, "read_mcp_resource").supports_parallel_tool_calls);
+    }
+
+    #[test]
+    fn test_parallel_support_flags() {
+        let model_family = find_family_for_model("test-gpt-5-codex")
+            .expect("test-gpt-5-codex should be a valid model family");
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            include_plan_tool: true,
+            include_apply_patch_tool: true,
+            include_web_search_request: false,
+            use_streamable_shell_tool: false,
+            include_view_image_tool: true,
+            experimental_unified_exec_tool: true,
+        });
         let (tools, _) = build_specs(&config, None).build();
 
         assert!(!find_tool(&tools, "unified_exec").supports_parallel_tool_calls);
+        assert!(!find_tool(&tools, "apply_patch").supports_parallel_tool_calls);
+        assert!(!find_tool(&tools, "update_plan").supports_parallel_tool_calls);
         assert!(find_tool(&tools, "grep_files").supports_parallel_tool_calls);
         assert!(find_tool(&tools, "list_dir").supports_parallel_tool_calls);
         assert!(find_tool(&tools, "read_file").supports_parallel_tool_calls);
+        assert!(find_tool(&tools, "view_image").supports_parallel_tool_calls);
     }
 
     #[test]
@@ -1583,4 +1811,34 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_messages_api_tools_json() {
+        let tools = vec![create_shell_tool(), create_apply_patch_freeform_tool()];
+
+        let tools_json = create_tools_json_for_messages_api(&tools).unwrap();
+
+        assert_eq!(tools_json.len(), 2);
+        assert_eq!(tools_json[0]["name"], "shell");
+        assert!(tools_json[0]["description"].is_string());
+        assert_eq!(tools_json[0]["input_schema"]["type"], "object");
+        assert!(tools_json[0].get("type").is_none());
+
+        assert_eq!(tools_json[1]["name"], "apply_patch");
+        assert_eq!(tools_json[1]["input_schema"]["required"], json!(["input"]));
+        assert_eq!(
+            tools_json[1]["input_schema"]["properties"]["input"]["type"],
+            "string"
+        );
+    }
+
+    #[test]
+    fn test_messages_api_tools_json_maps_local_shell_and_drops_web_search() {
+        let tools = vec![ToolSpec::LocalShell {}, ToolSpec::WebSearch {}];
+
+        let tools_json = create_tools_json_for_messages_api(&tools).unwrap();
+
+        assert_eq!(tools_json.len(), 1);
+        assert_eq!(tools_json[0]["name"], "shell");
+    }
 }
@@ -0,0 +1,112 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::path::Path;
+
+use codex_core::CodexConversation;
+use codex_core::config_types::CassetteConfig;
+use codex_core::config_types::CassetteMode;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+async fn run_session(
+    server: &MockServer,
+    cwd: &Path,
+    cassette: CassetteConfig,
+    text: &str,
+) -> Result<String, String> {
+    let cwd = cwd.to_path_buf();
+    let test = test_codex()
+        .with_config(move |config| {
+            config.cwd = cwd;
+            config.cassette = Some(cassette);
+        })
+        .build(server)
+        .await
+        .unwrap();
+    submit(&test.codex, text).await
+}
+
+async fn submit(codex: &CodexConversation, text: &str) -> Result<String, String> {
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text { text: text.into() }],
+        })
+        .await
+        .unwrap();
+
+    let mut last_message = String::new();
+    loop {
+        match wait_for_event(codex, |_| true).await {
+            EventMsg::AgentMessage(event) => last_message = event.message,
+            EventMsg::Error(event) => return Err(event.message),
+            EventMsg::TaskComplete(_) => return Ok(last_message),
+            _ => {}
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn recorded_session_replays_without_network() {
+    skip_if_no_network!();
+
+    let cassette_dir = TempDir::new().unwrap();
+    let cwd = TempDir::new().unwrap();
+
+    let server = start_mock_server().await;
+    let response = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "recorded answer"),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let record = CassetteConfig {
+        mode: CassetteMode::Record,
+        dir: cassette_dir.path().to_path_buf(),
+    };
+    let recorded = run_session(&server, cwd.path(), record, "hello").await;
+    assert_eq!(recorded.as_deref(), Ok("recorded answer"));
+
+    let request_files: Vec<_> = std::fs::read_dir(cassette_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.ends_with(".request.json"))
+        .collect();
+    assert_eq!(request_files.len(), 1, "{request_files:?}");
+    let recorded_body: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(cassette_dir.path().join(&request_files[0])).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(recorded_body, response.single_request().body_json());
+
+    // Nothing is mounted on this server, so any network request would fail.
+    let offline = start_mock_server().await;
+    let replay = CassetteConfig {
+        mode: CassetteMode::Replay,
+        dir: cassette_dir.path().to_path_buf(),
+    };
+    let replayed = run_session(&offline, cwd.path(), replay.clone(), "hello").await;
+    assert_eq!(replayed.as_deref(), Ok("recorded answer"));
+    assert!(offline.received_requests().await.unwrap().is_empty());
+
+    let mismatch = run_session(&offline, cwd.path(), replay, "a different prompt").await;
+    let error = mismatch.expect_err("unrecorded prompt should fail");
+    assert!(error.contains("no recorded response"), "{error}");
+    assert!(offline.received_requests().await.unwrap().is_empty());
+}
@@ -871,7 +871,9 @@ async fn token_count_includes_rate_limits_snapshot() {
                     "window_minutes": 60,
                     "resets_in_seconds": 7200
                 }
-            }
+            },
+            // 123 input tokens at gpt-5-codex's $1.25 per million
+            "cost_usd": 0.00015375
         })
     );
     let usage = final_payload
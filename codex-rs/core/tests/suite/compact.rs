@@ -7,6 +7,7 @@ use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
+use codex_core::protocol::PinnedItem;
 use codex_core::protocol::RolloutItem;
 use codex_core::protocol::RolloutLine;
 use core_test_support::load_default_config_for_test;
@@ -15,6 +16,7 @@ use core_test_support::wait_for_event;
 use tempfile::TempDir;
 
 use codex_core::codex::compact::SUMMARIZATION_PROMPT;
+use codex_protocol::config_types::CompactionStrategy;
 use core_test_support::responses::ev_assistant_message;
 use core_test_support::responses::ev_completed;
 use core_test_support::responses::ev_completed_with_tokens;
@@ -24,6 +26,7 @@ use core_test_support::responses::mount_sse_sequence;
 use core_test_support::responses::sse;
 use core_test_support::responses::sse_failed;
 use core_test_support::responses::start_mock_server;
+use core_test_support::test_codex::test_codex;
 use pretty_assertions::assert_eq;
 // --- Test helpers -----------------------------------------------------------
 
@@ -115,7 +118,7 @@ async fn summarize_context_three_requests_and_instructions() {
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     // 2) Summarize – second hit should include the summarization prompt.
-    codex.submit(Op::Compact).await.unwrap();
+    codex.submit(Op::Compact { strategy: None }).await.unwrap();
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     // 3) Next user input – third hit; history should include only the summary.
@@ -680,7 +683,7 @@ async fn manual_compact_retries_after_context_window_error() {
         .unwrap();
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
-    codex.submit(Op::Compact).await.unwrap();
+    codex.submit(Op::Compact { strategy: None }).await.unwrap();
 
     let EventMsg::BackgroundEvent(event) =
         wait_for_event(&codex, |ev| matches!(ev, EventMsg::BackgroundEvent(_))).await
@@ -860,3 +863,200 @@ async fn auto_compact_allows_multiple_attempts_when_interleaved_with_other_turn_
         "second auto compact request should include the summarization prompt"
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn keep_last_turns_compacts_without_asking_the_model() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_assistant_message("m1", "first reply"),
+                ev_completed("r1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m2", "second reply"),
+                ev_completed("r2"),
+            ]),
+            sse(vec![ev_completed("r3")]),
+        ],
+    )
+    .await;
+
+    let test = test_codex().build(&server).await.unwrap();
+    let codex = test.codex.clone();
+    for text in ["first turn", "second turn"] {
+        codex
+            .submit(Op::UserInput {
+                items: vec![InputItem::Text { text: text.into() }],
+            })
+            .await
+            .unwrap();
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    }
+
+    codex
+        .submit(Op::Compact {
+            strategy: Some(CompactionStrategy::KeepLastTurns(1)),
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "third turn".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 3, "compaction should not call the model");
+    let last_body = requests[2].body_json().to_string();
+    assert!(!last_body.contains("first turn"), "{last_body}");
+    assert!(!last_body.contains("first reply"), "{last_body}");
+    assert!(last_body.contains("second turn"), "{last_body}");
+    assert!(last_body.contains("second reply"), "{last_body}");
+
+    codex.submit(Op::Shutdown).await.unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::ShutdownComplete)).await;
+    let rollout = std::fs::read_to_string(&test.session_configured.rollout_path).unwrap();
+    let strategies: Vec<CompactionStrategy> = rollout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line).ok())
+        .filter_map(|line| match line.item {
+            RolloutItem::Compacted(compacted) => Some(compacted.strategy),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(strategies, vec![CompactionStrategy::KeepLastTurns(1)]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pinned_items_survive_compaction() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![ev_completed("r1")]),
+            sse(vec![ev_completed("r2")]),
+            sse(vec![ev_completed("r3")]),
+        ],
+    )
+    .await;
+
+    let test = test_codex().build(&server).await.unwrap();
+    let codex = test.codex.clone();
+    std::fs::write(test.cwd.path().join("spec.md"), "SPEC: retry three times").unwrap();
+
+    let user_turn = |text: &str| Op::UserInput {
+        items: vec![InputItem::Text { text: text.into() }],
+    };
+    codex
+        .submit(user_turn("the build fails with E0425"))
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::PinItem {
+            item: PinnedItem::File {
+                path: "missing.md".into(),
+            },
+        })
+        .await
+        .unwrap();
+    let EventMsg::Error(error) =
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::Error(_))).await
+    else {
+        unreachable!()
+    };
+    assert!(error.message.contains("not a file"), "{}", error.message);
+
+    for item in [
+        PinnedItem::Message {
+            text: "the build fails with E0425".into(),
+        },
+        PinnedItem::File {
+            path: "spec.md".into(),
+        },
+    ] {
+        codex.submit(Op::PinItem { item }).await.unwrap();
+    }
+    let EventMsg::PinnedItems(pinned) = wait_for_event(
+        &codex,
+        |ev| matches!(ev, EventMsg::PinnedItems(ev) if ev.items.len() == 2),
+    )
+    .await
+    else {
+        unreachable!()
+    };
+    assert_eq!(
+        pinned.items[1],
+        PinnedItem::File {
+            path: test.cwd.path().join("spec.md"),
+        }
+    );
+
+    codex.submit(user_turn("second turn")).await.unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    codex
+        .submit(Op::Compact {
+            strategy: Some(CompactionStrategy::KeepLastTurns(1)),
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    codex.submit(user_turn("third turn")).await.unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 3);
+    let last_body = requests[2].body_json().to_string();
+    // The first turn was dropped; only its pinned copy is left.
+    assert_eq!(
+        last_body.matches("the build fails with E0425").count(),
+        1,
+        "{last_body}"
+    );
+    assert!(
+        last_body.contains("<pinned_context>\\nthe build fails with E0425\\n</pinned_context>"),
+        "{last_body}"
+    );
+    assert!(last_body.contains("SPEC: retry three times"), "{last_body}");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unpinned_items_are_no_longer_kept() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    let codex = test_codex().build(&server).await.unwrap().codex;
+    let item = PinnedItem::Message {
+        text: "the build fails with E0425".into(),
+    };
+
+    codex
+        .submit(Op::PinItem { item: item.clone() })
+        .await
+        .unwrap();
+    wait_for_event(
+        &codex,
+        |ev| matches!(ev, EventMsg::PinnedItems(ev) if ev.items.len() == 1),
+    )
+    .await;
+
+    codex.submit(Op::UnpinItem { item }).await.unwrap();
+    wait_for_event(
+        &codex,
+        |ev| matches!(ev, EventMsg::PinnedItems(ev) if ev.items.is_empty()),
+    )
+    .await;
+}
@@ -0,0 +1,170 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use codex_core::CodexConversation;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+
+/// gpt-5 charges $1.25 per million input tokens. Stays below the auto-compact
+/// limit so the turn makes a single request.
+const TURN_TOKENS: u64 = 100_000;
+const TURN_COST_USD: f64 = 0.125;
+
+async fn submit(codex: &CodexConversation, text: &str) -> String {
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text { text: text.into() }],
+        })
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn session_pauses_when_its_cost_budget_is_reached() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "first"),
+            ev_completed_with_tokens("resp-1", TURN_TOKENS),
+        ]),
+    )
+    .await;
+
+    let codex = test_codex()
+        .with_config(|config| {
+            config.model = "gpt-5".to_string();
+            config.session_budget_usd = Some(0.1);
+        })
+        .build(&server)
+        .await
+        .unwrap()
+        .codex;
+
+    submit(&codex, "first").await;
+    let EventMsg::TokenCount(token_count) = wait_for_event(
+        &codex,
+        |ev| matches!(ev, EventMsg::TokenCount(ev) if ev.info.is_some()),
+    )
+    .await
+    else {
+        unreachable!()
+    };
+    let cost_usd = token_count.cost_usd.expect("gpt-5 has a known price");
+    assert!((cost_usd - TURN_COST_USD).abs() < 1e-9, "{cost_usd}");
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    // The first turn spent the whole budget, so the next one asks first.
+    let second = submit(&codex, "second").await;
+    let EventMsg::CostBudgetApprovalRequest(request) = wait_for_event(&codex, |ev| {
+        matches!(ev, EventMsg::CostBudgetApprovalRequest(_))
+    })
+    .await
+    else {
+        unreachable!()
+    };
+    assert_eq!(request.budget_usd, 0.1);
+    codex
+        .submit(Op::CostBudgetApproval {
+            id: second,
+            decision: ReviewDecision::Denied,
+        })
+        .await
+        .unwrap();
+    let EventMsg::Error(error) =
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::Error(_))).await
+    else {
+        unreachable!()
+    };
+    assert!(error.message.contains("cost budget"), "{}", error.message);
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+    // Approving allows another budget's worth of spending.
+    let approved = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_assistant_message("msg-2", "third"),
+            ev_completed_with_tokens("resp-2", 1),
+        ]),
+    )
+    .await;
+    let third = submit(&codex, "third").await;
+    wait_for_event(&codex, |ev| {
+        matches!(ev, EventMsg::CostBudgetApprovalRequest(_))
+    })
+    .await;
+    codex
+        .submit(Op::CostBudgetApproval {
+            id: third,
+            decision: ReviewDecision::Approved,
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    assert_eq!(approved.single_request().body_json()["model"], "gpt-5");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn budget_is_checked_before_each_request_of_a_turn() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call("call-1", "no_such_tool", "{}"),
+            ev_completed_with_tokens("resp-1", TURN_TOKENS),
+        ]),
+    )
+    .await;
+
+    let codex = test_codex()
+        .with_config(|config| {
+            config.model = "gpt-5".to_string();
+            config.session_budget_usd = Some(0.1);
+        })
+        .build(&server)
+        .await
+        .unwrap()
+        .codex;
+
+    // The first request spends the budget, so the follow-up request that
+    // returns the tool output waits for approval within the same turn.
+    let first = submit(&codex, "first").await;
+    wait_for_event(&codex, |ev| {
+        matches!(ev, EventMsg::CostBudgetApprovalRequest(_))
+    })
+    .await;
+    codex
+        .submit(Op::CostBudgetApproval {
+            id: first,
+            decision: ReviewDecision::Denied,
+        })
+        .await
+        .unwrap();
+    let EventMsg::Error(error) =
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::Error(_))).await
+    else {
+        unreachable!()
+    };
+    assert!(error.message.contains("cost budget"), "{}", error.message);
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
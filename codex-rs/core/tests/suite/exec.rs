@@ -3,10 +3,12 @@
 use std::collections::HashMap;
 use std::string::ToString;
 
+use codex_core::config_types::ResourceLimits;
 use codex_core::exec::ExecParams;
 use codex_core::exec::ExecToolCallOutput;
 use codex_core::exec::SandboxType;
 use codex_core::exec::process_exec_tool_call;
+use codex_core::landlock::LinuxSandboxOptions;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::spawn::CODEX_SANDBOX_ENV_VAR;
 use tempfile::TempDir;
@@ -40,7 +42,17 @@ async fn run_test_cmd(tmp: TempDir, cmd: Vec<&str>) -> Result<ExecToolCallOutput
 
     let policy = SandboxPolicy::new_read_only_policy();
 
-    process_exec_tool_call(params, sandbox_type, &policy, tmp.path(), &None, None).await
+    process_exec_tool_call(
+        params,
+        sandbox_type,
+        &policy,
+        tmp.path(),
+        &None,
+        &ResourceLimits::default(),
+        LinuxSandboxOptions::default(),
+        None,
+    )
+    .await
 }
 
 /// Command succeeds with exit code 0 normally
@@ -5,12 +5,14 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use async_channel::Receiver;
+use codex_core::config_types::ResourceLimits;
 use codex_core::error::CodexErr;
 use codex_core::error::SandboxErr;
 use codex_core::exec::ExecParams;
 use codex_core::exec::SandboxType;
 use codex_core::exec::StdoutStream;
 use codex_core::exec::process_exec_tool_call;
+use codex_core::landlock::LinuxSandboxOptions;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecCommandOutputDeltaEvent;
@@ -67,6 +69,8 @@ async fn test_exec_stdout_stream_events_echo() {
         &policy,
         cwd.as_path(),
         &None,
+        &ResourceLimits::default(),
+        LinuxSandboxOptions::default(),
         Some(stdout_stream),
     )
     .await;
@@ -119,6 +123,8 @@ async fn test_exec_stderr_stream_events_echo() {
         &policy,
         cwd.as_path(),
         &None,
+        &ResourceLimits::default(),
+        LinuxSandboxOptions::default(),
         Some(stdout_stream),
     )
     .await;
@@ -174,6 +180,8 @@ async fn test_aggregated_output_interleaves_in_order() {
         &policy,
         cwd.as_path(),
         &None,
+        &ResourceLimits::default(),
+        LinuxSandboxOptions::default(),
         None,
     )
     .await
@@ -212,6 +220,8 @@ async fn test_exec_timeout_returns_partial_output() {
         &policy,
         cwd.as_path(),
         &None,
+        &ResourceLimits::default(),
+        LinuxSandboxOptions::default(),
         None,
     )
     .await;
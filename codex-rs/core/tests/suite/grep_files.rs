@@ -22,32 +22,13 @@ use core_test_support::wait_for_event;
 use serde_json::Value;
 use std::collections::HashSet;
 use std::path::Path;
-use std::process::Command as StdCommand;
 use wiremock::matchers::any;
 
 const MODEL_WITH_TOOL: &str = "test-gpt-5-codex";
 
-fn ripgrep_available() -> bool {
-    StdCommand::new("rg")
-        .arg("--version")
-        .output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
-}
-
-macro_rules! skip_if_ripgrep_missing {
-    ($ret:expr $(,)?) => {{
-        if !ripgrep_available() {
-            eprintln!("rg not available in PATH; skipping test");
-            return $ret;
-        }
-    }};
-}
-
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn grep_files_tool_collects_matches() -> Result<()> {
     skip_if_no_network!(Ok(()));
-    skip_if_ripgrep_missing!(Ok(()));
 
     let server = start_mock_server().await;
     let test = build_test_codex(&server).await?;
@@ -80,6 +61,10 @@ async fn grep_files_tool_collects_matches() -> Result<()> {
     let success = success_opt.unwrap_or(true);
     assert!(success, "expected success for matches, got {payload:?}");
 
+    assert!(
+        content.contains(&format!("{}:1:alpha needle", alpha.display())),
+        "expected matching line with line number: {content}"
+    );
     let entries = collect_file_names(content);
     assert_eq!(entries.len(), 2, "content: {content}");
     assert!(
@@ -101,7 +86,6 @@ async fn grep_files_tool_collects_matches() -> Result<()> {
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn grep_files_tool_reports_empty_results() -> Result<()> {
     skip_if_no_network!(Ok(()));
-    skip_if_ripgrep_missing!(Ok(()));
 
     let server = start_mock_server().await;
     let test = build_test_codex(&server).await?;
@@ -218,7 +202,8 @@ fn collect_file_names(content: &str) -> HashSet<String> {
             if line.trim().is_empty() {
                 return None;
             }
-            Path::new(line)
+            let (path, _) = line.split_once(':')?;
+            Path::new(path)
                 .file_name()
                 .map(|name| name.to_string_lossy().into_owned())
         })
@@ -0,0 +1,140 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use anyhow::Result;
+use codex_core::config_types::HookConfig;
+use codex_core::config_types::Hooks;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use serde_json::Value;
+use serde_json::json;
+
+fn sh_hook(matcher: &str, script: &str) -> HookConfig {
+    HookConfig {
+        matcher: Some(matcher.to_string()),
+        command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+        timeout_sec: None,
+    }
+}
+
+async fn run_shell_call(test: &TestCodex, server: &wiremock::MockServer) -> Result<Value> {
+    let call_id = "hooked-shell";
+    let args = json!({
+        "command": ["sh", "-c", "touch created.txt && echo ran"],
+        "timeout_ms": 1_000,
+    });
+
+    mount_sse_once(
+        server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call(call_id, "shell", &serde_json::to_string(&args)?),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    let second_mock = mount_sse_once(
+        server,
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text {
+                text: "run the shell command".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    Ok(second_mock.single_request().function_call_output(call_id))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pre_tool_use_hook_blocks_shell_call() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex().with_config(|config| {
+        config.hooks = Hooks {
+            pre_tool_use: vec![
+                sh_hook("read_file", "exit 2"),
+                sh_hook("sh*ll", "echo 'shell is disabled here'; exit 2"),
+            ],
+            post_tool_use: Vec::new(),
+        };
+    });
+    let test = builder.build(&server).await?;
+
+    let item = run_shell_call(&test, &server).await?;
+
+    assert_eq!(
+        item.get("output").and_then(Value::as_str),
+        Some("tool call blocked by hook: shell is disabled here")
+    );
+    assert!(!test.cwd.path().join("created.txt").exists());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn hooks_inject_messages_into_tool_output() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex().with_config(|config| {
+        config.hooks = Hooks {
+            pre_tool_use: vec![sh_hook("shell", "echo 'pre check passed'")],
+            post_tool_use: vec![sh_hook(
+                "shell",
+                // Confirm the hook was handed the post-call payload on stdin.
+                r#"grep -q '"hook_event_name":"post_tool_use"' && echo 'post hook saw shell'"#,
+            )],
+        };
+    });
+    let test = builder.build(&server).await?;
+
+    let item = run_shell_call(&test, &server).await?;
+
+    let output = item
+        .get("output")
+        .and_then(Value::as_str)
+        .expect("function call output string");
+    assert!(
+        output.ends_with("\n\n[hook] pre check passed\n\n[hook] post hook saw shell"),
+        "unexpected output: {output}"
+    );
+    assert!(test.cwd.path().join("created.txt").exists());
+
+    Ok(())
+}
@@ -0,0 +1,397 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use codex_app_server_protocol::AuthMode;
+use codex_core::ContentItem;
+use codex_core::ModelClient;
+use codex_core::ModelProviderInfo;
+use codex_core::Prompt;
+use codex_core::ResponseEvent;
+use codex_core::ResponseItem;
+use codex_core::WireApi;
+use codex_core::error::CodexErr;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::ConversationId;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use core_test_support::load_default_config_for_test;
+use core_test_support::responses::sse_response;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use futures::StreamExt;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+/// Builds a Messages API SSE body, using each event's `type` as the SSE
+/// event name.
+fn messages_sse(events: Vec<Value>) -> String {
+    events
+        .into_iter()
+        .map(|event| {
+            format!(
+                "event: {}\ndata: {event}\n\n",
+                event["type"].as_str().unwrap()
+            )
+        })
+        .collect()
+}
+
+fn ev_message_start(id: &str, input_tokens: u64, cache_read_input_tokens: u64) -> Value {
+    json!({
+        "type": "message_start",
+        "message": {
+            "id": id,
+            "type": "message",
+            "role": "assistant",
+            "content": [],
+            "usage": {
+                "input_tokens": input_tokens,
+                "cache_read_input_tokens": cache_read_input_tokens,
+                "output_tokens": 1,
+            },
+        },
+    })
+}
+
+fn ev_text_block(index: u64, text: &str) -> Vec<Value> {
+    vec![
+        json!({"type": "content_block_start", "index": index, "content_block": {"type": "text", "text": ""}}),
+        json!({"type": "content_block_delta", "index": index, "delta": {"type": "text_delta", "text": text}}),
+        json!({"type": "content_block_stop", "index": index}),
+    ]
+}
+
+fn ev_thinking_block(index: u64, thinking: &str, signature: &str) -> Vec<Value> {
+    vec![
+        json!({"type": "content_block_start", "index": index, "content_block": {"type": "thinking", "thinking": ""}}),
+        json!({"type": "content_block_delta", "index": index, "delta": {"type": "thinking_delta", "thinking": thinking}}),
+        json!({"type": "content_block_delta", "index": index, "delta": {"type": "signature_delta", "signature": signature}}),
+        json!({"type": "content_block_stop", "index": index}),
+    ]
+}
+
+fn ev_tool_use_block(index: u64, id: &str, name: &str, input_json: &str) -> Vec<Value> {
+    let (head, tail) = input_json.split_at(input_json.len() / 2);
+    vec![
+        json!({"type": "content_block_start", "index": index, "content_block": {"type": "tool_use", "id": id, "name": name, "input": {}}}),
+        json!({"type": "content_block_delta", "index": index, "delta": {"type": "input_json_delta", "partial_json": head}}),
+        json!({"type": "content_block_delta", "index": index, "delta": {"type": "input_json_delta", "partial_json": tail}}),
+        json!({"type": "content_block_stop", "index": index}),
+    ]
+}
+
+fn ev_message_stop(stop_reason: &str, output_tokens: u64) -> Vec<Value> {
+    vec![
+        json!({"type": "message_delta", "delta": {"stop_reason": stop_reason}, "usage": {"output_tokens": output_tokens}}),
+        json!({"type": "message_stop"}),
+    ]
+}
+
+fn messages_provider(server: &MockServer) -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: "anthropic-gateway".into(),
+        base_url: Some(format!("{}/v1", server.uri())),
+        env_key: None,
+        env_key_instructions: None,
+        wire_api: WireApi::Messages,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(5_000),
+        requires_openai_auth: false,
+    }
+}
+
+async fn mount_messages_once(server: &MockServer, body: String) {
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(sse_response(body))
+        .up_to_n_times(1)
+        .mount(server)
+        .await;
+}
+
+async fn request_bodies(server: &MockServer) -> Vec<Value> {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|request| request.body_json().unwrap())
+        .collect()
+}
+
+async fn stream_prompt(
+    server: &MockServer,
+    prompt: Prompt,
+) -> Vec<Result<ResponseEvent, CodexErr>> {
+    let provider = messages_provider(server);
+    let codex_home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model_provider_id = provider.name.clone();
+    config.model_provider = provider.clone();
+    let summary = config.model_reasoning_summary;
+    let config = Arc::new(config);
+
+    let conversation_id = ConversationId::new();
+    let otel_event_manager = OtelEventManager::new(
+        conversation_id,
+        config.model.as_str(),
+        config.model_family.slug.as_str(),
+        None,
+        Some(AuthMode::ApiKey),
+        false,
+        "test".to_string(),
+    );
+
+    let client = ModelClient::new(
+        Arc::clone(&config),
+        None,
+        otel_event_manager,
+        provider,
+        Some(codex_protocol::config_types::ReasoningEffort::High),
+        summary,
+        conversation_id,
+    );
+
+    let mut stream = client.stream(&prompt).await.unwrap();
+    let mut events = Vec::new();
+    while let Some(event) = stream.next().await {
+        events.push(event);
+    }
+    events
+}
+
+fn user_prompt(text: &str) -> Prompt {
+    let mut prompt = Prompt::default();
+    prompt.input = vec![ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: text.to_string(),
+        }],
+    }];
+    prompt
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn streams_thinking_text_and_tool_use() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    let mut events = vec![ev_message_start("msg_1", 10, 90)];
+    events.extend(ev_thinking_block(0, "look around", "sig-1"));
+    events.extend(ev_text_block(1, "Listing files."));
+    events.extend(ev_tool_use_block(
+        2,
+        "toolu_1",
+        "shell",
+        r#"{"command":["ls"]}"#,
+    ));
+    events.extend(ev_message_stop("tool_use", 42));
+    mount_messages_once(&server, messages_sse(events)).await;
+
+    let events = stream_prompt(&server, user_prompt("what is here?")).await;
+    let events: Vec<ResponseEvent> = events.into_iter().map(Result::unwrap).collect();
+
+    assert_eq!(events.len(), 6, "unexpected events: {events:?}");
+    assert!(
+        matches!(&events[0], ResponseEvent::ReasoningSummaryDelta(delta) if delta == "look around")
+    );
+    match &events[1] {
+        ResponseEvent::OutputItemDone(ResponseItem::Reasoning {
+            id,
+            summary,
+            encrypted_content,
+            ..
+        }) => {
+            assert!(id.starts_with("thinking_"), "unexpected id: {id}");
+            assert_eq!(
+                summary,
+                &vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "look around".to_string()
+                }]
+            );
+            assert_eq!(encrypted_content.as_deref(), Some("sig-1"));
+        }
+        other => panic!("expected reasoning item, got {other:?}"),
+    }
+    assert!(
+        matches!(&events[2], ResponseEvent::OutputTextDelta(delta) if delta == "Listing files.")
+    );
+    assert!(matches!(
+        &events[3],
+        ResponseEvent::OutputItemDone(ResponseItem::Message { role, .. }) if role == "assistant"
+    ));
+    match &events[4] {
+        ResponseEvent::OutputItemDone(ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            ..
+        }) => {
+            assert_eq!(name, "shell");
+            assert_eq!(arguments, r#"{"command":["ls"]}"#);
+            assert_eq!(call_id, "toolu_1");
+        }
+        other => panic!("expected function call, got {other:?}"),
+    }
+    match &events[5] {
+        ResponseEvent::Completed {
+            response_id,
+            token_usage: Some(usage),
+        } => {
+            assert_eq!(response_id, "msg_1");
+            assert_eq!(usage.input_tokens, 100);
+            assert_eq!(usage.cached_input_tokens, 90);
+            assert_eq!(usage.output_tokens, 42);
+            assert_eq!(usage.total_tokens, 142);
+        }
+        other => panic!("expected completed with usage, got {other:?}"),
+    }
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert_eq!(
+        request
+            .headers
+            .get("anthropic-version")
+            .and_then(|v| v.to_str().ok()),
+        Some("2023-06-01")
+    );
+    let body: Value = request.body_json().unwrap();
+    assert_eq!(body["stream"], json!(true));
+    assert!(body["system"].as_str().is_some_and(|s| !s.is_empty()));
+    assert!(body["max_tokens"].as_u64().is_some());
+    assert_eq!(body["thinking"]["type"], json!("enabled"));
+    assert_eq!(
+        body["messages"],
+        json!([{"role": "user", "content": [{"type": "text", "text": "what is here?"}]}])
+    );
+    // `Prompt::default()` carries no tools, so the field is omitted.
+    assert!(body.get("tools").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn error_event_surfaces_as_stream_error() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    mount_messages_once(
+        &server,
+        messages_sse(vec![
+            ev_message_start("msg_1", 1, 0),
+            json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}),
+        ]),
+    )
+    .await;
+
+    let events = stream_prompt(&server, user_prompt("hello")).await;
+
+    match events.last() {
+        Some(Err(CodexErr::Stream(message, _))) => assert_eq!(message, "Overloaded"),
+        other => panic!("expected stream error, got {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tool_results_are_sent_back_as_tool_result_blocks() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut first = vec![ev_message_start("msg_1", 10, 0)];
+    first.extend(ev_thinking_block(0, "need a tool", "sig-1"));
+    first.extend(ev_tool_use_block(
+        1,
+        "toolu_1",
+        "missing_tool",
+        r#"{"x":1}"#,
+    ));
+    first.extend(ev_message_stop("tool_use", 5));
+    mount_messages_once(&server, messages_sse(first)).await;
+
+    let mut second = vec![ev_message_start("msg_2", 20, 0)];
+    second.extend(ev_text_block(0, "done"));
+    second.extend(ev_message_stop("end_turn", 1));
+    mount_messages_once(&server, messages_sse(second)).await;
+
+    let mut builder = test_codex().with_config(|config| {
+        config.model_provider.wire_api = WireApi::Messages;
+    });
+    let test = builder.build(&server).await?;
+
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text {
+                text: "use the tool".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    let bodies = request_bodies(&server).await;
+    assert_eq!(bodies.len(), 2);
+
+    let tools = bodies[0]["tools"].as_array().expect("tools array");
+    assert!(tools.iter().any(|tool| tool["name"] == "shell"));
+    assert!(
+        tools
+            .iter()
+            .all(|tool| tool["name"].is_string() && tool["input_schema"].is_object())
+    );
+
+    let messages = bodies[1]["messages"].as_array().expect("messages array");
+    let n = messages.len();
+    assert!(n >= 3, "unexpected messages: {messages:?}");
+    assert_eq!(
+        messages[n - 2],
+        json!({"role": "assistant", "content": [
+            {"type": "thinking", "thinking": "need a tool", "signature": "sig-1"},
+            {"type": "tool_use", "id": "toolu_1", "name": "missing_tool", "input": {"x": 1}},
+        ]})
+    );
+    assert_eq!(
+        messages[n - 1],
+        json!({"role": "user", "content": [{
+            "type": "tool_result",
+            "tool_use_id": "toolu_1",
+            "content": "unsupported call: missing_tool",
+            "is_error": true,
+        }]})
+    );
+    // Roles must alternate after merging consecutive items.
+    for pair in messages.windows(2) {
+        assert_ne!(pair[0]["role"], pair[1]["role"]);
+    }
+
+    Ok(())
+}
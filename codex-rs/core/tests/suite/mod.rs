@@ -2,17 +2,23 @@
 
 #[cfg(not(target_os = "windows"))]
 mod abort_tasks;
+mod cassette;
 mod cli_stream;
 mod client;
+mod code_outline;
 mod compact;
 mod compact_resume_fork;
+mod cost_budget;
 mod exec;
 mod exec_stream_events;
 mod fork_conversation;
 mod grep_files;
+mod hooks;
 mod json_result;
 mod list_dir;
 mod live_cli;
+mod messages_api;
+mod model_fallbacks;
 mod model_overrides;
 mod model_tools;
 mod otel;
@@ -0,0 +1,266 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use codex_core::CodexConversation;
+use codex_core::ModelProviderInfo;
+use codex_core::config::Config;
+use codex_core::config_types::ModelFallback;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::RolloutItem;
+use codex_core::protocol::RolloutLine;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once_match;
+use core_test_support::responses::sse;
+use core_test_support::responses::sse_response;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use wiremock::Mock;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+const FALLBACK_MODEL: &str = "gpt-fallback";
+
+/// Points the session at a primary provider under `/primary/v1` that gives up
+/// after one attempt, and keeps the test provider (`/v1`) as the only fallback.
+fn use_fallback_chain(config: &mut Config, server_uri: &str) {
+    let fallback_provider = config.model_provider.clone();
+    config.model_provider = ModelProviderInfo {
+        name: "primary".into(),
+        base_url: Some(format!("{server_uri}/primary/v1")),
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        ..fallback_provider.clone()
+    };
+    config.model_fallbacks = vec![ModelFallback {
+        model_provider_id: "backup".into(),
+        model_provider: fallback_provider,
+        model: FALLBACK_MODEL.into(),
+    }];
+}
+
+async fn submit_and_wait(codex: &CodexConversation, text: &str) -> Vec<String> {
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text { text: text.into() }],
+        })
+        .await
+        .unwrap();
+
+    let mut background = Vec::new();
+    loop {
+        match wait_for_event(codex, |_| true).await {
+            EventMsg::BackgroundEvent(event) => background.push(event.message),
+            EventMsg::Error(event) => panic!("unexpected error: {}", event.message),
+            EventMsg::TaskComplete(_) => return background,
+            _ => {}
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn server_errors_switch_to_fallback_for_the_turn() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(path("/primary/v1/responses"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2)
+        .mount(&server)
+        .await;
+    let fallback = mount_sse_once_match(
+        &server,
+        path("/v1/responses"),
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "from fallback"),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let server_uri = server.uri();
+    let test = test_codex()
+        .with_config(move |config| use_fallback_chain(config, &server_uri))
+        .build(&server)
+        .await
+        .unwrap();
+    let codex = test.codex.clone();
+
+    let background = submit_and_wait(&codex, "hello").await;
+    assert!(
+        background
+            .iter()
+            .any(|message| message.contains(&format!("to `{FALLBACK_MODEL}`"))),
+        "expected a failover notice, got {background:?}"
+    );
+    assert_eq!(
+        fallback.single_request().body_json()["model"],
+        FALLBACK_MODEL
+    );
+
+    // The next turn starts on the primary again, which is still failing.
+    let retry = mount_sse_once_match(
+        &server,
+        path("/v1/responses"),
+        sse(vec![
+            ev_assistant_message("msg-2", "fallback again"),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+    submit_and_wait(&codex, "again").await;
+    assert_eq!(retry.single_request().body_json()["model"], FALLBACK_MODEL);
+
+    codex.submit(Op::Shutdown).await.unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::ShutdownComplete)).await;
+
+    let rollout = std::fs::read_to_string(&test.session_configured.rollout_path).unwrap();
+    let turn_models: Vec<String> = rollout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line).ok())
+        .filter_map(|line| match line.item {
+            RolloutItem::TurnContext(context) => Some(context.model),
+            _ => None,
+        })
+        .collect();
+    assert!(
+        turn_models.iter().any(|model| model == FALLBACK_MODEL),
+        "rollout should record the effective model, got {turn_models:?}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exhausted_rate_limit_window_switches_to_fallback() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    let primary_body = sse(vec![
+        ev_response_created("resp-1"),
+        ev_function_call("call-1", "missing_tool", "{}"),
+        ev_completed("resp-1"),
+    ]);
+    Mock::given(method("POST"))
+        .and(path("/primary/v1/responses"))
+        .respond_with(
+            sse_response(primary_body).insert_header("x-codex-primary-used-percent", "100.0"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    let fallback = mount_sse_once_match(
+        &server,
+        path("/v1/responses"),
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    let server_uri = server.uri();
+    let codex = test_codex()
+        .with_config(move |config| use_fallback_chain(config, &server_uri))
+        .build(&server)
+        .await
+        .unwrap()
+        .codex;
+
+    let background = submit_and_wait(&codex, "hello").await;
+    assert!(
+        background
+            .iter()
+            .any(|message| message.contains("rate limit window exhausted")),
+        "expected a failover notice, got {background:?}"
+    );
+
+    let request = fallback.single_request();
+    assert_eq!(request.body_json()["model"], FALLBACK_MODEL);
+    assert!(
+        request.function_call_output("call-1").is_object(),
+        "tool output should be sent to the fallback"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fallback_model_limits_and_tools_apply_after_failover() {
+    skip_if_no_network!();
+
+    // Unlike the primary, this model has a 200k window and the `local_shell`
+    // tool.
+    const LOCAL_SHELL_MODEL: &str = "codex-mini-latest";
+
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(path("/primary/v1/responses"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let fallback = mount_sse_once_match(
+        &server,
+        path("/v1/responses"),
+        sse(vec![
+            ev_assistant_message("msg-1", "from fallback"),
+            ev_completed_with_tokens("resp-1", 1_000),
+        ]),
+    )
+    .await;
+
+    let server_uri = server.uri();
+    let codex = test_codex()
+        .with_config(move |config| {
+            use_fallback_chain(config, &server_uri);
+            config.model_fallbacks[0].model = LOCAL_SHELL_MODEL.into();
+        })
+        .build(&server)
+        .await
+        .unwrap()
+        .codex;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    let mut primary_window = None;
+    let mut token_info = None;
+    loop {
+        match wait_for_event(&codex, |_| true).await {
+            EventMsg::TaskStarted(event) => primary_window = event.model_context_window,
+            EventMsg::TokenCount(event) => token_info = event.info.or(token_info),
+            EventMsg::Error(event) => panic!("unexpected error: {}", event.message),
+            EventMsg::TaskComplete(_) => break,
+            _ => {}
+        }
+    }
+
+    assert_ne!(primary_window, Some(200_000));
+    let token_info = token_info.expect("token usage should be reported");
+    assert_eq!(token_info.model_context_window, Some(200_000));
+
+    let body = fallback.single_request().body_json();
+    assert_eq!(body["model"], LOCAL_SHELL_MODEL);
+    let tool_types: Vec<&str> = body["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|tool| tool["type"].as_str())
+        .collect();
+    assert!(
+        tool_types.contains(&"local_shell"),
+        "fallback should get its own shell tool, got {tool_types:?}"
+    );
+}
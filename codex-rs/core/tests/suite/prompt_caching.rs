@@ -436,8 +436,11 @@ async fn overrides_turn_context_but_keeps_cached_prefix_and_key_constant() {
             sandbox_policy: Some(SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![writable.path().to_path_buf()],
                 network_access: true,
+                allowed_domains: vec![],
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                readable_roots: vec![],
+                deny_read: vec![],
             }),
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::High)),
@@ -569,8 +572,11 @@ async fn per_turn_overrides_keep_cached_prefix_and_key_constant() {
             sandbox_policy: SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![writable.path().to_path_buf()],
                 network_access: true,
+                allowed_domains: vec![],
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                readable_roots: vec![],
+                deny_read: vec![],
             },
             model: "o3".to_string(),
             effort: Some(ReasoningEffort::High),
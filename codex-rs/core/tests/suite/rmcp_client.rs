@@ -14,10 +14,12 @@ use codex_core::protocol::AskForApproval;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::SandboxPolicy;
 use codex_protocol::config_types::ReasoningSummary;
 use core_test_support::responses;
 use core_test_support::responses::mount_sse_once_match;
+use core_test_support::responses::mount_sse_sequence;
 use core_test_support::skip_if_no_network;
 use core_test_support::test_codex::test_codex;
 use core_test_support::wait_for_event;
@@ -89,6 +91,7 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
                     enabled: true,
                     startup_timeout_sec: Some(Duration::from_secs(10)),
                     tool_timeout_sec: None,
+                    auto_approve_sampling: false,
                 },
             );
         })
@@ -238,6 +241,7 @@ async fn streamable_http_tool_call_round_trip() -> anyhow::Result<()> {
                     enabled: true,
                     startup_timeout_sec: Some(Duration::from_secs(10)),
                     tool_timeout_sec: None,
+                    auto_approve_sampling: false,
                 },
             );
         })
@@ -419,6 +423,7 @@ async fn streamable_http_with_oauth_round_trip() -> anyhow::Result<()> {
                     enabled: true,
                     startup_timeout_sec: Some(Duration::from_secs(10)),
                     tool_timeout_sec: None,
+                    auto_approve_sampling: false,
                 },
             );
         })
@@ -512,6 +517,404 @@ async fn streamable_http_with_oauth_round_trip() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn stdio_server_sampling_uses_session_model() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+
+    let call_id = "call-sample";
+    let server_name = "rmcp";
+    let tool_name = format!("{server_name}__summarize");
+
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            responses::sse(vec![
+                responses::ev_response_created("resp-1"),
+                responses::ev_function_call(
+                    call_id,
+                    &tool_name,
+                    "{\"text\":\"a very long document\"}",
+                ),
+                responses::ev_completed("resp-1"),
+            ]),
+            responses::sse(vec![
+                responses::ev_assistant_message("msg-sample", "a short summary"),
+                responses::ev_completed_with_tokens("resp-sample", 42),
+            ]),
+            responses::sse(vec![
+                responses::ev_assistant_message("msg-1", "summarized."),
+                responses::ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let rmcp_test_server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?
+        .path()
+        .to_string_lossy()
+        .into_owned();
+
+    let fixture = test_codex()
+        .with_config(move |config| {
+            config.use_experimental_use_rmcp_client = true;
+            config.mcp_servers.insert(
+                server_name.to_string(),
+                McpServerConfig {
+                    transport: McpServerTransportConfig::Stdio {
+                        command: rmcp_test_server_bin,
+                        args: Vec::new(),
+                        env: None,
+                    },
+                    enabled: true,
+                    startup_timeout_sec: Some(Duration::from_secs(10)),
+                    tool_timeout_sec: None,
+                    auto_approve_sampling: true,
+                },
+            );
+        })
+        .build(&server)
+        .await?;
+    let session_model = fixture.session_configured.model.clone();
+
+    fixture
+        .codex
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text {
+                text: "summarize the document".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: fixture.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let end_event = wait_for_event_with_timeout(
+        &fixture.codex,
+        |ev| matches!(ev, EventMsg::McpToolCallEnd(_)),
+        Duration::from_secs(10),
+    )
+    .await;
+    let EventMsg::McpToolCallEnd(end) = end_event else {
+        unreachable!("event guard guarantees McpToolCallEnd");
+    };
+    let result = end
+        .result
+        .as_ref()
+        .expect("rmcp summarize tool should return success");
+    let structured = result
+        .structured_content
+        .as_ref()
+        .expect("structured content");
+    assert_eq!(structured["summary"], "a short summary");
+    assert_eq!(structured["model"], session_model.as_str());
+
+    wait_for_event(&fixture.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = mock.requests();
+    let sampling_request = requests[1].body_json();
+    assert_eq!(
+        sampling_request["instructions"],
+        "Summarize the user's text."
+    );
+    assert_eq!(
+        sampling_request["input"][0]["content"][0]["text"],
+        "a very long document"
+    );
+
+    server.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn stdio_server_sampling_waits_for_approval() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+
+    let call_id = "call-sample";
+    let server_name = "rmcp";
+    let tool_name = format!("{server_name}__summarize");
+
+    mount_sse_sequence(
+        &server,
+        vec![
+            responses::sse(vec![
+                responses::ev_response_created("resp-1"),
+                responses::ev_function_call(
+                    call_id,
+                    &tool_name,
+                    "{\"text\":\"a very long document\"}",
+                ),
+                responses::ev_completed("resp-1"),
+            ]),
+            responses::sse(vec![
+                responses::ev_assistant_message("msg-1", "could not summarize."),
+                responses::ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let rmcp_test_server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?
+        .path()
+        .to_string_lossy()
+        .into_owned();
+
+    let fixture = test_codex()
+        .with_config(move |config| {
+            config.use_experimental_use_rmcp_client = true;
+            config.mcp_servers.insert(
+                server_name.to_string(),
+                McpServerConfig {
+                    transport: McpServerTransportConfig::Stdio {
+                        command: rmcp_test_server_bin,
+                        args: Vec::new(),
+                        env: None,
+                    },
+                    enabled: true,
+                    startup_timeout_sec: Some(Duration::from_secs(10)),
+                    tool_timeout_sec: None,
+                    auto_approve_sampling: false,
+                },
+            );
+        })
+        .build(&server)
+        .await?;
+    let session_model = fixture.session_configured.model.clone();
+
+    fixture
+        .codex
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text {
+                text: "summarize the document".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: fixture.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_model,
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    // The approval is answered by event id, so read events directly rather
+    // than through `wait_for_event`, which only yields the message.
+    let (id, request) = loop {
+        let event = tokio::time::timeout(Duration::from_secs(10), fixture.codex.next_event())
+            .await
+            .expect("timeout waiting for sampling approval request")?;
+        if let EventMsg::McpSamplingApprovalRequest(request) = event.msg {
+            break (event.id, request);
+        }
+    };
+    assert_eq!(request.server, server_name);
+    assert_eq!(
+        request.system_prompt.as_deref(),
+        Some("Summarize the user's text.")
+    );
+    assert_eq!(request.max_tokens, 100);
+
+    fixture
+        .codex
+        .submit(Op::McpSamplingApproval {
+            id,
+            decision: ReviewDecision::Denied,
+        })
+        .await?;
+
+    let end_event = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::McpToolCallEnd(_))
+    })
+    .await;
+    let EventMsg::McpToolCallEnd(end) = end_event else {
+        unreachable!("event guard guarantees McpToolCallEnd");
+    };
+    assert!(
+        end.result.is_err(),
+        "declined sampling should fail the tool call: {:?}",
+        end.result
+    );
+
+    wait_for_event(&fixture.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    server.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn reload_mcp_servers_updates_tools_for_next_turn() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+    let server_name = "rmcp";
+    let tool_name = format!("{server_name}__echo");
+
+    let mock = mount_sse_once_match(
+        &server,
+        any(),
+        responses::sse(vec![
+            responses::ev_assistant_message("msg-1", "tools reloaded."),
+            responses::ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let rmcp_test_server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?
+        .path()
+        .to_string_lossy()
+        .into_owned();
+
+    let fixture = test_codex()
+        .with_config(|config| {
+            config.use_experimental_use_rmcp_client = true;
+        })
+        .build(&server)
+        .await?;
+    let session_model = fixture.session_configured.model.clone();
+
+    // Configure the server only after the session started, then reload.
+    let config_path = fixture.home.path().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[mcp_servers.{server_name}]\ncommand = {}\n",
+            toml::Value::String(rmcp_test_server_bin)
+        ),
+    )?;
+    fixture.codex.submit(Op::ReloadMcpServers).await?;
+
+    let reloaded = wait_for_event_with_timeout(
+        &fixture.codex,
+        |ev| matches!(ev, EventMsg::McpServersReloaded(_)),
+        Duration::from_secs(10),
+    )
+    .await;
+    let EventMsg::McpServersReloaded(reloaded) = reloaded else {
+        unreachable!("event guard guarantees McpServersReloaded");
+    };
+    assert_eq!(reloaded.started, vec![server_name.to_string()]);
+    assert!(reloaded.restarted.is_empty());
+    assert!(reloaded.stopped.is_empty());
+    assert!(reloaded.tools.contains_key(&tool_name));
+
+    fixture
+        .codex
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text {
+                text: "what tools do you have?".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: fixture.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_model,
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    wait_for_event(&fixture.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let request = mock.single_request().body_json();
+    let tool_names: Vec<&str> = request["tools"]
+        .as_array()
+        .expect("tools array")
+        .iter()
+        .filter_map(|tool| tool["name"].as_str())
+        .collect();
+    assert!(
+        tool_names.contains(&tool_name.as_str()),
+        "reloaded tool missing from request: {tool_names:?}"
+    );
+
+    // Removing the server from the config shuts it down on the next reload.
+    fs::write(&config_path, "")?;
+    fixture.codex.submit(Op::ReloadMcpServers).await?;
+    let reloaded = wait_for_event_with_timeout(
+        &fixture.codex,
+        |ev| matches!(ev, EventMsg::McpServersReloaded(_)),
+        Duration::from_secs(10),
+    )
+    .await;
+    let EventMsg::McpServersReloaded(reloaded) = reloaded else {
+        unreachable!("event guard guarantees McpServersReloaded");
+    };
+    assert!(reloaded.started.is_empty());
+    assert_eq!(reloaded.stopped, vec![server_name.to_string()]);
+    assert!(reloaded.tools.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn concurrent_reloads_start_each_server_once() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+    let server_name = "rmcp";
+
+    let rmcp_test_server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?
+        .path()
+        .to_string_lossy()
+        .into_owned();
+
+    let fixture = test_codex()
+        .with_config(|config| {
+            config.use_experimental_use_rmcp_client = true;
+        })
+        .build(&server)
+        .await?;
+
+    fs::write(
+        fixture.home.path().join("config.toml"),
+        format!(
+            "[mcp_servers.{server_name}]\ncommand = {}\n",
+            toml::Value::String(rmcp_test_server_bin)
+        ),
+    )?;
+    fixture.codex.submit(Op::ReloadMcpServers).await?;
+    fixture.codex.submit(Op::ReloadMcpServers).await?;
+
+    let mut started = Vec::new();
+    for _ in 0..2 {
+        let reloaded = wait_for_event_with_timeout(
+            &fixture.codex,
+            |ev| matches!(ev, EventMsg::McpServersReloaded(_)),
+            Duration::from_secs(10),
+        )
+        .await;
+        let EventMsg::McpServersReloaded(reloaded) = reloaded else {
+            unreachable!("event guard guarantees McpServersReloaded");
+        };
+        assert!(reloaded.restarted.is_empty());
+        assert!(reloaded.stopped.is_empty());
+        started.extend(reloaded.started);
+    }
+    assert_eq!(started, vec![server_name.to_string()]);
+
+    Ok(())
+}
+
 async fn wait_for_streamable_http_server(
     server_child: &mut Child,
     address: &str,
@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
 
+use codex_core::config_types::ResourceLimits;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::seatbelt::spawn_command_under_seatbelt;
 use codex_core::spawn::CODEX_SANDBOX_ENV_VAR;
@@ -78,8 +79,11 @@ async fn if_parent_of_repo_is_writable_then_dot_git_folder_is_writable() {
     let policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![test_scenario.repo_parent.clone()],
         network_access: false,
+        allowed_domains: vec![],
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        readable_roots: vec![],
+        deny_read: vec![],
     };
 
     test_scenario
@@ -104,8 +108,11 @@ async fn if_git_repo_is_writable_root_then_dot_git_folder_is_read_only() {
     let policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![test_scenario.repo_root.clone()],
         network_access: false,
+        allowed_domains: vec![],
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        readable_roots: vec![],
+        deny_read: vec![],
     };
 
     test_scenario
@@ -145,7 +152,7 @@ async fn danger_full_access_allows_all_writes() {
 async fn read_only_forbids_all_writes() {
     let tmp = TempDir::new().expect("should be able to create temp dir");
     let test_scenario = create_test_scenario(&tmp);
-    let policy = SandboxPolicy::ReadOnly;
+    let policy = SandboxPolicy::new_read_only_policy();
 
     test_scenario
         .run_test(
@@ -176,7 +183,7 @@ async fn python_getpwuid_works_under_seatbelt() {
     }
 
     // ReadOnly is sufficient here since we are only exercising user lookup.
-    let policy = SandboxPolicy::ReadOnly;
+    let policy = SandboxPolicy::new_read_only_policy();
     let command_cwd = std::env::current_dir().expect("getcwd");
     let sandbox_cwd = command_cwd.clone();
 
@@ -190,6 +197,7 @@ async fn python_getpwuid_works_under_seatbelt() {
         command_cwd,
         &policy,
         sandbox_cwd.as_path(),
+        &ResourceLimits::default(),
         StdioPolicy::RedirectForShellTool,
         HashMap::new(),
     )
@@ -235,6 +243,7 @@ async fn touch(path: &Path, policy: &SandboxPolicy) -> bool {
         command_cwd,
         policy,
         sandbox_cwd.as_path(),
+        &ResourceLimits::default(),
         StdioPolicy::RedirectForShellTool,
         HashMap::new(),
     )
@@ -21,6 +21,7 @@ use core_test_support::skip_if_no_network;
 use core_test_support::test_codex::TestCodex;
 use core_test_support::test_codex::test_codex;
 use core_test_support::wait_for_event;
+use serde_json::Value;
 use serde_json::json;
 
 async fn run_turn(test: &TestCodex, prompt: &str) -> anyhow::Result<()> {
@@ -139,6 +140,64 @@ async fn read_file_tools_run_in_parallel() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[allow(clippy::expect_used)]
+async fn read_only_tools_run_in_parallel_without_model_support() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut model_family = find_family_for_model("gpt-5-codex").expect("gpt-5-codex model family");
+    assert!(!model_family.supports_parallel_tool_calls);
+    model_family
+        .experimental_supported_tools
+        .push("test_sync_tool".to_string());
+    let mut builder = test_codex().with_config(move |config| {
+        config.model = "gpt-5-codex".to_string();
+        config.model_family = model_family;
+    });
+    let test = builder.build(&server).await?;
+
+    let args = json!({
+        "sleep_after_ms": 300,
+        "barrier": {
+            "id": "parallel-without-model-support",
+            "participants": 3,
+            "timeout_ms": 1_000,
+        }
+    })
+    .to_string();
+    let first_response = sse(vec![
+        json!({"type": "response.created", "response": {"id": "resp-1"}}),
+        ev_function_call("call-1", "test_sync_tool", &args),
+        ev_function_call("call-2", "test_sync_tool", &args),
+        ev_function_call("call-3", "test_sync_tool", &args),
+        ev_completed("resp-1"),
+    ]);
+    let second_response = sse(vec![
+        ev_assistant_message("msg-1", "done"),
+        ev_completed("resp-2"),
+    ]);
+    let mock = mount_sse_sequence(&server, vec![first_response, second_response]).await;
+
+    let duration = run_turn_and_measure(&test, "read three things").await?;
+    assert_parallel_duration(duration);
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].body_json()["parallel_tool_calls"], json!(true));
+    let follow_up = requests.last().unwrap();
+    for call_id in ["call-1", "call-2", "call-3"] {
+        assert_eq!(
+            follow_up
+                .function_call_output(call_id)
+                .get("output")
+                .and_then(Value::as_str),
+            Some("ok")
+        );
+    }
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn non_parallel_tools_run_serially() -> anyhow::Result<()> {
     skip_if_no_network!(Ok(()));
@@ -204,3 +263,46 @@ async fn mixed_tools_fall_back_to_serial() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn parallel_outputs_keep_call_order() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = build_codex_with_test_tool(&server).await?;
+
+    // The first call finishes last; its output must still come first.
+    let slow_args = json!({ "sleep_before_ms": 300 }).to_string();
+    let fast_args = json!({}).to_string();
+    let shell_args = serde_json::to_string(&json!({
+        "command": ["/bin/echo", "serialized"],
+        "timeout_ms": 1_000,
+    }))?;
+
+    let first_response = sse(vec![
+        json!({"type": "response.created", "response": {"id": "resp-1"}}),
+        ev_function_call("call-slow", "test_sync_tool", &slow_args),
+        ev_function_call("call-fast", "test_sync_tool", &fast_args),
+        ev_function_call("call-shell", "shell", &shell_args),
+        ev_completed("resp-1"),
+    ]);
+    let second_response = sse(vec![
+        ev_assistant_message("msg-1", "done"),
+        ev_completed("resp-2"),
+    ]);
+    let mock = mount_sse_sequence(&server, vec![first_response, second_response]).await;
+
+    run_turn(&test, "order outputs").await?;
+
+    let requests = mock.requests();
+    let follow_up = requests.last().unwrap();
+    let output_ids: Vec<String> = follow_up
+        .input()
+        .iter()
+        .filter(|item| item["type"] == "function_call_output")
+        .filter_map(|item| item["call_id"].as_str().map(str::to_string))
+        .collect();
+    assert_eq!(output_ids, vec!["call-slow", "call-fast", "call-shell"]);
+
+    Ok(())
+}
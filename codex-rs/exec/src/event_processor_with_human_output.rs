@@ -60,6 +60,7 @@ pub(crate) struct EventProcessorWithHumanOutput {
     show_raw_agent_reasoning: bool,
     last_message_path: Option<PathBuf>,
     last_total_token_usage: Option<codex_core::protocol::TokenUsageInfo>,
+    last_cost_usd: Option<f64>,
     final_message: Option<String>,
 }
 
@@ -85,6 +86,7 @@ impl EventProcessorWithHumanOutput {
                 show_raw_agent_reasoning: config.show_raw_agent_reasoning,
                 last_message_path,
                 last_total_token_usage: None,
+                last_cost_usd: None,
                 final_message: None,
             }
         } else {
@@ -101,6 +103,7 @@ impl EventProcessorWithHumanOutput {
                 show_raw_agent_reasoning: config.show_raw_agent_reasoning,
                 last_message_path,
                 last_total_token_usage: None,
+                last_cost_usd: None,
                 final_message: None,
             }
         }
@@ -182,6 +185,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             }
             EventMsg::TokenCount(ev) => {
                 self.last_total_token_usage = ev.info;
+                if ev.cost_usd.is_some() {
+                    self.last_cost_usd = ev.cost_usd;
+                }
             }
 
             EventMsg::AgentReasoningSectionBreak(_) => {
@@ -221,6 +227,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 aggregated_output,
                 duration,
                 exit_code,
+                sandbox_denials,
                 ..
             }) => {
                 let duration = format!(" in {}", format_duration(duration));
@@ -241,6 +248,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     }
                 }
                 eprintln!("{}", truncated_output.style(self.dimmed));
+                for denial in sandbox_denials {
+                    eprintln!("{}", denial.to_string().style(self.red));
+                }
             }
             EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
                 call_id: _,
@@ -417,6 +427,23 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::ApplyPatchApprovalRequest(_) => {
                 // Should we exit?
             }
+            EventMsg::McpSamplingApprovalRequest(request) => {
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "declined sampling request from".style(self.dimmed),
+                    request.server
+                );
+            }
+            EventMsg::CostBudgetApprovalRequest(request) => {
+                ts_msg!(
+                    self,
+                    "{} ${:.2} of ${:.2}",
+                    "session cost budget reached:".style(self.dimmed),
+                    request.cost_usd,
+                    request.budget_usd
+                );
+            }
             EventMsg::AgentReasoning(agent_reasoning_event) => {
                 if self.show_agent_reasoning {
                     ts_msg!(
@@ -487,9 +514,18 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::McpListToolsResponse(_) => {
                 // Currently ignored in exec output.
             }
+            EventMsg::McpListResourcesResponse(_) => {
+                // Currently ignored in exec output.
+            }
+            EventMsg::McpServersReloaded(_) => {
+                // Currently ignored in exec output.
+            }
             EventMsg::ListCustomPromptsResponse(_) => {
                 // Currently ignored in exec output.
             }
+            EventMsg::PinnedItems(_) => {
+                // Currently ignored in exec output.
+            }
             EventMsg::ViewImageToolCall(view) => {
                 ts_msg!(
                     self,
@@ -529,6 +565,12 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 format_with_separators(usage_info.total_token_usage.blended_total())
             );
         }
+        if let Some(cost_usd) = self.last_cost_usd {
+            eprintln!(
+                "{}\n${cost_usd:.2}",
+                "estimated cost".style(self.magenta).style(self.italic)
+            );
+        }
 
         // If the user has not piped the final message to a file, they will see
         // it twice: once written to stderr as part of the normal event
@@ -17,12 +17,15 @@ use codex_core::ConversationManager;
 use codex_core::NewConversation;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
+use codex_core::config_types::CassetteConfig;
+use codex_core::config_types::CassetteMode;
 use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_ollama::DEFAULT_OSS_MODEL;
@@ -69,6 +72,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         prompt,
         output_schema: output_schema_path,
         include_plan_tool,
+        record,
+        replay,
         config_overrides,
     } = cli;
 
@@ -164,6 +169,17 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     };
 
     // Load configuration and determine approval policy
+    // Resolve cassette directories against the shell's cwd rather than `--cd`.
+    let cassette = match (record, replay) {
+        (Some(dir), _) => Some((CassetteMode::Record, dir)),
+        (None, Some(dir)) => Some((CassetteMode::Replay, dir)),
+        (None, None) => None,
+    }
+    .map(|(mode, dir)| CassetteConfig {
+        mode,
+        dir: std::path::absolute(&dir).unwrap_or(dir),
+    });
+
     let overrides = ConfigOverrides {
         model,
         review_model: None,
@@ -181,6 +197,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         include_view_image_tool: None,
         show_raw_agent_reasoning: oss.then_some(true),
         tools_web_search_request: None,
+        cassette,
     };
     // Parse `-c` overrides.
     let cli_kv_overrides = match config_overrides.parse_overrides() {
@@ -360,6 +377,25 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
+        if matches!(event.msg, EventMsg::McpSamplingApprovalRequest(_)) {
+            // Nobody can answer the prompt in exec mode; servers that need
+            // sampling here must be configured with `auto_approve_sampling`.
+            conversation
+                .submit(Op::McpSamplingApproval {
+                    id: event.id.clone(),
+                    decision: ReviewDecision::Denied,
+                })
+                .await?;
+        }
+        if matches!(event.msg, EventMsg::CostBudgetApprovalRequest(_)) {
+            // There is nobody to ask for more budget, so stop the task.
+            conversation
+                .submit(Op::CostBudgetApproval {
+                    id: event.id.clone(),
+                    decision: ReviewDecision::Denied,
+                })
+                .await?;
+        }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
             CodexStatus::Running => continue,
@@ -384,8 +420,14 @@ async fn resolve_resume_path(
     args: &crate::cli::ResumeArgs,
 ) -> anyhow::Result<Option<PathBuf>> {
     if args.last {
-        match codex_core::RolloutRecorder::list_conversations(&config.codex_home, 1, None, &[])
-            .await
+        match codex_core::RolloutRecorder::list_conversations(
+            &config.codex_home,
+            1,
+            None,
+            &[],
+            &codex_core::ConversationFilter::default(),
+        )
+        .await
         {
             Ok(page) => Ok(page.items.first().map(|it| it.path.clone())),
             Err(e) => {
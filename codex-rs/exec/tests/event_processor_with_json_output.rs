@@ -535,6 +535,7 @@ fn exec_command_end_success_produces_completed_command_item() {
             exit_code: 0,
             duration: Duration::from_millis(5),
             formatted_output: String::new(),
+            sandbox_denials: Vec::new(),
         }),
     );
     let out_ok = ep.collect_thread_events(&end_ok);
@@ -594,6 +595,7 @@ fn exec_command_end_failure_produces_failed_command_item() {
             exit_code: 1,
             duration: Duration::from_millis(2),
             formatted_output: String::new(),
+            sandbox_denials: Vec::new(),
         }),
     );
     let out_fail = ep.collect_thread_events(&end_fail);
@@ -628,6 +630,7 @@ fn exec_command_end_without_begin_is_ignored() {
             exit_code: 0,
             duration: Duration::from_millis(1),
             formatted_output: String::new(),
+            sandbox_denials: Vec::new(),
         }),
     );
     let out = ep.collect_thread_events(&end_only);
@@ -792,6 +795,7 @@ fn task_complete_produces_turn_completed_with_usage() {
         EventMsg::TokenCount(codex_core::protocol::TokenCountEvent {
             info: Some(info),
             rate_limits: None,
+            cost_usd: Some(0.0125),
         }),
     );
     assert!(ep.collect_thread_events(&token_count_event).is_empty());
@@ -811,6 +815,7 @@ fn task_complete_produces_turn_completed_with_usage() {
                 input_tokens: 1200,
                 cached_input_tokens: 200,
                 output_tokens: 345,
+                cost_usd: Some(0.0125),
             },
         })]
     );
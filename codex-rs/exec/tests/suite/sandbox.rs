@@ -1,4 +1,5 @@
 #![cfg(unix)]
+use codex_core::config_types::ResourceLimits;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::spawn::StdioPolicy;
 use std::collections::HashMap;
@@ -25,6 +26,7 @@ async fn spawn_command_under_sandbox(
         command_cwd,
         sandbox_policy,
         sandbox_cwd,
+        &ResourceLimits::default(),
         stdio_policy,
         env,
     )
@@ -40,6 +42,7 @@ async fn spawn_command_under_sandbox(
     stdio_policy: StdioPolicy,
     env: HashMap<String, String>,
 ) -> std::io::Result<Child> {
+    use codex_core::landlock::LinuxSandboxOptions;
     use codex_core::landlock::spawn_command_under_linux_sandbox;
     let codex_linux_sandbox_exe = assert_cmd::cargo::cargo_bin("codex-exec");
     spawn_command_under_linux_sandbox(
@@ -48,6 +51,10 @@ async fn spawn_command_under_sandbox(
         command_cwd,
         sandbox_policy,
         sandbox_cwd,
+        None,
+        &ResourceLimits::default(),
+        LinuxSandboxOptions::default(),
+        None,
         stdio_policy,
         env,
     )
@@ -70,8 +77,11 @@ async fn python_multiprocessing_lock_works_under_sandbox() {
     let policy = SandboxPolicy::WorkspaceWrite {
         writable_roots,
         network_access: false,
+        allowed_domains: vec![],
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        readable_roots: vec![],
+        deny_read: vec![],
     };
 
     let python_code = r#"import multiprocessing
@@ -130,8 +140,11 @@ async fn sandbox_distinguishes_command_and_policy_cwds() {
     let policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![],
         network_access: false,
+        allowed_domains: vec![],
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        readable_roots: vec![],
+        deny_read: vec![],
     };
 
     // Attempt to write inside the command cwd, which is outside of the sandbox policy cwd.
@@ -269,7 +282,7 @@ fn unix_sock_body() {
 async fn allow_unix_socketpair_recvfrom() {
     run_code_under_sandbox(
         "allow_unix_socketpair_recvfrom",
-        &SandboxPolicy::ReadOnly,
+        &SandboxPolicy::new_read_only_policy(),
         || async { unix_sock_body() },
     )
     .await
@@ -14,6 +14,7 @@ mod policy;
 mod policy_parser;
 mod program;
 mod sed_command;
+mod user_policy;
 mod valid_exec;
 
 pub use arg_matcher::ArgMatcher;
@@ -32,6 +33,11 @@ pub use program::NegativeExamplePassedCheck;
 pub use program::PositiveExampleFailedCheck;
 pub use program::ProgramSpec;
 pub use sed_command::parse_sed_command;
+pub use user_policy::LoadedPolicy;
+pub use user_policy::PolicyFileIssue;
+pub use user_policy::USER_POLICY_EXTENSION;
+pub use user_policy::load_policy_with_user_dirs;
+pub use user_policy::load_user_policy_files;
 pub use valid_exec::MatchedArg;
 pub use valid_exec::MatchedFlag;
 pub use valid_exec::MatchedOpt;
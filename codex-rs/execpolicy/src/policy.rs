@@ -12,9 +12,11 @@ use crate::error::Result;
 use crate::policy_parser::ForbiddenProgramRegex;
 use crate::program::PositiveExampleFailedCheck;
 
+#[derive(Debug)]
 pub struct Policy {
     programs: MultiMap<String, ProgramSpec>,
     forbidden_program_regexes: Vec<ForbiddenProgramRegex>,
+    forbidden_substrings: Vec<String>,
     forbidden_substrings_pattern: Option<Regex>,
 }
 
@@ -37,10 +39,30 @@ impl Policy {
         Ok(Self {
             programs,
             forbidden_program_regexes,
+            forbidden_substrings,
             forbidden_substrings_pattern,
         })
     }
 
+    /// Layers `overrides` on top of this policy. Programs defined in
+    /// `overrides` replace every spec this policy has for the same program;
+    /// forbidden program regexes and substrings from both policies apply.
+    pub fn merge(self, overrides: Policy) -> std::result::Result<Self, RegexError> {
+        let Policy {
+            mut programs,
+            mut forbidden_program_regexes,
+            mut forbidden_substrings,
+            ..
+        } = self;
+        for (program, specs) in overrides.programs {
+            programs.remove(&program);
+            programs.insert_many(program, specs);
+        }
+        forbidden_program_regexes.extend(overrides.forbidden_program_regexes);
+        forbidden_substrings.extend(overrides.forbidden_substrings);
+        Policy::new(programs, forbidden_program_regexes, forbidden_substrings)
+    }
+
     pub fn check(&self, exec_call: &ExecCall) -> Result<MatchedExec> {
         let ExecCall { program, args } = &exec_call;
         for ForbiddenProgramRegex { regex, reason } in &self.forbidden_program_regexes {
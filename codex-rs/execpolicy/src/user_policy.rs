@@ -0,0 +1,134 @@
+use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use multimap::MultiMap;
+
+use crate::Policy;
+use crate::PolicyParser;
+use crate::get_default_policy;
+
+/// Extension of user policy files, e.g. `~/.codex/policy.d/kubectl.policy`.
+pub const USER_POLICY_EXTENSION: &str = "policy";
+
+/// A policy with every user policy file layered on top.
+#[derive(Debug)]
+pub struct LoadedPolicy {
+    pub policy: Policy,
+    /// User policy files that were merged into `policy`, in load order.
+    pub user_files: Vec<PathBuf>,
+    /// Problems found in user policy files. Files with problems are skipped.
+    pub issues: Vec<PolicyFileIssue>,
+}
+
+/// A user policy file that could not be parsed or whose `should_match` /
+/// `should_not_match` examples disagree with its own rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyFileIssue {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for PolicyFileIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+/// Loads the default policy and merges every `*.policy` file found in `dirs`
+/// on top of it. Directories are processed in order and files within a
+/// directory are processed by name, so later files override earlier ones when
+/// they define the same program. Missing directories are ignored.
+///
+/// Each user file must pass its own `should_match` / `should_not_match`
+/// examples to be merged; failures are reported in [`LoadedPolicy::issues`].
+pub fn load_policy_with_user_dirs(dirs: &[PathBuf]) -> anyhow::Result<LoadedPolicy> {
+    let policy = get_default_policy().map_err(|e| anyhow::anyhow!("{e}"))?;
+    merge_policy_files(policy, dirs)
+}
+
+/// Like [`load_policy_with_user_dirs`], but without the default policy: only
+/// the programs that the user files define are known. For callers that must
+/// not trust the default policy's programs, such as `printenv`, on the user's
+/// behalf.
+pub fn load_user_policy_files(dirs: &[PathBuf]) -> anyhow::Result<LoadedPolicy> {
+    let policy = Policy::new(MultiMap::new(), Vec::new(), Vec::new())?;
+    merge_policy_files(policy, dirs)
+}
+
+fn merge_policy_files(mut policy: Policy, dirs: &[PathBuf]) -> anyhow::Result<LoadedPolicy> {
+    let mut user_files = Vec::new();
+    let mut issues = Vec::new();
+
+    for path in dirs.iter().flat_map(|dir| policy_files_in(dir)) {
+        match parse_user_policy(&path) {
+            Ok(user_policy) => {
+                policy = policy.merge(user_policy)?;
+                user_files.push(path);
+            }
+            Err(messages) => issues.extend(messages.into_iter().map(|message| PolicyFileIssue {
+                path: path.clone(),
+                message,
+            })),
+        }
+    }
+
+    Ok(LoadedPolicy {
+        policy,
+        user_files,
+        issues,
+    })
+}
+
+fn policy_files_in(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext == USER_POLICY_EXTENSION)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn parse_user_policy(path: &Path) -> Result<Policy, Vec<String>> {
+    let source = std::fs::read_to_string(path).map_err(|e| vec![format!("failed to read: {e}")])?;
+    let policy = PolicyParser::new(&path.to_string_lossy(), &source)
+        .parse()
+        .map_err(|e| vec![format!("failed to parse: {e}")])?;
+
+    let mut failures = Vec::new();
+    for violation in policy.check_each_good_list_individually() {
+        failures.push(format!(
+            "should_match example `{}` was rejected: {:?}",
+            render_argv(&violation.program, &violation.args),
+            violation.error
+        ));
+    }
+    for violation in policy.check_each_bad_list_individually() {
+        failures.push(format!(
+            "should_not_match example `{}` was allowed",
+            render_argv(&violation.program, &violation.args)
+        ));
+    }
+
+    if failures.is_empty() {
+        Ok(policy)
+    } else {
+        Err(failures)
+    }
+}
+
+fn render_argv(program: &str, args: &[String]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -8,3 +8,4 @@ mod ls;
 mod parse_sed_command;
 mod pwd;
 mod sed;
+mod user_policy;
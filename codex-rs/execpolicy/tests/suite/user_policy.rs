@@ -0,0 +1,182 @@
+extern crate codex_execpolicy;
+
+use std::path::Path;
+
+use codex_execpolicy::Error;
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::load_policy_with_user_dirs;
+use codex_execpolicy::load_user_policy_files;
+use tempfile::TempDir;
+
+#[expect(clippy::expect_used)]
+fn write_policy(dir: &Path, name: &str, contents: &str) {
+    std::fs::create_dir_all(dir).expect("create policy dir");
+    std::fs::write(dir.join(name), contents).expect("write policy file");
+}
+
+#[test]
+fn user_files_extend_the_default_policy() {
+    let home = TempDir::new().expect("tempdir");
+    write_policy(
+        home.path(),
+        "kubectl.policy",
+        r#"
+define_program(
+    program="kubectl",
+    args=["get", ARG_OPAQUE_VALUE],
+    should_match=[["get", "pods"]],
+    should_not_match=[["delete", "pods"]],
+)
+"#,
+    );
+
+    let loaded =
+        load_policy_with_user_dirs(&[home.path().to_path_buf()]).expect("load user policy");
+    assert_eq!(
+        Vec::<codex_execpolicy::PolicyFileIssue>::new(),
+        loaded.issues
+    );
+    assert_eq!(vec![home.path().join("kubectl.policy")], loaded.user_files);
+
+    assert!(matches!(
+        loaded
+            .policy
+            .check(&ExecCall::new("kubectl", &["get", "pods"])),
+        Ok(MatchedExec::Match { .. })
+    ));
+    // Programs from the default policy are still known.
+    assert!(matches!(
+        loaded.policy.check(&ExecCall::new("pwd", &[])),
+        Ok(MatchedExec::Match { .. })
+    ));
+}
+
+#[test]
+fn user_files_alone_do_not_know_the_default_programs() {
+    let home = TempDir::new().expect("tempdir");
+    write_policy(
+        home.path(),
+        "kubectl.policy",
+        r#"
+define_program(
+    program="kubectl",
+    args=["get", ARG_OPAQUE_VALUE],
+    should_match=[["get", "pods"]],
+)
+"#,
+    );
+
+    let loaded = load_user_policy_files(&[home.path().to_path_buf()]).expect("load user policy");
+    assert_eq!(vec![home.path().join("kubectl.policy")], loaded.user_files);
+
+    assert!(matches!(
+        loaded
+            .policy
+            .check(&ExecCall::new("kubectl", &["get", "pods"])),
+        Ok(MatchedExec::Match { .. })
+    ));
+    assert_eq!(
+        Err(Error::NoSpecForProgram {
+            program: "printenv".into(),
+        }),
+        loaded
+            .policy
+            .check(&ExecCall::new("printenv", &["AWS_SECRET_ACCESS_KEY"]))
+    );
+}
+
+#[test]
+fn later_directories_override_programs_from_earlier_ones() {
+    let home = TempDir::new().expect("tempdir");
+    let repo = TempDir::new().expect("tempdir");
+    write_policy(
+        repo.path(),
+        "pwd.policy",
+        r#"
+define_program(
+    program="pwd",
+    forbidden="pwd is not allowed in this repo",
+)
+"#,
+    );
+
+    let loaded = load_policy_with_user_dirs(&[
+        home.path().join("does-not-exist"),
+        repo.path().to_path_buf(),
+    ])
+    .expect("load user policy");
+
+    assert!(matches!(
+        loaded.policy.check(&ExecCall::new("pwd", &[])),
+        Ok(MatchedExec::Forbidden { .. })
+    ));
+    // Flags from the replaced default spec are no longer accepted.
+    assert_eq!(
+        Err(Error::UnknownOption {
+            program: "pwd".into(),
+            option: "-P".into(),
+        }),
+        loaded.policy.check(&ExecCall::new("pwd", &["-P"]))
+    );
+}
+
+#[test]
+fn files_failing_their_self_tests_are_reported_and_skipped() {
+    let home = TempDir::new().expect("tempdir");
+    write_policy(
+        home.path(),
+        "bazel.policy",
+        r#"
+define_program(
+    program="bazel",
+    args=["query", ARG_UNVERIFIED_VARARGS],
+    should_match=[["build", "//..."]],
+    should_not_match=[["query", "deps(//...)"]],
+)
+"#,
+    );
+    write_policy(home.path(), "broken.policy", "define_program(");
+    write_policy(home.path(), "notes.txt", "not a policy");
+
+    let loaded =
+        load_policy_with_user_dirs(&[home.path().to_path_buf()]).expect("load user policy");
+
+    assert_eq!(Vec::<std::path::PathBuf>::new(), loaded.user_files);
+    let messages: Vec<(String, String)> = loaded
+        .issues
+        .iter()
+        .map(|issue| {
+            (
+                issue
+                    .path
+                    .file_name()
+                    .expect("file name")
+                    .to_string_lossy()
+                    .to_string(),
+                issue.message.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(3, messages.len(), "unexpected issues: {messages:?}");
+    assert_eq!("bazel.policy", messages[0].0);
+    assert!(
+        messages[0]
+            .1
+            .starts_with("should_match example `bazel build //...`")
+    );
+    assert_eq!(
+        "should_not_match example `bazel query deps(//...)` was allowed",
+        messages[1].1
+    );
+    assert_eq!("broken.policy", messages[2].0);
+    assert!(messages[2].1.starts_with("failed to parse"));
+    assert_eq!(
+        Err(Error::NoSpecForProgram {
+            program: "bazel".into()
+        }),
+        loaded
+            .policy
+            .check(&ExecCall::new("bazel", &["query", "//..."]))
+    );
+}
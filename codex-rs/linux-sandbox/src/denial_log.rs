@@ -0,0 +1,609 @@
+use std::ffi::CStr;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::FileExt;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use codex_core::landlock::SANDBOX_DENIAL_MARKER;
+use codex_core::protocol::SandboxDenial;
+use codex_core::protocol::SandboxDeniedOperation;
+use codex_core::protocol::SandboxPolicy;
+
+use crate::proxy_bridge::wait_for_child;
+use crate::report::report;
+
+/// At most this many denials are reported per command, so that a command
+/// that keeps retrying does not flood its output.
+const MAX_REPORTED_DENIALS: usize = 20;
+
+// From `linux/seccomp.h`, `linux/filter.h` and `linux/audit.h`, which the
+// libc crate does not fully cover. The ioctl request type differs between
+// glibc and musl, hence the `as _` where they are used.
+const SECCOMP_SET_MODE_FILTER: libc::c_ulong = 1;
+const SECCOMP_FILTER_FLAG_NEW_LISTENER: libc::c_ulong = 1 << 3;
+const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+const SECCOMP_RET_USER_NOTIF: u32 = 0x7fc0_0000;
+const SECCOMP_USER_NOTIF_FLAG_CONTINUE: u32 = 1;
+const SECCOMP_IOCTL_NOTIF_RECV: libc::c_ulong = 0xc050_2100;
+const SECCOMP_IOCTL_NOTIF_SEND: libc::c_ulong = 0xc018_2101;
+const SECCOMP_IOCTL_NOTIF_ID_VALID: libc::c_ulong = 0x4008_2102;
+const BPF_LD_W_ABS: u16 = 0x20;
+const BPF_JMP_JEQ_K: u16 = 0x15;
+const BPF_JMP_JSET_K: u16 = 0x45;
+const BPF_RET_K: u16 = 0x06;
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xc000_003e;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xc000_00b7;
+
+/// `open(2)` flags that ask for write access.
+const OPEN_WRITE_FLAGS: u32 =
+    (libc::O_WRONLY | libc::O_RDWR | libc::O_TRUNC | libc::O_CREAT) as u32;
+
+// Layouts of the kernel structures, whose fields are not all read.
+#[repr(C)]
+#[allow(dead_code)]
+struct SeccompData {
+    nr: i32,
+    arch: u32,
+    instruction_pointer: u64,
+    args: [u64; 6],
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct SeccompNotif {
+    id: u64,
+    pid: u32,
+    flags: u32,
+    data: SeccompData,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct SeccompNotifResp {
+    id: u64,
+    val: i64,
+    error: i32,
+    flags: u32,
+}
+
+/// How a system call uses one of its path arguments.
+#[derive(Clone, Copy)]
+enum PathUse {
+    /// Opens the path with the flags in the given argument.
+    Open { flags_arg: usize },
+    /// Creates the path, which must not exist yet.
+    Creates,
+    /// Changes or removes the existing path.
+    Modifies,
+    /// Creates the path or replaces what is there.
+    Replaces,
+}
+
+/// A path argument of a system call, resolved against the directory file
+/// descriptor in `dirfd`, or the working directory when there is none.
+#[derive(Clone, Copy)]
+struct PathArg {
+    dirfd: Option<usize>,
+    path: usize,
+    path_use: PathUse,
+}
+
+const fn path_arg(dirfd: Option<usize>, path: usize, path_use: PathUse) -> PathArg {
+    PathArg {
+        dirfd,
+        path,
+        path_use,
+    }
+}
+
+/// The file-system calls that write, with the path arguments they write to.
+fn write_syscalls() -> Vec<(libc::c_long, Vec<PathArg>)> {
+    #[cfg_attr(not(target_arch = "x86_64"), allow(unused_mut))]
+    let mut syscalls = vec![
+        (
+            libc::SYS_openat,
+            vec![path_arg(Some(0), 1, PathUse::Open { flags_arg: 2 })],
+        ),
+        (
+            libc::SYS_mkdirat,
+            vec![path_arg(Some(0), 1, PathUse::Creates)],
+        ),
+        (
+            libc::SYS_mknodat,
+            vec![path_arg(Some(0), 1, PathUse::Creates)],
+        ),
+        (
+            libc::SYS_unlinkat,
+            vec![path_arg(Some(0), 1, PathUse::Modifies)],
+        ),
+        (
+            libc::SYS_renameat2,
+            vec![
+                path_arg(Some(0), 1, PathUse::Modifies),
+                path_arg(Some(2), 3, PathUse::Replaces),
+            ],
+        ),
+        (
+            libc::SYS_linkat,
+            vec![path_arg(Some(2), 3, PathUse::Creates)],
+        ),
+        (
+            libc::SYS_symlinkat,
+            vec![path_arg(Some(1), 2, PathUse::Creates)],
+        ),
+        (
+            libc::SYS_truncate,
+            vec![path_arg(None, 0, PathUse::Modifies)],
+        ),
+    ];
+    #[cfg(target_arch = "x86_64")]
+    syscalls.extend([
+        (
+            libc::SYS_open,
+            vec![path_arg(None, 0, PathUse::Open { flags_arg: 1 })],
+        ),
+        (libc::SYS_creat, vec![path_arg(None, 0, PathUse::Replaces)]),
+        (libc::SYS_mkdir, vec![path_arg(None, 0, PathUse::Creates)]),
+        (libc::SYS_mknod, vec![path_arg(None, 0, PathUse::Creates)]),
+        (libc::SYS_unlink, vec![path_arg(None, 0, PathUse::Modifies)]),
+        (libc::SYS_rmdir, vec![path_arg(None, 0, PathUse::Modifies)]),
+        (
+            libc::SYS_rename,
+            vec![
+                path_arg(None, 0, PathUse::Modifies),
+                path_arg(None, 1, PathUse::Replaces),
+            ],
+        ),
+        (
+            libc::SYS_renameat,
+            vec![
+                path_arg(Some(0), 1, PathUse::Modifies),
+                path_arg(Some(2), 3, PathUse::Replaces),
+            ],
+        ),
+        (libc::SYS_link, vec![path_arg(None, 1, PathUse::Creates)]),
+        (libc::SYS_symlink, vec![path_arg(None, 1, PathUse::Creates)]),
+    ]);
+    syscalls
+}
+
+/// What the supervisor watches for.
+struct Supervision {
+    /// Writable roots and their read-only subpaths, when writes are limited.
+    writable_roots: Option<Vec<(PathBuf, Vec<PathBuf>)>>,
+    /// Whether `socket(2)` calls for anything but Unix sockets are refused
+    /// by the supervisor rather than by the network seccomp filter.
+    sockets: bool,
+}
+
+/// Lets the command report what the sandbox blocked. Forks: the child
+/// installs a seccomp filter that hands its writes outside the writable
+/// roots (and, when `supervise_sockets` is set, its attempts to open a
+/// network socket) to the parent, and returns. The parent stays behind to
+/// note them, waits for the child, reports each one after
+/// [`SANDBOX_DENIAL_MARKER`] and exits with the child's status.
+///
+/// Writes are let through to Landlock, which still decides about them; the
+/// supervisor only tells which of them Landlock refuses. Sockets are refused
+/// by the supervisor itself, or fail with `ENOSYS` once it is gone.
+///
+/// Returns whether network sockets are now refused by the supervisor, in
+/// which case the network seccomp filter must leave `socket(2)` alone, since
+/// its `EPERM` would take precedence over the notification.
+///
+/// Must be called while the process is still single-threaded.
+///
+/// # Errors
+/// Returns an error, with nothing installed, when the kernel cannot hand
+/// system calls to a supervisor (Linux 5.6 or later is needed).
+pub(crate) fn report_sandbox_denials(
+    sandbox_policy: &SandboxPolicy,
+    cwd: &Path,
+    supervise_sockets: bool,
+) -> io::Result<bool> {
+    let writable_roots = (!sandbox_policy.has_full_disk_write_access()).then(|| {
+        sandbox_policy
+            .get_writable_roots_with_cwd(cwd)
+            .into_iter()
+            .map(|writable_root| {
+                (
+                    canonicalize_lossy(&writable_root.root),
+                    writable_root
+                        .read_only_subpaths
+                        .iter()
+                        .map(|path| canonicalize_lossy(path))
+                        .collect(),
+                )
+            })
+            .collect()
+    });
+    if writable_roots.is_none() && !supervise_sockets {
+        return Ok(false);
+    }
+    if !kernel_supports_supervision() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "seccomp notifications need Linux 5.6 or later",
+        ));
+    }
+    let supervision = Supervision {
+        writable_roots,
+        sockets: supervise_sockets,
+    };
+    let filter = build_filter(&supervision);
+
+    let (mut parent_end, mut child_end) = UnixStream::pair()?;
+    let parent = unsafe { libc::getpid() };
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => {
+            drop(parent_end);
+            // Codex kills the parent on timeout; take the command with it.
+            if unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) } == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            if unsafe { libc::getppid() } != parent {
+                std::process::exit(1);
+            }
+            // `pidfd_getfd(2)` only works on dumpable processes. The command
+            // we exec next cannot reach the report channel anyway.
+            if unsafe { libc::prctl(libc::PR_SET_DUMPABLE, 1) } == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            let listener = install_filter(&filter)?;
+            // Until the parent holds the listener, closing ours would make
+            // every watched call fail.
+            let mut ack = [0u8; 1];
+            if child_end
+                .write_all(&listener.as_raw_fd().to_ne_bytes())
+                .and_then(|()| child_end.read_exact(&mut ack))
+                .is_err()
+            {
+                eprintln!("codex-linux-sandbox: the sandbox denial supervisor did not start");
+                std::process::exit(1);
+            }
+            Ok(supervision.sockets)
+        }
+        child => {
+            drop(child_end);
+            let denials = Arc::new(Mutex::new(Vec::new()));
+            if let Ok(listener) = take_listener(child, &mut parent_end) {
+                let _ = parent_end.write_all(&[1]);
+                let denials = Arc::clone(&denials);
+                std::thread::spawn(move || handle_notifications(listener, &supervision, &denials));
+            }
+            let status = wait_for_child(child);
+            if let Ok(denials) = denials.lock() {
+                for denial in denials.iter() {
+                    report(&format!(
+                        "{SANDBOX_DENIAL_MARKER}{} {}",
+                        denial.operation, denial.target
+                    ));
+                }
+            }
+            std::process::exit(status);
+        }
+    }
+}
+
+/// `SECCOMP_USER_NOTIF_FLAG_CONTINUE` needs Linux 5.5 and `pidfd_getfd(2)`
+/// Linux 5.6.
+fn kernel_supports_supervision() -> bool {
+    let mut uts: libc::utsname = unsafe { std::mem::zeroed() };
+    if unsafe { libc::uname(&mut uts) } == -1 {
+        return false;
+    }
+    let release = unsafe { CStr::from_ptr(uts.release.as_ptr()) }.to_string_lossy();
+    let mut parts = release
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|part| part.parse::<u32>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor) >= (5, 6)
+}
+
+fn build_filter(supervision: &Supervision) -> Vec<libc::sock_filter> {
+    let stmt = |code, k| libc::sock_filter {
+        code,
+        jt: 0,
+        jf: 0,
+        k,
+    };
+    let jump = |code, k, jt, jf| libc::sock_filter { code, jt, jf, k };
+    let load_nr = stmt(BPF_LD_W_ABS, 0);
+    // The low half of an argument, on little-endian architectures.
+    let load_arg = |arg: usize| stmt(BPF_LD_W_ABS, 16 + 8 * arg as u32);
+    let notify = stmt(BPF_RET_K, SECCOMP_RET_USER_NOTIF);
+    let allow = stmt(BPF_RET_K, SECCOMP_RET_ALLOW);
+
+    let mut filter = vec![
+        stmt(BPF_LD_W_ABS, 4),
+        jump(BPF_JMP_JEQ_K, AUDIT_ARCH, 1, 0),
+        allow,
+    ];
+    if supervision.writable_roots.is_some() {
+        for (nr, path_args) in write_syscalls() {
+            let open_flags = path_args.iter().find_map(|arg| match arg.path_use {
+                PathUse::Open { flags_arg } => Some(flags_arg),
+                _ => None,
+            });
+            filter.push(load_nr);
+            match open_flags {
+                // Read-only opens are not worth a round trip.
+                Some(flags_arg) => filter.extend([
+                    jump(BPF_JMP_JEQ_K, nr as u32, 0, 3),
+                    load_arg(flags_arg),
+                    jump(BPF_JMP_JSET_K, OPEN_WRITE_FLAGS, 0, 1),
+                    notify,
+                ]),
+                None => filter.extend([jump(BPF_JMP_JEQ_K, nr as u32, 0, 1), notify]),
+            }
+        }
+    }
+    if supervision.sockets {
+        filter.extend([
+            load_nr,
+            jump(BPF_JMP_JEQ_K, libc::SYS_socket as u32, 0, 3),
+            load_arg(0),
+            jump(BPF_JMP_JEQ_K, libc::AF_UNIX as u32, 1, 0),
+            notify,
+        ]);
+    }
+    filter.push(allow);
+    filter
+}
+
+/// Installs `filter` on this process and returns the file descriptor on
+/// which its notifications arrive.
+fn install_filter(filter: &[libc::sock_filter]) -> io::Result<OwnedFd> {
+    if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let program = libc::sock_fprog {
+        len: filter.len() as u16,
+        filter: filter.as_ptr().cast_mut(),
+    };
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_seccomp,
+            SECCOMP_SET_MODE_FILTER,
+            SECCOMP_FILTER_FLAG_NEW_LISTENER,
+            &program as *const libc::sock_fprog,
+        )
+    };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) })
+}
+
+/// Copies the listener, whose number the child sent over `stream`, out of
+/// the child.
+fn take_listener(child: libc::pid_t, stream: &mut UnixStream) -> io::Result<OwnedFd> {
+    let mut fd_bytes = [0u8; 4];
+    stream.read_exact(&mut fd_bytes)?;
+    let child_fd = libc::c_int::from_ne_bytes(fd_bytes);
+
+    let pidfd = unsafe { libc::syscall(libc::SYS_pidfd_open, child, 0) };
+    if pidfd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let pidfd = unsafe { OwnedFd::from_raw_fd(pidfd as libc::c_int) };
+    let fd = unsafe { libc::syscall(libc::SYS_pidfd_getfd, pidfd.as_raw_fd(), child_fd, 0) };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) })
+}
+
+fn handle_notifications(
+    listener: OwnedFd,
+    supervision: &Supervision,
+    denials: &Mutex<Vec<SandboxDenial>>,
+) {
+    let write_syscalls = write_syscalls();
+    loop {
+        let mut notif: SeccompNotif = unsafe { std::mem::zeroed() };
+        let notif_ptr: *mut SeccompNotif = &mut notif;
+        if unsafe {
+            libc::ioctl(
+                listener.as_raw_fd(),
+                SECCOMP_IOCTL_NOTIF_RECV as _,
+                notif_ptr,
+            )
+        } == -1
+        {
+            match io::Error::last_os_error().raw_os_error() {
+                // Interrupted, or the caller died before we got to it.
+                Some(libc::EINTR | libc::ENOENT) => continue,
+                _ => return,
+            }
+        }
+
+        let mut response = SeccompNotifResp {
+            id: notif.id,
+            val: 0,
+            error: 0,
+            flags: SECCOMP_USER_NOTIF_FLAG_CONTINUE,
+        };
+        let denial = if i64::from(notif.data.nr) == libc::SYS_socket {
+            response.flags = 0;
+            response.error = -libc::EPERM;
+            Some(SandboxDenial {
+                operation: SandboxDeniedOperation::Network,
+                target: socket_family_name(notif.data.args[0] as libc::c_int),
+            })
+        } else {
+            write_syscalls
+                .iter()
+                .find(|(nr, _)| *nr == i64::from(notif.data.nr))
+                .and_then(|(_, path_args)| denied_write(&listener, &notif, path_args, supervision))
+        };
+        if let Some(denial) = denial
+            && let Ok(mut denials) = denials.lock()
+            && denials.len() < MAX_REPORTED_DENIALS
+            && !denials.contains(&denial)
+        {
+            denials.push(denial);
+        }
+
+        let response_ptr: *mut SeccompNotifResp = &mut response;
+        unsafe {
+            libc::ioctl(
+                listener.as_raw_fd(),
+                SECCOMP_IOCTL_NOTIF_SEND as _,
+                response_ptr,
+            )
+        };
+    }
+}
+
+/// The first path argument of the notified call that Landlock will refuse
+/// to write to.
+fn denied_write(
+    listener: &OwnedFd,
+    notif: &SeccompNotif,
+    path_args: &[PathArg],
+    supervision: &Supervision,
+) -> Option<SandboxDenial> {
+    let writable_roots = supervision.writable_roots.as_ref()?;
+    let mem = File::open(format!("/proc/{}/mem", notif.pid)).ok()?;
+    for arg in path_args {
+        let Some(path) = read_path(&mem, notif, arg) else {
+            continue;
+        };
+        // The process may have died and its PID been reused while we read
+        // its memory.
+        let mut id = notif.id;
+        let id_ptr: *mut u64 = &mut id;
+        if unsafe {
+            libc::ioctl(
+                listener.as_raw_fd(),
+                SECCOMP_IOCTL_NOTIF_ID_VALID as _,
+                id_ptr,
+            )
+        } == -1
+        {
+            return None;
+        }
+        if reaches_permission_check(&path, arg.path_use, notif)
+            && !is_writable(&path, writable_roots)
+        {
+            return Some(SandboxDenial {
+                operation: SandboxDeniedOperation::Write,
+                target: path.display().to_string(),
+            });
+        }
+    }
+    None
+}
+
+/// Reads a path argument out of the memory of the notifying process and
+/// makes it absolute.
+fn read_path(mem: &File, notif: &SeccompNotif, arg: &PathArg) -> Option<PathBuf> {
+    let path = read_c_string(mem, notif.data.args[arg.path])?;
+    let path = PathBuf::from(std::ffi::OsStr::from_bytes(&path));
+    if path.is_absolute() {
+        return Some(resolve_parent(&path));
+    }
+    let dirfd = arg
+        .dirfd
+        .map(|dirfd| notif.data.args[dirfd] as libc::c_int)
+        .unwrap_or(libc::AT_FDCWD);
+    let base = if dirfd == libc::AT_FDCWD {
+        std::fs::read_link(format!("/proc/{}/cwd", notif.pid)).ok()?
+    } else {
+        std::fs::read_link(format!("/proc/{}/fd/{dirfd}", notif.pid)).ok()?
+    };
+    Some(resolve_parent(&base.join(path)))
+}
+
+fn read_c_string(mem: &File, address: u64) -> Option<Vec<u8>> {
+    const PAGE_SIZE: u64 = 4096;
+    let mut bytes = Vec::new();
+    let mut address = address;
+    while bytes.len() < libc::PATH_MAX as usize {
+        // Reads stop at page boundaries, since the next page may be unmapped.
+        let mut chunk = vec![0u8; (PAGE_SIZE - address % PAGE_SIZE) as usize];
+        let read = mem.read_at(&mut chunk, address).ok()?;
+        if read == 0 {
+            return None;
+        }
+        if let Some(end) = chunk[..read].iter().position(|&byte| byte == 0) {
+            bytes.extend_from_slice(&chunk[..end]);
+            return Some(bytes);
+        }
+        bytes.extend_from_slice(&chunk[..read]);
+        address += read as u64;
+    }
+    None
+}
+
+/// Whether the call gets as far as asking Landlock, rather than failing
+/// because the path already exists or does not.
+fn reaches_permission_check(path: &Path, path_use: PathUse, notif: &SeccompNotif) -> bool {
+    let exists = path.symlink_metadata().is_ok();
+    let parent_exists = path.parent().is_some_and(Path::is_dir);
+    match path_use {
+        PathUse::Open { flags_arg } => {
+            let flags = notif.data.args[flags_arg] as libc::c_int;
+            if exists {
+                flags & (libc::O_WRONLY | libc::O_RDWR | libc::O_TRUNC) != 0
+            } else {
+                flags & libc::O_CREAT != 0 && parent_exists
+            }
+        }
+        PathUse::Creates => !exists && parent_exists,
+        PathUse::Modifies => exists,
+        PathUse::Replaces => parent_exists,
+    }
+}
+
+/// Mirrors the Landlock rules the sandbox installs for writes. Devices and
+/// `/proc` count as writable: programs routinely probe `/dev/tty` and the
+/// like, and no writable root would help with those.
+fn is_writable(path: &Path, writable_roots: &[(PathBuf, Vec<PathBuf>)]) -> bool {
+    path.starts_with("/dev")
+        || path.starts_with("/proc")
+        || writable_roots.iter().any(|(root, read_only_subpaths)| {
+            path.starts_with(root)
+                && !read_only_subpaths
+                    .iter()
+                    .any(|read_only| path.starts_with(read_only))
+        })
+}
+
+/// Resolves the symlinks and `..` of the folder containing `path`, which
+/// itself may not exist yet.
+fn resolve_parent(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => canonicalize_lossy(parent).join(name),
+        _ => canonicalize_lossy(path),
+    }
+}
+
+fn canonicalize_lossy(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn socket_family_name(family: libc::c_int) -> String {
+    match family {
+        libc::AF_INET => "AF_INET socket".to_string(),
+        libc::AF_INET6 => "AF_INET6 socket".to_string(),
+        libc::AF_NETLINK => "AF_NETLINK socket".to_string(),
+        libc::AF_PACKET => "AF_PACKET socket".to_string(),
+        other => format!("socket of address family {other}"),
+    }
+}
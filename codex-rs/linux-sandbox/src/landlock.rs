@@ -1,10 +1,13 @@
 use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::path::Path;
 use std::path::PathBuf;
 
 use codex_core::error::CodexErr;
 use codex_core::error::Result;
 use codex_core::error::SandboxErr;
+use codex_core::landlock::DENY_READ_SEARCH_INCOMPLETE_MARKER;
 use codex_core::protocol::SandboxPolicy;
 
 use landlock::ABI;
@@ -25,14 +28,35 @@ use seccompiler::SeccompRule;
 use seccompiler::TargetArch;
 use seccompiler::apply_filter;
 
+use crate::report::report;
+use crate::report::report_channel_open;
+
 /// Apply sandbox policies inside this thread so only the child inherits
 /// them, not the entire CLI process.
+///
+/// `network_namespaced` is `true` when the process already runs in a network
+/// namespace whose only way out is the Codex network proxy. TCP sockets are
+/// then allowed, but Unix sockets are not, so that the command cannot reach
+/// services on the host through their sockets.
+///
+/// `deny_read` holds the paths from [`resolve_deny_read_paths`] that Landlock
+/// still has to deny, once mounts hide the others.
+///
+/// `sockets_supervised` is `true` when a seccomp supervisor already refuses
+/// network sockets in order to report them, which this filter would hide.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    deny_read: Vec<PathBuf>,
+    network_namespaced: bool,
+    sockets_supervised: bool,
 ) -> Result<()> {
     if !sandbox_policy.has_full_network_access() {
-        install_network_seccomp_filter_on_current_thread()?;
+        if network_namespaced {
+            install_namespaced_network_seccomp_filter_on_current_thread()?;
+        } else {
+            install_network_seccomp_filter_on_current_thread(sockets_supervised)?;
+        }
     }
 
     if !sandbox_policy.has_full_disk_write_access() {
@@ -41,38 +65,213 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
             .into_iter()
             .map(|writable_root| writable_root.root)
             .collect();
-        install_filesystem_landlock_rules_on_current_thread(writable_roots)?;
+        let readable_roots = sandbox_policy.get_readable_roots_with_cwd(cwd);
+        install_filesystem_landlock_rules_on_current_thread(
+            writable_roots,
+            readable_roots,
+            deny_read,
+        )?;
     }
 
-    // TODO(ragona): Add appropriate restrictions if
-    // `sandbox_policy.has_full_disk_read_access()` is `false`.
-
     Ok(())
 }
 
-/// Installs Landlock file-system rules on the current thread allowing read
-/// access to the entire file-system while restricting write access to
-/// `/dev/null` and the provided list of `writable_roots`.
+/// Upper bound on the folders searched for `deny_read` names, so that a huge
+/// workspace does not delay every command.
+const MAX_DENY_READ_NAME_SCAN_DIRS: usize = 10_000;
+
+/// Resolves `deny_read` to the paths to deny. Denied paths keep their place
+/// even when they do not exist yet: their existing ancestors are
+/// canonicalized and the missing rest is appended. Names are matched against
+/// the files and folders that exist in the cwd and the writable roots; when
+/// there are too many folders to search, the ones that were left out are
+/// reported with [`DENY_READ_SEARCH_INCOMPLETE_MARKER`].
+pub(crate) fn resolve_deny_read_paths(sandbox_policy: &SandboxPolicy, cwd: &Path) -> Vec<PathBuf> {
+    let mut deny_read: Vec<PathBuf> = sandbox_policy
+        .get_deny_read_paths_with_cwd(cwd)
+        .iter()
+        .map(|path| canonicalize_existing_ancestor(path))
+        .collect();
+    let names = sandbox_policy.get_deny_read_names();
+    if !names.is_empty() {
+        let mut roots = vec![cwd.to_path_buf()];
+        roots.extend(
+            sandbox_policy
+                .get_writable_roots_with_cwd(cwd)
+                .into_iter()
+                .map(|writable_root| writable_root.root),
+        );
+        let (named, complete) = find_named_paths(&roots, &names);
+        deny_read.extend(named);
+        if !complete {
+            let line =
+                format!("{DENY_READ_SEARCH_INCOMPLETE_MARKER}{MAX_DENY_READ_NAME_SCAN_DIRS}");
+            if report_channel_open() {
+                report(&line);
+            } else {
+                eprintln!("{line}");
+            }
+        }
+    }
+    deny_read
+}
+
+/// Canonicalizes the deepest existing ancestor of `path` and appends the
+/// components below it.
+fn canonicalize_existing_ancestor(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |resolved, name| resolved.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// Finds the files and folders called one of `names` beneath `roots`,
+/// without following symlinks into other folders. A symlink with a denied
+/// name is resolved, so that its target is denied as well. Also returns
+/// whether every folder was searched, rather than only the first
+/// [`MAX_DENY_READ_NAME_SCAN_DIRS`].
+fn find_named_paths(roots: &[PathBuf], names: &[String]) -> (Vec<PathBuf>, bool) {
+    let mut found = Vec::new();
+    let mut scanned = HashSet::new();
+    let mut queue: VecDeque<PathBuf> = roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .collect();
+    while let Some(dir) = queue.pop_front() {
+        if scanned.contains(&dir) {
+            continue;
+        }
+        if scanned.len() >= MAX_DENY_READ_NAME_SCAN_DIRS {
+            return (found, false);
+        }
+        scanned.insert(dir.clone());
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if names.iter().any(|name| entry.file_name() == name.as_str()) {
+                if let Ok(target) = path.canonicalize()
+                    && target != path
+                {
+                    found.push(target);
+                }
+                found.push(path);
+            } else if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                queue.push_back(path);
+            }
+        }
+    }
+    (found, true)
+}
+
+/// Folders every program needs in order to run at all. They stay readable
+/// when `readable_roots` limits reads to a few folders.
+const SYSTEM_READABLE_ROOTS: &[&str] = &[
+    "/bin", "/sbin", "/usr", "/lib", "/lib32", "/lib64", "/libx32", "/etc", "/opt", "/dev",
+    "/proc", "/sys", "/run", "/nix", "/snap",
+];
+
+/// Returns the paths in `deny_read` that lie inside one of the writable roots
+/// of `sandbox_policy`. Landlock cannot deny them: the folders that hold them
+/// would only keep the access that does not allow reading files, so that a
+/// file the command creates next to them could not be read back either.
+/// Mounts have to hide the ones that exist, and the command must not run
+/// while one of them cannot be hidden.
+pub(crate) fn deny_read_in_writable_roots(
+    sandbox_policy: &SandboxPolicy,
+    cwd: &Path,
+    deny_read: &[PathBuf],
+) -> Vec<PathBuf> {
+    if sandbox_policy.has_full_disk_write_access() {
+        return Vec::new();
+    }
+    let writable_roots: Vec<PathBuf> = sandbox_policy
+        .get_writable_roots_with_cwd(cwd)
+        .into_iter()
+        .map(|writable_root| {
+            writable_root
+                .root
+                .canonicalize()
+                .unwrap_or(writable_root.root)
+        })
+        .collect();
+    deny_read
+        .iter()
+        .filter(|denied| {
+            writable_roots
+                .iter()
+                .any(|root| denied.starts_with(root) && *denied != root)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Installs Landlock file-system rules on the current thread that restrict
+/// reads to `readable_roots` (the entire file-system when `None`) and writes
+/// to `/dev/null` and the provided list of `writable_roots`. Paths in
+/// `deny_read` cannot be read; none of them may lie inside a writable root
+/// (see [`deny_read_in_writable_roots`]).
 ///
 /// # Errors
-/// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply.
-fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathBuf>) -> Result<()> {
+/// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply or
+/// a path in `deny_read` lies inside a writable root.
+fn install_filesystem_landlock_rules_on_current_thread(
+    writable_roots: Vec<PathBuf>,
+    readable_roots: Option<Vec<PathBuf>>,
+    deny_read: Vec<PathBuf>,
+) -> Result<()> {
     let abi = ABI::V5;
     let access_rw = AccessFs::from_all(abi);
     let access_ro = AccessFs::from_read(abi);
 
-    let mut ruleset = Ruleset::default()
+    let read_roots = match readable_roots {
+        Some(mut roots) => {
+            roots.extend(
+                SYSTEM_READABLE_ROOTS
+                    .iter()
+                    .map(PathBuf::from)
+                    .filter(|root| root.exists()),
+            );
+            roots
+        }
+        None => vec![PathBuf::from("/")],
+    };
+    let read_roots = split_roots(&read_roots, &deny_read);
+    let writable_roots = split_roots(&writable_roots, &deny_read);
+    if !writable_roots.partial.is_empty() {
+        return Err(CodexErr::Sandbox(SandboxErr::LandlockRestrict));
+    }
+
+    let ruleset = Ruleset::default()
         .set_compatibility(CompatLevel::BestEffort)
         .handle_access(access_rw)?
         .create()?
-        .add_rules(landlock::path_beneath_rules(&["/"], access_ro))?
+        .add_rules(landlock::path_beneath_rules(&read_roots.full, access_ro))?
+        .add_rules(landlock::path_beneath_rules(
+            &read_roots.partial,
+            AccessFs::ReadDir,
+        ))?
         .add_rules(landlock::path_beneath_rules(&["/dev/null"], access_rw))?
+        .add_rules(landlock::path_beneath_rules(
+            &writable_roots.full,
+            access_rw,
+        ))?
         .set_no_new_privs(true);
 
-    if !writable_roots.is_empty() {
-        ruleset = ruleset.add_rules(landlock::path_beneath_rules(&writable_roots, access_rw))?;
-    }
-
     let status = ruleset.restrict_self()?;
 
     if status.ruleset == landlock::RulesetStatus::NotEnforced {
@@ -82,9 +281,77 @@ fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathB
     Ok(())
 }
 
+/// Roots split up around the paths in `deny_read`.
+#[derive(Debug, Default)]
+struct SplitRoots {
+    /// Folders and files that get the full access of their root.
+    full: Vec<PathBuf>,
+    /// Ancestors of denied paths. They can only be listed, since Landlock
+    /// grants access to everything beneath a rule and cannot take it away
+    /// again.
+    partial: Vec<PathBuf>,
+}
+
+/// Splits `roots` so that no rule covers a path in `deny_read`: every
+/// ancestor of a denied path inside a root becomes a partial rule, and each
+/// of its other children gets a full rule of its own.
+///
+/// The names of the entries inside a denied folder stay visible. Entries that
+/// appear in a partial folder after the split are covered by the partial rule
+/// only, so they cannot be read. This is why a partial folder must not be
+/// writable: the command could not read back the files it creates there.
+///
+/// `deny_read` must come from [`resolve_deny_read_paths`], so that a denied
+/// path that does not exist yet still turns its ancestors into partial rules
+/// and cannot be read once it appears.
+fn split_roots(roots: &[PathBuf], deny_read: &[PathBuf]) -> SplitRoots {
+    let mut split = SplitRoots::default();
+    for root in roots {
+        let root = root.canonicalize().unwrap_or_else(|_| root.clone());
+        split_root(&root, deny_read, &mut split);
+    }
+    split
+}
+
+fn split_root(root: &Path, deny_read: &[PathBuf], split: &mut SplitRoots) {
+    if deny_read.iter().any(|denied| root.starts_with(denied)) {
+        return;
+    }
+    if !deny_read.iter().any(|denied| denied.starts_with(root)) {
+        split.full.push(root.to_path_buf());
+        return;
+    }
+
+    split.partial.push(root.to_path_buf());
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let child = entry.path();
+        // A symlink could lead into a denied folder under another name, so
+        // only grant it when its target is unrelated to every denied path.
+        let Ok(target) = child.canonicalize() else {
+            continue;
+        };
+        if target != child {
+            if !deny_read
+                .iter()
+                .any(|denied| target.starts_with(denied) || denied.starts_with(&target))
+            {
+                split.full.push(child);
+            }
+            continue;
+        }
+        split_root(&child, deny_read, split);
+    }
+}
+
 /// Installs a seccomp filter that blocks outbound network access except for
-/// AF_UNIX domain sockets.
-fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+/// AF_UNIX domain sockets. When `sockets_supervised` is set, `socket` itself
+/// is left to the supervisor that reports the denials.
+fn install_network_seccomp_filter_on_current_thread(
+    sockets_supervised: bool,
+) -> std::result::Result<(), SandboxErr> {
     // Build rule map.
     let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
 
@@ -121,9 +388,47 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
         libc::AF_UNIX as u64,
     )?])?;
 
-    rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
+    if !sockets_supervised {
+        rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
+    }
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
+    apply_seccomp_rules(rules)
+}
+
+/// Installs a seccomp filter for a process in a network namespace that only
+/// reaches the outside through the Codex network proxy: IPv4 and IPv6 sockets
+/// are allowed, every other socket family is blocked. Unix socket pairs keep
+/// working since they cannot reach other processes.
+fn install_namespaced_network_seccomp_filter_on_current_thread()
+-> std::result::Result<(), SandboxErr> {
+    let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
+    rules.insert(libc::SYS_ptrace, vec![]);
+
+    // Conditions of a rule must all match, so this denies any domain that is
+    // neither AF_INET nor AF_INET6.
+    let inet_only_rule = SeccompRule::new(vec![
+        SeccompCondition::new(
+            0,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_INET as u64,
+        )?,
+        SeccompCondition::new(
+            0,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_INET6 as u64,
+        )?,
+    ])?;
+    rules.insert(libc::SYS_socket, vec![inet_only_rule]);
+
+    apply_seccomp_rules(rules)
+}
+
+fn apply_seccomp_rules(
+    rules: BTreeMap<i64, Vec<SeccompRule>>,
+) -> std::result::Result<(), SandboxErr> {
     let filter = SeccompFilter::new(
         rules,
         SeccompAction::Allow,                     // default – allow
@@ -1,11 +1,61 @@
 use clap::Parser;
+use codex_core::config_types::ResourceLimits;
+use codex_core::landlock::LinuxSandboxIsolation;
 use std::ffi::CString;
 use std::path::PathBuf;
 
+use crate::denial_log::report_sandbox_denials;
 use crate::landlock::apply_sandbox_policy_to_current_thread;
+use crate::landlock::deny_read_in_writable_roots;
+use crate::landlock::resolve_deny_read_paths;
+use crate::namespaces::enter_isolated_namespaces;
+use crate::namespaces::hide_denied_paths;
+use crate::proxy_bridge::enter_proxied_network_namespace;
+use crate::report::open_report_channel;
+use crate::report::report_channel_open;
+use crate::resource_limits::apply_resource_limits;
 
 #[derive(Debug, Parser)]
 pub struct LandlockCommand {
+    /// Unix socket of the Codex network proxy. When set, the command runs in
+    /// its own network namespace and reaches the network through the proxy
+    /// only.
+    #[arg(long)]
+    pub network_proxy_socket: Option<PathBuf>,
+
+    /// Maximum memory of the command, in bytes.
+    #[arg(long)]
+    pub max_memory_bytes: Option<u64>,
+
+    /// Maximum CPU time of the command, in seconds.
+    #[arg(long)]
+    pub max_cpu_seconds: Option<u64>,
+
+    /// Maximum number of processes the command may run at once, enforced
+    /// through the cgroup of the command.
+    #[arg(long)]
+    pub max_processes: Option<u64>,
+
+    /// Maximum size of any file the command writes, in bytes.
+    #[arg(long)]
+    pub max_file_size_bytes: Option<u64>,
+
+    /// How the file-system view of the command is restricted. `namespaces`
+    /// adds user, mount and PID namespaces on top of Landlock and fails when
+    /// they are unavailable.
+    #[arg(long, default_value_t = LinuxSandboxIsolation::Landlock)]
+    pub isolation: LinuxSandboxIsolation,
+
+    /// Inherited file descriptor on which to report exceeded resource limits
+    /// and denied operations. Nothing is reported without it.
+    #[arg(long)]
+    pub report_fd: Option<i32>,
+
+    /// Watch the writes and network sockets of the command with a seccomp
+    /// supervisor, so that the ones the sandbox blocks are reported.
+    #[arg(long)]
+    pub report_denials: bool,
+
     /// It is possible that the cwd used in the context of the sandbox policy
     /// is different from the cwd of the process to spawn.
     pub sandbox_policy_cwd: PathBuf,
@@ -19,12 +69,103 @@ pub struct LandlockCommand {
 
 pub fn run_main() -> ! {
     let LandlockCommand {
+        network_proxy_socket,
+        max_memory_bytes,
+        max_cpu_seconds,
+        max_processes,
+        max_file_size_bytes,
+        isolation,
+        report_fd,
+        report_denials,
         sandbox_policy_cwd,
         sandbox_policy,
         command,
     } = LandlockCommand::parse();
 
-    if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
+    if let Some(fd) = report_fd
+        && let Err(e) = open_report_channel(fd)
+    {
+        panic!("error opening the report channel: {e:?}");
+    }
+
+    // Without the namespace, fall back to blocking the network entirely.
+    let network_namespaced = match &network_proxy_socket {
+        Some(socket) => match enter_proxied_network_namespace(socket) {
+            Ok(()) => true,
+            Err(err) => {
+                eprintln!(
+                    "codex-linux-sandbox: allowed_domains are unavailable, network access stays blocked: {err}"
+                );
+                false
+            }
+        },
+        None => false,
+    };
+
+    let resource_limits = ResourceLimits {
+        max_memory_bytes,
+        max_cpu_seconds,
+        max_processes,
+        max_file_size_bytes,
+    };
+    if let Err(e) = apply_resource_limits(&resource_limits) {
+        panic!("error applying resource limits: {e:?}");
+    }
+
+    let mut deny_read = resolve_deny_read_paths(&sandbox_policy, &sandbox_policy_cwd);
+    if isolation == LinuxSandboxIsolation::Namespaces {
+        // The command must not run with less isolation than configured.
+        if let Err(e) = enter_isolated_namespaces(&sandbox_policy, &sandbox_policy_cwd, &deny_read)
+        {
+            panic!("error entering isolated namespaces: {e:?}");
+        }
+        // Mounts can only hide paths that exist; Landlock keeps the others
+        // from being read once the command creates them.
+        deny_read.retain(|path| !path.exists());
+    } else {
+        let hidden: Vec<PathBuf> =
+            deny_read_in_writable_roots(&sandbox_policy, &sandbox_policy_cwd, &deny_read)
+                .into_iter()
+                .filter(|path| path.exists())
+                .collect();
+        if !hidden.is_empty() {
+            if let Err(e) = hide_denied_paths(&hidden) {
+                eprintln!(
+                    "codex-linux-sandbox: cannot keep {} unreadable: hiding deny_read paths inside a writable root needs unprivileged user namespaces ({e}); remove them from deny_read to run commands on this host",
+                    display_paths(&hidden)
+                );
+                std::process::exit(1);
+            }
+            deny_read.retain(|path| !hidden.contains(path));
+        }
+    }
+    // Landlock could only deny them by keeping every file that the command
+    // creates in the same folders from being read.
+    let unenforceable =
+        deny_read_in_writable_roots(&sandbox_policy, &sandbox_policy_cwd, &deny_read);
+    if !unenforceable.is_empty() {
+        eprintln!(
+            "codex-linux-sandbox: cannot keep {} unreadable: deny_read paths inside a writable root must exist when the command starts; create them or remove them from deny_read",
+            display_paths(&unenforceable)
+        );
+        std::process::exit(1);
+    }
+
+    // Denials are only reported when asked for and the kernel allows it; the
+    // sandbox is the same either way.
+    let supervise_sockets = !sandbox_policy.has_full_network_access() && !network_namespaced;
+    let sockets_supervised = report_denials
+        && report_channel_open()
+        && report_sandbox_denials(&sandbox_policy, &sandbox_policy_cwd, supervise_sockets)
+            .unwrap_or(false);
+
+    if let Err(e) = apply_sandbox_policy_to_current_thread(
+        &sandbox_policy,
+        &sandbox_policy_cwd,
+        deny_read,
+        network_namespaced,
+        sockets_supervised,
+    ) {
         panic!("error running landlock: {e:?}");
     }
 
@@ -52,3 +193,11 @@ pub fn run_main() -> ! {
     let err = std::io::Error::last_os_error();
     panic!("Failed to execvp {}: {err}", command[0].as_str());
 }
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
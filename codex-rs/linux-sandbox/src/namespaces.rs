@@ -0,0 +1,355 @@
+use std::ffi::CString;
+use std::ffi::OsString;
+use std::fs::Permissions;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::ffi::OsStringExt;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::protocol::SandboxPolicy;
+
+use crate::proxy_bridge::wait_for_child;
+use crate::report::while_dumpable;
+
+/// Flags of an existing mount that a read-only remount has to repeat, since
+/// a bind remount replaces all of them.
+const PRESERVED_MOUNT_FLAGS: &[(libc::c_ulong, libc::c_ulong)] = &[
+    (libc::ST_NOSUID, libc::MS_NOSUID),
+    (libc::ST_NODEV, libc::MS_NODEV),
+    (libc::ST_NOEXEC, libc::MS_NOEXEC),
+    (libc::ST_NOATIME, libc::MS_NOATIME),
+    (libc::ST_NODIRATIME, libc::MS_NODIRATIME),
+    (libc::ST_RELATIME, libc::MS_RELATIME),
+];
+
+/// Moves this process into new user, mount and PID namespaces in which the
+/// writable roots of `sandbox_policy` are the only writable mounts, `/tmp`
+/// is a private tmpfs and the existing paths in `deny_read` are hidden
+/// behind empty, unreadable mounts. Then forks: the parent stays behind, waits for the
+/// child and exits with its status, while the child returns as PID 1 of the
+/// new PID namespace, with a `/proc` of its own, to apply the rest of the
+/// sandbox and exec the command.
+///
+/// Must be called while the process is still single-threaded.
+///
+/// # Errors
+/// Returns an error when the namespaces cannot be created (for example when
+/// unprivileged user namespaces are disabled) or the file-system view or
+/// `/proc` cannot be set up. The process may then already be in some of the
+/// namespaces and must not run the command.
+pub(crate) fn enter_isolated_namespaces(
+    sandbox_policy: &SandboxPolicy,
+    cwd: &Path,
+    deny_read: &[PathBuf],
+) -> io::Result<()> {
+    let uid = unsafe { libc::getuid() };
+    let gid = unsafe { libc::getgid() };
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNS) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    map_current_ids(uid, gid)?;
+    set_up_filesystem_view(sandbox_policy, cwd, deny_read)?;
+
+    if unsafe { libc::unshare(libc::CLONE_NEWPID) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => {
+            // Codex kills the parent on timeout; take the command with it.
+            // Our parent lives outside the PID namespace, so `getppid` cannot
+            // tell whether it already died.
+            if unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) } == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            // Without a `/proc` of the new namespace, the command would still
+            // see the host's processes.
+            mount(
+                Some(Path::new("proc")),
+                Path::new("/proc"),
+                Some("proc"),
+                libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC,
+                None,
+            )
+        }
+        child => std::process::exit(wait_for_child(child)),
+    }
+}
+
+/// Moves this process into new user and mount namespaces in which the paths
+/// in `deny_read` are hidden behind empty, unreadable mounts, and leaves the
+/// rest of the file-system as it is. This is how Landlock isolation denies
+/// the paths that Landlock cannot deny by itself.
+///
+/// Must be called while the process is still single-threaded.
+///
+/// # Errors
+/// Returns an error when the namespaces cannot be created (for example when
+/// unprivileged user namespaces are disabled) or a path cannot be hidden. The
+/// process may then already be in the namespaces and must not run the
+/// command.
+pub(crate) fn hide_denied_paths(deny_read: &[PathBuf]) -> io::Result<()> {
+    let uid = unsafe { libc::getuid() };
+    let gid = unsafe { libc::getgid() };
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNS) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    map_current_ids(uid, gid)?;
+    // Keep our mounts from propagating back to the host.
+    mount(
+        None,
+        Path::new("/"),
+        None,
+        libc::MS_REC | libc::MS_PRIVATE,
+        None,
+    )?;
+    mask_denied_paths(deny_read)
+}
+
+/// Maps `uid` and `gid` to themselves in the user namespace this process
+/// just created, so that file ownership looks unchanged to the command.
+pub(crate) fn map_current_ids(uid: libc::uid_t, gid: libc::gid_t) -> io::Result<()> {
+    // The maps belong to root while this process is not dumpable.
+    while_dumpable(|| {
+        std::fs::write("/proc/self/setgroups", "deny")?;
+        std::fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+        std::fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))
+    })
+}
+
+fn set_up_filesystem_view(
+    sandbox_policy: &SandboxPolicy,
+    cwd: &Path,
+    deny_read: &[PathBuf],
+) -> io::Result<()> {
+    // Keep our mounts from propagating back to the host.
+    mount(
+        None,
+        Path::new("/"),
+        None,
+        libc::MS_REC | libc::MS_PRIVATE,
+        None,
+    )?;
+
+    let writable_roots: Vec<_> = sandbox_policy
+        .get_writable_roots_with_cwd(cwd)
+        .into_iter()
+        .filter(|writable_root| writable_root.root.exists())
+        .collect();
+
+    if !sandbox_policy.has_full_disk_write_access() {
+        // A bind mount of a writable root onto itself keeps it writable when
+        // the mount it lives on turns read-only.
+        for writable_root in &writable_roots {
+            bind_mount(&writable_root.root, &writable_root.root)?;
+        }
+        for mount_point in mount_points()? {
+            if writable_roots
+                .iter()
+                .any(|writable_root| mount_point.starts_with(&writable_root.root))
+            {
+                continue;
+            }
+            // Some mounts (of other users, or locked by the kernel) cannot be
+            // remounted; Landlock still keeps them read-only.
+            if let Err(err) = remount_read_only(&mount_point)
+                && mount_point == Path::new("/")
+            {
+                return Err(err);
+            }
+        }
+        for read_only in writable_roots
+            .iter()
+            .flat_map(|writable_root| &writable_root.read_only_subpaths)
+            .filter(|path| path.exists())
+        {
+            bind_mount(read_only, read_only)?;
+            remount_read_only(read_only)?;
+        }
+    }
+
+    mask_denied_paths(deny_read)?;
+
+    // A tmpfs on `/tmp` would hide a workspace that lives below it.
+    let tmp = Path::new("/tmp");
+    let hides_workspace = |path: &Path| path.starts_with(tmp) && path != tmp;
+    if tmp.is_dir()
+        && !hides_workspace(cwd)
+        && !writable_roots
+            .iter()
+            .any(|writable_root| hides_workspace(&writable_root.root))
+    {
+        mount(
+            Some(Path::new("tmpfs")),
+            tmp,
+            Some("tmpfs"),
+            libc::MS_NOSUID | libc::MS_NODEV,
+            Some("mode=1777"),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Mounts empty, unreadable placeholders over the paths in `deny_read`. They
+/// stay in place but can no longer be opened, so that the command fails with
+/// the usual "Permission denied". Paths that do not exist are left to
+/// Landlock.
+fn mask_denied_paths(deny_read: &[PathBuf]) -> io::Result<()> {
+    let mut denied_files = Vec::new();
+    for denied in deny_read {
+        if denied.is_dir() {
+            mount(
+                Some(Path::new("tmpfs")),
+                denied,
+                Some("tmpfs"),
+                libc::MS_RDONLY | libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC,
+                Some("mode=000"),
+            )?;
+        } else if denied.exists() {
+            // Opened up front, since the staging tmpfs below may hide them.
+            let file = std::fs::OpenOptions::new()
+                .read(true)
+                .custom_flags(libc::O_PATH)
+                .open(denied)?;
+            denied_files.push((denied, file));
+        }
+    }
+    if denied_files.is_empty() {
+        return Ok(());
+    }
+
+    // A bind mount needs an existing file as its source. Stage one on a
+    // tmpfs that is detached again once it is mounted over the denied files.
+    let staging = Path::new("/tmp");
+    mount(
+        Some(Path::new("tmpfs")),
+        staging,
+        Some("tmpfs"),
+        libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC,
+        Some("mode=700"),
+    )?;
+    let placeholder = staging.join("unreadable");
+    let result = std::fs::File::create(&placeholder)
+        .and_then(|_| std::fs::set_permissions(&placeholder, Permissions::from_mode(0o000)))
+        .and_then(|()| {
+            denied_files.iter().try_for_each(|(_, file)| {
+                bind_mount(
+                    &placeholder,
+                    Path::new(&format!("/proc/self/fd/{}", file.as_raw_fd())),
+                )
+            })
+        });
+    let staging_c = path_to_cstring(staging)?;
+    if unsafe { libc::umount2(staging_c.as_ptr(), libc::MNT_DETACH) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    result?;
+
+    for (denied, _) in &denied_files {
+        remount_read_only(denied)?;
+    }
+    Ok(())
+}
+
+/// Mount points of this process, from the fifth field of
+/// `/proc/self/mountinfo`.
+fn mount_points() -> io::Result<Vec<PathBuf>> {
+    let mountinfo = std::fs::read_to_string("/proc/self/mountinfo")?;
+    Ok(mountinfo
+        .lines()
+        .filter_map(|line| line.split(' ').nth(4))
+        .map(unescape_mount_path)
+        .collect())
+}
+
+/// Undoes the octal escapes (such as `\040` for a space) of paths in
+/// `/proc/self/mountinfo`.
+fn unescape_mount_path(escaped: &str) -> PathBuf {
+    let bytes = escaped.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes
+            .get(i + 1..i + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match octal {
+            Some(byte) if bytes[i] == b'\\' => {
+                unescaped.push(byte);
+                i += 4;
+            }
+            _ => {
+                unescaped.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    PathBuf::from(OsString::from_vec(unescaped))
+}
+
+fn bind_mount(source: &Path, target: &Path) -> io::Result<()> {
+    mount(
+        Some(source),
+        target,
+        None,
+        libc::MS_BIND | libc::MS_REC,
+        None,
+    )
+}
+
+fn remount_read_only(path: &Path) -> io::Result<()> {
+    let c_path = path_to_cstring(path)?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let flags = PRESERVED_MOUNT_FLAGS
+        .iter()
+        .filter(|(st_flag, _)| stat.f_flag & st_flag != 0)
+        .fold(
+            libc::MS_REMOUNT | libc::MS_BIND | libc::MS_RDONLY,
+            |flags, (_, ms_flag)| flags | ms_flag,
+        );
+    mount(None, path, None, flags, None)
+}
+
+fn mount(
+    source: Option<&Path>,
+    target: &Path,
+    fstype: Option<&str>,
+    flags: libc::c_ulong,
+    data: Option<&str>,
+) -> io::Result<()> {
+    let source = source.map(path_to_cstring).transpose()?;
+    let target = path_to_cstring(target)?;
+    let fstype = fstype.map(str_to_cstring).transpose()?;
+    let data = data.map(str_to_cstring).transpose()?;
+    let result = unsafe {
+        libc::mount(
+            source.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+            target.as_ptr(),
+            fstype.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+            flags,
+            data.as_ref()
+                .map_or(std::ptr::null(), |s| s.as_ptr().cast::<libc::c_void>()),
+        )
+    };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn path_to_cstring(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
+
+fn str_to_cstring(value: &str) -> io::Result<CString> {
+    CString::new(value).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
@@ -0,0 +1,150 @@
+use std::io;
+use std::net::Shutdown;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::namespaces::map_current_ids;
+
+/// Environment variables that point HTTP clients at the proxy.
+const PROXY_ENV_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+];
+
+/// Moves this process into new user and network namespaces, where the only
+/// network interface is loopback, and forks. The parent stays behind to
+/// forward connections from a loopback port to the Codex network proxy at
+/// `proxy_socket` until the child exits, then exits with the child's status.
+/// The child returns so that it can apply the sandbox and exec the command,
+/// with the proxy environment variables already set.
+///
+/// Must be called while the process is still single-threaded.
+///
+/// # Errors
+/// Returns an error, without having forked, when the namespaces cannot be
+/// created (for example when unprivileged user namespaces are disabled).
+pub(crate) fn enter_proxied_network_namespace(proxy_socket: &Path) -> io::Result<()> {
+    enter_user_and_network_namespace()?;
+    bring_up_loopback()?;
+
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    let proxy_url = format!("http://{}", listener.local_addr()?);
+
+    let parent = unsafe { libc::getpid() };
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => {
+            drop(listener);
+            // The parent is the process Codex watches and kills on timeout;
+            // make sure the command does not outlive it.
+            if unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) } == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            if unsafe { libc::getppid() } != parent {
+                std::process::exit(1);
+            }
+            for name in PROXY_ENV_VARS {
+                // SAFETY: the child of the fork has a single thread.
+                unsafe { std::env::set_var(name, &proxy_url) };
+            }
+            unsafe { std::env::remove_var("NO_PROXY") };
+            unsafe { std::env::remove_var("no_proxy") };
+            Ok(())
+        }
+        child => {
+            let proxy_socket = proxy_socket.to_path_buf();
+            std::thread::spawn(move || forward_connections(listener, proxy_socket));
+            std::process::exit(wait_for_child(child));
+        }
+    }
+}
+
+fn enter_user_and_network_namespace() -> io::Result<()> {
+    let uid = unsafe { libc::getuid() };
+    let gid = unsafe { libc::getgid() };
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    map_current_ids(uid, gid)
+}
+
+/// A new network namespace starts with its loopback interface down.
+fn bring_up_loopback() -> io::Result<()> {
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut ifreq: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in ifreq.ifr_name.iter_mut().zip(b"lo\0") {
+        *dst = *src as libc::c_char;
+    }
+    let ifreq_ptr: *mut libc::ifreq = &mut ifreq;
+    let result = unsafe {
+        if libc::ioctl(fd, libc::SIOCGIFFLAGS, ifreq_ptr) == -1 {
+            -1
+        } else {
+            (*ifreq_ptr).ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short;
+            libc::ioctl(fd, libc::SIOCSIFFLAGS, ifreq_ptr)
+        }
+    };
+    let err = io::Error::last_os_error();
+    unsafe { libc::close(fd) };
+    if result == -1 { Err(err) } else { Ok(()) }
+}
+
+fn forward_connections(listener: TcpListener, proxy_socket: PathBuf) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let proxy_socket = proxy_socket.clone();
+        std::thread::spawn(move || {
+            let _ = forward_connection(stream, &proxy_socket);
+        });
+    }
+}
+
+fn forward_connection(client: TcpStream, proxy_socket: &Path) -> io::Result<()> {
+    let proxy = UnixStream::connect(proxy_socket)?;
+    let mut client_reader = client.try_clone()?;
+    let mut proxy_writer = proxy.try_clone()?;
+    let upload = std::thread::spawn(move || {
+        let _ = io::copy(&mut client_reader, &mut proxy_writer);
+        let _ = proxy_writer.shutdown(Shutdown::Write);
+    });
+
+    let (mut proxy_reader, mut client_writer) = (proxy, client);
+    let _ = io::copy(&mut proxy_reader, &mut client_writer);
+    let _ = client_writer.shutdown(Shutdown::Write);
+    let _ = upload.join();
+    Ok(())
+}
+
+/// Waits for `child` and translates its status into an exit code the way a
+/// shell would.
+pub(crate) fn wait_for_child(child: libc::pid_t) -> i32 {
+    let mut status = 0;
+    loop {
+        if unsafe { libc::waitpid(child, &mut status, 0) } != -1 {
+            break;
+        }
+        if io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+            return 1;
+        }
+    }
+    if libc::WIFEXITED(status) {
+        libc::WEXITSTATUS(status)
+    } else if libc::WIFSIGNALED(status) {
+        128 + libc::WTERMSIG(status)
+    } else {
+        1
+    }
+}
@@ -0,0 +1,61 @@
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::os::fd::FromRawFd;
+use std::os::fd::RawFd;
+use std::sync::OnceLock;
+
+/// Where [`report`] writes, when Codex passed `--report-fd`.
+static REPORT_CHANNEL: OnceLock<File> = OnceLock::new();
+
+/// Takes over `fd`, inherited from Codex, as the channel on which the sandbox
+/// reports what happened to the command (see
+/// [`codex_core::landlock::SANDBOX_REPORT_FD`]).
+///
+/// The command must not be able to write to it: `fd` is closed when the
+/// command is executed, and this process becomes non-dumpable, so that the
+/// command cannot reopen it through `/proc/<pid>/fd` of the processes of the
+/// sandbox that stay behind to report on it. `execve(2)` makes the command
+/// itself dumpable again.
+///
+/// Must be called once, before anything is forked.
+pub(crate) fn open_report_channel(fd: RawFd) -> io::Result<()> {
+    if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let channel = unsafe { File::from_raw_fd(fd) };
+    if unsafe { libc::prctl(libc::PR_SET_DUMPABLE, 0) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    REPORT_CHANNEL
+        .set(channel)
+        .map_err(|_| io::Error::new(io::ErrorKind::AlreadyExists, "report channel already open"))
+}
+
+/// Whether Codex listens to what the sandbox reports.
+pub(crate) fn report_channel_open() -> bool {
+    REPORT_CHANNEL.get().is_some()
+}
+
+/// Sends `line` to Codex, if it listens. Lines are short enough to be
+/// written atomically, so processes of the sandbox can report concurrently.
+pub(crate) fn report(line: &str) {
+    if let Some(mut channel) = REPORT_CHANNEL.get() {
+        let _ = channel.write_all(format!("{line}\n").as_bytes());
+    }
+}
+
+/// Runs `f` with this process dumpable, which some `/proc/self` files and
+/// `pidfd_getfd(2)` on this process require, then makes it non-dumpable again
+/// if a report channel is open. Only call it while no command runs that
+/// could take advantage.
+pub(crate) fn while_dumpable<T>(f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+    if unsafe { libc::prctl(libc::PR_SET_DUMPABLE, 1) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let result = f();
+    if report_channel_open() && unsafe { libc::prctl(libc::PR_SET_DUMPABLE, 0) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    result
+}
@@ -0,0 +1,150 @@
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::config_types::ResourceLimits;
+use codex_core::resource_limits::RESOURCE_LIMIT_EXCEEDED_MARKER;
+use codex_core::resource_limits::ResourceLimitKind;
+use codex_core::resource_limits::apply_rlimits;
+
+use crate::proxy_bridge::wait_for_child;
+use crate::report::report;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Applies `limits` to the command this process is about to exec.
+///
+/// The memory and process limits are enforced through a cgroup v2 created
+/// for the command when the current cgroup allows it. Unlike `setrlimit(2)`,
+/// the cgroup limits cover the whole process tree, count actual memory use
+/// rather than address space and let us tell for sure that a limit was hit.
+/// In that case this process forks: the parent stays behind, waits for the
+/// command and reports [`RESOURCE_LIMIT_EXCEEDED_MARKER`] when the cgroup
+/// stopped it. Without a cgroup, the memory limit falls back to
+/// `setrlimit(2)` while the process limit is refused: `RLIMIT_NPROC` counts
+/// every process of the user, not just those of the command. The other limits
+/// are always applied with `setrlimit(2)`.
+///
+/// Must be called while the process is still single-threaded and before the
+/// filesystem sandbox is applied.
+pub(crate) fn apply_resource_limits(limits: &ResourceLimits) -> io::Result<()> {
+    if limits.is_unlimited() {
+        return Ok(());
+    }
+
+    let needs_cgroup = limits.max_memory_bytes.is_some() || limits.max_processes.is_some();
+    let in_cgroup = needs_cgroup
+        && match create_cgroup(limits) {
+            Ok(cgroup) => run_in_cgroup(&cgroup)?,
+            Err(_) => false,
+        };
+
+    if limits.max_processes.is_some() && !in_cgroup {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "`resource_limits.max_processes` needs a cgroup v2 that the sandbox can create, and none is available",
+        ));
+    }
+
+    apply_rlimits(&ResourceLimits {
+        max_memory_bytes: limits.max_memory_bytes.filter(|_| !in_cgroup),
+        max_processes: None,
+        ..limits.clone()
+    })
+}
+
+/// Creates a cgroup for the command below the cgroup of this process and
+/// sets its limits.
+fn create_cgroup(limits: &ResourceLimits) -> io::Result<PathBuf> {
+    let cgroup = Path::new(CGROUP_ROOT)
+        .join(current_cgroup()?.trim_start_matches('/'))
+        .join(format!("codex-exec-{}", std::process::id()));
+    std::fs::create_dir(&cgroup)?;
+
+    if let Err(err) = configure_cgroup(&cgroup, limits) {
+        let _ = std::fs::remove_dir(&cgroup);
+        return Err(err);
+    }
+    Ok(cgroup)
+}
+
+fn configure_cgroup(cgroup: &Path, limits: &ResourceLimits) -> io::Result<()> {
+    if let Some(bytes) = limits.max_memory_bytes {
+        std::fs::write(cgroup.join("memory.max"), bytes.to_string())?;
+        // Otherwise the command is swapped out rather than stopped.
+        let _ = std::fs::write(cgroup.join("memory.swap.max"), "0");
+    }
+    if let Some(processes) = limits.max_processes {
+        std::fs::write(cgroup.join("pids.max"), processes.to_string())?;
+    }
+    Ok(())
+}
+
+/// The cgroup v2 path of this process, relative to [`CGROUP_ROOT`].
+fn current_cgroup() -> io::Result<String> {
+    let cgroups = std::fs::read_to_string("/proc/self/cgroup")?;
+    cgroups
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(str::to_string)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no cgroup v2 hierarchy"))
+}
+
+/// Forks. The child moves itself into `cgroup` and returns whether it
+/// managed to; the parent waits for it, reports the limit the cgroup
+/// enforced, removes the cgroup and exits with the child's status.
+fn run_in_cgroup(cgroup: &Path) -> io::Result<bool> {
+    let parent = unsafe { libc::getpid() };
+    match unsafe { libc::fork() } {
+        -1 => {
+            let err = io::Error::last_os_error();
+            let _ = std::fs::remove_dir(cgroup);
+            Err(err)
+        }
+        0 => {
+            // Codex kills the parent on timeout; take the command with it.
+            if unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) } == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            if unsafe { libc::getppid() } != parent {
+                std::process::exit(1);
+            }
+            Ok(std::fs::write(cgroup.join("cgroup.procs"), "0").is_ok())
+        }
+        child => {
+            let status = wait_for_child(child);
+            if let Some(kind) = exceeded_cgroup_limit(cgroup) {
+                report(&format!(
+                    "{RESOURCE_LIMIT_EXCEEDED_MARKER}{}",
+                    kind.as_str()
+                ));
+            }
+            let _ = std::fs::remove_dir(cgroup);
+            std::process::exit(status);
+        }
+    }
+}
+
+/// Reads the cgroup's event counters to find out whether a limit stopped
+/// the command.
+fn exceeded_cgroup_limit(cgroup: &Path) -> Option<ResourceLimitKind> {
+    if read_event_count(&cgroup.join("memory.events"), "oom_kill") > 0 {
+        Some(ResourceLimitKind::Memory)
+    } else if read_event_count(&cgroup.join("pids.events"), "max") > 0 {
+        Some(ResourceLimitKind::Processes)
+    } else {
+        None
+    }
+}
+
+fn read_event_count(events_file: &Path, event: &str) -> u64 {
+    let Ok(events) = std::fs::read_to_string(events_file) else {
+        return 0;
+    };
+    events
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .find(|(name, _)| *name == event)
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .unwrap_or(0)
+}
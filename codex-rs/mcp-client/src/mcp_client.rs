@@ -6,6 +6,8 @@
 //!   2. Sending MCP requests and pairing them with their corresponding
 //!      responses.
 //!   3. Offering a convenience helper for the common `tools/list` request.
+//!   4. Answering `sampling/createMessage` requests from the server through a
+//!      caller-provided [`SamplingHandler`].
 //!
 //! The crate hides all JSON‐RPC framing details behind a typed API. Users
 //! interact with the [`ModelContextProtocolRequest`] trait from `mcp-types` to
@@ -13,6 +15,8 @@
 
 use std::collections::HashMap;
 use std::ffi::OsString;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::Ordering;
@@ -23,19 +27,39 @@ use anyhow::Result;
 use anyhow::anyhow;
 use mcp_types::CallToolRequest;
 use mcp_types::CallToolRequestParams;
+use mcp_types::CreateMessageRequest;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::GetPromptRequest;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::InitializeRequest;
 use mcp_types::InitializeRequestParams;
 use mcp_types::InitializedNotification;
 use mcp_types::JSONRPC_VERSION;
+use mcp_types::JSONRPCError;
+use mcp_types::JSONRPCErrorError;
 use mcp_types::JSONRPCMessage;
 use mcp_types::JSONRPCNotification;
 use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
+use mcp_types::ListPromptsRequest;
+use mcp_types::ListPromptsRequestParams;
+use mcp_types::ListPromptsResult;
+use mcp_types::ListResourceTemplatesRequest;
+use mcp_types::ListResourceTemplatesRequestParams;
+use mcp_types::ListResourceTemplatesResult;
+use mcp_types::ListResourcesRequest;
+use mcp_types::ListResourcesRequestParams;
+use mcp_types::ListResourcesResult;
 use mcp_types::ListToolsRequest;
 use mcp_types::ListToolsRequestParams;
 use mcp_types::ListToolsResult;
 use mcp_types::ModelContextProtocolNotification;
 use mcp_types::ModelContextProtocolRequest;
+use mcp_types::ReadResourceRequest;
+use mcp_types::ReadResourceRequestParams;
+use mcp_types::ReadResourceResult;
 use mcp_types::RequestId;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
@@ -56,9 +80,24 @@ use tracing::warn;
 /// client API and the IO tasks.
 const CHANNEL_CAPACITY: usize = 128;
 
+/// JSON-RPC error codes used when answering server-initiated requests.
+const METHOD_NOT_FOUND_ERROR_CODE: i64 = -32601;
+const INVALID_PARAMS_ERROR_CODE: i64 = -32602;
+const INTERNAL_ERROR_CODE: i64 = -32603;
+
 /// Internal representation of a pending request sender.
 type PendingSender = oneshot::Sender<JSONRPCMessage>;
 
+/// Callback that answers `sampling/createMessage` requests issued by the
+/// server. An `Err` is reported back to the server as a JSON-RPC error.
+pub type SamplingHandler = Arc<
+    dyn Fn(
+            CreateMessageRequestParams,
+        ) -> Pin<Box<dyn Future<Output = Result<CreateMessageResult, String>> + Send>>
+        + Send
+        + Sync,
+>;
+
 /// A running MCP client instance.
 pub struct McpClient {
     /// Retain this child process until the client is dropped. The Tokio runtime
@@ -76,6 +115,9 @@ pub struct McpClient {
 
     /// Monotonically increasing counter used to generate request IDs.
     id_counter: AtomicI64,
+
+    /// Handler for `sampling/createMessage`, shared with the reader task.
+    sampling_handler: Arc<Mutex<Option<SamplingHandler>>>,
 }
 
 impl McpClient {
@@ -111,6 +153,7 @@ impl McpClient {
 
         let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
         let pending: Arc<Mutex<HashMap<i64, PendingSender>>> = Arc::new(Mutex::new(HashMap::new()));
+        let sampling_handler: Arc<Mutex<Option<SamplingHandler>>> = Arc::new(Mutex::new(None));
 
         // Spawn writer task. It listens on the `outgoing_rx` channel and
         // writes messages to the child's STDIN.
@@ -138,9 +181,12 @@ impl McpClient {
         };
 
         // Spawn reader task. It reads line-delimited JSON from the child's
-        // STDOUT and dispatches responses to the pending map.
+        // STDOUT, dispatches responses to the pending map and answers
+        // requests initiated by the server.
         let reader_handle = {
             let pending = pending.clone();
+            let sampling_handler = sampling_handler.clone();
+            let outgoing_tx = outgoing_tx.clone();
             let mut lines = BufReader::new(stdout).lines();
 
             tokio::spawn(async move {
@@ -153,15 +199,13 @@ impl McpClient {
                         Ok(JSONRPCMessage::Error(err)) => {
                             Self::dispatch_error(err, &pending).await;
                         }
+                        Ok(JSONRPCMessage::Request(request)) => {
+                            Self::dispatch_request(request, &sampling_handler, &outgoing_tx).await;
+                        }
                         Ok(JSONRPCMessage::Notification(JSONRPCNotification { .. })) => {
                             // For now we only log server-initiated notifications.
                             info!("<- notification: {}", line);
                         }
-                        Ok(other) => {
-                            // Batch responses and requests are currently not
-                            // expected from the server – log and ignore.
-                            info!("<- unhandled message: {:?}", other);
-                        }
                         Err(e) => {
                             error!("failed to deserialize JSONRPCMessage: {e}; line = {}", line)
                         }
@@ -181,9 +225,18 @@ impl McpClient {
             outgoing_tx,
             pending,
             id_counter: AtomicI64::new(1),
+            sampling_handler,
         })
     }
 
+    /// Install the handler used to answer `sampling/createMessage` requests.
+    /// Until one is installed such requests are rejected. Callers that
+    /// install a handler should advertise the `sampling` capability in
+    /// [`initialize`](Self::initialize).
+    pub async fn set_sampling_handler(&self, handler: SamplingHandler) {
+        *self.sampling_handler.lock().await = Some(handler);
+    }
+
     /// Send an arbitrary MCP request and await the typed result.
     ///
     /// If `timeout` is `None` the call waits indefinitely. If `Some(duration)`
@@ -345,6 +398,55 @@ impl McpClient {
         self.send_request::<CallToolRequest>(params, timeout).await
     }
 
+    /// Convenience wrapper around `resources/list`.
+    pub async fn list_resources(
+        &self,
+        params: Option<ListResourcesRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<ListResourcesResult> {
+        self.send_request::<ListResourcesRequest>(params, timeout)
+            .await
+    }
+
+    /// Convenience wrapper around `resources/templates/list`.
+    pub async fn list_resource_templates(
+        &self,
+        params: Option<ListResourceTemplatesRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<ListResourceTemplatesResult> {
+        self.send_request::<ListResourceTemplatesRequest>(params, timeout)
+            .await
+    }
+
+    /// Convenience wrapper around `resources/read`.
+    pub async fn read_resource(
+        &self,
+        params: ReadResourceRequestParams,
+        timeout: Option<Duration>,
+    ) -> Result<ReadResourceResult> {
+        self.send_request::<ReadResourceRequest>(params, timeout)
+            .await
+    }
+
+    /// Convenience wrapper around `prompts/list`.
+    pub async fn list_prompts(
+        &self,
+        params: Option<ListPromptsRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<ListPromptsResult> {
+        self.send_request::<ListPromptsRequest>(params, timeout)
+            .await
+    }
+
+    /// Convenience wrapper around `prompts/get`.
+    pub async fn get_prompt(
+        &self,
+        params: GetPromptRequestParams,
+        timeout: Option<Duration>,
+    ) -> Result<GetPromptResult> {
+        self.send_request::<GetPromptRequest>(params, timeout).await
+    }
+
     /// Internal helper: route a JSON-RPC *response* object to the pending map.
     async fn dispatch_response(
         resp: JSONRPCResponse,
@@ -372,11 +474,59 @@ impl McpClient {
         }
     }
 
-    /// Internal helper: route a JSON-RPC *error* object to the pending map.
-    async fn dispatch_error(
-        err: mcp_types::JSONRPCError,
-        pending: &Arc<Mutex<HashMap<i64, PendingSender>>>,
+    /// Internal helper: answer a server-initiated *request*. Only
+    /// `sampling/createMessage` is supported; the handler runs on its own task
+    /// so that the reader keeps draining responses while it waits.
+    async fn dispatch_request(
+        request: JSONRPCRequest,
+        sampling_handler: &Arc<Mutex<Option<SamplingHandler>>>,
+        outgoing_tx: &mpsc::Sender<JSONRPCMessage>,
     ) {
+        let JSONRPCRequest {
+            id, method, params, ..
+        } = request;
+        let handler = sampling_handler.lock().await.clone();
+        let (handler, params) = match handler {
+            Some(handler) if method == CreateMessageRequest::METHOD => {
+                match serde_json::from_value::<CreateMessageRequestParams>(
+                    params.unwrap_or_default(),
+                ) {
+                    Ok(params) => (handler, params),
+                    Err(e) => {
+                        let message = format!("invalid {method} params: {e}");
+                        let error = error_message(id, INVALID_PARAMS_ERROR_CODE, message);
+                        let _ = outgoing_tx.send(error).await;
+                        return;
+                    }
+                }
+            }
+            _ => {
+                info!("<- unhandled request: {method}");
+                let message = format!("method not supported: {method}");
+                let error = error_message(id, METHOD_NOT_FOUND_ERROR_CODE, message);
+                let _ = outgoing_tx.send(error).await;
+                return;
+            }
+        };
+
+        let outgoing_tx = outgoing_tx.clone();
+        tokio::spawn(async move {
+            let message = match handler(params).await {
+                Ok(result) => JSONRPCMessage::Response(JSONRPCResponse {
+                    id,
+                    jsonrpc: JSONRPC_VERSION.to_string(),
+                    result: result.into(),
+                }),
+                Err(message) => error_message(id, INTERNAL_ERROR_CODE, message),
+            };
+            if outgoing_tx.send(message).await.is_err() {
+                error!("failed to send sampling response - channel closed");
+            }
+        });
+    }
+
+    /// Internal helper: route a JSON-RPC *error* object to the pending map.
+    async fn dispatch_error(err: JSONRPCError, pending: &Arc<Mutex<HashMap<i64, PendingSender>>>) {
         let id = match err.id {
             RequestId::Integer(i) => i,
             RequestId::String(_) => return, // see comment above
@@ -402,6 +552,18 @@ impl Drop for McpClient {
     }
 }
 
+fn error_message(id: RequestId, code: i64, message: String) -> JSONRPCMessage {
+    JSONRPCMessage::Error(JSONRPCError {
+        error: JSONRPCErrorError {
+            code,
+            data: None,
+            message,
+        },
+        id,
+        jsonrpc: JSONRPC_VERSION.to_string(),
+    })
+}
+
 /// Environment variables that are always included when spawning a new MCP
 /// server.
 #[rustfmt::skip]
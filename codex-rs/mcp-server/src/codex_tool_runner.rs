@@ -20,6 +20,7 @@ use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::Submission;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_protocol::ConversationId;
@@ -183,6 +184,7 @@ async fn run_codex_tool_session_inner(
                         cwd,
                         call_id,
                         reason: _,
+                        sandbox_denials: _,
                     }) => {
                         handle_exec_approval_request(
                             command,
@@ -225,6 +227,35 @@ async fn run_codex_tool_session_inner(
                         .await;
                         continue;
                     }
+                    EventMsg::McpSamplingApprovalRequest(_) => {
+                        // Sampling prompts are not forwarded to the MCP client,
+                        // so only servers configured with
+                        // `auto_approve_sampling` can sample.
+                        if let Err(err) = codex
+                            .submit(Op::McpSamplingApproval {
+                                id: event.id.clone(),
+                                decision: ReviewDecision::Denied,
+                            })
+                            .await
+                        {
+                            tracing::error!("failed to decline MCP sampling request: {err}");
+                        }
+                        continue;
+                    }
+                    EventMsg::CostBudgetApprovalRequest(_) => {
+                        // Budget prompts are not forwarded to the MCP client, so
+                        // a session that reaches `session_budget_usd` stops.
+                        if let Err(err) = codex
+                            .submit(Op::CostBudgetApproval {
+                                id: event.id.clone(),
+                                decision: ReviewDecision::Denied,
+                            })
+                            .await
+                        {
+                            tracing::error!("failed to answer cost budget request: {err}");
+                        }
+                        continue;
+                    }
                     EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
                         let base_text = match last_agent_message {
                             Some(msg) => msg,
@@ -293,7 +324,10 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::McpToolCallBegin(_)
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::McpListToolsResponse(_)
+                    | EventMsg::McpListResourcesResponse(_)
+                    | EventMsg::McpServersReloaded(_)
                     | EventMsg::ListCustomPromptsResponse(_)
+                    | EventMsg::PinnedItems(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::ExecCommandOutputDelta(_)
                     | EventMsg::ExecCommandEnd(_)
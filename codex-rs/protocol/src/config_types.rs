@@ -59,3 +59,21 @@ pub enum SandboxMode {
     #[serde(rename = "danger-full-access")]
     DangerFullAccess,
 }
+
+/// How the conversation history is shrunk by `/compact` and by automatic
+/// compaction near the context limit.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionStrategy {
+    /// Ask the model for a summary and replace the history with it and the
+    /// user's earlier messages.
+    #[default]
+    Summarize,
+
+    /// Keep every tool call but replace bulky outputs from earlier turns with
+    /// a short stub.
+    DropToolOutputs,
+
+    /// Keep the last `n` turns verbatim and drop everything before them.
+    KeepLastTurns(usize),
+}
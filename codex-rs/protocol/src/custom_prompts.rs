@@ -1,3 +1,4 @@
+use mcp_types::PromptArgument;
 use serde::Deserialize;
 use serde::Serialize;
 use std::path::PathBuf;
@@ -9,6 +10,11 @@ use ts_rs::TS;
 /// - Full slash prefix: `"/{PROMPTS_CMD_PREFIX}:"`
 pub const PROMPTS_CMD_PREFIX: &str = "prompts";
 
+/// Namespace for prompts published by MCP servers: `/mcp:server:name`. Kept
+/// apart from [`PROMPTS_CMD_PREFIX`] so a server named `prompts` cannot shadow
+/// local prompts.
+pub const MCP_PROMPTS_CMD_PREFIX: &str = "mcp";
+
 #[derive(Serialize, Deserialize, Debug, Clone, TS)]
 pub struct CustomPrompt {
     pub name: String,
@@ -16,4 +22,27 @@ pub struct CustomPrompt {
     pub content: String,
     pub description: Option<String>,
     pub argument_hint: Option<String>,
+    /// Set for prompts published by an MCP server instead of read from disk.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp: Option<McpPromptSource>,
+}
+
+/// Origin of a prompt published by an MCP server. Such prompts are invoked as
+/// `/mcp:server:name` and have no local `content`: the server renders them through
+/// `prompts/get` when the message is submitted.
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+pub struct McpPromptSource {
+    pub server: String,
+    pub arguments: Vec<PromptArgument>,
+}
+
+impl CustomPrompt {
+    /// The slash command token (without the leading `/`) that invokes this
+    /// prompt: `prompts:name` for local prompts, `mcp:server:name` for MCP ones.
+    pub fn command(&self) -> String {
+        match &self.mcp {
+            Some(mcp) => format!("{MCP_PROMPTS_CMD_PREFIX}:{}:{}", mcp.server, self.name),
+            None => format!("{PROMPTS_CMD_PREFIX}:{}", self.name),
+        }
+    }
 }
@@ -4,13 +4,16 @@
 //! between user and agent.
 
 use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::fmt;
+use std::path::Component;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 
 use crate::ConversationId;
+use crate::config_types::CompactionStrategy;
 use crate::config_types::ReasoningEffort as ReasoningEffortConfig;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use crate::custom_prompts::CustomPrompt;
@@ -21,6 +24,9 @@ use crate::num_format::format_with_separators;
 use crate::parse_command::ParsedCommand;
 use crate::plan_tool::UpdatePlanArgs;
 use mcp_types::CallToolResult;
+use mcp_types::Resource as McpResource;
+use mcp_types::ResourceTemplate as McpResourceTemplate;
+use mcp_types::SamplingMessage;
 use mcp_types::Tool as McpTool;
 use serde::Deserialize;
 use serde::Serialize;
@@ -35,6 +41,8 @@ pub const USER_INSTRUCTIONS_OPEN_TAG: &str = "<user_instructions>";
 pub const USER_INSTRUCTIONS_CLOSE_TAG: &str = "</user_instructions>";
 pub const ENVIRONMENT_CONTEXT_OPEN_TAG: &str = "<environment_context>";
 pub const ENVIRONMENT_CONTEXT_CLOSE_TAG: &str = "</environment_context>";
+pub const PINNED_CONTEXT_OPEN_TAG: &str = "<pinned_context>";
+pub const PINNED_CONTEXT_CLOSE_TAG: &str = "</pinned_context>";
 pub const USER_MESSAGE_BEGIN: &str = "## My request for Codex:";
 
 /// Submission Queue Entry - requests from user
@@ -143,6 +151,24 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Approve a `sampling/createMessage` request from an MCP server
+    McpSamplingApproval {
+        /// The id of the `McpSamplingApprovalRequest` event we are approving
+        id: String,
+        /// The user's decision in response to the request.
+        decision: ReviewDecision,
+    },
+
+    /// Answer a `CostBudgetApprovalRequest`. `Approved` allows another
+    /// budget's worth of spending, `ApprovedForSession` lifts the budget, and
+    /// anything else stops the task.
+    CostBudgetApproval {
+        /// The id of the `CostBudgetApprovalRequest` event we are answering
+        id: String,
+        /// The user's decision in response to the request.
+        decision: ReviewDecision,
+    },
+
     /// Append an entry to the persistent cross-session message history.
     ///
     /// Note the entry is not guaranteed to be logged if the user has
@@ -163,13 +189,35 @@ pub enum Op {
     /// Reply is delivered via `EventMsg::McpListToolsResponse`.
     ListMcpTools,
 
+    /// Request the resources and resource templates exposed by MCP servers.
+    /// Reply is delivered via `EventMsg::McpListResourcesResponse`.
+    ListMcpResources,
+
+    /// Re-read `mcp_servers` from `config.toml`, with the session's `-c`
+    /// overrides applied, and start, stop or restart MCP servers to match it. Reply is delivered via
+    /// `EventMsg::McpServersReloaded`.
+    ReloadMcpServers,
+
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
-    /// Request the agent to summarize the current conversation context.
-    /// The agent will use its existing context (either conversation history or previous response id)
-    /// to generate a summary which will be returned as an AgentMessage event.
-    Compact,
+    /// Request the agent to compact the current conversation context.
+    /// With the default strategy the agent uses its existing context (either conversation
+    /// history or previous response id) to generate a summary which will be returned as an
+    /// AgentMessage event.
+    Compact {
+        /// Strategy to use instead of the configured `compaction_strategy`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        strategy: Option<CompactionStrategy>,
+    },
+
+    /// Keep a message or file verbatim in the history whenever it is
+    /// compacted. Reply is delivered via `EventMsg::PinnedItems`.
+    PinItem { item: PinnedItem },
+
+    /// Stop keeping a previously pinned item. Reply is delivered via
+    /// `EventMsg::PinnedItems`.
+    UnpinItem { item: PinnedItem },
 
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
@@ -215,9 +263,23 @@ pub enum SandboxPolicy {
     #[serde(rename = "danger-full-access")]
     DangerFullAccess,
 
-    /// Read-only access to the entire file-system.
+    /// Read-only access to the file-system, minus `deny_read`.
     #[serde(rename = "read-only")]
-    ReadOnly,
+    ReadOnly {
+        /// When non-empty, reads are limited to these folders, the cwd and the
+        /// system folders commands need to run. Empty means the entire
+        /// file-system is readable.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        readable_roots: Vec<PathBuf>,
+
+        /// Paths that must never be read from within the sandbox. Like in
+        /// `.gitignore`, an entry without a `/` (such as `.env`) names files
+        /// and folders in every folder; other entries are paths, which may
+        /// start with `~/` or be relative to the cwd. Defaults to
+        /// [`DEFAULT_DENY_READ`].
+        #[serde(default = "default_deny_read")]
+        deny_read: Vec<PathBuf>,
+    },
 
     /// Same as `ReadOnly` but additionally grants write access to the current
     /// working directory ("workspace").
@@ -228,11 +290,25 @@ pub enum SandboxPolicy {
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         writable_roots: Vec<PathBuf>,
 
+        /// Same as for `ReadOnly`.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        readable_roots: Vec<PathBuf>,
+
+        /// Same as for `ReadOnly`.
+        #[serde(default = "default_deny_read")]
+        deny_read: Vec<PathBuf>,
+
         /// When set to `true`, outbound network access is allowed. `false` by
         /// default.
         #[serde(default)]
         network_access: bool,
 
+        /// Domains that commands may reach through the Codex network proxy
+        /// while `network_access` is `false`. Entries are host names; a
+        /// leading `*.` matches every subdomain.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        allowed_domains: Vec<String>,
+
         /// When set to `true`, will NOT include the per-user `TMPDIR`
         /// environment variable among the default writable roots. Defaults to
         /// `false`.
@@ -246,6 +322,14 @@ pub enum SandboxPolicy {
     },
 }
 
+/// Credentials that sandboxed commands must not read unless `deny_read` is
+/// configured otherwise.
+pub const DEFAULT_DENY_READ: &[&str] = &["~/.ssh", "~/.aws", "~/.config/gcloud", ".env"];
+
+fn default_deny_read() -> Vec<PathBuf> {
+    DEFAULT_DENY_READ.iter().map(PathBuf::from).collect()
+}
+
 /// A writable root path accompanied by a list of subpaths that should remain
 /// read‑only even when the root is writable. This is primarily used to ensure
 /// top‑level VCS metadata directories (e.g. `.git`) under a writable root are
@@ -277,6 +361,43 @@ impl WritableRoot {
     }
 }
 
+/// The `deny_read` entries of a policy, resolved for one cwd.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DenyRead {
+    cwd: PathBuf,
+
+    /// Absolute paths, with their symlinks resolved as well.
+    paths: Vec<PathBuf>,
+
+    /// Names denied in every folder.
+    names: Vec<String>,
+}
+
+impl DenyRead {
+    /// Returns `true` when `path` lies inside a denied path or goes through a
+    /// file or folder with a denied name. Relative paths are resolved against
+    /// the cwd, and existing paths are checked with their symlinks resolved
+    /// as well.
+    pub fn is_path_denied(&self, path: &Path) -> bool {
+        if self.paths.is_empty() && self.names.is_empty() {
+            return false;
+        }
+        let path = self.cwd.join(path);
+        self.covers(&path)
+            || path
+                .canonicalize()
+                .is_ok_and(|canonical| self.covers(&canonical))
+    }
+
+    fn covers(&self, path: &Path) -> bool {
+        self.paths.iter().any(|denied| path.starts_with(denied))
+            || path.components().any(|component| {
+                matches!(component, Component::Normal(part)
+                    if self.names.iter().any(|name| part == name.as_str()))
+            })
+    }
+}
+
 impl FromStr for SandboxPolicy {
     type Err = serde_json::Error;
 
@@ -286,9 +407,13 @@ impl FromStr for SandboxPolicy {
 }
 
 impl SandboxPolicy {
-    /// Returns a policy with read-only disk access and no network.
+    /// Returns a policy with read-only disk access, minus the default
+    /// `deny_read` paths, and no network.
     pub fn new_read_only_policy() -> Self {
-        SandboxPolicy::ReadOnly
+        SandboxPolicy::ReadOnly {
+            readable_roots: vec![],
+            deny_read: default_deny_read(),
+        }
     }
 
     /// Returns a policy that can read the entire disk, but can only write to
@@ -297,21 +422,36 @@ impl SandboxPolicy {
     pub fn new_workspace_write_policy() -> Self {
         SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
+            readable_roots: vec![],
+            deny_read: default_deny_read(),
             network_access: false,
+            allowed_domains: vec![],
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
         }
     }
 
-    /// Always returns `true`; restricting read access is not supported.
+    /// Whether every path on disk may be read, i.e. there are neither
+    /// `readable_roots` nor `deny_read` entries.
     pub fn has_full_disk_read_access(&self) -> bool {
-        true
+        match self {
+            SandboxPolicy::DangerFullAccess => true,
+            SandboxPolicy::ReadOnly {
+                readable_roots,
+                deny_read,
+            }
+            | SandboxPolicy::WorkspaceWrite {
+                readable_roots,
+                deny_read,
+                ..
+            } => readable_roots.is_empty() && deny_read.is_empty(),
+        }
     }
 
     pub fn has_full_disk_write_access(&self) -> bool {
         match self {
             SandboxPolicy::DangerFullAccess => true,
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly { .. } => false,
             SandboxPolicy::WorkspaceWrite { .. } => false,
         }
     }
@@ -319,23 +459,107 @@ impl SandboxPolicy {
     pub fn has_full_network_access(&self) -> bool {
         match self {
             SandboxPolicy::DangerFullAccess => true,
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly { .. } => false,
             SandboxPolicy::WorkspaceWrite { network_access, .. } => *network_access,
         }
     }
 
+    /// Returns the domains that commands may reach through the Codex network
+    /// proxy. Empty unless the policy blocks the network but lists
+    /// `allowed_domains`.
+    pub fn get_allowed_domains(&self) -> &[String] {
+        match self {
+            SandboxPolicy::WorkspaceWrite {
+                network_access: false,
+                allowed_domains,
+                ..
+            } => allowed_domains,
+            _ => &[],
+        }
+    }
+
+    /// Returns the folders reads are limited to, tailored to the current
+    /// working directory, or `None` if the entire file-system is readable.
+    /// Writable roots are always readable and are not repeated here.
+    pub fn get_readable_roots_with_cwd(&self, cwd: &Path) -> Option<Vec<PathBuf>> {
+        match self {
+            SandboxPolicy::DangerFullAccess => None,
+            SandboxPolicy::ReadOnly { readable_roots, .. }
+            | SandboxPolicy::WorkspaceWrite { readable_roots, .. } => {
+                if readable_roots.is_empty() {
+                    return None;
+                }
+                let mut roots: Vec<PathBuf> = readable_roots
+                    .iter()
+                    .map(|root| resolve_policy_path(root, cwd))
+                    .collect();
+                roots.push(cwd.to_path_buf());
+                Some(roots)
+            }
+        }
+    }
+
+    /// Returns the absolute paths that must not be read, resolving `~/` and
+    /// cwd-relative entries. Names, which apply in every folder, are returned
+    /// by [`SandboxPolicy::get_deny_read_names`] instead.
+    pub fn get_deny_read_paths_with_cwd(&self, cwd: &Path) -> Vec<PathBuf> {
+        self.deny_read()
+            .iter()
+            .filter(|entry| deny_read_name(entry).is_none())
+            .map(|path| resolve_policy_path(path, cwd))
+            .collect()
+    }
+
+    /// Returns the `deny_read` entries that are a single file name, such as
+    /// `.env`, and deny every file or folder of that name.
+    pub fn get_deny_read_names(&self) -> Vec<String> {
+        self.deny_read()
+            .iter()
+            .filter_map(|entry| deny_read_name(entry))
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    /// Returns `deny_read` resolved for `cwd`, for checking the paths that
+    /// Codex reads itself on behalf of the model.
+    pub fn get_deny_read_with_cwd(&self, cwd: &Path) -> DenyRead {
+        let mut paths = self.get_deny_read_paths_with_cwd(cwd);
+        let canonical_paths: Vec<PathBuf> = paths
+            .iter()
+            .filter_map(|path| path.canonicalize().ok())
+            .filter(|canonical| !paths.contains(canonical))
+            .collect();
+        paths.extend(canonical_paths);
+        DenyRead {
+            cwd: cwd.to_path_buf(),
+            paths,
+            names: self.get_deny_read_names(),
+        }
+    }
+
+    fn deny_read(&self) -> &[PathBuf] {
+        match self {
+            SandboxPolicy::DangerFullAccess => &[],
+            SandboxPolicy::ReadOnly { deny_read, .. }
+            | SandboxPolicy::WorkspaceWrite { deny_read, .. } => deny_read,
+        }
+    }
+
     /// Returns the list of writable roots (tailored to the current working
     /// directory) together with subpaths that should remain read‑only under
     /// each writable root.
     pub fn get_writable_roots_with_cwd(&self, cwd: &Path) -> Vec<WritableRoot> {
         match self {
             SandboxPolicy::DangerFullAccess => Vec::new(),
-            SandboxPolicy::ReadOnly => Vec::new(),
+            SandboxPolicy::ReadOnly { .. } => Vec::new(),
             SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                readable_roots: _,
+                deny_read: _,
                 network_access: _,
+                allowed_domains: _,
             } => {
                 // Start from explicitly configured writable roots.
                 let mut roots: Vec<PathBuf> = writable_roots.clone();
@@ -387,6 +611,27 @@ impl SandboxPolicy {
     }
 }
 
+/// Expands a leading `~/` to `$HOME` and resolves relative paths against `cwd`.
+fn resolve_policy_path(path: &Path, cwd: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~")
+        && let Some(home) = std::env::var_os("HOME")
+    {
+        return PathBuf::from(home).join(rest);
+    }
+    // Collecting the components drops the `.` of entries like `./.env`.
+    cwd.join(path).components().collect()
+}
+
+/// Returns the name a `deny_read` entry denies in every folder, when the
+/// entry is a single file name.
+fn deny_read_name(entry: &Path) -> Option<&OsStr> {
+    let mut components = entry.components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(name)), None) if name != "~" => Some(name),
+        _ => None,
+    }
+}
+
 /// User input
 #[non_exhaustive]
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -484,6 +729,14 @@ pub enum EventMsg {
 
     ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent),
 
+    /// An MCP server asked Codex to sample the model on its behalf. Answer
+    /// with `Op::McpSamplingApproval`.
+    McpSamplingApprovalRequest(McpSamplingApprovalRequestEvent),
+
+    /// The session cost reached `session_budget_usd` and the agent waits for
+    /// an `Op::CostBudgetApproval` before making more model requests.
+    CostBudgetApprovalRequest(CostBudgetApprovalRequestEvent),
+
     BackgroundEvent(BackgroundEventEvent),
 
     /// Notification that a model stream experienced an error or disconnect
@@ -505,9 +758,18 @@ pub enum EventMsg {
     /// List of MCP tools available to the agent.
     McpListToolsResponse(McpListToolsResponseEvent),
 
+    /// List of MCP resources and resource templates available to the agent.
+    McpListResourcesResponse(McpListResourcesResponseEvent),
+
+    /// MCP servers were reloaded; carries the tools available afterwards.
+    McpServersReloaded(McpServersReloadedEvent),
+
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// The items currently pinned in the session.
+    PinnedItems(PinnedItemsEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
     TurnAborted(TurnAbortedEvent),
@@ -579,7 +841,12 @@ impl TokenUsageInfo {
         }
 
         let mut info = match info {
-            Some(info) => info.clone(),
+            // The window follows the model serving requests, which a failover
+            // to a `model_fallbacks` entry changes.
+            Some(info) => Self {
+                model_context_window: model_context_window.or(info.model_context_window),
+                ..info.clone()
+            },
             None => Self {
                 total_token_usage: TokenUsage::default(),
                 last_token_usage: TokenUsage::default(),
@@ -627,6 +894,10 @@ impl TokenUsageInfo {
 pub struct TokenCountEvent {
     pub info: Option<TokenUsageInfo>,
     pub rate_limits: Option<RateLimitSnapshot>,
+    /// Estimated cost of the session so far in USD. Unset until a request to
+    /// a model with a known price completes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -765,6 +1036,8 @@ pub enum InputMessageKind {
     UserInstructions,
     /// XML-wrapped environment context (<environment_context>...)
     EnvironmentContext,
+    /// XML-wrapped pinned item re-inserted by compaction (<pinned_context>...)
+    PinnedContext,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -793,6 +1066,10 @@ where
             && ends_with_ignore_ascii_case(trimmed, USER_INSTRUCTIONS_CLOSE_TAG)
         {
             InputMessageKind::UserInstructions
+        } else if starts_with_ignore_ascii_case(trimmed, PINNED_CONTEXT_OPEN_TAG)
+            && ends_with_ignore_ascii_case(trimmed, PINNED_CONTEXT_CLOSE_TAG)
+        {
+            InputMessageKind::PinnedContext
         } else {
             InputMessageKind::Plain
         }
@@ -1016,6 +1293,13 @@ pub enum RolloutItem {
 #[derive(Serialize, Deserialize, Clone, Debug, TS)]
 pub struct CompactedItem {
     pub message: String,
+    /// How the history was compacted. Rollouts written before strategies
+    /// existed were always summarized.
+    #[serde(default)]
+    pub strategy: CompactionStrategy,
+    /// Pinned items as they were inserted into the compacted history.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned: Vec<String>,
 }
 
 impl From<CompactedItem> for ResponseItem {
@@ -1141,6 +1425,62 @@ pub struct ExecCommandEndEvent {
     pub duration: Duration,
     /// Formatted output from the command, as seen by the model.
     pub formatted_output: String,
+    /// Operations the sandbox blocked, including those of a sandboxed attempt
+    /// that failed before the user approved running the command without it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sandbox_denials: Vec<SandboxDenial>,
+}
+
+/// An operation of a command that the sandbox blocked.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, TS)]
+pub struct SandboxDenial {
+    pub operation: SandboxDeniedOperation,
+    /// The path, host or socket family the operation was aimed at.
+    pub target: String,
+}
+
+impl SandboxDenial {
+    /// Parses the `"<operation> <target>"` form produced by [`fmt::Display`]
+    /// without its `denied: ` prefix.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (operation, target) = text.trim().split_once(' ')?;
+        let operation = match operation {
+            "read" => SandboxDeniedOperation::Read,
+            "write" => SandboxDeniedOperation::Write,
+            "network" => SandboxDeniedOperation::Network,
+            _ => return None,
+        };
+        Some(Self {
+            operation,
+            target: target.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for SandboxDenial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "denied: {} {}", self.operation, self.target)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum SandboxDeniedOperation {
+    Read,
+    Write,
+    Network,
+}
+
+impl fmt::Display for SandboxDeniedOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            SandboxDeniedOperation::Read => "read",
+            SandboxDeniedOperation::Write => "write",
+            SandboxDeniedOperation::Network => "network",
+        };
+        f.write_str(text)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -1182,6 +1522,9 @@ pub struct ExecApprovalRequestEvent {
     /// Optional human-readable reason for the approval (e.g. retry without sandbox).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
+    /// When asking to retry without the sandbox, what the sandbox blocked.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sandbox_denials: Vec<SandboxDenial>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -1197,6 +1540,27 @@ pub struct ApplyPatchApprovalRequestEvent {
     pub grant_root: Option<PathBuf>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct McpSamplingApprovalRequestEvent {
+    /// Name of the MCP server that issued the request.
+    pub server: String,
+    /// System prompt requested by the server, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Messages the server wants sent to the model.
+    pub messages: Vec<SamplingMessage>,
+    /// Maximum number of tokens the server asked to be sampled.
+    pub max_tokens: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct CostBudgetApprovalRequestEvent {
+    /// Estimated cost of the session so far in USD.
+    pub cost_usd: f64,
+    /// Spending limit that was reached, in USD.
+    pub budget_usd: f64,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct BackgroundEventEvent {
     pub message: String,
@@ -1251,6 +1615,27 @@ pub struct McpListToolsResponseEvent {
     pub auth_statuses: std::collections::HashMap<String, McpAuthStatus>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct McpListResourcesResponseEvent {
+    /// Server name -> resources listed by that server.
+    pub resources: std::collections::HashMap<String, Vec<McpResource>>,
+    /// Server name -> resource templates listed by that server.
+    pub resource_templates: std::collections::HashMap<String, Vec<McpResourceTemplate>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct McpServersReloadedEvent {
+    /// Servers that were started because they are newly configured.
+    pub started: Vec<String>,
+    /// Servers that were restarted because their configuration changed or
+    /// they stopped responding.
+    pub restarted: Vec<String>,
+    /// Servers that were shut down because they were removed or disabled.
+    pub stopped: Vec<String>,
+    /// Fully qualified tool name -> tool definition, after the reload.
+    pub tools: std::collections::HashMap<String, McpTool>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "snake_case")]
 #[ts(rename_all = "snake_case")]
@@ -1273,6 +1658,22 @@ impl fmt::Display for McpAuthStatus {
     }
 }
 
+/// Context the user asked to keep across compaction.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PinnedItem {
+    /// Text such as a spec or an error log from the conversation.
+    Message { text: String },
+    /// A file, read again each time the history is compacted. Relative paths
+    /// are resolved against the session's working directory when pinned.
+    File { path: PathBuf },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct PinnedItemsEvent {
+    pub items: Vec<PinnedItem>,
+}
+
 /// Response payload for `Op::ListCustomPrompts`.
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct ListCustomPromptsResponseEvent {
@@ -1317,6 +1718,11 @@ pub enum ReviewDecision {
     /// remainder of the session.
     ApprovedForSession,
 
+    /// User has approved this command and wants to automatically approve any
+    /// future command matching the same argv pattern (e.g. `cargo test *`) in
+    /// this project. The pattern is persisted across sessions.
+    ApprovedForProject,
+
     /// User has denied this command and the agent should not execute it, but
     /// it should continue the session and try something else.
     #[default]
@@ -1422,4 +1828,61 @@ mod tests {
         assert_eq!(deserialized, event);
         Ok(())
     }
+
+    #[test]
+    fn sandbox_policy_defaults_deny_read_and_resolves_paths() -> Result<()> {
+        let policy: SandboxPolicy = serde_json::from_value(json!({ "mode": "read-only" }))?;
+        assert_eq!(policy, SandboxPolicy::new_read_only_policy());
+        assert!(!policy.has_full_disk_read_access());
+
+        let cwd = Path::new("/work/project");
+        let policy: SandboxPolicy = serde_json::from_value(json!({
+            "mode": "workspace-write",
+            "readable_roots": ["/srv/shared"],
+            "deny_read": [".env", "/etc/secrets", "./local.key"],
+        }))?;
+        assert_eq!(
+            policy.get_deny_read_paths_with_cwd(cwd),
+            vec![
+                PathBuf::from("/etc/secrets"),
+                PathBuf::from("/work/project/local.key")
+            ]
+        );
+        assert_eq!(policy.get_deny_read_names(), vec![".env".to_string()]);
+        let deny_read = policy.get_deny_read_with_cwd(cwd);
+        assert!(deny_read.is_path_denied(Path::new(".env")));
+        assert!(deny_read.is_path_denied(Path::new("/work/other/app/.env")));
+        assert!(deny_read.is_path_denied(Path::new("/etc/secrets/key")));
+        assert!(deny_read.is_path_denied(Path::new("local.key")));
+        assert!(!deny_read.is_path_denied(Path::new("app/.envrc")));
+        assert!(!deny_read.is_path_denied(Path::new("sub/local.key")));
+        assert_eq!(
+            policy.get_readable_roots_with_cwd(cwd),
+            Some(vec![
+                PathBuf::from("/srv/shared"),
+                PathBuf::from("/work/project")
+            ])
+        );
+
+        let policy: SandboxPolicy =
+            serde_json::from_value(json!({ "mode": "read-only", "deny_read": [] }))?;
+        assert!(policy.has_full_disk_read_access());
+        assert_eq!(policy.get_readable_roots_with_cwd(cwd), None);
+        Ok(())
+    }
+
+    #[test]
+    fn sandbox_denial_round_trips_through_display() {
+        let denial = SandboxDenial {
+            operation: SandboxDeniedOperation::Write,
+            target: "/home/x/.cache/my dir/foo".to_string(),
+        };
+        let text = denial.to_string();
+        assert_eq!(text, "denied: write /home/x/.cache/my dir/foo");
+        assert_eq!(
+            SandboxDenial::parse(text.trim_start_matches("denied: ")),
+            Some(denial)
+        );
+        assert_eq!(SandboxDenial::parse("chmod /etc/passwd"), None);
+    }
 }
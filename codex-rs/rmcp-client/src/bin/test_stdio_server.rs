@@ -7,9 +7,13 @@ use rmcp::ServiceExt;
 use rmcp::handler::server::ServerHandler;
 use rmcp::model::CallToolRequestParam;
 use rmcp::model::CallToolResult;
+use rmcp::model::Content;
+use rmcp::model::CreateMessageRequestParam;
 use rmcp::model::JsonObject;
 use rmcp::model::ListToolsResult;
 use rmcp::model::PaginatedRequestParam;
+use rmcp::model::Role;
+use rmcp::model::SamplingMessage;
 use rmcp::model::ServerCapabilities;
 use rmcp::model::ServerInfo;
 use rmcp::model::Tool;
@@ -26,7 +30,7 @@ pub fn stdio() -> (tokio::io::Stdin, tokio::io::Stdout) {
 }
 impl TestToolServer {
     fn new() -> Self {
-        let tools = vec![Self::echo_tool()];
+        let tools = vec![Self::echo_tool(), Self::summarize_tool()];
         Self {
             tools: Arc::new(tools),
         }
@@ -51,6 +55,25 @@ impl TestToolServer {
             Arc::new(schema),
         )
     }
+
+    fn summarize_tool() -> Tool {
+        #[expect(clippy::expect_used)]
+        let schema: JsonObject = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {
+                "text": { "type": "string" }
+            },
+            "required": ["text"],
+            "additionalProperties": false
+        }))
+        .expect("summarize tool schema should deserialize");
+
+        Tool::new(
+            Cow::Borrowed("summarize"),
+            Cow::Borrowed("Summarize the provided text by sampling the client's model."),
+            Arc::new(schema),
+        )
+    }
 }
 
 #[derive(Deserialize)]
@@ -60,6 +83,11 @@ struct EchoArgs {
     env_var: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct SummarizeArgs {
+    text: String,
+}
+
 impl ServerHandler for TestToolServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -88,7 +116,7 @@ impl ServerHandler for TestToolServer {
     async fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+        context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         match request.name.as_ref() {
             "echo" => {
@@ -118,6 +146,54 @@ impl ServerHandler for TestToolServer {
                     meta: None,
                 })
             }
+            "summarize" => {
+                let args: SummarizeArgs = match request.arguments {
+                    Some(arguments) => serde_json::from_value(serde_json::Value::Object(
+                        arguments.into_iter().collect(),
+                    ))
+                    .map_err(|err| McpError::invalid_params(err.to_string(), None))?,
+                    None => {
+                        return Err(McpError::invalid_params(
+                            "missing arguments for summarize tool",
+                            None,
+                        ));
+                    }
+                };
+
+                let sampled = context
+                    .peer
+                    .create_message(CreateMessageRequestParam {
+                        messages: vec![SamplingMessage {
+                            role: Role::User,
+                            content: Content::text(args.text),
+                        }],
+                        model_preferences: None,
+                        system_prompt: Some("Summarize the user's text.".to_string()),
+                        include_context: None,
+                        temperature: None,
+                        max_tokens: 100,
+                        stop_sequences: None,
+                        metadata: None,
+                    })
+                    .await
+                    .map_err(|err| McpError::internal_error(err.to_string(), None))?;
+                let summary = sampled
+                    .message
+                    .content
+                    .as_text()
+                    .map(|text| text.text.clone())
+                    .unwrap_or_default();
+
+                Ok(CallToolResult {
+                    content: Vec::new(),
+                    structured_content: Some(json!({
+                        "summary": summary,
+                        "model": sampled.model,
+                    })),
+                    is_error: Some(false),
+                    meta: None,
+                })
+            }
             other => Err(McpError::invalid_params(
                 format!("unknown tool: {other}"),
                 None,
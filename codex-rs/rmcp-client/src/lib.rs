@@ -7,6 +7,7 @@ mod rmcp_client;
 mod utils;
 
 pub use auth_status::determine_streamable_http_auth_status;
+pub use codex_mcp_client::SamplingHandler;
 pub use codex_protocol::protocol::McpAuthStatus;
 pub use oauth::OAuthCredentialsStoreMode;
 pub use oauth::StoredOAuthTokens;
@@ -1,9 +1,13 @@
+use codex_mcp_client::SamplingHandler;
 use rmcp::ClientHandler;
 use rmcp::RoleClient;
 use rmcp::model::CancelledNotificationParam;
 use rmcp::model::ClientInfo;
 use rmcp::model::CreateElicitationRequestParam;
 use rmcp::model::CreateElicitationResult;
+use rmcp::model::CreateMessageRequestMethod;
+use rmcp::model::CreateMessageRequestParam;
+use rmcp::model::CreateMessageResult;
 use rmcp::model::ElicitationAction;
 use rmcp::model::LoggingLevel;
 use rmcp::model::LoggingMessageNotificationParam;
@@ -16,14 +20,21 @@ use tracing::error;
 use tracing::info;
 use tracing::warn;
 
-#[derive(Debug, Clone)]
+use crate::utils::convert_to_mcp;
+use crate::utils::convert_to_rmcp;
+
+#[derive(Clone)]
 pub(crate) struct LoggingClientHandler {
     client_info: ClientInfo,
+    sampling_handler: Option<SamplingHandler>,
 }
 
 impl LoggingClientHandler {
-    pub(crate) fn new(client_info: ClientInfo) -> Self {
-        Self { client_info }
+    pub(crate) fn new(client_info: ClientInfo, sampling_handler: Option<SamplingHandler>) -> Self {
+        Self {
+            client_info,
+            sampling_handler,
+        }
     }
 }
 
@@ -44,6 +55,25 @@ impl ClientHandler for LoggingClientHandler {
         })
     }
 
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, rmcp::ErrorData> {
+        let Some(handler) = &self.sampling_handler else {
+            return Err(rmcp::ErrorData::method_not_found::<
+                CreateMessageRequestMethod,
+            >());
+        };
+        let params = convert_to_mcp(params)
+            .map_err(|err| rmcp::ErrorData::invalid_params(err.to_string(), None))?;
+        let result = handler(params)
+            .await
+            .map_err(|message| rmcp::ErrorData::internal_error(message, None))?;
+        convert_to_rmcp(result)
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))
+    }
+
     async fn on_cancelled(
         &self,
         params: CancelledNotificationParam,
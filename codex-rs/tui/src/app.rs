@@ -2,6 +2,7 @@ use crate::app_backtrack::BacktrackState;
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::ApprovalRequest;
+use crate::bottom_pane::sampling_request_lines;
 use crate::chatwidget::ChatWidget;
 use crate::diff_render::DiffSummary;
 use crate::exec_command::strip_bash_lc_and_escape;
@@ -18,6 +19,7 @@ use codex_core::ConversationManager;
 use codex_core::config::Config;
 use codex_core::config::persist_model_selection;
 use codex_core::model_family::find_family_for_model;
+use codex_core::protocol::Op;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
@@ -133,6 +135,25 @@ impl App {
                     resumed.session_configured,
                 )
             }
+            ResumeSelection::Fork {
+                path,
+                nth_user_message,
+            } => {
+                let forked = conversation_manager
+                    .fork_conversation(nth_user_message, config.clone(), path.clone())
+                    .await
+                    .wrap_err_with(|| format!("Failed to fork session from {}", path.display()))?;
+                let init = crate::chatwidget::ChatWidgetInit {
+                    config: config.clone(),
+                    frame_requester: tui.frame_requester(),
+                    app_event_tx: app_event_tx.clone(),
+                    initial_prompt: initial_prompt.clone(),
+                    initial_images: initial_images.clone(),
+                    enhanced_keys_supported,
+                    auth_manager: auth_manager.clone(),
+                };
+                ChatWidget::new_from_existing(init, forked.conversation, forked.session_configured)
+            }
         };
 
         let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
@@ -372,6 +393,23 @@ impl App {
             AppEvent::UpdateSandboxPolicy(policy) => {
                 self.chat_widget.set_sandbox_policy(policy);
             }
+            AppEvent::ReloadMcpServers => {
+                // Keep the servers listed by `/mcp` in step with what the
+                // session is about to load.
+                match self.config.reload().await {
+                    Ok(config) => {
+                        let servers = config.mcp_servers;
+                        self.config.mcp_servers = servers.clone();
+                        self.chat_widget.set_mcp_servers(servers);
+                        self.chat_widget.submit_op(Op::ReloadMcpServers);
+                    }
+                    Err(err) => {
+                        self.chat_widget.add_error_message(format!(
+                            "Failed to load MCP server configuration: {err}"
+                        ));
+                    }
+                }
+            }
             AppEvent::OpenReviewBranchPicker(cwd) => {
                 self.chat_widget.show_review_branch_picker(&cwd).await;
             }
@@ -399,6 +437,19 @@ impl App {
                         "E X E C".to_string(),
                     ));
                 }
+                ApprovalRequest::McpSampling {
+                    system_prompt,
+                    messages,
+                    ..
+                } => {
+                    let _ = tui.enter_alt_screen();
+                    self.overlay = Some(Overlay::new_static_with_lines(
+                        sampling_request_lines(system_prompt.as_deref(), &messages),
+                        "S A M P L I N G".to_string(),
+                    ));
+                }
+                // The prompt already shows everything there is to know.
+                ApprovalRequest::CostBudget { .. } => {}
             },
         }
         Ok(true)
@@ -3,6 +3,8 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::app::App;
+use crate::exec_cell::ExecCell;
+use crate::history_cell::AgentMessageCell;
 use crate::history_cell::CompositeHistoryCell;
 use crate::history_cell::UserHistoryCell;
 use crate::pager_overlay::Overlay;
@@ -15,6 +17,17 @@ use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 
+/// What to pin from the turn selected in the backtrack preview.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PinTarget {
+    /// The user message itself.
+    UserMessage,
+    /// The agent's reply to it.
+    AgentReply,
+    /// The last command the agent ran in reply to it, with its output.
+    CommandOutput,
+}
+
 /// Aggregates all backtrack-related state used by the App.
 #[derive(Default)]
 pub(crate) struct BacktrackState {
@@ -32,7 +45,9 @@ pub(crate) struct BacktrackState {
 
 impl App {
     /// Route overlay events when transcript overlay is active.
-    /// - If backtrack preview is active: Esc steps selection; Enter confirms.
+    /// - If backtrack preview is active: Esc steps selection; Enter confirms;
+    ///   p, a and o pin the selected message, the reply to it, or the output
+    ///   of the last command in that reply.
     /// - Otherwise: Esc begins preview; all other events forward to overlay.
     ///   interactions (Esc to step target, Enter to confirm) and overlay lifecycle.
     pub(crate) async fn handle_backtrack_overlay_event(
@@ -58,6 +73,19 @@ impl App {
                     self.overlay_confirm_backtrack(tui);
                     Ok(true)
                 }
+                TuiEvent::Key(KeyEvent {
+                    code: KeyCode::Char(c @ ('p' | 'a' | 'o')),
+                    kind: KeyEventKind::Press,
+                    ..
+                }) => {
+                    let target = match c {
+                        'p' => PinTarget::UserMessage,
+                        'a' => PinTarget::AgentReply,
+                        _ => PinTarget::CommandOutput,
+                    };
+                    self.overlay_pin_selection(target);
+                    Ok(true)
+                }
                 // Catchall: forward any other events to the overlay widget.
                 _ => {
                     self.overlay_forward_event(tui, event)?;
@@ -235,6 +263,18 @@ impl App {
         self.reset_backtrack_state();
     }
 
+    /// Handle p, a or o in overlay backtrack preview: pin part of the selected
+    /// turn so it survives compaction. The overlay stays open.
+    fn overlay_pin_selection(&mut self, target: PinTarget) {
+        if let Some(text) = pin_text(
+            &self.transcript_cells,
+            self.backtrack.nth_user_message,
+            target,
+        ) {
+            self.chat_widget.pin_message(text);
+        }
+    }
+
     /// Handle Esc in overlay backtrack preview: step selection if armed, else forward.
     fn overlay_step_backtrack(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         if self.backtrack.base_id.is_some() {
@@ -382,6 +422,37 @@ fn nth_user_position(
         .find_map(|(i, idx)| (i == nth).then_some(idx))
 }
 
+/// Text to pin for `target` in the turn started by the `nth` user message.
+fn pin_text(
+    cells: &[Arc<dyn crate::history_cell::HistoryCell>],
+    nth: usize,
+    target: PinTarget,
+) -> Option<String> {
+    let start = nth_user_position(cells, nth)?;
+    let end = nth_user_position(cells, nth + 1).unwrap_or(cells.len());
+    let turn = &cells[start..end];
+    match target {
+        PinTarget::UserMessage => turn
+            .first()
+            .and_then(|cell| cell.as_any().downcast_ref::<UserHistoryCell>())
+            .map(|cell| cell.message.clone()),
+        PinTarget::AgentReply => {
+            let reply = turn
+                .iter()
+                .filter_map(|cell| cell.as_any().downcast_ref::<AgentMessageCell>())
+                .map(AgentMessageCell::plain_text)
+                .collect::<Vec<_>>()
+                .join("\n");
+            (!reply.trim().is_empty()).then_some(reply)
+        }
+        PinTarget::CommandOutput => turn
+            .iter()
+            .rev()
+            .filter_map(|cell| cell.as_any().downcast_ref::<ExecCell>())
+            .find_map(ExecCell::last_output_text),
+    }
+}
+
 fn user_positions_iter(
     cells: &[Arc<dyn crate::history_cell::HistoryCell>],
 ) -> impl Iterator<Item = usize> + '_ {
@@ -404,8 +475,10 @@ fn user_positions_iter(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::history_cell::AgentMessageCell;
+    use crate::exec_cell::CommandOutput;
+    use crate::exec_cell::ExecCall;
     use crate::history_cell::HistoryCell;
+    use pretty_assertions::assert_eq;
     use ratatui::prelude::Line;
     use std::sync::Arc;
 
@@ -500,4 +573,50 @@ mod tests {
             .collect();
         assert_eq!(between_text, "  between");
     }
+
+    #[test]
+    fn pin_text_picks_the_message_reply_or_command_of_the_selected_turn() {
+        let exec = |command: &str, output: &str| {
+            ExecCell::new(ExecCall {
+                call_id: command.to_string(),
+                command: vec![command.to_string()],
+                parsed: Vec::new(),
+                output: Some(CommandOutput {
+                    exit_code: 0,
+                    stdout: output.to_string(),
+                    stderr: String::new(),
+                    formatted_output: output.to_string(),
+                    sandbox_denials: Vec::new(),
+                }),
+                start_time: None,
+                duration: None,
+            })
+        };
+        let cells: Vec<Arc<dyn HistoryCell>> = vec![
+            Arc::new(UserHistoryCell {
+                message: "first".to_string(),
+            }),
+            Arc::new(exec("make", "error: E0425")),
+            Arc::new(AgentMessageCell::new(vec![Line::from("It fails.")], true)),
+            Arc::new(AgentMessageCell::new(vec![Line::from("Fixing it.")], false)),
+            Arc::new(UserHistoryCell {
+                message: "second".to_string(),
+            }),
+        ];
+
+        assert_eq!(
+            pin_text(&cells, 0, PinTarget::UserMessage).as_deref(),
+            Some("first")
+        );
+        assert_eq!(
+            pin_text(&cells, 0, PinTarget::AgentReply).as_deref(),
+            Some("It fails.\nFixing it.")
+        );
+        assert_eq!(
+            pin_text(&cells, 0, PinTarget::CommandOutput).as_deref(),
+            Some("$ make\nerror: E0425")
+        );
+        assert_eq!(pin_text(&cells, 1, PinTarget::AgentReply), None);
+        assert_eq!(pin_text(&cells, 1, PinTarget::CommandOutput), None);
+    }
 }
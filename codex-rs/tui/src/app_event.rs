@@ -73,6 +73,10 @@ pub(crate) enum AppEvent {
     /// Update the current sandbox policy in the running app and widget.
     UpdateSandboxPolicy(SandboxPolicy),
 
+    /// Re-read the MCP servers from `config.toml` and reload them in the
+    /// running session.
+    ReloadMcpServers,
+
     /// Forwarded conversation history snapshot from the current conversation.
     ConversationHistory(ConversationPathResponseEvent),
 
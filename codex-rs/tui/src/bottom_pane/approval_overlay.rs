@@ -16,13 +16,18 @@ use crate::key_hint::KeyBinding;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
+use codex_core::executor::ApprovalPattern;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
+use codex_core::protocol::SandboxDenial;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
+use mcp_types::Role;
+use mcp_types::SamplingMessage;
+use mcp_types::SamplingMessageContent;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
@@ -38,6 +43,8 @@ pub(crate) enum ApprovalRequest {
         id: String,
         command: Vec<String>,
         reason: Option<String>,
+        /// What the sandbox blocked when the command failed under it.
+        sandbox_denials: Vec<SandboxDenial>,
     },
     ApplyPatch {
         id: String,
@@ -45,6 +52,17 @@ pub(crate) enum ApprovalRequest {
         cwd: PathBuf,
         changes: HashMap<PathBuf, FileChange>,
     },
+    McpSampling {
+        id: String,
+        server: String,
+        system_prompt: Option<String>,
+        messages: Vec<SamplingMessage>,
+    },
+    CostBudget {
+        id: String,
+        cost_usd: f64,
+        budget_usd: f64,
+    },
 }
 
 /// Modal overlay asking the user to approve or deny one or more requests.
@@ -94,14 +112,22 @@ impl ApprovalOverlay {
         header: Box<dyn Renderable>,
     ) -> (Vec<ApprovalOption>, SelectionViewParams) {
         let (options, title) = match &variant {
-            ApprovalVariant::Exec { .. } => (
-                exec_options(),
+            ApprovalVariant::Exec { command, .. } => (
+                exec_options(command),
                 "Would you like to run the following command?".to_string(),
             ),
             ApprovalVariant::ApplyPatch { .. } => (
                 patch_options(),
                 "Would you like to make the following edits?".to_string(),
             ),
+            ApprovalVariant::McpSampling { server, .. } => (
+                sampling_options(),
+                format!("Would you like {server} to send the following messages to the model?"),
+            ),
+            ApprovalVariant::CostBudget { .. } => (
+                cost_budget_options(),
+                "Would you like to keep going past the session budget?".to_string(),
+            ),
         };
 
         let header = Box::new(ColumnRenderable::with([
@@ -151,6 +177,12 @@ impl ApprovalOverlay {
                 (ApprovalVariant::ApplyPatch { id, .. }, decision) => {
                     self.handle_patch_decision(id, decision);
                 }
+                (ApprovalVariant::McpSampling { id, .. }, decision) => {
+                    self.handle_sampling_decision(id, decision);
+                }
+                (ApprovalVariant::CostBudget { id }, decision) => {
+                    self.handle_cost_budget_decision(id, decision);
+                }
             }
         }
 
@@ -174,6 +206,22 @@ impl ApprovalOverlay {
         }));
     }
 
+    fn handle_sampling_decision(&self, id: &str, decision: ReviewDecision) {
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::McpSamplingApproval {
+                id: id.to_string(),
+                decision,
+            }));
+    }
+
+    fn handle_cost_budget_decision(&self, id: &str, decision: ReviewDecision) {
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::CostBudgetApproval {
+                id: id.to_string(),
+                decision,
+            }));
+    }
+
     fn advance_queue(&mut self) {
         if let Some(next) = self.queue.pop() {
             self.set_current(next);
@@ -239,6 +287,12 @@ impl BottomPaneView for ApprovalOverlay {
                 ApprovalVariant::ApplyPatch { id, .. } => {
                     self.handle_patch_decision(id, ReviewDecision::Abort);
                 }
+                ApprovalVariant::McpSampling { id, .. } => {
+                    self.handle_sampling_decision(id, ReviewDecision::Denied);
+                }
+                ApprovalVariant::CostBudget { id } => {
+                    self.handle_cost_budget_decision(id, ReviewDecision::Denied);
+                }
             }
         }
         self.queue.clear();
@@ -285,6 +339,7 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 id,
                 command,
                 reason,
+                sandbox_denials,
             } => {
                 let mut header: Vec<Line<'static>> = Vec::new();
                 if let Some(reason) = reason
@@ -293,6 +348,18 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     header.push(Line::from(vec!["Reason: ".into(), reason.italic()]));
                     header.push(Line::from(""));
                 }
+                if !sandbox_denials.is_empty() {
+                    header.extend(
+                        sandbox_denials
+                            .iter()
+                            .map(|denial| Line::from(denial.to_string().red())),
+                    );
+                    header.push(Line::from(
+                        "Add these paths to `sandbox_workspace_write.writable_roots` to allow them without disabling the sandbox."
+                            .dim(),
+                    ));
+                    header.push(Line::from(""));
+                }
                 let full_cmd = strip_bash_lc_and_escape(&command);
                 let mut full_cmd_lines = highlight_bash_to_lines(&full_cmd);
                 if let Some(first) = full_cmd_lines.first_mut() {
@@ -326,14 +393,79 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     header: Box::new(ColumnRenderable::with(header)),
                 }
             }
+            ApprovalRequest::McpSampling {
+                id,
+                server,
+                system_prompt,
+                messages,
+            } => {
+                let header = sampling_request_lines(system_prompt.as_deref(), &messages);
+                Self {
+                    variant: ApprovalVariant::McpSampling { id, server },
+                    header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
+                }
+            }
+            ApprovalRequest::CostBudget {
+                id,
+                cost_usd,
+                budget_usd,
+            } => {
+                let header = Line::from(vec![
+                    "This session has spent an estimated ".into(),
+                    format!("${cost_usd:.2}").bold(),
+                    " against a budget of ".into(),
+                    format!("${budget_usd:.2}").bold(),
+                    ".".into(),
+                ]);
+                Self {
+                    variant: ApprovalVariant::CostBudget { id },
+                    header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
+                }
+            }
+        }
+    }
+}
+
+/// Renders the system prompt and messages of a sampling request, one line per
+/// line of text, prefixed with the speaker.
+pub(crate) fn sampling_request_lines(
+    system_prompt: Option<&str>,
+    messages: &[SamplingMessage],
+) -> Vec<Line<'static>> {
+    let system = system_prompt.map(|prompt| ("system", prompt.to_string()));
+    let messages = messages.iter().map(|message| {
+        let speaker = match message.role {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        };
+        let text = match &message.content {
+            SamplingMessageContent::TextContent(text) => text.text.clone(),
+            SamplingMessageContent::ImageContent(image) => format!("[{} image]", image.mime_type),
+            SamplingMessageContent::AudioContent(audio) => format!("[{} audio]", audio.mime_type),
+        };
+        (speaker, text)
+    });
+
+    let mut lines = Vec::new();
+    for (speaker, text) in system.into_iter().chain(messages) {
+        for (idx, line) in text.lines().enumerate() {
+            let prefix = if idx == 0 {
+                format!("{speaker}: ")
+            } else {
+                " ".repeat(speaker.len() + 2)
+            };
+            lines.push(Line::from(vec![prefix.dim(), line.to_string().into()]));
         }
     }
+    lines
 }
 
 #[derive(Clone)]
 enum ApprovalVariant {
     Exec { id: String, command: Vec<String> },
     ApplyPatch { id: String },
+    McpSampling { id: String, server: String },
+    CostBudget { id: String },
 }
 
 #[derive(Clone)]
@@ -352,8 +484,8 @@ impl ApprovalOption {
     }
 }
 
-fn exec_options() -> Vec<ApprovalOption> {
-    vec![
+fn exec_options(command: &[String]) -> Vec<ApprovalOption> {
+    let mut options = vec![
         ApprovalOption {
             label: "Yes, proceed".to_string(),
             decision: ReviewDecision::Approved,
@@ -366,6 +498,33 @@ fn exec_options() -> Vec<ApprovalOption> {
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
+    ];
+    // Scripts get no project-wide pattern; it would cover any other script.
+    if let Some(pattern) = ApprovalPattern::for_command(command) {
+        options.push(ApprovalOption {
+            label: format!("Yes, and always allow `{pattern}` in this project"),
+            decision: ReviewDecision::ApprovedForProject,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
+        });
+    }
+    options.push(ApprovalOption {
+        label: "No, and tell Codex what to do differently".to_string(),
+        decision: ReviewDecision::Abort,
+        display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+    });
+    options
+}
+
+fn patch_options() -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: "Yes, proceed".to_string(),
+            decision: ReviewDecision::Approved,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        },
         ApprovalOption {
             label: "No, and tell Codex what to do differently".to_string(),
             decision: ReviewDecision::Abort,
@@ -375,7 +534,7 @@ fn exec_options() -> Vec<ApprovalOption> {
     ]
 }
 
-fn patch_options() -> Vec<ApprovalOption> {
+fn sampling_options() -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
             label: "Yes, proceed".to_string(),
@@ -384,8 +543,37 @@ fn patch_options() -> Vec<ApprovalOption> {
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
-            label: "No, and tell Codex what to do differently".to_string(),
-            decision: ReviewDecision::Abort,
+            label: "Yes, and don't ask again for this server".to_string(),
+            decision: ReviewDecision::ApprovedForSession,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+        },
+        ApprovalOption {
+            label: "No, decline the request".to_string(),
+            decision: ReviewDecision::Denied,
+            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+        },
+    ]
+}
+
+fn cost_budget_options() -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: "Yes, allow another budget's worth".to_string(),
+            decision: ReviewDecision::Approved,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        },
+        ApprovalOption {
+            label: "Yes, and don't stop again this session".to_string(),
+            decision: ReviewDecision::ApprovedForSession,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+        },
+        ApprovalOption {
+            label: "No, stop here".to_string(),
+            decision: ReviewDecision::Denied,
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
@@ -396,6 +584,7 @@ fn patch_options() -> Vec<ApprovalOption> {
 mod tests {
     use super::*;
     use crate::app_event::AppEvent;
+    use codex_core::protocol::SandboxDeniedOperation;
     use pretty_assertions::assert_eq;
     use tokio::sync::mpsc::unbounded_channel;
 
@@ -404,6 +593,7 @@ mod tests {
             id: "test".to_string(),
             command: vec!["echo".to_string(), "hi".to_string()],
             reason: Some("reason".to_string()),
+            sandbox_denials: Vec::new(),
         }
     }
 
@@ -445,6 +635,7 @@ mod tests {
             id: "test".into(),
             command,
             reason: None,
+            sandbox_denials: Vec::new(),
         };
 
         let view = ApprovalOverlay::new(exec_request, tx);
@@ -466,6 +657,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn header_lists_sandbox_denials() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let exec_request = ApprovalRequest::Exec {
+            id: "test".into(),
+            command: vec!["touch".into(), "/home/x/.cache/foo".into()],
+            reason: None,
+            sandbox_denials: vec![SandboxDenial {
+                operation: SandboxDeniedOperation::Write,
+                target: "/home/x/.cache/foo".into(),
+            }],
+        };
+
+        let view = ApprovalOverlay::new(exec_request, tx);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, view.desired_height(80)));
+        view.render(Rect::new(0, 0, 80, view.desired_height(80)), &mut buf);
+
+        let rendered: Vec<String> = (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+        assert!(
+            rendered
+                .iter()
+                .any(|line| line.contains("denied: write /home/x/.cache/foo")),
+            "expected header to list the sandbox denial, got {rendered:?}"
+        );
+    }
+
     #[test]
     fn exec_history_cell_wraps_with_two_space_indent() {
         let command = vec![
@@ -516,4 +740,150 @@ mod tests {
         }
         assert_eq!(decision, Some(ReviewDecision::ApprovedForSession));
     }
+
+    #[test]
+    fn project_shortcut_approves_command_pattern() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let exec_request = ApprovalRequest::Exec {
+            id: "test".into(),
+            command: vec!["cargo".into(), "test".into(), "-p".into(), "foo".into()],
+            reason: None,
+            sandbox_denials: Vec::new(),
+        };
+        let mut view = ApprovalOverlay::new(exec_request, tx);
+        assert!(
+            view.options
+                .iter()
+                .any(|opt| opt.label == "Yes, and always allow `cargo test *` in this project"),
+            "expected project option to show the derived pattern"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+
+        let mut decision = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::ExecApproval { decision: d, .. }) = ev {
+                decision = Some(d);
+                break;
+            }
+        }
+        assert_eq!(decision, Some(ReviewDecision::ApprovedForProject));
+    }
+
+    #[test]
+    fn scripts_offer_no_project_pattern() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let exec_request = ApprovalRequest::Exec {
+            id: "test".into(),
+            command: vec![
+                "bash".into(),
+                "-lc".into(),
+                "cargo fmt && rm -rf target".into(),
+            ],
+            reason: None,
+            sandbox_denials: Vec::new(),
+        };
+        let view = ApprovalOverlay::new(exec_request, tx);
+        assert!(
+            !view
+                .options
+                .iter()
+                .any(|opt| opt.decision == ReviewDecision::ApprovedForProject),
+            "expected no project option for a multi-command script"
+        );
+    }
+
+    #[test]
+    fn sampling_request_shows_messages_and_can_be_declined() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let request = ApprovalRequest::McpSampling {
+            id: "sample-1".into(),
+            server: "docs".into(),
+            system_prompt: Some("Summarize the user's text.".into()),
+            messages: vec![SamplingMessage {
+                content: SamplingMessageContent::TextContent(mcp_types::TextContent {
+                    annotations: None,
+                    text: "first line\nsecond line".into(),
+                    r#type: "text".into(),
+                }),
+                role: Role::User,
+            }],
+        };
+        let mut view = ApprovalOverlay::new(request, tx);
+
+        let area = Rect::new(0, 0, 80, view.desired_height(80));
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        let rendered: Vec<String> = (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        for expected in [
+            "Would you like docs to send the following messages to the model?",
+            "system: Summarize the user's text.",
+            "user: first line",
+            "      second line",
+        ] {
+            assert!(
+                rendered.iter().any(|line| line.contains(expected)),
+                "expected {expected:?} in {rendered:?}"
+            );
+        }
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+
+        let mut decision = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::McpSamplingApproval { id, decision: d }) = ev {
+                assert_eq!(id, "sample-1");
+                decision = Some(d);
+                break;
+            }
+        }
+        assert_eq!(decision, Some(ReviewDecision::Denied));
+    }
+
+    #[test]
+    fn cost_budget_prompt_shows_spend_and_can_lift_the_limit() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let request = ApprovalRequest::CostBudget {
+            id: "turn-1".into(),
+            cost_usd: 5.123,
+            budget_usd: 5.0,
+        };
+        let mut view = ApprovalOverlay::new(request, tx);
+
+        let area = Rect::new(0, 0, 80, view.desired_height(80));
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        let rendered: String = (0..buf.area.height)
+            .flat_map(|row| (0..buf.area.width).map(move |col| (col, row)))
+            .map(|pos| buf[pos].symbol().to_string())
+            .collect();
+        assert!(
+            rendered.contains("spent an estimated $5.12 against a budget of $5.00"),
+            "{rendered}"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE));
+
+        let mut decision = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::CostBudgetApproval { id, decision: d }) = ev {
+                assert_eq!(id, "turn-1");
+                decision = Some(d);
+                break;
+            }
+        }
+        assert_eq!(decision, Some(ReviewDecision::ApprovedForSession));
+    }
 }
@@ -32,14 +32,14 @@ use crate::bottom_pane::paste_burst::FlushResult;
 use crate::bottom_pane::prompt_args::expand_custom_prompt;
 use crate::bottom_pane::prompt_args::expand_if_numeric_with_positional_args;
 use crate::bottom_pane::prompt_args::parse_slash_name;
-use crate::bottom_pane::prompt_args::prompt_argument_names;
 use crate::bottom_pane::prompt_args::prompt_command_with_arg_placeholders;
 use crate::bottom_pane::prompt_args::prompt_has_numeric_placeholders;
+use crate::bottom_pane::prompt_args::prompt_named_args;
 use crate::slash_command::SlashCommand;
 use crate::slash_command::built_in_slash_commands;
 use crate::style::user_message_style;
+use codex_common::fuzzy_match::fuzzy_match;
 use codex_protocol::custom_prompts::CustomPrompt;
-use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
@@ -66,6 +66,8 @@ const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
 pub enum InputResult {
     Submitted(String),
     Command(SlashCommand),
+    /// A built-in command followed by arguments, e.g. `/mcp reload`.
+    CommandWithArgs(SlashCommand, String),
     None,
 }
 
@@ -106,6 +108,8 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    /// MCP resources offered in the `@` popup, as `server:resource` mentions.
+    mcp_resources: Vec<String>,
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     context_window_percent: Option<u8>,
@@ -149,6 +153,7 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            mcp_resources: Vec::new(),
             footer_mode: FooterMode::ShortcutSummary,
             footer_hint_override: None,
             context_window_percent: None,
@@ -357,11 +362,39 @@ impl ChatComposer {
             return;
         }
 
+        // MCP resources are listed ahead of files so that typing the server
+        // name surfaces them first.
+        let mut combined = self.mcp_resource_matches(&query);
+        combined.extend(matches);
         if let ActivePopup::File(popup) = &mut self.active_popup {
-            popup.set_matches(&query, matches);
+            popup.set_matches(&query, combined);
         }
     }
 
+    /// Returns the MCP resources matching `query` as popup entries whose path
+    /// is the full `@server:resource` mention.
+    fn mcp_resource_matches(&self, query: &str) -> Vec<FileMatch> {
+        let mut matches: Vec<(i32, FileMatch)> = self
+            .mcp_resources
+            .iter()
+            .filter_map(|resource| {
+                let mention = format!("@{resource}");
+                let (indices, score) = fuzzy_match(resource, query)?;
+                Some((
+                    score,
+                    FileMatch {
+                        score: 0,
+                        path: mention,
+                        // Shift past the leading '@'.
+                        indices: Some(indices.into_iter().map(|i| i as u32 + 1).collect()),
+                    },
+                ))
+            })
+            .collect();
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.path.cmp(&b.1.path)));
+        matches.into_iter().map(|(_, m)| m).collect()
+    }
+
     pub fn set_ctrl_c_quit_hint(&mut self, show: bool, has_focus: bool) {
         self.ctrl_c_quit_hint = show;
         if show {
@@ -495,8 +528,7 @@ impl ChatComposer {
                 // immediately regardless of the popup selection.
                 let first_line = self.textarea.text().lines().next().unwrap_or("");
                 if let Some((name, _rest)) = parse_slash_name(first_line)
-                    && let Some(prompt_name) = name.strip_prefix(&format!("{PROMPTS_CMD_PREFIX}:"))
-                    && let Some(prompt) = self.custom_prompts.iter().find(|p| p.name == prompt_name)
+                    && let Some(prompt) = self.custom_prompts.iter().find(|p| p.command() == name)
                     && let Some(expanded) =
                         expand_if_numeric_with_positional_args(prompt, first_line)
                 {
@@ -900,16 +932,21 @@ impl ChatComposer {
                 // "/diff ", then press Enter to run it. Tab moves the cursor beyond
                 // the '/name' token and our caret-based heuristic hides the popup,
                 // but Enter should still dispatch the command rather than submit
-                // literal text.
+                // literal text. Commands that accept arguments are dispatched with
+                // them.
                 let first_line = self.textarea.text().lines().next().unwrap_or("");
                 if let Some((name, rest)) = parse_slash_name(first_line)
-                    && rest.is_empty()
                     && let Some((_n, cmd)) = built_in_slash_commands()
                         .into_iter()
                         .find(|(n, _)| *n == name)
+                    && (rest.is_empty() || cmd.accepts_args())
                 {
+                    let args = rest.trim_end().to_string();
                     self.textarea.set_text("");
-                    return (InputResult::Command(cmd), true);
+                    if args.is_empty() {
+                        return (InputResult::Command(cmd), true);
+                    }
+                    return (InputResult::CommandWithArgs(cmd, args), true);
                 }
                 // If we're in a paste-like burst capture, treat Enter as part of the burst
                 // and accumulate it rather than submitting or inserting immediately.
@@ -1406,6 +1443,10 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_mcp_resources(&mut self, resources: Vec<String>) {
+        self.mcp_resources = resources;
+    }
+
     pub(crate) fn set_custom_prompts(&mut self, prompts: Vec<CustomPrompt>) {
         self.custom_prompts = prompts.clone();
         if let ActivePopup::Command(popup) = &mut self.active_popup {
@@ -1558,30 +1599,29 @@ fn prompt_selection_action(
     first_line: &str,
     mode: PromptSelectionMode,
 ) -> PromptSelectionAction {
-    let named_args = prompt_argument_names(&prompt.content);
+    let named_args = prompt_named_args(prompt);
     let has_numeric = prompt_has_numeric_placeholders(&prompt.content);
+    let command = prompt.command();
 
     match mode {
         PromptSelectionMode::Completion => {
             if !named_args.is_empty() {
-                let (text, cursor) =
-                    prompt_command_with_arg_placeholders(&prompt.name, &named_args);
+                let (text, cursor) = prompt_command_with_arg_placeholders(prompt, &named_args);
                 return PromptSelectionAction::Insert {
                     text,
                     cursor: Some(cursor),
                 };
             }
             if has_numeric {
-                let text = format!("/{PROMPTS_CMD_PREFIX}:{} ", prompt.name);
+                let text = format!("/{command} ");
                 return PromptSelectionAction::Insert { text, cursor: None };
             }
-            let text = format!("/{PROMPTS_CMD_PREFIX}:{}", prompt.name);
+            let text = format!("/{command}");
             PromptSelectionAction::Insert { text, cursor: None }
         }
         PromptSelectionMode::Submit => {
             if !named_args.is_empty() {
-                let (text, cursor) =
-                    prompt_command_with_arg_placeholders(&prompt.name, &named_args);
+                let (text, cursor) = prompt_command_with_arg_placeholders(prompt, &named_args);
                 return PromptSelectionAction::Insert {
                     text,
                     cursor: Some(cursor),
@@ -1591,12 +1631,16 @@ fn prompt_selection_action(
                 if let Some(expanded) = expand_if_numeric_with_positional_args(prompt, first_line) {
                     return PromptSelectionAction::Submit { text: expanded };
                 }
-                let text = format!("/{PROMPTS_CMD_PREFIX}:{} ", prompt.name);
+                let text = format!("/{command} ");
                 return PromptSelectionAction::Insert { text, cursor: None };
             }
-            PromptSelectionAction::Submit {
-                text: prompt.content.clone(),
-            }
+            // MCP prompts are rendered by their server when the command is sent.
+            let text = if prompt.mcp.is_some() {
+                format!("/{command}")
+            } else {
+                prompt.content.clone()
+            };
+            PromptSelectionAction::Submit { text }
         }
     }
 }
@@ -2204,6 +2248,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn at_popup_offers_mcp_resources_and_inserts_mention() {
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_mcp_resources(vec![
+            "docs-server:deploy.md".to_string(),
+            "docs-server:runbook.md".to_string(),
+        ]);
+        type_chars_humanlike(&mut composer, &['@', 'r', 'u', 'n']);
+
+        composer.on_file_search_result(
+            "run".to_string(),
+            vec![FileMatch {
+                score: 1,
+                path: "scripts/run.sh".to_string(),
+                indices: None,
+            }],
+        );
+
+        let _ = composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(composer.textarea.text(), "@docs-server:runbook.md ");
+    }
+
     // Test helper: simulate human typing with a brief delay and flush the paste-burst buffer
     fn type_chars_humanlike(composer: &mut ChatComposer, chars: &[char]) {
         use crossterm::event::KeyCode;
@@ -2248,6 +2326,9 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!("expected bare command, got '/{} {args}'", cmd.command())
+            }
             InputResult::None => panic!("expected Command result for '/init'"),
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
@@ -2321,6 +2402,9 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch after Tab completion, got literal submit: {text}")
             }
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!("expected bare command, got '/{} {args}'", cmd.command())
+            }
             InputResult::None => panic!("expected Command result for '/diff'"),
         }
         assert!(composer.textarea.is_empty());
@@ -2354,6 +2438,9 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!("expected bare command, got '/{} {args}'", cmd.command())
+            }
             InputResult::None => panic!("expected Command result for '/mention'"),
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
@@ -2361,6 +2448,37 @@ mod tests {
         assert_eq!(composer.textarea.text(), "@");
     }
 
+    #[test]
+    fn slash_mcp_reload_dispatches_command_with_args() {
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        type_chars_humanlike(
+            &mut composer,
+            &['/', 'm', 'c', 'p', ' ', 'r', 'e', 'l', 'o', 'a', 'd'],
+        );
+
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(
+            result,
+            InputResult::CommandWithArgs(SlashCommand::Mcp, "reload".to_string())
+        );
+        assert!(composer.textarea.is_empty(), "composer should be cleared");
+    }
+
     #[test]
     fn test_multiple_pastes_submission() {
         use crossterm::event::KeyCode;
@@ -2768,6 +2886,7 @@ mod tests {
             content: prompt_text.to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }]);
 
         type_chars_humanlike(
@@ -2803,6 +2922,7 @@ mod tests {
             content: "Review $USER changes on $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }]);
 
         composer
@@ -2837,6 +2957,7 @@ mod tests {
             content: "Pair $USER with $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }]);
 
         composer
@@ -2871,6 +2992,7 @@ mod tests {
             content: "Review $USER changes".to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }]);
 
         composer
@@ -2921,6 +3043,7 @@ mod tests {
             content: "Review $USER changes on $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }]);
 
         // Provide only one of the required args
@@ -2974,6 +3097,7 @@ mod tests {
             content: prompt_text.to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }]);
 
         // Type the slash command with two args and hit Enter to submit.
@@ -3011,6 +3135,7 @@ mod tests {
             content: "Echo: $ARGUMENTS".to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }]);
 
         // Type positional args; should submit with numeric expansion, no errors.
@@ -3042,6 +3167,7 @@ mod tests {
             content: prompt_text.to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }]);
 
         type_chars_humanlike(
@@ -3078,6 +3204,7 @@ mod tests {
             content: prompt_text.to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }]);
 
         type_chars_humanlike(
@@ -3115,6 +3242,7 @@ mod tests {
             content: prompt_text.to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }]);
 
         type_chars_humanlike(
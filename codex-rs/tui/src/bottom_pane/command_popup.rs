@@ -12,7 +12,6 @@ use crate::slash_command::SlashCommand;
 use crate::slash_command::built_in_slash_commands;
 use codex_common::fuzzy_match::fuzzy_match;
 use codex_protocol::custom_prompts::CustomPrompt;
-use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use std::collections::HashSet;
 
 /// A selectable item in the popup: either a built-in command or a user prompt.
@@ -33,10 +32,11 @@ pub(crate) struct CommandPopup {
 impl CommandPopup {
     pub(crate) fn new(mut prompts: Vec<CustomPrompt>) -> Self {
         let builtins = built_in_slash_commands();
-        // Exclude prompts that collide with builtin command names and sort by name.
+        // Exclude prompts that collide with builtin command names and sort
+        // saved prompts by name, ahead of MCP prompts.
         let exclude: HashSet<String> = builtins.iter().map(|(n, _)| (*n).to_string()).collect();
-        prompts.retain(|p| !exclude.contains(&p.name));
-        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        prompts.retain(|p| p.mcp.is_some() || !exclude.contains(&p.name));
+        prompts.sort_by_key(prompt_sort_key);
         Self {
             command_filter: String::new(),
             builtins,
@@ -51,8 +51,8 @@ impl CommandPopup {
             .iter()
             .map(|(n, _)| (*n).to_string())
             .collect();
-        prompts.retain(|p| !exclude.contains(&p.name));
-        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        prompts.retain(|p| p.mcp.is_some() || !exclude.contains(&p.name));
+        prompts.sort_by_key(prompt_sort_key);
         self.prompts = prompts;
     }
 
@@ -124,10 +124,10 @@ impl CommandPopup {
             }
         }
         // Support both search styles:
-        // - Typing "name" should surface "/prompts:name" results.
+        // - Typing "name" should surface "/prompts:name" (or "/mcp:server:name") results.
         // - Typing "prompts:name" should also work.
         for (idx, p) in self.prompts.iter().enumerate() {
-            let display = format!("{PROMPTS_CMD_PREFIX}:{}", p.name);
+            let display = p.command();
             if let Some((indices, score)) = fuzzy_match(&display, filter) {
                 out.push((CommandItem::UserPrompt(idx), Some(indices), score));
             }
@@ -164,10 +164,17 @@ impl CommandPopup {
                     CommandItem::Builtin(cmd) => {
                         (format!("/{}", cmd.command()), cmd.description().to_string())
                     }
-                    CommandItem::UserPrompt(i) => (
-                        format!("/{PROMPTS_CMD_PREFIX}:{}", self.prompts[i].name),
-                        "send saved prompt".to_string(),
-                    ),
+                    CommandItem::UserPrompt(i) => {
+                        let prompt = &self.prompts[i];
+                        let description = match &prompt.mcp {
+                            Some(mcp) => prompt
+                                .description
+                                .clone()
+                                .unwrap_or_else(|| format!("send prompt from {}", mcp.server)),
+                            None => "send saved prompt".to_string(),
+                        };
+                        (format!("/{}", prompt.command()), description)
+                    }
                 };
                 GenericDisplayRow {
                     name,
@@ -218,6 +225,10 @@ impl WidgetRef for CommandPopup {
     }
 }
 
+fn prompt_sort_key(prompt: &CustomPrompt) -> (bool, String) {
+    (prompt.mcp.is_some(), prompt.command())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -271,6 +282,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn mcp_prompts_are_listed_under_their_server() {
+        use codex_protocol::custom_prompts::McpPromptSource;
+
+        let prompts = vec![
+            CustomPrompt {
+                name: "review".to_string(),
+                path: Default::default(),
+                content: String::new(),
+                description: Some("Review a pull request".to_string()),
+                argument_hint: None,
+                mcp: Some(McpPromptSource {
+                    server: "github".to_string(),
+                    arguments: Vec::new(),
+                }),
+            },
+            CustomPrompt {
+                name: "review".to_string(),
+                path: "/tmp/review.md".to_string().into(),
+                content: "review my changes".to_string(),
+                description: None,
+                argument_hint: None,
+                mcp: None,
+            },
+        ];
+        let mut popup = CommandPopup::new(prompts);
+        popup.on_composer_text_change("/mcp:github:rev".to_string());
+
+        let rows = popup.rows_from_matches(popup.filtered());
+        let names: Vec<&str> = rows.iter().map(|row| row.name.as_str()).collect();
+        assert_eq!(names.first(), Some(&"/mcp:github:review"));
+        assert_eq!(
+            rows[0].description.as_deref(),
+            Some("Review a pull request")
+        );
+    }
+
     #[test]
     fn prompt_discovery_lists_custom_prompts() {
         let prompts = vec![
@@ -280,6 +328,7 @@ mod tests {
                 content: "hello from foo".to_string(),
                 description: None,
                 argument_hint: None,
+                mcp: None,
             },
             CustomPrompt {
                 name: "bar".to_string(),
@@ -287,6 +336,7 @@ mod tests {
                 content: "hello from bar".to_string(),
                 description: None,
                 argument_hint: None,
+                mcp: None,
             },
         ];
         let popup = CommandPopup::new(prompts);
@@ -311,6 +361,7 @@ mod tests {
             content: "should be ignored".to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }]);
         let items = popup.filtered_items();
         let has_collision_prompt = items.into_iter().any(|it| match it {
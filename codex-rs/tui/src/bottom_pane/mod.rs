@@ -17,6 +17,7 @@ use std::time::Duration;
 mod approval_overlay;
 pub(crate) use approval_overlay::ApprovalOverlay;
 pub(crate) use approval_overlay::ApprovalRequest;
+pub(crate) use approval_overlay::sampling_request_lines;
 mod bottom_pane_view;
 mod chat_composer;
 mod chat_composer_history;
@@ -381,6 +382,12 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    /// Update MCP resources available for `@server:resource` mentions.
+    pub(crate) fn set_mcp_resources(&mut self, resources: Vec<String>) {
+        self.composer.set_mcp_resources(resources);
+        self.request_redraw();
+    }
+
     pub(crate) fn composer_is_empty(&self) -> bool {
         self.composer.is_empty()
     }
@@ -550,6 +557,7 @@ mod tests {
             id: "1".to_string(),
             command: vec!["echo".into(), "ok".into()],
             reason: None,
+            sandbox_denials: Vec::new(),
         }
     }
 
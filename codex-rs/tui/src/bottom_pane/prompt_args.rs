@@ -134,6 +134,9 @@ pub fn parse_prompt_inputs(rest: &str) -> Result<HashMap<String, String>, Prompt
 /// If the text does not start with `/prompts:`, or if no prompt named `name` exists,
 /// the function returns `Ok(None)`. On success it returns
 /// `Ok(Some(expanded))`; otherwise it returns a descriptive error.
+///
+/// MCP prompts (`/mcp:server:name`) are rendered by their server, so for those the
+/// arguments are only validated and `Ok(None)` is returned to send the text as is.
 pub fn expand_custom_prompt(
     text: &str,
     custom_prompts: &[CustomPrompt],
@@ -142,15 +145,32 @@ pub fn expand_custom_prompt(
         return Ok(None);
     };
 
-    // Only handle custom prompts when using the explicit prompts prefix with a colon.
-    let Some(prompt_name) = name.strip_prefix(&format!("{PROMPTS_CMD_PREFIX}:")) else {
-        return Ok(None);
-    };
-
-    let prompt = match custom_prompts.iter().find(|p| p.name == prompt_name) {
+    // Only handle custom prompts when using the explicit `prompts:` (or MCP
+    // `mcp:server:`) prefix with a colon.
+    let prompt = match custom_prompts.iter().find(|p| p.command() == name) {
         Some(prompt) => prompt,
         None => return Ok(None),
     };
+
+    if let Some(mcp) = &prompt.mcp {
+        let inputs = parse_prompt_inputs(rest).map_err(|error| PromptExpansionError::Args {
+            command: format!("/{name}"),
+            error,
+        })?;
+        let missing: Vec<String> = mcp
+            .arguments
+            .iter()
+            .filter(|arg| arg.required.unwrap_or(false) && !inputs.contains_key(&arg.name))
+            .map(|arg| arg.name.clone())
+            .collect();
+        if !missing.is_empty() {
+            return Err(PromptExpansionError::MissingArgs {
+                command: format!("/{name}"),
+                missing,
+            });
+        }
+        return Ok(None);
+    }
     // If there are named placeholders, expect key=value inputs.
     let required = prompt_argument_names(&prompt.content);
     if !required.is_empty() {
@@ -192,6 +212,15 @@ pub fn expand_custom_prompt(
     Ok(Some(expanded))
 }
 
+/// Names of the `key=value` arguments a prompt accepts: the arguments declared
+/// by an MCP prompt, or the `$NAME` placeholders of a saved prompt.
+pub fn prompt_named_args(prompt: &CustomPrompt) -> Vec<String> {
+    match &prompt.mcp {
+        Some(mcp) => mcp.arguments.iter().map(|arg| arg.name.clone()).collect(),
+        None => prompt_argument_names(&prompt.content),
+    }
+}
+
 /// Detect whether `content` contains numeric placeholders ($1..$9) or `$ARGUMENTS`.
 pub fn prompt_has_numeric_placeholders(content: &str) -> bool {
     if content.contains("$ARGUMENTS") {
@@ -298,8 +327,11 @@ pub fn expand_numeric_placeholders(content: &str, args: &[String]) -> String {
 
 /// Constructs a command text for a custom prompt with arguments.
 /// Returns the text and the cursor position (inside the first double quote).
-pub fn prompt_command_with_arg_placeholders(name: &str, args: &[String]) -> (String, usize) {
-    let mut text = format!("/{PROMPTS_CMD_PREFIX}:{name}");
+pub fn prompt_command_with_arg_placeholders(
+    prompt: &CustomPrompt,
+    args: &[String],
+) -> (String, usize) {
+    let mut text = format!("/{}", prompt.command());
     let mut cursor: usize = text.len();
     for (i, arg) in args.iter().enumerate() {
         text.push_str(format!(" {arg}=\"\"").as_str());
@@ -322,6 +354,7 @@ mod tests {
             content: "Review $USER changes on $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }];
 
         let out =
@@ -337,6 +370,7 @@ mod tests {
             content: "Pair $USER with $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }];
 
         let out = expand_custom_prompt(
@@ -355,6 +389,7 @@ mod tests {
             content: "Review $USER changes".to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }];
         let err = expand_custom_prompt("/prompts:my-prompt USER=Alice stray", &prompts)
             .unwrap_err()
@@ -370,6 +405,7 @@ mod tests {
             content: "Review $USER changes on $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }];
         let err = expand_custom_prompt("/prompts:my-prompt USER=Alice", &prompts)
             .unwrap_err()
@@ -398,9 +434,42 @@ mod tests {
             content: "literal $$USER".to_string(),
             description: None,
             argument_hint: None,
+            mcp: None,
         }];
 
         let out = expand_custom_prompt("/prompts:my-prompt", &prompts).unwrap();
         assert_eq!(out, Some("literal $$USER".to_string()));
     }
+
+    #[test]
+    fn mcp_prompt_args_are_validated_but_not_expanded() {
+        use codex_protocol::custom_prompts::McpPromptSource;
+        use mcp_types::PromptArgument;
+
+        let prompts = vec![CustomPrompt {
+            name: "review".to_string(),
+            path: Default::default(),
+            content: String::new(),
+            description: None,
+            argument_hint: None,
+            mcp: Some(McpPromptSource {
+                server: "github".to_string(),
+                arguments: vec![PromptArgument {
+                    description: None,
+                    name: "file".to_string(),
+                    required: Some(true),
+                    title: None,
+                }],
+            }),
+        }];
+
+        let err = expand_custom_prompt("/mcp:github:review", &prompts)
+            .unwrap_err()
+            .user_message();
+        assert!(err.contains("/mcp:github:review"));
+        assert!(err.contains("file"));
+
+        let out = expand_custom_prompt("/mcp:github:review file=src/lib.rs", &prompts).unwrap();
+        assert_eq!(out, None);
+    }
 }
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use codex_core::config::Config;
+use codex_core::config_types::McpServerConfig;
 use codex_core::config_types::Notifications;
 use codex_core::git_info::current_branch_name;
 use codex_core::git_info::local_git_branches;
@@ -15,6 +16,7 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CostBudgetApprovalRequestEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -25,11 +27,16 @@ use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::InputMessageKind;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
+use codex_core::protocol::McpListResourcesResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
+use codex_core::protocol::McpSamplingApprovalRequestEvent;
+use codex_core::protocol::McpServersReloadedEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::PinnedItem;
+use codex_core::protocol::PinnedItemsEvent;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::StreamErrorEvent;
@@ -43,6 +50,7 @@ use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_protocol::ConversationId;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::parse_command::ParsedCommand;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -228,6 +236,10 @@ pub(crate) struct ChatWidget {
     session_header: SessionHeader,
     initial_user_message: Option<UserMessage>,
     token_info: Option<TokenUsageInfo>,
+    /// Estimated cost of the session so far, when the model has known prices.
+    cost_usd: Option<f64>,
+    /// Items the session keeps across compaction.
+    pinned_items: Vec<PinnedItem>,
     rate_limit_snapshot: Option<RateLimitSnapshotDisplay>,
     rate_limit_warnings: RateLimitWarningState,
     // Stream lifecycle controller
@@ -321,6 +333,8 @@ impl ChatWidget {
         }
         // Ask codex-core to enumerate custom prompts for this session.
         self.submit_op(Op::ListCustomPrompts);
+        // And the MCP resources that can be mentioned with `@server:resource`.
+        self.submit_op(Op::ListMcpResources);
         if let Some(user_message) = self.initial_user_message.take() {
             self.submit_user_message(user_message);
         }
@@ -531,6 +545,28 @@ impl ChatWidget {
         );
     }
 
+    fn on_mcp_sampling_approval_request(
+        &mut self,
+        id: String,
+        ev: McpSamplingApprovalRequestEvent,
+    ) {
+        let id2 = id.clone();
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_mcp_sampling_approval(id, ev),
+            |s| s.handle_mcp_sampling_approval_now(id2, ev2),
+        );
+    }
+
+    fn on_cost_budget_approval_request(&mut self, id: String, ev: CostBudgetApprovalRequestEvent) {
+        let id2 = id.clone();
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_cost_budget_approval(id, ev),
+            |s| s.handle_cost_budget_approval_now(id2, ev2),
+        );
+    }
+
     fn on_exec_command_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.flush_answer_stream_with_separator();
         let ev2 = ev.clone();
@@ -732,6 +768,7 @@ impl ChatWidget {
                     stdout: ev.stdout.clone(),
                     stderr: ev.stderr.clone(),
                     formatted_output: ev.formatted_output.clone(),
+                    sandbox_denials: ev.sandbox_denials.clone(),
                 },
                 ev.duration,
             );
@@ -762,6 +799,7 @@ impl ChatWidget {
             id,
             command: ev.command,
             reason: ev.reason,
+            sandbox_denials: ev.sandbox_denials,
         };
         self.bottom_pane.push_approval_request(request);
         self.request_redraw();
@@ -788,6 +826,39 @@ impl ChatWidget {
         });
     }
 
+    pub(crate) fn handle_mcp_sampling_approval_now(
+        &mut self,
+        id: String,
+        ev: McpSamplingApprovalRequestEvent,
+    ) {
+        self.flush_answer_stream_with_separator();
+
+        let request = ApprovalRequest::McpSampling {
+            id,
+            server: ev.server,
+            system_prompt: ev.system_prompt,
+            messages: ev.messages,
+        };
+        self.bottom_pane.push_approval_request(request);
+        self.request_redraw();
+    }
+
+    pub(crate) fn handle_cost_budget_approval_now(
+        &mut self,
+        id: String,
+        ev: CostBudgetApprovalRequestEvent,
+    ) {
+        self.flush_answer_stream_with_separator();
+
+        let request = ApprovalRequest::CostBudget {
+            id,
+            cost_usd: ev.cost_usd,
+            budget_usd: ev.budget_usd,
+        };
+        self.bottom_pane.push_approval_request(request);
+        self.request_redraw();
+    }
+
     pub(crate) fn handle_exec_begin_now(&mut self, ev: ExecCommandBeginEvent) {
         // Ensure the status indicator is visible while the command runs.
         self.running_commands.insert(
@@ -920,6 +991,8 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            cost_usd: None,
+            pinned_items: Vec::new(),
             rate_limit_snapshot: None,
             rate_limit_warnings: RateLimitWarningState::default(),
             stream_controller: None,
@@ -983,6 +1056,8 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            cost_usd: None,
+            pinned_items: Vec::new(),
             rate_limit_snapshot: None,
             rate_limit_warnings: RateLimitWarningState::default(),
             stream_controller: None,
@@ -1072,12 +1147,45 @@ impl ChatWidget {
                     InputResult::Command(cmd) => {
                         self.dispatch_command(cmd);
                     }
+                    InputResult::CommandWithArgs(cmd, args) => {
+                        self.dispatch_command_with_args(cmd, &args);
+                    }
                     InputResult::None => {}
                 }
             }
         }
     }
 
+    fn dispatch_command_with_args(&mut self, cmd: SlashCommand, args: &str) {
+        if self.reject_during_task(cmd) {
+            return;
+        }
+        match (cmd, args) {
+            (SlashCommand::Mcp, "reload") => {
+                self.add_info_message("Reloading MCP servers…".to_string(), None);
+                self.app_event_tx.send(AppEvent::ReloadMcpServers);
+            }
+            (SlashCommand::Compact, args) => match parse_compaction_strategy(args) {
+                Some(strategy) => self.compact(Some(strategy)),
+                None => self.add_error_message(format!(
+                    "Unknown compaction strategy '{args}'. Use summarize, drop-tool-outputs or keep-last <turns>."
+                )),
+            },
+            _ => {
+                self.add_error_message(format!(
+                    "Unrecognized arguments for '/{}': {args}",
+                    cmd.command()
+                ));
+            }
+        }
+    }
+
+    fn compact(&mut self, strategy: Option<CompactionStrategy>) {
+        self.clear_token_usage();
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::Compact { strategy }));
+    }
+
     pub(crate) fn attach_image(
         &mut self,
         path: PathBuf,
@@ -1093,14 +1201,23 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    /// Reports an error and returns `true` if `cmd` cannot run while a task
+    /// is in progress and one is.
+    fn reject_during_task(&mut self, cmd: SlashCommand) -> bool {
+        if cmd.available_during_task() || !self.bottom_pane.is_task_running() {
+            return false;
+        }
+        let message = format!(
+            "'/{}' is disabled while a task is in progress.",
+            cmd.command()
+        );
+        self.add_to_history(history_cell::new_error_event(message));
+        self.request_redraw();
+        true
+    }
+
     fn dispatch_command(&mut self, cmd: SlashCommand) {
-        if !cmd.available_during_task() && self.bottom_pane.is_task_running() {
-            let message = format!(
-                "'/{}' is disabled while a task is in progress.",
-                cmd.command()
-            );
-            self.add_to_history(history_cell::new_error_event(message));
-            self.request_redraw();
+        if self.reject_during_task(cmd) {
             return;
         }
         match cmd {
@@ -1112,8 +1229,7 @@ impl ChatWidget {
                 self.submit_text_message(INIT_PROMPT.to_string());
             }
             SlashCommand::Compact => {
-                self.clear_token_usage();
-                self.app_event_tx.send(AppEvent::CodexOp(Op::Compact));
+                self.compact(None);
             }
             SlashCommand::Review => {
                 self.open_review_popup();
@@ -1159,6 +1275,9 @@ impl ChatWidget {
             SlashCommand::Status => {
                 self.add_status_output();
             }
+            SlashCommand::Unpin => {
+                self.open_unpin_popup();
+            }
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
@@ -1247,6 +1366,12 @@ impl ChatWidget {
 
     fn submit_user_message(&mut self, user_message: UserMessage) {
         let UserMessage { text, image_paths } = user_message;
+        let (pinned_paths, text) = take_pin_directives(text);
+        for path in pinned_paths {
+            self.submit_op(Op::PinItem {
+                item: PinnedItem::File { path },
+            });
+        }
         if text.is_empty() && image_paths.is_empty() {
             return;
         }
@@ -1393,6 +1518,9 @@ impl ChatWidget {
             }
             EventMsg::TokenCount(ev) => {
                 self.set_token_info(ev.info);
+                if ev.cost_usd.is_some() {
+                    self.cost_usd = ev.cost_usd;
+                }
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
             EventMsg::Error(ErrorEvent { message }) => self.on_error(message),
@@ -1415,6 +1543,12 @@ impl ChatWidget {
             EventMsg::ApplyPatchApprovalRequest(ev) => {
                 self.on_apply_patch_approval_request(id.unwrap_or_default(), ev)
             }
+            EventMsg::McpSamplingApprovalRequest(ev) => {
+                self.on_mcp_sampling_approval_request(id.unwrap_or_default(), ev)
+            }
+            EventMsg::CostBudgetApprovalRequest(ev) => {
+                self.on_cost_budget_approval_request(id.unwrap_or_default(), ev)
+            }
             EventMsg::ExecCommandBegin(ev) => self.on_exec_command_begin(ev),
             EventMsg::ExecCommandOutputDelta(delta) => self.on_exec_command_output_delta(delta),
             EventMsg::PatchApplyBegin(ev) => self.on_patch_apply_begin(ev),
@@ -1428,6 +1562,9 @@ impl ChatWidget {
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::McpListResourcesResponse(ev) => self.on_list_mcp_resources(ev),
+            EventMsg::McpServersReloaded(ev) => self.on_mcp_servers_reloaded(ev),
+            EventMsg::PinnedItems(ev) => self.on_pinned_items(ev, from_replay),
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
@@ -1502,7 +1639,8 @@ impl ChatWidget {
     fn on_user_message_event(&mut self, event: UserMessageEvent) {
         match event.kind {
             Some(InputMessageKind::EnvironmentContext)
-            | Some(InputMessageKind::UserInstructions) => {
+            | Some(InputMessageKind::UserInstructions)
+            | Some(InputMessageKind::PinnedContext) => {
                 // Skip XML‑wrapped context blocks in the transcript.
             }
             Some(InputMessageKind::Plain) | None => {
@@ -1586,6 +1724,8 @@ impl ChatWidget {
             &self.config,
             total_usage,
             context_usage,
+            self.cost_usd,
+            &self.pinned_items,
             &self.conversation_id,
             self.rate_limit_snapshot.as_ref(),
         ));
@@ -1913,6 +2053,110 @@ impl ChatWidget {
         ));
     }
 
+    fn on_mcp_servers_reloaded(&mut self, ev: McpServersReloadedEvent) {
+        let mut changes = Vec::new();
+        for (label, servers) in [
+            ("started", &ev.started),
+            ("restarted", &ev.restarted),
+            ("stopped", &ev.stopped),
+        ] {
+            if !servers.is_empty() {
+                changes.push(format!("{label} {}", servers.join(", ")));
+            }
+        }
+        let message = if changes.is_empty() {
+            "MCP servers reloaded; nothing changed".to_string()
+        } else {
+            format!("MCP servers reloaded: {}", changes.join("; "))
+        };
+        let tool_count = ev.tools.len();
+        let hint = if tool_count == 1 {
+            "1 tool available".to_string()
+        } else {
+            format!("{tool_count} tools available")
+        };
+        self.add_info_message(message, Some(hint));
+
+        // Prompts and resources may have changed along with the servers.
+        self.submit_op(Op::ListCustomPrompts);
+        self.submit_op(Op::ListMcpResources);
+    }
+
+    fn on_pinned_items(&mut self, ev: PinnedItemsEvent, from_replay: bool) {
+        let unpinned = ev.items.len() < self.pinned_items.len();
+        self.pinned_items = ev.items;
+        if from_replay {
+            return;
+        }
+        let count = self.pinned_items.len();
+        let hint = match count {
+            0 => "nothing is pinned".to_string(),
+            1 => "1 item pinned; /status lists it".to_string(),
+            _ => format!("{count} items pinned; /status lists them"),
+        };
+        let message = if unpinned {
+            "Unpinned. It will no longer be kept when the conversation is compacted."
+        } else {
+            "Pinned. It will be kept verbatim when the conversation is compacted."
+        };
+        self.add_info_message(message.to_string(), Some(hint));
+    }
+
+    /// Lists the pinned items so one, or all of them, can be unpinned.
+    fn open_unpin_popup(&mut self) {
+        if self.pinned_items.is_empty() {
+            self.add_info_message("Nothing is pinned.".to_string(), None);
+            return;
+        }
+        let mut items: Vec<SelectionItem> = self
+            .pinned_items
+            .iter()
+            .map(|item| {
+                let item = item.clone();
+                SelectionItem {
+                    name: crate::status::describe_pinned_item(&item, &self.config.cwd),
+                    actions: vec![Box::new(move |tx: &AppEventSender| {
+                        tx.send(AppEvent::CodexOp(Op::UnpinItem { item: item.clone() }));
+                    })],
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        let all = self.pinned_items.clone();
+        items.push(SelectionItem {
+            name: "Unpin everything".to_string(),
+            actions: vec![Box::new(move |tx: &AppEventSender| {
+                for item in &all {
+                    tx.send(AppEvent::CodexOp(Op::UnpinItem { item: item.clone() }));
+                }
+            })],
+            dismiss_on_select: true,
+            ..Default::default()
+        });
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Unpin".to_string()),
+            subtitle: Some(
+                "Pinned items are kept verbatim when the conversation is compacted.".to_string(),
+            ),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    /// Pins `text` so it survives compaction.
+    pub(crate) fn pin_message(&mut self, text: String) {
+        self.submit_op(Op::PinItem {
+            item: PinnedItem::Message { text },
+        });
+    }
+
+    pub(crate) fn set_mcp_servers(&mut self, mcp_servers: HashMap<String, McpServerConfig>) {
+        self.config.mcp_servers = mcp_servers;
+    }
+
     fn on_list_custom_prompts(&mut self, ev: ListCustomPromptsResponseEvent) {
         let len = ev.custom_prompts.len();
         debug!("received {len} custom prompts");
@@ -1920,6 +2164,31 @@ impl ChatWidget {
         self.bottom_pane.set_custom_prompts(ev.custom_prompts);
     }
 
+    fn on_list_mcp_resources(&mut self, ev: McpListResourcesResponseEvent) {
+        // Mentions use the resource name when it is a single token and fall
+        // back to the URI otherwise; core resolves either.
+        let mut mentions: Vec<String> = ev
+            .resources
+            .into_iter()
+            .flat_map(|(server, resources)| {
+                resources.into_iter().map(move |resource| {
+                    let resource = if resource.name.is_empty()
+                        || resource.name.chars().any(char::is_whitespace)
+                    {
+                        resource.uri
+                    } else {
+                        resource.name
+                    };
+                    format!("{server}:{resource}")
+                })
+            })
+            .collect();
+        mentions.sort();
+        mentions.dedup();
+        debug!("received {} MCP resources", mentions.len());
+        self.bottom_pane.set_mcp_resources(mentions);
+    }
+
     pub(crate) fn open_review_popup(&mut self) {
         let mut items: Vec<SelectionItem> = Vec::new();
 
@@ -2110,6 +2379,7 @@ impl ChatWidget {
 
     pub(crate) fn clear_token_usage(&mut self) {
         self.token_info = None;
+        self.cost_usd = None;
     }
 
     pub fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
@@ -2211,6 +2481,27 @@ const EXAMPLE_PROMPTS: [&str; 6] = [
     "Improve documentation in @filename",
 ];
 
+/// Splits `@pin <path>` lines out of a composer message, returning the paths
+/// to pin and the rest of the message. The message is returned unchanged when
+/// it has no such lines.
+fn take_pin_directives(text: String) -> (Vec<PathBuf>, String) {
+    let mut paths = Vec::new();
+    let mut rest = Vec::new();
+    for line in text.lines() {
+        match line.trim().strip_prefix("@pin ") {
+            Some(path) if !path.trim().is_empty() => {
+                let path = path.trim();
+                paths.push(PathBuf::from(path.strip_prefix('@').unwrap_or(path)));
+            }
+            _ => rest.push(line),
+        }
+    }
+    if paths.is_empty() {
+        return (paths, text);
+    }
+    (paths, rest.join("\n").trim().to_string())
+}
+
 // Extract the first bold (Markdown) element in the form **...** from `s`.
 // Returns the inner text if found; otherwise `None`.
 fn extract_first_bold(s: &str) -> Option<String> {
@@ -2241,6 +2532,19 @@ fn extract_first_bold(s: &str) -> Option<String> {
     None
 }
 
+/// Parses the arguments of `/compact`, e.g. `drop-tool-outputs` or
+/// `keep-last 3`.
+fn parse_compaction_strategy(args: &str) -> Option<CompactionStrategy> {
+    let mut words = args.split_whitespace();
+    let strategy = match (words.next()?, words.next()) {
+        ("summarize", None) => CompactionStrategy::Summarize,
+        ("drop-tool-outputs", None) => CompactionStrategy::DropToolOutputs,
+        ("keep-last", Some(turns)) => CompactionStrategy::KeepLastTurns(turns.parse().ok()?),
+        _ => return None,
+    };
+    words.next().is_none().then_some(strategy)
+}
+
 #[cfg(test)]
 pub(crate) fn show_review_commit_picker_with_entries(
     chat: &mut ChatWidget,
@@ -1,9 +1,11 @@
 use std::collections::VecDeque;
 
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::CostBudgetApprovalRequestEvent;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::McpSamplingApprovalRequestEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyEndEvent;
@@ -14,6 +16,8 @@ use super::ChatWidget;
 pub(crate) enum QueuedInterrupt {
     ExecApproval(String, ExecApprovalRequestEvent),
     ApplyPatchApproval(String, ApplyPatchApprovalRequestEvent),
+    McpSamplingApproval(String, McpSamplingApprovalRequestEvent),
+    CostBudgetApproval(String, CostBudgetApprovalRequestEvent),
     ExecBegin(ExecCommandBeginEvent),
     ExecEnd(ExecCommandEndEvent),
     McpBegin(McpToolCallBeginEvent),
@@ -51,6 +55,24 @@ impl InterruptManager {
             .push_back(QueuedInterrupt::ApplyPatchApproval(id, ev));
     }
 
+    pub(crate) fn push_mcp_sampling_approval(
+        &mut self,
+        id: String,
+        ev: McpSamplingApprovalRequestEvent,
+    ) {
+        self.queue
+            .push_back(QueuedInterrupt::McpSamplingApproval(id, ev));
+    }
+
+    pub(crate) fn push_cost_budget_approval(
+        &mut self,
+        id: String,
+        ev: CostBudgetApprovalRequestEvent,
+    ) {
+        self.queue
+            .push_back(QueuedInterrupt::CostBudgetApproval(id, ev));
+    }
+
     pub(crate) fn push_exec_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.queue.push_back(QueuedInterrupt::ExecBegin(ev));
     }
@@ -78,6 +100,12 @@ impl InterruptManager {
                 QueuedInterrupt::ApplyPatchApproval(id, ev) => {
                     chat.handle_apply_patch_approval_now(id, ev)
                 }
+                QueuedInterrupt::McpSamplingApproval(id, ev) => {
+                    chat.handle_mcp_sampling_approval_now(id, ev)
+                }
+                QueuedInterrupt::CostBudgetApproval(id, ev) => {
+                    chat.handle_cost_budget_approval_now(id, ev)
+                }
                 QueuedInterrupt::ExecBegin(ev) => chat.handle_exec_begin_now(ev),
                 QueuedInterrupt::ExecEnd(ev) => chat.handle_exec_end_now(ev),
                 QueuedInterrupt::McpBegin(ev) => chat.handle_mcp_begin_now(ev),
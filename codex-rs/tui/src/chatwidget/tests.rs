@@ -22,10 +22,12 @@ use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::InputItem;
 use codex_core::protocol::InputMessageKind;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::PinnedItem;
 use codex_core::protocol::ReviewCodeLocation;
 use codex_core::protocol::ReviewFinding;
 use codex_core::protocol::ReviewLineRange;
@@ -268,6 +270,8 @@ fn make_chatwidget_manual() -> (
         session_header: SessionHeader::new(cfg.model),
         initial_user_message: None,
         token_info: None,
+        cost_usd: None,
+        pinned_items: Vec::new(),
         rate_limit_snapshot: None,
         rate_limit_warnings: RateLimitWarningState::default(),
         stream_controller: None,
@@ -390,6 +394,7 @@ fn exec_approval_emits_proposed_command_and_decision_history() {
         reason: Some(
             "this is a test reason such as one that would be produced by the model".into(),
         ),
+        sandbox_denials: Vec::new(),
     };
     chat.handle_codex_event(Event {
         id: "sub-short".into(),
@@ -431,6 +436,7 @@ fn exec_approval_decision_truncates_multiline_and_long_commands() {
         reason: Some(
             "this is a test reason such as one that would be produced by the model".into(),
         ),
+        sandbox_denials: Vec::new(),
     };
     chat.handle_codex_event(Event {
         id: "sub-multi".into(),
@@ -478,6 +484,7 @@ fn exec_approval_decision_truncates_multiline_and_long_commands() {
         command: vec!["bash".into(), "-lc".into(), long],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
         reason: None,
+        sandbox_denials: Vec::new(),
     };
     chat.handle_codex_event(Event {
         id: "sub-long".into(),
@@ -534,6 +541,7 @@ fn end_exec(chat: &mut ChatWidget, call_id: &str, stdout: &str, stderr: &str, ex
             exit_code,
             duration: std::time::Duration::from_millis(5),
             formatted_output: aggregated,
+            sandbox_denials: Vec::new(),
         }),
     });
 }
@@ -674,6 +682,104 @@ fn streaming_final_answer_keeps_task_running_state() {
     assert!(chat.bottom_pane.ctrl_c_quit_hint_visible());
 }
 
+#[test]
+fn compact_command_passes_the_requested_strategy() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.dispatch_command_with_args(SlashCommand::Compact, "keep-last 3");
+    chat.dispatch_command_with_args(SlashCommand::Compact, "keep-last three");
+
+    let mut strategies = Vec::new();
+    let mut errors = Vec::new();
+    while let Ok(ev) = rx.try_recv() {
+        match ev {
+            AppEvent::CodexOp(Op::Compact { strategy }) => strategies.push(strategy),
+            AppEvent::InsertHistoryCell(cell) => {
+                errors.push(lines_to_single_string(&cell.display_lines(80)));
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(strategies, vec![Some(CompactionStrategy::KeepLastTurns(3))]);
+    assert!(
+        errors
+            .iter()
+            .any(|error| error.contains("Unknown compaction strategy 'keep-last three'")),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn pin_directives_pin_files_instead_of_sending_a_turn() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+
+    chat.submit_user_message(UserMessage::from(
+        "@pin docs/spec.md\n@pin @logs/build.log".to_string(),
+    ));
+    chat.submit_user_message(UserMessage::from("@pin a.md\nfix the build".to_string()));
+
+    let mut pinned = Vec::new();
+    let mut turns = Vec::new();
+    while let Ok(op) = op_rx.try_recv() {
+        match op {
+            Op::PinItem {
+                item: PinnedItem::File { path },
+            } => pinned.push(path),
+            Op::UserInput { items } => turns.push(items),
+            _ => {}
+        }
+    }
+    assert_eq!(
+        pinned,
+        vec![
+            PathBuf::from("docs/spec.md"),
+            PathBuf::from("logs/build.log"),
+            PathBuf::from("a.md"),
+        ]
+    );
+    assert_eq!(turns.len(), 1);
+    assert!(
+        matches!(&turns[0][..], [InputItem::Text { text }] if text == "fix the build"),
+        "{turns:?}"
+    );
+}
+
+#[test]
+fn mcp_reload_command_and_event_refresh_mcp_state() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+
+    chat.dispatch_command_with_args(SlashCommand::Mcp, "reload");
+    let mut saw_reload = false;
+    while let Ok(ev) = rx.try_recv() {
+        saw_reload |= matches!(ev, AppEvent::ReloadMcpServers);
+    }
+    assert!(saw_reload, "expected AppEvent::ReloadMcpServers");
+
+    chat.handle_codex_event(Event {
+        id: "reload".into(),
+        msg: EventMsg::McpServersReloaded(McpServersReloadedEvent {
+            started: vec!["docs".to_string()],
+            restarted: Vec::new(),
+            stopped: vec!["search".to_string(), "web".to_string()],
+            tools: HashMap::new(),
+        }),
+    });
+
+    let cells = drain_insert_history(&mut rx);
+    let rendered = cells
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        rendered.contains("MCP servers reloaded: started docs; stopped search, web"),
+        "unexpected history: {rendered}"
+    );
+    assert!(rendered.contains("0 tools available"));
+
+    assert_matches!(op_rx.try_recv(), Ok(Op::ListCustomPrompts));
+    assert_matches!(op_rx.try_recv(), Ok(Op::ListMcpResources));
+}
+
 #[test]
 fn exec_history_cell_shows_working_then_completed() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -1309,6 +1415,7 @@ fn approval_modal_exec_snapshot() {
         reason: Some(
             "this is a test reason such as one that would be produced by the model".into(),
         ),
+        sandbox_denials: Vec::new(),
     };
     chat.handle_codex_event(Event {
         id: "sub-approve".into(),
@@ -1352,6 +1459,7 @@ fn approval_modal_exec_without_reason_snapshot() {
         command: vec!["bash".into(), "-lc".into(), "echo hello world".into()],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
         reason: None,
+        sandbox_denials: Vec::new(),
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-noreason".into(),
@@ -1561,6 +1669,7 @@ fn status_widget_and_approval_modal_snapshot() {
         reason: Some(
             "this is a test reason such as one that would be produced by the model".into(),
         ),
+        sandbox_denials: Vec::new(),
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-exec".into(),
@@ -2246,6 +2355,7 @@ fn chatwidget_exec_and_status_layout_vt100_snapshot() {
             exit_code: 0,
             duration: std::time::Duration::from_millis(16000),
             formatted_output: String::new(),
+            sandbox_denials: Vec::new(),
         }),
     });
     chat.handle_codex_event(Event {
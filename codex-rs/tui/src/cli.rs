@@ -1,6 +1,7 @@
 use clap::Parser;
 use codex_common::ApprovalModeCliArg;
 use codex_common::CliConfigOverrides;
+use codex_core::ConversationFilter;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -27,6 +28,17 @@ pub struct Cli {
     #[clap(skip)]
     pub resume_session_id: Option<String>,
 
+    /// Internal: with `resume_session_id`, start a new session from the history
+    /// before this 0-based user turn instead of resuming. Set by
+    /// `codex resume <SESSION_ID> --fork-at-turn <N>`.
+    #[clap(skip)]
+    pub resume_fork_at_turn: Option<usize>,
+
+    /// Internal: narrows down the sessions offered by the resume picker and
+    /// `--last`. Set by the `codex resume` filter flags.
+    #[clap(skip)]
+    pub resume_filter: ConversationFilter,
+
     /// Model the agent should use.
     #[arg(long, short = 'm')]
     pub model: Option<String>,
@@ -2,6 +2,9 @@ use std::time::Duration;
 use std::time::Instant;
 
 use codex_protocol::parse_command::ParsedCommand;
+use codex_protocol::protocol::SandboxDenial;
+
+use crate::exec_command::strip_bash_lc_and_escape;
 
 #[derive(Clone, Debug)]
 pub(crate) struct CommandOutput {
@@ -9,6 +12,7 @@ pub(crate) struct CommandOutput {
     pub(crate) stdout: String,
     pub(crate) stderr: String,
     pub(crate) formatted_output: String,
+    pub(crate) sandbox_denials: Vec<SandboxDenial>,
 }
 
 #[derive(Debug, Clone)]
@@ -85,6 +89,7 @@ impl ExecCell {
                     stdout: String::new(),
                     stderr: String::new(),
                     formatted_output: String::new(),
+                    sandbox_denials: Vec::new(),
                 });
             }
         }
@@ -109,6 +114,19 @@ impl ExecCell {
         self.calls.iter()
     }
 
+    /// The last finished command and its output as plain text, e.g. for
+    /// pinning it from the transcript.
+    pub(crate) fn last_output_text(&self) -> Option<String> {
+        self.calls.iter().rev().find_map(|call| {
+            let output = call.output.as_ref()?;
+            Some(format!(
+                "$ {}\n{}",
+                strip_bash_lc_and_escape(&call.command),
+                output.formatted_output
+            ))
+        })
+    }
+
     pub(super) fn is_exploring_call(call: &ExecCall) -> bool {
         !call.parsed.is_empty()
             && call.parsed.iter().all(|p| {
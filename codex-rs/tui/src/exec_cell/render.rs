@@ -117,6 +117,15 @@ pub(crate) fn output_lines(
     out
 }
 
+/// One `denied: <operation> <target>` line per operation the sandbox blocked.
+fn denial_lines(output: &CommandOutput) -> Vec<Line<'static>> {
+    output
+        .sandbox_denials
+        .iter()
+        .map(|denial| Line::from(denial.to_string().red()))
+        .collect()
+}
+
 pub(crate) fn spinner(start_time: Option<Instant>) -> Span<'static> {
     let elapsed = start_time.map(|st| st.elapsed()).unwrap_or_default();
     if supports_color::on_cached(supports_color::Stream::Stdout)
@@ -161,6 +170,7 @@ impl HistoryCell for ExecCell {
 
             if let Some(output) = call.output.as_ref() {
                 lines.extend(output.formatted_output.lines().map(ansi_escape_line));
+                lines.extend(denial_lines(output));
                 let duration = call
                     .duration
                     .map(format_duration)
@@ -409,6 +419,17 @@ impl ExecCell {
                     ));
                 }
             }
+
+            let wrapped_denials = word_wrap_lines(
+                &denial_lines(output),
+                RtOptions::new(layout.output_block.wrap_width(width))
+                    .word_splitter(WordSplitter::NoHyphenation),
+            );
+            lines.extend(prefix_lines(
+                wrapped_denials,
+                Span::from(layout.output_block.subsequent_prefix),
+                Span::from(layout.output_block.subsequent_prefix),
+            ));
         }
 
         lines
@@ -23,6 +23,7 @@ use base64::Engine;
 use codex_core::config::Config;
 use codex_core::config_types::McpServerTransportConfig;
 use codex_core::config_types::ReasoningSummaryFormat;
+use codex_core::executor::ApprovalPattern;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
@@ -227,6 +228,20 @@ impl AgentMessageCell {
             is_first_line,
         }
     }
+
+    /// The message text without the bullet and wrapping added for display.
+    pub(crate) fn plain_text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 }
 
 impl HistoryCell for AgentMessageCell {
@@ -352,6 +367,21 @@ pub fn new_approval_decision_cell(
                 ],
             )
         }
+        ApprovedForProject => {
+            let pattern = ApprovalPattern::for_command(&command)
+                .map(|pattern| pattern.to_string())
+                .unwrap_or_else(|| exec_snippet(&command));
+            (
+                "✔ ".green(),
+                vec![
+                    "You ".into(),
+                    "approved".bold(),
+                    " codex to run ".into(),
+                    Span::from(pattern).dim(),
+                    " every time in this project".bold(),
+                ],
+            )
+        }
         Denied => {
             let snippet = Span::from(exec_snippet(&command)).dim();
             (
@@ -1159,6 +1189,7 @@ pub(crate) fn new_patch_apply_failure(stderr: String) -> PlainHistoryCell {
                 stdout: String::new(),
                 stderr,
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             }),
             OutputLinesParams {
                 only_err: true,
@@ -1608,6 +1639,7 @@ mod tests {
                 stdout: String::new(),
                 stderr: String::new(),
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -1639,6 +1671,7 @@ mod tests {
                 stdout: String::new(),
                 stderr: String::new(),
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -1660,6 +1693,7 @@ mod tests {
                 stdout: String::new(),
                 stderr: String::new(),
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -1681,6 +1715,7 @@ mod tests {
                 stdout: String::new(),
                 stderr: String::new(),
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -1720,6 +1755,7 @@ mod tests {
                 stdout: String::new(),
                 stderr: String::new(),
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -1749,6 +1785,7 @@ mod tests {
                 stdout: String::new(),
                 stderr: String::new(),
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -1778,6 +1815,7 @@ mod tests {
                 stdout: String::new(),
                 stderr: String::new(),
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -1806,6 +1844,7 @@ mod tests {
                 stdout: String::new(),
                 stderr: String::new(),
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -1833,6 +1872,7 @@ mod tests {
                 stdout: String::new(),
                 stderr: String::new(),
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -1861,6 +1901,7 @@ mod tests {
                 stdout: String::new(),
                 stderr: String::new(),
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -1893,6 +1934,7 @@ mod tests {
                 stdout: String::new(),
                 stderr,
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -1937,6 +1979,7 @@ mod tests {
                 stdout: String::new(),
                 stderr,
                 formatted_output: String::new(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(5),
         );
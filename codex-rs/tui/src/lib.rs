@@ -59,6 +59,7 @@ mod pager_overlay;
 pub mod public_widgets;
 mod render;
 mod resume_picker;
+pub mod session_export;
 mod session_log;
 mod shimmer;
 mod slash_command;
@@ -148,6 +149,7 @@ pub async fn run_main(
         include_view_image_tool: None,
         show_raw_agent_reasoning: cli.oss.then_some(true),
         tools_web_search_request: cli.web_search.then_some(true),
+        cassette: None,
     };
     let raw_overrides = cli.config_overrides.raw_overrides.clone();
     let overrides_cli = codex_common::CliConfigOverrides { raw_overrides };
@@ -411,7 +413,13 @@ async fn run_ratatui_app(
     // Determine resume behavior: explicit id, then resume last, then picker.
     let resume_selection = if let Some(id_str) = cli.resume_session_id.as_deref() {
         match find_conversation_path_by_id_str(&config.codex_home, id_str).await? {
-            Some(path) => resume_picker::ResumeSelection::Resume(path),
+            Some(path) => match cli.resume_fork_at_turn {
+                Some(nth_user_message) => resume_picker::ResumeSelection::Fork {
+                    path,
+                    nth_user_message,
+                },
+                None => resume_picker::ResumeSelection::Resume(path),
+            },
             None => {
                 error!("Error finding conversation path: {id_str}");
                 resume_picker::ResumeSelection::StartFresh
@@ -423,6 +431,7 @@ async fn run_ratatui_app(
             1,
             None,
             INTERACTIVE_SESSION_SOURCES,
+            &cli.resume_filter,
         )
         .await
         {
@@ -434,7 +443,13 @@ async fn run_ratatui_app(
             Err(_) => resume_picker::ResumeSelection::StartFresh,
         }
     } else if cli.resume_picker {
-        match resume_picker::run_resume_picker(&mut tui, &config.codex_home).await? {
+        match resume_picker::run_resume_picker(
+            &mut tui,
+            &config.codex_home,
+            cli.resume_filter.clone(),
+        )
+        .await?
+        {
             resume_picker::ResumeSelection::Exit => {
                 restore();
                 session_log::log_session_end();
@@ -74,6 +74,9 @@ const KEY_END: KeyBinding = key_hint::plain(KeyCode::End);
 const KEY_Q: KeyBinding = key_hint::plain(KeyCode::Char('q'));
 const KEY_ESC: KeyBinding = key_hint::plain(KeyCode::Esc);
 const KEY_ENTER: KeyBinding = key_hint::plain(KeyCode::Enter);
+const KEY_P: KeyBinding = key_hint::plain(KeyCode::Char('p'));
+const KEY_A: KeyBinding = key_hint::plain(KeyCode::Char('a'));
+const KEY_O: KeyBinding = key_hint::plain(KeyCode::Char('o'));
 const KEY_CTRL_T: KeyBinding = key_hint::ctrl(KeyCode::Char('t'));
 const KEY_CTRL_C: KeyBinding = key_hint::ctrl(KeyCode::Char('c'));
 
@@ -453,6 +456,9 @@ impl TranscriptOverlay {
             vec![(&[KEY_Q], "to quit"), (&[KEY_ESC], "to edit prev")];
         if self.highlight_cell.is_some() {
             pairs.push((&[KEY_ENTER], "to edit message"));
+            pairs.push((&[KEY_P], "to pin message"));
+            pairs.push((&[KEY_A], "reply"));
+            pairs.push((&[KEY_O], "output"));
         }
         render_key_hints(line2, buf, &pairs);
     }
@@ -727,6 +733,7 @@ mod tests {
                 stdout: "src\nREADME.md\n".into(),
                 stderr: String::new(),
                 formatted_output: "src\nREADME.md\n".into(),
+                sandbox_denials: Vec::new(),
             },
             Duration::from_millis(420),
         );
@@ -2,14 +2,19 @@ use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
 
 use chrono::DateTime;
 use chrono::Utc;
+use codex_core::ConversationFilter;
 use codex_core::ConversationItem;
 use codex_core::ConversationsPage;
 use codex_core::Cursor;
 use codex_core::INTERACTIVE_SESSION_SOURCES;
 use codex_core::RolloutRecorder;
+use codex_core::SearchHitKind;
+use codex_core::SessionSearchHit;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -21,6 +26,7 @@ use ratatui::style::Stylize as _;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use unicode_width::UnicodeWidthStr;
@@ -30,18 +36,23 @@ use crate::text_formatting::truncate_text;
 use crate::tui::FrameRequester;
 use crate::tui::Tui;
 use crate::tui::TuiEvent;
-use codex_protocol::models::ContentItem;
-use codex_protocol::models::ResponseItem;
-use codex_protocol::protocol::InputMessageKind;
-use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 
 const PAGE_SIZE: usize = 25;
 const LOAD_NEAR_THRESHOLD: usize = 5;
+const FULL_TEXT_RESULT_LIMIT: usize = 100;
+/// Full-text searches read every session, so they wait for typing to pause.
+const FULL_TEXT_DEBOUNCE: Duration = Duration::from_millis(250);
 
 #[derive(Debug, Clone)]
 pub enum ResumeSelection {
     StartFresh,
     Resume(PathBuf),
+    /// Start a new session from the history of `path` up to, but not
+    /// including, its `nth_user_message`.
+    Fork {
+        path: PathBuf,
+        nth_user_message: usize,
+    },
     Exit,
 }
 
@@ -55,30 +66,55 @@ struct PageLoadRequest {
 
 type PageLoader = Arc<dyn Fn(PageLoadRequest) + Send + Sync>;
 
+#[derive(Clone)]
+struct FullTextRequest {
+    codex_home: PathBuf,
+    query: String,
+    search_token: usize,
+}
+
+/// Starts a full-text search, replacing the one in flight. `None` only
+/// cancels the search in flight.
+type FullTextLoader = Arc<dyn Fn(Option<FullTextRequest>) + Send + Sync>;
+
 enum BackgroundEvent {
     PageLoaded {
         request_token: usize,
         search_token: Option<usize>,
         page: std::io::Result<ConversationsPage>,
     },
+    FullTextLoaded {
+        search_token: usize,
+        hits: std::io::Result<Vec<SessionSearchHit>>,
+    },
 }
 
 /// Interactive session picker that lists recorded rollout files with simple
 /// search and pagination. Shows the first user input as the preview, relative
-/// time (e.g., "5 seconds ago"), and the absolute path.
-pub async fn run_resume_picker(tui: &mut Tui, codex_home: &Path) -> Result<ResumeSelection> {
+/// time (e.g., "5 seconds ago"), and the absolute path. Tab switches to a
+/// full-text search over the messages and commands of every session.
+pub async fn run_resume_picker(
+    tui: &mut Tui,
+    codex_home: &Path,
+    filter: ConversationFilter,
+) -> Result<ResumeSelection> {
     let alt = AltScreenGuard::enter(tui);
     let (bg_tx, bg_rx) = mpsc::unbounded_channel();
 
+    let full_text_loader = debounced_full_text_loader(bg_tx.clone(), filter.clone());
+
     let loader_tx = bg_tx.clone();
+    let page_filter = filter.clone();
     let page_loader: PageLoader = Arc::new(move |request: PageLoadRequest| {
         let tx = loader_tx.clone();
+        let filter = page_filter.clone();
         tokio::spawn(async move {
             let page = RolloutRecorder::list_conversations(
                 &request.codex_home,
                 PAGE_SIZE,
                 request.cursor.as_ref(),
                 INTERACTIVE_SESSION_SOURCES,
+                &filter,
             )
             .await;
             let _ = tx.send(BackgroundEvent::PageLoaded {
@@ -93,6 +129,8 @@ pub async fn run_resume_picker(tui: &mut Tui, codex_home: &Path) -> Result<Resum
         codex_home.to_path_buf(),
         alt.tui.frame_requester(),
         page_loader,
+        full_text_loader,
+        filter,
     );
     state.load_initial_page().await?;
     state.request_frame();
@@ -152,8 +190,50 @@ impl Drop for AltScreenGuard<'_> {
     }
 }
 
+/// Returns a loader that runs one full-text search at a time, starting it
+/// once [`FULL_TEXT_DEBOUNCE`] passes without a newer request.
+fn debounced_full_text_loader(
+    tx: mpsc::UnboundedSender<BackgroundEvent>,
+    filter: ConversationFilter,
+) -> FullTextLoader {
+    let in_flight: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+    Arc::new(move |request: Option<FullTextRequest>| {
+        let Ok(mut in_flight) = in_flight.lock() else {
+            return;
+        };
+        if let Some(previous) = in_flight.take() {
+            previous.abort();
+        }
+        let Some(request) = request else {
+            return;
+        };
+        let tx = tx.clone();
+        let filter = filter.clone();
+        *in_flight = Some(tokio::spawn(async move {
+            tokio::time::sleep(FULL_TEXT_DEBOUNCE).await;
+            let hits = RolloutRecorder::search_conversations(
+                &request.codex_home,
+                &request.query,
+                INTERACTIVE_SESSION_SOURCES,
+                FULL_TEXT_RESULT_LIMIT,
+            )
+            .await
+            .map(|mut hits| {
+                hits.retain(|hit| filter.matches(&hit.conversation));
+                hits
+            });
+            let _ = tx.send(BackgroundEvent::FullTextLoaded {
+                search_token: request.search_token,
+                hits,
+            });
+        }));
+    })
+}
+
 struct PickerState {
     codex_home: PathBuf,
+    /// Restricts both the listing and the full-text search.
+    filter: ConversationFilter,
     requester: FrameRequester,
     pagination: PaginationState,
     all_rows: Vec<Row>,
@@ -167,12 +247,19 @@ struct PickerState {
     next_search_token: usize,
     page_loader: PageLoader,
     view_rows: Option<usize>,
+    full_text: FullTextState,
+    full_text_loader: FullTextLoader,
+}
+
+/// Rows found by the full-text search; `enabled` is toggled with Tab.
+struct FullTextState {
+    enabled: bool,
+    rows: Vec<Row>,
+    pending_token: Option<usize>,
 }
 
 struct PaginationState {
     next_cursor: Option<Cursor>,
-    num_scanned_files: usize,
-    reached_scan_cap: bool,
     loading: LoadingState,
 }
 
@@ -222,19 +309,42 @@ impl SearchState {
 struct Row {
     path: PathBuf,
     preview: String,
+    cwd: PathBuf,
+    git_branch: Option<String>,
     created_at: Option<DateTime<Utc>>,
     updated_at: Option<DateTime<Utc>>,
+    /// Set for rows produced by the full-text search: the user turn the match
+    /// belongs to, which is where a fork starts.
+    turn: Option<usize>,
+}
+
+impl Row {
+    /// Matches the lowercased search query against the preview, the git branch
+    /// and the working directory of the session.
+    fn matches_query(&self, query: &str) -> bool {
+        self.preview.to_lowercase().contains(query)
+            || self
+                .git_branch
+                .as_ref()
+                .is_some_and(|branch| branch.to_lowercase().contains(query))
+            || self.cwd.to_string_lossy().to_lowercase().contains(query)
+    }
 }
 
 impl PickerState {
-    fn new(codex_home: PathBuf, requester: FrameRequester, page_loader: PageLoader) -> Self {
+    fn new(
+        codex_home: PathBuf,
+        requester: FrameRequester,
+        page_loader: PageLoader,
+        full_text_loader: FullTextLoader,
+        filter: ConversationFilter,
+    ) -> Self {
         Self {
             codex_home,
+            filter,
             requester,
             pagination: PaginationState {
                 next_cursor: None,
-                num_scanned_files: 0,
-                reached_scan_cap: false,
                 loading: LoadingState::Idle,
             },
             all_rows: Vec::new(),
@@ -248,6 +358,12 @@ impl PickerState {
             next_search_token: 0,
             page_loader,
             view_rows: None,
+            full_text: FullTextState {
+                enabled: false,
+                rows: Vec::new(),
+                pending_token: None,
+            },
+            full_text_loader,
         }
     }
 
@@ -265,11 +381,26 @@ impl PickerState {
             {
                 return Ok(Some(ResumeSelection::Exit));
             }
+            KeyCode::Char('f')
+                if key
+                    .modifiers
+                    .contains(crossterm::event::KeyModifiers::CONTROL) =>
+            {
+                if let Some(row) = self.filtered_rows.get(self.selected)
+                    && let Some(turn) = row.turn
+                {
+                    return Ok(Some(ResumeSelection::Fork {
+                        path: row.path.clone(),
+                        nth_user_message: turn,
+                    }));
+                }
+            }
             KeyCode::Enter => {
                 if let Some(row) = self.filtered_rows.get(self.selected) {
                     return Ok(Some(ResumeSelection::Resume(row.path.clone())));
                 }
             }
+            KeyCode::Tab => self.toggle_full_text(),
             KeyCode::Up => {
                 if self.selected > 0 {
                     self.selected -= 1;
@@ -331,6 +462,7 @@ impl PickerState {
             PAGE_SIZE,
             None,
             INTERACTIVE_SESSION_SOURCES,
+            &self.filter,
         )
         .await?;
         self.reset_pagination();
@@ -363,14 +495,21 @@ impl PickerState {
                 let completed_token = pending.search_token.or(search_token);
                 self.continue_search_if_token_matches(completed_token);
             }
+            BackgroundEvent::FullTextLoaded { search_token, hits } => {
+                if self.full_text.pending_token != Some(search_token) {
+                    return Ok(());
+                }
+                self.full_text.pending_token = None;
+                let hits = hits.map_err(color_eyre::Report::from)?;
+                self.full_text.rows = hits.iter().map(hit_to_row).collect();
+                self.apply_filter();
+            }
         }
         Ok(())
     }
 
     fn reset_pagination(&mut self) {
         self.pagination.next_cursor = None;
-        self.pagination.num_scanned_files = 0;
-        self.pagination.reached_scan_cap = false;
         self.pagination.loading = LoadingState::Idle;
     }
 
@@ -380,13 +519,6 @@ impl PickerState {
         } else {
             self.pagination.next_cursor = None;
         }
-        self.pagination.num_scanned_files = self
-            .pagination
-            .num_scanned_files
-            .saturating_add(page.num_scanned_files);
-        if page.reached_scan_cap {
-            self.pagination.reached_scan_cap = true;
-        }
 
         let rows = rows_from_items(page.items);
         for row in rows {
@@ -399,14 +531,16 @@ impl PickerState {
     }
 
     fn apply_filter(&mut self) {
-        if self.query.is_empty() {
+        if self.full_text.enabled {
+            self.filtered_rows = self.full_text.rows.clone();
+        } else if self.query.is_empty() {
             self.filtered_rows = self.all_rows.clone();
         } else {
             let q = self.query.to_lowercase();
             self.filtered_rows = self
                 .all_rows
                 .iter()
-                .filter(|r| r.preview.to_lowercase().contains(&q))
+                .filter(|r| r.matches_query(&q))
                 .cloned()
                 .collect();
         }
@@ -426,6 +560,10 @@ impl PickerState {
         }
         self.query = new_query;
         self.selected = 0;
+        if self.full_text.enabled {
+            self.start_full_text_search();
+            return;
+        }
         self.apply_filter();
         if self.query.is_empty() {
             self.search_state = SearchState::Idle;
@@ -435,7 +573,7 @@ impl PickerState {
             self.search_state = SearchState::Idle;
             return;
         }
-        if self.pagination.reached_scan_cap || self.pagination.next_cursor.is_none() {
+        if self.pagination.next_cursor.is_none() {
             self.search_state = SearchState::Idle;
             return;
         }
@@ -444,6 +582,41 @@ impl PickerState {
         self.load_more_if_needed(LoadTrigger::Search { token });
     }
 
+    fn toggle_full_text(&mut self) {
+        self.full_text.enabled = !self.full_text.enabled;
+        self.selected = 0;
+        self.scroll_top = 0;
+        if self.full_text.enabled {
+            self.search_state = SearchState::Idle;
+            self.start_full_text_search();
+        } else {
+            self.full_text.pending_token = None;
+            (self.full_text_loader)(None);
+            self.full_text.rows.clear();
+            // Re-run the session search, which may need to page in more rows.
+            let query = std::mem::take(&mut self.query);
+            self.set_query(query);
+            self.apply_filter();
+        }
+    }
+
+    fn start_full_text_search(&mut self) {
+        self.full_text.rows.clear();
+        if self.query.trim().is_empty() {
+            self.full_text.pending_token = None;
+            (self.full_text_loader)(None);
+        } else {
+            let search_token = self.allocate_search_token();
+            self.full_text.pending_token = Some(search_token);
+            (self.full_text_loader)(Some(FullTextRequest {
+                codex_home: self.codex_home.clone(),
+                query: self.query.clone(),
+                search_token,
+            }));
+        }
+        self.apply_filter();
+    }
+
     fn continue_search_if_needed(&mut self) {
         let Some(token) = self.search_state.active_token() else {
             return;
@@ -452,7 +625,7 @@ impl PickerState {
             self.search_state = SearchState::Idle;
             return;
         }
-        if self.pagination.reached_scan_cap || self.pagination.next_cursor.is_none() {
+        if self.pagination.next_cursor.is_none() {
             self.search_state = SearchState::Idle;
             return;
         }
@@ -494,7 +667,7 @@ impl PickerState {
     }
 
     fn ensure_minimum_rows_for_view(&mut self, minimum_rows: usize) {
-        if minimum_rows == 0 {
+        if minimum_rows == 0 || self.full_text.enabled {
             return;
         }
         if self.filtered_rows.len() >= minimum_rows {
@@ -516,7 +689,7 @@ impl PickerState {
     }
 
     fn maybe_load_more_for_scroll(&mut self) {
-        if self.pagination.loading.is_pending() {
+        if self.pagination.loading.is_pending() || self.full_text.enabled {
             return;
         }
         if self.pagination.next_cursor.is_none() {
@@ -575,82 +748,49 @@ fn rows_from_items(items: Vec<ConversationItem>) -> Vec<Row> {
 }
 
 fn head_to_row(item: &ConversationItem) -> Row {
-    let created_at = item
-        .created_at
-        .as_deref()
-        .and_then(parse_timestamp_str)
-        .or_else(|| item.head.first().and_then(extract_timestamp));
-    let updated_at = item
-        .updated_at
-        .as_deref()
-        .and_then(parse_timestamp_str)
-        .or(created_at);
+    let created_at = parse_timestamp_str(&item.created_at);
+    let updated_at = parse_timestamp_str(&item.updated_at).or(created_at);
 
-    let preview = preview_from_head(&item.head)
-        .map(|s| s.trim().to_string())
+    let preview = item
+        .preview
+        .as_deref()
+        .map(str::trim)
         .filter(|s| !s.is_empty())
-        .unwrap_or_else(|| String::from("(no message yet)"));
+        .unwrap_or("(no message yet)")
+        .to_string();
 
     Row {
         path: item.path.clone(),
         preview,
+        cwd: item.cwd.clone(),
+        git_branch: item.git_branch.clone(),
         created_at,
         updated_at,
+        turn: None,
     }
 }
 
+/// Shows the snippet of a full-text hit, labelled with where it was found,
+/// and dates it by the matching record.
+fn hit_to_row(hit: &SessionSearchHit) -> Row {
+    let mut row = head_to_row(&hit.conversation);
+    let label = match hit.kind {
+        SearchHitKind::UserMessage => "you:",
+        SearchHitKind::AssistantMessage => "codex:",
+        SearchHitKind::Command => "$",
+    };
+    row.preview = format!("{label} {}", hit.snippet);
+    row.updated_at = parse_timestamp_str(&hit.timestamp).or(row.updated_at);
+    row.turn = Some(hit.turn);
+    row
+}
+
 fn parse_timestamp_str(ts: &str) -> Option<DateTime<Utc>> {
     chrono::DateTime::parse_from_rfc3339(ts)
         .map(|dt| dt.with_timezone(&Utc))
         .ok()
 }
 
-fn extract_timestamp(value: &serde_json::Value) -> Option<DateTime<Utc>> {
-    value
-        .get("timestamp")
-        .and_then(|v| v.as_str())
-        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
-        .map(|dt| dt.with_timezone(&Utc))
-}
-
-fn preview_from_head(head: &[serde_json::Value]) -> Option<String> {
-    head.iter()
-        .filter_map(|value| serde_json::from_value::<ResponseItem>(value.clone()).ok())
-        .find_map(|item| match item {
-            ResponseItem::Message { content, .. } => {
-                // Find the actual user message (as opposed to user instructions or ide context)
-                let preview = content
-                    .into_iter()
-                    .filter_map(|content| match content {
-                        ContentItem::InputText { text }
-                            if matches!(
-                                InputMessageKind::from(("user", text.as_str())),
-                                InputMessageKind::Plain
-                            ) =>
-                        {
-                            // Strip ide context.
-                            let text = match text.find(USER_MESSAGE_BEGIN) {
-                                Some(idx) => {
-                                    text[idx + USER_MESSAGE_BEGIN.len()..].trim().to_string()
-                                }
-                                None => text,
-                            };
-                            Some(text)
-                        }
-                        _ => None,
-                    })
-                    .collect::<String>();
-
-                if preview.is_empty() {
-                    None
-                } else {
-                    Some(preview)
-                }
-            }
-            _ => None,
-        })
-}
-
 fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
     // Render full-screen overlay
     let height = tui.terminal.size()?.height;
@@ -672,10 +812,11 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
         );
 
         // Search line
-        let q = if state.query.is_empty() {
-            "Type to search".dim().to_string()
-        } else {
-            format!("Search: {}", state.query)
+        let q = match (state.full_text.enabled, state.query.is_empty()) {
+            (false, true) => "Type to search".dim().to_string(),
+            (false, false) => format!("Search: {}", state.query),
+            (true, true) => "Type to search all messages and commands".dim().to_string(),
+            (true, false) => format!("Search all messages: {}", state.query),
         };
         frame.render_widget_ref(Line::from(q), search);
 
@@ -686,10 +827,27 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
         render_list(frame, list, state, &metrics);
 
         // Hint line
-        let hint_line: Line = vec![
+        let mut hint_spans: Vec<Span> = vec![
             key_hint::plain(KeyCode::Enter).into(),
             " to resume ".dim(),
             "    ".dim(),
+        ];
+        if state.full_text.enabled {
+            hint_spans.extend([
+                key_hint::ctrl(KeyCode::Char('f')).into(),
+                " to fork at match ".dim(),
+                "    ".dim(),
+                key_hint::plain(KeyCode::Tab).into(),
+                " to search sessions ".dim(),
+            ]);
+        } else {
+            hint_spans.extend([
+                key_hint::plain(KeyCode::Tab).into(),
+                " to search messages ".dim(),
+            ]);
+        }
+        hint_spans.extend([
+            "    ".dim(),
             key_hint::plain(KeyCode::Esc).into(),
             " to start new ".dim(),
             "    ".dim(),
@@ -700,9 +858,8 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
             "/".dim(),
             key_hint::plain(KeyCode::Down).into(),
             " to browse".dim(),
-        ]
-        .into();
-        frame.render_widget_ref(hint_line, hint);
+        ]);
+        frame.render_widget_ref(Line::from(hint_spans), hint);
     })
 }
 
@@ -783,7 +940,10 @@ fn render_list(
         y = y.saturating_add(1);
     }
 
-    if state.pagination.loading.is_pending() && y < area.y.saturating_add(area.height) {
+    if !state.full_text.enabled
+        && state.pagination.loading.is_pending()
+        && y < area.y.saturating_add(area.height)
+    {
         let loading_line: Line = vec!["  ".into(), "Loading older sessions…".italic().dim()].into();
         let rect = Rect::new(area.x, y, area.width, 1);
         frame.render_widget_ref(loading_line, rect);
@@ -791,26 +951,25 @@ fn render_list(
 }
 
 fn render_empty_state_line(state: &PickerState) -> Line<'static> {
+    if state.full_text.enabled {
+        if state.query.trim().is_empty() {
+            return vec!["Type to search all messages and commands".italic().dim()].into();
+        }
+        if state.full_text.pending_token.is_some() {
+            return vec!["Searching…".italic().dim()].into();
+        }
+        return vec!["No results for your search".italic().dim()].into();
+    }
+
     if !state.query.is_empty() {
         if state.search_state.is_active()
             || (state.pagination.loading.is_pending() && state.pagination.next_cursor.is_some())
         {
             return vec!["Searching…".italic().dim()].into();
         }
-        if state.pagination.reached_scan_cap {
-            let msg = format!(
-                "Search scanned first {} sessions; more may exist",
-                state.pagination.num_scanned_files
-            );
-            return vec![Span::from(msg).italic().dim()].into();
-        }
         return vec!["No results for your search".italic().dim()].into();
     }
 
-    if state.all_rows.is_empty() && state.pagination.num_scanned_files == 0 {
-        return vec!["No sessions yet".italic().dim()].into();
-    }
-
     if state.pagination.loading.is_pending() {
         return vec!["Loading older sessions…".italic().dim()].into();
     }
@@ -929,37 +1088,28 @@ fn calculate_column_metrics(rows: &[Row]) -> ColumnMetrics {
 mod tests {
     use super::*;
     use chrono::Duration;
+    use codex_protocol::ConversationId;
+    use codex_protocol::protocol::SessionSource;
     use crossterm::event::KeyCode;
     use crossterm::event::KeyEvent;
     use crossterm::event::KeyModifiers;
     use insta::assert_snapshot;
-    use serde_json::json;
     use std::future::Future;
     use std::path::PathBuf;
     use std::sync::Arc;
     use std::sync::Mutex;
 
-    fn head_with_ts_and_user_text(ts: &str, texts: &[&str]) -> Vec<serde_json::Value> {
-        vec![
-            json!({ "timestamp": ts }),
-            json!({
-                "type": "message",
-                "role": "user",
-                "content": texts
-                    .iter()
-                    .map(|t| json!({ "type": "input_text", "text": *t }))
-                    .collect::<Vec<_>>()
-            }),
-        ]
-    }
-
     fn make_item(path: &str, ts: &str, preview: &str) -> ConversationItem {
         ConversationItem {
+            id: ConversationId::new(),
             path: PathBuf::from(path),
-            head: head_with_ts_and_user_text(ts, &[preview]),
-            tail: Vec::new(),
-            created_at: Some(ts.to_string()),
-            updated_at: Some(ts.to_string()),
+            source: SessionSource::Cli,
+            cwd: PathBuf::from("/tmp/project"),
+            git_branch: Some("main".to_string()),
+            preview: Some(preview.to_string()),
+            created_at: ts.to_string(),
+            updated_at: ts.to_string(),
+            total_tokens: 0,
         }
     }
 
@@ -968,18 +1118,8 @@ mod tests {
             .expect("cursor format should deserialize")
     }
 
-    fn page(
-        items: Vec<ConversationItem>,
-        next_cursor: Option<Cursor>,
-        num_scanned_files: usize,
-        reached_scan_cap: bool,
-    ) -> ConversationsPage {
-        ConversationsPage {
-            items,
-            next_cursor,
-            num_scanned_files,
-            reached_scan_cap,
-        }
+    fn page(items: Vec<ConversationItem>, next_cursor: Option<Cursor>) -> ConversationsPage {
+        ConversationsPage { items, next_cursor }
     }
 
     fn block_on_future<F: Future<Output = T>, T>(future: F) -> T {
@@ -990,46 +1130,10 @@ mod tests {
             .block_on(future)
     }
 
-    #[test]
-    fn preview_uses_first_message_input_text() {
-        let head = vec![
-            json!({ "timestamp": "2025-01-01T00:00:00Z" }),
-            json!({
-                "type": "message",
-                "role": "user",
-                "content": [
-                    { "type": "input_text", "text": "<user_instructions>hi</user_instructions>" },
-                    { "type": "input_text", "text": "real question" },
-                    { "type": "input_image", "image_url": "ignored" }
-                ]
-            }),
-            json!({
-                "type": "message",
-                "role": "user",
-                "content": [ { "type": "input_text", "text": "later text" } ]
-            }),
-        ];
-        let preview = preview_from_head(&head);
-        assert_eq!(preview.as_deref(), Some("real question"));
-    }
-
     #[test]
     fn rows_from_items_preserves_backend_order() {
-        // Construct two items with different timestamps and real user text.
-        let a = ConversationItem {
-            path: PathBuf::from("/tmp/a.jsonl"),
-            head: head_with_ts_and_user_text("2025-01-01T00:00:00Z", &["A"]),
-            tail: Vec::new(),
-            created_at: Some("2025-01-01T00:00:00Z".into()),
-            updated_at: Some("2025-01-01T00:00:00Z".into()),
-        };
-        let b = ConversationItem {
-            path: PathBuf::from("/tmp/b.jsonl"),
-            head: head_with_ts_and_user_text("2025-01-02T00:00:00Z", &["B"]),
-            tail: Vec::new(),
-            created_at: Some("2025-01-02T00:00:00Z".into()),
-            updated_at: Some("2025-01-02T00:00:00Z".into()),
-        };
+        let a = make_item("/tmp/a.jsonl", "2025-01-01T00:00:00Z", "A");
+        let b = make_item("/tmp/b.jsonl", "2025-01-02T00:00:00Z", "B");
         let rows = rows_from_items(vec![a, b]);
         assert_eq!(rows.len(), 2);
         // Preserve the given order even if timestamps differ; backend already provides newest-first.
@@ -1038,26 +1142,9 @@ mod tests {
     }
 
     #[test]
-    fn row_uses_tail_timestamp_for_updated_at() {
-        let head = head_with_ts_and_user_text("2025-01-01T00:00:00Z", &["Hello"]);
-        let tail = vec![json!({
-            "timestamp": "2025-01-01T01:00:00Z",
-            "type": "message",
-            "role": "assistant",
-            "content": [
-                {
-                    "type": "output_text",
-                    "text": "hi",
-                }
-            ],
-        })];
-        let item = ConversationItem {
-            path: PathBuf::from("/tmp/a.jsonl"),
-            head,
-            tail,
-            created_at: Some("2025-01-01T00:00:00Z".into()),
-            updated_at: Some("2025-01-01T01:00:00Z".into()),
-        };
+    fn row_uses_updated_at_from_index() {
+        let mut item = make_item("/tmp/a.jsonl", "2025-01-01T00:00:00Z", "Hello");
+        item.updated_at = "2025-01-01T01:00:00Z".to_string();
 
         let row = head_to_row(&item);
         let expected_created = chrono::DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
@@ -1071,6 +1158,145 @@ mod tests {
         assert_eq!(row.updated_at, Some(expected_updated));
     }
 
+    #[test]
+    fn search_matches_branch_and_cwd() {
+        let loader: PageLoader = Arc::new(|_| {});
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            Arc::new(|_| {}),
+            ConversationFilter::default(),
+        );
+        let mut feature = make_item("/tmp/a.jsonl", "2025-01-02T00:00:00Z", "add tests");
+        feature.git_branch = Some("feature/login".to_string());
+        let mut other = make_item("/tmp/b.jsonl", "2025-01-01T00:00:00Z", "fix typo");
+        other.cwd = PathBuf::from("/work/website");
+        state.ingest_page(page(vec![feature, other], None));
+
+        state.set_query("login".to_string());
+        assert_eq!(state.filtered_rows.len(), 1);
+        assert_eq!(state.filtered_rows[0].preview, "add tests");
+
+        state.set_query("website".to_string());
+        assert_eq!(state.filtered_rows.len(), 1);
+        assert_eq!(state.filtered_rows[0].preview, "fix typo");
+    }
+
+    #[tokio::test]
+    async fn full_text_loader_only_runs_the_latest_search() {
+        let codex_home = tempfile::TempDir::new().unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let loader = debounced_full_text_loader(tx, ConversationFilter::default());
+        for (search_token, query) in ["m", "mi", "mig"].into_iter().enumerate() {
+            loader(Some(FullTextRequest {
+                codex_home: codex_home.path().to_path_buf(),
+                query: query.to_string(),
+                search_token,
+            }));
+        }
+
+        let Some(BackgroundEvent::FullTextLoaded { search_token, hits }) = rx.recv().await else {
+            panic!("expected full-text results");
+        };
+        assert_eq!(search_token, 2);
+        assert!(hits.unwrap().is_empty());
+
+        loader(Some(FullTextRequest {
+            codex_home: codex_home.path().to_path_buf(),
+            query: "migration".to_string(),
+            search_token: 3,
+        }));
+        loader(None);
+        drop(loader);
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[test]
+    fn full_text_search_forks_at_matching_turn() {
+        let recorded_requests: Arc<Mutex<Vec<FullTextRequest>>> = Arc::new(Mutex::new(Vec::new()));
+        let request_sink = recorded_requests.clone();
+        let full_text_loader: FullTextLoader = Arc::new(move |req: Option<FullTextRequest>| {
+            if let Some(req) = req {
+                request_sink.lock().unwrap().push(req);
+            }
+        });
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            Arc::new(|_| {}),
+            full_text_loader,
+            ConversationFilter::default(),
+        );
+        state.ingest_page(page(
+            vec![make_item("/tmp/a.jsonl", "2025-01-01T00:00:00Z", "hello")],
+            None,
+        ));
+
+        block_on_future(async {
+            state
+                .handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE))
+                .await
+                .unwrap();
+        });
+        assert!(state.filtered_rows.is_empty());
+        state.set_query("migr".to_string());
+        state.set_query("migration".to_string());
+        let (stale, current) = {
+            let guard = recorded_requests.lock().unwrap();
+            assert_eq!(guard.len(), 2);
+            (guard[0].search_token, guard[1].clone())
+        };
+        assert_eq!(current.query, "migration");
+
+        let hit = |turn: usize| SessionSearchHit {
+            conversation: make_item("/tmp/b.jsonl", "2025-01-02T00:00:00Z", "start"),
+            kind: SearchHitKind::Command,
+            turn,
+            snippet: "cargo run-migration".to_string(),
+            timestamp: "2025-01-02T00:05:00Z".to_string(),
+            score: 2,
+        };
+        state
+            .handle_background_event(BackgroundEvent::FullTextLoaded {
+                search_token: stale,
+                hits: Ok(vec![hit(0)]),
+            })
+            .unwrap();
+        assert!(state.filtered_rows.is_empty());
+        state
+            .handle_background_event(BackgroundEvent::FullTextLoaded {
+                search_token: current.search_token,
+                hits: Ok(vec![hit(3)]),
+            })
+            .unwrap();
+        assert_eq!(state.filtered_rows.len(), 1);
+        assert_eq!(state.filtered_rows[0].preview, "$ cargo run-migration");
+
+        let selection = block_on_future(async {
+            state
+                .handle_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL))
+                .await
+                .unwrap()
+        });
+        assert!(matches!(
+            selection,
+            Some(ResumeSelection::Fork { path, nth_user_message: 3 })
+                if path == PathBuf::from("/tmp/b.jsonl")
+        ));
+
+        block_on_future(async {
+            state
+                .handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE))
+                .await
+                .unwrap();
+        });
+        assert!(state.filtered_rows.is_empty());
+        state.set_query(String::new());
+        assert_eq!(state.filtered_rows.len(), 1);
+        assert_eq!(state.filtered_rows[0].preview, "hello");
+    }
+
     #[test]
     fn resume_table_snapshot() {
         use crate::custom_terminal::Terminal;
@@ -1079,28 +1305,42 @@ mod tests {
         use ratatui::layout::Layout;
 
         let loader: PageLoader = Arc::new(|_| {});
-        let mut state =
-            PickerState::new(PathBuf::from("/tmp"), FrameRequester::test_dummy(), loader);
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            Arc::new(|_| {}),
+            ConversationFilter::default(),
+        );
 
         let now = Utc::now();
         let rows = vec![
             Row {
                 path: PathBuf::from("/tmp/a.jsonl"),
                 preview: String::from("Fix resume picker timestamps"),
+                cwd: PathBuf::from("/tmp/project"),
+                git_branch: None,
                 created_at: Some(now - Duration::minutes(16)),
                 updated_at: Some(now - Duration::seconds(42)),
+                turn: None,
             },
             Row {
                 path: PathBuf::from("/tmp/b.jsonl"),
                 preview: String::from("Investigate lazy pagination cap"),
+                cwd: PathBuf::from("/tmp/project"),
+                git_branch: None,
                 created_at: Some(now - Duration::hours(1)),
                 updated_at: Some(now - Duration::minutes(35)),
+                turn: None,
             },
             Row {
                 path: PathBuf::from("/tmp/c.jsonl"),
                 preview: String::from("Explain the codebase"),
+                cwd: PathBuf::from("/tmp/project"),
+                git_branch: None,
                 created_at: Some(now - Duration::hours(2)),
                 updated_at: Some(now - Duration::hours(2)),
+                turn: None,
             },
         ];
         state.all_rows = rows.clone();
@@ -1135,8 +1375,13 @@ mod tests {
     #[test]
     fn pageless_scrolling_deduplicates_and_keeps_order() {
         let loader: PageLoader = Arc::new(|_| {});
-        let mut state =
-            PickerState::new(PathBuf::from("/tmp"), FrameRequester::test_dummy(), loader);
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            Arc::new(|_| {}),
+            ConversationFilter::default(),
+        );
 
         state.reset_pagination();
         state.ingest_page(page(
@@ -1147,8 +1392,6 @@ mod tests {
             Some(cursor_from_str(
                 "2025-01-02T00-00-00|00000000-0000-0000-0000-000000000000",
             )),
-            2,
-            false,
         ));
 
         state.ingest_page(page(
@@ -1159,8 +1402,6 @@ mod tests {
             Some(cursor_from_str(
                 "2025-01-01T00-00-00|00000000-0000-0000-0000-000000000001",
             )),
-            2,
-            false,
         ));
 
         state.ingest_page(page(
@@ -1170,8 +1411,6 @@ mod tests {
                 "very old",
             )],
             None,
-            1,
-            false,
         ));
 
         let previews: Vec<_> = state
@@ -1197,8 +1436,13 @@ mod tests {
             request_sink.lock().unwrap().push(req);
         });
 
-        let mut state =
-            PickerState::new(PathBuf::from("/tmp"), FrameRequester::test_dummy(), loader);
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            Arc::new(|_| {}),
+            ConversationFilter::default(),
+        );
         state.reset_pagination();
         state.ingest_page(page(
             vec![
@@ -1208,8 +1452,6 @@ mod tests {
             Some(cursor_from_str(
                 "2025-01-03T00-00-00|00000000-0000-0000-0000-000000000000",
             )),
-            2,
-            false,
         ));
 
         assert!(recorded_requests.lock().unwrap().is_empty());
@@ -1222,8 +1464,13 @@ mod tests {
     #[test]
     fn page_navigation_uses_view_rows() {
         let loader: PageLoader = Arc::new(|_| {});
-        let mut state =
-            PickerState::new(PathBuf::from("/tmp"), FrameRequester::test_dummy(), loader);
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            Arc::new(|_| {}),
+            ConversationFilter::default(),
+        );
 
         let mut items = Vec::new();
         for idx in 0..20 {
@@ -1234,7 +1481,7 @@ mod tests {
         }
 
         state.reset_pagination();
-        state.ingest_page(page(items, None, 20, false));
+        state.ingest_page(page(items, None));
         state.update_view_rows(5);
 
         assert_eq!(state.selected, 0);
@@ -1266,8 +1513,13 @@ mod tests {
     #[test]
     fn up_at_bottom_does_not_scroll_when_visible() {
         let loader: PageLoader = Arc::new(|_| {});
-        let mut state =
-            PickerState::new(PathBuf::from("/tmp"), FrameRequester::test_dummy(), loader);
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            Arc::new(|_| {}),
+            ConversationFilter::default(),
+        );
 
         let mut items = Vec::new();
         for idx in 0..10 {
@@ -1278,7 +1530,7 @@ mod tests {
         }
 
         state.reset_pagination();
-        state.ingest_page(page(items, None, 10, false));
+        state.ingest_page(page(items, None));
         state.update_view_rows(5);
 
         state.selected = state.filtered_rows.len().saturating_sub(1);
@@ -1306,8 +1558,13 @@ mod tests {
             request_sink.lock().unwrap().push(req);
         });
 
-        let mut state =
-            PickerState::new(PathBuf::from("/tmp"), FrameRequester::test_dummy(), loader);
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            Arc::new(|_| {}),
+            ConversationFilter::default(),
+        );
         state.reset_pagination();
         state.ingest_page(page(
             vec![make_item(
@@ -1318,8 +1575,6 @@ mod tests {
             Some(cursor_from_str(
                 "2025-01-02T00-00-00|00000000-0000-0000-0000-000000000000",
             )),
-            1,
-            false,
         ));
         recorded_requests.lock().unwrap().clear();
 
@@ -1339,8 +1594,6 @@ mod tests {
                     Some(cursor_from_str(
                         "2025-01-03T00-00-00|00000000-0000-0000-0000-000000000001",
                     )),
-                    5,
-                    false,
                 )),
             })
             .unwrap();
@@ -1366,8 +1619,6 @@ mod tests {
                     Some(cursor_from_str(
                         "2025-01-04T00-00-00|00000000-0000-0000-0000-000000000002",
                     )),
-                    7,
-                    false,
                 )),
             })
             .unwrap();
@@ -1387,7 +1638,7 @@ mod tests {
             .handle_background_event(BackgroundEvent::PageLoaded {
                 request_token: second_request.request_token,
                 search_token: second_request.search_token,
-                page: Ok(page(Vec::new(), None, 0, false)),
+                page: Ok(page(Vec::new(), None)),
             })
             .unwrap();
         assert_eq!(recorded_requests.lock().unwrap().len(), 1);
@@ -1396,12 +1647,11 @@ mod tests {
             .handle_background_event(BackgroundEvent::PageLoaded {
                 request_token: active_request.request_token,
                 search_token: active_request.search_token,
-                page: Ok(page(Vec::new(), None, 3, true)),
+                page: Ok(page(Vec::new(), None)),
             })
             .unwrap();
 
         assert!(state.filtered_rows.is_empty());
         assert!(!state.search_state.is_active());
-        assert!(state.pagination.reached_scan_cap);
     }
 }
@@ -0,0 +1,948 @@
+//! Renders a recorded session as a transcript that can be shared outside of
+//! Codex: Markdown for pull requests, standalone HTML for anything else, and
+//! JSON for tooling.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_apply_patch::Hunk;
+use codex_apply_patch::MaybeApplyPatch;
+use codex_apply_patch::UpdateFileChunk;
+use codex_core::protocol::FileChange;
+use codex_core::protocol::TokenUsage;
+use codex_core::read_rollout_text;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::InputMessageKind;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::USER_MESSAGE_BEGIN;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::text::Line;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::diff_render::create_diff_summary;
+use crate::diff_render::display_path_for;
+
+/// Output format of [`export_session`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    #[value(name = "md")]
+    Markdown,
+    Html,
+    Json,
+}
+
+/// Reads the rollout file at `path` and renders it as a transcript.
+pub async fn export_session(path: &Path, format: ExportFormat) -> std::io::Result<String> {
+    let text = read_rollout_text(path).await?;
+    let transcript = Transcript::from_rollout(&text);
+    Ok(match format {
+        ExportFormat::Markdown => transcript.to_markdown(),
+        ExportFormat::Html => transcript.to_html(),
+        ExportFormat::Json => serde_json::to_string_pretty(&transcript)?,
+    })
+}
+
+#[derive(Serialize, Debug, Default)]
+struct Transcript {
+    id: Option<String>,
+    cwd: PathBuf,
+    created_at: Option<String>,
+    entries: Vec<TranscriptEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token_usage: Option<TokenUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_usd: Option<f64>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum TranscriptEntry {
+    UserMessage {
+        text: String,
+    },
+    AgentMessage {
+        text: String,
+    },
+    Reasoning {
+        text: String,
+    },
+    Command {
+        command: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exit_code: Option<i32>,
+    },
+    Patch {
+        changes: BTreeMap<PathBuf, FileChange>,
+        /// Whether the tool reported the patch as applied. Stays false until
+        /// its output is recorded.
+        applied: bool,
+    },
+}
+
+/// Shape of the output the shell tool reports back to the model.
+#[derive(Deserialize)]
+struct ShellOutput {
+    output: String,
+    metadata: ShellOutputMetadata,
+}
+
+#[derive(Deserialize)]
+struct ShellOutputMetadata {
+    exit_code: i32,
+}
+
+#[derive(Deserialize)]
+struct ApplyPatchToolArgs {
+    input: String,
+}
+
+impl Transcript {
+    fn from_rollout(text: &str) -> Self {
+        let mut transcript = Transcript::default();
+        // Commands and patches waiting for their output, by call id.
+        let mut pending_calls: HashMap<String, usize> = HashMap::new();
+        for line in text.lines() {
+            let Ok(RolloutLine { item, .. }) = serde_json::from_str::<RolloutLine>(line) else {
+                continue;
+            };
+            match item {
+                RolloutItem::SessionMeta(session_meta_line) if transcript.id.is_none() => {
+                    let meta = session_meta_line.meta;
+                    transcript.id = Some(meta.id.to_string());
+                    transcript.cwd = meta.cwd;
+                    transcript.created_at = Some(meta.timestamp);
+                }
+                RolloutItem::EventMsg(EventMsg::UserMessage(ev))
+                    if matches!(ev.kind, None | Some(InputMessageKind::Plain)) =>
+                {
+                    // Strip ide context.
+                    let message = match ev.message.find(USER_MESSAGE_BEGIN) {
+                        Some(idx) => &ev.message[idx + USER_MESSAGE_BEGIN.len()..],
+                        None => ev.message.as_str(),
+                    };
+                    transcript.entries.push(TranscriptEntry::UserMessage {
+                        text: message.trim().to_string(),
+                    });
+                }
+                RolloutItem::EventMsg(EventMsg::AgentMessage(ev)) => {
+                    transcript
+                        .entries
+                        .push(TranscriptEntry::AgentMessage { text: ev.message });
+                }
+                RolloutItem::EventMsg(EventMsg::AgentReasoning(ev)) => {
+                    transcript
+                        .entries
+                        .push(TranscriptEntry::Reasoning { text: ev.text });
+                }
+                RolloutItem::EventMsg(EventMsg::TokenCount(ev)) => {
+                    if let Some(info) = ev.info {
+                        transcript.token_usage = Some(info.total_token_usage);
+                    }
+                    if ev.cost_usd.is_some() {
+                        transcript.cost_usd = ev.cost_usd;
+                    }
+                }
+                RolloutItem::ResponseItem(item) => {
+                    transcript.push_response_item(item, &mut pending_calls);
+                }
+                _ => {}
+            }
+        }
+        transcript
+    }
+
+    fn push_response_item(
+        &mut self,
+        item: ResponseItem,
+        pending_calls: &mut HashMap<String, usize>,
+    ) {
+        match item {
+            ResponseItem::LocalShellCall {
+                call_id,
+                action: LocalShellAction::Exec(exec),
+                ..
+            } => self.push_command(call_id, &exec.command, None, pending_calls),
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => match name.as_str() {
+                "shell" | "container.exec" => {
+                    if let Ok(params) = serde_json::from_str::<ShellToolCallParams>(&arguments) {
+                        self.push_command(
+                            Some(call_id),
+                            &params.command,
+                            params.workdir.as_deref(),
+                            pending_calls,
+                        );
+                    }
+                }
+                "apply_patch" => {
+                    if let Ok(args) = serde_json::from_str::<ApplyPatchToolArgs>(&arguments) {
+                        self.push_patch(Some(call_id), &args.input, None, pending_calls);
+                    }
+                }
+                _ => {}
+            },
+            ResponseItem::CustomToolCall {
+                name,
+                input,
+                call_id,
+                ..
+            } if name == "apply_patch" => {
+                self.push_patch(Some(call_id), &input, None, pending_calls);
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                self.record_output(&call_id, output.content, pending_calls);
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                self.record_output(&call_id, output, pending_calls);
+            }
+            _ => {}
+        }
+    }
+
+    /// Attaches the output of a tool call to the command or patch that made it.
+    fn record_output(
+        &mut self,
+        call_id: &str,
+        content: String,
+        pending_calls: &mut HashMap<String, usize>,
+    ) {
+        let Some(idx) = pending_calls.remove(call_id) else {
+            return;
+        };
+        let parsed = serde_json::from_str::<ShellOutput>(&content);
+        match self.entries.get_mut(idx) {
+            Some(TranscriptEntry::Command {
+                output, exit_code, ..
+            }) => match parsed {
+                Ok(parsed) => {
+                    *output = Some(parsed.output);
+                    *exit_code = Some(parsed.metadata.exit_code);
+                }
+                Err(_) => *output = Some(content),
+            },
+            // Patches that were rejected or failed to verify report a plain
+            // error message rather than an exit code.
+            Some(TranscriptEntry::Patch { applied, .. }) => {
+                *applied = parsed.is_ok_and(|parsed| parsed.metadata.exit_code == 0);
+            }
+            _ => {}
+        }
+    }
+
+    fn push_command(
+        &mut self,
+        call_id: Option<String>,
+        command: &[String],
+        workdir: Option<&str>,
+        pending_calls: &mut HashMap<String, usize>,
+    ) {
+        // Patches applied through the shell are shown as diffs, not commands.
+        if let MaybeApplyPatch::Body(args) = codex_apply_patch::maybe_parse_apply_patch(command) {
+            let workdir = args.workdir.as_deref().or(workdir);
+            self.push_patch(call_id, &args.patch, workdir, pending_calls);
+            return;
+        }
+        let command = shlex::try_join(command.iter().map(String::as_str))
+            .unwrap_or_else(|_| command.join(" "));
+        if let Some(call_id) = call_id {
+            pending_calls.insert(call_id, self.entries.len());
+        }
+        self.entries.push(TranscriptEntry::Command {
+            command,
+            output: None,
+            exit_code: None,
+        });
+    }
+
+    fn push_patch(
+        &mut self,
+        call_id: Option<String>,
+        patch: &str,
+        workdir: Option<&str>,
+        pending_calls: &mut HashMap<String, usize>,
+    ) {
+        let Ok(args) = codex_apply_patch::parse_patch(patch) else {
+            return;
+        };
+        let cwd = match workdir {
+            Some(workdir) => self.cwd.join(workdir),
+            None => self.cwd.clone(),
+        };
+        let changes = args
+            .hunks
+            .iter()
+            .map(|hunk| (hunk.resolve_path(&cwd), file_change_from_hunk(hunk)))
+            .collect();
+        if let Some(call_id) = call_id {
+            pending_calls.insert(call_id, self.entries.len());
+        }
+        self.entries.push(TranscriptEntry::Patch {
+            changes,
+            applied: false,
+        });
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!(
+            "# Codex session {}\n",
+            self.id.as_deref().unwrap_or("")
+        ));
+        out.push('\n');
+        if let Some(created_at) = &self.created_at {
+            out.push_str(&format!("- Started: {created_at}\n"));
+        }
+        out.push_str(&format!("- Directory: `{}`\n", self.cwd.display()));
+
+        for entry in &self.entries {
+            out.push('\n');
+            match entry {
+                TranscriptEntry::UserMessage { text } => {
+                    out.push_str(&format!("## User\n\n{text}\n"));
+                }
+                TranscriptEntry::AgentMessage { text } => {
+                    out.push_str(&format!("## Codex\n\n{text}\n"));
+                }
+                TranscriptEntry::Reasoning { text } => {
+                    out.push_str("> **Reasoning**\n>\n");
+                    for line in text.lines() {
+                        out.push_str(&format!("> {line}\n"));
+                    }
+                }
+                TranscriptEntry::Command {
+                    command,
+                    output,
+                    exit_code,
+                } => {
+                    match exit_code {
+                        Some(code) => {
+                            out.push_str(&format!("**Ran command** (exit code {code})\n"));
+                        }
+                        None => {
+                            out.push_str("**Ran command**\n");
+                        }
+                    }
+                    let mut body = format!("$ {command}\n");
+                    if let Some(output) = output {
+                        body.push_str(output.trim_end());
+                        body.push('\n');
+                    }
+                    out.push('\n');
+                    push_fenced(&mut out, "console", &body);
+                }
+                TranscriptEntry::Patch { changes, applied } => {
+                    if !applied {
+                        out.push_str("**Patch not applied**\n\n");
+                    }
+                    for (idx, (path, change)) in changes.iter().enumerate() {
+                        if idx > 0 {
+                            out.push('\n');
+                        }
+                        self.push_markdown_change(&mut out, path, change);
+                    }
+                }
+            }
+        }
+
+        if let Some(usage) = &self.token_usage {
+            out.push_str("\n## Token usage\n\n");
+            out.push_str(&format!(
+                "- Input: {} ({} cached)\n",
+                usage.input_tokens, usage.cached_input_tokens
+            ));
+            out.push_str(&format!(
+                "- Output: {} ({} reasoning)\n",
+                usage.output_tokens, usage.reasoning_output_tokens
+            ));
+            out.push_str(&format!("- Total: {}\n", usage.total_tokens));
+            if let Some(cost) = self.cost_usd {
+                out.push_str(&format!("- Estimated cost: ${cost:.2}\n"));
+            }
+        }
+        out
+    }
+
+    fn push_markdown_change(&self, out: &mut String, path: &Path, change: &FileChange) {
+        let display = display_path_for(path, &self.cwd);
+        match change {
+            FileChange::Add { content } => {
+                out.push_str(&format!("**Added** `{display}`\n\n"));
+                let body: String = content.lines().map(|line| format!("+{line}\n")).collect();
+                push_fenced(out, "diff", &body);
+            }
+            FileChange::Delete { .. } => {
+                out.push_str(&format!("**Deleted** `{display}`\n"));
+            }
+            FileChange::Update {
+                unified_diff,
+                move_path,
+            } => {
+                match move_path {
+                    Some(move_path) => {
+                        let moved = display_path_for(move_path, &self.cwd);
+                        out.push_str(&format!("**Edited** `{display}` → `{moved}`\n\n"));
+                    }
+                    None => {
+                        out.push_str(&format!("**Edited** `{display}`\n\n"));
+                    }
+                }
+                push_fenced(out, "diff", unified_diff);
+            }
+        }
+    }
+
+    fn to_html(&self) -> String {
+        let id = self.id.as_deref().unwrap_or("");
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        out.push_str(&format!(
+            "<title>Codex session {}</title>\n",
+            escape_html(id)
+        ));
+        out.push_str(HTML_STYLE);
+        out.push_str("</head>\n<body>\n");
+        out.push_str(&format!("<h1>Codex session {}</h1>\n", escape_html(id)));
+        out.push_str("<p class=\"meta\">");
+        if let Some(created_at) = &self.created_at {
+            out.push_str(&format!("Started {} · ", escape_html(created_at)));
+        }
+        out.push_str(&format!(
+            "<code>{}</code></p>\n",
+            escape_html(&self.cwd.display().to_string())
+        ));
+
+        for entry in &self.entries {
+            match entry {
+                TranscriptEntry::UserMessage { text } => {
+                    out.push_str("<section class=\"user\">\n<h2>User</h2>\n");
+                    out.push_str(&markdown_to_html(text));
+                    out.push_str("</section>\n");
+                }
+                TranscriptEntry::AgentMessage { text } => {
+                    out.push_str("<section class=\"agent\">\n<h2>Codex</h2>\n");
+                    out.push_str(&markdown_to_html(text));
+                    out.push_str("</section>\n");
+                }
+                TranscriptEntry::Reasoning { text } => {
+                    out.push_str("<details class=\"reasoning\">\n<summary>Reasoning</summary>\n");
+                    out.push_str(&markdown_to_html(text));
+                    out.push_str("</details>\n");
+                }
+                TranscriptEntry::Command {
+                    command,
+                    output,
+                    exit_code,
+                } => {
+                    let status = match exit_code {
+                        Some(0) => " <span class=\"ok\">exit code 0</span>".to_string(),
+                        Some(code) => format!(" <span class=\"del\">exit code {code}</span>"),
+                        None => String::new(),
+                    };
+                    out.push_str(&format!(
+                        "<section class=\"command\">\n<h3>Ran command{status}</h3>\n"
+                    ));
+                    out.push_str(&format!(
+                        "<pre><span class=\"dim\">$</span> {}",
+                        escape_html(command)
+                    ));
+                    if let Some(output) = output {
+                        out.push_str(&format!("\n{}", escape_html(output.trim_end())));
+                    }
+                    out.push_str("</pre>\n</section>\n");
+                }
+                TranscriptEntry::Patch { changes, applied } => {
+                    let changes: HashMap<PathBuf, FileChange> = changes
+                        .iter()
+                        .map(|(path, change)| (path.clone(), change.clone()))
+                        .collect();
+                    // `<pre>` scrolls horizontally, so lines are never wrapped.
+                    let lines = create_diff_summary(&changes, &self.cwd, usize::MAX);
+                    out.push_str("<section class=\"patch\">\n");
+                    if !applied {
+                        out.push_str("<h3><span class=\"del\">Patch not applied</span></h3>\n");
+                    }
+                    out.push_str("<pre>");
+                    for (idx, line) in lines.iter().enumerate() {
+                        if idx > 0 {
+                            out.push('\n');
+                        }
+                        push_html_line(&mut out, line);
+                    }
+                    out.push_str("</pre>\n</section>\n");
+                }
+            }
+        }
+
+        if let Some(usage) = &self.token_usage {
+            out.push_str("<section class=\"usage\">\n<h2>Token usage</h2>\n<table>\n");
+            let mut rows = vec![
+                (
+                    "Input",
+                    format!(
+                        "{} ({} cached)",
+                        usage.input_tokens, usage.cached_input_tokens
+                    ),
+                ),
+                (
+                    "Output",
+                    format!(
+                        "{} ({} reasoning)",
+                        usage.output_tokens, usage.reasoning_output_tokens
+                    ),
+                ),
+                ("Total", usage.total_tokens.to_string()),
+            ];
+            if let Some(cost) = self.cost_usd {
+                rows.push(("Estimated cost", format!("${cost:.2}")));
+            }
+            for (label, value) in rows {
+                out.push_str(&format!("<tr><th>{label}</th><td>{value}</td></tr>\n"));
+            }
+            out.push_str("</table>\n</section>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+const HTML_STYLE: &str = "<style>
+body { font-family: -apple-system, BlinkMacSystemFont, \"Segoe UI\", sans-serif; max-width: 960px; margin: 2em auto; padding: 0 1em; line-height: 1.5; }
+pre { background: #f6f8fa; padding: 0.75em; overflow-x: auto; }
+section { margin: 1.5em 0; }
+h2, h3 { margin-bottom: 0.25em; }
+.meta, .dim, .reasoning { color: #57606a; }
+.add, .ok { color: #1a7f37; }
+.del { color: #cf222e; }
+.bold { font-weight: bold; }
+</style>
+";
+
+/// Builds the change `diff_render` shows for a parsed patch hunk. The patch
+/// does not record where in the file a chunk applied, so line numbers in
+/// the diff count from the start of each chunk.
+fn file_change_from_hunk(hunk: &Hunk) -> FileChange {
+    match hunk {
+        Hunk::AddFile { contents, .. } => FileChange::Add {
+            content: contents.clone(),
+        },
+        Hunk::DeleteFile { .. } => FileChange::Delete {
+            content: String::new(),
+        },
+        Hunk::UpdateFile {
+            move_path, chunks, ..
+        } => FileChange::Update {
+            unified_diff: unified_diff_from_chunks(chunks),
+            move_path: move_path.clone(),
+        },
+    }
+}
+
+fn unified_diff_from_chunks(chunks: &[UpdateFileChunk]) -> String {
+    let mut diff = String::new();
+    for chunk in chunks {
+        let old = lines_to_text(&chunk.old_lines);
+        let new = lines_to_text(&chunk.new_lines);
+        let patch = diffy::create_patch(&old, &new).to_string();
+        // Keep the hunks only, like the diffs `apply_patch` reports.
+        for line in patch
+            .lines()
+            .skip_while(|line| line.starts_with("--- ") || line.starts_with("+++ "))
+        {
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
+fn lines_to_text(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// Appends `body` as a fenced code block whose fence is longer than any run
+/// of backticks inside it.
+fn push_fenced(out: &mut String, info: &str, body: &str) {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in body.chars() {
+        if c == '`' {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 0;
+        }
+    }
+    let fence = "`".repeat((longest + 1).max(3));
+    out.push_str(&format!("{fence}{info}\n"));
+    out.push_str(body);
+    if !body.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(&format!("{fence}\n"));
+}
+
+/// Renders Markdown as HTML. Raw HTML in the source is escaped rather than
+/// passed through, and images become links, since transcripts include model
+/// output. Links to anything but http(s) or relative URLs are dropped.
+fn markdown_to_html(text: &str) -> String {
+    use pulldown_cmark::Event;
+    use pulldown_cmark::Parser;
+    use pulldown_cmark::Tag;
+    use pulldown_cmark::TagEnd;
+
+    let mut events = Vec::new();
+    // Whether each open link or image is rendered as a link. Links do not
+    // nest, so an image inside a link only keeps its text.
+    let mut open_links: Vec<bool> = Vec::new();
+    for event in Parser::new(text) {
+        match event {
+            Event::Html(html) | Event::InlineHtml(html) => events.push(Event::Text(html)),
+            Event::Start(
+                Tag::Link {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }
+                | Tag::Image {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                },
+            ) => {
+                let keep = is_safe_link(&dest_url) && !open_links.contains(&true);
+                open_links.push(keep);
+                if keep {
+                    events.push(Event::Start(Tag::Link {
+                        link_type,
+                        dest_url,
+                        title,
+                        id,
+                    }));
+                }
+            }
+            Event::End(TagEnd::Link | TagEnd::Image) => {
+                if open_links.pop().unwrap_or(false) {
+                    events.push(Event::End(TagEnd::Link));
+                }
+            }
+            other => events.push(other),
+        }
+    }
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, events.into_iter());
+    html
+}
+
+/// Whether `url` is an http(s) URL or relative. Browsers ignore whitespace
+/// and control characters in a scheme, so those are removed before looking
+/// for one.
+fn is_safe_link(url: &str) -> bool {
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect();
+    let scheme_end =
+        url.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')));
+    match scheme_end {
+        Some(end) if end > 0 && url[end..].starts_with(':') => {
+            let scheme = url[..end].to_ascii_lowercase();
+            scheme == "http" || scheme == "https"
+        }
+        _ => true,
+    }
+}
+
+fn push_html_line(out: &mut String, line: &Line<'_>) {
+    for span in &line.spans {
+        let style = line.style.patch(span.style);
+        let mut classes = Vec::new();
+        match style.fg {
+            Some(Color::Green) => classes.push("add"),
+            Some(Color::Red) => classes.push("del"),
+            _ => {}
+        }
+        if style.add_modifier.contains(Modifier::DIM) {
+            classes.push("dim");
+        }
+        if style.add_modifier.contains(Modifier::BOLD) {
+            classes.push("bold");
+        }
+        let content = escape_html(&span.content);
+        if classes.is_empty() {
+            out.push_str(&content);
+        } else {
+            out.push_str(&format!(
+                "<span class=\"{}\">{content}</span>",
+                classes.join(" ")
+            ));
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn rollout() -> String {
+        let lines = [
+            json!({
+                "timestamp": "2025-07-01T09:00:00.000Z",
+                "type": "session_meta",
+                "payload": {
+                    "id": "0199a213-81c0-7800-8aa1-bbab2a035a53",
+                    "timestamp": "2025-07-01T09:00:00.000Z",
+                    "cwd": "/repo",
+                    "originator": "codex_cli_rs",
+                    "cli_version": "0.0.0",
+                    "instructions": null,
+                    "source": "cli",
+                },
+            }),
+            json!({
+                "timestamp": "2025-07-01T09:00:01.000Z",
+                "type": "event_msg",
+                "payload": {
+                    "type": "user_message",
+                    "message": "Fix the <b>migration</b> bug",
+                    "kind": "plain",
+                },
+            }),
+            json!({
+                "timestamp": "2025-07-01T09:00:02.000Z",
+                "type": "event_msg",
+                "payload": { "type": "agent_reasoning", "text": "Check the schema first." },
+            }),
+            json!({
+                "timestamp": "2025-07-01T09:00:03.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call",
+                    "name": "shell",
+                    "arguments": "{\"command\":[\"cargo\",\"test\"]}",
+                    "call_id": "call-1",
+                },
+            }),
+            json!({
+                "timestamp": "2025-07-01T09:00:04.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call_output",
+                    "call_id": "call-1",
+                    "output": "{\"output\":\"test result: FAILED\\n\",\"metadata\":{\"exit_code\":101,\"duration_seconds\":1.0}}",
+                },
+            }),
+            json!({
+                "timestamp": "2025-07-01T09:00:05.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "custom_tool_call",
+                    "name": "apply_patch",
+                    "call_id": "call-2",
+                    "input": "*** Begin Patch\n*** Update File: src/db.rs\n@@\n fn migrate() {\n-    run(1);\n+    run(2);\n }\n*** End Patch",
+                },
+            }),
+            json!({
+                "timestamp": "2025-07-01T09:00:05.500Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "custom_tool_call_output",
+                    "call_id": "call-2",
+                    "output": "{\"output\":\"Success. Updated the following files:\\nM src/db.rs\\n\",\"metadata\":{\"exit_code\":0,\"duration_seconds\":0.0}}",
+                },
+            }),
+            json!({
+                "timestamp": "2025-07-01T09:00:06.000Z",
+                "type": "event_msg",
+                "payload": { "type": "agent_message", "message": "Fixed the `run` call." },
+            }),
+            json!({
+                "timestamp": "2025-07-01T09:00:07.000Z",
+                "type": "event_msg",
+                "payload": {
+                    "type": "token_count",
+                    "info": {
+                        "total_token_usage": {
+                            "input_tokens": 1200,
+                            "cached_input_tokens": 200,
+                            "output_tokens": 300,
+                            "reasoning_output_tokens": 100,
+                            "total_tokens": 1500,
+                        },
+                        "last_token_usage": {
+                            "input_tokens": 0,
+                            "cached_input_tokens": 0,
+                            "output_tokens": 0,
+                            "reasoning_output_tokens": 0,
+                            "total_tokens": 0,
+                        },
+                        "model_context_window": null,
+                    },
+                    "rate_limits": null,
+                },
+            }),
+        ];
+        lines
+            .iter()
+            .map(|line| format!("{line}\n"))
+            .collect::<String>()
+    }
+
+    #[test]
+    fn markdown_transcript() {
+        let transcript = Transcript::from_rollout(&rollout());
+        assert_snapshot!("session_export_markdown", transcript.to_markdown());
+    }
+
+    #[test]
+    fn html_transcript_colors_diffs_and_escapes_markup() {
+        let html = Transcript::from_rollout(&rollout()).to_html();
+        assert!(html.contains("Fix the &lt;b&gt;migration&lt;/b&gt; bug"));
+        assert!(html.contains("<span class=\"add\">+    run(2);</span>"));
+        assert!(html.contains("<span class=\"del\">-    run(1);</span>"));
+        assert!(html.contains("<span class=\"del\">exit code 101</span>"));
+        assert!(html.contains("<tr><th>Total</th><td>1500</td></tr>"));
+    }
+
+    #[test]
+    fn json_transcript_lists_entries_in_order() {
+        let transcript = Transcript::from_rollout(&rollout());
+        let value = serde_json::to_value(&transcript).unwrap();
+        let types: Vec<&str> = value["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                "user_message",
+                "reasoning",
+                "command",
+                "patch",
+                "agent_message"
+            ]
+        );
+        assert_eq!(value["entries"][2]["exit_code"], json!(101));
+        assert_eq!(value["token_usage"]["total_tokens"], json!(1500));
+    }
+
+    #[test]
+    fn patches_take_their_outcome_from_the_tool_output() {
+        let patch = |call_id: &str| {
+            json!({
+                "timestamp": "2025-07-01T09:00:01.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call",
+                    "name": "apply_patch",
+                    "arguments": json!({
+                        "input": "*** Begin Patch\n*** Add File: notes.txt\n+hi\n*** End Patch",
+                    })
+                    .to_string(),
+                    "call_id": call_id,
+                },
+            })
+        };
+        let output = |call_id: &str, output: &str| {
+            json!({
+                "timestamp": "2025-07-01T09:00:02.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call_output",
+                    "call_id": call_id,
+                    "output": output,
+                },
+            })
+        };
+        let rollout: String = [
+            patch("rejected"),
+            output("rejected", "patch rejected by user"),
+            patch("failed"),
+            output(
+                "failed",
+                r#"{"output":"notes.txt already exists","metadata":{"exit_code":1,"duration_seconds":0.0}}"#,
+            ),
+            patch("applied"),
+            output(
+                "applied",
+                r#"{"output":"Success.","metadata":{"exit_code":0,"duration_seconds":0.0}}"#,
+            ),
+        ]
+        .iter()
+        .map(|line| format!("{line}\n"))
+        .collect();
+
+        let transcript = Transcript::from_rollout(&rollout);
+        let applied: Vec<bool> = transcript
+            .entries
+            .iter()
+            .map(|entry| match entry {
+                TranscriptEntry::Patch { applied, .. } => *applied,
+                _ => panic!("expected only patches"),
+            })
+            .collect();
+        assert_eq!(applied, vec![false, false, true]);
+        assert_eq!(
+            transcript
+                .to_markdown()
+                .matches("**Patch not applied**")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn html_keeps_only_web_and_relative_links() {
+        let html = markdown_to_html(
+            "[docs](https://example.com/a) [file](src/main.rs) [x](javascript:alert(1)) \
+             [y](<JaVa\tScRiPt:alert(1)>) <data:text/html,hi> ![logo](https://example.com/logo.png)",
+        );
+        assert!(html.contains("<a href=\"https://example.com/a\">docs</a>"));
+        assert!(html.contains("<a href=\"src/main.rs\">file</a>"));
+        assert!(html.contains("<a href=\"https://example.com/logo.png\">logo</a>"));
+        assert_eq!(html.matches("<a ").count(), 3);
+        assert!(!html.contains("<img"));
+    }
+
+    #[test]
+    fn fence_outgrows_backticks_in_body() {
+        let mut out = String::new();
+        push_fenced(&mut out, "console", "echo ```\n");
+        assert_eq!(out, "````console\necho ```\n````\n");
+    }
+}
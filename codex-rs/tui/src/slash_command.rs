@@ -22,6 +22,7 @@ pub enum SlashCommand {
     Diff,
     Mention,
     Status,
+    Unpin,
     Mcp,
     Logout,
     Quit,
@@ -42,9 +43,10 @@ impl SlashCommand {
             SlashCommand::Diff => "show git diff (including untracked files)",
             SlashCommand::Mention => "mention a file",
             SlashCommand::Status => "show current session configuration and token usage",
+            SlashCommand::Unpin => "stop keeping a pinned message or file across compaction",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
-            SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::Mcp => "list configured MCP tools, or `/mcp reload` to restart servers",
             SlashCommand::Logout => "log out of Codex",
             #[cfg(debug_assertions)]
             SlashCommand::TestApproval => "test approval request",
@@ -57,6 +59,11 @@ impl SlashCommand {
         self.into()
     }
 
+    /// Whether this command takes arguments after its name, e.g. `/mcp reload`.
+    pub fn accepts_args(self) -> bool {
+        matches!(self, SlashCommand::Mcp | SlashCommand::Compact)
+    }
+
     /// Whether this command can be run while a task is in progress.
     pub fn available_during_task(self) -> bool {
         match self {
@@ -71,6 +78,7 @@ impl SlashCommand {
             SlashCommand::Diff
             | SlashCommand::Mention
             | SlashCommand::Status
+            | SlashCommand::Unpin
             | SlashCommand::Mcp
             | SlashCommand::Quit => true,
 
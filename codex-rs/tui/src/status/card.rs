@@ -5,12 +5,14 @@ use crate::history_cell::with_border_with_inner_width;
 use crate::version::CODEX_CLI_VERSION;
 use codex_common::create_config_summary_entries;
 use codex_core::config::Config;
+use codex_core::protocol::PinnedItem;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::TokenUsage;
 use codex_protocol::ConversationId;
 use ratatui::prelude::*;
 use ratatui::style::Stylize;
 use std::collections::BTreeSet;
+use std::path::Path;
 use std::path::PathBuf;
 
 use super::account::StatusAccountDisplay;
@@ -41,6 +43,7 @@ pub(crate) struct StatusTokenUsageData {
     total: u64,
     input: u64,
     output: u64,
+    cost_usd: Option<f64>,
     context_window: Option<StatusContextWindowData>,
 }
 
@@ -54,6 +57,7 @@ struct StatusHistoryCell {
     agents_summary: String,
     account: Option<StatusAccountDisplay>,
     session_id: Option<String>,
+    pinned: Vec<String>,
     token_usage: StatusTokenUsageData,
     rate_limits: StatusRateLimitData,
 }
@@ -62,11 +66,21 @@ pub(crate) fn new_status_output(
     config: &Config,
     total_usage: &TokenUsage,
     context_usage: Option<&TokenUsage>,
+    cost_usd: Option<f64>,
+    pinned_items: &[PinnedItem],
     session_id: &Option<ConversationId>,
     rate_limits: Option<&RateLimitSnapshotDisplay>,
 ) -> CompositeHistoryCell {
     let command = PlainHistoryCell::new(vec!["/status".magenta().into()]);
-    let card = StatusHistoryCell::new(config, total_usage, context_usage, session_id, rate_limits);
+    let card = StatusHistoryCell::new(
+        config,
+        total_usage,
+        context_usage,
+        cost_usd,
+        pinned_items,
+        session_id,
+        rate_limits,
+    );
 
     CompositeHistoryCell::new(vec![Box::new(command), Box::new(card)])
 }
@@ -76,6 +90,8 @@ impl StatusHistoryCell {
         config: &Config,
         total_usage: &TokenUsage,
         context_usage: Option<&TokenUsage>,
+        cost_usd: Option<f64>,
+        pinned_items: &[PinnedItem],
         session_id: &Option<ConversationId>,
         rate_limits: Option<&RateLimitSnapshotDisplay>,
     ) -> Self {
@@ -88,12 +104,16 @@ impl StatusHistoryCell {
             .unwrap_or_else(|| "<unknown>".to_string());
         let sandbox = match &config.sandbox_policy {
             SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
-            SandboxPolicy::ReadOnly => "read-only".to_string(),
+            SandboxPolicy::ReadOnly { .. } => "read-only".to_string(),
             SandboxPolicy::WorkspaceWrite { .. } => "workspace-write".to_string(),
         };
         let agents_summary = compose_agents_summary(config);
         let account = compose_account_display(config);
         let session_id = session_id.as_ref().map(std::string::ToString::to_string);
+        let pinned = pinned_items
+            .iter()
+            .map(|item| describe_pinned_item(item, &config.cwd))
+            .collect();
         let context_window = config.model_context_window.and_then(|window| {
             context_usage.map(|usage| StatusContextWindowData {
                 percent_remaining: usage.percent_of_context_window_remaining(window),
@@ -106,6 +126,7 @@ impl StatusHistoryCell {
             total: total_usage.blended_total(),
             input: total_usage.non_cached_input(),
             output: total_usage.output_tokens,
+            cost_usd,
             context_window,
         };
         let rate_limits = compose_rate_limit_data(rate_limits);
@@ -119,6 +140,7 @@ impl StatusHistoryCell {
             agents_summary,
             account,
             session_id,
+            pinned,
             token_usage,
             rate_limits,
         }
@@ -269,7 +291,13 @@ impl HistoryCell for StatusHistoryCell {
         if self.session_id.is_some() {
             push_label(&mut labels, &mut seen, "Session");
         }
+        if !self.pinned.is_empty() {
+            push_label(&mut labels, &mut seen, "Pinned");
+        }
         push_label(&mut labels, &mut seen, "Token usage");
+        if self.token_usage.cost_usd.is_some() {
+            push_label(&mut labels, &mut seen, "Cost");
+        }
         if self.token_usage.context_window.is_some() {
             push_label(&mut labels, &mut seen, "Context window");
         }
@@ -301,10 +329,26 @@ impl HistoryCell for StatusHistoryCell {
             lines.push(formatter.line("Session", vec![Span::from(session.clone())]));
         }
 
+        if let Some((first, rest)) = self.pinned.split_first() {
+            lines.push(formatter.line("Pinned", vec![Span::from(first.clone())]));
+            for item in rest {
+                lines.push(formatter.continuation(vec![Span::from(item.clone())]));
+            }
+        }
+
         lines.push(Line::from(Vec::<Span<'static>>::new()));
         // Hide token usage only for ChatGPT subscribers
         if !matches!(self.account, Some(StatusAccountDisplay::ChatGpt { .. })) {
             lines.push(formatter.line("Token usage", self.token_usage_spans()));
+            if let Some(cost_usd) = self.token_usage.cost_usd {
+                lines.push(formatter.line(
+                    "Cost",
+                    vec![
+                        Span::from(format!("${cost_usd:.2}")),
+                        Span::from(" (estimated)").dim(),
+                    ],
+                ));
+            }
         }
 
         if let Some(spans) = self.context_window_spans() {
@@ -323,3 +367,17 @@ impl HistoryCell for StatusHistoryCell {
         with_border_with_inner_width(truncated_lines, inner_width)
     }
 }
+
+/// One-line description of a pinned item: the file path relative to `cwd`,
+/// or the first line of a message.
+pub(crate) fn describe_pinned_item(item: &PinnedItem, cwd: &Path) -> String {
+    match item {
+        PinnedItem::File { path } => path.strip_prefix(cwd).unwrap_or(path).display().to_string(),
+        PinnedItem::Message { text } => {
+            let mut lines = text.trim().lines();
+            let first = lines.next().unwrap_or_default();
+            let ellipsis = if lines.next().is_some() { "…" } else { "" };
+            format!("\"{first}{ellipsis}\"")
+        }
+    }
+}
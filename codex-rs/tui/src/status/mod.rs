@@ -4,6 +4,7 @@ mod format;
 mod helpers;
 mod rate_limits;
 
+pub(crate) use card::describe_pinned_item;
 pub(crate) use card::new_status_output;
 pub(crate) use rate_limits::RateLimitSnapshotDisplay;
 pub(crate) use rate_limits::rate_limit_snapshot_display;
@@ -5,6 +5,7 @@ use chrono::TimeZone;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
+use codex_core::protocol::PinnedItem;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::RateLimitWindow;
 use codex_core::protocol::SandboxPolicy;
@@ -71,8 +72,11 @@ fn status_snapshot_includes_reasoning_details() {
     config.sandbox_policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: Vec::new(),
         network_access: false,
+        allowed_domains: vec![],
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        readable_roots: vec![],
+        deny_read: vec![],
     };
 
     config.cwd = PathBuf::from("/workspace/tests");
@@ -103,7 +107,15 @@ fn status_snapshot_includes_reasoning_details() {
         .expect("timestamp");
     let rate_display = rate_limit_snapshot_display(&snapshot, captured_at);
 
-    let composite = new_status_output(&config, &usage, Some(&usage), &None, Some(&rate_display));
+    let composite = new_status_output(
+        &config,
+        &usage,
+        Some(&usage),
+        None,
+        &[],
+        &None,
+        Some(&rate_display),
+    );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
         for line in &mut rendered_lines {
@@ -144,7 +156,15 @@ fn status_snapshot_includes_monthly_limit() {
         .expect("timestamp");
     let rate_display = rate_limit_snapshot_display(&snapshot, captured_at);
 
-    let composite = new_status_output(&config, &usage, Some(&usage), &None, Some(&rate_display));
+    let composite = new_status_output(
+        &config,
+        &usage,
+        Some(&usage),
+        None,
+        &[],
+        &None,
+        Some(&rate_display),
+    );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
         for line in &mut rendered_lines {
@@ -170,7 +190,7 @@ fn status_card_token_usage_excludes_cached_tokens() {
         total_tokens: 2_100,
     };
 
-    let composite = new_status_output(&config, &usage, Some(&usage), &None, None);
+    let composite = new_status_output(&config, &usage, Some(&usage), None, &[], &None, None);
     let rendered = render_lines(&composite.display_lines(120));
 
     assert!(
@@ -179,6 +199,38 @@ fn status_card_token_usage_excludes_cached_tokens() {
     );
 }
 
+#[test]
+fn status_card_lists_pinned_items() {
+    let temp_home = TempDir::new().expect("temp home");
+    let mut config = test_config(&temp_home);
+    config.cwd = PathBuf::from("/workspace/tests");
+
+    let pinned = vec![
+        PinnedItem::File {
+            path: PathBuf::from("/workspace/tests/docs/spec.md"),
+        },
+        PinnedItem::Message {
+            text: "error[E0425]: cannot find value\n  --> src/main.rs".to_string(),
+        },
+    ];
+    let usage = TokenUsage::default();
+    let composite = new_status_output(&config, &usage, None, None, &pinned, &None, None);
+    let rendered = render_lines(&composite.display_lines(120));
+
+    let pinned_lines: Vec<&str> = rendered
+        .iter()
+        .filter(|line| line.contains("spec.md") || line.contains("E0425"))
+        .map(|line| line.trim_matches(|c: char| c == '│' || c.is_whitespace()))
+        .collect();
+    assert_eq!(
+        pinned_lines,
+        vec![
+            "Pinned:        docs/spec.md",
+            "\"error[E0425]: cannot find value…\"",
+        ]
+    );
+}
+
 #[test]
 fn status_snapshot_truncates_in_narrow_terminal() {
     let temp_home = TempDir::new().expect("temp home");
@@ -211,7 +263,15 @@ fn status_snapshot_truncates_in_narrow_terminal() {
         .expect("timestamp");
     let rate_display = rate_limit_snapshot_display(&snapshot, captured_at);
 
-    let composite = new_status_output(&config, &usage, Some(&usage), &None, Some(&rate_display));
+    let composite = new_status_output(
+        &config,
+        &usage,
+        Some(&usage),
+        None,
+        &[],
+        &None,
+        Some(&rate_display),
+    );
     let mut rendered_lines = render_lines(&composite.display_lines(46));
     if cfg!(windows) {
         for line in &mut rendered_lines {
@@ -238,7 +298,33 @@ fn status_snapshot_shows_missing_limits_message() {
         total_tokens: 750,
     };
 
-    let composite = new_status_output(&config, &usage, Some(&usage), &None, None);
+    let composite = new_status_output(&config, &usage, Some(&usage), None, &[], &None, None);
+    let mut rendered_lines = render_lines(&composite.display_lines(80));
+    if cfg!(windows) {
+        for line in &mut rendered_lines {
+            *line = line.replace('\\', "/");
+        }
+    }
+    let sanitized = sanitize_directory(rendered_lines).join("\n");
+    assert_snapshot!(sanitized);
+}
+
+#[test]
+fn status_snapshot_includes_estimated_cost() {
+    let temp_home = TempDir::new().expect("temp home");
+    let mut config = test_config(&temp_home);
+    config.model = "gpt-5-codex".to_string();
+    config.cwd = PathBuf::from("/workspace/tests");
+
+    let usage = TokenUsage {
+        input_tokens: 120_000,
+        cached_input_tokens: 40_000,
+        output_tokens: 9_000,
+        reasoning_output_tokens: 2_000,
+        total_tokens: 129_000,
+    };
+
+    let composite = new_status_output(&config, &usage, Some(&usage), Some(0.195), &[], &None, None);
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
         for line in &mut rendered_lines {
@@ -274,7 +360,15 @@ fn status_snapshot_shows_empty_limits_message() {
         .expect("timestamp");
     let rate_display = rate_limit_snapshot_display(&snapshot, captured_at);
 
-    let composite = new_status_output(&config, &usage, Some(&usage), &None, Some(&rate_display));
+    let composite = new_status_output(
+        &config,
+        &usage,
+        Some(&usage),
+        None,
+        &[],
+        &None,
+        Some(&rate_display),
+    );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
         for line in &mut rendered_lines {
@@ -306,7 +400,15 @@ fn status_context_window_uses_last_usage() {
         total_tokens: 13_679,
     };
 
-    let composite = new_status_output(&config, &total_usage, Some(&last_usage), &None, None);
+    let composite = new_status_output(
+        &config,
+        &total_usage,
+        Some(&last_usage),
+        None,
+        &[],
+        &None,
+        None,
+    );
     let rendered_lines = render_lines(&composite.display_lines(80));
     let context_line = rendered_lines
         .into_iter()